@@ -76,6 +76,84 @@ namespace Sysworxx
         [DllImport(__DllName, EntryPoint = "IoServiceWatchdog", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern IoResult IoServiceWatchdog();
 
+        /// <summary>
+        ///  @brief Set the timeout of the system watchdog
+        ///
+        ///  The timeout is applied immediately if the watchdog is enabled, otherwise on
+        ///  IoEnableWatchdog(). The driver may round it to a supported value.
+        ///
+        ///  @param uTimeout_p Timeout in seconds
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoSetWatchdogTimeout", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoSetWatchdogTimeout(uint uTimeout_p);
+
+        /// <summary>
+        ///  @brief Get the timeout of the system watchdog and the time until it expires
+        ///
+        ///  @param puTimeout_p Pointer to the resulting timeout in seconds
+        ///  @param puTimeLeft_p Pointer to the resulting time left in seconds (may be NULL)
+        ///  @return IoResult Driver result code of type IoResult
+        ///
+        ///  # Safety
+        ///
+        ///  `puTimeout_p` must be a valid pointer
+        ///  `puTimeLeft_p` must be a valid pointer or NULL
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoGetWatchdogTimeout", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoGetWatchdogTimeout(uint* puTimeout_p, uint* puTimeLeft_p);
+
+        /// <summary>
+        ///  @brief Get the reason of the last reset reported by the system watchdog
+        ///
+        ///  @param puBootStatus_p Pointer to the resulting WDIOF_* flags of
+        ///         &lt;linux/watchdog.h&gt;, WDIOF_CARDRESET (0x20) if the watchdog reset
+        ///         the system
+        ///  @return IoResult Driver result code of type IoResult
+        ///
+        ///  # Safety
+        ///
+        ///  `puBootStatus_p` must be a valid pointer
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoGetWatchdogResetReason", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoGetWatchdogResetReason(uint* puBootStatus_p);
+
+        /// <summary>
+        ///  @brief Register the process at the watchdog supervisor of iodaemon
+        ///
+        ///  The process must call IoServiceWatchdogClient() within the timeout, otherwise
+        ///  iodaemon applies the action configured for the client (see
+        ///  /etc/sysworxx-io/iodaemon.conf). Terminating the process without
+        ///  IoUnregisterWatchdogClient() or IoShutdown() is handled as failure.
+        ///
+        ///  @param pszName_p Name of the client (without whitespace)
+        ///  @param uTimeout_p Timeout in milliseconds
+        ///  @return IoResult Driver result code of type IoResult
+        ///
+        ///  # Safety
+        ///
+        ///  `pszName_p` must be a valid pointer to a null terminated string
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoRegisterWatchdogClient", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoRegisterWatchdogClient(byte* pszName_p, uint uTimeout_p);
+
+        /// <summary>
+        ///  @brief Send a heartbeat to the watchdog supervisor of iodaemon
+        ///
+        ///  @return IoResult Driver result code of type IoResult, IoResult::WatchdogTimeout if
+        ///          the timeout was already exceeded
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoServiceWatchdogClient", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoServiceWatchdogClient();
+
+        /// <summary>
+        ///  @brief Unregister the process from the watchdog supervisor of iodaemon
+        ///
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoUnregisterWatchdogClient", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoUnregisterWatchdogClient();
+
         /// <summary>
         ///  @brief Get information about device revision and available I/O channels
         ///
@@ -268,6 +346,74 @@ namespace Sysworxx
         [DllImport(__DllName, EntryPoint = "IoCntGetValue", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern IoResult IoCntGetValue(byte uChannel_p, int* piValue_p);
 
+        /// <summary>
+        ///  @brief Load the preload value into a counter channel
+        ///
+        ///  @param uChannel_p The channel to reset
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoCntReset", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoCntReset(byte uChannel_p);
+
+        /// <summary>
+        ///  @brief Set the compare value of a counter channel
+        ///
+        ///  @param uChannel_p The channel to setup
+        ///  @param fEnable_p Enable the compare value with true value, false will disable it
+        ///  @param iCompare_p The compare value
+        ///  @param fAutoReload_p Reload the counter at the compare value
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoCntSetCompare", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoCntSetCompare(byte uChannel_p, IoBool fEnable_p, int iCompare_p, IoBool fAutoReload_p);
+
+        /// <summary>
+        ///  @brief Register a callback for events of a counter channel
+        ///
+        ///  @param uChannel_p The channel of the counter
+        ///  @param pfnCallback_p The callback function to register of type #IoCntCallback
+        ///  @param uEvents_p Mask of the events to signal, bits of #IoCntEvent
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoCntRegisterCallback", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoCntRegisterCallback(byte uChannel_p, delegate* unmanaged[Cdecl]<byte, byte, int, void> pfnCallback_p, byte uEvents_p);
+
+        /// <summary>
+        ///  @brief Un-register the callback for events of a counter channel
+        ///
+        ///  @param uChannel_p Analogous to #IoCntRegisterCallback
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoCntUnregisterCallback", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoCntUnregisterCallback(byte uChannel_p);
+
+        /// <summary>
+        ///  @brief Set the handling of the index pulse (Z) of a counter channel
+        ///
+        ///  @param uChannel_p The channel of the counter
+        ///  @param uMode_p The handling of the index pulse, see IoCntIndexMode
+        ///  @param uSource_p The source of the index pulse, see IoCntIndexSource
+        ///  @param uInput_p The digital input of the index pulse with IoCntIndexSource::Input
+        ///  @param uTrigger_p The edges of the digital input, see IoInputTrigger
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoCntSetIndex", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoCntSetIndex(byte uChannel_p, IoCntIndexMode uMode_p, IoCntIndexSource uSource_p, byte uInput_p, IoInputTrigger uTrigger_p);
+
+        /// <summary>
+        ///  @brief Get the value of a counter channel captured on the index pulse
+        ///
+        ///  @param uChannel_p The channel of the counter
+        ///  @param pfCaptured_p Pointer to the destination which is set to true value
+        ///                      if a value was captured since the last call
+        ///  @param piValue_p Pointer to the destination of the captured value
+        ///  @param puTimestamp_p Pointer to the destination of the time of the index
+        ///                       pulse in nanoseconds since the Unix epoch
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoCntGetCapture", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoCntGetCapture(byte uChannel_p, IoBool* pfCaptured_p, int* piValue_p, ulong* puTimestamp_p);
+
         /// <summary>
         ///  @brief Set the timebase for PWM output
         ///
@@ -299,6 +445,340 @@ namespace Sysworxx
         [DllImport(__DllName, EntryPoint = "IoPwmEnable", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern IoResult IoPwmEnable(byte uChannel_p, [MarshalAs(UnmanagedType.U1)] bool fRun_p);
 
+        /// <summary>
+        ///  @brief Configure period and duty cycle of a PWM output in nanoseconds
+        ///
+        ///  The output never uses a duty cycle which is larger than the period. If both
+        ///  values change, it may run one cycle with the new period and the old duty
+        ///  cycle (or vice versa). Overrides the values of IoPwmSetup().
+        ///
+        ///  @param uChannel_p The channel to configure
+        ///  @param ulPeriod_p The period in nanoseconds
+        ///  @param ulDutyCycle_p The duty cycle in nanoseconds, must not exceed the period
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPwmConfigure", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPwmConfigure(byte uChannel_p, ulong ulPeriod_p, ulong ulDutyCycle_p);
+
+        /// <summary>
+        ///  @brief Configure a PWM output by frequency and duty cycle in percent
+        ///
+        ///  The values are rounded to the nearest nanosecond, see IoPwmConfigure().
+        ///
+        ///  @param uChannel_p The channel to configure
+        ///  @param dFrequency_p The frequency in Hz
+        ///  @param dDutyCycle_p The duty cycle in percent (0.0 - 100.0)
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPwmSetFrequency", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPwmSetFrequency(byte uChannel_p, double dFrequency_p, double dDutyCycle_p);
+
+        /// <summary>
+        ///  @brief Set the polarity of a PWM output
+        ///
+        ///  Most PWM controllers only accept a change of the polarity while disabled,
+        ///  so an enabled output is stopped for the change.
+        ///
+        ///  @param uChannel_p The channel to configure
+        ///  @param polarity Polarity enum
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPwmSetPolarity", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPwmSetPolarity(byte uChannel_p, IoPwmPolarity polarity);
+
+        /// <summary>
+        ///  @brief Append a segment to the parameter table of a pulse train output (PTO)
+        ///
+        ///  @param uChannel_p The PWM channel
+        ///  @param ulPeriod_p The period of the first pulse in nanoseconds
+        ///  @param lDelta_p Change of the period after each pulse in nanoseconds
+        ///  @param uPulseCount_p Number of pulses of the segment
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPtoAddSegment", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPtoAddSegment(byte uChannel_p, ulong ulPeriod_p, long lDelta_p, uint uPulseCount_p);
+
+        /// <summary>
+        ///  @brief Append a segment to the parameter table of a pulse train output (PTO)
+        ///         in units of the PWM timebase
+        ///
+        ///  @param uChannel_p The PWM channel
+        ///  @param uPeriod_p The period of the first pulse in units of the timebase
+        ///  @param iDelta_p Change of the period after each pulse in units of the timebase
+        ///  @param uPulseCount_p Number of pulses of the segment
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPtoAddSegmentTimebase", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPtoAddSegmentTimebase(byte uChannel_p, ushort uPeriod_p, short iDelta_p, uint uPulseCount_p);
+
+        /// <summary>
+        ///  @brief Start or stop a pulse train output (PTO)
+        ///
+        ///  @param uChannel_p The PWM channel
+        ///  @param fRun_p true to start the output of the parameter table, false to stop it
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPtoEnable", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPtoEnable(byte uChannel_p, [MarshalAs(UnmanagedType.U1)] bool fRun_p);
+
+        /// <summary>
+        ///  @brief Get the state of a pulse train output (PTO)
+        ///
+        ///  @param uChannel_p The PWM channel
+        ///  @param peState_p Pointer to the state destination
+        ///  @param pulPulses_p Pointer to the destination for the number of pulses, may be NULL
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPtoGetState", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPtoGetState(byte uChannel_p, IoPtoState* peState_p, ulong* pulPulses_p);
+
+        /// <summary>
+        ///  @brief Register a callback for the completion of a pulse train output (PTO)
+        ///
+        ///  @param uChannel_p The PWM channel
+        ///  @param pfnCallback_p The callback function, NULL to unregister the callback
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPtoRegisterCallback", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPtoRegisterCallback(byte uChannel_p, delegate* unmanaged[Cdecl]<byte, void> pfnCallback_p);
+
+        /// <summary>
+        ///  @brief Open a stepper axis
+        ///
+        ///  The steps are output by the pulse train output of a PWM channel, so the
+        ///  PTO of the channel must not be used at the same time. The direction is
+        ///  output by a digital output. The position of an opened axis is 0. An axis
+        ///  with the same number is closed before.
+        ///
+        ///  @param uAxis_p Number of the axis
+        ///  @param pConfig_p Pointer to the configuration of the axis
+        ///  @return IoResult Driver result code of type IoResult
+        ///
+        ///  # Safety
+        ///
+        ///  `pConfig_p` must be a valid pointer
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoAxisOpen", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoAxisOpen(byte uAxis_p, IoAxisConfig* pConfig_p);
+
+        /// <summary>
+        ///  @brief Close a stepper axis, a movement is stopped immediately
+        ///
+        ///  @param uAxis_p Number of the axis
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoAxisClose", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoAxisClose(byte uAxis_p);
+
+        /// <summary>
+        ///  @brief Move a stepper axis to an absolute position
+        ///
+        ///  The movement follows a trapezoidal velocity profile. A running movement is
+        ///  changed to the new target.
+        ///
+        ///  @param uAxis_p Number of the axis
+        ///  @param lPosition_p Target position in steps
+        ///  @return IoResult Driver result code of type IoResult,
+        ///          IoResult::InvalidParameter if the target is outside of the soft limits
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoAxisMoveTo", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoAxisMoveTo(byte uAxis_p, long lPosition_p);
+
+        /// <summary>
+        ///  @brief Move a stepper axis relative to its current position
+        ///
+        ///  @param uAxis_p Number of the axis
+        ///  @param lSteps_p Number of steps, negative in negative direction
+        ///  @return IoResult Driver result code of type IoResult,
+        ///          IoResult::InvalidParameter if the target is outside of the soft limits
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoAxisMoveBy", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoAxisMoveBy(byte uAxis_p, long lSteps_p);
+
+        /// <summary>
+        ///  @brief Search the limit switch of a stepper axis and set the reference position
+        ///
+        ///  @param uAxis_p Number of the axis
+        ///  @return IoResult Driver result code of type IoResult,
+        ///          IoResult::NotImplemented if homing is not configured
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoAxisHome", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoAxisHome(byte uAxis_p);
+
+        /// <summary>
+        ///  @brief Decelerate a stepper axis to standstill with its acceleration
+        ///
+        ///  @param uAxis_p Number of the axis
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoAxisStop", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoAxisStop(byte uAxis_p);
+
+        /// <summary>
+        ///  @brief Decelerate a stepper axis to standstill with its emergency deceleration
+        ///
+        ///  @param uAxis_p Number of the axis
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoAxisEmergencyStop", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoAxisEmergencyStop(byte uAxis_p);
+
+        /// <summary>
+        ///  @brief Get the status of a stepper axis
+        ///
+        ///  @param uAxis_p Number of the axis
+        ///  @param pStatus_p Pointer to the status destination
+        ///  @return IoResult Driver result code of type IoResult
+        ///
+        ///  # Safety
+        ///
+        ///  `pStatus_p` must be a valid pointer
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoAxisGetStatus", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoAxisGetStatus(byte uAxis_p, IoAxisStatus* pStatus_p);
+
+        /// <summary>
+        ///  @brief Load an external channel provider plugin and append its channels
+        ///
+        ///  The channels of the plugin are appended to the existing channels of each
+        ///  type. Use IoGetHardwareInfo before and after loading the plugin to
+        ///  determine the channel numbers or IoGetJson to get the channel labels.
+        ///
+        ///  @param sPath_p Path to the shared library of the plugin
+        ///  @return IoResult Driver result code of type IoResult
+        ///
+        ///  # Safety
+        ///
+        ///  `sPath_p` must be a valid pointer
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoAddProviderPlugin", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoAddProviderPlugin(byte* sPath_p);
+
+        /// <summary>
+        ///  @brief Start a PID control loop
+        ///
+        ///  The loop reads the process value from an analog input (raw value) or a
+        ///  temperature input (°C) and drives the output with the controller output
+        ///  in percent. A running loop with the same number is stopped before. The
+        ///  loop starts in automatic mode with the setpoint 0 and the gains Kp = 1,
+        ///  Ki = 0 and Kd = 0.
+        ///
+        ///  @param uLoop_p Number of the loop
+        ///  @param eInput_p Type of the process value input (analog or temperature)
+        ///  @param uInputChannel_p Channel of the process value input
+        ///  @param eOutput_p Type of the output #IoPidOutput
+        ///  @param uOutputChannel_p Channel of the output
+        ///  @param uOutputRange_p Range of the output (see #IoPidOutput)
+        ///  @param uCycleMs_p Cycle time of the loop in milliseconds
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPidStart", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPidStart(byte uLoop_p, IoChannelType eInput_p, byte uInputChannel_p, IoPidOutput eOutput_p, byte uOutputChannel_p, uint uOutputRange_p, uint uCycleMs_p);
+
+        /// <summary>
+        ///  @brief Stop a PID control loop and switch off its output
+        ///
+        ///  @param uLoop_p Number of the loop
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPidStop", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPidStop(byte uLoop_p);
+
+        /// <summary>
+        ///  @brief Set the gains of a PID control loop
+        ///
+        ///  @param uLoop_p Number of the loop
+        ///  @param dKp_p Proportional gain (percent per process value unit)
+        ///  @param dKi_p Integral gain (percent per process value unit and second)
+        ///  @param dKd_p Derivative gain (percent per process value unit per second)
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPidSetTunings", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPidSetTunings(byte uLoop_p, double dKp_p, double dKi_p, double dKd_p);
+
+        /// <summary>
+        ///  @brief Get the gains of a PID control loop (e.g. after an autotune)
+        ///
+        ///  @param uLoop_p Number of the loop
+        ///  @param pdKp_p Pointer to the resulting proportional gain
+        ///  @param pdKi_p Pointer to the resulting integral gain
+        ///  @param pdKd_p Pointer to the resulting derivative gain
+        ///  @return IoResult Driver result code of type IoResult
+        ///
+        ///  # Safety
+        ///
+        ///  `pdKp_p` must be a valid pointer
+        ///  `pdKi_p` must be a valid pointer
+        ///  `pdKd_p` must be a valid pointer
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPidGetTunings", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPidGetTunings(byte uLoop_p, double* pdKp_p, double* pdKi_p, double* pdKd_p);
+
+        /// <summary>
+        ///  @brief Limit the output of a PID control loop
+        ///
+        ///  @param uLoop_p Number of the loop
+        ///  @param dMin_p Minimum output in percent (0 to 100)
+        ///  @param dMax_p Maximum output in percent (0 to 100)
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPidSetLimits", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPidSetLimits(byte uLoop_p, double dMin_p, double dMax_p);
+
+        /// <summary>
+        ///  @brief Set the setpoint of a PID control loop
+        ///
+        ///  @param uLoop_p Number of the loop
+        ///  @param dSetpoint_p The setpoint in the unit of the process value
+        ///  @param dRate_p Rate of the setpoint ramp in units per second (0 for a step)
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPidSetSetpoint", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPidSetSetpoint(byte uLoop_p, double dSetpoint_p, double dRate_p);
+
+        /// <summary>
+        ///  @brief Switch a PID control loop between manual and automatic mode
+        ///
+        ///  The transfer from manual to automatic mode is bumpless.
+        ///
+        ///  @param uLoop_p Number of the loop
+        ///  @param fManual_p Switch to manual mode
+        ///  @param dOutput_p Output in percent in manual mode
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPidSetManual", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPidSetManual(byte uLoop_p, IoBool fManual_p, double dOutput_p);
+
+        /// <summary>
+        ///  @brief Start a relay autotune of a PID control loop
+        ///
+        ///  The output is switched between two levels around the current setpoint
+        ///  until the process oscillates. On success the gains are set, the state
+        ///  can be polled with IoPidGetStatus.
+        ///
+        ///  @param uLoop_p Number of the loop
+        ///  @param dLow_p Output in percent while the process value is above the setpoint
+        ///  @param dHigh_p Output in percent while the process value is below the setpoint
+        ///  @param dHysteresis_p Hysteresis around the setpoint (noise band of the process value)
+        ///  @return IoResult Driver result code of type IoResult
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPidAutotune", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPidAutotune(byte uLoop_p, double dLow_p, double dHigh_p, double dHysteresis_p);
+
+        /// <summary>
+        ///  @brief Get the status of a PID control loop
+        ///
+        ///  @param uLoop_p Number of the loop
+        ///  @param pStatus_p Pointer to the status destination
+        ///  @return IoResult Driver result code of type IoResult
+        ///
+        ///  # Safety
+        ///
+        ///  `pStatus_p` must be a valid pointer
+        /// </summary>
+        [DllImport(__DllName, EntryPoint = "IoPidGetStatus", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern IoResult IoPidGetStatus(byte uLoop_p, IoPidStatus* pStatus_p);
+
 
     }
 
@@ -320,6 +800,50 @@ namespace Sysworxx
         public byte m_uLegacyRelayOffset;
     }
 
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct IoAxisConfig
+    {
+        public byte m_uStepChannel;
+        public byte m_uDirectionChannel;
+        public IoBool m_fDirectionInverted;
+        public IoBool m_fEncoder;
+        public byte m_uEncoderChannel;
+        public double m_dCountsPerStep;
+        public double m_dStartVelocity;
+        public double m_dMaxVelocity;
+        public double m_dAcceleration;
+        public double m_dEmergencyDeceleration;
+        public IoBool m_fSoftLimits;
+        public long m_lMinPosition;
+        public long m_lMaxPosition;
+        public IoBool m_fHoming;
+        public byte m_uHomeInput;
+        public IoBool m_fHomeActiveLow;
+        public double m_dHomeVelocity;
+        public long m_lHomePosition;
+        public double m_dTolerance;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct IoAxisStatus
+    {
+        public IoAxisState m_uState;
+        public double m_dPosition;
+        public double m_dVelocity;
+        public IoBool m_fHomed;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct IoPidStatus
+    {
+        public double m_dSetpoint;
+        public double m_dProcessValue;
+        public IoBool m_fValid;
+        public double m_dOutput;
+        public IoBool m_fManual;
+        public IoPidAutotuneState m_uAutotune;
+    }
+
 
     internal enum IoResult : uint
     {
@@ -392,11 +916,70 @@ namespace Sysworxx
         Down = 1,
     }
 
+    internal enum IoCntIndexMode : byte
+    {
+        Off = 0,
+        Reset = 1,
+        Capture = 2,
+    }
+
+    internal enum IoCntIndexSource : byte
+    {
+        Index = 0,
+        Input = 1,
+    }
+
     internal enum IoPwmTimebase : byte
     {
         Ns800 = 1,
         Ms1 = 2,
     }
 
+    internal enum IoPwmPolarity : byte
+    {
+        Normal = 0,
+        Inversed = 1,
+    }
+
+    internal enum IoPtoState : byte
+    {
+        Idle = 0,
+        Running = 1,
+        Done = 2,
+    }
+
+    internal enum IoAxisState : byte
+    {
+        Idle = 0,
+        Moving = 1,
+        Homing = 2,
+        Stopping = 3,
+        Fault = 4,
+    }
+
+    internal enum IoPidOutput : byte
+    {
+        AnalogOutput = 0,
+        Pwm = 1,
+        DigitalOutput = 2,
+    }
+
+    internal enum IoPidAutotuneState : byte
+    {
+        Off = 0,
+        Running = 1,
+        Done = 2,
+        Failed = 3,
+    }
+
+    internal enum IoChannelType : byte
+    {
+        DigitalInput = 0,
+        DigitalOutput = 1,
+        AnalogInput = 2,
+        AnalogOutput = 3,
+        Temperature = 4,
+    }
+
 
 }
@@ -273,6 +273,49 @@ impl From<u8> for IoCntDirection {
     }
 }
 
+/// @brief Handling of the index pulse (Z) of a counter
+#[repr(u8)]
+#[derive(Debug, Copy, Clone)]
+pub enum IoCntIndexMode {
+    /// The index pulse is ignored
+    Off = 0,
+    /// The preload value is loaded into the counter on the index pulse
+    Reset = 1,
+    /// The value of the counter is captured on the index pulse, see IoCntGetCapture
+    Capture = 2,
+}
+
+impl From<u8> for IoCntIndexMode {
+    fn from(value: u8) -> IoCntIndexMode {
+        match value {
+            0 => IoCntIndexMode::Off,
+            1 => IoCntIndexMode::Reset,
+            2 => IoCntIndexMode::Capture,
+            _ => IoCntIndexMode::Off,
+        }
+    }
+}
+
+/// @brief Source of the index pulse of a counter
+#[repr(u8)]
+#[derive(Debug, Copy, Clone)]
+pub enum IoCntIndexSource {
+    /// The index input of the counter hardware
+    Index = 0,
+    /// The edges of a digital input
+    Input = 1,
+}
+
+impl From<u8> for IoCntIndexSource {
+    fn from(value: u8) -> IoCntIndexSource {
+        match value {
+            0 => IoCntIndexSource::Index,
+            1 => IoCntIndexSource::Input,
+            _ => IoCntIndexSource::Index,
+        }
+    }
+}
+
 /// @brief PWM timebase for period and duty cycle setting.
 #[repr(u8)]
 #[derive(Debug, Copy, Clone)]
@@ -291,6 +334,195 @@ impl From<u8> for IoPwmTimebase {
     }
 }
 
+/// @brief Polarity of a PWM output
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum IoPwmPolarity {
+    /// The output is high for the duty cycle
+    Normal = 0,
+    /// The output is low for the duty cycle
+    Inversed = 1,
+}
+
+/// @brief States of a pulse train output (PTO)
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum IoPtoState {
+    Idle = 0,
+    Running = 1,
+    /// All segments of the parameter table were output
+    Done = 2,
+}
+
+/// Callback function for events of counter inputs, called with the channel, the event (bit of
+/// the event mask) and the value of the counter
+pub type IoCntCallback = Option<unsafe extern "C" fn(u8, u8, i32)>;
+
+/// Callback function for the completion of a pulse train output, called with the channel
+pub type IoPtoCallback = Option<unsafe extern "C" fn(u8)>;
+
+/// @brief States of a stepper axis
+#[repr(u8)]
+#[derive(Debug, Copy, Clone)]
+pub enum IoAxisState {
+    Idle = 0,
+    /// Moving to a target position
+    Moving = 1,
+    Homing = 2,
+    /// Decelerating after a stop or emergency stop
+    Stopping = 3,
+    /// The output failed, the axis was stopped
+    Fault = 4,
+}
+
+/// @brief Output types of a PID control loop
+#[repr(u8)]
+#[derive(Debug, Copy, Clone)]
+pub enum IoPidOutput {
+    /// Analog output, the range is the raw value for 100 %
+    AnalogOutput = 0,
+    /// Duty cycle of a PWM output, the range is the PWM period
+    Pwm = 1,
+    /// Time proportioned digital output, the range is the time window in ms
+    DigitalOutput = 2,
+}
+
+/// @brief Autotune states of a PID control loop
+#[repr(u8)]
+#[derive(Debug, Copy, Clone)]
+pub enum IoPidAutotuneState {
+    Off = 0,
+    Running = 1,
+    /// The autotune succeeded and the gains were set
+    Done = 2,
+    Failed = 3,
+}
+
+/// @brief Configuration of a stepper axis
+///
+/// This structure is passed to IoAxisOpen. Positions are in steps, velocities
+/// in steps/s and accelerations in steps/s².
+#[repr(C)]
+#[allow(non_snake_case)]
+#[derive(Debug)]
+pub struct IoAxisConfig {
+    /// PWM channel of the step signal
+    pub m_uStepChannel: u8,
+    /// Digital output of the direction signal, it is set for positive movements
+    pub m_uDirectionChannel: u8,
+    pub m_fDirectionInverted: IoBool,
+    /// Close the loop with the A/B encoder of a counter channel
+    pub m_fEncoder: IoBool,
+    pub m_uEncoderChannel: u8,
+    /// Encoder counts per step, negative if the encoder counts in the opposite direction
+    pub m_dCountsPerStep: f64,
+    /// Velocity at which the axis starts and stops without ramp
+    pub m_dStartVelocity: f64,
+    pub m_dMaxVelocity: f64,
+    pub m_dAcceleration: f64,
+    /// Deceleration of an emergency stop, 0 to stop immediately
+    pub m_dEmergencyDeceleration: f64,
+    /// Reject movements outside of m_lMinPosition and m_lMaxPosition
+    pub m_fSoftLimits: IoBool,
+    pub m_lMinPosition: i64,
+    pub m_lMaxPosition: i64,
+    /// Enable homing on the limit switch at digital input m_uHomeInput
+    pub m_fHoming: IoBool,
+    pub m_uHomeInput: u8,
+    pub m_fHomeActiveLow: IoBool,
+    /// Velocity of the search, the sign gives the direction
+    pub m_dHomeVelocity: f64,
+    /// Position of the axis at the limit switch
+    pub m_lHomePosition: i64,
+    /// Maximum distance to the target at which a movement is finished
+    pub m_dTolerance: f64,
+}
+
+/// @brief Status of a stepper axis
+///
+/// This structure will be filled by IoAxisGetStatus.
+#[repr(C)]
+#[allow(non_snake_case)]
+#[derive(Debug)]
+pub struct IoAxisStatus {
+    pub m_uState: IoAxisState,
+    /// Position in steps
+    pub m_dPosition: f64,
+    /// Velocity in steps/s, negative in negative direction
+    pub m_dVelocity: f64,
+    /// The reference position was set by homing
+    pub m_fHomed: IoBool,
+}
+
+/// @brief Status of a PID control loop
+///
+/// This structure will be filled by IoPidGetStatus.
+#[repr(C)]
+#[allow(non_snake_case)]
+#[derive(Debug)]
+pub struct IoPidStatus {
+    /// Current (ramped) setpoint
+    pub m_dSetpoint: f64,
+    /// Last process value
+    pub m_dProcessValue: f64,
+    /// The process value could be read, otherwise the output is switched off
+    pub m_fValid: IoBool,
+    /// Controller output in percent
+    pub m_dOutput: f64,
+    /// The loop is in manual mode
+    pub m_fManual: IoBool,
+    pub m_uAutotune: IoPidAutotuneState,
+}
+
+/// @brief Channel types of the I/O API
+#[repr(u8)]
+#[derive(Debug, Copy, Clone)]
+pub enum IoChannelType {
+    DigitalInput = 0,
+    DigitalOutput = 1,
+    AnalogInput = 2,
+    AnalogOutput = 3,
+    Temperature = 4,
+}
+
+/// @brief Interface of an external channel provider plugin
+///
+/// A plugin is a shared library which exports the function
+/// `IoResult IoProviderPluginInit(IoProviderPlugin *pPlugin_p)`, which has to
+/// fill this structure. Function pointers for channel types without any
+/// channels may be NULL. The channel numbers passed to the functions are
+/// relative to the plugin. The functions return an IoResult code, unknown codes
+/// are treated as IoResult_Error. A digital input is set for any value other
+/// than 0.
+#[repr(C)]
+#[allow(non_snake_case)]
+pub struct IoProviderPlugin {
+    /// Name of the provider (static NUL terminated string)
+    pub m_pszName: *const std::os::raw::c_char,
+    /// Number of digital inputs
+    pub m_uDiChannels: u8,
+    /// Number of digital outputs
+    pub m_uDoChannels: u8,
+    /// Number of analog inputs
+    pub m_uAiChannels: u8,
+    /// Number of analog outputs
+    pub m_uAoChannels: u8,
+    /// Number of temperature inputs
+    pub m_uTmpChannels: u8,
+    /// Get the label of a channel (static NUL terminated string or NULL)
+    pub m_pfnGetLabel: Option<extern "C" fn(IoChannelType, u8) -> *const std::os::raw::c_char>,
+    /// Get the value of a digital input
+    pub m_pfnGetInput: Option<extern "C" fn(u8, *mut u8) -> u32>,
+    /// Set the value of a digital output
+    pub m_pfnSetOutput: Option<extern "C" fn(u8, IoBool) -> u32>,
+    /// Get the value of an analog input
+    pub m_pfnAdcGetValue: Option<extern "C" fn(u8, *mut i64) -> u32>,
+    /// Set the value of an analog output
+    pub m_pfnDacSetValue: Option<extern "C" fn(u8, i64) -> u32>,
+    /// Get the value of a temperature input in 1/10000 °C
+    pub m_pfnTmpGetValue: Option<extern "C" fn(u8, *mut i32) -> u32>,
+}
+
 extern "C" {
     /// @brief Initializes the I/O driver.
     /// @note This function has to be called before any of the other API
@@ -346,6 +578,81 @@ extern "C" {
     pub fn IoServiceWatchdog() -> IoResult;
 }
 
+extern "C" {
+    /// @brief Set the timeout of the system watchdog
+    ///
+    /// The timeout is applied immediately if the watchdog is enabled, otherwise on
+    /// IoEnableWatchdog(). The driver may round it to a supported value.
+    ///
+    /// @param uTimeout_p Timeout in seconds
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoSetWatchdogTimeout(uTimeout_p: u32) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Get the timeout of the system watchdog and the time until it expires
+    ///
+    /// @param puTimeout_p Pointer to the resulting timeout in seconds
+    /// @param puTimeLeft_p Pointer to the resulting time left in seconds (may be NULL)
+    /// @return IoResult Driver result code of type IoResult
+    ///
+    /// # Safety
+    ///
+    /// `puTimeout_p` must be a valid pointer
+    /// `puTimeLeft_p` must be a valid pointer or NULL
+    pub fn IoGetWatchdogTimeout(puTimeout_p: *mut u32, puTimeLeft_p: *mut u32) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Get the reason of the last reset reported by the system watchdog
+    ///
+    /// @param puBootStatus_p Pointer to the resulting WDIOF_* flags of
+    ///        <linux/watchdog.h>, WDIOF_CARDRESET (0x20) if the watchdog reset
+    ///        the system
+    /// @return IoResult Driver result code of type IoResult
+    ///
+    /// # Safety
+    ///
+    /// `puBootStatus_p` must be a valid pointer
+    pub fn IoGetWatchdogResetReason(puBootStatus_p: *mut u32) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Register the process at the watchdog supervisor of iodaemon
+    ///
+    /// The process must call IoServiceWatchdogClient() within the timeout, otherwise
+    /// iodaemon applies the action configured for the client (see
+    /// /etc/sysworxx-io/iodaemon.conf). Terminating the process without
+    /// IoUnregisterWatchdogClient() or IoShutdown() is handled as failure.
+    ///
+    /// @param pszName_p Name of the client (without whitespace)
+    /// @param uTimeout_p Timeout in milliseconds
+    /// @return IoResult Driver result code of type IoResult
+    ///
+    /// # Safety
+    ///
+    /// `pszName_p` must be a valid pointer to a null terminated string
+    pub fn IoRegisterWatchdogClient(
+        pszName_p: *const std::os::raw::c_char,
+        uTimeout_p: u32,
+    ) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Send a heartbeat to the watchdog supervisor of iodaemon
+    ///
+    /// @return IoResult Driver result code of type IoResult, IoResult::WatchdogTimeout if
+    ///         the timeout was already exceeded
+    pub fn IoServiceWatchdogClient() -> IoResult;
+}
+
+extern "C" {
+    /// @brief Unregister the process from the watchdog supervisor of iodaemon
+    ///
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoUnregisterWatchdogClient() -> IoResult;
+}
+
 #[allow(clippy::not_unsafe_ptr_arg_deref)] // for some reason the unsafe block is ignored by clippy
 extern "C" {
     /// @brief Get information about device revision and available I/O channels
@@ -529,6 +836,88 @@ extern "C" {
     pub fn IoCntGetValue(uChannel_p: u8, piValue_p: *mut i32) -> IoResult;
 }
 
+extern "C" {
+    /// @brief Load the preload value into a counter channel
+    ///
+    /// @param uChannel_p The channel to reset
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoCntReset(uChannel_p: u8) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Set the compare value of a counter channel
+    ///
+    /// @param uChannel_p The channel to setup
+    /// @param fEnable_p Enable the compare value with true value, false will disable it
+    /// @param iCompare_p The compare value
+    /// @param fAutoReload_p Reload the counter at the compare value
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoCntSetCompare(
+        uChannel_p: u8,
+        fEnable_p: IoBool,
+        iCompare_p: i32,
+        fAutoReload_p: IoBool,
+    ) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Register a callback for events of a counter channel
+    ///
+    /// @param uChannel_p The channel of the counter
+    /// @param pfnCallback_p The callback function to register of type #IoCntCallback
+    /// @param uEvents_p Mask of the events to signal, bits of #IoCntEvent
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoCntRegisterCallback(
+        uChannel_p: u8,
+        pfnCallback_p: IoCntCallback,
+        uEvents_p: u8,
+    ) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Un-register the callback for events of a counter channel
+    ///
+    /// @param uChannel_p Analogous to #IoCntRegisterCallback
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoCntUnregisterCallback(uChannel_p: u8) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Set the handling of the index pulse (Z) of a counter channel
+    ///
+    /// @param uChannel_p The channel of the counter
+    /// @param uMode_p The handling of the index pulse, see IoCntIndexMode
+    /// @param uSource_p The source of the index pulse, see IoCntIndexSource
+    /// @param uInput_p The digital input of the index pulse with IoCntIndexSource::Input
+    /// @param uTrigger_p The edges of the digital input, see IoInputTrigger
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoCntSetIndex(
+        uChannel_p: u8,
+        uMode_p: IoCntIndexMode,
+        uSource_p: IoCntIndexSource,
+        uInput_p: u8,
+        uTrigger_p: IoInputTrigger,
+    ) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Get the value of a counter channel captured on the index pulse
+    ///
+    /// @param uChannel_p The channel of the counter
+    /// @param pfCaptured_p Pointer to the destination which is set to true value
+    ///                     if a value was captured since the last call
+    /// @param piValue_p Pointer to the destination of the captured value
+    /// @param puTimestamp_p Pointer to the destination of the time of the index
+    ///                      pulse in nanoseconds since the Unix epoch
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoCntGetCapture(
+        uChannel_p: u8,
+        pfCaptured_p: *mut IoBool,
+        piValue_p: *mut i32,
+        puTimestamp_p: *mut u64,
+    ) -> IoResult;
+}
+
 extern "C" {
     /// @brief Set the timebase for PWM output
     ///
@@ -556,3 +945,338 @@ extern "C" {
     /// @return IoResult Driver result code of type IoResult
     pub fn IoPwmEnable(uChannel_p: u8, fRun_p: bool) -> IoResult;
 }
+
+extern "C" {
+    /// @brief Configure period and duty cycle of a PWM output in nanoseconds
+    ///
+    /// The output never uses a duty cycle which is larger than the period. If both
+    /// values change, it may run one cycle with the new period and the old duty
+    /// cycle (or vice versa). Overrides the values of IoPwmSetup().
+    ///
+    /// @param uChannel_p The channel to configure
+    /// @param ulPeriod_p The period in nanoseconds
+    /// @param ulDutyCycle_p The duty cycle in nanoseconds, must not exceed the period
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPwmConfigure(uChannel_p: u8, ulPeriod_p: u64, ulDutyCycle_p: u64) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Configure a PWM output by frequency and duty cycle in percent
+    ///
+    /// The values are rounded to the nearest nanosecond, see IoPwmConfigure().
+    ///
+    /// @param uChannel_p The channel to configure
+    /// @param dFrequency_p The frequency in Hz
+    /// @param dDutyCycle_p The duty cycle in percent (0.0 - 100.0)
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPwmSetFrequency(uChannel_p: u8, dFrequency_p: f64, dDutyCycle_p: f64) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Set the polarity of a PWM output
+    ///
+    /// Most PWM controllers only accept a change of the polarity while disabled,
+    /// so an enabled output is stopped for the change.
+    ///
+    /// @param uChannel_p The channel to configure
+    /// @param polarity Polarity enum
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPwmSetPolarity(uChannel_p: u8, polarity: IoPwmPolarity) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Append a segment to the parameter table of a pulse train output (PTO)
+    ///
+    /// @param uChannel_p The PWM channel
+    /// @param ulPeriod_p The period of the first pulse in nanoseconds
+    /// @param lDelta_p Change of the period after each pulse in nanoseconds
+    /// @param uPulseCount_p Number of pulses of the segment
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPtoAddSegment(
+        uChannel_p: u8,
+        ulPeriod_p: u64,
+        lDelta_p: i64,
+        uPulseCount_p: u32,
+    ) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Append a segment to the parameter table of a pulse train output (PTO)
+    ///        in units of the PWM timebase
+    ///
+    /// @param uChannel_p The PWM channel
+    /// @param uPeriod_p The period of the first pulse in units of the timebase
+    /// @param iDelta_p Change of the period after each pulse in units of the timebase
+    /// @param uPulseCount_p Number of pulses of the segment
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPtoAddSegmentTimebase(
+        uChannel_p: u8,
+        uPeriod_p: u16,
+        iDelta_p: i16,
+        uPulseCount_p: u32,
+    ) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Start or stop a pulse train output (PTO)
+    ///
+    /// @param uChannel_p The PWM channel
+    /// @param fRun_p true to start the output of the parameter table, false to stop it
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPtoEnable(uChannel_p: u8, fRun_p: bool) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Get the state of a pulse train output (PTO)
+    ///
+    /// @param uChannel_p The PWM channel
+    /// @param peState_p Pointer to the state destination
+    /// @param pulPulses_p Pointer to the destination for the number of pulses, may be NULL
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPtoGetState(
+        uChannel_p: u8,
+        peState_p: *mut IoPtoState,
+        pulPulses_p: *mut u64,
+    ) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Register a callback for the completion of a pulse train output (PTO)
+    ///
+    /// @param uChannel_p The PWM channel
+    /// @param pfnCallback_p The callback function, NULL to unregister the callback
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPtoRegisterCallback(uChannel_p: u8, pfnCallback_p: IoPtoCallback) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Open a stepper axis
+    ///
+    /// The steps are output by the pulse train output of a PWM channel, so the
+    /// PTO of the channel must not be used at the same time. The direction is
+    /// output by a digital output. The position of an opened axis is 0. An axis
+    /// with the same number is closed before.
+    ///
+    /// @param uAxis_p Number of the axis
+    /// @param pConfig_p Pointer to the configuration of the axis
+    /// @return IoResult Driver result code of type IoResult
+    ///
+    /// # Safety
+    ///
+    /// `pConfig_p` must be a valid pointer
+    pub fn IoAxisOpen(uAxis_p: u8, pConfig_p: *const IoAxisConfig) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Close a stepper axis, a movement is stopped immediately
+    ///
+    /// @param uAxis_p Number of the axis
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoAxisClose(uAxis_p: u8) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Move a stepper axis to an absolute position
+    ///
+    /// The movement follows a trapezoidal velocity profile. A running movement is
+    /// changed to the new target.
+    ///
+    /// @param uAxis_p Number of the axis
+    /// @param lPosition_p Target position in steps
+    /// @return IoResult Driver result code of type IoResult,
+    ///         IoResult::InvalidParameter if the target is outside of the soft limits
+    pub fn IoAxisMoveTo(uAxis_p: u8, lPosition_p: i64) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Move a stepper axis relative to its current position
+    ///
+    /// @param uAxis_p Number of the axis
+    /// @param lSteps_p Number of steps, negative in negative direction
+    /// @return IoResult Driver result code of type IoResult,
+    ///         IoResult::InvalidParameter if the target is outside of the soft limits
+    pub fn IoAxisMoveBy(uAxis_p: u8, lSteps_p: i64) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Search the limit switch of a stepper axis and set the reference position
+    ///
+    /// @param uAxis_p Number of the axis
+    /// @return IoResult Driver result code of type IoResult,
+    ///         IoResult::NotImplemented if homing is not configured
+    pub fn IoAxisHome(uAxis_p: u8) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Decelerate a stepper axis to standstill with its acceleration
+    ///
+    /// @param uAxis_p Number of the axis
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoAxisStop(uAxis_p: u8) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Decelerate a stepper axis to standstill with its emergency deceleration
+    ///
+    /// @param uAxis_p Number of the axis
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoAxisEmergencyStop(uAxis_p: u8) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Get the status of a stepper axis
+    ///
+    /// @param uAxis_p Number of the axis
+    /// @param pStatus_p Pointer to the status destination
+    /// @return IoResult Driver result code of type IoResult
+    ///
+    /// # Safety
+    ///
+    /// `pStatus_p` must be a valid pointer
+    pub fn IoAxisGetStatus(uAxis_p: u8, pStatus_p: *mut IoAxisStatus) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Load an external channel provider plugin and append its channels
+    ///
+    /// The channels of the plugin are appended to the existing channels of each
+    /// type. Use IoGetHardwareInfo before and after loading the plugin to
+    /// determine the channel numbers or IoGetJson to get the channel labels.
+    ///
+    /// @param sPath_p Path to the shared library of the plugin
+    /// @return IoResult Driver result code of type IoResult
+    ///
+    /// # Safety
+    ///
+    /// `sPath_p` must be a valid pointer
+    pub fn IoAddProviderPlugin(sPath_p: *const std::os::raw::c_char) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Start a PID control loop
+    ///
+    /// The loop reads the process value from an analog input (raw value) or a
+    /// temperature input (°C) and drives the output with the controller output
+    /// in percent. A running loop with the same number is stopped before. The
+    /// loop starts in automatic mode with the setpoint 0 and the gains Kp = 1,
+    /// Ki = 0 and Kd = 0.
+    ///
+    /// @param uLoop_p Number of the loop
+    /// @param eInput_p Type of the process value input (analog or temperature)
+    /// @param uInputChannel_p Channel of the process value input
+    /// @param eOutput_p Type of the output #IoPidOutput
+    /// @param uOutputChannel_p Channel of the output
+    /// @param uOutputRange_p Range of the output (see #IoPidOutput)
+    /// @param uCycleMs_p Cycle time of the loop in milliseconds
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPidStart(
+        uLoop_p: u8,
+        eInput_p: IoChannelType,
+        uInputChannel_p: u8,
+        eOutput_p: IoPidOutput,
+        uOutputChannel_p: u8,
+        uOutputRange_p: u32,
+        uCycleMs_p: u32,
+    ) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Stop a PID control loop and switch off its output
+    ///
+    /// @param uLoop_p Number of the loop
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPidStop(uLoop_p: u8) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Set the gains of a PID control loop
+    ///
+    /// @param uLoop_p Number of the loop
+    /// @param dKp_p Proportional gain (percent per process value unit)
+    /// @param dKi_p Integral gain (percent per process value unit and second)
+    /// @param dKd_p Derivative gain (percent per process value unit per second)
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPidSetTunings(uLoop_p: u8, dKp_p: f64, dKi_p: f64, dKd_p: f64) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Get the gains of a PID control loop (e.g. after an autotune)
+    ///
+    /// @param uLoop_p Number of the loop
+    /// @param pdKp_p Pointer to the resulting proportional gain
+    /// @param pdKi_p Pointer to the resulting integral gain
+    /// @param pdKd_p Pointer to the resulting derivative gain
+    /// @return IoResult Driver result code of type IoResult
+    ///
+    /// # Safety
+    ///
+    /// `pdKp_p` must be a valid pointer
+    /// `pdKi_p` must be a valid pointer
+    /// `pdKd_p` must be a valid pointer
+    pub fn IoPidGetTunings(
+        uLoop_p: u8,
+        pdKp_p: *mut f64,
+        pdKi_p: *mut f64,
+        pdKd_p: *mut f64,
+    ) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Limit the output of a PID control loop
+    ///
+    /// @param uLoop_p Number of the loop
+    /// @param dMin_p Minimum output in percent (0 to 100)
+    /// @param dMax_p Maximum output in percent (0 to 100)
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPidSetLimits(uLoop_p: u8, dMin_p: f64, dMax_p: f64) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Set the setpoint of a PID control loop
+    ///
+    /// @param uLoop_p Number of the loop
+    /// @param dSetpoint_p The setpoint in the unit of the process value
+    /// @param dRate_p Rate of the setpoint ramp in units per second (0 for a step)
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPidSetSetpoint(uLoop_p: u8, dSetpoint_p: f64, dRate_p: f64) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Switch a PID control loop between manual and automatic mode
+    ///
+    /// The transfer from manual to automatic mode is bumpless.
+    ///
+    /// @param uLoop_p Number of the loop
+    /// @param fManual_p Switch to manual mode
+    /// @param dOutput_p Output in percent in manual mode
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPidSetManual(uLoop_p: u8, fManual_p: IoBool, dOutput_p: f64) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Start a relay autotune of a PID control loop
+    ///
+    /// The output is switched between two levels around the current setpoint
+    /// until the process oscillates. On success the gains are set, the state
+    /// can be polled with IoPidGetStatus.
+    ///
+    /// @param uLoop_p Number of the loop
+    /// @param dLow_p Output in percent while the process value is above the setpoint
+    /// @param dHigh_p Output in percent while the process value is below the setpoint
+    /// @param dHysteresis_p Hysteresis around the setpoint (noise band of the process value)
+    /// @return IoResult Driver result code of type IoResult
+    pub fn IoPidAutotune(uLoop_p: u8, dLow_p: f64, dHigh_p: f64, dHysteresis_p: f64) -> IoResult;
+}
+
+extern "C" {
+    /// @brief Get the status of a PID control loop
+    ///
+    /// @param uLoop_p Number of the loop
+    /// @param pStatus_p Pointer to the status destination
+    /// @return IoResult Driver result code of type IoResult
+    ///
+    /// # Safety
+    ///
+    /// `pStatus_p` must be a valid pointer
+    pub fn IoPidGetStatus(uLoop_p: u8, pStatus_p: *mut IoPidStatus) -> IoResult;
+}
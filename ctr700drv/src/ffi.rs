@@ -83,6 +83,34 @@ pub const kCtr700DrvCounterDirection_Down: tCtr700DrvCounterDirection = 1;
 #[doc = " @brief Counter direction type can be used to invert the direction of"]
 #[doc = "        counting."]
 pub type tCtr700DrvCounterDirection = ::std::os::raw::c_uint;
+#[doc = "< The counter reached or passed the compare value"]
+pub const kCtr700DrvCounterEvent_Compare: tCtr700DrvCounterEvent = 0x01;
+#[doc = "< The counter passed its maximum value"]
+pub const kCtr700DrvCounterEvent_Overflow: tCtr700DrvCounterEvent = 0x02;
+#[doc = "< The counter passed its minimum value"]
+pub const kCtr700DrvCounterEvent_Underflow: tCtr700DrvCounterEvent = 0x04;
+#[doc = "< The direction of counting changed"]
+pub const kCtr700DrvCounterEvent_Direction: tCtr700DrvCounterEvent = 0x08;
+#[doc = " @brief Counter events, bits of the event mask"]
+pub type tCtr700DrvCounterEvent = ::std::os::raw::c_uint;
+#[doc = " @brief Callback function type for counter events, called with the channel,"]
+#[doc = "        the event and the value of the counter"]
+pub type tCtr700DrvCounterCallback =
+    ::std::option::Option<unsafe extern "C" fn(arg1: u8, arg2: u8, arg3: i32)>;
+#[doc = "< The index pulse is ignored"]
+pub const kCtr700DrvCounterIndex_Off: tCtr700DrvCounterIndex = 0;
+#[doc = "< The preload value is loaded into the counter on the index pulse"]
+pub const kCtr700DrvCounterIndex_Reset: tCtr700DrvCounterIndex = 1;
+#[doc = "< The value of the counter is captured on the index pulse"]
+pub const kCtr700DrvCounterIndex_Capture: tCtr700DrvCounterIndex = 2;
+#[doc = " @brief Handling of the index pulse (Z) of a counter"]
+pub type tCtr700DrvCounterIndex = ::std::os::raw::c_uint;
+#[doc = "< The index input of the counter hardware"]
+pub const kCtr700DrvCounterIndexSource_Index: tCtr700DrvCounterIndexSource = 0;
+#[doc = "< The edges of a digital input"]
+pub const kCtr700DrvCounterIndexSource_Input: tCtr700DrvCounterIndexSource = 1;
+#[doc = " @brief Source of the index pulse of a counter"]
+pub type tCtr700DrvCounterIndexSource = ::std::os::raw::c_uint;
 pub const kCtr700DrvPwm_Channel0: tCtr700DrvPwm = 0;
 pub const kCtr700DrvPwm_Channel1: tCtr700DrvPwm = 1;
 #[doc = " @brief PWM channel type"]
@@ -699,6 +727,124 @@ pub extern "C" fn Ctr700DrvCntGetValue(uChannel_p: u8, piValue_p: *mut i32) -> i
     unsafe { IoCntGetValue(uChannel_p, piValue_p) as i32 }
 }
 
+#[doc = " @brief Load the preload value into a counter channel"]
+#[doc = ""]
+#[doc = " @param uChannel_p The channel to reset"]
+#[doc = " @return int32_t Driver result code of type tCtr700DrvResult"]
+#[no_mangle]
+pub extern "C" fn Ctr700DrvCntReset(uChannel_p: u8) -> i32 {
+    unsafe { IoCntReset(uChannel_p) as i32 }
+}
+
+#[doc = " @brief Set the compare value of a counter channel"]
+#[doc = ""]
+#[doc = " @param uChannel_p The channel to setup"]
+#[doc = " @param fEnable_p @see kCtr700Drv_True to enable the compare value,"]
+#[doc = "                  @see kCtr700Drv_False to disable it"]
+#[doc = " @param iCompare_p The compare value"]
+#[doc = " @param fAutoReload_p @see kCtr700Drv_True to reload the counter with the"]
+#[doc = "                      preload value after it passed the compare value"]
+#[doc = " @return int32_t Driver result code of type tCtr700DrvResult"]
+#[no_mangle]
+pub extern "C" fn Ctr700DrvCntSetCompare(
+    uChannel_p: u8,
+    fEnable_p: u8,
+    iCompare_p: i32,
+    fAutoReload_p: u8,
+) -> i32 {
+    unsafe {
+        IoCntSetCompare(
+            uChannel_p,
+            IoBool::from(fEnable_p),
+            iCompare_p,
+            IoBool::from(fAutoReload_p),
+        ) as i32
+    }
+}
+
+#[doc = " @brief Register a callback to signal events of a counter channel"]
+#[doc = ""]
+#[doc = " @param uChannel_p The channel of the counter"]
+#[doc = " @param pfnCallback_p The callback function to register of type"]
+#[doc = "                      #tCtr700DrvCounterCallback"]
+#[doc = " @param uEvents_p Mask of the events to signal, see #tCtr700DrvCounterEvent"]
+#[doc = " @return int32_t Driver result code of type tCtr700DrvResult"]
+#[no_mangle]
+pub extern "C" fn Ctr700DrvCntRegisterCallback(
+    uChannel_p: u8,
+    pfnCallback_p: tCtr700DrvCounterCallback,
+    uEvents_p: u8,
+) -> i32 {
+    unsafe { IoCntRegisterCallback(uChannel_p, pfnCallback_p, uEvents_p) as i32 }
+}
+
+#[doc = " @brief Un-register the callback for events of a counter channel"]
+#[doc = ""]
+#[doc = " @param uChannel_p Analogous to #Ctr700DrvCntRegisterCallback"]
+#[doc = " @return int32_t Driver result code of type tCtr700DrvResult"]
+#[no_mangle]
+pub extern "C" fn Ctr700DrvCntUnregisterCallback(uChannel_p: u8) -> i32 {
+    unsafe { IoCntUnregisterCallback(uChannel_p) as i32 }
+}
+
+#[doc = " @brief Set the handling of the index pulse (Z) of a counter channel"]
+#[doc = ""]
+#[doc = " @param uChannel_p The channel to setup"]
+#[doc = " @param uMode_p The handling of the index pulse #tCtr700DrvCounterIndex"]
+#[doc = " @param uSource_p The source of the index pulse #tCtr700DrvCounterIndexSource"]
+#[doc = " @param uInput_p The digital input of the index pulse with"]
+#[doc = "                 #kCtr700DrvCounterIndexSource_Input"]
+#[doc = " @param uTrigger_p The edges of the digital input #tCtr700DrvInterruptTrigger"]
+#[doc = " @return int32_t Driver result code of type tCtr700DrvResult"]
+#[no_mangle]
+pub extern "C" fn Ctr700DrvCntSetIndex(
+    uChannel_p: u8,
+    uMode_p: u8,
+    uSource_p: u8,
+    uInput_p: u8,
+    uTrigger_p: u32,
+) -> i32 {
+    unsafe {
+        IoCntSetIndex(
+            uChannel_p,
+            IoCntIndexMode::from(uMode_p),
+            IoCntIndexSource::from(uSource_p),
+            uInput_p,
+            IoInputTrigger::from(uTrigger_p),
+        ) as i32
+    }
+}
+
+#[doc = " @brief Get the value of a counter channel captured on the index pulse"]
+#[doc = ""]
+#[doc = " @param uChannel_p The channel of the counter"]
+#[doc = " @param pfCaptured_p Pointer to the destination which is set to"]
+#[doc = "                    @see kCtr700Drv_True if a value was captured"]
+#[doc = " @param piValue_p Pointer to the destination of the captured value"]
+#[doc = " @param puTimestamp_p Pointer to the destination of the time of the index"]
+#[doc = "                     pulse in nanoseconds since the Unix epoch"]
+#[doc = " @return int32_t Driver result code of type tCtr700DrvResult"]
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)] // for some reason the unsafe block is ignored by clippy
+pub extern "C" fn Ctr700DrvCntGetCapture(
+    uChannel_p: u8,
+    pfCaptured_p: *mut u8,
+    piValue_p: *mut i32,
+    puTimestamp_p: *mut u64,
+) -> i32 {
+    check_ptr!(pfCaptured_p, kCtr700DrvResult_InvalidParameter as i32);
+
+    unsafe {
+        let mut captured: IoBool = IoBool::False;
+        let ret = IoCntGetCapture(uChannel_p, &mut captured, piValue_p, puTimestamp_p) as i32;
+        match captured {
+            IoBool::True => *pfCaptured_p = tCtr700Drv_Bool_kCtr700Drv_True,
+            IoBool::False => *pfCaptured_p = tCtr700Drv_Bool_kCtr700Drv_False,
+        }
+        ret
+    }
+}
+
 #[doc = " @brief Set the timebase for PWM output"]
 #[doc = ""]
 #[doc = " @param uChannel_p The channel to get the value for"]
@@ -731,41 +877,52 @@ pub extern "C" fn Ctr700DrvPwmEnable(uChannel_p: u8, fRun_p: u8) -> i32 {
     unsafe { IoPwmEnable(uChannel_p, fRun_p != 0) as i32 }
 }
 
-#[doc = " @brief *Not implemented*"]
+#[doc = " @brief Append a segment to the parameter table of a PTO channel"]
 #[doc = ""]
-#[doc = " @param uChannel_p -"]
-#[doc = " @param uPeriod_p -"]
-#[doc = " @param iDelta_p -"]
-#[doc = " @param uPulseCnt_p -"]
+#[doc = " @param uChannel_p The channel to setup"]
+#[doc = " @param uPeriod_p The period of the first pulse in units set by #Ctr700DrvPwmSetTimeBase"]
+#[doc = " @param iDelta_p Change of the period after each pulse in units set by"]
+#[doc = "                 #Ctr700DrvPwmSetTimeBase"]
+#[doc = " @param uPulseCnt_p Number of pulses of the segment"]
 #[doc = " @return int32_t Driver result code of type tCtr700DrvResult"]
 #[no_mangle]
 pub extern "C" fn Ctr700DrvPtoSetParam(
-    _uChannel_p: u8,
-    _uPeriod_p: u16,
-    _iDelta_p: i16,
-    _uPulseCnt_p: u32,
+    uChannel_p: u8,
+    uPeriod_p: u16,
+    iDelta_p: i16,
+    uPulseCnt_p: u32,
 ) -> i32 {
-    kCtr700DrvResult_NotImplemented as i32
+    unsafe { IoPtoAddSegmentTimebase(uChannel_p, uPeriod_p, iDelta_p, uPulseCnt_p) as i32 }
 }
 
-#[doc = " @brief *Not implemented*"]
+#[doc = " @brief Start / stop the output of the parameter table of a PTO channel"]
 #[doc = ""]
-#[doc = " @param uChannel_p -"]
-#[doc = " @param fRun_p -"]
+#[doc = " @param uChannel_p The channel to start/stop"]
+#[doc = " @param fRun_p @see kCtr700Drv_True to start,"]
+#[doc = "               @see kCtr700Drv_False to stop and discard the remaining segments"]
 #[doc = " @return int32_t Driver result code of type tCtr700DrvResult"]
 #[no_mangle]
-pub extern "C" fn Ctr700DrvPtoEnable(_uChannel_p: u8, _fRun_p: u8) -> i32 {
-    kCtr700DrvResult_NotImplemented as i32
+pub extern "C" fn Ctr700DrvPtoEnable(uChannel_p: u8, fRun_p: u8) -> i32 {
+    unsafe { IoPtoEnable(uChannel_p, fRun_p != 0) as i32 }
 }
 
-#[doc = " @brief *Not implemented*"]
+#[doc = " @brief Get the state of a PTO channel"]
 #[doc = ""]
-#[doc = " @param uChannel_p -"]
-#[doc = " @param pfRun_p -"]
+#[doc = " @param uChannel_p The channel to get the state for"]
+#[doc = " @param pfRun_p Pointer to the destination, @see kCtr700Drv_True while the"]
+#[doc = "                parameter table is output"]
 #[doc = " @return int32_t Driver result code of type tCtr700DrvResult"]
 #[no_mangle]
-pub extern "C" fn Ctr700DrvPtoGetState(_uChannel_p: u8, _pfRun_p: *mut u8) -> i32 {
-    kCtr700DrvResult_NotImplemented as i32
+#[allow(clippy::not_unsafe_ptr_arg_deref)] // for some reason the unsafe block is ignored by clippy
+pub extern "C" fn Ctr700DrvPtoGetState(uChannel_p: u8, pfRun_p: *mut u8) -> i32 {
+    check_ptr!(pfRun_p, kCtr700DrvResult_InvalidParameter as i32);
+
+    let mut state = IoPtoState::Idle;
+    let result = unsafe { IoPtoGetState(uChannel_p, &mut state, std::ptr::null_mut()) };
+    if let IoResult::Success = result {
+        unsafe { *pfRun_p = (state == IoPtoState::Running) as u8 };
+    }
+    result as i32
 }
 
 #[doc = " @brief Get the value of an ADC channel"]
@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Staircase light: a button on DI0 switches DO0 on for 10 seconds, pressing the button again
+// restarts the time. DO1 blinks while the light is on.
+
+use std::time::Duration;
+
+use sysworxx_io::blocks::{DigitalIn, DigitalOut, RTrig, ScanCycle, Tof, Ton};
+use sysworxx_io::{definition, hw_rev, signal};
+
+fn main() -> Result<(), sysworxx_io::error::Error> {
+    let device = hw_rev::get_device_name().unwrap_or("fallback".to_string());
+    let mut io = definition::load_device_definition(&device);
+    io.init()?;
+
+    let button = DigitalIn::bind(&io, 0)?;
+    let light = DigitalOut::bind(&io, 0)?;
+    let blink = DigitalOut::bind(&io, 1)?;
+
+    let mut pressed = RTrig::new();
+    let mut on_time = Tof::new(Duration::from_secs(10));
+    let mut blink_on = Ton::new(Duration::from_millis(500));
+    let mut blink_off = Ton::new(Duration::from_millis(500));
+
+    let stop = signal::notify(&[signal::SIGINT, signal::SIGTERM])?;
+    let mut cycle = ScanCycle::new(io, Duration::from_millis(10));
+    cycle.run(&stop, |scan| {
+        let now = scan.now();
+        let on = on_time.call(pressed.call(scan.get(button)?), now);
+        let phase = blink_on.call(on && !blink_off.q(), now);
+        blink_off.call(phase, now);
+
+        scan.set(light, on);
+        scan.set(blink, on && !phase);
+        Ok(())
+    })?;
+
+    cycle.into_inner().shutdown()
+}
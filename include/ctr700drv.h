@@ -157,6 +157,42 @@ typedef enum
     kCtr700DrvCounterDirection_Down     = 1
 } tCtr700DrvCounterDirection;
 
+/**
+ * @brief Counter events, bits of the event mask
+ */
+typedef enum
+{
+    kCtr700DrvCounterEvent_Compare      = 0x01, /**< The counter reached or passed the compare value */
+    kCtr700DrvCounterEvent_Overflow     = 0x02, /**< The counter passed its maximum value */
+    kCtr700DrvCounterEvent_Underflow    = 0x04, /**< The counter passed its minimum value */
+    kCtr700DrvCounterEvent_Direction    = 0x08  /**< The direction of counting changed */
+} tCtr700DrvCounterEvent;
+
+/**
+ * @brief Callback function type for counter events, called with the channel,
+ *        the event and the value of the counter
+ */
+typedef void (*tCtr700DrvCounterCallback)(uint8_t, uint8_t, int32_t);
+
+/**
+ * @brief Handling of the index pulse (Z) of a counter
+ */
+typedef enum
+{
+    kCtr700DrvCounterIndex_Off          = 0, /**< The index pulse is ignored */
+    kCtr700DrvCounterIndex_Reset        = 1, /**< The preload value is loaded into the counter on the index pulse */
+    kCtr700DrvCounterIndex_Capture      = 2  /**< The value of the counter is captured on the index pulse */
+} tCtr700DrvCounterIndex;
+
+/**
+ * @brief Source of the index pulse of a counter
+ */
+typedef enum
+{
+    kCtr700DrvCounterIndexSource_Index  = 0, /**< The index input of the counter hardware */
+    kCtr700DrvCounterIndexSource_Input  = 1  /**< The edges of a digital input */
+} tCtr700DrvCounterIndexSource;
+
 /**
  * @brief PWM channel type
  */
@@ -497,6 +533,78 @@ int32_t Ctr700DrvCntSetPreload      (uint8_t uChannel_p, int32_t iPreload_p);
  */
 int32_t Ctr700DrvCntGetValue        (uint8_t uChannel_p, int32_t* piValue_p);
 
+/**
+ * @brief Load the preload value into a counter channel
+ *
+ * @param uChannel_p The channel to reset
+ * @return int32_t Driver result code of type tCtr700DrvResult
+ */
+int32_t Ctr700DrvCntReset           (uint8_t uChannel_p);
+
+/**
+ * @brief Set the compare value of a counter channel
+ *
+ * @param uChannel_p The channel to setup
+ * @param fEnable_p @see kCtr700Drv_True to enable the compare value,
+ *                  @see kCtr700Drv_False to disable it
+ * @param iCompare_p The compare value
+ * @param fAutoReload_p @see kCtr700Drv_True to reload the counter with the
+ *                      preload value after it passed the compare value
+ * @return int32_t Driver result code of type tCtr700DrvResult
+ */
+int32_t Ctr700DrvCntSetCompare      (uint8_t uChannel_p, uint8_t fEnable_p,
+                                     int32_t iCompare_p, uint8_t fAutoReload_p);
+
+/**
+ * @brief Register a callback to signal events of a counter channel
+ *
+ * @param uChannel_p The channel of the counter
+ * @param pfnCallback_p The callback function to register of type
+ *                      #tCtr700DrvCounterCallback
+ * @param uEvents_p Mask of the events to signal, see #tCtr700DrvCounterEvent
+ * @return int32_t Driver result code of type tCtr700DrvResult
+ */
+int32_t Ctr700DrvCntRegisterCallback (uint8_t uChannel_p,
+                                      tCtr700DrvCounterCallback pfnCallback_p,
+                                      uint8_t uEvents_p);
+
+/**
+ * @brief Un-register the callback for events of a counter channel
+ *
+ * @param uChannel_p Analogous to #Ctr700DrvCntRegisterCallback
+ * @return int32_t Driver result code of type tCtr700DrvResult
+ */
+int32_t Ctr700DrvCntUnregisterCallback (uint8_t uChannel_p);
+
+/**
+ * @brief Set the handling of the index pulse (Z) of a counter channel
+ *
+ * @param uChannel_p The channel to setup
+ * @param uMode_p The handling of the index pulse #tCtr700DrvCounterIndex
+ * @param uSource_p The source of the index pulse #tCtr700DrvCounterIndexSource
+ * @param uInput_p The digital input of the index pulse with
+ *                 #kCtr700DrvCounterIndexSource_Input
+ * @param uTrigger_p The edges of the digital input #tCtr700DrvInterruptTrigger
+ * @return int32_t Driver result code of type tCtr700DrvResult
+ */
+int32_t Ctr700DrvCntSetIndex        (uint8_t uChannel_p, uint8_t uMode_p,
+                                     uint8_t uSource_p, uint8_t uInput_p,
+                                     uint32_t uTrigger_p);
+
+/**
+ * @brief Get the value of a counter channel captured on the index pulse
+ *
+ * @param uChannel_p The channel of the counter
+ * @param pfCaptured_p Pointer to the destination which is set to
+ *                    @see kCtr700Drv_True if a value was captured
+ * @param piValue_p Pointer to the destination of the captured value
+ * @param puTimestamp_p Pointer to the destination of the time of the index
+ *                     pulse in nanoseconds since the Unix epoch
+ * @return int32_t Driver result code of type tCtr700DrvResult
+ */
+int32_t Ctr700DrvCntGetCapture      (uint8_t uChannel_p, uint8_t* pfCaptured_p,
+                                     int32_t* piValue_p, uint64_t* puTimestamp_p);
+
 /** @} */
 
 /**
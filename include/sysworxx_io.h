@@ -27,6 +27,34 @@ enum IoAnalogMode
 typedef uint8_t IoAnalogMode;
 #endif // __cplusplus
 
+/**
+ * @brief States of a stepper axis
+ */
+enum IoAxisState
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+
+{
+    IoAxisState_Idle = 0,
+    /**
+     * Moving to a target position
+     */
+    IoAxisState_Moving = 1,
+    IoAxisState_Homing = 2,
+    /**
+     * Decelerating after a stop or emergency stop
+     */
+    IoAxisState_Stopping = 3,
+    /**
+     * The output failed, the axis was stopped
+     */
+    IoAxisState_Fault = 4,
+};
+#ifndef __cplusplus
+typedef uint8_t IoAxisState;
+#endif // __cplusplus
+
 /**
  * @brief Boolean type for usage of this API
  */
@@ -43,6 +71,25 @@ enum IoBool
 typedef uint8_t IoBool;
 #endif // __cplusplus
 
+/**
+ * @brief Channel types of the I/O API
+ */
+enum IoChannelType
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+
+{
+    IoChannelType_DigitalInput = 0,
+    IoChannelType_DigitalOutput = 1,
+    IoChannelType_AnalogInput = 2,
+    IoChannelType_AnalogOutput = 3,
+    IoChannelType_Temperature = 4,
+};
+#ifndef __cplusplus
+typedef uint8_t IoChannelType;
+#endif // __cplusplus
+
 /**
  * @brief Counter direction type can be used to invert the direction of counting.
  */
@@ -59,6 +106,84 @@ enum IoCntDirection
 typedef uint8_t IoCntDirection;
 #endif // __cplusplus
 
+/**
+ * @brief Events of counter inputs, the bits of the event mask for IoCntRegisterCallback
+ */
+enum IoCntEvent
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+
+{
+    /**
+     * The counter reached or passed the compare value
+     */
+    IoCntEvent_Compare = 1,
+    /**
+     * The counter passed its maximum value (the compare value with auto-reload)
+     */
+    IoCntEvent_Overflow = 2,
+    /**
+     * The counter passed its minimum value (the preload value with auto-reload)
+     */
+    IoCntEvent_Underflow = 4,
+    /**
+     * The direction of counting changed
+     */
+    IoCntEvent_Direction = 8,
+};
+#ifndef __cplusplus
+typedef uint8_t IoCntEvent;
+#endif // __cplusplus
+
+/**
+ * @brief Handling of the index pulse (Z) of a counter
+ */
+enum IoCntIndexMode
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+
+{
+    /**
+     * The index pulse is ignored
+     */
+    IoCntIndexMode_Off = 0,
+    /**
+     * The preload value is loaded into the counter on the index pulse
+     */
+    IoCntIndexMode_Reset = 1,
+    /**
+     * The value of the counter is captured on the index pulse, see IoCntGetCapture
+     */
+    IoCntIndexMode_Capture = 2,
+};
+#ifndef __cplusplus
+typedef uint8_t IoCntIndexMode;
+#endif // __cplusplus
+
+/**
+ * @brief Source of the index pulse of a counter
+ */
+enum IoCntIndexSource
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+
+{
+    /**
+     * The index input of the counter hardware
+     */
+    IoCntIndexSource_Index = 0,
+    /**
+     * The edges of a digital input
+     */
+    IoCntIndexSource_Input = 1,
+};
+#ifndef __cplusplus
+typedef uint8_t IoCntIndexSource;
+#endif // __cplusplus
+
 /**
  * @brief Counter mode type
  */
@@ -131,6 +256,53 @@ enum IoInputTrigger
 typedef uint8_t IoInputTrigger;
 #endif // __cplusplus
 
+/**
+ * @brief Autotune states of a PID control loop
+ */
+enum IoPidAutotuneState
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+
+{
+    IoPidAutotuneState_Off = 0,
+    IoPidAutotuneState_Running = 1,
+    /**
+     * The autotune succeeded and the gains were set
+     */
+    IoPidAutotuneState_Done = 2,
+    IoPidAutotuneState_Failed = 3,
+};
+#ifndef __cplusplus
+typedef uint8_t IoPidAutotuneState;
+#endif // __cplusplus
+
+/**
+ * @brief Output types of a PID control loop
+ */
+enum IoPidOutput
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+
+{
+    /**
+     * Analog output, the range is the raw value for 100 %
+     */
+    IoPidOutput_AnalogOutput = 0,
+    /**
+     * Duty cycle of a PWM output, the range is the PWM period
+     */
+    IoPidOutput_Pwm = 1,
+    /**
+     * Time proportioned digital output, the range is the time window in ms
+     */
+    IoPidOutput_DigitalOutput = 2,
+};
+#ifndef __cplusplus
+typedef uint8_t IoPidOutput;
+#endif // __cplusplus
+
 /**
  * @brief PWM timebase for period and duty cycle setting.
  */
@@ -147,6 +319,48 @@ enum IoPwmTimebase
 typedef uint8_t IoPwmTimebase;
 #endif // __cplusplus
 
+/**
+ * @brief Polarity of a PWM output
+ */
+enum IoPwmPolarity
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+
+{
+    /**
+     * The output is high for the duty cycle
+     */
+    IoPwmPolarity_Normal = 0,
+    /**
+     * The output is low for the duty cycle
+     */
+    IoPwmPolarity_Inversed = 1,
+};
+#ifndef __cplusplus
+typedef uint8_t IoPwmPolarity;
+#endif // __cplusplus
+
+/**
+ * @brief States of a pulse train output (PTO)
+ */
+enum IoPtoState
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+
+{
+    IoPtoState_Idle = 0,
+    IoPtoState_Running = 1,
+    /**
+     * All segments of the parameter table were output
+     */
+    IoPtoState_Done = 2,
+};
+#ifndef __cplusplus
+typedef uint8_t IoPtoState;
+#endif // __cplusplus
+
 enum IoResult
 #ifdef __cplusplus
   : uint32_t
@@ -251,6 +465,90 @@ enum IoTmpSensorType
 typedef uint8_t IoTmpSensorType;
 #endif // __cplusplus
 
+/**
+ * @brief Configuration of a stepper axis
+ *
+ * This structure is passed to IoAxisOpen. Positions are in steps, velocities
+ * in steps/s and accelerations in steps/s².
+ */
+struct IoAxisConfig
+{
+    /**
+     * PWM channel of the step signal
+     */
+    uint8_t m_uStepChannel;
+    /**
+     * Digital output of the direction signal, it is set for positive movements
+     */
+    uint8_t m_uDirectionChannel;
+    IoBool m_fDirectionInverted;
+    /**
+     * Close the loop with the A/B encoder of a counter channel
+     */
+    IoBool m_fEncoder;
+    uint8_t m_uEncoderChannel;
+    /**
+     * Encoder counts per step, negative if the encoder counts in the opposite direction
+     */
+    double m_dCountsPerStep;
+    /**
+     * Velocity at which the axis starts and stops without ramp
+     */
+    double m_dStartVelocity;
+    double m_dMaxVelocity;
+    double m_dAcceleration;
+    /**
+     * Deceleration of an emergency stop, 0 to stop immediately
+     */
+    double m_dEmergencyDeceleration;
+    /**
+     * Reject movements outside of m_lMinPosition and m_lMaxPosition
+     */
+    IoBool m_fSoftLimits;
+    int64_t m_lMinPosition;
+    int64_t m_lMaxPosition;
+    /**
+     * Enable homing on the limit switch at digital input m_uHomeInput
+     */
+    IoBool m_fHoming;
+    uint8_t m_uHomeInput;
+    IoBool m_fHomeActiveLow;
+    /**
+     * Velocity of the search, the sign gives the direction
+     */
+    double m_dHomeVelocity;
+    /**
+     * Position of the axis at the limit switch
+     */
+    int64_t m_lHomePosition;
+    /**
+     * Maximum distance to the target at which a movement is finished
+     */
+    double m_dTolerance;
+};
+
+/**
+ * @brief Status of a stepper axis
+ *
+ * This structure will be filled by IoAxisGetStatus.
+ */
+struct IoAxisStatus
+{
+    IoAxisState m_uState;
+    /**
+     * Position in steps
+     */
+    double m_dPosition;
+    /**
+     * Velocity in steps/s, negative in negative direction
+     */
+    double m_dVelocity;
+    /**
+     * The reference position was set by homing
+     */
+    IoBool m_fHomed;
+};
+
 /**
  * @brief Hardware information structure
  *
@@ -314,11 +612,115 @@ struct IoHwInfo
     uint8_t m_uLegacyRelayOffset;
 };
 
+/**
+ * @brief Status of a PID control loop
+ *
+ * This structure will be filled by IoPidGetStatus.
+ */
+struct IoPidStatus
+{
+    /**
+     * Current (ramped) setpoint
+     */
+    double m_dSetpoint;
+    /**
+     * Last process value
+     */
+    double m_dProcessValue;
+    /**
+     * The process value could be read, otherwise the output is switched off
+     */
+    IoBool m_fValid;
+    /**
+     * Controller output in percent
+     */
+    double m_dOutput;
+    /**
+     * The loop is in manual mode
+     */
+    IoBool m_fManual;
+    IoPidAutotuneState m_uAutotune;
+};
+
+/**
+ * @brief Interface of an external channel provider plugin
+ *
+ * A plugin is a shared library which exports the function
+ * `IoResult IoProviderPluginInit(IoProviderPlugin *pPlugin_p)`, which has to
+ * fill this structure. Function pointers for channel types without any
+ * channels may be NULL. The channel numbers passed to the functions are
+ * relative to the plugin. The functions return an IoResult code, unknown codes
+ * are treated as IoResult_Error. A digital input is set for any value other
+ * than 0.
+ */
+struct IoProviderPlugin
+{
+    /**
+     * Name of the provider (static NUL terminated string)
+     */
+    const char *m_pszName;
+    /**
+     * Number of digital inputs
+     */
+    uint8_t m_uDiChannels;
+    /**
+     * Number of digital outputs
+     */
+    uint8_t m_uDoChannels;
+    /**
+     * Number of analog inputs
+     */
+    uint8_t m_uAiChannels;
+    /**
+     * Number of analog outputs
+     */
+    uint8_t m_uAoChannels;
+    /**
+     * Number of temperature inputs
+     */
+    uint8_t m_uTmpChannels;
+    /**
+     * Get the label of a channel (static NUL terminated string or NULL)
+     */
+    const char *(*m_pfnGetLabel)(IoChannelType, uint8_t);
+    /**
+     * Get the value of a digital input
+     */
+    uint32_t (*m_pfnGetInput)(uint8_t, uint8_t*);
+    /**
+     * Set the value of a digital output
+     */
+    uint32_t (*m_pfnSetOutput)(uint8_t, IoBool);
+    /**
+     * Get the value of an analog input
+     */
+    uint32_t (*m_pfnAdcGetValue)(uint8_t, int64_t*);
+    /**
+     * Set the value of an analog output
+     */
+    uint32_t (*m_pfnDacSetValue)(uint8_t, int64_t);
+    /**
+     * Get the value of a temperature input in 1/10000 °C
+     */
+    uint32_t (*m_pfnTmpGetValue)(uint8_t, int32_t*);
+};
+
 /**
  * Callback function for changes on digital inputs
  */
 typedef void (*IoInputCallback)(uint8_t, IoBool);
 
+/**
+ * Callback function for events of counter inputs, called with the channel, the event and the
+ * value of the counter
+ */
+typedef void (*IoCntCallback)(uint8_t, IoCntEvent, int32_t);
+
+/**
+ * Callback function for the completion of a pulse train output, called with the channel
+ */
+typedef void (*IoPtoCallback)(uint8_t);
+
 #ifdef __cplusplus
 extern "C" {
 #endif // __cplusplus
@@ -386,6 +788,79 @@ IoResult IoEnableWatchdog(IoBool fMonitorOnly_p);
  */
 IoResult IoServiceWatchdog(void);
 
+/**
+ * @brief Set the timeout of the system watchdog
+ *
+ * The timeout is applied immediately if the watchdog is enabled, otherwise on
+ * IoEnableWatchdog(). The driver may round it to a supported value.
+ *
+ * @param uTimeout_p Timeout in seconds
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoSetWatchdogTimeout(uint32_t uTimeout_p);
+
+/**
+ * @brief Get the timeout of the system watchdog and the time until it expires
+ *
+ * @param puTimeout_p Pointer to the resulting timeout in seconds
+ * @param puTimeLeft_p Pointer to the resulting time left in seconds (may be NULL)
+ * @return IoResult Driver result code of type IoResult
+ *
+ * # Safety
+ *
+ * `puTimeout_p` must be a valid pointer
+ * `puTimeLeft_p` must be a valid pointer or NULL
+ */
+IoResult IoGetWatchdogTimeout(uint32_t *puTimeout_p,
+                              uint32_t *puTimeLeft_p);
+
+/**
+ * @brief Get the reason of the last reset reported by the system watchdog
+ *
+ * @param puBootStatus_p Pointer to the resulting WDIOF_* flags of
+ *        <linux/watchdog.h>, WDIOF_CARDRESET (0x20) if the watchdog reset
+ *        the system
+ * @return IoResult Driver result code of type IoResult
+ *
+ * # Safety
+ *
+ * `puBootStatus_p` must be a valid pointer
+ */
+IoResult IoGetWatchdogResetReason(uint32_t *puBootStatus_p);
+
+/**
+ * @brief Register the process at the watchdog supervisor of iodaemon
+ *
+ * The process must call IoServiceWatchdogClient() within the timeout, otherwise
+ * iodaemon applies the action configured for the client (see
+ * /etc/sysworxx-io/iodaemon.conf). Terminating the process without
+ * IoUnregisterWatchdogClient() or IoShutdown() is handled as failure.
+ *
+ * @param pszName_p Name of the client (without whitespace)
+ * @param uTimeout_p Timeout in milliseconds
+ * @return IoResult Driver result code of type IoResult
+ *
+ * # Safety
+ *
+ * `pszName_p` must be a valid pointer to a null terminated string
+ */
+IoResult IoRegisterWatchdogClient(const char *pszName_p, uint32_t uTimeout_p);
+
+/**
+ * @brief Send a heartbeat to the watchdog supervisor of iodaemon
+ *
+ * @return IoResult Driver result code of type IoResult, IoResult::WatchdogTimeout if
+ *         the timeout was already exceeded
+ */
+IoResult IoServiceWatchdogClient(void);
+
+/**
+ * @brief Unregister the process from the watchdog supervisor of iodaemon
+ *
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoUnregisterWatchdogClient(void);
+
 /**
  * @brief Get information about device revision and available I/O channels
  *
@@ -580,6 +1055,8 @@ IoResult IoCntSetup(uint8_t uChannel_p,
 /**
  * @brief Set the initial value of the counter
  *
+ * The value is loaded into the counter, also by IoCntReset.
+ *
  * @param uChannel_p The channel to setup
  * @param iPreload_p The initial value to set
  * @return IoResult Driver result code of type IoResult
@@ -599,6 +1076,101 @@ IoResult IoCntSetPreload(uint8_t uChannel_p, int32_t iPreload_p);
  */
 IoResult IoCntGetValue(uint8_t uChannel_p, int32_t *piValue_p);
 
+/**
+ * @brief Load the preload value into a counter channel
+ *
+ * @param uChannel_p The channel to reset
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoCntReset(uint8_t uChannel_p);
+
+/**
+ * @brief Set the compare value of a counter channel
+ *
+ * With auto-reload the counter continues at the preload value after it
+ * passed the compare value counting up, and at the compare value after it
+ * passed the preload value counting down. The auto-reload is done by the
+ * counter hardware if supported and the preload value is 0.
+ *
+ * @param uChannel_p The channel to setup
+ * @param fEnable_p Enable the compare value with true value, false will disable it
+ * @param iCompare_p The compare value
+ * @param fAutoReload_p Reload the counter at the compare value
+ * @return IoResult Driver result code of type IoResult,
+ *         IoResult::InvalidParameter if the auto-reload is enabled and the
+ *         compare value is not greater than the preload value
+ */
+IoResult IoCntSetCompare(uint8_t uChannel_p,
+                         IoBool fEnable_p,
+                         int32_t iCompare_p,
+                         IoBool fAutoReload_p);
+
+/**
+ * @brief Register a callback for events of a counter channel
+ *
+ * The compare and overflow events are detected by sampling the counter, unless
+ * the counter hardware signals them. The callback is called from a separate
+ * thread.
+ *
+ * @param uChannel_p The channel of the counter
+ * @param pfnCallback_p The callback function to register of type #IoCntCallback
+ * @param uEvents_p Mask of the events to signal, bits of #IoCntEvent
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoCntRegisterCallback(uint8_t uChannel_p,
+                               IoCntCallback pfnCallback_p,
+                               uint8_t uEvents_p);
+
+/**
+ * @brief Un-register the callback for events of a counter channel
+ *
+ * @param uChannel_p Analogous to #IoCntRegisterCallback
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoCntUnregisterCallback(uint8_t uChannel_p);
+
+/**
+ * @brief Set the handling of the index pulse (Z) of a counter channel
+ *
+ * The index pulse is the index input of the counter hardware, or the edges
+ * of a digital input matching the trigger. The edges of digital inputs are
+ * handled with the time taken by the kernel, the captured value is read
+ * from the counter when the edge is handled.
+ *
+ * @param uChannel_p The channel of the counter
+ * @param uMode_p The handling of the index pulse, see IoCntIndexMode
+ * @param uSource_p The source of the index pulse, see IoCntIndexSource
+ * @param uInput_p The digital input of the index pulse with IoCntIndexSource::Input
+ * @param uTrigger_p The edges of the digital input, see IoInputTrigger
+ * @return IoResult Driver result code of type IoResult,
+ *         IoResult::NotImplemented if the source is not supported
+ */
+IoResult IoCntSetIndex(uint8_t uChannel_p,
+                       IoCntIndexMode uMode_p,
+                       IoCntIndexSource uSource_p,
+                       uint8_t uInput_p,
+                       IoInputTrigger uTrigger_p);
+
+/**
+ * @brief Get the value of a counter channel captured on the index pulse
+ *
+ * @param uChannel_p The channel of the counter
+ * @param pfCaptured_p Pointer to the destination which is set to true value
+ *                     if a value was captured since the last call
+ * @param piValue_p Pointer to the destination of the captured value
+ * @param puTimestamp_p Pointer to the destination of the time of the index
+ *                      pulse in nanoseconds since the Unix epoch
+ * @return IoResult Driver result code of type IoResult
+ *
+ * # Safety
+ *
+ * `pfCaptured_p`, `piValue_p` and `puTimestamp_p` must be valid pointers
+ */
+IoResult IoCntGetCapture(uint8_t uChannel_p,
+                         IoBool *pfCaptured_p,
+                         int32_t *piValue_p,
+                         uint64_t *puTimestamp_p);
+
 /**
  * @brief Enable PWM output
  *
@@ -627,6 +1199,352 @@ IoResult IoPwmSetup(uint8_t uChannel_p, uint16_t period, uint16_t duty_cycle);
  */
 IoResult IoPwmSetTimebase(uint8_t uChannel_p, IoPwmTimebase timebase);
 
+/**
+ * @brief Configure period and duty cycle of a PWM output in nanoseconds
+ *
+ * The output never uses a duty cycle which is larger than the period. If both
+ * values change, it may run one cycle with the new period and the old duty
+ * cycle (or vice versa). Overrides the values of IoPwmSetup().
+ *
+ * @param uChannel_p The channel to configure
+ * @param ulPeriod_p The period in nanoseconds
+ * @param ulDutyCycle_p The duty cycle in nanoseconds, must not exceed the period
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoPwmConfigure(uint8_t uChannel_p, uint64_t ulPeriod_p, uint64_t ulDutyCycle_p);
+
+/**
+ * @brief Configure a PWM output by frequency and duty cycle in percent
+ *
+ * The values are rounded to the nearest nanosecond, see IoPwmConfigure().
+ *
+ * @param uChannel_p The channel to configure
+ * @param dFrequency_p The frequency in Hz
+ * @param dDutyCycle_p The duty cycle in percent (0.0 - 100.0)
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoPwmSetFrequency(uint8_t uChannel_p, double dFrequency_p, double dDutyCycle_p);
+
+/**
+ * @brief Set the polarity of a PWM output
+ *
+ * Most PWM controllers only accept a change of the polarity while disabled,
+ * so an enabled output is stopped for the change.
+ *
+ * @param uChannel_p The channel to configure
+ * @param polarity Polarity enum
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoPwmSetPolarity(uint8_t uChannel_p, IoPwmPolarity polarity);
+
+/**
+ * @brief Append a segment to the parameter table of a pulse train output (PTO)
+ *
+ * The PTO uses the PWM output of the same channel with a duty cycle of 50 %.
+ * The segments are output one after the other, segments may be appended while
+ * the output is running.
+ *
+ * @param uChannel_p The PWM channel
+ * @param ulPeriod_p The period of the first pulse in nanoseconds
+ * @param lDelta_p Change of the period after each pulse in nanoseconds
+ *                 (negative to accelerate, positive to decelerate)
+ * @param uPulseCount_p Number of pulses of the segment
+ * @return IoResult Driver result code of type IoResult, IoResult::PtoParamTabFull
+ *         if the table is full, IoResult::InvalidDelta if the period of the
+ *         last pulse would be less than 1 us
+ */
+IoResult IoPtoAddSegment(uint8_t uChannel_p,
+                         uint64_t ulPeriod_p,
+                         int64_t lDelta_p,
+                         uint32_t uPulseCount_p);
+
+/**
+ * @brief Append a segment to the parameter table of a pulse train output (PTO)
+ *        in units of the PWM timebase
+ *
+ * Like IoPtoAddSegment(), but the period and the delta are given in units
+ * set by IoPwmSetTimebase() (e.g. for the CTR-700 compatibility library).
+ *
+ * @param uChannel_p The PWM channel
+ * @param uPeriod_p The period of the first pulse in units of the timebase
+ * @param iDelta_p Change of the period after each pulse in units of the timebase
+ * @param uPulseCount_p Number of pulses of the segment
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoPtoAddSegmentTimebase(uint8_t uChannel_p,
+                                 uint16_t uPeriod_p,
+                                 int16_t iDelta_p,
+                                 uint32_t uPulseCount_p);
+
+/**
+ * @brief Start or stop a pulse train output (PTO)
+ *
+ * Stopping the output discards the remaining segments of the parameter table.
+ *
+ * @param uChannel_p The PWM channel
+ * @param fRun_p true to start the output of the parameter table, false to stop it
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoPtoEnable(uint8_t uChannel_p, bool fRun_p);
+
+/**
+ * @brief Get the state of a pulse train output (PTO)
+ *
+ * @param uChannel_p The PWM channel
+ * @param peState_p Pointer to the state destination
+ * @param pulPulses_p Pointer to the destination for the number of pulses
+ *                    output by the current or last run, may be NULL
+ * @return IoResult Driver result code of type IoResult
+ *
+ * # Safety
+ *
+ * `peState_p` must be a valid pointer, `pulPulses_p` must be a valid pointer or NULL
+ */
+IoResult IoPtoGetState(uint8_t uChannel_p, IoPtoState *peState_p, uint64_t *pulPulses_p);
+
+/**
+ * @brief Register a callback for the completion of a pulse train output (PTO)
+ *
+ * The callback is called from a separate thread after all segments were output.
+ *
+ * @param uChannel_p The PWM channel
+ * @param pfnCallback_p The callback function of type #IoPtoCallback, NULL to
+ *                      unregister the callback
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoPtoRegisterCallback(uint8_t uChannel_p, IoPtoCallback pfnCallback_p);
+
+/**
+ * @brief Open a stepper axis
+ *
+ * The steps are output by the pulse train output of a PWM channel, so the
+ * PTO of the channel must not be used at the same time. The direction is
+ * output by a digital output. The position of an opened axis is 0. An axis
+ * with the same number is closed before.
+ *
+ * @param uAxis_p Number of the axis
+ * @param pConfig_p Pointer to the configuration of the axis
+ * @return IoResult Driver result code of type IoResult
+ *
+ * # Safety
+ *
+ * `pConfig_p` must be a valid pointer
+ */
+IoResult IoAxisOpen(uint8_t uAxis_p, const struct IoAxisConfig *pConfig_p);
+
+/**
+ * @brief Close a stepper axis, a movement is stopped immediately
+ *
+ * @param uAxis_p Number of the axis
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoAxisClose(uint8_t uAxis_p);
+
+/**
+ * @brief Move a stepper axis to an absolute position
+ *
+ * The movement follows a trapezoidal velocity profile. A running movement is
+ * changed to the new target.
+ *
+ * @param uAxis_p Number of the axis
+ * @param lPosition_p Target position in steps
+ * @return IoResult Driver result code of type IoResult,
+ *         IoResult::InvalidParameter if the target is outside of the soft limits
+ */
+IoResult IoAxisMoveTo(uint8_t uAxis_p, int64_t lPosition_p);
+
+/**
+ * @brief Move a stepper axis relative to its current position
+ *
+ * @param uAxis_p Number of the axis
+ * @param lSteps_p Number of steps, negative in negative direction
+ * @return IoResult Driver result code of type IoResult,
+ *         IoResult::InvalidParameter if the target is outside of the soft limits
+ */
+IoResult IoAxisMoveBy(uint8_t uAxis_p, int64_t lSteps_p);
+
+/**
+ * @brief Search the limit switch of a stepper axis and set the reference position
+ *
+ * @param uAxis_p Number of the axis
+ * @return IoResult Driver result code of type IoResult,
+ *         IoResult::NotImplemented if homing is not configured
+ */
+IoResult IoAxisHome(uint8_t uAxis_p);
+
+/**
+ * @brief Decelerate a stepper axis to standstill with its acceleration
+ *
+ * @param uAxis_p Number of the axis
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoAxisStop(uint8_t uAxis_p);
+
+/**
+ * @brief Decelerate a stepper axis to standstill with its emergency deceleration
+ *
+ * @param uAxis_p Number of the axis
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoAxisEmergencyStop(uint8_t uAxis_p);
+
+/**
+ * @brief Get the status of a stepper axis
+ *
+ * @param uAxis_p Number of the axis
+ * @param pStatus_p Pointer to the status destination
+ * @return IoResult Driver result code of type IoResult
+ *
+ * # Safety
+ *
+ * `pStatus_p` must be a valid pointer
+ */
+IoResult IoAxisGetStatus(uint8_t uAxis_p, struct IoAxisStatus *pStatus_p);
+
+/**
+ * @brief Load an external channel provider plugin and append its channels
+ *
+ * The channels of the plugin are appended to the existing channels of each
+ * type. Use IoGetHardwareInfo before and after loading the plugin to
+ * determine the channel numbers or IoGetJson to get the channel labels.
+ *
+ * @param sPath_p Path to the shared library of the plugin
+ * @return IoResult Driver result code of type IoResult
+ *
+ * # Safety
+ *
+ * `sPath_p` must be a valid pointer
+ */
+IoResult IoAddProviderPlugin(const char *sPath_p);
+
+/**
+ * @brief Start a PID control loop
+ *
+ * The loop reads the process value from an analog input (raw value) or a
+ * temperature input (°C) and drives the output with the controller output
+ * in percent. A running loop with the same number is stopped before. The
+ * loop starts in automatic mode with the setpoint 0 and the gains Kp = 1,
+ * Ki = 0 and Kd = 0.
+ *
+ * @param uLoop_p Number of the loop
+ * @param eInput_p Type of the process value input (analog or temperature)
+ * @param uInputChannel_p Channel of the process value input
+ * @param eOutput_p Type of the output #IoPidOutput
+ * @param uOutputChannel_p Channel of the output
+ * @param uOutputRange_p Range of the output (see #IoPidOutput)
+ * @param uCycleMs_p Cycle time of the loop in milliseconds
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoPidStart(uint8_t uLoop_p,
+                    IoChannelType eInput_p,
+                    uint8_t uInputChannel_p,
+                    IoPidOutput eOutput_p,
+                    uint8_t uOutputChannel_p,
+                    uint32_t uOutputRange_p,
+                    uint32_t uCycleMs_p);
+
+/**
+ * @brief Stop a PID control loop and switch off its output
+ *
+ * @param uLoop_p Number of the loop
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoPidStop(uint8_t uLoop_p);
+
+/**
+ * @brief Set the gains of a PID control loop
+ *
+ * @param uLoop_p Number of the loop
+ * @param dKp_p Proportional gain (percent per process value unit)
+ * @param dKi_p Integral gain (percent per process value unit and second)
+ * @param dKd_p Derivative gain (percent per process value unit per second)
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoPidSetTunings(uint8_t uLoop_p, double dKp_p, double dKi_p, double dKd_p);
+
+/**
+ * @brief Get the gains of a PID control loop (e.g. after an autotune)
+ *
+ * @param uLoop_p Number of the loop
+ * @param pdKp_p Pointer to the resulting proportional gain
+ * @param pdKi_p Pointer to the resulting integral gain
+ * @param pdKd_p Pointer to the resulting derivative gain
+ * @return IoResult Driver result code of type IoResult
+ *
+ * # Safety
+ *
+ * `pdKp_p` must be a valid pointer
+ * `pdKi_p` must be a valid pointer
+ * `pdKd_p` must be a valid pointer
+ */
+IoResult IoPidGetTunings(uint8_t uLoop_p,
+                         double *pdKp_p,
+                         double *pdKi_p,
+                         double *pdKd_p);
+
+/**
+ * @brief Limit the output of a PID control loop
+ *
+ * @param uLoop_p Number of the loop
+ * @param dMin_p Minimum output in percent (0 to 100)
+ * @param dMax_p Maximum output in percent (0 to 100)
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoPidSetLimits(uint8_t uLoop_p, double dMin_p, double dMax_p);
+
+/**
+ * @brief Set the setpoint of a PID control loop
+ *
+ * @param uLoop_p Number of the loop
+ * @param dSetpoint_p The setpoint in the unit of the process value
+ * @param dRate_p Rate of the setpoint ramp in units per second (0 for a step)
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoPidSetSetpoint(uint8_t uLoop_p, double dSetpoint_p, double dRate_p);
+
+/**
+ * @brief Switch a PID control loop between manual and automatic mode
+ *
+ * The transfer from manual to automatic mode is bumpless.
+ *
+ * @param uLoop_p Number of the loop
+ * @param fManual_p Switch to manual mode
+ * @param dOutput_p Output in percent in manual mode
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoPidSetManual(uint8_t uLoop_p, IoBool fManual_p, double dOutput_p);
+
+/**
+ * @brief Start a relay autotune of a PID control loop
+ *
+ * The output is switched between two levels around the current setpoint
+ * until the process oscillates. On success the gains are set, the state
+ * can be polled with IoPidGetStatus.
+ *
+ * @param uLoop_p Number of the loop
+ * @param dLow_p Output in percent while the process value is above the setpoint
+ * @param dHigh_p Output in percent while the process value is below the setpoint
+ * @param dHysteresis_p Hysteresis around the setpoint (noise band of the process value)
+ * @return IoResult Driver result code of type IoResult
+ */
+IoResult IoPidAutotune(uint8_t uLoop_p,
+                       double dLow_p,
+                       double dHigh_p,
+                       double dHysteresis_p);
+
+/**
+ * @brief Get the status of a PID control loop
+ *
+ * @param uLoop_p Number of the loop
+ * @param pStatus_p Pointer to the status destination
+ * @return IoResult Driver result code of type IoResult
+ *
+ * # Safety
+ *
+ * `pStatus_p` must be a valid pointer
+ */
+IoResult IoPidGetStatus(uint8_t uLoop_p, struct IoPidStatus *pStatus_p);
+
 #ifdef __cplusplus
 }  // extern "C"
 #endif  // __cplusplus
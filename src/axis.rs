@@ -0,0 +1,798 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Positioning of a stepper axis with a step and a direction signal. The steps are output by the
+// pulse train output (see `pto`) of a PWM output, so a movement outputs an exact number of steps
+// and the position is counted in steps. A movement is planned as the segments of a trapezoidal
+// profile: a ramp from the start velocity to the maximum velocity, a constant part and a ramp
+// back to the start velocity. The period of a ramp changes by the same delta after each step,
+// which approximates the constant acceleration.
+//
+// A thread follows the pulse train output. A new command during a movement stops the output and
+// plans the rest from the current velocity, a reversal decelerates first.
+//
+// With an A/B encoder (see `IoCntMode::ABEncoder`) the position is read from the counter
+// instead, so lost steps are corrected by further movements until the position is within the
+// tolerance.
+//
+// Positions are in steps, velocities in steps per second and accelerations in steps per second
+// squared.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crossbeam_channel::{Receiver, Sender};
+
+use crate::error::{Error, Result};
+use crate::pto::{self, Pto, Segment};
+use crate::{ffi, Io};
+
+/// Interval in which the movement is followed
+const UPDATE_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Segments of a ramp, two ramps and the constant part fit into the parameter table
+const RAMP_SEGMENTS: u32 = (pto::MAX_SEGMENTS as u32 - 1) / 2;
+
+/// Period of a step in nanoseconds
+fn period(velocity: f64) -> u64 {
+    (1e9 / velocity).round() as u64
+}
+
+/// Segments of a ramp from the velocity `from` to `to` in `steps`
+///
+/// The velocity of a constant acceleration is v(k)² = from² + (to² - from²) * k / steps at step k.
+/// The period of a segment changes linearly, which takes too long at low velocities, so the ramp
+/// is split into segments in which the velocity changes by the same factor.
+fn ramp(from: f64, to: f64, steps: u32) -> Vec<Segment> {
+    let velocity =
+        |step: u32| (from * from + (to * to - from * from) * step as f64 / steps as f64).sqrt();
+    let mut segments = vec![];
+    let mut first = 0;
+    for i in 1..=RAMP_SEGMENTS {
+        let end = from * (to / from).powf(i as f64 / RAMP_SEGMENTS as f64);
+        let last = match i {
+            RAMP_SEGMENTS => steps,
+            _ => {
+                ((end * end - from * from) / (to * to - from * from) * steps as f64).round() as u32
+            }
+        };
+        if last > first {
+            let pulses = last - first;
+            let (start, end) = (
+                period(velocity(first)) as i64,
+                period(velocity(last)) as i64,
+            );
+            segments.push(Segment {
+                period: start as u64,
+                delta: (end - start) / pulses as i64,
+                pulses,
+            });
+            first = last;
+        }
+    }
+    segments
+}
+
+/// A/B encoder to close the loop
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Encoder {
+    /// Counter channel, it is set up in A/B encoder mode
+    pub channel: usize,
+    /// Encoder counts per step, negative if the encoder counts in the opposite direction
+    pub counts_per_step: f64,
+}
+
+/// Homing on a limit switch
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Homing {
+    /// Digital input of the limit switch
+    pub input: usize,
+    pub active_low: bool,
+    /// Velocity of the search, the sign gives the direction
+    pub velocity: f64,
+    /// Position of the axis at the limit switch
+    pub position: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AxisConfig {
+    /// PWM channel of the step signal, it is used as pulse train output
+    pub step: usize,
+    /// Digital output of the direction signal, it is set for positive movements
+    pub direction: usize,
+    pub direction_inverted: bool,
+    pub encoder: Option<Encoder>,
+    /// Velocity at which the axis starts and stops without ramp
+    pub start_velocity: f64,
+    pub max_velocity: f64,
+    pub acceleration: f64,
+    /// Deceleration of an emergency stop, 0 to stop immediately
+    pub emergency_deceleration: f64,
+    /// Minimum and maximum position of a movement
+    pub soft_limits: Option<(i64, i64)>,
+    pub homing: Option<Homing>,
+    /// Maximum distance to the target at which a movement is finished
+    pub tolerance: f64,
+}
+
+impl AxisConfig {
+    pub fn new(step: usize, direction: usize) -> AxisConfig {
+        AxisConfig {
+            step,
+            direction,
+            direction_inverted: false,
+            encoder: None,
+            start_velocity: 100.0,
+            max_velocity: 1000.0,
+            acceleration: 1000.0,
+            emergency_deceleration: 0.0,
+            soft_limits: None,
+            homing: None,
+            tolerance: 0.5,
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let valid = self.start_velocity > 0.0
+            && self.max_velocity >= self.start_velocity
+            && 1e9 / self.max_velocity >= pto::MIN_PERIOD as f64
+            && self.acceleration > 0.0
+            && self.emergency_deceleration >= 0.0
+            && self.tolerance >= 0.0
+            && !matches!(self.encoder, Some(e) if e.counts_per_step == 0.0)
+            && !matches!(self.soft_limits, Some((min, max)) if min > max)
+            && !matches!(self.homing, Some(h) if h.velocity == 0.0);
+        if valid {
+            Ok(())
+        } else {
+            Err(Error::InvalidParameter)
+        }
+    }
+
+    /// Steps to decelerate from `speed` to the start velocity
+    fn stop_distance(&self, speed: f64, deceleration: f64) -> u32 {
+        let start = self.start_velocity;
+        ((speed * speed - start * start).max(0.0) / (2.0 * deceleration)).round() as u32
+    }
+
+    /// Segments of a movement of `steps`, which starts with `speed` and can stop within the
+    /// steps: v² = speed² + 2 * a * accelerate = start² + 2 * a * decelerate
+    fn trapezoid(&self, speed: f64, steps: u32) -> Vec<Segment> {
+        let (start, acceleration) = (self.start_velocity, self.acceleration);
+        let speed = speed.max(start);
+        let peak = ((2.0 * acceleration * steps as f64 + speed * speed + start * start) / 2.0)
+            .sqrt()
+            .min(self.max_velocity)
+            .max(speed);
+        let decelerate = self.stop_distance(peak, acceleration).min(steps);
+        let accelerate = (((peak * peak - speed * speed) / (2.0 * acceleration)).round() as u32)
+            .min(steps - decelerate);
+        let constant = steps - accelerate - decelerate;
+
+        let mut segments = vec![];
+        if accelerate > 0 {
+            segments.extend(ramp(speed, peak, accelerate));
+        }
+        if constant > 0 {
+            segments.push(Segment {
+                period: period(peak),
+                delta: 0,
+                pulses: constant,
+            });
+        }
+        if decelerate > 0 {
+            segments.extend(ramp(peak, start, decelerate));
+        }
+        segments
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum State {
+    Idle,
+    /// Moving to a target position
+    Moving,
+    Homing,
+    /// Decelerating after a stop or emergency stop
+    Stopping,
+    /// The output failed, the axis was stopped
+    Fault,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Status {
+    pub state: State,
+    pub position: f64,
+    /// Velocity in steps per second, negative in negative direction
+    pub velocity: f64,
+    /// The reference position was set by homing
+    pub homed: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Goal {
+    Idle,
+    Position(f64),
+    /// Constant velocity, used for homing
+    Velocity(f64),
+    /// Decelerate to standstill with the given deceleration
+    Stop(f64),
+}
+
+/// Movement which is output by the pulse train output
+#[derive(Debug)]
+struct Run {
+    /// Position at the start
+    start: f64,
+    /// 1 in positive direction, -1 in negative direction
+    direction: f64,
+    segments: Vec<Segment>,
+}
+
+impl Run {
+    fn position(&self, steps: u64) -> f64 {
+        self.start + self.direction * steps as f64
+    }
+
+    /// Velocity of the step with the given (zero based) number
+    fn velocity(&self, steps: u64) -> f64 {
+        let mut first = 0;
+        for segment in &self.segments {
+            let pulses = segment.pulses as u64;
+            if steps < first + pulses {
+                let period = segment.period_at((steps - first) as u32);
+                return self.direction * 1e9 / period as f64;
+            }
+            first += pulses;
+        }
+        0.0
+    }
+}
+
+/// Position of the encoder, relative to the reference set by homing
+#[derive(Debug)]
+struct Feedback {
+    encoder: Encoder,
+    count: i32,
+    position: f64,
+}
+
+impl Feedback {
+    fn read(&self, io: &mut Io) -> Result<f64> {
+        let counts = io.cnt_get(self.encoder.channel)?.wrapping_sub(self.count);
+        Ok(self.position + counts as f64 / self.encoder.counts_per_step)
+    }
+
+    fn reset(&mut self, io: &mut Io, position: f64) -> Result<()> {
+        self.count = io.cnt_get(self.encoder.channel)?;
+        self.position = position;
+        Ok(())
+    }
+}
+
+struct Shared {
+    goal: Goal,
+    /// The goal was changed by a command
+    changed: bool,
+    position: f64,
+    velocity: f64,
+    homed: bool,
+    fault: bool,
+}
+
+/// Drives the step and direction outputs
+struct Driver {
+    config: AxisConfig,
+    io: Arc<Mutex<Io>>,
+    feedback: Option<Feedback>,
+    pto: Pto,
+    run: Option<Run>,
+}
+
+impl Driver {
+    fn io(&self) -> Result<MutexGuard<'_, Io>> {
+        self.io.lock().map_err(|_| Error::GenericError)
+    }
+
+    fn read_feedback(&self, shared: &mut Shared) -> Result<()> {
+        if let Some(feedback) = &self.feedback {
+            shared.position = feedback.read(&mut *self.io()?)?;
+        }
+        Ok(())
+    }
+
+    /// Update the position and velocity, returns true if the movement is finished
+    fn follow(&mut self, shared: &mut Shared) -> Result<bool> {
+        let mut finished = false;
+        if let Some(run) = &self.run {
+            let (state, steps) = self.pto.state();
+            shared.position = run.position(steps);
+            shared.velocity = match state {
+                pto::State::Running => run.velocity(steps),
+                pto::State::Done => {
+                    finished = true;
+                    0.0
+                }
+                // the pulse train output failed
+                pto::State::Idle => return Err(Error::generic_access_error()),
+            };
+        }
+        self.read_feedback(shared)?;
+        Ok(finished)
+    }
+
+    /// Stop the output immediately, returns the velocity at the stop
+    fn halt(&mut self, shared: &mut Shared) -> Result<f64> {
+        let velocity = match self.run.take() {
+            Some(run) => {
+                self.pto.stop();
+                let steps = self.pto.state().1;
+                shared.position = run.position(steps);
+                run.velocity(steps)
+            }
+            None => 0.0,
+        };
+        shared.velocity = 0.0;
+        self.read_feedback(shared)?;
+        Ok(velocity)
+    }
+
+    fn start(&mut self, shared: &Shared, direction: f64, segments: Vec<Segment>) -> Result<()> {
+        self.io()?.output_set(
+            self.config.direction,
+            (direction > 0.0) != self.config.direction_inverted,
+        )?;
+        for segment in &segments {
+            self.pto.add_segment(*segment)?;
+        }
+        self.pto.start()?;
+        self.run = Some(Run {
+            start: shared.position,
+            direction,
+            segments,
+        });
+        Ok(())
+    }
+
+    /// Decelerate from `velocity` to the start velocity, returns false if the axis can stop
+    /// immediately
+    fn decelerate(&mut self, shared: &Shared, velocity: f64, deceleration: f64) -> Result<bool> {
+        let speed = velocity.abs();
+        let steps = match deceleration > 0.0 {
+            true => self.config.stop_distance(speed, deceleration),
+            false => 0,
+        };
+        if steps == 0 {
+            return Ok(false);
+        }
+        let segments = ramp(speed, self.config.start_velocity, steps);
+        self.start(shared, velocity.signum(), segments)?;
+        Ok(true)
+    }
+
+    /// Start the movement to the goal with the current `velocity`
+    fn plan(&mut self, shared: &mut Shared, velocity: f64) -> Result<()> {
+        let acceleration = self.config.acceleration;
+        match shared.goal {
+            Goal::Idle => {}
+            Goal::Stop(deceleration) => {
+                if !self.decelerate(shared, velocity, deceleration)? {
+                    shared.goal = Goal::Idle;
+                }
+            }
+            Goal::Position(target) => {
+                let remaining = target - shared.position;
+                let steps = remaining.abs().round().min(u32::MAX as f64) as u32;
+                if remaining.abs() <= self.config.tolerance || steps == 0 {
+                    shared.goal = Goal::Idle;
+                    return Ok(());
+                }
+                let speed = velocity.abs();
+                let overshoot = velocity != 0.0
+                    && (velocity.signum() != remaining.signum()
+                        || self.config.stop_distance(speed, acceleration) > steps);
+                // the rest is planned after the deceleration
+                if !(overshoot && self.decelerate(shared, velocity, acceleration)?) {
+                    let speed = if overshoot { 0.0 } else { speed };
+                    let segments = self.config.trapezoid(speed, steps);
+                    self.start(shared, remaining.signum(), segments)?;
+                }
+            }
+            Goal::Velocity(target) => {
+                let reverse = velocity != 0.0 && velocity.signum() != target.signum();
+                if !(reverse && self.decelerate(shared, velocity, acceleration)?) {
+                    let speed = target.abs();
+                    let from = match reverse {
+                        true => 0.0,
+                        false => velocity.abs(),
+                    }
+                    .max(self.config.start_velocity.min(speed));
+                    let mut segments = vec![];
+                    if speed > from {
+                        let steps = ((speed * speed - from * from) / (2.0 * acceleration))
+                            .round()
+                            .max(1.0) as u32;
+                        segments.extend(ramp(from, speed, steps));
+                    }
+                    segments.push(Segment {
+                        period: period(speed),
+                        delta: 0,
+                        pulses: u32::MAX,
+                    });
+                    self.start(shared, target.signum(), segments)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, shared: &mut Shared) -> Result<()> {
+        let finished = self.follow(shared)?;
+
+        if shared.changed {
+            shared.changed = false;
+            let velocity = self.halt(shared)?;
+            return self.plan(shared, velocity);
+        }
+
+        if let (Goal::Velocity(_), Some(homing)) = (shared.goal, self.config.homing) {
+            if self.io()?.input_get(homing.input)? != homing.active_low {
+                self.halt(shared)?;
+                shared.goal = Goal::Idle;
+                shared.position = homing.position as f64;
+                if let Some(feedback) = self.feedback.as_mut() {
+                    let mut io = self.io.lock().map_err(|_| Error::GenericError)?;
+                    feedback.reset(&mut io, shared.position)?;
+                }
+                shared.homed = true;
+                return Ok(());
+            }
+        }
+
+        if finished {
+            self.run = None;
+            self.plan(shared, 0.0)?;
+        }
+        Ok(())
+    }
+}
+
+fn run(shared: &Mutex<Shared>, driver: &mut Driver, wake: &Receiver<bool>) {
+    loop {
+        let idle = {
+            let mut shared = shared.lock().unwrap();
+            if let Err(e) = driver.update(&mut shared) {
+                error!("Axis failed: {}", e);
+                driver.run = None;
+                driver.pto.stop();
+                shared.goal = Goal::Idle;
+                shared.velocity = 0.0;
+                shared.fault = true;
+            }
+            driver.run.is_none()
+        };
+
+        // an idle axis only follows the encoder, it is woken up by commands
+        let timeout = if idle && driver.feedback.is_none() {
+            Duration::from_secs(1)
+        } else {
+            UPDATE_INTERVAL
+        };
+        match wake.recv_timeout(timeout) {
+            Ok(true) => break,
+            Ok(false) | Err(crossbeam_channel::RecvTimeoutError::Timeout) => {}
+            Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    // switches off the output
+    driver.pto.stop();
+}
+
+/// Stepper axis, it is stopped immediately on drop
+pub struct Axis {
+    config: AxisConfig,
+    shared: Arc<Mutex<Shared>>,
+    /// Wakes up the thread for a new command, `true` to stop it
+    wake: Sender<bool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+fn check_channel(count: usize, channel: usize) -> Result<()> {
+    if channel < count {
+        Ok(())
+    } else {
+        Err(Error::InvalidChannel)
+    }
+}
+
+impl Axis {
+    /// Start the thread called `name` of an axis, the initial position is 0
+    pub fn start(io: Arc<Mutex<Io>>, name: &str, config: &AxisConfig) -> Result<Axis> {
+        config.validate()?;
+        let feedback = {
+            let mut io = io.lock().map_err(|_| Error::GenericError)?;
+            let info = io.get_channel_info();
+            check_channel(info.pwm_outputs.len(), config.step)?;
+            check_channel(info.outputs.len(), config.direction)?;
+            if let Some(homing) = config.homing {
+                check_channel(info.inputs.len(), homing.input)?;
+            }
+            io.pwm_enable(config.step, false)?;
+
+            match config.encoder {
+                Some(encoder) => {
+                    io.cnt_setup(
+                        encoder.channel,
+                        ffi::IoCntMode::ABEncoder,
+                        ffi::IoCntTrigger::AnyEdge,
+                        ffi::IoCntDirection::Up,
+                    )?;
+                    io.cnt_enable(encoder.channel, true)?;
+                    let mut feedback = Feedback {
+                        encoder,
+                        count: 0,
+                        position: 0.0,
+                    };
+                    feedback.reset(&mut io, 0.0)?;
+                    Some(feedback)
+                }
+                None => None,
+            }
+        };
+
+        let pto = Pto::new(io.clone(), config.step)?;
+        let shared = Arc::new(Mutex::new(Shared {
+            goal: Goal::Idle,
+            changed: false,
+            position: 0.0,
+            velocity: 0.0,
+            homed: false,
+            fault: false,
+        }));
+        let (wake, woken) = crossbeam_channel::unbounded();
+        let mut driver = Driver {
+            config: config.clone(),
+            io,
+            feedback,
+            pto,
+            run: None,
+        };
+        let thread_shared = shared.clone();
+
+        let thread = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || run(&thread_shared, &mut driver, &woken))
+            .map_err(|e| {
+                error!("Failed to start the axis: {}", e);
+                Error::GenericError
+            })?;
+
+        Ok(Axis {
+            config: config.clone(),
+            shared,
+            wake,
+            thread: Some(thread),
+        })
+    }
+
+    fn command(&self, goal: Goal) {
+        {
+            let mut shared = self.shared.lock().unwrap();
+            shared.goal = goal;
+            shared.changed = true;
+            shared.fault = false;
+        }
+        self.wake.send(false).ok();
+    }
+
+    /// Move to an absolute position, a running movement is changed to the new target
+    pub fn move_to(&self, position: i64) -> Result<()> {
+        if let Some((min, max)) = self.config.soft_limits {
+            if position < min || position > max {
+                return Err(Error::InvalidParameter);
+            }
+        }
+        self.command(Goal::Position(position as f64));
+        Ok(())
+    }
+
+    /// Move relative to the current position
+    pub fn move_by(&self, steps: i64) -> Result<()> {
+        let position = self.shared.lock().unwrap().position.round() as i64;
+        self.move_to(position + steps)
+    }
+
+    /// Search the limit switch and set the reference position
+    pub fn home(&self) -> Result<()> {
+        let homing = self.config.homing.ok_or(Error::NotImplemented)?;
+        let velocity = homing
+            .velocity
+            .clamp(-self.config.max_velocity, self.config.max_velocity);
+        self.shared.lock().unwrap().homed = false;
+        self.command(Goal::Velocity(velocity));
+        Ok(())
+    }
+
+    /// Decelerate to standstill with the acceleration of the profile
+    pub fn stop(&self) {
+        self.command(Goal::Stop(self.config.acceleration));
+    }
+
+    /// Decelerate to standstill with the emergency deceleration
+    pub fn emergency_stop(&self) {
+        self.command(Goal::Stop(self.config.emergency_deceleration));
+    }
+
+    pub fn status(&self) -> Status {
+        let shared = self.shared.lock().unwrap();
+        let state = match shared.goal {
+            Goal::Idle if shared.fault => State::Fault,
+            Goal::Idle => State::Idle,
+            Goal::Position(_) => State::Moving,
+            Goal::Velocity(_) => State::Homing,
+            Goal::Stop(_) => State::Stopping,
+        };
+        Status {
+            state,
+            position: shared.position,
+            velocity: shared.velocity,
+            homed: shared.homed,
+        }
+    }
+
+    /// Stop the thread and switch off the step output
+    pub fn close(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.wake.send(true).ok();
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+impl Drop for Axis {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+    use crate::DigitalOutput;
+
+    fn wait_idle(axis: &Axis) -> Status {
+        for _ in 0..500 {
+            let status = axis.status();
+            match status.state {
+                State::Moving | State::Homing | State::Stopping => {}
+                _ => return status,
+            }
+            thread::sleep(Duration::from_millis(2));
+        }
+        panic!("axis did not stop: {:?}", axis.status());
+    }
+
+    #[test]
+    fn plan_test() {
+        let config = AxisConfig::new(0, 0);
+        let pulses = |segments: &[Segment]| segments.iter().map(|s| s.pulses).sum::<u32>();
+        let duration = |segments: &[Segment]| {
+            segments
+                .iter()
+                .flat_map(|s| (0..s.pulses).map(move |i| s.period_at(i)))
+                .sum::<u64>() as f64
+                / 1e9
+        };
+
+        // accelerate from 100 to 1000 steps/s in (1000² - 100²) / 2000 steps and 0.9 s
+        let segments = config.trapezoid(0.0, 1000);
+        assert_eq!(pulses(&segments), 1000);
+        assert!(segments.len() <= pto::MAX_SEGMENTS);
+        assert_eq!(segments[0].period, 10_000_000);
+        let constant = segments.iter().find(|s| s.delta == 0).unwrap();
+        assert_eq!((constant.period, constant.pulses), (1_000_000, 10));
+        let time = duration(&segments);
+        assert!(time > 1.8 && time < 1.8 * 1.2, "{} s", time);
+
+        // the maximum velocity is not reached
+        let segments = config.trapezoid(0.0, 10);
+        assert_eq!(pulses(&segments), 10);
+        assert!(segments.iter().all(|s| s.delta != 0));
+        let segments = config.trapezoid(1000.0, 495);
+        assert_eq!(pulses(&segments), 495);
+        assert_eq!(segments[0].period, 1_000_000);
+        assert_eq!(config.stop_distance(1000.0, 1000.0), 495);
+        assert_eq!(config.stop_distance(50.0, 1000.0), 0);
+
+        let run = Run {
+            start: 10.0,
+            direction: -1.0,
+            segments: config.trapezoid(0.0, 1000),
+        };
+        assert_eq!(run.position(20), -10.0);
+        assert_eq!(run.velocity(0), -100.0);
+        assert_eq!(run.velocity(500), -1000.0);
+        assert_eq!(run.velocity(1000), 0.0);
+
+        let mut config = AxisConfig::new(0, 0);
+        config.max_velocity = 2_000_000.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn axis_test() {
+        let sim = Simulator::new("SIM", 2, 0, 0).with_pwm_outputs(1);
+        let io = IoBuilder::new()
+            .output(Box::new(sim.output(0)))
+            .input(Box::new(sim.input(1)))
+            .pwm(Box::new(sim.pwm(0)))
+            .build();
+        let io = Arc::new(Mutex::new(io));
+
+        let mut config = AxisConfig::new(0, 0);
+        config.max_velocity = 20_000.0;
+        config.acceleration = 200_000.0;
+        config.soft_limits = Some((-100, 1000));
+        config.homing = Some(Homing {
+            input: 0,
+            active_low: false,
+            velocity: -1000.0,
+            position: -100,
+        });
+        assert!(matches!(
+            Axis::start(io.clone(), "axis", &AxisConfig::new(1, 0)),
+            Err(Error::InvalidChannel)
+        ));
+        let axis = Axis::start(io, "axis", &config).unwrap();
+
+        assert!(axis.move_to(1001).is_err());
+        axis.move_to(1000).unwrap();
+        assert_eq!(axis.status().state, State::Moving);
+        thread::sleep(Duration::from_millis(5));
+        assert!(sim.pwm_state(0).unwrap().enabled);
+        assert!(sim.output(0).get().unwrap());
+        let status = wait_idle(&axis);
+        assert_eq!(status.state, State::Idle);
+        assert!((status.position - 1000.0).abs() <= 0.5);
+        assert!(!sim.pwm_state(0).unwrap().enabled);
+
+        axis.move_by(-500).unwrap();
+        thread::sleep(Duration::from_millis(5));
+        assert!(!sim.output(0).get().unwrap());
+        wait_idle(&axis);
+        assert!((axis.status().position - 500.0).abs() <= 0.5);
+
+        axis.home().unwrap();
+        thread::sleep(Duration::from_millis(20));
+        let status = axis.status();
+        assert_eq!(status.state, State::Homing);
+        assert!(status.velocity < 0.0);
+        assert!(sim.pwm_state(0).unwrap().enabled);
+        sim.set_input(1, true).unwrap();
+        let status = wait_idle(&axis);
+        assert_eq!(status.state, State::Idle);
+        assert_eq!(status.position, -100.0);
+        assert!(status.homed);
+        sim.set_input(1, false).unwrap();
+
+        axis.move_to(1000).unwrap();
+        thread::sleep(Duration::from_millis(20));
+        axis.emergency_stop();
+        let status = wait_idle(&axis);
+        assert_eq!(status.velocity, 0.0);
+        assert!(status.position > -100.0 && status.position < 1000.0);
+        assert!(!sim.pwm_state(0).unwrap().enabled);
+
+        axis.move_to(0).unwrap();
+        axis.close();
+        assert!(!sim.pwm_state(0).unwrap().enabled);
+    }
+}
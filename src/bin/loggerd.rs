@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Data logger writing the configured inputs of the active device definition to files or a
+// database.
+//
+// Usage: loggerd [CONFIG]
+//
+// The configuration is read from /etc/sysworxx-io/loggerd.conf by default. See
+// `config/loggerd.conf` for the available options and `sysworxx_io::logger` for the sinks.
+
+#[macro_use]
+extern crate log;
+
+use sysworxx_io::logger::{Config, Logger};
+use sysworxx_io::{definition, hw_rev, signal};
+
+const DEFAULT_CONFIG: &str = "/etc/sysworxx-io/loggerd.conf";
+
+pub fn main() {
+    use env_logger::Env;
+    let env = Env::new().filter("IO_LOG").write_style("IO_LOG_STYLE");
+    env_logger::init_from_env(env);
+
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_CONFIG.to_string());
+
+    let config = match Config::load(&path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid configuration {}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+
+    let device = hw_rev::get_device_name().unwrap_or("fallback".to_string());
+    let mut io = definition::load_device_definition(&device);
+
+    if let Err(e) = io.init() {
+        eprintln!("Failed to initialize: {}", e);
+        std::process::exit(1);
+    }
+
+    let signal_notifier = match signal::notify(&[signal::SIGINT, signal::SIGTERM]) {
+        Ok(notifier) => notifier,
+        Err(e) => {
+            eprintln!("Failed to create signal notifier: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut logger = match Logger::new(io, &device, &config) {
+        Ok(logger) => logger,
+        Err(e) => {
+            eprintln!("Failed to start the logger: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    info!(
+        "Logging {} channels to {}",
+        config.channels.len(),
+        config.path.display()
+    );
+    if let Err(e) = logger.run(&signal_notifier) {
+        error!("Failed to sync the log: {}", e);
+        std::process::exit(1);
+    }
+    info!("Exit due to signal");
+}
@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Modbus TCP server for the channels of the active device definition.
+//
+// Usage: modbusd [ADDRESS]
+//
+// The server listens on 0.0.0.0:502 by default. The register map is part of the channel
+// information written by `IoGetJson` (key "modbus").
+
+#[macro_use]
+extern crate log;
+
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use sysworxx_io::modbus::server::Server;
+use sysworxx_io::{definition, hw_rev, signal};
+
+const DEFAULT_ADDRESS: &str = "0.0.0.0:502";
+
+pub fn main() {
+    use env_logger::Env;
+    let env = Env::new().filter("IO_LOG").write_style("IO_LOG_STYLE");
+    env_logger::init_from_env(env);
+
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+
+    let mut io = definition::load_device_definition(
+        &hw_rev::get_device_name().unwrap_or("fallback".to_string()),
+    );
+
+    if let Err(e) = io.init() {
+        eprintln!("Failed to initialize: {}", e);
+        std::process::exit(1);
+    }
+
+    let server = Arc::new(Server::new(Arc::new(Mutex::new(io))));
+
+    let listener = match TcpListener::bind(&address) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to listen on {}: {}", address, e);
+            std::process::exit(1);
+        }
+    };
+
+    let signal_notifier = match signal::notify(&[signal::SIGINT, signal::SIGTERM]) {
+        Ok(notifier) => notifier,
+        Err(e) => {
+            eprintln!("Failed to create signal notifier: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    info!("Modbus TCP server listening on {}", address);
+
+    thread::Builder::new()
+        .name("modbus-server".to_string())
+        .spawn(move || {
+            if let Err(e) = server.serve(listener) {
+                error!("Modbus TCP server failed: {}", e);
+                std::process::exit(1);
+            }
+        })
+        .unwrap();
+
+    if let Ok(signal) = signal_notifier.recv() {
+        info!("Exit due to signal: {}", signal);
+    }
+}
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// MQTT bridge for the channels of the active device definition.
+//
+// Usage: mqttd [CONFIG]
+//
+// The configuration is read from /etc/sysworxx-io/mqttd.conf by default. See `config/mqttd.conf`
+// for the available options and `sysworxx_io::mqtt::bridge` for the topic layout.
+
+#[macro_use]
+extern crate log;
+
+use sysworxx_io::mqtt::bridge::{Bridge, Config};
+use sysworxx_io::{definition, hw_rev, signal};
+
+const DEFAULT_CONFIG: &str = "/etc/sysworxx-io/mqttd.conf";
+
+pub fn main() {
+    use env_logger::Env;
+    let env = Env::new().filter("IO_LOG").write_style("IO_LOG_STYLE");
+    env_logger::init_from_env(env);
+
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_CONFIG.to_string());
+
+    let config = match Config::load(&path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid configuration {}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut io = definition::load_device_definition(
+        &hw_rev::get_device_name().unwrap_or("fallback".to_string()),
+    );
+
+    if let Err(e) = io.init() {
+        eprintln!("Failed to initialize: {}", e);
+        std::process::exit(1);
+    }
+
+    let signal_notifier = match signal::notify(&[signal::SIGINT, signal::SIGTERM]) {
+        Ok(notifier) => notifier,
+        Err(e) => {
+            eprintln!("Failed to create signal notifier: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    Bridge::new(io, config).run(&signal_notifier);
+    info!("Exit due to signal");
+}
@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// OPC UA server for the channels of the active device definition.
+//
+// Usage: opcuad [ADDRESS]
+//
+// The server listens on 0.0.0.0:4840 by default. Only the security policy None with anonymous
+// sessions is supported.
+
+#[macro_use]
+extern crate log;
+
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use sysworxx_io::opcua::server::Server;
+use sysworxx_io::{definition, hw_rev, signal};
+
+const DEFAULT_ADDRESS: &str = "0.0.0.0:4840";
+
+/// URL of the endpoint, the unspecified address is replaced with the host name
+fn endpoint_url(listener: &TcpListener) -> std::io::Result<String> {
+    let address = listener.local_addr()?;
+
+    let mut buffer = [0u8; 64];
+    let host = match nix::unistd::gethostname(&mut buffer) {
+        Ok(host) if address.ip().is_unspecified() => host.to_string_lossy().into_owned(),
+        _ => address.ip().to_string(),
+    };
+
+    Ok(format!("opc.tcp://{}:{}", host, address.port()))
+}
+
+pub fn main() {
+    use env_logger::Env;
+    let env = Env::new().filter("IO_LOG").write_style("IO_LOG_STYLE");
+    env_logger::init_from_env(env);
+
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+
+    let device = hw_rev::get_device_name().unwrap_or("fallback".to_string());
+    let mut io = definition::load_device_definition(&device);
+
+    if let Err(e) = io.init() {
+        eprintln!("Failed to initialize: {}", e);
+        std::process::exit(1);
+    }
+
+    let listener = match TcpListener::bind(&address) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to listen on {}: {}", address, e);
+            std::process::exit(1);
+        }
+    };
+
+    let url = match endpoint_url(&listener) {
+        Ok(url) => url,
+        Err(e) => {
+            eprintln!("Failed to get endpoint URL: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let server = Arc::new(Server::new(Arc::new(Mutex::new(io)), &device, &url));
+
+    let signal_notifier = match signal::notify(&[signal::SIGINT, signal::SIGTERM]) {
+        Ok(notifier) => notifier,
+        Err(e) => {
+            eprintln!("Failed to create signal notifier: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    info!("OPC UA server listening on {}", url);
+
+    thread::Builder::new()
+        .name("opcua-server".to_string())
+        .spawn(move || {
+            if let Err(e) = server.serve(listener) {
+                error!("OPC UA server failed: {}", e);
+                std::process::exit(1);
+            }
+        })
+        .unwrap();
+
+    if let Ok(signal) = signal_notifier.recv() {
+        info!("Exit due to signal: {}", signal);
+    }
+}
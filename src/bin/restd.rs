@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// HTTP/JSON REST API with WebSocket notifications for the channels of the active device
+// definition.
+//
+// Usage: restd [CONFIG]
+//
+// The configuration is read from /etc/sysworxx-io/restd.conf by default. See `config/restd.conf`
+// for the available options and `sysworxx_io::http::server` for the API.
+
+#[macro_use]
+extern crate log;
+
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+
+use sysworxx_io::http::server::{Config, Server};
+use sysworxx_io::{definition, hw_rev, signal};
+
+const DEFAULT_CONFIG: &str = "/etc/sysworxx-io/restd.conf";
+
+pub fn main() {
+    use env_logger::Env;
+    let env = Env::new().filter("IO_LOG").write_style("IO_LOG_STYLE");
+    env_logger::init_from_env(env);
+
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_CONFIG.to_string());
+
+    let config = match Config::load(&path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid configuration {}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+
+    let device = hw_rev::get_device_name().unwrap_or("fallback".to_string());
+    let mut io = definition::load_device_definition(&device);
+
+    if let Err(e) = io.init() {
+        eprintln!("Failed to initialize: {}", e);
+        std::process::exit(1);
+    }
+
+    let listener = match TcpListener::bind(&config.address) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to listen on {}: {}", config.address, e);
+            std::process::exit(1);
+        }
+    };
+
+    let signal_notifier = match signal::notify(&[signal::SIGINT, signal::SIGTERM]) {
+        Ok(notifier) => notifier,
+        Err(e) => {
+            eprintln!("Failed to create signal notifier: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    info!(
+        "REST API listening on {}{}{}",
+        config.address,
+        if config.token.is_some() {
+            " (token required)"
+        } else {
+            ""
+        },
+        if config.read_only { " (read-only)" } else { "" },
+    );
+    let server = Arc::new(Server::new(io, &device, config));
+
+    thread::Builder::new()
+        .name("http-server".to_string())
+        .spawn(move || {
+            if let Err(e) = server.serve(listener) {
+                error!("HTTP server failed: {}", e);
+                std::process::exit(1);
+            }
+        })
+        .unwrap();
+
+    if let Ok(signal) = signal_notifier.recv() {
+        info!("Exit due to signal: {}", signal);
+    }
+}
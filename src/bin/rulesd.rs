@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Rule engine driving outputs and LEDs of the active device definition.
+//
+// Usage: rulesd [RULES]
+//
+// The rules are read from /etc/sysworxx-io/rules.toml by default, files with the extension .yaml
+// or .yml are read as YAML. See `config/rules.toml` for an example and `sysworxx_io::rules` for
+// the syntax.
+
+#[macro_use]
+extern crate log;
+
+use sysworxx_io::rules::{Engine, RuleSet};
+use sysworxx_io::{definition, hw_rev, signal};
+
+const DEFAULT_CONFIG: &str = "/etc/sysworxx-io/rules.toml";
+
+pub fn main() {
+    use env_logger::Env;
+    let env = Env::new().filter("IO_LOG").write_style("IO_LOG_STYLE");
+    env_logger::init_from_env(env);
+
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_CONFIG.to_string());
+
+    let rule_set = match RuleSet::load(&path) {
+        Ok(rule_set) => rule_set,
+        Err(e) => {
+            eprintln!("Invalid rules {}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+
+    let device = hw_rev::get_device_name().unwrap_or("fallback".to_string());
+    let mut io = definition::load_device_definition(&device);
+
+    if let Err(e) = io.init() {
+        eprintln!("Failed to initialize: {}", e);
+        std::process::exit(1);
+    }
+
+    let signal_notifier = match signal::notify(&[signal::SIGINT, signal::SIGTERM]) {
+        Ok(notifier) => notifier,
+        Err(e) => {
+            eprintln!("Failed to create signal notifier: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut engine = match Engine::new(io, &rule_set) {
+        Ok(engine) => engine,
+        Err(e) => {
+            eprintln!("Invalid rules {}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+
+    info!(
+        "Evaluating {} rules every {:?}",
+        rule_set.rules.len(),
+        rule_set.cycle
+    );
+    engine.run(&signal_notifier);
+    info!("Exit due to signal");
+}
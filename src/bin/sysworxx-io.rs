@@ -0,0 +1,678 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Command line tool to access the channels of the active device definition, e.g. for
+// commissioning. See `sysworxx-io --help` for the available commands.
+
+use std::fmt;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use sysworxx_io::{definition, events, ffi, hw_rev, Io};
+
+const USAGE: &str = "\
+Usage: sysworxx-io [--json] <COMMAND> [ARGS]
+
+Commands:
+  list                                  List all channels with labels and metadata
+  get <KIND> [CHANNEL...]               Read channels (all channels of the kind by default)
+  set <KIND> <CHANNEL> <VALUE>          Set a digital output (do), analog output (ao) or LED
+  pwm <CHANNEL> [period=N] [duty=N] [timebase=800ns|1ms] [on|off]
+  pwm <CHANNEL> [period_ns=N duty_ns=N | frequency=HZ duty_percent=P]
+      [polarity=normal|inversed] [on|off]
+                                        Configure and enable/disable a PWM output
+  analog-mode <CHANNEL> <voltage|current>
+                                        Set the mode of an analog input
+  rtd-mode <CHANNEL> <2wire|3wire|4wire> <pt100|pt1000>
+                                        Set the mode and sensor type of a temperature input
+  counter <CHANNEL> [mode=counter|ab_encoder] [trigger=rising|falling|any]
+          [direction=up|down] [preload=N] [compare=N|off] [reload=on|off]
+          [reset] [on|off]
+                                        Set up, reset and enable/disable a counter
+  watch [--interval MS] [KIND...]       Print changes of inputs with timestamps (default kinds:
+                                        di ai tmp cnt, interval 100 ms)
+
+Kinds: do, di, ai, ao, tmp, cnt, pwm, led (run, err), switch (run, config)
+Channels are selected by label or index. Digital values are 1/0, on/off or true/false.
+Temperatures are given in °C, analog values as raw values of the device.
+
+Options:
+  --json                                Print JSON instead of tables
+  -h, --help                            Print this help";
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Kind {
+    Do,
+    Di,
+    Ai,
+    Ao,
+    Tmp,
+    Cnt,
+    Pwm,
+    Led,
+    Switch,
+}
+
+const KINDS: [Kind; 9] = [
+    Kind::Do,
+    Kind::Di,
+    Kind::Ai,
+    Kind::Ao,
+    Kind::Tmp,
+    Kind::Cnt,
+    Kind::Pwm,
+    Kind::Led,
+    Kind::Switch,
+];
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Do => "do",
+            Kind::Di => "di",
+            Kind::Ai => "ai",
+            Kind::Ao => "ao",
+            Kind::Tmp => "tmp",
+            Kind::Cnt => "cnt",
+            Kind::Pwm => "pwm",
+            Kind::Led => "led",
+            Kind::Switch => "switch",
+        }
+    }
+
+    fn parse(name: &str) -> Result<Kind> {
+        KINDS
+            .iter()
+            .copied()
+            .find(|kind| kind.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("Unknown channel kind: {}", name))
+    }
+
+    fn unit(self) -> &'static str {
+        match self {
+            Kind::Ai | Kind::Ao => "raw",
+            Kind::Tmp => "°C",
+            Kind::Cnt => "counts",
+            _ => "",
+        }
+    }
+
+    fn labels(self, io: &Io) -> Vec<Option<&'static str>> {
+        let info = io.get_channel_info();
+        match self {
+            Kind::Do => info.outputs.iter().map(|c| c.label()).collect(),
+            Kind::Di => info.inputs.iter().map(|c| c.label()).collect(),
+            Kind::Ai => info.analog_inputs.iter().map(|c| c.label()).collect(),
+            Kind::Ao => info.analog_outputs.iter().map(|c| c.label()).collect(),
+            Kind::Tmp => info.temp_sensors.iter().map(|c| c.label()).collect(),
+            Kind::Cnt => info.counter_input.iter().map(|c| c.label()).collect(),
+            Kind::Pwm => info.pwm_outputs.iter().map(|c| c.label()).collect(),
+            Kind::Led => vec![Some("run"), Some("err")],
+            Kind::Switch => vec![Some("run"), Some("config")],
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, String>;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Value {
+    Digital(bool),
+    Analog(i64),
+    Temperature(f64),
+    Counter(i32),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Digital(value) => write!(f, "{}", *value as u8),
+            Value::Analog(value) => write!(f, "{}", value),
+            Value::Temperature(value) => write!(f, "{:.2}", value),
+            Value::Counter(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<Value> for json::JsonValue {
+    fn from(value: Value) -> json::JsonValue {
+        match value {
+            Value::Digital(value) => value.into(),
+            Value::Analog(value) => value.into(),
+            Value::Temperature(value) => value.into(),
+            Value::Counter(value) => value.into(),
+        }
+    }
+}
+
+/// Channel of a kind, selected by label or index
+#[derive(Debug, Copy, Clone)]
+struct Channel {
+    kind: Kind,
+    index: usize,
+    label: Option<&'static str>,
+}
+
+impl Channel {
+    fn find(io: &Io, kind: Kind, id: &str) -> Result<Channel> {
+        let labels = kind.labels(io);
+        labels
+            .iter()
+            .position(|label| label.is_some_and(|label| label.eq_ignore_ascii_case(id)))
+            .or_else(|| id.parse().ok().filter(|index| *index < labels.len()))
+            .map(|index| Channel {
+                kind,
+                index,
+                label: labels[index],
+            })
+            .ok_or_else(|| format!("Unknown {} channel: {}", kind.name(), id))
+    }
+
+    fn all(io: &Io, kind: Kind) -> Vec<Channel> {
+        kind.labels(io)
+            .into_iter()
+            .enumerate()
+            .map(|(index, label)| Channel { kind, index, label })
+            .collect()
+    }
+
+    fn read(&self, io: &mut Io) -> sysworxx_io::error::Result<Value> {
+        let i = self.index;
+        match self.kind {
+            Kind::Do => io.output_get(i).map(Value::Digital),
+            Kind::Di => io.input_get(i).map(Value::Digital),
+            Kind::Ai => io.analog_input_get(i).map(Value::Analog),
+            Kind::Tmp => io.tmp_input_get(i).map(Value::Temperature),
+            Kind::Cnt => io.cnt_get(i).map(Value::Counter),
+            Kind::Led if i == 0 => io.get_run_led().map(Value::Digital),
+            Kind::Led => io.get_err_led().map(Value::Digital),
+            Kind::Switch if i == 0 => io.get_run_switch().map(Value::Digital),
+            Kind::Switch => io.get_config_switch().map(Value::Digital),
+            // the state of analog and PWM outputs cannot be read back
+            Kind::Ao | Kind::Pwm => Err(sysworxx_io::error::Error::NotImplemented),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self.label {
+            Some(label) => label.to_string(),
+            None => self.index.to_string(),
+        }
+    }
+
+    /// Prefix for error messages
+    fn context(&self) -> String {
+        format!("{} {}", self.kind.name(), self.name())
+    }
+
+    fn to_json(self) -> json::JsonValue {
+        json::object! {
+            kind: self.kind.name(),
+            index: self.index,
+            label: self.label,
+        }
+    }
+}
+
+fn parse_digital(value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "on" | "true" => Ok(true),
+        "0" | "off" | "false" => Ok(false),
+        _ => Err(format!("Invalid digital value: {}", value)),
+    }
+}
+
+fn parse<T: std::str::FromStr>(name: &str, value: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {}: {}", name, value))
+}
+
+/// Split "key=value" arguments, other arguments are returned with an empty key
+fn options(args: &[String]) -> Vec<(&str, &str)> {
+    args.iter()
+        .map(|arg| match arg.find('=') {
+            Some(position) => (&arg[..position], &arg[position + 1..]),
+            None => ("", arg.as_str()),
+        })
+        .collect()
+}
+
+fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: Vec<&str>| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| {
+                let padding = width - cell.chars().count();
+                format!("{}{}", cell, " ".repeat(padding))
+            })
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    };
+
+    line(header.to_vec());
+    for row in rows {
+        line(row.iter().map(String::as_str).collect());
+    }
+}
+
+/// Local time with milliseconds
+fn format_time(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs() as libc::time_t;
+    // SAFETY: localtime_r only writes to the given struct
+    let tm = unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        libc::localtime_r(&seconds, &mut tm);
+        tm
+    };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec,
+        since_epoch.subsec_millis()
+    )
+}
+
+fn list(io: &Io, device: &str, json: bool) -> Result<()> {
+    let channels: Vec<Channel> = KINDS
+        .iter()
+        .flat_map(|kind| Channel::all(io, *kind))
+        .collect();
+
+    if json {
+        let providers: Vec<json::JsonValue> = io
+            .providers()
+            .iter()
+            .map(|provider| provider.to_json())
+            .collect();
+        let channels: Vec<json::JsonValue> = channels
+            .iter()
+            .map(|channel| {
+                let mut value = channel.to_json();
+                value["unit"] = channel.kind.unit().into();
+                value
+            })
+            .collect();
+        let list = json::object! {
+            device: device,
+            channels: channels,
+            providers: providers,
+        };
+        println!("{}", list.pretty(2));
+        return Ok(());
+    }
+
+    println!("Device: {}", device);
+    for provider in io.providers() {
+        println!("Provider: {} ({})", provider.name, provider.description);
+    }
+    println!();
+    let rows: Vec<Vec<String>> = channels
+        .iter()
+        .map(|channel| {
+            vec![
+                channel.kind.name().to_string(),
+                channel.index.to_string(),
+                channel.label.unwrap_or("").to_string(),
+                channel.kind.unit().to_string(),
+            ]
+        })
+        .collect();
+    print_table(&["KIND", "INDEX", "LABEL", "UNIT"], &rows);
+    Ok(())
+}
+
+fn get(io: &mut Io, args: &[String], json: bool) -> Result<()> {
+    let (kind, ids) = args.split_first().ok_or("Missing channel kind")?;
+    let kind = Kind::parse(kind)?;
+    if kind == Kind::Ao || kind == Kind::Pwm {
+        return Err(format!("Channels of kind {} cannot be read", kind.name()));
+    }
+    let channels = if ids.is_empty() {
+        Channel::all(io, kind)
+    } else {
+        ids.iter()
+            .map(|id| Channel::find(io, kind, id))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let mut values = vec![];
+    for channel in channels {
+        let value = channel.read(io);
+        if let (Err(e), false) = (&value, ids.is_empty()) {
+            return Err(format!("{}: {}", channel.context(), e));
+        }
+        values.push((channel, value));
+    }
+
+    if json {
+        let values: Vec<json::JsonValue> = values
+            .into_iter()
+            .map(|(channel, value)| {
+                let mut object = channel.to_json();
+                match value {
+                    Ok(value) => object["value"] = value.into(),
+                    Err(e) => object["error"] = e.to_string().into(),
+                }
+                object["unit"] = channel.kind.unit().into();
+                object
+            })
+            .collect();
+        println!("{}", json::stringify_pretty(values, 2));
+        return Ok(());
+    }
+
+    let rows: Vec<Vec<String>> = values
+        .iter()
+        .map(|(channel, value)| {
+            vec![
+                channel.index.to_string(),
+                channel.label.unwrap_or("").to_string(),
+                match value {
+                    Ok(value) => value.to_string(),
+                    Err(e) => format!("({})", e),
+                },
+                channel.kind.unit().to_string(),
+            ]
+        })
+        .collect();
+    print_table(&["INDEX", "LABEL", "VALUE", "UNIT"], &rows);
+    Ok(())
+}
+
+fn set(io: &mut Io, args: &[String]) -> Result<()> {
+    let (kind, id, value) = match args {
+        [kind, id, value] => (Kind::parse(kind)?, id, value),
+        _ => return Err("Expected <KIND> <CHANNEL> <VALUE>".to_string()),
+    };
+    let channel = Channel::find(io, kind, id)?;
+    let result = match (kind, channel.index) {
+        (Kind::Do, i) => io.output_set(i, parse_digital(value)?),
+        (Kind::Ao, i) => io.analog_output_set(i, parse("analog output", value)?),
+        (Kind::Led, 0) => io.set_run_led(parse_digital(value)?),
+        (Kind::Led, _) => io.set_err_led(parse_digital(value)?),
+        _ => return Err(format!("Channels of kind {} cannot be set", kind.name())),
+    };
+    result.map_err(|e| format!("{}: {}", channel.context(), e))
+}
+
+fn pwm(io: &mut Io, args: &[String]) -> Result<()> {
+    let (id, args) = args.split_first().ok_or("Missing PWM channel")?;
+    let channel = Channel::find(io, Kind::Pwm, id)?;
+    let i = channel.index;
+
+    let (mut period, mut duty_cycle, mut timebase, mut enable) = (None, None, None, None);
+    let (mut period_ns, mut duty_ns, mut frequency, mut duty_percent) = (None, None, None, None);
+    let mut polarity = None;
+    for (key, value) in options(args) {
+        match key {
+            "period" => period = Some(parse(key, value)?),
+            "duty" | "duty_cycle" => duty_cycle = Some(parse(key, value)?),
+            "timebase" => timebase = Some(parse::<ffi::IoPwmTimebase>(key, value)?),
+            "period_ns" => period_ns = Some(parse(key, value)?),
+            "duty_ns" => duty_ns = Some(parse(key, value)?),
+            "frequency" => frequency = Some(parse(key, value)?),
+            "duty_percent" => duty_percent = Some(parse(key, value)?),
+            "polarity" => polarity = Some(parse::<ffi::IoPwmPolarity>(key, value)?),
+            "" => enable = Some(parse_digital(value)?),
+            _ => return Err(format!("Unknown PWM option: {}", key)),
+        }
+    }
+
+    let context = |e: sysworxx_io::error::Error| format!("{}: {}", channel.context(), e);
+    if let Some(timebase) = timebase {
+        io.pwm_set_timebase(i, timebase).map_err(context)?;
+    }
+    match (period, duty_cycle) {
+        (Some(period), Some(duty_cycle)) => io.pwm_setup(i, period, duty_cycle).map_err(context)?,
+        (None, None) => {}
+        _ => return Err("Period and duty cycle have to be set together".to_string()),
+    }
+    match (period_ns, duty_ns) {
+        (Some(period), Some(duty_cycle)) => {
+            io.pwm_configure(i, period, duty_cycle).map_err(context)?
+        }
+        (None, None) => {}
+        _ => return Err("Period and duty cycle have to be set together".to_string()),
+    }
+    match (frequency, duty_percent) {
+        (Some(frequency), Some(duty_cycle)) => io
+            .pwm_set_frequency(i, frequency, duty_cycle)
+            .map_err(context)?,
+        (None, None) => {}
+        _ => return Err("Frequency and duty cycle have to be set together".to_string()),
+    }
+    if let Some(polarity) = polarity {
+        io.pwm_set_polarity(i, polarity).map_err(context)?;
+    }
+    if let Some(enable) = enable {
+        io.pwm_enable(i, enable).map_err(context)?;
+    }
+    Ok(())
+}
+
+fn analog_mode(io: &mut Io, args: &[String]) -> Result<()> {
+    let (channel, mode) = match args {
+        [id, mode] => (Channel::find(io, Kind::Ai, id)?, parse("mode", mode)?),
+        _ => return Err("Expected <CHANNEL> <voltage|current>".to_string()),
+    };
+    io.analog_mode_set(channel.index, mode)
+        .map_err(|e| format!("{}: {}", channel.context(), e))
+}
+
+fn rtd_mode(io: &mut Io, args: &[String]) -> Result<()> {
+    let (channel, mode, sensor) = match args {
+        [id, mode, sensor] => (
+            Channel::find(io, Kind::Tmp, id)?,
+            parse("mode", mode)?,
+            parse("sensor type", sensor)?,
+        ),
+        _ => return Err("Expected <CHANNEL> <2wire|3wire|4wire> <pt100|pt1000>".to_string()),
+    };
+    io.tmp_set_mode(channel.index, mode, sensor)
+        .map_err(|e| format!("{}: {}", channel.context(), e))
+}
+
+fn counter(io: &mut Io, args: &[String]) -> Result<()> {
+    let (id, args) = args.split_first().ok_or("Missing counter channel")?;
+    let channel = Channel::find(io, Kind::Cnt, id)?;
+    let i = channel.index;
+
+    let (mut mode, mut trigger, mut direction) = (None, None, None);
+    let (mut preload, mut enable) = (None, None);
+    let (mut compare, mut reload, mut reset) = (None, None, false);
+    for (key, value) in options(args) {
+        match key {
+            "mode" => mode = Some(parse::<ffi::IoCntMode>(key, value)?),
+            "trigger" => trigger = Some(parse::<ffi::IoCntTrigger>(key, value)?),
+            "direction" => direction = Some(parse::<ffi::IoCntDirection>(key, value)?),
+            "preload" => preload = Some(parse(key, value)?),
+            "compare" if value == "off" => compare = Some(None),
+            "compare" => compare = Some(Some(parse(key, value)?)),
+            "reload" => reload = Some(parse_digital(value)?),
+            "" if value == "reset" => reset = true,
+            "" => enable = Some(parse_digital(value)?),
+            _ => return Err(format!("Unknown counter option: {}", key)),
+        }
+    }
+
+    let context = |e: sysworxx_io::error::Error| format!("{}: {}", channel.context(), e);
+    if mode.is_some() || trigger.is_some() || direction.is_some() {
+        io.cnt_setup(
+            i,
+            mode.unwrap_or(ffi::IoCntMode::Counter),
+            trigger.unwrap_or(ffi::IoCntTrigger::RisingEdge),
+            direction.unwrap_or(ffi::IoCntDirection::Up),
+        )
+        .map_err(context)?;
+    }
+    if compare.is_some() || reload.is_some() {
+        io.cnt_set_compare(i, compare.flatten(), reload.unwrap_or(false))
+            .map_err(context)?;
+    }
+    if let Some(preload) = preload {
+        io.cnt_set_preload(i, preload).map_err(context)?;
+    }
+    if reset {
+        io.cnt_reset(i).map_err(context)?;
+    }
+    if let Some(enable) = enable {
+        io.cnt_enable(i, enable).map_err(context)?;
+    }
+    Ok(())
+}
+
+fn watch(io: &mut Io, args: &[String], json: bool) -> Result<()> {
+    let mut interval = Duration::from_millis(100);
+    let mut kinds = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--interval" => {
+                let value = args.next().ok_or("Missing interval")?;
+                interval = Duration::from_millis(parse("interval", value)?);
+            }
+            kind => kinds.push(Kind::parse(kind)?),
+        }
+    }
+    if kinds.is_empty() {
+        kinds = vec![Kind::Di, Kind::Ai, Kind::Tmp, Kind::Cnt];
+    }
+
+    let mut channels: Vec<Channel> = kinds
+        .iter()
+        .flat_map(|kind| Channel::all(io, *kind))
+        .collect();
+    channels.retain(|channel| channel.read(io).is_ok());
+    if channels.is_empty() {
+        return Err("No readable channels".to_string());
+    }
+
+    // edges are printed immediately if supported by the channel, otherwise on the next poll
+    let (event_tx, events) = crossbeam_channel::unbounded();
+    if kinds.contains(&Kind::Di) {
+        events::subscribe(event_tx);
+        for i in 0..Kind::Di.labels(io).len() {
+            events::register(io, i).ok();
+        }
+    }
+
+    let print = |channel: &Channel, value: Value| {
+        let now = SystemTime::now();
+        if json {
+            let mut event = channel.to_json();
+            event["value"] = value.into();
+            event["timestamp"] = (now
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64)
+                .into();
+            println!("{}", event.dump());
+        } else {
+            let line = format!(
+                "{}  {:<6}  {:<16}  {} {}",
+                format_time(now),
+                channel.kind.name(),
+                channel.name(),
+                value,
+                channel.kind.unit()
+            );
+            println!("{}", line.trim_end());
+        }
+    };
+
+    let mut last: Vec<Option<Value>> = vec![None; channels.len()];
+    let mut next_poll = Instant::now();
+    loop {
+        let timeout = next_poll.saturating_duration_since(Instant::now());
+        crossbeam_channel::select! {
+            recv(events) -> event => {
+                if let Ok((index, value)) = event {
+                    let position = channels
+                        .iter()
+                        .position(|c| c.kind == Kind::Di && c.index == index as usize);
+                    if let Some(position) = position {
+                        let value = Value::Digital(value);
+                        if last[position] != Some(value) {
+                            last[position] = Some(value);
+                            print(&channels[position], value);
+                        }
+                    }
+                }
+            },
+            default(timeout) => {},
+        }
+
+        if Instant::now() >= next_poll {
+            for (channel, last) in channels.iter().zip(last.iter_mut()) {
+                if let Ok(value) = channel.read(io) {
+                    if *last != Some(value) {
+                        *last = Some(value);
+                        print(channel, value);
+                    }
+                }
+            }
+            next_poll = Instant::now() + interval;
+        }
+    }
+}
+
+pub fn main() {
+    use env_logger::Env;
+    let env = Env::new().filter("IO_LOG").write_style("IO_LOG_STYLE");
+    env_logger::init_from_env(env);
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let json = args.iter().any(|arg| arg == "--json");
+    args.retain(|arg| arg != "--json");
+
+    let command = match args.first().map(String::as_str) {
+        Some("-h") | Some("--help") | Some("help") => {
+            println!("{}", USAGE);
+            return;
+        }
+        Some(command) => command.to_string(),
+        None => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+    let args = &args[1..];
+
+    let device = hw_rev::get_device_name().unwrap_or("fallback".to_string());
+    let mut io = definition::load_device_definition(&device);
+
+    if let Err(e) = io.init() {
+        eprintln!("Failed to initialize: {}", e);
+        std::process::exit(1);
+    }
+
+    let result = match command.as_str() {
+        "list" => list(&io, &device, json),
+        "get" => get(&mut io, args, json),
+        "set" => set(&mut io, args),
+        "pwm" => pwm(&mut io, args),
+        "analog-mode" => analog_mode(&mut io, args),
+        "rtd-mode" => rtd_mode(&mut io, args),
+        "counter" => counter(&mut io, args),
+        "watch" => watch(&mut io, args, json),
+        _ => Err(format!("Unknown command: {}\n\n{}", command, USAGE)),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Analog blocks: hysteresis comparator, linear scaling and ramp generator
+
+use std::time::Instant;
+
+/// Comparator switching on above `high` and off below `low`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hysteresis {
+    low: f64,
+    high: f64,
+    q: bool,
+}
+
+impl Hysteresis {
+    pub fn new(low: f64, high: f64) -> Hysteresis {
+        Hysteresis {
+            low: low.min(high),
+            high: low.max(high),
+            q: false,
+        }
+    }
+
+    pub fn call(&mut self, value: f64) -> bool {
+        if value > self.high {
+            self.q = true;
+        } else if value < self.low {
+            self.q = false;
+        }
+        self.q
+    }
+
+    pub fn q(&self) -> bool {
+        self.q
+    }
+}
+
+/// Linear scaling from an input range to an output range, e.g. from the raw value of an analog
+/// input to a physical unit. The output is limited to the output range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scale {
+    in_min: f64,
+    in_max: f64,
+    out_min: f64,
+    out_max: f64,
+}
+
+impl Scale {
+    pub fn new(in_min: f64, in_max: f64, out_min: f64, out_max: f64) -> Scale {
+        Scale {
+            in_min,
+            in_max,
+            out_min,
+            out_max,
+        }
+    }
+
+    pub fn call(&self, value: f64) -> f64 {
+        if self.in_max == self.in_min {
+            return self.out_min;
+        }
+        let out = self.out_min
+            + (value - self.in_min) * (self.out_max - self.out_min) / (self.in_max - self.in_min);
+        out.max(self.out_min.min(self.out_max))
+            .min(self.out_min.max(self.out_max))
+    }
+}
+
+/// Ramp generator: the output follows the target with a limited rate (units per second)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ramp {
+    rate: f64,
+    out: f64,
+    last: Option<Instant>,
+}
+
+impl Ramp {
+    pub fn new(rate: f64, initial: f64) -> Ramp {
+        Ramp {
+            rate: rate.abs(),
+            out: initial,
+            last: None,
+        }
+    }
+
+    pub fn call(&mut self, target: f64, now: Instant) -> f64 {
+        let step = match self.last {
+            Some(last) => now.saturating_duration_since(last).as_secs_f64() * self.rate,
+            None => 0.0,
+        };
+        self.last = Some(now);
+        self.out = if target > self.out {
+            (self.out + step).min(target)
+        } else {
+            (self.out - step).max(target)
+        };
+        self.out
+    }
+
+    pub fn out(&self) -> f64 {
+        self.out
+    }
+
+    /// Set the output without ramping
+    pub fn reset(&mut self, value: f64) {
+        self.out = value;
+    }
+
+    pub fn set_rate(&mut self, rate: f64) {
+        self.rate = rate.abs();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn analog_test() {
+        let mut hysteresis = Hysteresis::new(20.0, 25.0);
+        assert!(!hysteresis.call(22.0));
+        assert!(hysteresis.call(25.5));
+        assert!(hysteresis.call(21.0));
+        assert!(!hysteresis.call(19.9));
+
+        // 4..20 mA to 0..10 bar
+        let scale = Scale::new(4.0, 20.0, 0.0, 10.0);
+        assert_eq!(scale.call(12.0), 5.0);
+        assert_eq!(scale.call(2.0), 0.0);
+        assert_eq!(scale.call(24.0), 10.0);
+        let inverted = Scale::new(0.0, 10.0, 100.0, 0.0);
+        assert_eq!(inverted.call(2.5), 75.0);
+        assert_eq!(inverted.call(-1.0), 100.0);
+
+        let start = Instant::now();
+        let mut ramp = Ramp::new(10.0, 0.0);
+        assert_eq!(ramp.call(5.0, start), 0.0);
+        assert_eq!(ramp.call(5.0, start + Duration::from_millis(200)), 2.0);
+        assert_eq!(ramp.call(5.0, start + Duration::from_secs(1)), 5.0);
+        assert_eq!(ramp.call(-5.0, start + Duration::from_millis(1500)), 0.0);
+        ramp.reset(3.0);
+        assert_eq!(ramp.out(), 3.0);
+    }
+}
@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Latches (SR, RS)
+
+/// Set dominant latch
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sr {
+    q: bool,
+}
+
+impl Sr {
+    pub fn new() -> Sr {
+        Sr::default()
+    }
+
+    pub fn call(&mut self, set: bool, reset: bool) -> bool {
+        self.q = set || (!reset && self.q);
+        self.q
+    }
+
+    pub fn q(&self) -> bool {
+        self.q
+    }
+}
+
+/// Reset dominant latch
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Rs {
+    q: bool,
+}
+
+impl Rs {
+    pub fn new() -> Rs {
+        Rs::default()
+    }
+
+    pub fn call(&mut self, set: bool, reset: bool) -> bool {
+        self.q = !reset && (set || self.q);
+        self.q
+    }
+
+    pub fn q(&self) -> bool {
+        self.q
+    }
+}
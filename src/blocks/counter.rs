@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Counters (CTU, CTD, CTUD) counting rising edges of their count inputs
+
+use super::RTrig;
+
+/// Up counter: `q` is true if the counter reached the preset value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ctu {
+    pv: i64,
+    cv: i64,
+    cu: RTrig,
+}
+
+impl Ctu {
+    pub fn new(pv: i64) -> Ctu {
+        Ctu {
+            pv,
+            cv: 0,
+            cu: RTrig::new(),
+        }
+    }
+
+    pub fn call(&mut self, cu: bool, reset: bool) -> bool {
+        let edge = self.cu.call(cu);
+        if reset {
+            self.cv = 0;
+        } else if edge {
+            self.cv = self.cv.saturating_add(1);
+        }
+        self.q()
+    }
+
+    pub fn q(&self) -> bool {
+        self.cv >= self.pv
+    }
+
+    /// Counter value
+    pub fn cv(&self) -> i64 {
+        self.cv
+    }
+}
+
+/// Down counter: `q` is true if the counter reached zero
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ctd {
+    pv: i64,
+    cv: i64,
+    cd: RTrig,
+}
+
+impl Ctd {
+    pub fn new(pv: i64) -> Ctd {
+        Ctd {
+            pv,
+            cv: 0,
+            cd: RTrig::new(),
+        }
+    }
+
+    /// Count down on a rising edge of `cd`, `load` sets the counter to the preset value
+    pub fn call(&mut self, cd: bool, load: bool) -> bool {
+        let edge = self.cd.call(cd);
+        if load {
+            self.cv = self.pv;
+        } else if edge {
+            self.cv = self.cv.saturating_sub(1);
+        }
+        self.q()
+    }
+
+    pub fn q(&self) -> bool {
+        self.cv <= 0
+    }
+
+    /// Counter value
+    pub fn cv(&self) -> i64 {
+        self.cv
+    }
+}
+
+/// Up/down counter: `qu` is true if the counter reached the preset value, `qd` if it reached
+/// zero
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ctud {
+    pv: i64,
+    cv: i64,
+    cu: RTrig,
+    cd: RTrig,
+}
+
+impl Ctud {
+    pub fn new(pv: i64) -> Ctud {
+        Ctud {
+            pv,
+            cv: 0,
+            cu: RTrig::new(),
+            cd: RTrig::new(),
+        }
+    }
+
+    /// Returns (`qu`, `qd`). Simultaneous edges of `cu` and `cd` cancel out, `reset` takes
+    /// precedence over `load`.
+    pub fn call(&mut self, cu: bool, cd: bool, reset: bool, load: bool) -> (bool, bool) {
+        let (up, down) = (self.cu.call(cu), self.cd.call(cd));
+        if reset {
+            self.cv = 0;
+        } else if load {
+            self.cv = self.pv;
+        } else if up && !down {
+            self.cv = self.cv.saturating_add(1);
+        } else if down && !up {
+            self.cv = self.cv.saturating_sub(1);
+        }
+        (self.qu(), self.qd())
+    }
+
+    pub fn qu(&self) -> bool {
+        self.cv >= self.pv
+    }
+
+    pub fn qd(&self) -> bool {
+        self.cv <= 0
+    }
+
+    /// Counter value
+    pub fn cv(&self) -> i64 {
+        self.cv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_test() {
+        let mut ctu = Ctu::new(2);
+        assert!(!ctu.call(true, false));
+        // only edges are counted
+        assert!(!ctu.call(true, false));
+        assert!(!ctu.call(false, false));
+        assert!(ctu.call(true, false));
+        assert_eq!(ctu.cv(), 2);
+        assert!(!ctu.call(true, true));
+        assert_eq!(ctu.cv(), 0);
+
+        let mut ctd = Ctd::new(2);
+        assert!(ctd.call(false, false));
+        assert!(!ctd.call(false, true));
+        assert!(!ctd.call(true, false));
+        assert!(!ctd.call(false, false));
+        assert!(ctd.call(true, false));
+        assert_eq!(ctd.cv(), 0);
+
+        let mut ctud = Ctud::new(1);
+        assert_eq!(ctud.call(false, false, false, false), (false, true));
+        assert_eq!(ctud.call(true, false, false, false), (true, false));
+        assert_eq!(ctud.call(false, true, false, false), (false, true));
+        // simultaneous edges cancel out
+        assert_eq!(ctud.call(false, false, false, false), (false, true));
+        assert_eq!(ctud.call(true, true, false, false), (false, true));
+        assert_eq!(ctud.call(false, false, false, true), (true, false));
+        assert_eq!(ctud.call(false, false, true, true), (false, true));
+    }
+}
@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Edge detectors (R_TRIG, F_TRIG)
+
+/// Rising edge detector: true for one cycle if `clk` changes from false to true
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RTrig {
+    last: bool,
+    q: bool,
+}
+
+impl RTrig {
+    pub fn new() -> RTrig {
+        RTrig::default()
+    }
+
+    pub fn call(&mut self, clk: bool) -> bool {
+        self.q = clk && !self.last;
+        self.last = clk;
+        self.q
+    }
+
+    pub fn q(&self) -> bool {
+        self.q
+    }
+}
+
+/// Falling edge detector: true for one cycle if `clk` changes from true to false
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FTrig {
+    last: bool,
+    q: bool,
+}
+
+impl FTrig {
+    pub fn new() -> FTrig {
+        FTrig::default()
+    }
+
+    pub fn call(&mut self, clk: bool) -> bool {
+        self.q = !clk && self.last;
+        self.last = clk;
+        self.q
+    }
+
+    pub fn q(&self) -> bool {
+        self.q
+    }
+}
@@ -0,0 +1,317 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// IEC 61131-3 style function blocks for Rust applications. The blocks are plain state machines
+// which are called once per scan cycle. A `ScanCycle` executes a program on a deterministic
+// cycle (see `periodic::Periodic`) with a process image: inputs are read at most once per cycle
+// and outputs are written after the program, only if they changed.
+//
+//   let pump = DigitalIn::bind(&io, "DI2")?;
+//   let valve = DigitalOut::bind(&io, 0)?;
+//   let mut delay = Ton::new(Duration::from_secs(2));
+//   let mut cycle = ScanCycle::new(io, Duration::from_millis(10));
+//   cycle.run(&stop, |scan| {
+//       let on = delay.call(scan.get(pump)?, scan.now());
+//       scan.set(valve, on);
+//       Ok(())
+//   })?;
+
+mod analog;
+mod bistable;
+mod counter;
+mod edge;
+mod timer;
+
+pub use self::analog::{Hysteresis, Ramp, Scale};
+pub use self::bistable::{Rs, Sr};
+pub use self::counter::{Ctd, Ctu, Ctud};
+pub use self::edge::{FTrig, RTrig};
+pub use self::timer::{Tof, Ton, Tp};
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Receiver;
+
+use crate::error::{Error, Result};
+use crate::periodic::Periodic;
+use crate::rules::expr::Context;
+use crate::rules::{find_channel, Channel, IoContext};
+use crate::Io;
+
+/// Channel by index or by label
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ChannelRef<'a> {
+    Index(usize),
+    Label(&'a str),
+}
+
+impl From<usize> for ChannelRef<'_> {
+    fn from(index: usize) -> Self {
+        ChannelRef::Index(index)
+    }
+}
+
+impl<'a> From<&'a str> for ChannelRef<'a> {
+    fn from(label: &'a str) -> Self {
+        ChannelRef::Label(label)
+    }
+}
+
+/// Resolve a channel by index or by label (or prefix + index, e.g. "di0")
+fn bind<'a>(
+    labels: impl Iterator<Item = Option<&'a str>> + Clone,
+    prefix: &str,
+    channel: ChannelRef,
+) -> Result<usize> {
+    match channel {
+        ChannelRef::Index(index) if index < labels.clone().count() => Ok(index),
+        ChannelRef::Label(name) => find_channel(labels, prefix, name).ok_or_else(|| {
+            error!("Unknown channel {}", name);
+            Error::InvalidChannel
+        }),
+        ChannelRef::Index(_) => Err(Error::InvalidChannel),
+    }
+}
+
+/// Digital input bound to a channel of an `Io`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DigitalIn(Channel);
+
+impl DigitalIn {
+    pub fn bind<'a>(io: &Io, channel: impl Into<ChannelRef<'a>>) -> Result<DigitalIn> {
+        let info = io.get_channel_info();
+        let labels = info.inputs.iter().map(|c| c.label());
+        Ok(DigitalIn(Channel::Input(bind(
+            labels,
+            "di",
+            channel.into(),
+        )?)))
+    }
+}
+
+/// Analog input, temperature sensor or counter bound to a channel of an `Io`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AnalogIn(Channel);
+
+impl AnalogIn {
+    pub fn analog<'a>(io: &Io, channel: impl Into<ChannelRef<'a>>) -> Result<AnalogIn> {
+        let info = io.get_channel_info();
+        let labels = info.analog_inputs.iter().map(|c| c.label());
+        Ok(AnalogIn(Channel::AnalogInput(bind(
+            labels,
+            "ai",
+            channel.into(),
+        )?)))
+    }
+
+    pub fn temperature<'a>(io: &Io, channel: impl Into<ChannelRef<'a>>) -> Result<AnalogIn> {
+        let info = io.get_channel_info();
+        let labels = info.temp_sensors.iter().map(|c| c.label());
+        Ok(AnalogIn(Channel::Temperature(bind(
+            labels,
+            "tmp",
+            channel.into(),
+        )?)))
+    }
+
+    pub fn counter<'a>(io: &Io, channel: impl Into<ChannelRef<'a>>) -> Result<AnalogIn> {
+        let info = io.get_channel_info();
+        let labels = info.counter_input.iter().map(|c| c.label());
+        Ok(AnalogIn(Channel::Counter(bind(
+            labels,
+            "cnt",
+            channel.into(),
+        )?)))
+    }
+}
+
+/// Digital output bound to a channel of an `Io`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DigitalOut(usize);
+
+impl DigitalOut {
+    pub fn bind<'a>(io: &Io, channel: impl Into<ChannelRef<'a>>) -> Result<DigitalOut> {
+        let info = io.get_channel_info();
+        let labels = info.outputs.iter().map(|c| c.label());
+        Ok(DigitalOut(bind(labels, "do", channel.into())?))
+    }
+}
+
+/// Analog output bound to a channel of an `Io`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AnalogOut(usize);
+
+impl AnalogOut {
+    pub fn bind<'a>(io: &Io, channel: impl Into<ChannelRef<'a>>) -> Result<AnalogOut> {
+        let info = io.get_channel_info();
+        let labels = info.analog_outputs.iter().map(|c| c.label());
+        Ok(AnalogOut(bind(labels, "ao", channel.into())?))
+    }
+}
+
+/// Process image of one scan cycle
+pub struct Scan<'a> {
+    inputs: IoContext<'a>,
+    now: Instant,
+    outputs: HashMap<usize, bool>,
+    analog_outputs: HashMap<usize, i64>,
+}
+
+impl Scan<'_> {
+    /// Start time of the cycle
+    pub fn now(&self) -> Instant {
+        self.now
+    }
+
+    pub fn get(&mut self, input: DigitalIn) -> Result<bool> {
+        Ok(self.inputs.read(input.0)?.as_bool())
+    }
+
+    /// Raw value of an analog input, temperature in °C or counter value
+    pub fn get_analog(&mut self, input: AnalogIn) -> Result<f64> {
+        Ok(self.inputs.read(input.0)?.as_f64())
+    }
+
+    pub fn set(&mut self, output: DigitalOut, value: bool) {
+        self.outputs.insert(output.0, value);
+    }
+
+    pub fn set_analog(&mut self, output: AnalogOut, value: i64) {
+        self.analog_outputs.insert(output.0, value);
+    }
+}
+
+/// Executes a program cyclically on an `Io`
+pub struct ScanCycle {
+    io: Io,
+    periodic: Periodic,
+    /// Values written to the outputs
+    outputs: HashMap<usize, bool>,
+    analog_outputs: HashMap<usize, i64>,
+}
+
+impl ScanCycle {
+    pub fn new(io: Io, interval: Duration) -> ScanCycle {
+        ScanCycle {
+            io,
+            periodic: Periodic::new(interval),
+            outputs: HashMap::new(),
+            analog_outputs: HashMap::new(),
+        }
+    }
+
+    pub fn io(&mut self) -> &mut Io {
+        &mut self.io
+    }
+
+    pub fn into_inner(self) -> Io {
+        self.io
+    }
+
+    /// Wait for the next cycle and execute the program once
+    pub fn scan<F>(&mut self, program: F) -> Result<()>
+    where
+        F: FnOnce(&mut Scan) -> Result<()>,
+    {
+        self.periodic.next();
+        self.scan_at(Instant::now(), program)
+    }
+
+    /// Execute the program once with the given cycle time without waiting
+    pub fn scan_at<F>(&mut self, now: Instant, program: F) -> Result<()>
+    where
+        F: FnOnce(&mut Scan) -> Result<()>,
+    {
+        let mut scan = Scan {
+            inputs: IoContext {
+                io: &mut self.io,
+                cache: HashMap::new(),
+            },
+            now,
+            outputs: HashMap::new(),
+            analog_outputs: HashMap::new(),
+        };
+        program(&mut scan)?;
+        let (outputs, analog_outputs) = (scan.outputs, scan.analog_outputs);
+
+        for (index, value) in outputs {
+            if self.outputs.get(&index) != Some(&value) {
+                self.io.output_set(index, value)?;
+                self.outputs.insert(index, value);
+            }
+        }
+        for (index, value) in analog_outputs {
+            if self.analog_outputs.get(&index) != Some(&value) {
+                self.io.analog_output_set(index, value)?;
+                self.analog_outputs.insert(index, value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Execute the program cyclically until a message is received from `stop` or the program
+    /// fails
+    pub fn run<T, F>(&mut self, stop: &Receiver<T>, mut program: F) -> Result<()>
+    where
+        F: FnMut(&mut Scan) -> Result<()>,
+    {
+        while stop.try_recv().is_err() {
+            self.scan(&mut program)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+    use crate::labeled::Labeled;
+
+    #[test]
+    fn scan_cycle_test() {
+        // DO0 is looped back to DI0
+        let sim = Simulator::new("SIM", 2, 0, 1);
+        let io = IoBuilder::new()
+            .output(Box::new(Labeled::new("Start", sim.output(0))))
+            .output(Box::new(Labeled::new("Fan", sim.output(1))))
+            .input(Box::new(Labeled::new("Button", sim.input(0))))
+            .temp_sensor(Box::new(sim.temp_sensor(0)))
+            .build();
+
+        let button = DigitalIn::bind(&io, "button").unwrap();
+        let temperature = AnalogIn::temperature(&io, "tmp0").unwrap();
+        let fan = DigitalOut::bind(&io, 1).unwrap();
+        assert_eq!(DigitalOut::bind(&io, "Fan").unwrap(), fan);
+        assert!(DigitalIn::bind(&io, 1).is_err());
+        assert!(AnalogOut::bind(&io, "AO0").is_err());
+
+        let mut cycle = ScanCycle::new(io, Duration::from_millis(10));
+        cycle.io().init().unwrap();
+        let mut latch = Sr::new();
+        let mut trigger = RTrig::new();
+        let mut hysteresis = Hysteresis::new(30.0, 40.0);
+        let mut program = |scan: &mut Scan| {
+            let pressed = trigger.call(scan.get(button)?);
+            let hot = hysteresis.call(scan.get_analog(temperature)?);
+            scan.set(fan, latch.call(hot, pressed));
+            Ok(())
+        };
+
+        let start = Instant::now();
+        cycle.scan_at(start, &mut program).unwrap();
+        assert!(!cycle.io().output_get(1).unwrap());
+
+        sim.set_temperature(0, 45.0).unwrap();
+        cycle.scan_at(start, &mut program).unwrap();
+        assert!(cycle.io().output_get(1).unwrap());
+
+        // the fan stays on until the button is pressed
+        sim.set_temperature(0, 20.0).unwrap();
+        cycle.io().output_set(0, true).unwrap();
+        cycle.scan_at(start, &mut program).unwrap();
+        assert!(!cycle.io().output_get(1).unwrap());
+    }
+}
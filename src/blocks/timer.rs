@@ -0,0 +1,193 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Timers (TON, TOF, TP). The time is passed to every call, usually the start of the scan cycle,
+// so all blocks of a cycle see the same time.
+
+use std::time::{Duration, Instant};
+
+/// On delay: `q` is true if `input` is true for at least the preset time
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ton {
+    pt: Duration,
+    start: Option<Instant>,
+    et: Duration,
+    q: bool,
+}
+
+impl Ton {
+    pub fn new(pt: Duration) -> Ton {
+        Ton {
+            pt,
+            start: None,
+            et: Duration::default(),
+            q: false,
+        }
+    }
+
+    pub fn call(&mut self, input: bool, now: Instant) -> bool {
+        if input {
+            let start = *self.start.get_or_insert(now);
+            self.et = now.saturating_duration_since(start).min(self.pt);
+            self.q = self.et >= self.pt;
+        } else {
+            self.start = None;
+            self.et = Duration::default();
+            self.q = false;
+        }
+        self.q
+    }
+
+    pub fn q(&self) -> bool {
+        self.q
+    }
+
+    /// Elapsed time
+    pub fn et(&self) -> Duration {
+        self.et
+    }
+
+    pub fn set_pt(&mut self, pt: Duration) {
+        self.pt = pt;
+    }
+}
+
+/// Off delay: `q` is true while `input` is true and for the preset time after `input` became
+/// false
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tof {
+    pt: Duration,
+    start: Option<Instant>,
+    et: Duration,
+    q: bool,
+}
+
+impl Tof {
+    pub fn new(pt: Duration) -> Tof {
+        Tof {
+            pt,
+            start: None,
+            et: Duration::default(),
+            q: false,
+        }
+    }
+
+    pub fn call(&mut self, input: bool, now: Instant) -> bool {
+        if input {
+            self.start = None;
+            self.et = Duration::default();
+            self.q = true;
+        } else if self.q {
+            let start = *self.start.get_or_insert(now);
+            self.et = now.saturating_duration_since(start).min(self.pt);
+            self.q = self.et < self.pt;
+        }
+        self.q
+    }
+
+    pub fn q(&self) -> bool {
+        self.q
+    }
+
+    /// Elapsed time
+    pub fn et(&self) -> Duration {
+        self.et
+    }
+
+    pub fn set_pt(&mut self, pt: Duration) {
+        self.pt = pt;
+    }
+}
+
+/// Pulse: `q` is true for the preset time after a rising edge of `input`, edges during the pulse
+/// are ignored
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tp {
+    pt: Duration,
+    start: Option<Instant>,
+    last: bool,
+    et: Duration,
+    q: bool,
+}
+
+impl Tp {
+    pub fn new(pt: Duration) -> Tp {
+        Tp {
+            pt,
+            start: None,
+            last: false,
+            et: Duration::default(),
+            q: false,
+        }
+    }
+
+    pub fn call(&mut self, input: bool, now: Instant) -> bool {
+        if self.start.is_none() && input && !self.last {
+            self.start = Some(now);
+        }
+        self.last = input;
+
+        if let Some(start) = self.start {
+            self.et = now.saturating_duration_since(start).min(self.pt);
+            self.q = self.et < self.pt;
+            // the elapsed time is kept until the input is false
+            if !self.q && !input {
+                self.start = None;
+                self.et = Duration::default();
+            }
+        }
+        self.q
+    }
+
+    pub fn q(&self) -> bool {
+        self.q
+    }
+
+    /// Elapsed time
+    pub fn et(&self) -> Duration {
+        self.et
+    }
+
+    pub fn set_pt(&mut self, pt: Duration) {
+        self.pt = pt;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timer_test() {
+        let start = Instant::now();
+        let ms = |ms| start + Duration::from_millis(ms);
+
+        let mut ton = Ton::new(Duration::from_millis(100));
+        assert!(!ton.call(true, ms(0)));
+        assert!(!ton.call(true, ms(99)));
+        assert_eq!(ton.et(), Duration::from_millis(99));
+        assert!(ton.call(true, ms(100)));
+        assert_eq!(ton.et(), Duration::from_millis(100));
+        assert!(!ton.call(false, ms(150)));
+        assert!(!ton.call(true, ms(200)));
+
+        let mut tof = Tof::new(Duration::from_millis(100));
+        assert!(!tof.call(false, ms(0)));
+        assert!(tof.call(true, ms(10)));
+        assert!(tof.call(false, ms(20)));
+        assert!(tof.call(false, ms(119)));
+        assert!(!tof.call(false, ms(120)));
+        assert_eq!(tof.et(), Duration::from_millis(100));
+
+        let mut tp = Tp::new(Duration::from_millis(100));
+        assert!(tp.call(true, ms(0)));
+        assert!(tp.call(false, ms(50)));
+        // edges during the pulse are ignored
+        assert!(tp.call(true, ms(60)));
+        assert!(!tp.call(true, ms(100)));
+        assert_eq!(tp.et(), Duration::from_millis(100));
+        assert!(!tp.call(false, ms(110)));
+        assert_eq!(tp.et(), Duration::default());
+        assert!(tp.call(true, ms(120)));
+    }
+}
@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+use crate::io::null;
+use crate::{
+    AnalogInput, AnalogOutput, CounterInput, DigitalInput, DigitalOutput, Io, PwmOutput,
+    TempSensor, Watchdog,
+};
+
+/// Builder for `Io` instances, e.g. to describe derivative boards outside of this crate
+///
+/// All channels which are not set explicitly are initialized with the corresponding `null`
+/// implementation. Channels of the same type are numbered in the order they are added.
+#[derive(Debug)]
+pub struct IoBuilder {
+    io: Io,
+}
+
+impl Default for IoBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IoBuilder {
+    pub fn new() -> IoBuilder {
+        IoBuilder {
+            io: Io {
+                watchdog: Box::new(null::Wdg::new()),
+                run_led: Box::new(null::Output::not_implemented()),
+                err_led: Box::new(null::Output::not_implemented()),
+                run_switch: Box::new(null::Input::not_implemented()),
+                config_switch: Box::new(null::Input::not_implemented()),
+                outputs: vec![],
+                inputs: vec![],
+                analog_inputs: vec![],
+                analog_outputs: vec![],
+                temp_sensors: vec![],
+                counter_input: vec![],
+                relay_offset: None,
+                pwm_outputs: vec![],
+                providers: vec![],
+            },
+        }
+    }
+
+    pub fn watchdog(mut self, watchdog: Box<dyn Watchdog>) -> IoBuilder {
+        self.io.watchdog = watchdog;
+        self
+    }
+
+    pub fn run_led(mut self, led: Box<dyn DigitalOutput>) -> IoBuilder {
+        self.io.run_led = led;
+        self
+    }
+
+    pub fn err_led(mut self, led: Box<dyn DigitalOutput>) -> IoBuilder {
+        self.io.err_led = led;
+        self
+    }
+
+    pub fn run_switch(mut self, switch: Box<dyn DigitalInput>) -> IoBuilder {
+        self.io.run_switch = switch;
+        self
+    }
+
+    pub fn config_switch(mut self, switch: Box<dyn DigitalInput>) -> IoBuilder {
+        self.io.config_switch = switch;
+        self
+    }
+
+    pub fn output(mut self, output: Box<dyn DigitalOutput>) -> IoBuilder {
+        self.io.outputs.push(output);
+        self
+    }
+
+    /// Fill up the digital outputs with not implemented channels until `len` channels exist.
+    /// This can be used to place channels at fixed channel numbers (e.g. 32 for special outputs).
+    pub fn pad_outputs(mut self, len: usize) -> IoBuilder {
+        while self.io.outputs.len() < len {
+            self.io
+                .outputs
+                .push(Box::new(null::Output::not_implemented()));
+        }
+        self
+    }
+
+    pub fn input(mut self, input: Box<dyn DigitalInput>) -> IoBuilder {
+        self.io.inputs.push(input);
+        self
+    }
+
+    /// Fill up the digital inputs with not implemented channels until `len` channels exist.
+    pub fn pad_inputs(mut self, len: usize) -> IoBuilder {
+        while self.io.inputs.len() < len {
+            self.io
+                .inputs
+                .push(Box::new(null::Input::not_implemented()));
+        }
+        self
+    }
+
+    pub fn analog_input(mut self, input: Box<dyn AnalogInput>) -> IoBuilder {
+        self.io.analog_inputs.push(input);
+        self
+    }
+
+    pub fn analog_output(mut self, output: Box<dyn AnalogOutput>) -> IoBuilder {
+        self.io.analog_outputs.push(output);
+        self
+    }
+
+    pub fn temp_sensor(mut self, sensor: Box<dyn TempSensor<f64>>) -> IoBuilder {
+        self.io.temp_sensors.push(sensor);
+        self
+    }
+
+    pub fn counter(mut self, counter: Box<dyn CounterInput>) -> IoBuilder {
+        self.io.counter_input.push(counter);
+        self
+    }
+
+    pub fn pwm(mut self, pwm: Box<dyn PwmOutput>) -> IoBuilder {
+        self.io.pwm_outputs.push(pwm);
+        self
+    }
+
+    /// Set the offset of the relay outputs in the digital outputs (legacy information in
+    /// `IoHwInfo`)
+    pub fn relay_offset(mut self, offset: u8) -> IoBuilder {
+        self.io.relay_offset = Some(offset);
+        self
+    }
+
+    pub fn build(self) -> Io {
+        self.io
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi;
+
+    #[test]
+    fn build_channel_counts_test() {
+        let mut io = IoBuilder::new()
+            .output(Box::new(null::Output::not_implemented()))
+            .pad_outputs(4)
+            .input(Box::new(null::Input::always_active()))
+            .temp_sensor(Box::new(null::Temp::new()))
+            .build();
+
+        let mut hwinfo = ffi::IoHwInfo::default();
+        io.get_hardware_info(&mut hwinfo).unwrap();
+        assert_eq!(hwinfo.m_uDoChannels, 4);
+        assert_eq!(hwinfo.m_uDiChannels, 1);
+        assert_eq!(hwinfo.m_uTmpChannels, 1);
+        assert_eq!(hwinfo.m_uAiChannels, 0);
+
+        assert!(io.input_get(0).unwrap());
+        assert!(io.input_get(1).is_err());
+    }
+}
@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+use crate::error::{Error, Result};
+
+const NS_PER_SECOND: f64 = 1_000_000_000.0;
+
+/// Convert frequency (Hz) and duty cycle (percent) to period and duty cycle in nanoseconds
+pub fn from_frequency(frequency: f64, duty_cycle: f64) -> Result<(u64, u64)> {
+    let valid = frequency > 0.0 && frequency <= NS_PER_SECOND;
+    if !valid || !(0.0..=100.0).contains(&duty_cycle) {
+        return Err(Error::InvalidParameter);
+    }
+    let period = (NS_PER_SECOND / frequency).round();
+    let duty_cycle = (period * duty_cycle / 100.0).round();
+    Ok((period as u64, duty_cycle as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_frequency_test() {
+        assert_eq!(from_frequency(1000.0, 25.0).unwrap(), (1_000_000, 250_000));
+        assert_eq!(
+            from_frequency(3.0, 50.0).unwrap(),
+            (333_333_333, 166_666_667)
+        );
+        assert_eq!(
+            from_frequency(0.5, 100.0).unwrap(),
+            (2_000_000_000, 2_000_000_000)
+        );
+        assert_eq!(from_frequency(20_000.0, 0.0).unwrap(), (50_000, 0));
+        assert!(from_frequency(0.0, 50.0).is_err());
+        assert!(from_frequency(f64::NAN, 50.0).is_err());
+        assert!(from_frequency(1000.0, 100.1).is_err());
+    }
+}
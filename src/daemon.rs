@@ -0,0 +1,411 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// The I/O daemon samples slow peripherals (e.g. ADCs via iio) and provides the values to other
+// processes via shared memory. It is used by the `iodaemon` binary, but can also be run by
+// other binaries, which register own device definitions beforehand
+// (see `definition::register_device_definition_shm`).
+//
+// The daemon also owns GPIO lines, which can be requested by one process only (see `io::cdev`).
+// The digital outputs of the definition are set on behalf of the other processes and the digital
+// inputs are sampled like the other inputs.
+//
+// If started as systemd service (Type=notify), readiness is reported after the shared memory was
+// created and every worker provided its first sample. With WatchdogSec= the watchdog is only
+// pinged as long as every worker keeps providing samples.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use raw_sync::Timeout;
+
+use crate::health;
+use crate::hw_rev;
+use crate::scheduler;
+use crate::shm;
+use crate::signal;
+use crate::supervisor::{self, Supervisor};
+use crate::systemd;
+
+/// Configuration of the watchdog supervisor, it is not started if the file does not exist
+const SUPERVISOR_CONFIG: &str = "/etc/sysworxx-io/iodaemon.conf";
+
+#[derive(Debug)]
+enum ValueChanged {
+    Ain(usize, i64),
+    Temp(usize, f64),
+    Di(usize, bool),
+    /// All values of the worker with the given index were sent
+    Flush(usize),
+}
+
+pub fn run() {
+    systemd::init_logger("IO_LOG", "IO_LOG_STYLE");
+
+    println!("=======================================================");
+    println!("=                                                     =");
+    println!("=     SYS TEC electronic AG                           =");
+    println!("=     D-08468 Heinsdorfergrund, Am Windrad 2          =");
+    println!("=     www.systec-electronic.com                       =");
+    println!("=                                                     =");
+    println!(
+        "=     sysWORXX I/O daemon, Version {}.{}.{}              =",
+        env!("CARGO_PKG_VERSION_MAJOR").parse::<u8>().unwrap(),
+        env!("CARGO_PKG_VERSION_MINOR").parse::<u8>().unwrap(),
+        env!("CARGO_PKG_VERSION_PATCH").parse::<u8>().unwrap()
+    );
+    println!("=                                                     =");
+    println!("=     (c) 2025 SYS TEC electronic AG                  =");
+    println!("=                                                     =");
+    println!("=======================================================");
+
+    // the sampling threads are configured via IO_SCHED_PRIORITY and IO_SCHED_CPUS
+    if std::env::var_os("IO_SCHED_MLOCK").is_some() && scheduler::lock_memory().is_err() {
+        std::process::exit(1);
+    }
+
+    let device = hw_rev::get_device_name().unwrap_or("fallback".to_string());
+    let (mut io, mut mappings) = match crate::definition::load_device_definition_shm(&device) {
+        Some(e) => e,
+        None => {
+            eprintln!("Device does not need/support iodaemon!");
+            std::process::exit(0);
+        }
+    };
+
+    match io.init() {
+        Ok(()) => {}
+        Err(e) => {
+            error!(device = device.as_str(), error:% = e; "Failed to initialize: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let io = Arc::new(Mutex::new(io));
+
+    let mut shm_server = match shm::ShmServer::new() {
+        Ok(shared) => shared,
+        Err(e) => {
+            error!(error:% = e; "Failed to create/open shared memory: {}", e);
+            std::process::exit(1);
+        }
+    };
+    notify("STATUS=Waiting for first samples");
+
+    let _supervisor = match start_supervisor() {
+        Ok(supervisor) => supervisor,
+        Err(e) => {
+            error!(error:% = e; "Failed to start watchdog supervisor: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let (tx, rx) = crossbeam_channel::unbounded();
+
+    let count_adc: usize = mappings
+        .groups
+        .iter()
+        .filter_map(|group| match &group.channels {
+            shm::Channels::AnalogInput(channels) => Some(channels.len()),
+            _ => None,
+        })
+        .sum();
+
+    let count_temp: usize = mappings
+        .groups
+        .iter()
+        .filter_map(|group| match &group.channels {
+            shm::Channels::TempInput(channels) => Some(channels.len()),
+            _ => None,
+        })
+        .sum();
+
+    let count_di: usize = mappings
+        .groups
+        .iter()
+        .filter_map(|group| match &group.channels {
+            shm::Channels::DigitalInput(channels) => Some(channels.len()),
+            _ => None,
+        })
+        .sum();
+
+    // the outputs are not sampled, their state is published after changing them
+    let count_do = {
+        let mut io = io.lock().unwrap();
+        let count = io
+            .get_channel_info()
+            .outputs
+            .len()
+            .min(shm::NUM_CHANNELS_PER_TYPE);
+        let mut shm = shm_server.lock();
+        for i in 0..count {
+            if let Ok(value) = io.output_get(i) {
+                shm.digital_output_set(i, value);
+            }
+        }
+        count
+    };
+
+    let count_workers = mappings.groups.len();
+    let mut index = 0;
+    while !mappings.groups.is_empty() {
+        let mapping = mappings.groups.swap_remove(0);
+
+        let io = io.clone();
+        let tx = tx.clone();
+        let worker = index;
+
+        thread::Builder::new()
+            .name(format!("worker{}", index))
+            .spawn(move || loop {
+                let evt = mapping.notifier.recv();
+
+                {
+                    let mut io = io.lock().unwrap();
+
+                    match evt.unwrap() {
+                        shm::Event::Update => match &mapping.channels {
+                            shm::Channels::AnalogInput(cs) => {
+                                for index in cs {
+                                    io.analog_input_get(*index)
+                                        .map(|val| tx.send(ValueChanged::Ain(*index, val)).unwrap())
+                                        .ok();
+                                }
+
+                                tx.send(ValueChanged::Flush(worker)).unwrap();
+                            }
+                            shm::Channels::TempInput(cs) => {
+                                for index in cs {
+                                    io.tmp_input_get(*index)
+                                        .map(|val| {
+                                            tx.send(ValueChanged::Temp(*index, val)).unwrap()
+                                        })
+                                        .ok();
+                                }
+
+                                tx.send(ValueChanged::Flush(worker)).unwrap();
+                            }
+                            shm::Channels::DigitalInput(cs) => {
+                                for index in cs {
+                                    io.input_get(*index)
+                                        .map(|val| tx.send(ValueChanged::Di(*index, val)).unwrap())
+                                        .ok();
+                                }
+
+                                tx.send(ValueChanged::Flush(worker)).unwrap();
+                            }
+                        },
+                    }
+                }
+            })
+            .unwrap();
+
+        index += 1;
+    }
+
+    let signal_notifier = match signal::notify(&[signal::SIGINT, signal::SIGTERM]) {
+        Ok(notifier) => notifier,
+        Err(e) => {
+            eprintln!("Failed to create signal notifier: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // outputs set by other processes are applied with the check for new configurations
+    let config_check = crossbeam_channel::tick(std::time::Duration::from_millis(10));
+
+    // the service manager expects a ping twice per watchdog interval
+    let watchdog_interval = systemd::watchdog_interval();
+    let watchdog_tick = match watchdog_interval {
+        Some(interval) => crossbeam_channel::tick(interval / 2),
+        None => crossbeam_channel::never(),
+    };
+    let start = Instant::now();
+    let mut last_samples: Vec<Option<Instant>> = vec![None; count_workers];
+    let mut ready = false;
+
+    if count_workers == 0 {
+        notify_ready(0);
+        ready = true;
+    }
+
+    loop {
+        crossbeam_channel::select! {
+            recv(rx) -> change => {
+                match change.unwrap() {
+                    ValueChanged::Ain(channel, value) => {
+                        shm_server.lock().analog_value_set(channel, value)
+                    },
+                    ValueChanged::Temp(channel, value) => {
+                        shm_server.lock().temperature_value_set(channel, value)
+                    },
+                    ValueChanged::Di(channel, value) => {
+                        shm_server.lock().digital_input_set(channel, value)
+                    },
+                    ValueChanged::Flush(worker) => {
+                        shm_server.lock().health_set(&health::samplers());
+                        shm_server.emit_server_event().expect("emit values updated");
+
+                        last_samples[worker] = Some(Instant::now());
+                        if !ready && last_samples.iter().all(Option::is_some) {
+                            notify_ready(count_adc + count_temp + count_di);
+                            ready = true;
+                        }
+                    }
+                }
+            }
+
+            recv(config_check) -> _ => {
+                match shm_server.await_client_event(Timeout::Val(Duration::from_millis(0))) {
+                    Ok(_) => {
+                        debug!("--- Apply new configuration ---");
+
+                        for i in 0..count_adc {
+                            let mut shm = shm_server.lock();
+                            match shm.analog_cfg_get(i) {
+                                shm::Config::Keep => { /* nothing to change */ }
+                                shm::Config::Change(mode) => {
+                                    let mut io = io.lock().unwrap();
+
+                                    debug!("Set mode AIN{}: {:?}", i, mode);
+
+                                    match io.analog_mode_set(i, mode) {
+                                        Ok(()) => {}
+                                        Err(e) => {
+                                            error!(
+                                                channel = format!("AIN{}", i).as_str(),
+                                                error:% = e;
+                                                "Failed to change configuration of AIN{} to {:?}: {}",
+                                                i, mode, e
+                                            );
+                                        }
+                                    }
+                                }
+                            }
+
+                            shm.analog_cfg_set_confirm(i);
+                        }
+
+                        for i in 0..count_temp {
+                            let mut shm = shm_server.lock();
+                            match shm.temperature_cfg_get(i) {
+                                shm::Config::Keep => { /* nothing to change */ }
+                                shm::Config::Change(ref cfg) => {
+                                    let mut io = io.lock().unwrap();
+
+                                    debug!("Set mode TMP{}: {:?} / {:?}", i, cfg.0, cfg.1);
+
+                                    match io.tmp_set_mode(i, cfg.0, cfg.1) {
+                                        Ok(()) => {}
+                                        Err(e) => {
+                                            error!(
+                                                channel = format!("TMP{}", i).as_str(),
+                                                error:% = e;
+                                                "Failed to change configuration of TMP{} to {:?}: {}",
+                                                i, cfg, e
+                                            );
+                                        }
+                                    }
+                                }
+                            }
+
+                            shm.temperature_cfg_set_confirm(i);
+                        }
+
+                        for i in 0..count_do {
+                            let mut shm = shm_server.lock();
+                            match shm.digital_output_cfg_get(i) {
+                                shm::Config::Keep => { /* nothing to change */ }
+                                shm::Config::Change(value) => {
+                                    let mut io = io.lock().unwrap();
+
+                                    debug!("Set DO{}: {}", i, value);
+
+                                    match io.output_set(i, value) {
+                                        Ok(()) => shm.digital_output_set(i, value),
+                                        Err(e) => {
+                                            error!(
+                                                channel = format!("DO{}", i).as_str(),
+                                                error:% = e;
+                                                "Failed to set DO{} to {}: {}",
+                                                i, value, e
+                                            );
+                                        }
+                                    }
+                                }
+                            }
+
+                            shm.digital_output_cfg_set_confirm(i);
+                        }
+                    }
+                    Err(_) => {
+                        // FIXME: there is no way the differentiate between actual errors and a
+                        //        timeout currently
+                    }
+                }
+            }
+
+            recv(watchdog_tick) -> _ => {
+                let timeout = watchdog_interval.unwrap_or_default();
+                let stalled: Vec<usize> = last_samples
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, last)| last.unwrap_or(start).elapsed() > timeout)
+                    .map(|(worker, _)| worker)
+                    .collect();
+
+                if stalled.is_empty() {
+                    notify("WATCHDOG=1");
+                } else {
+                    warn!("No samples from worker {:?}, stop pinging the watchdog", stalled);
+                    notify(&format!("STATUS=No samples from worker {:?}", stalled));
+                }
+            }
+
+            recv(signal_notifier) -> signal => match signal {
+                Ok(signal) => {
+                    notify("STOPPING=1");
+                    info!("Exit due to signal: {}", signal);
+                    drop(shm_server);
+                    break;
+                }
+                Err(_) => {
+                    notify("STOPPING=1");
+                    info!("Exit due to error");
+                    drop(shm_server);
+                    break;
+                }
+            },
+        }
+    }
+}
+
+fn start_supervisor() -> crate::error::Result<Option<Supervisor>> {
+    let path = std::env::var("IO_WATCHDOG_CONFIG").unwrap_or(SUPERVISOR_CONFIG.to_string());
+    if !std::path::Path::new(&path).exists() {
+        return Ok(None);
+    }
+
+    let config = supervisor::Config::load(&path)?;
+    if !config.enable {
+        return Ok(None);
+    }
+    info!("Start watchdog supervisor on {}", config.socket);
+    Supervisor::start(config, supervisor::device_safe_state()).map(Some)
+}
+
+/// Notify the service manager, errors are only logged as the daemon works without it
+fn notify(state: &str) {
+    if let Err(e) = systemd::notify(state) {
+        warn!(error:% = e; "Failed to notify systemd ({}): {}", state, e);
+    }
+}
+
+fn notify_ready(count_channels: usize) {
+    info!("Ready, sampling {} channels", count_channels);
+    notify(&format!(
+        "READY=1\nSTATUS=Sampling {} channels",
+        count_channels
+    ));
+}
@@ -4,7 +4,7 @@
 use std::env;
 use std::time::Duration;
 
-use crate::io::{evdev, imx, null, sensors, sysfs};
+use crate::io::{evdev, imx, null, sensors, sysfs, wdg_dev};
 use crate::labeled::Labeled;
 use crate::Io;
 
@@ -32,7 +32,10 @@ pub fn definition() -> Io {
     let mut digi_inputs = evdev::EvdevCollector::from_name("user_input").unwrap();
 
     Io {
-        watchdog: Box::new(Labeled::new("Watchdog", null::Wdg::new())),
+        watchdog: Box::new(Labeled::new(
+            "Watchdog",
+            wdg_dev::Wdg::new("/dev/watchdog0"),
+        )),
         run_led: Box::new(Labeled::new("Run_LED", sysfs::Do::new(73))),
         err_led: Box::new(Labeled::new("Error_LED", sysfs::Do::new(83))),
         run_switch: Box::new(Labeled::new(
@@ -155,5 +158,6 @@ pub fn definition() -> Io {
             Box::new(sysfs::Pwm::new(0, 0)),
             Box::new(sysfs::Pwm::new(1, 0)),
         ],
+        providers: vec![],
     }
 }
@@ -5,7 +5,7 @@ use std::time::Duration;
 
 use crate::io::am62x;
 use crate::io::lookup::{LogicLevel, Lookup};
-use crate::io::{evdev, null, sensors, sysfs};
+use crate::io::{evdev, null, sensors, sysfs, wdg_dev};
 use crate::labeled::Labeled;
 use crate::Io;
 
@@ -27,7 +27,7 @@ pub fn definition() -> Io {
     let mut digi_inputs = evdev::EvdevCollector::from_name("gpio_input").unwrap();
 
     Io {
-        watchdog: Box::new(null::Wdg::new()),
+        watchdog: Box::new(wdg_dev::Wdg::new("/dev/watchdog0")),
         run_led: lookup.gpio_do("Run_LED", "600000.gpio", 5),
         err_led: lookup.gpio_do("Error_LED", "600000.gpio", 6),
         run_switch: Box::new(Labeled::new(
@@ -150,5 +150,6 @@ pub fn definition() -> Io {
             Box::new(sysfs::Pwm::new(0, 0)), // chip, channel
             Box::new(sysfs::Pwm::new(2, 0)), // chip, channel
         ],
+        providers: vec![],
     }
 }
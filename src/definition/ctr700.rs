@@ -8,7 +8,7 @@ use ini::Ini;
 
 use crate::hw_rev;
 use crate::io::shm as shmio;
-use crate::io::{evdev, iio, imx, null, sensors, sysfs, util};
+use crate::io::{evdev, iio, imx, null, sensors, sysfs, util, wdg_dev};
 use crate::labeled::Labeled;
 use crate::shm;
 use crate::Io;
@@ -54,7 +54,10 @@ pub fn definition() -> Io {
     };
 
     Io {
-        watchdog: Box::new(Labeled::new("Watchdog", null::Wdg::new())),
+        watchdog: Box::new(Labeled::new(
+            "Watchdog",
+            wdg_dev::Wdg::new("/dev/watchdog0"),
+        )),
         run_led: Box::new(Labeled::new("Run_LED", sysfs::Do::new(73))),
         err_led: Box::new(Labeled::new("Error_LED", sysfs::Do::new(83))),
         run_switch: Box::new(Labeled::new(
@@ -218,6 +221,7 @@ pub fn definition() -> Io {
             Box::new(sysfs::Pwm::new(0, 0)),
             Box::new(sysfs::Pwm::new(1, 0)),
         ],
+        providers: vec![],
     }
 }
 
@@ -261,6 +265,7 @@ pub fn definition_shm() -> (Io, shm::Mappings) {
         counter_input: vec![],
         relay_offset: None,
         pwm_outputs: vec![],
+        providers: vec![],
     };
 
     let shm_mapping = shm::Mappings {
@@ -8,7 +8,7 @@ use ini::Ini;
 
 use crate::convert::rtd;
 use crate::io::shm as shmio;
-use crate::io::{evdev, iio, imx, led, null, sensors, sysfs, util};
+use crate::io::{evdev, iio, imx, led, null, sensors, sysfs, util, wdg_dev};
 use crate::labeled::Labeled;
 use crate::shm;
 use crate::Io;
@@ -46,7 +46,7 @@ pub fn definition() -> Io {
     let shm_sampler = shmio::Sampler::new();
 
     Io {
-        watchdog: Box::new(null::Wdg::new()),
+        watchdog: Box::new(wdg_dev::Wdg::new("/dev/watchdog0")),
         run_led: Box::new(led::Led::new("RUN")),
         err_led: Box::new(led::Led::new("ERROR")),
         run_switch: Box::new(null::Input::always_active()),
@@ -265,6 +265,7 @@ pub fn definition() -> Io {
         ],
         relay_offset: Some(0),
         pwm_outputs: vec![],
+        providers: vec![],
     }
 }
 
@@ -388,6 +389,7 @@ pub fn definition_shm() -> (Io, shm::Mappings) {
         counter_input: vec![],
         relay_offset: None,
         pwm_outputs: vec![],
+        providers: vec![],
     };
 
     let shm_mapping = shm::Mappings {
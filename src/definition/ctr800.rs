@@ -5,15 +5,65 @@ use std::time::Duration;
 
 use ini::Ini;
 
-use crate::io::lookup::{LogicLevel, Lookup};
 use crate::io::{am62x, shm as shmio};
-use crate::io::{evdev, iio, null, sensors, sysfs, util};
+use crate::io::{cdev, evdev, iio, null, sensors, sysfs, util, wdg_dev};
 use crate::labeled::Labeled;
 use crate::shm;
-use crate::Io;
+use crate::{DigitalInput, DigitalOutput, Io};
 
 const CNT0_PATH: &str = "/sys/bus/counter/devices/counter0/count0/";
 
+// The GPIO lines are owned by iodaemon, as other processes (e.g. the REST or Modbus daemon) use
+// them too. The index in the tables is the index in the shared memory.
+
+/// Label, chip and offset of the GPIO outputs
+const GPIO_OUTPUTS: [(&str, &str, u32); 18] = [
+    ("Run_LED", "600000.gpio", 5),
+    ("Error_LED", "600000.gpio", 6),
+    // 2
+    ("DO0", "4201000.gpio", 2),
+    ("DO1", "4201000.gpio", 3),
+    ("DO2", "600000.gpio", 51),
+    ("DO3", "600000.gpio", 52),
+    ("DO4", "600000.gpio", 57),
+    ("DO5", "600000.gpio", 58),
+    ("DO6", "600000.gpio", 59),
+    ("DO7", "600000.gpio", 60),
+    ("DO8", "600000.gpio", 61),
+    ("DO9", "600000.gpio", 17),
+    ("DO10", "600000.gpio", 1),
+    ("DO11", "600000.gpio", 26),
+    ("DO12", "600000.gpio", 19),
+    ("DO13", "600000.gpio", 20),
+    // 16
+    ("Relay0", "600000.gpio", 3),
+    ("Relay1", "600000.gpio", 4),
+];
+
+/// Label, chip, offset and active low flag of the GPIO inputs
+const GPIO_INPUTS: [(&str, &str, u32, bool); 6] = [
+    ("Config_Switch", "600000.gpio", 62, true),
+    ("PF", "600000.gpio", 43, false),
+    ("DI_ERR", "1-003a", 2, false),
+    ("USB_OC", "1-003a", 3, false),
+    ("DO_PF", "1-003a", 0, false),
+    ("DO_DIAG", "1-003a", 1, false),
+];
+
+fn gpio_do(sampler: &shmio::Sampler, index: usize) -> Box<dyn DigitalOutput> {
+    Box::new(Labeled::new(
+        GPIO_OUTPUTS[index].0,
+        shmio::Do::new(sampler, index),
+    ))
+}
+
+fn gpio_di(sampler: &shmio::Sampler, index: usize) -> Box<dyn DigitalInput> {
+    Box::new(Labeled::new(
+        GPIO_INPUTS[index].0,
+        shmio::Di::new(sampler, index),
+    ))
+}
+
 pub fn definition() -> Io {
     let tmp0 = Box::new(Labeled::new(
         "CPU",
@@ -25,42 +75,40 @@ pub fn definition() -> Io {
         sensors::LmSensor::new("lm75-i2c-1-48", Duration::from_millis(2000)),
     ));
 
-    let lookup: Lookup = Lookup::new();
-
     let mut digi_inputs = evdev::EvdevCollector::from_name("gpio_input").unwrap();
 
     let shm_sampler = shmio::Sampler::new();
 
     Io {
-        watchdog: Box::new(null::Wdg::new()),
-        run_led: lookup.gpio_do("Run_LED", "600000.gpio", 5),
-        err_led: lookup.gpio_do("Error_LED", "600000.gpio", 6),
+        watchdog: Box::new(wdg_dev::Wdg::new("/dev/watchdog0")),
+        run_led: gpio_do(&shm_sampler, 0),
+        err_led: gpio_do(&shm_sampler, 1),
         run_switch: Box::new(Labeled::new(
             "Run_Switch",
             evdev::Di::active_low(&mut digi_inputs, evdev::KeyCode::KEY_1),
         )),
-        config_switch: lookup.gpio_di("Config_Switch", "600000.gpio", 62, LogicLevel::ActiveLow),
+        config_switch: gpio_di(&shm_sampler, 0),
         outputs: vec![
-            lookup.gpio_do("DO0", "4201000.gpio", 2),
-            lookup.gpio_do("DO1", "4201000.gpio", 3),
-            lookup.gpio_do("DO2", "600000.gpio", 51),
-            lookup.gpio_do("DO3", "600000.gpio", 52),
-            lookup.gpio_do("DO4", "600000.gpio", 57),
-            lookup.gpio_do("DO5", "600000.gpio", 58),
-            lookup.gpio_do("DO6", "600000.gpio", 59),
-            lookup.gpio_do("DO7", "600000.gpio", 60),
-            lookup.gpio_do("DO8", "600000.gpio", 61),
-            lookup.gpio_do("DO9", "600000.gpio", 17),
-            lookup.gpio_do("DO10", "600000.gpio", 1),
-            lookup.gpio_do("DO11", "600000.gpio", 26),
-            lookup.gpio_do("DO12", "600000.gpio", 19),
-            lookup.gpio_do("DO13", "600000.gpio", 20),
+            gpio_do(&shm_sampler, 2),
+            gpio_do(&shm_sampler, 3),
+            gpio_do(&shm_sampler, 4),
+            gpio_do(&shm_sampler, 5),
+            gpio_do(&shm_sampler, 6),
+            gpio_do(&shm_sampler, 7),
+            gpio_do(&shm_sampler, 8),
+            gpio_do(&shm_sampler, 9),
+            gpio_do(&shm_sampler, 10),
+            gpio_do(&shm_sampler, 11),
+            gpio_do(&shm_sampler, 12),
+            gpio_do(&shm_sampler, 13),
+            gpio_do(&shm_sampler, 14),
+            gpio_do(&shm_sampler, 15),
             // 14
             Box::new(Labeled::new("DO14", sysfs::Pwm::new(0, 0))), // chip 0, channel 0
             Box::new(Labeled::new("DO15", sysfs::Pwm::new(2, 0))), // chip 2, channel 0
             // 16
-            lookup.gpio_do("Relay0", "600000.gpio", 3),
-            lookup.gpio_do("Relay1", "600000.gpio", 4),
+            gpio_do(&shm_sampler, 16),
+            gpio_do(&shm_sampler, 17),
             // 18
             Box::new(null::Output::not_implemented()),
             Box::new(null::Output::not_implemented()),
@@ -167,11 +215,11 @@ pub fn definition() -> Io {
             Box::new(null::Input::not_implemented()),
             Box::new(null::Input::not_implemented()),
             // 32
-            lookup.gpio_di("PF", "600000.gpio", 43, LogicLevel::ActiveHigh),
-            lookup.gpio_di("DI_ERR", "1-003a", 2, LogicLevel::ActiveHigh),
-            lookup.gpio_di("USB_OC", "1-003a", 3, LogicLevel::ActiveHigh),
-            lookup.gpio_di("DO_PF", "1-003a", 0, LogicLevel::ActiveHigh),
-            lookup.gpio_di("DO_DIAG", "1-003a", 1, LogicLevel::ActiveHigh),
+            gpio_di(&shm_sampler, 1),
+            gpio_di(&shm_sampler, 2),
+            gpio_di(&shm_sampler, 3),
+            gpio_di(&shm_sampler, 4),
+            gpio_di(&shm_sampler, 5),
             Box::new(null::Input::not_implemented()), // EXT_FAIL?
             Box::new(Labeled::new(
                 "RUN",
@@ -196,6 +244,7 @@ pub fn definition() -> Io {
             Box::new(sysfs::Pwm::new(0, 0)), // chip, channel
             Box::new(sysfs::Pwm::new(2, 0)), // chip, channel
         ],
+        providers: vec![],
     }
 }
 
@@ -205,17 +254,16 @@ pub fn definition_shm() -> (Io, shm::Mappings) {
     let adc_sampler: iio::Sampler<i64> =
         iio::Sampler::new("iio:device0", Duration::from_millis(100));
     let notifier_adc = adc_sampler.get_notifier();
-    let lookup: Lookup = Lookup::new();
-
+    let notifier_gpio = shm::ticker("gpio", Duration::from_millis(10));
     let adc_channel = |index, gpio_offset, gpio_chip, calib_section| {
-        let (do_v, do_i) = lookup.gpio_pair_adc(gpio_chip, gpio_offset);
         util::AiIniCalib::new_shift(
             &adc_calib,
             calib_section,
             util::AiSwitch::new(
                 iio::Ai::new(&adc_sampler, index),
-                sysfs::Do::new(do_v),
-                sysfs::Do::new(do_i),
+                cdev::Do::new(gpio_chip, gpio_offset),
+                // the line for current is 4 lines higher
+                cdev::Do::new(gpio_chip, gpio_offset + 4),
             ),
             shifter,
         )
@@ -227,8 +275,24 @@ pub fn definition_shm() -> (Io, shm::Mappings) {
         err_led: Box::new(null::Output::not_implemented()),
         run_switch: Box::new(null::Input::not_implemented()),
         config_switch: Box::new(null::Input::not_implemented()),
-        outputs: vec![],
-        inputs: vec![],
+        outputs: GPIO_OUTPUTS
+            .iter()
+            .map(|&(label, chip, offset)| {
+                Box::new(Labeled::new(label, cdev::Do::new(chip, offset))) as Box<dyn DigitalOutput>
+            })
+            .collect(),
+        inputs: GPIO_INPUTS
+            .iter()
+            .map(|&(label, chip, offset, active_low)| {
+                let input = cdev::Di::new(chip, offset);
+                let input = if active_low {
+                    input.active_low()
+                } else {
+                    input
+                };
+                Box::new(Labeled::new(label, input)) as Box<dyn DigitalInput>
+            })
+            .collect(),
         analog_inputs: vec![
             Box::new(adc_channel(1, 0, "1-0038", "AIN0")),
             Box::new(adc_channel(4, 1, "1-0038", "AIN1")),
@@ -240,13 +304,20 @@ pub fn definition_shm() -> (Io, shm::Mappings) {
         counter_input: vec![],
         relay_offset: None,
         pwm_outputs: vec![],
+        providers: vec![],
     };
 
     let shm_mapping = shm::Mappings {
-        groups: vec![shm::Group {
-            notifier: notifier_adc,
-            channels: shm::Channels::AnalogInput(vec![0, 1, 2, 3]),
-        }],
+        groups: vec![
+            shm::Group {
+                notifier: notifier_adc,
+                channels: shm::Channels::AnalogInput(vec![0, 1, 2, 3]),
+            },
+            shm::Group {
+                notifier: notifier_gpio,
+                channels: shm::Channels::DigitalInput((0..GPIO_INPUTS.len()).collect()),
+            },
+        ],
     };
 
     (io, shm_mapping)
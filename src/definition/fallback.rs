@@ -105,6 +105,7 @@ pub fn definition() -> Io {
         counter_input: vec![],
         relay_offset: None,
         pwm_outputs: vec![],
+        providers: vec![],
     }
 }
 
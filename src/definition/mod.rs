@@ -8,13 +8,50 @@ mod ctr750;
 mod ctr800;
 mod fallback;
 mod pi;
+mod sim;
 
 use crate::shm;
 use crate::Io;
 
 use std::collections::HashMap;
+use std::sync::Mutex;
+
+type Definition = fn() -> Io;
+type DefinitionShm = fn() -> (Io, shm::Mappings);
+
+lazy_static! {
+    static ref CUSTOM_DEFINITIONS: Mutex<HashMap<String, Definition>> = Mutex::new(HashMap::new());
+    static ref CUSTOM_DEFINITIONS_SHM: Mutex<HashMap<String, DefinitionShm>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Register a device definition (e.g. built with `builder::IoBuilder`) under the given device
+/// name. Registered definitions take precedence over the built-in ones.
+///
+/// The C API instance is created on first use, so the registration has to be done before
+/// calling `IoInit`.
+pub fn register_device_definition(device: &str, definition: Definition) {
+    CUSTOM_DEFINITIONS
+        .lock()
+        .unwrap()
+        .insert(device.to_string(), definition);
+}
+
+/// Register a device definition for the I/O daemon (see `daemon::run`) under the given device
+/// name.
+pub fn register_device_definition_shm(device: &str, definition: DefinitionShm) {
+    CUSTOM_DEFINITIONS_SHM
+        .lock()
+        .unwrap()
+        .insert(device.to_string(), definition);
+}
 
 pub fn load_device_definition(device: &str) -> Io {
+    let custom = CUSTOM_DEFINITIONS.lock().unwrap().get(device).copied();
+    if let Some(entry) = custom {
+        return entry();
+    }
+
     let mut definitions_map = HashMap::from([
         ("ctr500", ctr500::definition as fn() -> Io),
         ("ctr600", ctr600::definition as fn() -> Io),
@@ -22,6 +59,7 @@ pub fn load_device_definition(device: &str) -> Io {
         ("ctr750", ctr750::definition as fn() -> Io),
         ("ctr800", ctr800::definition as fn() -> Io),
         ("pi", pi::definition as fn() -> Io),
+        ("sim", sim::definition as fn() -> Io),
     ]);
 
     match definitions_map.remove(device) {
@@ -31,6 +69,11 @@ pub fn load_device_definition(device: &str) -> Io {
 }
 
 pub fn load_device_definition_shm(device: &str) -> Option<(Io, shm::Mappings)> {
+    let custom = CUSTOM_DEFINITIONS_SHM.lock().unwrap().get(device).copied();
+    if let Some(entry) = custom {
+        return Some(entry());
+    }
+
     let mut definitions_map = HashMap::from([
         (
             "ctr700",
@@ -44,6 +87,7 @@ pub fn load_device_definition_shm(device: &str) -> Option<(Io, shm::Mappings)> {
             "ctr800",
             ctr800::definition_shm as fn() -> (Io, shm::Mappings),
         ),
+        ("pi", pi::definition_shm as fn() -> (Io, shm::Mappings)),
     ]);
 
     match definitions_map.remove(device) {
@@ -5,9 +5,9 @@ use std::collections::HashMap;
 use std::time::Duration;
 
 use crate::io;
-use crate::io::lookup::Lookup;
-use crate::io::{null, sensors};
+use crate::io::{cdev, null, sensors, shm as shmio, wdg_dev};
 use crate::labeled::Labeled;
+use crate::shm;
 use crate::{DigitalInput, DigitalOutput, Io};
 
 use evdev;
@@ -100,15 +100,22 @@ fn build_sysworxx_pi_inputs() -> Vec<Box<dyn DigitalInput>> {
     inputs
 }
 
-/// Build outputs for RGB-LED and all gpio-chips ('gpio-aggregator') with name 'sysworxx-' with
-/// stable order.
-fn build_sysworxx_pi_outputs() -> Vec<Box<dyn DigitalOutput>> {
-    // TODO: simplify this when transitioning to character devices
-    let lookup: Lookup = Lookup::new();
-    let mut outputs = vec![
-        lookup.gpio_do("LED_BL", "600000.gpio", 11) as Box<dyn DigitalOutput>,
-        lookup.gpio_do("LED_RD", "600000.gpio", 12) as Box<dyn DigitalOutput>,
-        lookup.gpio_do("LED_GN", "600000.gpio", 14) as Box<dyn DigitalOutput>,
+/// Lines of the RGB-LED and all gpio-chips ('gpio-aggregator') with name 'sysworxx-' with stable
+/// order. The lines are owned by iodaemon, the index is the index in the shared memory.
+fn sysworxx_pi_output_lines() -> Vec<(&'static str, cdev::LineRef)> {
+    let mut lines = vec![
+        (
+            "LED_BL",
+            cdev::LineRef::Offset("600000.gpio".to_string(), 11),
+        ),
+        (
+            "LED_RD",
+            cdev::LineRef::Offset("600000.gpio".to_string(), 12),
+        ),
+        (
+            "LED_GN",
+            cdev::LineRef::Offset("600000.gpio".to_string(), 14),
+        ),
     ];
 
     let mut chips: Vec<_> = gpio_cdev::chips()
@@ -118,29 +125,22 @@ fn build_sysworxx_pi_outputs() -> Vec<Box<dyn DigitalOutput>> {
         .collect();
     chips.sort_by_key(|c| c.label().to_string());
 
-    let lines: Vec<(String, Vec<String>)> = chips
-        .into_iter()
-        .map(|c| {
-            let lines = c
-                .lines()
-                .filter_map(|l| l.info().ok())
-                .filter_map(|l_info| l_info.name().map(ToString::to_string))
-                .collect::<Vec<_>>();
-            (c.label().to_string(), lines)
-        })
-        .collect();
-
-    for (chip_name, line_names) in lines.into_iter() {
-        for (i, line_name) in line_names.into_iter().enumerate() {
-            outputs.push(lookup.gpio_do(
-                String::leak(line_name),
-                String::leak(chip_name.clone()),
-                i,
-            ));
+    for chip in chips {
+        for line in chip.lines() {
+            let name = line
+                .info()
+                .ok()
+                .and_then(|info| info.name().map(ToString::to_string));
+            if let Some(name) = name {
+                lines.push((
+                    String::leak(name),
+                    cdev::LineRef::Offset(chip.label().to_string(), line.offset()),
+                ));
+            }
         }
     }
 
-    outputs
+    lines
 }
 
 pub fn definition() -> Io {
@@ -149,13 +149,23 @@ pub fn definition() -> Io {
         sensors::LmSensor::new("main1_thermal-virtual-0", Duration::from_millis(2000)),
     ));
 
+    let shm_sampler = shmio::Sampler::new();
+    let outputs = sysworxx_pi_output_lines()
+        .into_iter()
+        .enumerate()
+        .map(|(index, (label, _))| {
+            Box::new(Labeled::new(label, shmio::Do::new(&shm_sampler, index)))
+                as Box<dyn DigitalOutput>
+        })
+        .collect();
+
     Io {
-        watchdog: Box::new(null::Wdg::new()),
+        watchdog: Box::new(wdg_dev::Wdg::new("/dev/watchdog0")),
         run_led: Box::new(null::Output::not_implemented()),
         err_led: Box::new(null::Output::not_implemented()),
         run_switch: Box::new(null::Input::not_implemented()),
         config_switch: Box::new(null::Input::not_implemented()),
-        outputs: build_sysworxx_pi_outputs(),
+        outputs,
         inputs: build_sysworxx_pi_inputs(),
         analog_inputs: vec![],
         analog_outputs: vec![],
@@ -163,5 +173,34 @@ pub fn definition() -> Io {
         counter_input: vec![],
         relay_offset: None,
         pwm_outputs: vec![],
+        providers: vec![],
     }
 }
+
+pub fn definition_shm() -> (Io, shm::Mappings) {
+    let outputs = sysworxx_pi_output_lines()
+        .into_iter()
+        .map(|(label, line)| {
+            Box::new(Labeled::new(label, cdev::Do::from_line(line))) as Box<dyn DigitalOutput>
+        })
+        .collect();
+
+    let io = Io {
+        watchdog: Box::new(null::Wdg::new()),
+        run_led: Box::new(null::Output::not_implemented()),
+        err_led: Box::new(null::Output::not_implemented()),
+        run_switch: Box::new(null::Input::not_implemented()),
+        config_switch: Box::new(null::Input::not_implemented()),
+        outputs,
+        inputs: vec![],
+        analog_inputs: vec![],
+        analog_outputs: vec![],
+        temp_sensors: vec![],
+        counter_input: vec![],
+        relay_offset: None,
+        pwm_outputs: vec![],
+        providers: vec![],
+    };
+
+    (io, shm::Mappings { groups: vec![] })
+}
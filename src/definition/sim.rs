@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Simulated device for testing applications and services on a PC (`SYSWORXX_IO_DEVICE=sim`).
+// Digital outputs are looped back to the digital inputs, analog outputs to the analog inputs.
+// The LEDs use two additional simulated outputs, the run switch is always active. PWM outputs
+// only record their configuration.
+
+use crate::builder::IoBuilder;
+use crate::io::null;
+use crate::io::sim::Simulator;
+use crate::labeled::Labeled;
+use crate::Io;
+
+const DIGITAL_CHANNELS: usize = 16;
+const ANALOG_CHANNELS: usize = 4;
+const TEMP_CHANNELS: usize = 2;
+const PWM_CHANNELS: usize = 2;
+
+pub fn definition() -> Io {
+    let sim = Simulator::new("sim", DIGITAL_CHANNELS + 2, ANALOG_CHANNELS, TEMP_CHANNELS)
+        .with_pwm_outputs(PWM_CHANNELS);
+    let mut builder = IoBuilder::new()
+        .run_led(Box::new(Labeled::new(
+            "Run_LED",
+            sim.output(DIGITAL_CHANNELS),
+        )))
+        .err_led(Box::new(Labeled::new(
+            "Error_LED",
+            sim.output(DIGITAL_CHANNELS + 1),
+        )))
+        .run_switch(Box::new(Labeled::new(
+            "Run_Switch",
+            null::Input::always_active(),
+        )));
+
+    for i in 0..DIGITAL_CHANNELS {
+        builder = builder
+            .output(Box::new(Labeled::new(
+                String::leak(format!("DO{}", i)),
+                sim.output(i),
+            )))
+            .input(Box::new(Labeled::new(
+                String::leak(format!("DI{}", i)),
+                sim.input(i),
+            )));
+    }
+
+    for i in 0..ANALOG_CHANNELS {
+        builder = builder
+            .analog_input(Box::new(Labeled::new(
+                String::leak(format!("AI{}", i)),
+                sim.analog_input(i),
+            )))
+            .analog_output(Box::new(Labeled::new(
+                String::leak(format!("AO{}", i)),
+                sim.analog_output(i),
+            )));
+    }
+
+    for i in 0..TEMP_CHANNELS {
+        builder = builder.temp_sensor(Box::new(Labeled::new(
+            String::leak(format!("TMP{}", i)),
+            sim.temp_sensor(i),
+        )));
+    }
+
+    for i in 0..PWM_CHANNELS {
+        builder = builder.pwm(Box::new(Labeled::new(
+            String::leak(format!("PWM{}", i)),
+            sim.pwm(i),
+        )));
+    }
+
+    builder.build()
+}
@@ -14,6 +14,8 @@ pub enum Error {
     InvalidParameter,
     NotImplemented,
     WatchdogTimeout,
+    InvalidDelta,
+    PtoParamTabFull,
     AccessFailed(std::io::Error),
     ParseIntError,
     GenericError,
@@ -26,6 +28,8 @@ impl fmt::Display for Error {
             Error::InvalidParameter => write!(f, "Invalid parameter specified"),
             Error::NotImplemented => write!(f, "Functionality is not implemented"),
             Error::WatchdogTimeout => write!(f, "Watchdog timed out"),
+            Error::InvalidDelta => write!(f, "Invalid delta specified"),
+            Error::PtoParamTabFull => write!(f, "PTO parameter table is full"),
             Error::AccessFailed(_) => write!(f, "Failed to access device"),
             Error::ParseIntError => write!(f, "Failed to convert number"),
             Error::GenericError => write!(f, "Generic internal error"),
@@ -40,6 +44,8 @@ impl error::Error for Error {
             Error::InvalidParameter => None,
             Error::NotImplemented => None,
             Error::WatchdogTimeout => None,
+            Error::InvalidDelta => None,
+            Error::PtoParamTabFull => None,
             Error::AccessFailed(ref err) => Some(err),
             Error::ParseIntError => None,
             Error::GenericError => None,
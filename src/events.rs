@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Broadcast of the edges of digital inputs within a process. An input supports a single callback,
+// so the services (e.g. HTTP server, MQTT bridge, rule engine) register the broadcast for their
+// inputs and subscribe to it, instead of replacing each other's callback.
+
+use std::sync::Mutex;
+
+use crossbeam_channel::{Sender, TrySendError};
+
+use crate::error::Result;
+use crate::{ffi, Io};
+
+/// Input channel and new value
+pub type InputEvent = (u8, bool);
+
+lazy_static! {
+    static ref SUBSCRIBERS: Mutex<Vec<Sender<InputEvent>>> = Mutex::new(vec![]);
+}
+
+extern "C" fn input_callback(channel: u8, value: ffi::IoBool) {
+    if let Ok(mut subscribers) = SUBSCRIBERS.lock() {
+        subscribers.retain(|tx| {
+            !matches!(
+                tx.try_send((channel, *value)),
+                Err(TrySendError::Disconnected(_))
+            )
+        });
+    }
+}
+
+/// Broadcast the edges of an input, fails if the input does not support callbacks
+pub fn register(io: &mut Io, channel: usize) -> Result<()> {
+    io.input_register_callback(channel, Some(input_callback), ffi::IoInputTrigger::BothEdge)
+}
+
+/// Send the edges of all registered inputs to `tx` until its receiver is dropped. Events are
+/// discarded while a bounded channel is full.
+pub fn subscribe(tx: Sender<InputEvent>) {
+    SUBSCRIBERS.lock().unwrap().push(tx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+    use std::time::Duration;
+
+    #[test]
+    fn broadcast_test() {
+        let sim = Simulator::new("SIM", 1, 0, 0);
+        let mut io = IoBuilder::new().input(Box::new(sim.input(0))).build();
+        io.init().unwrap();
+        register(&mut io, 0).unwrap();
+
+        let (tx0, rx0) = crossbeam_channel::unbounded();
+        let (tx1, rx1) = crossbeam_channel::bounded(1);
+        subscribe(tx0);
+        subscribe(tx1);
+
+        // other tests may broadcast edges of their inputs as well
+        let received = |rx: &crossbeam_channel::Receiver<InputEvent>, event| {
+            while let Ok(received) = rx.recv_timeout(Duration::from_secs(1)) {
+                if received == event {
+                    return true;
+                }
+            }
+            false
+        };
+
+        sim.set_input(0, true).unwrap();
+        assert!(received(&rx0, (0, true)));
+        assert!(received(&rx1, (0, true)));
+
+        drop(rx1);
+        sim.set_input(0, false).unwrap();
+        assert!(received(&rx0, (0, false)));
+    }
+}
@@ -5,10 +5,18 @@
 
 // This provides the Foreign Function Interface (FFI) for the C API.
 
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
 
+use crate::axis::{self, Axis};
 use crate::error::{Error, Result};
 use crate::hw_rev;
+use crate::pid::{self, PidLoop};
+use crate::pto::{self, Pto};
+use crate::supervisor::WatchdogClient;
 use crate::Io;
 
 lazy_static! {
@@ -16,6 +24,10 @@ lazy_static! {
         Arc::new(Mutex::new(crate::definition::load_device_definition(
             &hw_rev::get_device_name().unwrap_or("fallback".to_string())
         )));
+    static ref PID_LOOPS: Mutex<BTreeMap<u8, PidLoop>> = Mutex::new(BTreeMap::new());
+    static ref PTO_CHANNELS: Mutex<BTreeMap<u8, Pto>> = Mutex::new(BTreeMap::new());
+    static ref AXES: Mutex<BTreeMap<u8, Axis>> = Mutex::new(BTreeMap::new());
+    static ref WATCHDOG_CLIENT: Mutex<Option<WatchdogClient>> = Mutex::new(None);
 }
 
 #[repr(u32)]
@@ -63,6 +75,8 @@ impl<T> From<Result<T>> for IoResult {
             Err(Error::InvalidParameter) => IoResult::InvalidParameter,
             Err(Error::NotImplemented) => IoResult::NotImplemented,
             Err(Error::WatchdogTimeout) => IoResult::WatchdogTimeout,
+            Err(Error::InvalidDelta) => IoResult::InvalidDelta,
+            Err(Error::PtoParamTabFull) => IoResult::PtoParamTabFull,
             Err(Error::AccessFailed(_)) => IoResult::DevAccessFailed,
             Err(Error::ParseIntError) => IoResult::Error,
             Err(Error::GenericError) => IoResult::Error,
@@ -70,6 +84,23 @@ impl<T> From<Result<T>> for IoResult {
     }
 }
 
+impl IoResult {
+    /// Convert a plain C style error code to the Result type (e.g. for results of plugins)
+    pub fn into_result(self) -> Result<()> {
+        match self {
+            IoResult::Success => Ok(()),
+            IoResult::InvalidChannel => Err(Error::InvalidChannel),
+            IoResult::InvalidParameter => Err(Error::InvalidParameter),
+            IoResult::NotImplemented => Err(Error::NotImplemented),
+            IoResult::WatchdogTimeout => Err(Error::WatchdogTimeout),
+            IoResult::InvalidDelta => Err(Error::InvalidDelta),
+            IoResult::PtoParamTabFull => Err(Error::PtoParamTabFull),
+            IoResult::DevAccessFailed => Err(Error::generic_access_error()),
+            _ => Err(Error::GenericError),
+        }
+    }
+}
+
 /// @brief Hardware information structure
 ///
 /// This structure will be filled by IoGetHardwareInfo. It contains the
@@ -160,9 +191,24 @@ pub enum IoInputTrigger {
     BothEdge = 3,
 }
 
+impl IoInputTrigger {
+    /// Check if a callback has to be called for the new value of an input
+    pub fn matches(self, new_value: bool) -> bool {
+        match self {
+            IoInputTrigger::None => false,
+            IoInputTrigger::RisingEdge => new_value,
+            IoInputTrigger::FallingEdge => !new_value,
+            IoInputTrigger::BothEdge => true,
+        }
+    }
+}
+
 /// Callback function for changes on digital inputs
 pub type IoInputCallback = Option<extern "C" fn(u8, IoBool)>;
 
+/// Callback function for the completion of a pulse train output, called with the channel
+pub type IoPtoCallback = Option<extern "C" fn(u8)>;
+
 /// @brief Analog channel mode type
 #[repr(u8)]
 #[derive(Debug, Copy, Clone)]
@@ -218,6 +264,55 @@ pub enum IoCntDirection {
     Down = 1,
 }
 
+/// @brief Events of counter inputs, the bits of the event mask for IoCntRegisterCallback
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum IoCntEvent {
+    /// The counter reached or passed the compare value
+    Compare = 0x01,
+    /// The counter passed its maximum value (the compare value with auto-reload)
+    Overflow = 0x02,
+    /// The counter passed its minimum value (the preload value with auto-reload)
+    Underflow = 0x04,
+    /// The direction of counting changed
+    Direction = 0x08,
+}
+
+impl IoCntEvent {
+    pub const ALL: [IoCntEvent; 4] = [
+        IoCntEvent::Compare,
+        IoCntEvent::Overflow,
+        IoCntEvent::Underflow,
+        IoCntEvent::Direction,
+    ];
+}
+
+/// @brief Handling of the index pulse (Z) of a counter
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum IoCntIndexMode {
+    /// The index pulse is ignored
+    Off = 0,
+    /// The preload value is loaded into the counter on the index pulse
+    Reset = 1,
+    /// The value of the counter is captured on the index pulse, see IoCntGetCapture
+    Capture = 2,
+}
+
+/// @brief Source of the index pulse of a counter
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum IoCntIndexSource {
+    /// The index input of the counter hardware
+    Index = 0,
+    /// The edges of a digital input
+    Input = 1,
+}
+
+/// Callback function for events of counter inputs, called with the channel, the event and the
+/// value of the counter
+pub type IoCntCallback = Option<extern "C" fn(u8, IoCntEvent, i32)>;
+
 /// @brief PWM timebase for period and duty cycle setting.
 #[repr(u8)]
 #[derive(Debug, Copy, Clone)]
@@ -226,6 +321,269 @@ pub enum IoPwmTimebase {
     Ms1 = 2,
 }
 
+/// @brief Polarity of a PWM output
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum IoPwmPolarity {
+    /// The output is high for the duty cycle
+    Normal = 0,
+    /// The output is low for the duty cycle
+    Inversed = 1,
+}
+
+/// @brief States of a pulse train output (PTO)
+#[repr(u8)]
+#[derive(Debug, Copy, Clone)]
+pub enum IoPtoState {
+    Idle = 0,
+    Running = 1,
+    /// All segments of the parameter table were output
+    Done = 2,
+}
+
+/// @brief States of a stepper axis
+#[repr(u8)]
+#[derive(Debug, Copy, Clone)]
+pub enum IoAxisState {
+    Idle = 0,
+    /// Moving to a target position
+    Moving = 1,
+    Homing = 2,
+    /// Decelerating after a stop or emergency stop
+    Stopping = 3,
+    /// The output failed, the axis was stopped
+    Fault = 4,
+}
+
+/// @brief Output types of a PID control loop
+#[repr(u8)]
+#[derive(Debug, Copy, Clone)]
+pub enum IoPidOutput {
+    /// Analog output, the range is the raw value for 100 %
+    AnalogOutput = 0,
+    /// Duty cycle of a PWM output, the range is the PWM period
+    Pwm = 1,
+    /// Time proportioned digital output, the range is the time window in ms
+    DigitalOutput = 2,
+}
+
+/// @brief Autotune states of a PID control loop
+#[repr(u8)]
+#[derive(Debug, Copy, Clone)]
+pub enum IoPidAutotuneState {
+    Off = 0,
+    Running = 1,
+    /// The autotune succeeded and the gains were set
+    Done = 2,
+    Failed = 3,
+}
+
+/// @brief Configuration of a stepper axis
+///
+/// This structure is passed to IoAxisOpen. Positions are in steps, velocities
+/// in steps/s and accelerations in steps/s².
+#[repr(C)]
+#[derive(Debug)]
+pub struct IoAxisConfig {
+    /// PWM channel of the step signal
+    pub m_uStepChannel: u8,
+    /// Digital output of the direction signal, it is set for positive movements
+    pub m_uDirectionChannel: u8,
+    pub m_fDirectionInverted: IoBool,
+    /// Close the loop with the A/B encoder of a counter channel
+    pub m_fEncoder: IoBool,
+    pub m_uEncoderChannel: u8,
+    /// Encoder counts per step, negative if the encoder counts in the opposite direction
+    pub m_dCountsPerStep: f64,
+    /// Velocity at which the axis starts and stops without ramp
+    pub m_dStartVelocity: f64,
+    pub m_dMaxVelocity: f64,
+    pub m_dAcceleration: f64,
+    /// Deceleration of an emergency stop, 0 to stop immediately
+    pub m_dEmergencyDeceleration: f64,
+    /// Reject movements outside of m_lMinPosition and m_lMaxPosition
+    pub m_fSoftLimits: IoBool,
+    pub m_lMinPosition: i64,
+    pub m_lMaxPosition: i64,
+    /// Enable homing on the limit switch at digital input m_uHomeInput
+    pub m_fHoming: IoBool,
+    pub m_uHomeInput: u8,
+    pub m_fHomeActiveLow: IoBool,
+    /// Velocity of the search, the sign gives the direction
+    pub m_dHomeVelocity: f64,
+    /// Position of the axis at the limit switch
+    pub m_lHomePosition: i64,
+    /// Maximum distance to the target at which a movement is finished
+    pub m_dTolerance: f64,
+}
+
+/// @brief Status of a stepper axis
+///
+/// This structure will be filled by IoAxisGetStatus.
+#[repr(C)]
+#[derive(Debug)]
+pub struct IoAxisStatus {
+    pub m_uState: IoAxisState,
+    /// Position in steps
+    pub m_dPosition: f64,
+    /// Velocity in steps/s, negative in negative direction
+    pub m_dVelocity: f64,
+    /// The reference position was set by homing
+    pub m_fHomed: IoBool,
+}
+
+/// @brief Status of a PID control loop
+///
+/// This structure will be filled by IoPidGetStatus.
+#[repr(C)]
+#[derive(Debug)]
+pub struct IoPidStatus {
+    /// Current (ramped) setpoint
+    pub m_dSetpoint: f64,
+    /// Last process value
+    pub m_dProcessValue: f64,
+    /// The process value could be read, otherwise the output is switched off
+    pub m_fValid: IoBool,
+    /// Controller output in percent
+    pub m_dOutput: f64,
+    /// The loop is in manual mode
+    pub m_fManual: IoBool,
+    pub m_uAutotune: IoPidAutotuneState,
+}
+
+/// Implements the conversion of raw values (e.g. received via network) to the enum types of the
+/// API. Unknown values are rejected with `Error::InvalidParameter`.
+macro_rules! enum_try_from {
+    ( $t:ident, $( $v:ident ),+ ) => {
+        enum_try_from!($t: u8, $( $v ),+);
+    };
+    ( $t:ident: $repr:ty, $( $v:ident ),+ ) => {
+        impl TryFrom<$repr> for $t {
+            type Error = Error;
+
+            fn try_from(value: $repr) -> Result<$t> {
+                $(
+                    if value == $t::$v as $repr {
+                        return Ok($t::$v);
+                    }
+                )+
+                Err(Error::InvalidParameter)
+            }
+        }
+    };
+}
+
+enum_try_from!(IoAnalogMode, Voltage, Current);
+enum_try_from!(IoTmpMode, RtdTwoWire, RtdThreeWire, RtdFourWire);
+enum_try_from!(IoTmpSensorType, PT100, PT1000);
+enum_try_from!(IoCntMode, Counter, ABEncoder);
+enum_try_from!(IoCntTrigger, RisingEdge, FallingEdge, AnyEdge);
+enum_try_from!(IoCntDirection, Up, Down);
+enum_try_from!(IoPwmTimebase, Ns800, Ms1);
+enum_try_from!(IoPwmPolarity, Normal, Inversed);
+enum_try_from!(
+    IoResult: u32,
+    Success,
+    Error,
+    NotImplemented,
+    InvalidParameter,
+    InvalidChannel,
+    InvalidMode,
+    InvalidTimebase,
+    InvalidDelta,
+    PtoParamTabFull,
+    DevAccessFailed,
+    Reserved0,
+    Reserved1,
+    ShpImgError,
+    AddressOutOfRange,
+    WatchdogTimeout
+);
+
+/// Implements the conversion between the enum types of the API and the names used by the
+/// network daemons and command line tools (e.g. "voltage"). Names are case insensitive.
+macro_rules! enum_names {
+    ( $t:ident, $( $v:ident => $n:expr ),+ ) => {
+        impl $t {
+            pub fn name(self) -> &'static str {
+                match self {
+                    $( $t::$v => $n, )+
+                }
+            }
+        }
+
+        impl std::str::FromStr for $t {
+            type Err = Error;
+
+            fn from_str(value: &str) -> Result<$t> {
+                $(
+                    if value.eq_ignore_ascii_case($n) {
+                        return Ok($t::$v);
+                    }
+                )+
+                Err(Error::InvalidParameter)
+            }
+        }
+    };
+}
+
+enum_names!(IoAnalogMode, Voltage => "voltage", Current => "current");
+enum_names!(IoTmpMode, RtdTwoWire => "2wire", RtdThreeWire => "3wire", RtdFourWire => "4wire");
+enum_names!(IoTmpSensorType, PT100 => "pt100", PT1000 => "pt1000");
+enum_names!(IoCntMode, Counter => "counter", ABEncoder => "ab_encoder");
+enum_names!(IoCntTrigger, RisingEdge => "rising", FallingEdge => "falling", AnyEdge => "any");
+enum_names!(IoCntDirection, Up => "up", Down => "down");
+enum_names!(IoPwmTimebase, Ns800 => "800ns", Ms1 => "1ms");
+enum_names!(IoPwmPolarity, Normal => "normal", Inversed => "inversed");
+
+/// @brief Channel types of the I/O API
+#[repr(u8)]
+#[derive(Debug, Copy, Clone)]
+pub enum IoChannelType {
+    DigitalInput = 0,
+    DigitalOutput = 1,
+    AnalogInput = 2,
+    AnalogOutput = 3,
+    Temperature = 4,
+}
+
+/// @brief Interface of an external channel provider plugin
+///
+/// A plugin is a shared library which exports the function
+/// `IoResult IoProviderPluginInit(IoProviderPlugin *pPlugin_p)`, which has to
+/// fill this structure. Function pointers for channel types without any
+/// channels may be NULL. The channel numbers passed to the functions are
+/// relative to the plugin. The functions return an IoResult code, unknown codes
+/// are treated as IoResult_Error. A digital input is set for any value other
+/// than 0.
+#[repr(C)]
+pub struct IoProviderPlugin {
+    /// Name of the provider (static NUL terminated string)
+    pub m_pszName: *const std::os::raw::c_char,
+    /// Number of digital inputs
+    pub m_uDiChannels: u8,
+    /// Number of digital outputs
+    pub m_uDoChannels: u8,
+    /// Number of analog inputs
+    pub m_uAiChannels: u8,
+    /// Number of analog outputs
+    pub m_uAoChannels: u8,
+    /// Number of temperature inputs
+    pub m_uTmpChannels: u8,
+    /// Get the label of a channel (static NUL terminated string or NULL)
+    pub m_pfnGetLabel: Option<extern "C" fn(IoChannelType, u8) -> *const std::os::raw::c_char>,
+    /// Get the value of a digital input
+    pub m_pfnGetInput: Option<extern "C" fn(u8, *mut u8) -> u32>,
+    /// Set the value of a digital output
+    pub m_pfnSetOutput: Option<extern "C" fn(u8, IoBool) -> u32>,
+    /// Get the value of an analog input
+    pub m_pfnAdcGetValue: Option<extern "C" fn(u8, *mut i64) -> u32>,
+    /// Set the value of an analog output
+    pub m_pfnDacSetValue: Option<extern "C" fn(u8, i64) -> u32>,
+    /// Get the value of a temperature input in 1/10000 °C
+    pub m_pfnTmpGetValue: Option<extern "C" fn(u8, *mut i32) -> u32>,
+}
+
 /// @brief Initializes the I/O driver.
 /// @note This function has to be called before any of the other API
 ///       functions can be used.
@@ -258,6 +616,20 @@ pub extern "C" fn IoShutdown() -> IoResult {
     debug!("IoShutdown");
 
     catch_unwind! {{
+        // the loops need the instance to switch off their outputs
+        if let Ok(mut loops) = PID_LOOPS.lock() {
+            loops.clear();
+        }
+        if let Ok(mut channels) = PTO_CHANNELS.lock() {
+            channels.clear();
+        }
+        if let Ok(mut axes) = AXES.lock() {
+            axes.clear();
+        }
+        if let Some(client) = WATCHDOG_CLIENT.lock().ok().and_then(|mut c| c.take()) {
+            client.unregister().ok();
+        }
+
         io_do! {
             io,
             io.shutdown()
@@ -351,6 +723,166 @@ pub extern "C" fn IoServiceWatchdog() -> IoResult {
     }}
 }
 
+/// @brief Set the timeout of the system watchdog
+///
+/// The timeout is applied immediately if the watchdog is enabled, otherwise on
+/// IoEnableWatchdog(). The driver may round it to a supported value.
+///
+/// @param uTimeout_p Timeout in seconds
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoSetWatchdogTimeout(uTimeout_p: u32) -> IoResult {
+    debug!("IoSetWatchdogTimeout({})", uTimeout_p);
+
+    catch_unwind! {{
+        io_do! {
+            io,
+            io.watchdog_set_timeout(Duration::from_secs(uTimeout_p as u64))
+        }
+    }}
+}
+
+/// @brief Get the timeout of the system watchdog and the time until it expires
+///
+/// @param puTimeout_p Pointer to the resulting timeout in seconds
+/// @param puTimeLeft_p Pointer to the resulting time left in seconds (may be NULL)
+/// @return IoResult Driver result code of type IoResult
+///
+/// # Safety
+///
+/// `puTimeout_p` must be a valid pointer
+/// `puTimeLeft_p` must be a valid pointer or NULL
+#[no_mangle]
+pub unsafe extern "C" fn IoGetWatchdogTimeout(
+    puTimeout_p: *mut u32,
+    puTimeLeft_p: *mut u32,
+) -> IoResult {
+    debug!(
+        "IoGetWatchdogTimeout({:?}, {:?})",
+        puTimeout_p, puTimeLeft_p
+    );
+
+    catch_unwind! {{
+        check_ptr!(puTimeout_p, IoResult::InvalidParameter);
+
+        io_do! {
+            io,
+            io.watchdog_timeout().and_then(|timeout| {
+                if !puTimeLeft_p.is_null() {
+                    let time_left = io.watchdog_time_left()?;
+                    unsafe { *puTimeLeft_p = time_left.as_secs() as u32 };
+                }
+                unsafe { *puTimeout_p = timeout.as_secs() as u32 };
+                Ok(())
+            })
+        }
+    }}
+}
+
+/// @brief Get the reason of the last reset reported by the system watchdog
+///
+/// @param puBootStatus_p Pointer to the resulting WDIOF_* flags of
+///        <linux/watchdog.h>, WDIOF_CARDRESET (0x20) if the watchdog reset
+///        the system
+/// @return IoResult Driver result code of type IoResult
+///
+/// # Safety
+///
+/// `puBootStatus_p` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn IoGetWatchdogResetReason(puBootStatus_p: *mut u32) -> IoResult {
+    debug!("IoGetWatchdogResetReason({:?})", puBootStatus_p);
+
+    catch_unwind! {{
+        check_ptr!(puBootStatus_p, IoResult::InvalidParameter);
+
+        io_do! {
+            io,
+            io.watchdog_boot_status().map(|status| unsafe { *puBootStatus_p = status; })
+        }
+    }}
+}
+
+/// @brief Register the process at the watchdog supervisor of iodaemon
+///
+/// The process must call IoServiceWatchdogClient() within the timeout, otherwise
+/// iodaemon applies the action configured for the client (see
+/// /etc/sysworxx-io/iodaemon.conf). Terminating the process without
+/// IoUnregisterWatchdogClient() or IoShutdown() is handled as failure.
+///
+/// @param pszName_p Name of the client (without whitespace)
+/// @param uTimeout_p Timeout in milliseconds
+/// @return IoResult Driver result code of type IoResult
+///
+/// # Safety
+///
+/// `pszName_p` must be a valid pointer to a null terminated string
+#[no_mangle]
+pub unsafe extern "C" fn IoRegisterWatchdogClient(
+    pszName_p: *const std::os::raw::c_char,
+    uTimeout_p: u32,
+) -> IoResult {
+    debug!("IoRegisterWatchdogClient({:?}, {})", pszName_p, uTimeout_p);
+
+    catch_unwind! {{
+        check_ptr!(pszName_p, IoResult::InvalidParameter);
+
+        let name = match unsafe { std::ffi::CStr::from_ptr(pszName_p) }.to_str() {
+            Ok(name) => name,
+            Err(_) => return IoResult::InvalidParameter,
+        };
+        let mut client = match WATCHDOG_CLIENT.lock() {
+            Ok(client) => client,
+            Err(_) => return IoResult::Error,
+        };
+        if let Some(previous) = client.take() {
+            previous.unregister().ok();
+        }
+        let result = WatchdogClient::register(name, Duration::from_millis(uTimeout_p.into()))
+            .map(|registered| {
+                *client = Some(registered);
+            });
+        IoResult::from(result)
+    }}
+}
+
+/// @brief Send a heartbeat to the watchdog supervisor of iodaemon
+///
+/// @return IoResult Driver result code of type IoResult, IoResult::WatchdogTimeout if
+///         the timeout was already exceeded
+#[no_mangle]
+pub extern "C" fn IoServiceWatchdogClient() -> IoResult {
+    debug!("IoServiceWatchdogClient");
+
+    catch_unwind! {{
+        match WATCHDOG_CLIENT.lock() {
+            Ok(mut client) => match client.as_mut() {
+                Some(client) => IoResult::from(client.heartbeat()),
+                None => IoResult::InvalidParameter,
+            },
+            Err(_) => IoResult::Error,
+        }
+    }}
+}
+
+/// @brief Unregister the process from the watchdog supervisor of iodaemon
+///
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoUnregisterWatchdogClient() -> IoResult {
+    debug!("IoUnregisterWatchdogClient");
+
+    catch_unwind! {{
+        match WATCHDOG_CLIENT.lock() {
+            Ok(mut client) => match client.take() {
+                Some(client) => IoResult::from(client.unregister()),
+                None => IoResult::InvalidParameter,
+            },
+            Err(_) => IoResult::Error,
+        }
+    }}
+}
+
 /// @brief Get information about device revision and available I/O channels
 ///
 /// @param pHwInfo_p Destination structure with the resulting information
@@ -725,6 +1257,8 @@ pub extern "C" fn IoCntSetup(
 
 /// @brief Set the initial value of the counter
 ///
+/// The value is loaded into the counter, also by IoCntReset.
+///
 /// @param uChannel_p The channel to setup
 /// @param iPreload_p The initial value to set
 /// @return IoResult Driver result code of type IoResult
@@ -764,54 +1298,1053 @@ pub unsafe extern "C" fn IoCntGetValue(uChannel_p: u8, piValue_p: *mut i32) -> I
     }}
 }
 
-/// @brief Enable PWM output
+/// @brief Load the preload value into a counter channel
 ///
-/// @param uChannel_p The channel of the digital output
-/// @param fRun_p The value to set
+/// @param uChannel_p The channel to reset
 /// @return IoResult Driver result code of type IoResult
 #[no_mangle]
-pub extern "C" fn IoPwmEnable(uChannel_p: u8, fRun_p: bool) -> IoResult {
-    debug!("IoPwmEnable({} {})", uChannel_p, fRun_p);
+pub extern "C" fn IoCntReset(uChannel_p: u8) -> IoResult {
+    debug!("IoCntReset({})", uChannel_p);
 
     catch_unwind! {{
         io_do! {
             io,
-            io.pwm_enable(uChannel_p as usize, fRun_p)
+            io.cnt_reset(uChannel_p as usize)
         }
     }}
 }
 
-/// @brief Setup an PWM channel
+/// @brief Set the compare value of a counter channel
+///
+/// With auto-reload the counter continues at the preload value after it
+/// passed the compare value counting up, and at the compare value after it
+/// passed the preload value counting down. The auto-reload is done by the
+/// counter hardware if supported and the preload value is 0.
 ///
 /// @param uChannel_p The channel to setup
-/// @param period The periode of the PWM
-/// @param duty_cycle The duty cycle of the PWM
+/// @param fEnable_p Enable the compare value with true value, false will disable it
+/// @param iCompare_p The compare value
+/// @param fAutoReload_p Reload the counter at the compare value
+/// @return IoResult Driver result code of type IoResult,
+///         IoResult::InvalidParameter if the auto-reload is enabled and the
+///         compare value is not greater than the preload value
+#[no_mangle]
+pub extern "C" fn IoCntSetCompare(
+    uChannel_p: u8,
+    fEnable_p: IoBool,
+    iCompare_p: i32,
+    fAutoReload_p: IoBool,
+) -> IoResult {
+    debug!(
+        "IoCntSetCompare({}, {:?}, {}, {:?})",
+        uChannel_p, *fEnable_p, iCompare_p, *fAutoReload_p
+    );
+
+    catch_unwind! {{
+        let compare = if *fEnable_p { Some(iCompare_p) } else { None };
+        io_do! {
+            io,
+            io.cnt_set_compare(uChannel_p as usize, compare, *fAutoReload_p)
+        }
+    }}
+}
+
+/// @brief Register a callback for events of a counter channel
+///
+/// The compare and overflow events are detected by sampling the counter, unless
+/// the counter hardware signals them. The callback is called from a separate
+/// thread.
+///
+/// @param uChannel_p The channel of the counter
+/// @param pfnCallback_p The callback function to register of type #IoCntCallback
+/// @param uEvents_p Mask of the events to signal, bits of #IoCntEvent
 /// @return IoResult Driver result code of type IoResult
 #[no_mangle]
-pub extern "C" fn IoPwmSetup(uChannel_p: u8, period: u16, duty_cycle: u16) -> IoResult {
-    debug!("IoPwmSetup({}, {:?}, {:?})", uChannel_p, period, duty_cycle);
+pub extern "C" fn IoCntRegisterCallback(
+    uChannel_p: u8,
+    pfnCallback_p: IoCntCallback,
+    uEvents_p: u8,
+) -> IoResult {
+    debug!(
+        "IoCntRegisterCallback({}, {:?}, {:#x})",
+        uChannel_p, pfnCallback_p, uEvents_p
+    );
+
+    catch_unwind! {{
+        match pfnCallback_p {
+            None => IoResult::InvalidParameter,
+            Some(_) => {
+                io_do! {
+                    io,
+                    io.cnt_register_callback(uChannel_p as usize, pfnCallback_p, uEvents_p)
+                }
+            }
+        }
+    }}
+}
+
+/// @brief Un-register the callback for events of a counter channel
+///
+/// @param uChannel_p Analogous to #IoCntRegisterCallback
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoCntUnregisterCallback(uChannel_p: u8) -> IoResult {
+    debug!("IoCntUnregisterCallback({})", uChannel_p);
 
     catch_unwind! {{
         io_do! {
             io,
-            io.pwm_setup(uChannel_p as usize, period, duty_cycle)
+            io.cnt_unregister_callback(uChannel_p as usize)
         }
     }}
 }
 
-/// @brief Set the timebase for PWM output
+/// @brief Set the handling of the index pulse (Z) of a counter channel
 ///
-/// @param uChannel_p The channel to get the value for
-/// @param timebase Timebase enum
+/// The index pulse is the index input of the counter hardware, or the edges
+/// of a digital input matching the trigger. The edges of digital inputs are
+/// handled with the time taken by the kernel, the captured value is read
+/// from the counter when the edge is handled.
+///
+/// @param uChannel_p The channel of the counter
+/// @param uMode_p The handling of the index pulse, see IoCntIndexMode
+/// @param uSource_p The source of the index pulse, see IoCntIndexSource
+/// @param uInput_p The digital input of the index pulse with IoCntIndexSource::Input
+/// @param uTrigger_p The edges of the digital input, see IoInputTrigger
+/// @return IoResult Driver result code of type IoResult,
+///         IoResult::NotImplemented if the source is not supported
+#[no_mangle]
+pub extern "C" fn IoCntSetIndex(
+    uChannel_p: u8,
+    uMode_p: IoCntIndexMode,
+    uSource_p: IoCntIndexSource,
+    uInput_p: u8,
+    uTrigger_p: IoInputTrigger,
+) -> IoResult {
+    debug!(
+        "IoCntSetIndex({}, {:?}, {:?}, {}, {:?})",
+        uChannel_p, uMode_p, uSource_p, uInput_p, uTrigger_p as u8
+    );
+
+    catch_unwind! {{
+        let input = match uSource_p {
+            IoCntIndexSource::Index => None,
+            IoCntIndexSource::Input => Some(uInput_p as usize),
+        };
+        io_do! {
+            io,
+            io.cnt_set_index(uChannel_p as usize, uMode_p, uTrigger_p, input)
+        }
+    }}
+}
+
+/// @brief Get the value of a counter channel captured on the index pulse
+///
+/// @param uChannel_p The channel of the counter
+/// @param pfCaptured_p Pointer to the destination which is set to true value
+///                     if a value was captured since the last call
+/// @param piValue_p Pointer to the destination of the captured value
+/// @param puTimestamp_p Pointer to the destination of the time of the index
+///                      pulse in nanoseconds since the Unix epoch
 /// @return IoResult Driver result code of type IoResult
+///
+/// # Safety
+///
+/// `pfCaptured_p`, `piValue_p` and `puTimestamp_p` must be valid pointers
 #[no_mangle]
-pub extern "C" fn IoPwmSetTimebase(uChannel_p: u8, timebase: IoPwmTimebase) -> IoResult {
-    debug!("IoPwmSetup({}, {:?},)", uChannel_p, timebase,);
+pub unsafe extern "C" fn IoCntGetCapture(
+    uChannel_p: u8,
+    pfCaptured_p: *mut IoBool,
+    piValue_p: *mut i32,
+    puTimestamp_p: *mut u64,
+) -> IoResult {
+    debug!("IoCntGetCapture({})", uChannel_p);
 
     catch_unwind! {{
+        check_ptr!(pfCaptured_p, IoResult::InvalidParameter);
+        check_ptr!(piValue_p, IoResult::InvalidParameter);
+        check_ptr!(puTimestamp_p, IoResult::InvalidParameter);
+
         io_do! {
             io,
-            io.pwm_set_timebase(uChannel_p as usize, timebase)
+            io.cnt_capture(uChannel_p as usize).map(|capture| unsafe {
+                *pfCaptured_p = capture.is_some().into();
+                if let Some(capture) = capture {
+                    *piValue_p = capture.value;
+                    *puTimestamp_p = capture
+                        .timestamp
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |timestamp| timestamp.as_nanos() as u64);
+                }
+            })
         }
     }}
 }
+
+/// @brief Enable PWM output
+///
+/// @param uChannel_p The channel of the digital output
+/// @param fRun_p The value to set
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPwmEnable(uChannel_p: u8, fRun_p: bool) -> IoResult {
+    debug!("IoPwmEnable({} {})", uChannel_p, fRun_p);
+
+    catch_unwind! {{
+        io_do! {
+            io,
+            io.pwm_enable(uChannel_p as usize, fRun_p)
+        }
+    }}
+}
+
+/// @brief Setup an PWM channel
+///
+/// @param uChannel_p The channel to setup
+/// @param period The periode of the PWM
+/// @param duty_cycle The duty cycle of the PWM
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPwmSetup(uChannel_p: u8, period: u16, duty_cycle: u16) -> IoResult {
+    debug!("IoPwmSetup({}, {:?}, {:?})", uChannel_p, period, duty_cycle);
+
+    catch_unwind! {{
+        io_do! {
+            io,
+            io.pwm_setup(uChannel_p as usize, period, duty_cycle)
+        }
+    }}
+}
+
+/// @brief Set the timebase for PWM output
+///
+/// @param uChannel_p The channel to get the value for
+/// @param timebase Timebase enum
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPwmSetTimebase(uChannel_p: u8, timebase: IoPwmTimebase) -> IoResult {
+    debug!("IoPwmSetup({}, {:?},)", uChannel_p, timebase,);
+
+    catch_unwind! {{
+        io_do! {
+            io,
+            io.pwm_set_timebase(uChannel_p as usize, timebase)
+        }
+    }}
+}
+
+/// @brief Configure period and duty cycle of a PWM output in nanoseconds
+///
+/// The output never uses a duty cycle which is larger than the period. If both
+/// values change, it may run one cycle with the new period and the old duty
+/// cycle (or vice versa). Overrides the values of IoPwmSetup().
+///
+/// @param uChannel_p The channel to configure
+/// @param ulPeriod_p The period in nanoseconds
+/// @param ulDutyCycle_p The duty cycle in nanoseconds, must not exceed the period
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPwmConfigure(uChannel_p: u8, ulPeriod_p: u64, ulDutyCycle_p: u64) -> IoResult {
+    debug!(
+        "IoPwmConfigure({}, {}, {})",
+        uChannel_p, ulPeriod_p, ulDutyCycle_p
+    );
+
+    catch_unwind! {{
+        io_do! {
+            io,
+            io.pwm_configure(uChannel_p as usize, ulPeriod_p, ulDutyCycle_p)
+        }
+    }}
+}
+
+/// @brief Configure a PWM output by frequency and duty cycle in percent
+///
+/// The values are rounded to the nearest nanosecond, see IoPwmConfigure().
+///
+/// @param uChannel_p The channel to configure
+/// @param dFrequency_p The frequency in Hz
+/// @param dDutyCycle_p The duty cycle in percent (0.0 - 100.0)
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPwmSetFrequency(
+    uChannel_p: u8,
+    dFrequency_p: f64,
+    dDutyCycle_p: f64,
+) -> IoResult {
+    debug!(
+        "IoPwmSetFrequency({}, {}, {})",
+        uChannel_p, dFrequency_p, dDutyCycle_p
+    );
+
+    catch_unwind! {{
+        io_do! {
+            io,
+            io.pwm_set_frequency(uChannel_p as usize, dFrequency_p, dDutyCycle_p)
+        }
+    }}
+}
+
+/// @brief Set the polarity of a PWM output
+///
+/// Most PWM controllers only accept a change of the polarity while disabled,
+/// so an enabled output is stopped for the change.
+///
+/// @param uChannel_p The channel to configure
+/// @param polarity Polarity enum
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPwmSetPolarity(uChannel_p: u8, polarity: IoPwmPolarity) -> IoResult {
+    debug!("IoPwmSetPolarity({}, {:?})", uChannel_p, polarity);
+
+    catch_unwind! {{
+        io_do! {
+            io,
+            io.pwm_set_polarity(uChannel_p as usize, polarity)
+        }
+    }}
+}
+
+/// Evaluate the given function for the pulse train output of a PWM channel and convert its
+/// result to `IoResult`
+fn pto_do<F: FnOnce(&mut Pto) -> Result<()>>(channel: u8, f: F) -> IoResult {
+    let mut channels = match PTO_CHANNELS.lock() {
+        Ok(channels) => channels,
+        Err(_) => return IoResult::Error,
+    };
+    let result = match channels.entry(channel) {
+        Entry::Occupied(entry) => f(entry.into_mut()),
+        Entry::Vacant(entry) => {
+            Pto::new(INSTANCE.clone(), channel as usize).and_then(|pto| f(entry.insert(pto)))
+        }
+    };
+    if let Err(e) = &result {
+        debug!("Error: {}", e);
+    }
+    IoResult::from(result)
+}
+
+/// @brief Append a segment to the parameter table of a pulse train output (PTO)
+///
+/// The PTO uses the PWM output of the same channel with a duty cycle of 50 %.
+/// The segments are output one after the other, segments may be appended while
+/// the output is running.
+///
+/// @param uChannel_p The PWM channel
+/// @param ulPeriod_p The period of the first pulse in nanoseconds
+/// @param lDelta_p Change of the period after each pulse in nanoseconds
+///                 (negative to accelerate, positive to decelerate)
+/// @param uPulseCount_p Number of pulses of the segment
+/// @return IoResult Driver result code of type IoResult, IoResult::PtoParamTabFull
+///         if the table is full, IoResult::InvalidDelta if the period of the
+///         last pulse would be less than 1 us
+#[no_mangle]
+pub extern "C" fn IoPtoAddSegment(
+    uChannel_p: u8,
+    ulPeriod_p: u64,
+    lDelta_p: i64,
+    uPulseCount_p: u32,
+) -> IoResult {
+    debug!(
+        "IoPtoAddSegment({}, {}, {}, {})",
+        uChannel_p, ulPeriod_p, lDelta_p, uPulseCount_p
+    );
+
+    catch_unwind! {{
+        pto_do(uChannel_p, |pto| {
+            pto.add_segment(pto::Segment {
+                period: ulPeriod_p,
+                delta: lDelta_p,
+                pulses: uPulseCount_p,
+            })
+        })
+    }}
+}
+
+/// @brief Append a segment to the parameter table of a pulse train output (PTO)
+///        in units of the PWM timebase
+///
+/// Like IoPtoAddSegment(), but the period and the delta are given in units
+/// set by IoPwmSetTimebase() (e.g. for the CTR-700 compatibility library).
+///
+/// @param uChannel_p The PWM channel
+/// @param uPeriod_p The period of the first pulse in units of the timebase
+/// @param iDelta_p Change of the period after each pulse in units of the timebase
+/// @param uPulseCount_p Number of pulses of the segment
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPtoAddSegmentTimebase(
+    uChannel_p: u8,
+    uPeriod_p: u16,
+    iDelta_p: i16,
+    uPulseCount_p: u32,
+) -> IoResult {
+    debug!(
+        "IoPtoAddSegmentTimebase({}, {}, {}, {})",
+        uChannel_p, uPeriod_p, iDelta_p, uPulseCount_p
+    );
+
+    catch_unwind! {{
+        let timebase = match INSTANCE.lock() {
+            Ok(io) => io.pwm_timebase(uChannel_p as usize),
+            Err(_) => Err(Error::GenericError),
+        };
+        let timebase = match timebase {
+            Ok(timebase) => timebase,
+            Err(e) => return IoResult::from(Err::<(), _>(e)),
+        };
+        pto_do(uChannel_p, |pto| {
+            pto.add_segment(pto::Segment {
+                period: uPeriod_p as u64 * timebase,
+                delta: iDelta_p as i64 * timebase as i64,
+                pulses: uPulseCount_p,
+            })
+        })
+    }}
+}
+
+/// @brief Start or stop a pulse train output (PTO)
+///
+/// Stopping the output discards the remaining segments of the parameter table.
+///
+/// @param uChannel_p The PWM channel
+/// @param fRun_p true to start the output of the parameter table, false to stop it
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPtoEnable(uChannel_p: u8, fRun_p: bool) -> IoResult {
+    debug!("IoPtoEnable({}, {})", uChannel_p, fRun_p);
+
+    catch_unwind! {{
+        pto_do(uChannel_p, |pto| {
+            if fRun_p {
+                pto.start()
+            } else {
+                pto.stop();
+                Ok(())
+            }
+        })
+    }}
+}
+
+/// @brief Get the state of a pulse train output (PTO)
+///
+/// @param uChannel_p The PWM channel
+/// @param peState_p Pointer to the state destination
+/// @param pulPulses_p Pointer to the destination for the number of pulses
+///                    output by the current or last run, may be NULL
+/// @return IoResult Driver result code of type IoResult
+///
+/// # Safety
+///
+/// `peState_p` must be a valid pointer, `pulPulses_p` must be a valid pointer or NULL
+#[no_mangle]
+pub unsafe extern "C" fn IoPtoGetState(
+    uChannel_p: u8,
+    peState_p: *mut IoPtoState,
+    pulPulses_p: *mut u64,
+) -> IoResult {
+    debug!(
+        "IoPtoGetState({}, {:?}, {:?})",
+        uChannel_p, peState_p, pulPulses_p
+    );
+
+    catch_unwind! {{
+        check_ptr!(peState_p, IoResult::InvalidParameter);
+
+        pto_do(uChannel_p, |pto| {
+            let (state, pulses) = pto.state();
+            let state = match state {
+                pto::State::Idle => IoPtoState::Idle,
+                pto::State::Running => IoPtoState::Running,
+                pto::State::Done => IoPtoState::Done,
+            };
+            unsafe {
+                *peState_p = state;
+                if !pulPulses_p.is_null() {
+                    *pulPulses_p = pulses;
+                }
+            }
+            Ok(())
+        })
+    }}
+}
+
+/// @brief Register a callback for the completion of a pulse train output (PTO)
+///
+/// The callback is called from a separate thread after all segments were output.
+///
+/// @param uChannel_p The PWM channel
+/// @param pfnCallback_p The callback function of type #IoPtoCallback, NULL to
+///                      unregister the callback
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPtoRegisterCallback(uChannel_p: u8, pfnCallback_p: IoPtoCallback) -> IoResult {
+    debug!("IoPtoRegisterCallback({}, {:?})", uChannel_p, pfnCallback_p);
+
+    catch_unwind! {{
+        pto_do(uChannel_p, |pto| {
+            pto.set_callback(pfnCallback_p.map(|callback| -> pto::Callback {
+                Box::new(move || callback(uChannel_p))
+            }));
+            Ok(())
+        })
+    }}
+}
+
+/// Evaluate the given function for a stepper axis and convert its result to `IoResult`
+fn axis_do<F: FnOnce(&Axis) -> Result<()>>(axis: u8, f: F) -> IoResult {
+    let axes = match AXES.lock() {
+        Ok(axes) => axes,
+        Err(_) => return IoResult::Error,
+    };
+    let result = axes.get(&axis).ok_or(Error::InvalidChannel).and_then(f);
+    if let Err(e) = &result {
+        debug!("Error: {}", e);
+    }
+    IoResult::from(result)
+}
+
+/// @brief Open a stepper axis
+///
+/// The steps are output by the pulse train output of a PWM channel, so the
+/// PTO of the channel must not be used at the same time. The direction is
+/// output by a digital output. The position of an opened axis is 0. An axis
+/// with the same number is closed before.
+///
+/// @param uAxis_p Number of the axis
+/// @param pConfig_p Pointer to the configuration of the axis
+/// @return IoResult Driver result code of type IoResult
+///
+/// # Safety
+///
+/// `pConfig_p` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn IoAxisOpen(uAxis_p: u8, pConfig_p: *const IoAxisConfig) -> IoResult {
+    debug!("IoAxisOpen({}, {:?})", uAxis_p, pConfig_p);
+
+    catch_unwind! {{
+        check_ptr!(pConfig_p, IoResult::InvalidParameter);
+        let c = unsafe { &*pConfig_p };
+
+        let mut config = axis::AxisConfig::new(
+            c.m_uStepChannel as usize,
+            c.m_uDirectionChannel as usize,
+        );
+        config.direction_inverted = *c.m_fDirectionInverted;
+        if *c.m_fEncoder {
+            config.encoder = Some(axis::Encoder {
+                channel: c.m_uEncoderChannel as usize,
+                counts_per_step: c.m_dCountsPerStep,
+            });
+        }
+        config.start_velocity = c.m_dStartVelocity;
+        config.max_velocity = c.m_dMaxVelocity;
+        config.acceleration = c.m_dAcceleration;
+        config.emergency_deceleration = c.m_dEmergencyDeceleration;
+        if *c.m_fSoftLimits {
+            config.soft_limits = Some((c.m_lMinPosition, c.m_lMaxPosition));
+        }
+        if *c.m_fHoming {
+            config.homing = Some(axis::Homing {
+                input: c.m_uHomeInput as usize,
+                active_low: *c.m_fHomeActiveLow,
+                velocity: c.m_dHomeVelocity,
+                position: c.m_lHomePosition,
+            });
+        }
+        config.tolerance = c.m_dTolerance;
+
+        let mut axes = match AXES.lock() {
+            Ok(axes) => axes,
+            Err(_) => return IoResult::Error,
+        };
+        axes.remove(&uAxis_p);
+        let result = Axis::start(INSTANCE.clone(), &format!("axis{}", uAxis_p), &config)
+            .map(|axis| {
+                axes.insert(uAxis_p, axis);
+            });
+        IoResult::from(result)
+    }}
+}
+
+/// @brief Close a stepper axis, a movement is stopped immediately
+///
+/// @param uAxis_p Number of the axis
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoAxisClose(uAxis_p: u8) -> IoResult {
+    debug!("IoAxisClose({})", uAxis_p);
+
+    catch_unwind! {{
+        match AXES.lock() {
+            Ok(mut axes) => match axes.remove(&uAxis_p) {
+                Some(axis) => {
+                    axis.close();
+                    IoResult::Success
+                }
+                None => IoResult::InvalidChannel,
+            },
+            Err(_) => IoResult::Error,
+        }
+    }}
+}
+
+/// @brief Move a stepper axis to an absolute position
+///
+/// The movement follows a trapezoidal velocity profile. A running movement is
+/// changed to the new target.
+///
+/// @param uAxis_p Number of the axis
+/// @param lPosition_p Target position in steps
+/// @return IoResult Driver result code of type IoResult,
+///         IoResult::InvalidParameter if the target is outside of the soft limits
+#[no_mangle]
+pub extern "C" fn IoAxisMoveTo(uAxis_p: u8, lPosition_p: i64) -> IoResult {
+    debug!("IoAxisMoveTo({}, {})", uAxis_p, lPosition_p);
+
+    catch_unwind! {{
+        axis_do(uAxis_p, |axis| axis.move_to(lPosition_p))
+    }}
+}
+
+/// @brief Move a stepper axis relative to its current position
+///
+/// @param uAxis_p Number of the axis
+/// @param lSteps_p Number of steps, negative in negative direction
+/// @return IoResult Driver result code of type IoResult,
+///         IoResult::InvalidParameter if the target is outside of the soft limits
+#[no_mangle]
+pub extern "C" fn IoAxisMoveBy(uAxis_p: u8, lSteps_p: i64) -> IoResult {
+    debug!("IoAxisMoveBy({}, {})", uAxis_p, lSteps_p);
+
+    catch_unwind! {{
+        axis_do(uAxis_p, |axis| axis.move_by(lSteps_p))
+    }}
+}
+
+/// @brief Search the limit switch of a stepper axis and set the reference position
+///
+/// @param uAxis_p Number of the axis
+/// @return IoResult Driver result code of type IoResult,
+///         IoResult::NotImplemented if homing is not configured
+#[no_mangle]
+pub extern "C" fn IoAxisHome(uAxis_p: u8) -> IoResult {
+    debug!("IoAxisHome({})", uAxis_p);
+
+    catch_unwind! {{
+        axis_do(uAxis_p, |axis| axis.home())
+    }}
+}
+
+/// @brief Decelerate a stepper axis to standstill with its acceleration
+///
+/// @param uAxis_p Number of the axis
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoAxisStop(uAxis_p: u8) -> IoResult {
+    debug!("IoAxisStop({})", uAxis_p);
+
+    catch_unwind! {{
+        axis_do(uAxis_p, |axis| {
+            axis.stop();
+            Ok(())
+        })
+    }}
+}
+
+/// @brief Decelerate a stepper axis to standstill with its emergency deceleration
+///
+/// @param uAxis_p Number of the axis
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoAxisEmergencyStop(uAxis_p: u8) -> IoResult {
+    debug!("IoAxisEmergencyStop({})", uAxis_p);
+
+    catch_unwind! {{
+        axis_do(uAxis_p, |axis| {
+            axis.emergency_stop();
+            Ok(())
+        })
+    }}
+}
+
+/// @brief Get the status of a stepper axis
+///
+/// @param uAxis_p Number of the axis
+/// @param pStatus_p Pointer to the status destination
+/// @return IoResult Driver result code of type IoResult
+///
+/// # Safety
+///
+/// `pStatus_p` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn IoAxisGetStatus(uAxis_p: u8, pStatus_p: *mut IoAxisStatus) -> IoResult {
+    debug!("IoAxisGetStatus({}, {:?})", uAxis_p, pStatus_p);
+
+    catch_unwind! {{
+        check_ptr!(pStatus_p, IoResult::InvalidParameter);
+
+        axis_do(uAxis_p, |axis| {
+            let status = axis.status();
+            let status = IoAxisStatus {
+                m_uState: match status.state {
+                    axis::State::Idle => IoAxisState::Idle,
+                    axis::State::Moving => IoAxisState::Moving,
+                    axis::State::Homing => IoAxisState::Homing,
+                    axis::State::Stopping => IoAxisState::Stopping,
+                    axis::State::Fault => IoAxisState::Fault,
+                },
+                m_dPosition: status.position,
+                m_dVelocity: status.velocity,
+                m_fHomed: status.homed.into(),
+            };
+            unsafe { *pStatus_p = status };
+            Ok(())
+        })
+    }}
+}
+
+/// @brief Load an external channel provider plugin and append its channels
+///
+/// The channels of the plugin are appended to the existing channels of each
+/// type. Use IoGetHardwareInfo before and after loading the plugin to
+/// determine the channel numbers or IoGetJson to get the channel labels.
+///
+/// @param sPath_p Path to the shared library of the plugin
+/// @return IoResult Driver result code of type IoResult
+///
+/// # Safety
+///
+/// `sPath_p` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn IoAddProviderPlugin(sPath_p: *const std::os::raw::c_char) -> IoResult {
+    debug!("IoAddProviderPlugin({:?})", sPath_p);
+
+    catch_unwind! {{
+        check_ptr!(sPath_p, IoResult::InvalidParameter);
+
+        let path = match unsafe { std::ffi::CStr::from_ptr(sPath_p) }.to_str() {
+            Ok(path) => path,
+            Err(_) => return IoResult::InvalidParameter,
+        };
+
+        io_do! {
+            io,
+            crate::io::plugin::Plugin::load(path)
+                .and_then(|mut plugin| io.add_provider(&mut plugin))
+        }
+    }}
+}
+
+/// Evaluate the given function for a PID control loop and convert its result to `IoResult`
+fn pid_do<F: FnOnce(&PidLoop) -> Result<()>>(loop_number: u8, f: F) -> IoResult {
+    let loops = match PID_LOOPS.lock() {
+        Ok(loops) => loops,
+        Err(_) => return IoResult::Error,
+    };
+    let result = loops
+        .get(&loop_number)
+        .ok_or(Error::InvalidChannel)
+        .and_then(f);
+    if let Err(e) = &result {
+        debug!("Error: {}", e);
+    }
+    IoResult::from(result)
+}
+
+/// @brief Start a PID control loop
+///
+/// The loop reads the process value from an analog input (raw value) or a
+/// temperature input (°C) and drives the output with the controller output
+/// in percent. A running loop with the same number is stopped before. The
+/// loop starts in automatic mode with the setpoint 0 and the gains Kp = 1,
+/// Ki = 0 and Kd = 0.
+///
+/// @param uLoop_p Number of the loop
+/// @param eInput_p Type of the process value input (analog or temperature)
+/// @param uInputChannel_p Channel of the process value input
+/// @param eOutput_p Type of the output #IoPidOutput
+/// @param uOutputChannel_p Channel of the output
+/// @param uOutputRange_p Range of the output (see #IoPidOutput)
+/// @param uCycleMs_p Cycle time of the loop in milliseconds
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPidStart(
+    uLoop_p: u8,
+    eInput_p: IoChannelType,
+    uInputChannel_p: u8,
+    eOutput_p: IoPidOutput,
+    uOutputChannel_p: u8,
+    uOutputRange_p: u32,
+    uCycleMs_p: u32,
+) -> IoResult {
+    debug!(
+        "IoPidStart({}, {:?}, {}, {:?}, {}, {}, {})",
+        uLoop_p, eInput_p, uInputChannel_p, eOutput_p, uOutputChannel_p, uOutputRange_p, uCycleMs_p
+    );
+
+    catch_unwind! {{
+        let input = match eInput_p {
+            IoChannelType::AnalogInput => pid::Input::AnalogInput(uInputChannel_p as usize),
+            IoChannelType::Temperature => pid::Input::Temperature(uInputChannel_p as usize),
+            _ => return IoResult::InvalidParameter,
+        };
+        let channel = uOutputChannel_p as usize;
+        let output = match eOutput_p {
+            IoPidOutput::AnalogOutput => pid::Output::AnalogOutput {
+                channel,
+                full_scale: uOutputRange_p.into(),
+            },
+            IoPidOutput::Pwm => match u16::try_from(uOutputRange_p) {
+                Ok(period) => pid::Output::Pwm { channel, period },
+                Err(_) => return IoResult::InvalidParameter,
+            },
+            IoPidOutput::DigitalOutput => pid::Output::TimeProportional {
+                channel,
+                window: Duration::from_millis(uOutputRange_p.into()),
+            },
+        };
+        let mut config = pid::LoopConfig::new(input, output);
+        config.cycle = Duration::from_millis(uCycleMs_p.into());
+
+        let mut loops = match PID_LOOPS.lock() {
+            Ok(loops) => loops,
+            Err(_) => return IoResult::Error,
+        };
+        loops.remove(&uLoop_p);
+        let result = PidLoop::start(INSTANCE.clone(), &format!("pid{}", uLoop_p), &config)
+            .map(|pid_loop| {
+                loops.insert(uLoop_p, pid_loop);
+            });
+        IoResult::from(result)
+    }}
+}
+
+/// @brief Stop a PID control loop and switch off its output
+///
+/// @param uLoop_p Number of the loop
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPidStop(uLoop_p: u8) -> IoResult {
+    debug!("IoPidStop({})", uLoop_p);
+
+    catch_unwind! {{
+        match PID_LOOPS.lock() {
+            Ok(mut loops) => match loops.remove(&uLoop_p) {
+                Some(pid_loop) => {
+                    pid_loop.stop();
+                    IoResult::Success
+                }
+                None => IoResult::InvalidChannel,
+            },
+            Err(_) => IoResult::Error,
+        }
+    }}
+}
+
+/// @brief Set the gains of a PID control loop
+///
+/// @param uLoop_p Number of the loop
+/// @param dKp_p Proportional gain (percent per process value unit)
+/// @param dKi_p Integral gain (percent per process value unit and second)
+/// @param dKd_p Derivative gain (percent per process value unit per second)
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPidSetTunings(uLoop_p: u8, dKp_p: f64, dKi_p: f64, dKd_p: f64) -> IoResult {
+    debug!(
+        "IoPidSetTunings({}, {}, {}, {})",
+        uLoop_p, dKp_p, dKi_p, dKd_p
+    );
+
+    catch_unwind! {{
+        pid_do(uLoop_p, |pid_loop| {
+            pid_loop.set_gains(pid::Gains {
+                kp: dKp_p,
+                ki: dKi_p,
+                kd: dKd_p,
+            });
+            Ok(())
+        })
+    }}
+}
+
+/// @brief Get the gains of a PID control loop (e.g. after an autotune)
+///
+/// @param uLoop_p Number of the loop
+/// @param pdKp_p Pointer to the resulting proportional gain
+/// @param pdKi_p Pointer to the resulting integral gain
+/// @param pdKd_p Pointer to the resulting derivative gain
+/// @return IoResult Driver result code of type IoResult
+///
+/// # Safety
+///
+/// `pdKp_p` must be a valid pointer
+/// `pdKi_p` must be a valid pointer
+/// `pdKd_p` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn IoPidGetTunings(
+    uLoop_p: u8,
+    pdKp_p: *mut f64,
+    pdKi_p: *mut f64,
+    pdKd_p: *mut f64,
+) -> IoResult {
+    debug!(
+        "IoPidGetTunings({}, {:?}, {:?}, {:?})",
+        uLoop_p, pdKp_p, pdKi_p, pdKd_p
+    );
+
+    catch_unwind! {{
+        check_ptr!(pdKp_p, IoResult::InvalidParameter);
+        check_ptr!(pdKi_p, IoResult::InvalidParameter);
+        check_ptr!(pdKd_p, IoResult::InvalidParameter);
+
+        pid_do(uLoop_p, |pid_loop| {
+            let gains = pid_loop.gains();
+            unsafe {
+                *pdKp_p = gains.kp;
+                *pdKi_p = gains.ki;
+                *pdKd_p = gains.kd;
+            }
+            Ok(())
+        })
+    }}
+}
+
+/// @brief Limit the output of a PID control loop
+///
+/// @param uLoop_p Number of the loop
+/// @param dMin_p Minimum output in percent (0 to 100)
+/// @param dMax_p Maximum output in percent (0 to 100)
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPidSetLimits(uLoop_p: u8, dMin_p: f64, dMax_p: f64) -> IoResult {
+    debug!("IoPidSetLimits({}, {}, {})", uLoop_p, dMin_p, dMax_p);
+
+    catch_unwind! {{
+        pid_do(uLoop_p, |pid_loop| pid_loop.set_limits(dMin_p, dMax_p))
+    }}
+}
+
+/// @brief Set the setpoint of a PID control loop
+///
+/// @param uLoop_p Number of the loop
+/// @param dSetpoint_p The setpoint in the unit of the process value
+/// @param dRate_p Rate of the setpoint ramp in units per second (0 for a step)
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPidSetSetpoint(uLoop_p: u8, dSetpoint_p: f64, dRate_p: f64) -> IoResult {
+    debug!(
+        "IoPidSetSetpoint({}, {}, {})",
+        uLoop_p, dSetpoint_p, dRate_p
+    );
+
+    catch_unwind! {{
+        pid_do(uLoop_p, |pid_loop| {
+            pid_loop.set_setpoint(dSetpoint_p, dRate_p);
+            Ok(())
+        })
+    }}
+}
+
+/// @brief Switch a PID control loop between manual and automatic mode
+///
+/// The transfer from manual to automatic mode is bumpless.
+///
+/// @param uLoop_p Number of the loop
+/// @param fManual_p Switch to manual mode
+/// @param dOutput_p Output in percent in manual mode
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPidSetManual(uLoop_p: u8, fManual_p: IoBool, dOutput_p: f64) -> IoResult {
+    debug!(
+        "IoPidSetManual({}, {:?}, {})",
+        uLoop_p, fManual_p, dOutput_p
+    );
+
+    catch_unwind! {{
+        pid_do(uLoop_p, |pid_loop| {
+            if *fManual_p {
+                pid_loop.set_manual(dOutput_p);
+            } else {
+                pid_loop.set_auto();
+            }
+            Ok(())
+        })
+    }}
+}
+
+/// @brief Start a relay autotune of a PID control loop
+///
+/// The output is switched between two levels around the current setpoint
+/// until the process oscillates. On success the gains are set, the state
+/// can be polled with IoPidGetStatus.
+///
+/// @param uLoop_p Number of the loop
+/// @param dLow_p Output in percent while the process value is above the setpoint
+/// @param dHigh_p Output in percent while the process value is below the setpoint
+/// @param dHysteresis_p Hysteresis around the setpoint (noise band of the process value)
+/// @return IoResult Driver result code of type IoResult
+#[no_mangle]
+pub extern "C" fn IoPidAutotune(
+    uLoop_p: u8,
+    dLow_p: f64,
+    dHigh_p: f64,
+    dHysteresis_p: f64,
+) -> IoResult {
+    debug!(
+        "IoPidAutotune({}, {}, {}, {})",
+        uLoop_p, dLow_p, dHigh_p, dHysteresis_p
+    );
+
+    catch_unwind! {{
+        pid_do(uLoop_p, |pid_loop| {
+            if dLow_p >= dHigh_p || dHysteresis_p < 0.0 {
+                return Err(Error::InvalidParameter);
+            }
+            pid_loop.autotune(pid::AutotuneConfig {
+                low: dLow_p,
+                high: dHigh_p,
+                hysteresis: dHysteresis_p,
+                ..pid::AutotuneConfig::default()
+            });
+            Ok(())
+        })
+    }}
+}
+
+/// @brief Get the status of a PID control loop
+///
+/// @param uLoop_p Number of the loop
+/// @param pStatus_p Pointer to the status destination
+/// @return IoResult Driver result code of type IoResult
+///
+/// # Safety
+///
+/// `pStatus_p` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn IoPidGetStatus(uLoop_p: u8, pStatus_p: *mut IoPidStatus) -> IoResult {
+    debug!("IoPidGetStatus({}, {:?})", uLoop_p, pStatus_p);
+
+    catch_unwind! {{
+        check_ptr!(pStatus_p, IoResult::InvalidParameter);
+
+        pid_do(uLoop_p, |pid_loop| {
+            let status = pid_loop.status();
+            let status = IoPidStatus {
+                m_dSetpoint: status.setpoint,
+                m_dProcessValue: status.process_value.unwrap_or_default(),
+                m_fValid: status.process_value.is_some().into(),
+                m_dOutput: status.output,
+                m_fManual: status.manual.into(),
+                m_uAutotune: match status.autotune {
+                    pid::AutotuneStatus::Off => IoPidAutotuneState::Off,
+                    pid::AutotuneStatus::Running => IoPidAutotuneState::Running,
+                    pid::AutotuneStatus::Done => IoPidAutotuneState::Done,
+                    pid::AutotuneStatus::Failed => IoPidAutotuneState::Failed,
+                },
+            };
+            unsafe { *pStatus_p = status };
+            Ok(())
+        })
+    }}
+}
@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Health statistics of the library itself. The sampling threads (iio, lm-sensors) record their
+// cycles and read errors per thread name, so that monitoring services can export them. The
+// iodaemon publishes its statistics via shared memory (see `shm::ShmServerGuard::health_set`).
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SamplerStats {
+    /// Number of finished sampling cycles
+    pub cycles: u64,
+    /// Number of failed channel reads
+    pub read_errors: u64,
+    /// Number of intervals which were skipped, because a cycle took too long
+    pub missed_intervals: u64,
+    /// Duration of the last sampling cycle, measured from the scheduled start
+    pub cycle_time: Duration,
+    /// Time when the last sampling cycle finished
+    pub last_cycle: Option<SystemTime>,
+}
+
+impl SamplerStats {
+    /// Time since the last finished cycle
+    pub fn age(&self) -> Option<Duration> {
+        self.last_cycle
+            .map(|time| time.elapsed().unwrap_or_default())
+    }
+}
+
+lazy_static! {
+    static ref SAMPLERS: Mutex<BTreeMap<String, SamplerStats>> = Mutex::new(BTreeMap::new());
+}
+
+fn update<F: FnOnce(&mut SamplerStats)>(f: F) {
+    let name = thread::current().name().unwrap_or("unnamed").to_string();
+    f(SAMPLERS.lock().unwrap().entry(name).or_default())
+}
+
+/// Record a finished sampling cycle of the current thread
+pub fn sampler_cycle(cycle_time: Duration) {
+    update(|stats| {
+        stats.cycles += 1;
+        stats.cycle_time = cycle_time;
+        stats.last_cycle = Some(SystemTime::now());
+    })
+}
+
+/// Record a failed read of the current sampling thread
+pub fn sampler_read_error() {
+    update(|stats| stats.read_errors += 1)
+}
+
+/// Record intervals skipped by the current sampling thread
+pub fn sampler_missed_intervals(count: u64) {
+    update(|stats| stats.missed_intervals += count)
+}
+
+/// Statistics of all sampling threads of this process, sorted by thread name
+pub fn samplers() -> BTreeMap<String, SamplerStats> {
+    SAMPLERS.lock().unwrap().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sampler_test() {
+        thread::Builder::new()
+            .name("health-test".to_string())
+            .spawn(|| {
+                sampler_cycle(Duration::from_millis(3));
+                sampler_cycle(Duration::from_millis(5));
+                sampler_read_error();
+                sampler_missed_intervals(2);
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+
+        let stats = samplers()["health-test"];
+        assert_eq!(stats.cycles, 2);
+        assert_eq!(stats.read_errors, 1);
+        assert_eq!(stats.missed_intervals, 2);
+        assert_eq!(stats.cycle_time, Duration::from_millis(5));
+        assert!(stats.age().unwrap() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,295 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// HTTP/1.1 support (subset required by a small REST API with persistent connections).
+
+pub mod server;
+pub mod websocket;
+
+use std::io::{BufRead, Error, ErrorKind, Read, Result, Write};
+
+/// Maximum size of the request line and all header fields
+const MAX_HEADER_SIZE: usize = 8 * 1024;
+
+/// Maximum size of a request body
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+/// Decode the percent-encoding of a path segment or query value
+pub fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    /// Decoded path segments, e.g. ["api", "outputs", "DO0"]
+    pub path: Vec<String>,
+    /// Decoded query parameters
+    pub query: Vec<(String, String)>,
+    /// Header fields with lower case names
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// HTTP/1.0 request or "Connection: close"
+    pub close: bool,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Check if a comma separated header contains a token (case insensitive)
+    pub fn header_contains(&self, name: &str, token: &str) -> bool {
+        self.header(name).is_some_and(|value| {
+            value
+                .split(',')
+                .any(|t| t.trim().eq_ignore_ascii_case(token))
+        })
+    }
+
+    /// Read a request, returns `None` if the connection was closed before a new request
+    pub fn read<R: BufRead>(reader: &mut R) -> Result<Option<Request>> {
+        let mut lines = vec![];
+        let mut size = 0;
+        loop {
+            let mut line = String::new();
+            let length = reader
+                .by_ref()
+                .take((MAX_HEADER_SIZE - size + 1) as u64)
+                .read_line(&mut line)?;
+            size += length;
+            if length == 0 {
+                if lines.is_empty() && size == 0 {
+                    return Ok(None);
+                }
+                return Err(invalid_data("incomplete HTTP request"));
+            }
+            if size > MAX_HEADER_SIZE {
+                return Err(invalid_data("HTTP header too large"));
+            }
+            let line = line.trim_end_matches(&['\r', '\n'][..]).to_string();
+            if line.is_empty() {
+                // empty lines before the request line are ignored (RFC 7230, 3.5)
+                if lines.is_empty() {
+                    continue;
+                }
+                break;
+            }
+            lines.push(line);
+        }
+
+        let mut request_line = lines[0].split(' ');
+        let (method, target, version) = match (
+            request_line.next(),
+            request_line.next(),
+            request_line.next(),
+        ) {
+            (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => {
+                (method, target, version)
+            }
+            _ => return Err(invalid_data("invalid HTTP request line")),
+        };
+
+        let (path, query) = match target.find('?') {
+            Some(position) => (&target[..position], &target[position + 1..]),
+            None => (target, ""),
+        };
+
+        let mut headers = vec![];
+        for line in &lines[1..] {
+            let position = line
+                .find(':')
+                .ok_or_else(|| invalid_data("invalid HTTP header field"))?;
+            headers.push((
+                line[..position].trim().to_lowercase(),
+                line[position + 1..].trim().to_string(),
+            ));
+        }
+
+        let mut request = Request {
+            method: method.to_string(),
+            path: path
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .map(percent_decode)
+                .collect(),
+            query: query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| match pair.find('=') {
+                    Some(position) => (
+                        percent_decode(&pair[..position]),
+                        percent_decode(&pair[position + 1..]),
+                    ),
+                    None => (percent_decode(pair), String::new()),
+                })
+                .collect(),
+            headers,
+            body: vec![],
+            close: version == "HTTP/1.0",
+        };
+        if request.header_contains("connection", "close") {
+            request.close = true;
+        }
+        if request.header("transfer-encoding").is_some() {
+            return Err(invalid_data("chunked HTTP requests are not supported"));
+        }
+
+        if let Some(length) = request.header("content-length") {
+            let length: usize = length
+                .parse()
+                .map_err(|_| invalid_data("invalid HTTP content length"))?;
+            if length > MAX_BODY_SIZE {
+                return Err(invalid_data("HTTP request body too large"));
+            }
+            request.body = vec![0; length];
+            reader.read_exact(&mut request.body)?;
+        }
+
+        Ok(Some(request))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// Reason phrase of the status codes used by the server
+fn reason(status: u16) -> &'static str {
+    match status {
+        101 => "Switching Protocols",
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+impl Response {
+    pub fn new(status: u16) -> Response {
+        Response {
+            status,
+            headers: vec![],
+            body: vec![],
+        }
+    }
+
+    pub fn json(status: u16, value: &json::JsonValue) -> Response {
+        Response::new(status)
+            .header("Content-Type", "application/json")
+            .body(value.dump().into_bytes())
+    }
+
+    /// JSON response with an error message
+    pub fn error(status: u16, message: &str) -> Response {
+        Response::json(status, &json::object! { error: message })
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Response {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: Vec<u8>) -> Response {
+        self.body = body;
+        self
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W, close: bool) -> Result<()> {
+        let mut buffer = format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status));
+        for (name, value) in &self.headers {
+            buffer.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        if self.status != 101 {
+            buffer.push_str(&format!("Content-Length: {}\r\n", self.body.len()));
+            if close {
+                buffer.push_str("Connection: close\r\n");
+            }
+        }
+        buffer.push_str("\r\n");
+
+        let mut data = buffer.into_bytes();
+        data.extend_from_slice(&self.body);
+        writer.write_all(&data)?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_test() {
+        let data = b"PUT /api/outputs/DO%200?token=a%2Bb&x HTTP/1.1\r\n\
+                     Host: localhost\r\nConnection: keep-alive, Upgrade\r\n\
+                     Content-Length: 14\r\n\r\n{\"value\":true}GET / HTTP/1.0\r\n\r\n";
+        let mut reader = &data[..];
+
+        let request = Request::read(&mut reader).unwrap().unwrap();
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.path, ["api", "outputs", "DO 0"]);
+        assert_eq!(request.query("token"), Some("a+b"));
+        assert_eq!(request.query("x"), Some(""));
+        assert_eq!(request.header("host"), Some("localhost"));
+        assert!(request.header_contains("connection", "upgrade"));
+        assert_eq!(request.body, b"{\"value\":true}");
+        assert!(!request.close);
+
+        let request = Request::read(&mut reader).unwrap().unwrap();
+        assert!(request.path.is_empty());
+        assert!(request.close);
+        assert_eq!(Request::read(&mut reader).unwrap(), None);
+
+        let mut reader = &b"GET /\r\n\r\n"[..];
+        assert!(Request::read(&mut reader).is_err());
+    }
+}
@@ -0,0 +1,854 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// REST API and WebSocket notifications for the channels of a device.
+//
+//   GET       /api/channels                  channels with labels and metadata
+//   GET       /api/<kind>                    state of all channels of a kind
+//   GET, PUT  /api/<kind>/<label|index>      state of a single channel
+//   GET       /api/ws                        WebSocket with change notifications
+//
+// Kinds are "inputs", "outputs", "analog_inputs", "analog_outputs", "temp_sensors",
+// "counter_inputs", "pwm_outputs" and "system" (run_led, err_led, run_switch, config_switch).
+//
+// The body of a PUT request is a JSON object, a plain value is a shortcut for {"value": ...}:
+//
+//   outputs, system        {"value": true}
+//   analog_outputs         {"value": 1000}
+//   analog_inputs          {"mode": "voltage" | "current"}
+//   temp_sensors           {"mode": "2wire" | "3wire" | "4wire", "sensor": "pt100" | "pt1000"}
+//   counter_inputs         {"mode": "counter" | "ab_encoder", "trigger": "rising" | "falling" |
+//                           "any", "direction": "up" | "down", "preload": 0, "enable": true}
+//   pwm_outputs            {"period": 1000, "duty_cycle": 500, "timebase": "1ms",
+//                           "enable": true}
+//
+// The WebSocket sends a JSON text message for each changed input, analog input, temperature and
+// counter, e.g. {"kind": "inputs", "index": 0, "label": "DI0", "value": true, "timestamp": ms}.
+// The current values are sent after connecting.
+
+use std::collections::BTreeMap;
+use std::io::BufReader;
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use ini::Ini;
+
+use super::websocket::{self, Frame};
+use super::{Request, Response};
+use crate::error::{Error, Result};
+use crate::events;
+use crate::{ffi, Io};
+
+/// Idle time after which a connection without WebSocket is closed
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub address: String,
+    /// Token required as "Authorization: Bearer <token>" header or "token" query parameter
+    pub token: Option<String>,
+    /// Reject all PUT requests
+    pub read_only: bool,
+    /// Value of the "Access-Control-Allow-Origin" header
+    pub allow_origin: Option<String>,
+    pub poll_interval: Duration,
+    /// Minimum change of an analog input (raw value) to send a notification
+    pub analog_deadband: i64,
+    /// Minimum change of a temperature (°C) to send a notification
+    pub temperature_deadband: f64,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            address: "0.0.0.0:8080".to_string(),
+            token: None,
+            read_only: false,
+            allow_origin: None,
+            poll_interval: Duration::from_millis(100),
+            analog_deadband: 0,
+            temperature_deadband: 0.0,
+        }
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: Option<&String>, default: T) -> Result<T> {
+    match value {
+        Some(value) => value.trim().parse().map_err(|_| Error::InvalidParameter),
+        None => Ok(default),
+    }
+}
+
+impl Config {
+    /// Load the configuration from an INI file with the sections "server" and "websocket"
+    pub fn load(path: &str) -> Result<Config> {
+        let ini = Ini::load_from_file(path).map_err(|e| {
+            error!("Failed to load {}: {}", path, e);
+            Error::InvalidParameter
+        })?;
+        Config::from_ini(&ini)
+    }
+
+    pub fn from_ini(ini: &Ini) -> Result<Config> {
+        let mut config = Config::default();
+
+        if let Some(server) = ini.section(Some("server")) {
+            config.address = server.get("address").unwrap_or(&config.address).to_string();
+            config.token = server
+                .get("token")
+                .map(|token| token.trim().to_string())
+                .filter(|token| !token.is_empty());
+            config.read_only = parse(server.get("read_only"), config.read_only)?;
+            config.allow_origin = server.get("allow_origin").cloned();
+        }
+
+        if let Some(websocket) = ini.section(Some("websocket")) {
+            config.poll_interval = Duration::from_millis(parse(
+                websocket.get("poll_interval"),
+                config.poll_interval.as_millis() as u64,
+            )?);
+            config.analog_deadband =
+                parse(websocket.get("analog_deadband"), config.analog_deadband)?;
+            config.temperature_deadband = parse(
+                websocket.get("temperature_deadband"),
+                config.temperature_deadband,
+            )?;
+        }
+
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Kind {
+    Input,
+    Output,
+    AnalogInput,
+    AnalogOutput,
+    Temperature,
+    Counter,
+    Pwm,
+    System,
+}
+
+const KINDS: [Kind; 8] = [
+    Kind::Input,
+    Kind::Output,
+    Kind::AnalogInput,
+    Kind::AnalogOutput,
+    Kind::Temperature,
+    Kind::Counter,
+    Kind::Pwm,
+    Kind::System,
+];
+
+/// Labels of the system channels (run_led, err_led, run_switch, config_switch)
+const SYSTEM_CHANNELS: [&str; 4] = ["run_led", "err_led", "run_switch", "config_switch"];
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Input => "inputs",
+            Kind::Output => "outputs",
+            Kind::AnalogInput => "analog_inputs",
+            Kind::AnalogOutput => "analog_outputs",
+            Kind::Temperature => "temp_sensors",
+            Kind::Counter => "counter_inputs",
+            Kind::Pwm => "pwm_outputs",
+            Kind::System => "system",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Kind> {
+        KINDS.iter().copied().find(|kind| kind.name() == name)
+    }
+
+    /// Check if a PUT request is accepted for a channel
+    fn writable(self, index: usize) -> bool {
+        match self {
+            Kind::Input => false,
+            Kind::System => index < 2,
+            _ => true,
+        }
+    }
+}
+
+fn status(error: &Error) -> u16 {
+    match error {
+        Error::InvalidChannel => 404,
+        Error::InvalidParameter | Error::InvalidDelta | Error::ParseIntError => 400,
+        Error::NotImplemented => 501,
+        _ => 500,
+    }
+}
+
+fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn as_bool(value: &json::JsonValue) -> Result<bool> {
+    match value {
+        json::JsonValue::Boolean(value) => Ok(*value),
+        value => match value.as_u8() {
+            Some(0) => Ok(false),
+            Some(1) => Ok(true),
+            _ => Err(Error::InvalidParameter),
+        },
+    }
+}
+
+fn as_name<T: std::str::FromStr<Err = Error>>(value: &json::JsonValue) -> Result<T> {
+    value.as_str().ok_or(Error::InvalidParameter)?.parse()
+}
+
+/// Io with the state written by requests, since outputs and settings cannot be read back
+struct Device {
+    io: Io,
+    settings: BTreeMap<(Kind, usize), json::JsonValue>,
+}
+
+impl Device {
+    fn read(&mut self, kind: Kind, index: usize) -> Result<Option<json::JsonValue>> {
+        let io = &mut self.io;
+        Ok(match kind {
+            Kind::Input => Some(io.input_get(index)?.into()),
+            Kind::AnalogInput => Some(io.analog_input_get(index)?.into()),
+            Kind::Temperature => Some(io.tmp_input_get(index)?.into()),
+            Kind::Counter => Some(io.cnt_get(index)?.into()),
+            Kind::System if index == 2 => Some(io.get_run_switch()?.into()),
+            Kind::System if index == 3 => Some(io.get_config_switch()?.into()),
+            _ => None,
+        })
+    }
+
+    fn write(&mut self, kind: Kind, index: usize, body: &json::JsonValue) -> Result<()> {
+        let io = &mut self.io;
+        let mut settings = self
+            .settings
+            .get(&(kind, index))
+            .cloned()
+            .unwrap_or_else(|| json::object! {});
+
+        match kind {
+            Kind::Output | Kind::System => {
+                let value = as_bool(&body["value"])?;
+                match (kind, index) {
+                    (Kind::System, 0) => io.set_run_led(value)?,
+                    (Kind::System, _) => io.set_err_led(value)?,
+                    _ => io.output_set(index, value)?,
+                }
+                settings["value"] = value.into();
+            }
+            Kind::AnalogOutput => {
+                let value = body["value"].as_i64().ok_or(Error::InvalidParameter)?;
+                io.analog_output_set(index, value)?;
+                settings["value"] = value.into();
+            }
+            Kind::AnalogInput => {
+                let mode: ffi::IoAnalogMode = as_name(&body["mode"])?;
+                io.analog_mode_set(index, mode)?;
+                settings["mode"] = mode.name().into();
+            }
+            Kind::Temperature => {
+                let mode: ffi::IoTmpMode = as_name(&body["mode"])?;
+                let sensor: ffi::IoTmpSensorType = as_name(&body["sensor"])?;
+                io.tmp_set_mode(index, mode, sensor)?;
+                settings["mode"] = mode.name().into();
+                settings["sensor"] = sensor.name().into();
+            }
+            Kind::Counter => {
+                // mode, trigger and direction can only be set together
+                if !body["mode"].is_null()
+                    || !body["trigger"].is_null()
+                    || !body["direction"].is_null()
+                {
+                    let mode: ffi::IoCntMode = as_name(&body["mode"])?;
+                    let trigger: ffi::IoCntTrigger = as_name(&body["trigger"])?;
+                    let direction: ffi::IoCntDirection = as_name(&body["direction"])?;
+                    io.cnt_setup(index, mode, trigger, direction)?;
+                    settings["mode"] = mode.name().into();
+                    settings["trigger"] = trigger.name().into();
+                    settings["direction"] = direction.name().into();
+                }
+                if !body["preload"].is_null() {
+                    let preload = body["preload"].as_i32().ok_or(Error::InvalidParameter)?;
+                    io.cnt_set_preload(index, preload)?;
+                    settings["preload"] = preload.into();
+                }
+                if !body["enable"].is_null() {
+                    let enable = as_bool(&body["enable"])?;
+                    io.cnt_enable(index, enable)?;
+                    settings["enable"] = enable.into();
+                }
+            }
+            Kind::Pwm => {
+                if !body["timebase"].is_null() {
+                    let timebase: ffi::IoPwmTimebase = as_name(&body["timebase"])?;
+                    io.pwm_set_timebase(index, timebase)?;
+                    settings["timebase"] = timebase.name().into();
+                }
+                // period and duty cycle can only be set together
+                if !body["period"].is_null() || !body["duty_cycle"].is_null() {
+                    let period = body["period"].as_u16().ok_or(Error::InvalidParameter)?;
+                    let duty_cycle = body["duty_cycle"].as_u16().ok_or(Error::InvalidParameter)?;
+                    io.pwm_setup(index, period, duty_cycle)?;
+                    settings["period"] = period.into();
+                    settings["duty_cycle"] = duty_cycle.into();
+                }
+                if !body["enable"].is_null() {
+                    let enable = as_bool(&body["enable"])?;
+                    io.pwm_enable(index, enable)?;
+                    settings["enable"] = enable.into();
+                }
+            }
+            Kind::Input => return Err(Error::NotImplemented),
+        }
+
+        self.settings.insert((kind, index), settings);
+        Ok(())
+    }
+}
+
+/// Values of the inputs, which were sent last to a WebSocket
+#[derive(Debug, Default)]
+struct Published {
+    inputs: Vec<Option<bool>>,
+    analog_inputs: Vec<Option<i64>>,
+    temperatures: Vec<Option<f64>>,
+    counters: Vec<Option<i32>>,
+}
+
+pub struct Server {
+    config: Config,
+    device: String,
+    labels: BTreeMap<Kind, Vec<Option<&'static str>>>,
+    providers: json::JsonValue,
+    shared: Mutex<Device>,
+}
+
+impl Server {
+    /// Create a server for an initialized `Io`
+    pub fn new(mut io: Io, device: &str, config: Config) -> Server {
+        let mut labels: BTreeMap<Kind, Vec<Option<&'static str>>> = BTreeMap::new();
+        let providers;
+        {
+            let info = io.get_channel_info();
+            labels.insert(Kind::Input, info.inputs.iter().map(|c| c.label()).collect());
+            labels.insert(
+                Kind::Output,
+                info.outputs.iter().map(|c| c.label()).collect(),
+            );
+            labels.insert(
+                Kind::AnalogInput,
+                info.analog_inputs.iter().map(|c| c.label()).collect(),
+            );
+            labels.insert(
+                Kind::AnalogOutput,
+                info.analog_outputs.iter().map(|c| c.label()).collect(),
+            );
+            labels.insert(
+                Kind::Temperature,
+                info.temp_sensors.iter().map(|c| c.label()).collect(),
+            );
+            labels.insert(
+                Kind::Counter,
+                info.counter_input.iter().map(|c| c.label()).collect(),
+            );
+            labels.insert(
+                Kind::Pwm,
+                info.pwm_outputs.iter().map(|c| c.label()).collect(),
+            );
+            labels.insert(
+                Kind::System,
+                SYSTEM_CHANNELS.iter().map(|name| Some(*name)).collect(),
+            );
+            providers = json::JsonValue::Array(
+                info.providers
+                    .iter()
+                    .map(|provider| provider.to_json())
+                    .collect(),
+            );
+        }
+
+        // edges are sent immediately if supported by the channel, otherwise on the next poll
+        for i in 0..labels[&Kind::Input].len() {
+            if let Err(e) = events::register(&mut io, i) {
+                debug!("No callback for input {}: {}", i, e);
+            }
+        }
+
+        Server {
+            config,
+            device: device.to_string(),
+            labels,
+            providers,
+            shared: Mutex::new(Device {
+                io,
+                settings: BTreeMap::new(),
+            }),
+        }
+    }
+
+    pub fn serve(self: Arc<Self>, listener: TcpListener) -> std::io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = self.clone();
+
+            thread::Builder::new()
+                .name("http-client".to_string())
+                .spawn(move || {
+                    if let Err(e) = server.handle_client(&stream) {
+                        debug!("HTTP connection closed: {}", e);
+                    }
+                    stream.shutdown(Shutdown::Both).ok();
+                })?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_client(&self, stream: &TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = stream.try_clone()?;
+
+        while let Some(request) = Request::read(&mut reader)? {
+            if request.path == ["api", "ws"] && websocket::is_upgrade(&request) {
+                let response = match self.authorize(&request) {
+                    Ok(()) => websocket::handshake(&request),
+                    Err(response) => Err(response),
+                };
+                match response {
+                    Ok(response) => {
+                        response.write(&mut writer, false)?;
+                        stream.set_read_timeout(None)?;
+                        return self.run_websocket(reader, writer);
+                    }
+                    Err(response) => {
+                        self.finish(response).write(&mut writer, true)?;
+                        return Ok(());
+                    }
+                }
+            }
+
+            let response = self.finish(self.handle(&request));
+            response.write(&mut writer, request.close)?;
+            if request.close {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add the headers common to all responses
+    fn finish(&self, response: Response) -> Response {
+        match &self.config.allow_origin {
+            Some(origin) => response.header("Access-Control-Allow-Origin", origin),
+            None => response,
+        }
+    }
+
+    fn authorize(&self, request: &Request) -> std::result::Result<(), Response> {
+        let token = match &self.config.token {
+            Some(token) => token,
+            None => return Ok(()),
+        };
+        let header = request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "));
+        let query = request.query("token");
+        if [header, query].contains(&Some(token.as_str())) {
+            Ok(())
+        } else {
+            Err(Response::error(401, "invalid or missing token")
+                .header("WWW-Authenticate", "Bearer"))
+        }
+    }
+
+    fn handle(&self, request: &Request) -> Response {
+        if request.method == "OPTIONS" {
+            // CORS preflight requests are answered without authorization
+            return Response::new(204)
+                .header("Allow", "GET, PUT, OPTIONS")
+                .header("Access-Control-Allow-Methods", "GET, PUT, OPTIONS")
+                .header(
+                    "Access-Control-Allow-Headers",
+                    "Authorization, Content-Type",
+                );
+        }
+        if let Err(response) = self.authorize(request) {
+            return response;
+        }
+
+        let path: Vec<&str> = request.path.iter().map(String::as_str).collect();
+        let (kind, id) = match path.as_slice() {
+            ["api", "channels"] if request.method == "GET" => {
+                return Response::json(200, &self.channels());
+            }
+            ["api", "channels"] | ["api", "ws"] => {
+                return Response::error(405, "method not allowed").header("Allow", "GET");
+            }
+            ["api", kind] => (*kind, None),
+            ["api", kind, id] => (*kind, Some(*id)),
+            _ => return Response::error(404, "not found"),
+        };
+        let kind = match Kind::from_name(kind) {
+            Some(kind) => kind,
+            None => return Response::error(404, "unknown channel kind"),
+        };
+
+        let result = match (request.method.as_str(), id) {
+            ("GET", None) => self.get_all(kind),
+            ("GET", Some(id)) => self.find(kind, id).and_then(|i| self.get(kind, i)),
+            ("PUT", Some(id)) => match self.find(kind, id) {
+                Ok(_) if self.config.read_only => {
+                    return Response::error(403, "server is read-only");
+                }
+                Ok(index) if !kind.writable(index) => {
+                    return Response::error(405, "channel is not writable").header("Allow", "GET");
+                }
+                Ok(index) => self.put(kind, index, &request.body),
+                Err(e) => Err(e),
+            },
+            (_, None) => {
+                return Response::error(405, "method not allowed").header("Allow", "GET");
+            }
+            _ => {
+                return Response::error(405, "method not allowed").header("Allow", "GET, PUT");
+            }
+        };
+
+        match result {
+            Ok(value) => Response::json(200, &value),
+            Err(e) => Response::error(status(&e), &e.to_string()),
+        }
+    }
+
+    /// Find a channel by label or index
+    fn find(&self, kind: Kind, id: &str) -> Result<usize> {
+        let labels = &self.labels[&kind];
+        labels
+            .iter()
+            .position(|label| *label == Some(id))
+            .or_else(|| id.parse().ok().filter(|index| *index < labels.len()))
+            .ok_or(Error::InvalidChannel)
+    }
+
+    fn describe(&self, kind: Kind, index: usize) -> json::JsonValue {
+        let mut channel = json::object! {
+            index: index,
+            label: self.labels[&kind][index],
+            writable: kind.writable(index),
+        };
+        if kind == Kind::Temperature {
+            channel["unit"] = "°C".into();
+        }
+        channel
+    }
+
+    fn channels(&self) -> json::JsonValue {
+        let mut channels = json::object! {
+            device: self.device.as_str(),
+            read_only: self.config.read_only,
+            providers: self.providers.clone(),
+        };
+        for kind in KINDS.iter().copied() {
+            channels[kind.name()] = (0..self.labels[&kind].len())
+                .map(|i| self.describe(kind, i))
+                .collect::<Vec<_>>()
+                .into();
+        }
+        channels
+    }
+
+    fn get(&self, kind: Kind, index: usize) -> Result<json::JsonValue> {
+        let mut device = self.shared.lock().unwrap();
+        let mut channel = self.describe(kind, index);
+        if let Some(settings) = device.settings.get(&(kind, index)) {
+            for (key, value) in settings.entries() {
+                channel[key] = value.clone();
+            }
+        }
+        if let Some(value) = device.read(kind, index)? {
+            channel["value"] = value;
+        }
+        Ok(channel)
+    }
+
+    /// State of all channels of a kind, channels which cannot be read contain an error message
+    fn get_all(&self, kind: Kind) -> Result<json::JsonValue> {
+        let channels = (0..self.labels[&kind].len())
+            .map(|i| {
+                self.get(kind, i).unwrap_or_else(|e| {
+                    let mut channel = self.describe(kind, i);
+                    channel["error"] = e.to_string().into();
+                    channel
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(channels.into())
+    }
+
+    fn put(&self, kind: Kind, index: usize, body: &[u8]) -> Result<json::JsonValue> {
+        let body = std::str::from_utf8(body).map_err(|_| Error::InvalidParameter)?;
+        let body = json::parse(body).map_err(|_| Error::InvalidParameter)?;
+        let body = if body.is_object() {
+            body
+        } else {
+            json::object! { value: body }
+        };
+
+        self.shared.lock().unwrap().write(kind, index, &body)?;
+        self.get(kind, index)
+    }
+
+    fn event(&self, kind: Kind, index: usize, value: json::JsonValue) -> Frame {
+        let event = json::object! {
+            kind: kind.name(),
+            index: index,
+            label: self.labels[&kind][index],
+            value: value,
+            timestamp: timestamp(),
+        };
+        Frame::text(&event.dump())
+    }
+
+    /// Read all inputs and return the events for changed values
+    fn poll(&self, published: &mut Published) -> Vec<Frame> {
+        let mut events = vec![];
+        let mut device = self.shared.lock().unwrap();
+        let io = &mut device.io;
+
+        for (i, published) in published.inputs.iter_mut().enumerate() {
+            if let Ok(value) = io.input_get(i) {
+                if *published != Some(value) {
+                    *published = Some(value);
+                    events.push(self.event(Kind::Input, i, value.into()));
+                }
+            }
+        }
+
+        let deadband = self.config.analog_deadband.max(1);
+        for (i, published) in published.analog_inputs.iter_mut().enumerate() {
+            if let Ok(value) = io.analog_input_get(i) {
+                match *published {
+                    Some(last) if (value - last).abs() < deadband => {}
+                    _ => {
+                        *published = Some(value);
+                        events.push(self.event(Kind::AnalogInput, i, value.into()));
+                    }
+                }
+            }
+        }
+
+        let deadband = self.config.temperature_deadband;
+        for (i, published) in published.temperatures.iter_mut().enumerate() {
+            if let Ok(value) = io.tmp_input_get(i) {
+                match *published {
+                    Some(last) if last == value || (value - last).abs() < deadband => {}
+                    _ => {
+                        *published = Some(value);
+                        events.push(self.event(Kind::Temperature, i, value.into()));
+                    }
+                }
+            }
+        }
+
+        for (i, published) in published.counters.iter_mut().enumerate() {
+            if let Ok(value) = io.cnt_get(i) {
+                if *published != Some(value) {
+                    *published = Some(value);
+                    events.push(self.event(Kind::Counter, i, value.into()));
+                }
+            }
+        }
+
+        events
+    }
+
+    fn run_websocket(
+        &self,
+        mut reader: BufReader<TcpStream>,
+        mut writer: TcpStream,
+    ) -> std::io::Result<()> {
+        let (frame_tx, frames) = crossbeam_channel::bounded(16);
+        thread::Builder::new()
+            .name("http-websocket".to_string())
+            .spawn(move || loop {
+                let frame = Frame::read(&mut reader);
+                let failed = frame.is_err();
+                if frame_tx.send(frame).is_err() || failed {
+                    break;
+                }
+            })?;
+
+        let (event_tx, events) = crossbeam_channel::unbounded();
+        events::subscribe(event_tx);
+
+        let mut published = Published {
+            inputs: vec![None; self.labels[&Kind::Input].len()],
+            analog_inputs: vec![None; self.labels[&Kind::AnalogInput].len()],
+            temperatures: vec![None; self.labels[&Kind::Temperature].len()],
+            counters: vec![None; self.labels[&Kind::Counter].len()],
+        };
+        let mut next_poll = Instant::now();
+
+        loop {
+            let mut outgoing = vec![];
+            let timeout = next_poll.saturating_duration_since(Instant::now());
+
+            crossbeam_channel::select! {
+                recv(frames) -> frame => match frame {
+                    Ok(Ok(frame)) => match frame.opcode {
+                        websocket::PING => {
+                            outgoing.push(Frame::new(websocket::PONG, frame.payload));
+                        }
+                        websocket::CLOSE => {
+                            Frame::close(1000).write(&mut writer).ok();
+                            return Ok(());
+                        }
+                        // messages from the client are ignored
+                        _ => {}
+                    },
+                    Ok(Err(e)) => {
+                        Frame::close(1002).write(&mut writer).ok();
+                        return Err(e);
+                    }
+                    Err(_) => return Ok(()),
+                },
+                recv(events) -> event => {
+                    if let Ok((channel, value)) = event {
+                        let channel = channel as usize;
+                        if let Some(last) = published.inputs.get_mut(channel) {
+                            if *last != Some(value) {
+                                *last = Some(value);
+                                outgoing.push(self.event(Kind::Input, channel, value.into()));
+                            }
+                        }
+                    }
+                },
+                default(timeout) => {},
+            }
+
+            if Instant::now() >= next_poll {
+                outgoing.extend(self.poll(&mut published));
+                next_poll = Instant::now() + self.config.poll_interval;
+            }
+
+            for frame in &outgoing {
+                frame.write(&mut writer)?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+    use crate::labeled::Labeled;
+
+    fn server(sim: &Simulator, config: Config) -> Server {
+        let mut io = IoBuilder::new()
+            .output(Box::new(Labeled::new("DO0", sim.output(0))))
+            .input(Box::new(Labeled::new("DI0", sim.input(0))))
+            .analog_input(Box::new(sim.analog_input(0)))
+            .analog_output(Box::new(sim.analog_output(0)))
+            .temp_sensor(Box::new(sim.temp_sensor(0)))
+            .build();
+        io.init().unwrap();
+        Server::new(io, "sim", config)
+    }
+
+    fn request(method: &str, target: &str, body: &str) -> Request {
+        let data = format!(
+            "{} {} HTTP/1.1\r\nAuthorization: Bearer secret\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            target,
+            body.len(),
+            body
+        );
+        Request::read(&mut data.as_bytes()).unwrap().unwrap()
+    }
+
+    fn body(response: &Response) -> json::JsonValue {
+        json::parse(std::str::from_utf8(&response.body).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn config_test() {
+        let ini = Ini::load_from_str(
+            "[server]\naddress = 127.0.0.1:80\ntoken = secret\nread_only = true\n\
+             [websocket]\npoll_interval = 250\n",
+        )
+        .unwrap();
+        let config = Config::from_ini(&ini).unwrap();
+        assert_eq!(config.address, "127.0.0.1:80");
+        assert_eq!(config.token.as_deref(), Some("secret"));
+        assert!(config.read_only);
+        assert_eq!(config.poll_interval, Duration::from_millis(250));
+
+        let ini = Ini::load_from_str("[server]\nread_only = maybe\n").unwrap();
+        assert!(Config::from_ini(&ini).is_err());
+    }
+
+    #[test]
+    fn api_test() {
+        let sim = Simulator::new("SIM", 1, 1, 1);
+        let server = server(&sim, Config::default());
+
+        let channels = body(&server.handle(&request("GET", "/api/channels", "")));
+        assert_eq!(channels["inputs"][0]["label"], "DI0");
+        assert_eq!(channels["analog_inputs"][0]["label"], json::Null);
+        assert_eq!(channels["system"].len(), 4);
+
+        // the output is looped back to the input by the simulator
+        let response = server.handle(&request("PUT", "/api/outputs/DO0", "true"));
+        assert_eq!(response.status, 200);
+        assert_eq!(body(&response)["value"], true);
+        assert_eq!(
+            body(&server.handle(&request("GET", "/api/inputs/0", "")))["value"],
+            true
+        );
+
+        let response = server.handle(&request("PUT", "/api/analog_outputs/0", "{\"value\":42}"));
+        assert_eq!(response.status, 200);
+        let inputs = body(&server.handle(&request("GET", "/api/analog_inputs", "")));
+        assert_eq!(inputs[0]["value"], 42);
+
+        let status = |method, target, body| server.handle(&request(method, target, body)).status;
+        assert_eq!(status("PUT", "/api/inputs/DI0", "true"), 405);
+        assert_eq!(status("PUT", "/api/outputs/DO1", "true"), 404);
+        assert_eq!(status("PUT", "/api/outputs/DO0", "\"on\""), 400);
+        assert_eq!(status("GET", "/api/leds", ""), 404);
+    }
+
+    #[test]
+    fn access_test() {
+        let sim = Simulator::new("SIM", 1, 1, 1);
+        let config = Config {
+            token: Some("other".to_string()),
+            ..Default::default()
+        };
+        let protected = server(&sim, config);
+        let status = |target| protected.handle(&request("GET", target, "")).status;
+        assert_eq!(status("/api/channels"), 401);
+        assert_eq!(status("/api/channels?token=other"), 200);
+
+        let config = Config {
+            read_only: true,
+            ..Default::default()
+        };
+        let read_only = server(&sim, config);
+        let status = |method, target| read_only.handle(&request(method, target, "1")).status;
+        assert_eq!(status("PUT", "/api/outputs/0"), 403);
+        assert_eq!(status("GET", "/api/outputs/0"), 200);
+    }
+}
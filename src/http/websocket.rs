@@ -0,0 +1,270 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// WebSocket protocol (RFC 6455) support for the server side: opening handshake and frames.
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use super::{Request, Response};
+
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Maximum payload size of a received frame
+const MAX_PAYLOAD_SIZE: u64 = 64 * 1024;
+
+pub const CONTINUATION: u8 = 0x0;
+pub const TEXT: u8 = 0x1;
+pub const BINARY: u8 = 0x2;
+pub const CLOSE: u8 = 0x8;
+pub const PING: u8 = 0x9;
+pub const PONG: u8 = 0xa;
+
+/// SHA-1 digest (FIPS 180-4), only used to compute the accept key of the handshake
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [
+        0x6745_2301,
+        0xefcd_ab89,
+        0x98ba_dcfe,
+        0x1032_5476,
+        0xc3d2_e1f0,
+    ];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5a82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ed9_eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+                _ => (b ^ c ^ d, 0xca62_c1d6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (h, value) in h.iter_mut().zip(&[a, b, c, d, e]) {
+            *h = h.wrapping_add(*value);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (bytes, value) in digest.chunks_mut(4).zip(&h) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+/// Base64 encoding with padding (RFC 4648)
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let value = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(value >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Value of the "Sec-WebSocket-Accept" header for a "Sec-WebSocket-Key"
+pub fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{}", key.trim(), GUID).as_bytes()))
+}
+
+/// Check if the request asks for a WebSocket connection
+pub fn is_upgrade(request: &Request) -> bool {
+    request.header_contains("connection", "upgrade")
+        && request.header_contains("upgrade", "websocket")
+}
+
+/// Response completing the opening handshake, or an error response for an invalid request
+pub fn handshake(request: &Request) -> std::result::Result<Response, Response> {
+    if request.method != "GET" {
+        return Err(Response::error(405, "WebSocket requires GET"));
+    }
+    if request.header("sec-websocket-version") != Some("13") {
+        return Err(Response::error(400, "unsupported WebSocket version")
+            .header("Sec-WebSocket-Version", "13"));
+    }
+    let key = request
+        .header("sec-websocket-key")
+        .ok_or_else(|| Response::error(400, "missing WebSocket key"))?;
+
+    Ok(Response::new(101)
+        .header("Upgrade", "websocket")
+        .header("Connection", "Upgrade")
+        .header("Sec-WebSocket-Accept", &accept_key(key)))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub fin: bool,
+    pub opcode: u8,
+    pub payload: Vec<u8>,
+}
+
+impl Frame {
+    pub fn new(opcode: u8, payload: Vec<u8>) -> Frame {
+        Frame {
+            fin: true,
+            opcode,
+            payload,
+        }
+    }
+
+    pub fn text(text: &str) -> Frame {
+        Frame::new(TEXT, text.as_bytes().to_vec())
+    }
+
+    /// Close frame with a status code (RFC 6455, 7.4)
+    pub fn close(code: u16) -> Frame {
+        Frame::new(CLOSE, code.to_be_bytes().to_vec())
+    }
+
+    /// Read a frame sent by a client, which has to be masked
+    pub fn read<R: Read>(reader: &mut R) -> Result<Frame> {
+        let mut header = [0u8; 2];
+        reader.read_exact(&mut header)?;
+
+        if header[0] & 0x70 != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "reserved WebSocket bits set",
+            ));
+        }
+        if header[1] & 0x80 == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "unmasked WebSocket frame",
+            ));
+        }
+
+        let length = match header[1] & 0x7f {
+            126 => {
+                let mut length = [0u8; 2];
+                reader.read_exact(&mut length)?;
+                u16::from_be_bytes(length) as u64
+            }
+            127 => {
+                let mut length = [0u8; 8];
+                reader.read_exact(&mut length)?;
+                u64::from_be_bytes(length)
+            }
+            length => length as u64,
+        };
+        if length > MAX_PAYLOAD_SIZE {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "WebSocket frame too large",
+            ));
+        }
+
+        let mut mask = [0u8; 4];
+        reader.read_exact(&mut mask)?;
+        let mut payload = vec![0u8; length as usize];
+        reader.read_exact(&mut payload)?;
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+
+        Ok(Frame {
+            fin: header[0] & 0x80 != 0,
+            opcode: header[0] & 0x0f,
+            payload,
+        })
+    }
+
+    /// Write an unmasked frame as sent by a server
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let mut data = Vec::with_capacity(self.payload.len() + 10);
+        data.push(((self.fin as u8) << 7) | self.opcode);
+        match self.payload.len() {
+            length @ 0..=125 => data.push(length as u8),
+            length @ 126..=0xffff => {
+                data.push(126);
+                data.extend_from_slice(&(length as u16).to_be_bytes());
+            }
+            length => {
+                data.push(127);
+                data.extend_from_slice(&(length as u64).to_be_bytes());
+            }
+        }
+        data.extend_from_slice(&self.payload);
+        writer.write_all(&data)?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_key_test() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        // RFC 6455, 1.3
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn frame_test() {
+        // masked "Hello" from RFC 6455, 5.7
+        let data = [
+            0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58,
+        ];
+        let frame = Frame::read(&mut &data[..]).unwrap();
+        assert_eq!(frame, Frame::text("Hello"));
+
+        let mut buffer = vec![];
+        frame.write(&mut buffer).unwrap();
+        assert_eq!(buffer, [0x81, 0x05, 0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+
+        let mut buffer = vec![];
+        Frame::new(BINARY, vec![0; 256]).write(&mut buffer).unwrap();
+        assert_eq!(buffer[..4], [0x82, 126, 0x01, 0x00]);
+
+        // unmasked frames from a client are rejected
+        assert!(Frame::read(&mut &[0x81, 0x00][..]).is_err());
+    }
+}
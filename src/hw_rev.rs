@@ -6,8 +6,15 @@ use std::fs;
 use crate::error::*;
 
 const PATH_FW_COMPATIBLE: &str = "/sys/firmware/devicetree/base/compatible";
+const ENV_DEVICE_NAME: &str = "SYSWORXX_IO_DEVICE";
 
+/// Get the name of the device definition to use. The name can be overridden by the environment
+/// variable `SYSWORXX_IO_DEVICE` (e.g. for custom definitions or for testing on a PC).
 pub fn get_device_name() -> Result<String> {
+    if let Ok(device) = std::env::var(ENV_DEVICE_NAME) {
+        return Ok(device);
+    }
+
     let compatibles = fs::read_to_string(PATH_FW_COMPATIBLE)?;
     let devices = ["ctr", "pi"];
     for device in devices {
@@ -47,12 +54,12 @@ mod tests {
     #[test]
     fn decode_test() {
         let rev = decode_hw_revision("systec,ctr700,rev0".to_string());
-        assert_eq!(rev, Ok(0));
+        assert!(matches!(rev, Ok(0)));
 
         let rev = decode_hw_revision("systec,ctr700,rev1\0systec,ctr700".to_string());
-        assert_eq!(rev, Ok(1));
+        assert!(matches!(rev, Ok(1)));
 
         let rev = decode_hw_revision("systec,ctr7002\0systec,ctr700".to_string());
-        assert_eq!(rev, Err(Error::GenericError));
+        assert!(matches!(rev, Err(Error::GenericError)));
     }
 }
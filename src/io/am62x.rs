@@ -3,10 +3,13 @@
 
 use std::fs;
 use std::path;
+use std::sync::{Arc, Mutex};
 
 use crate::error::{Error, Result};
 use crate::ffi;
-use crate::{CounterInput, DigitalInput, IoChannel};
+use crate::io::cnt_event;
+use crate::io::counter::{self, Capture, Count};
+use crate::{CounterInput, DigitalInput, EdgeWatch, IoChannel};
 
 // Differences to IMX counter:
 // By using function setting "pulse-direction" (meaning: counter)
@@ -19,6 +22,16 @@ use crate::{CounterInput, DigitalInput, IoChannel};
 //
 // Regardless which function is used, there seems to be no way to change the
 // trigger edge for the counter - it changes on BOTH edges at all times.
+//
+// The preload is written to the `count` attribute (and to `preset` if the driver
+// supports it). The counter counts from 0 to its `ceiling`, which is used for the
+// auto-reload at the compare value if the preload is 0.
+//
+// Overflows, underflows and direction changes are read from the event chrdev of the counter if
+// the driver supports them, the other events are detected by sampling the count.
+//
+// On the index input of the hardware the preset is loaded with `preset_enable`. The driver cannot
+// capture the count, so captures need a digital input as index pulse.
 
 #[derive(Debug)]
 pub struct Counter {
@@ -26,8 +39,46 @@ pub struct Counter {
     function: ffi::IoCntMode,
     trigger: ffi::IoCntTrigger,
     dir: ffi::IoCntDirection,
-    preload: i32,
+    count: Arc<Mutex<Count>>,
     input: Box<dyn DigitalInput>,
+    number: usize,
+    events: Option<cnt_event::Registration>,
+    index: Option<EdgeWatch>,
+    captured: Arc<Mutex<Option<Capture>>>,
+}
+
+fn write_attr(path: &str, name: &str, value: impl ToString) -> Result<()> {
+    fs::write(format!("{}/{}", path, name), value.to_string())?;
+    Ok(())
+}
+
+fn read_count(path: &str) -> Result<i32> {
+    counter::parse(&fs::read_to_string(format!("{}/count", path))?)
+}
+
+/// Load the preload into the hardware, or in software if the count cannot be written
+fn load(path: &str, count: &mut Count) -> Result<()> {
+    if write_attr(path, "count", count.preload() as u32).is_ok() {
+        count.loaded();
+    } else {
+        count.load(read_count(path)?);
+    }
+    Ok(())
+}
+
+/// Set the ceiling of the hardware for the auto-reload, the maximum count otherwise
+fn write_ceiling(path: &str, count: &mut Count) {
+    let ceiling = count.ceiling();
+    let written = write_attr(path, "ceiling", ceiling.unwrap_or(u32::MAX)).is_ok();
+    count.set_hardware_reload(ceiling.is_some() && written);
+}
+
+/// Load the preload and set the ceiling, which depends on where the preload was loaded
+fn reset(path: &str, count: &Mutex<Count>) -> Result<()> {
+    let mut count = count.lock().unwrap();
+    load(path, &mut count)?;
+    write_ceiling(path, &mut count);
+    Ok(())
 }
 
 impl Counter {
@@ -37,17 +88,26 @@ impl Counter {
             function: ffi::IoCntMode::Counter,
             trigger: ffi::IoCntTrigger::RisingEdge,
             dir: ffi::IoCntDirection::Up,
-            preload: 0,
+            count: Arc::new(Mutex::new(Count::default())),
             input,
+            number: 0,
+            events: None,
+            index: None,
+            captured: Arc::new(Mutex::new(None)),
         }
     }
+
+    fn write_attr(&self, name: &str, value: impl ToString) -> Result<()> {
+        write_attr(self.path, name, value)
+    }
 }
 
 impl IoChannel for Counter {
-    fn init(&mut self, _chan_number: usize) -> Result<()> {
+    fn init(&mut self, chan_number: usize) -> Result<()> {
         if !path::Path::new(self.path).exists() {
             return Err(Error::generic_access_error());
         }
+        self.number = chan_number;
 
         self.input.init(0)?;
 
@@ -55,6 +115,8 @@ impl IoChannel for Counter {
     }
 
     fn shutdown(&mut self) -> Result<()> {
+        self.events = None;
+        self.index = None;
         self.input.shutdown()?;
 
         Ok(())
@@ -115,20 +177,104 @@ impl CounterInput for Counter {
     }
 
     fn set_preload(&mut self, preload: i32) -> Result<()> {
-        self.preload = preload;
-        Ok(())
+        self.count.lock().unwrap().set_preload(preload)?;
+        // optional, the preset is loaded by the hardware on the index pulse
+        self.write_attr("preset", preload as u32).ok();
+        reset(self.path, &self.count)
     }
 
     fn get(&mut self) -> Result<i32> {
-        let path = format!("{}/count", self.path);
-        let value = fs::read_to_string(path)?;
-        let mut value = value
-            .trim()
-            .parse::<i32>()
-            .map_err(|_| Error::generic_access_error())?;
+        let raw = read_count(self.path)?;
+        Ok(self.count.lock().unwrap().value(raw))
+    }
 
-        value += self.preload;
+    fn reset(&mut self) -> Result<()> {
+        reset(self.path, &self.count)
+    }
+
+    fn set_compare(&mut self, compare: Option<i32>, auto_reload: bool) -> Result<()> {
+        let mut count = self.count.lock().unwrap();
+        count.set_compare(compare, auto_reload)?;
+        write_ceiling(self.path, &mut count);
+        Ok(())
+    }
+
+    fn has_encoder(&self) -> bool {
+        true
+    }
+
+    fn register_callback(&mut self, callback: ffi::IoCntCallback, events: u8) -> Result<()> {
+        // the chrdev can be opened once only
+        self.events = None;
+        let chrdev = match cnt_event::Chrdev::open(self.path) {
+            Ok(chrdev) => Some(chrdev),
+            Err(e) => {
+                debug!("No events of counter {}: {}", self.number, e);
+                None
+            }
+        };
+
+        let (path, count) = (self.path, Arc::clone(&self.count));
+        let sampler: cnt_event::Sampler = Box::new(move || {
+            let raw = read_count(path)?;
+            Ok((raw, count.lock().unwrap().clone()))
+        });
+        self.events = Some(cnt_event::watch(
+            self.number,
+            sampler,
+            chrdev,
+            callback,
+            events,
+        )?);
+        Ok(())
+    }
+
+    fn unregister_callback(&mut self) -> Result<()> {
+        self.events = None;
+        Ok(())
+    }
+
+    fn set_index(
+        &mut self,
+        mode: ffi::IoCntIndexMode,
+        trigger: ffi::IoInputTrigger,
+        input: Option<&mut dyn DigitalInput>,
+    ) -> Result<()> {
+        if input.is_none() && mode == ffi::IoCntIndexMode::Capture {
+            return Err(Error::NotImplemented);
+        }
+
+        self.index = None;
+        let hardware_reset = input.is_none() && mode == ffi::IoCntIndexMode::Reset;
+        if let Err(e) = self.write_attr("preset_enable", u8::from(hardware_reset)) {
+            if hardware_reset {
+                debug!("No index input of counter {}: {}", self.number, e);
+                return Err(Error::NotImplemented);
+            }
+        }
+
+        match (input, mode) {
+            (None, _) | (_, ffi::IoCntIndexMode::Off) => Ok(()),
+            (Some(input), _) => {
+                let (path, count) = (self.path, Arc::clone(&self.count));
+                let count_cloned = Arc::clone(&count);
+                let handler = counter::index_handler(
+                    mode,
+                    trigger,
+                    Arc::clone(&self.captured),
+                    move || reset(path, &count),
+                    move || {
+                        let raw = read_count(path)?;
+                        Ok(count_cloned.lock().unwrap().value(raw))
+                    },
+                );
+                self.index = Some(input.watch_edges(handler)?);
+                Ok(())
+            }
+        }
+    }
 
-        Ok(value)
+    fn capture(&mut self) -> Result<Option<Capture>> {
+        Ok(self.captured.lock().unwrap().take())
     }
 }
@@ -0,0 +1,492 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// GPIOs via the character devices (/dev/gpiochipN), which replace the deprecated sysfs interface
+// (see `io::sysfs`). Lines are addressed by the label of their chip and the offset or by the line
+// name, so they do not depend on the global GPIO numbers.
+//
+// The chips are enumerated with `gpio_cdev`. It only implements the uAPI v1, which lacks the
+// debounce attribute, so the lines are requested with the uAPI v2 (Linux 5.10) directly.
+//
+// Callbacks of inputs are triggered by line events (see `io::edge`).
+//
+// A requested line is exclusively owned by the process until `shutdown()`, in contrast to
+// exported sysfs GPIOs, which can be accessed by multiple processes. So the lines of the device
+// definitions are owned by iodaemon, which provides them to the other processes via shared memory
+// (see `io::shm`).
+//
+// Outputs are requested without changing their direction and keep their level, so requesting a
+// line does not switch a running output off.
+
+use std::convert::TryFrom;
+use std::fs::File;
+use std::mem;
+use std::os::unix::io::FromRawFd;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::ffi;
+use crate::io::edge;
+use crate::io::util::{ioctl, iowr};
+use crate::{DigitalInput, DigitalOutput, IoChannel};
+
+const GPIO_V2_LINES_MAX: usize = 64;
+const GPIO_V2_LINE_NUM_ATTRS_MAX: usize = 10;
+const GPIO_MAX_NAME_SIZE: usize = 32;
+
+const GPIO_V2_LINE_FLAG_ACTIVE_LOW: u64 = 1 << 1;
+const GPIO_V2_LINE_FLAG_INPUT: u64 = 1 << 2;
+const GPIO_V2_LINE_FLAG_OUTPUT: u64 = 1 << 3;
+const GPIO_V2_LINE_FLAG_EDGE_RISING: u64 = 1 << 4;
+const GPIO_V2_LINE_FLAG_EDGE_FALLING: u64 = 1 << 5;
+const GPIO_V2_LINE_FLAG_OPEN_DRAIN: u64 = 1 << 6;
+const GPIO_V2_LINE_FLAG_OPEN_SOURCE: u64 = 1 << 7;
+const GPIO_V2_LINE_FLAG_BIAS_PULL_UP: u64 = 1 << 8;
+const GPIO_V2_LINE_FLAG_BIAS_PULL_DOWN: u64 = 1 << 9;
+const GPIO_V2_LINE_FLAG_BIAS_DISABLED: u64 = 1 << 10;
+
+const GPIO_V2_LINE_ATTR_ID_OUTPUT_VALUES: u32 = 2;
+const GPIO_V2_LINE_ATTR_ID_DEBOUNCE: u32 = 3;
+
+const CONSUMER: &[u8] = b"sysworxx-io";
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct LineAttribute {
+    id: u32,
+    padding: u32,
+    /// Union of the flags, the output values and the debounce period (u32)
+    value: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct LineConfigAttribute {
+    attr: LineAttribute,
+    mask: u64,
+}
+
+#[repr(C)]
+struct LineConfig {
+    flags: u64,
+    num_attrs: u32,
+    padding: [u32; 5],
+    attrs: [LineConfigAttribute; GPIO_V2_LINE_NUM_ATTRS_MAX],
+}
+
+#[repr(C)]
+struct LineRequest {
+    offsets: [u32; GPIO_V2_LINES_MAX],
+    consumer: [u8; GPIO_MAX_NAME_SIZE],
+    config: LineConfig,
+    num_lines: u32,
+    event_buffer_size: u32,
+    padding: [u32; 5],
+    fd: i32,
+}
+
+#[repr(C)]
+struct LineValues {
+    bits: u64,
+    mask: u64,
+}
+
+const GPIO_IOCTL_BASE: u8 = 0xB4;
+const GPIO_V2_GET_LINE_IOCTL: u64 = iowr::<LineRequest>(GPIO_IOCTL_BASE, 0x07);
+const GPIO_V2_LINE_SET_CONFIG_IOCTL: u64 = iowr::<LineConfig>(GPIO_IOCTL_BASE, 0x0D);
+const GPIO_V2_LINE_GET_VALUES_IOCTL: u64 = iowr::<LineValues>(GPIO_IOCTL_BASE, 0x0E);
+const GPIO_V2_LINE_SET_VALUES_IOCTL: u64 = iowr::<LineValues>(GPIO_IOCTL_BASE, 0x0F);
+
+fn cdev_error(err: gpio_cdev::Error) -> Error {
+    error!("GPIO character device: {}", err);
+    Error::generic_access_error()
+}
+
+/// Address of a GPIO line
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineRef {
+    /// Label of the chip (e.g. "600000.gpio") and offset of the line
+    Offset(String, u32),
+    /// Line name from the device tree ("gpio-line-names")
+    Name(String),
+}
+
+impl LineRef {
+    /// Path of the chip device and the offset of the line
+    fn find(&self) -> Result<(PathBuf, u32)> {
+        for chip in gpio_cdev::chips().map_err(cdev_error)? {
+            let chip = chip.map_err(cdev_error)?;
+            match self {
+                LineRef::Offset(label, offset) if chip.label() == label => {
+                    if *offset >= chip.num_lines() {
+                        error!("{} has no line {}", label, offset);
+                        return Err(Error::InvalidChannel);
+                    }
+                    return Ok((chip.path().to_path_buf(), *offset));
+                }
+                LineRef::Offset(..) => {}
+                LineRef::Name(name) => {
+                    let line = chip.lines().find(|line| {
+                        line.info()
+                            .is_ok_and(|info| info.name() == Some(name.as_str()))
+                    });
+                    if let Some(line) = line {
+                        return Ok((chip.path().to_path_buf(), line.offset()));
+                    }
+                }
+            }
+        }
+
+        error!("GPIO line {:?} not found", self);
+        Err(Error::InvalidChannel)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bias {
+    /// Keep the bias configured by the device tree or the firmware
+    AsIs,
+    PullUp,
+    PullDown,
+    Disabled,
+}
+
+impl Bias {
+    fn flags(self) -> u64 {
+        match self {
+            Bias::AsIs => 0,
+            Bias::PullUp => GPIO_V2_LINE_FLAG_BIAS_PULL_UP,
+            Bias::PullDown => GPIO_V2_LINE_FLAG_BIAS_PULL_DOWN,
+            Bias::Disabled => GPIO_V2_LINE_FLAG_BIAS_DISABLED,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Drive {
+    PushPull,
+    OpenDrain,
+    OpenSource,
+}
+
+impl Drive {
+    fn flags(self) -> u64 {
+        match self {
+            Drive::PushPull => 0,
+            Drive::OpenDrain => GPIO_V2_LINE_FLAG_OPEN_DRAIN,
+            Drive::OpenSource => GPIO_V2_LINE_FLAG_OPEN_SOURCE,
+        }
+    }
+}
+
+impl LineConfig {
+    fn new(flags: u64, output: Option<bool>, debounce: Option<Duration>) -> Result<LineConfig> {
+        let mut config: LineConfig = unsafe { mem::zeroed() };
+        config.flags = flags;
+
+        let mut attributes = vec![];
+        if let Some(value) = output {
+            attributes.push((GPIO_V2_LINE_ATTR_ID_OUTPUT_VALUES, value as u64));
+        }
+        if let Some(debounce) = debounce {
+            let period =
+                u32::try_from(debounce.as_micros()).map_err(|_| Error::InvalidParameter)?;
+            // the period is the first u32 of the union
+            let value = if cfg!(target_endian = "little") {
+                period as u64
+            } else {
+                (period as u64) << 32
+            };
+            attributes.push((GPIO_V2_LINE_ATTR_ID_DEBOUNCE, value));
+        }
+        for (attr, (id, value)) in config.attrs.iter_mut().zip(attributes.iter()) {
+            attr.attr.id = *id;
+            attr.attr.value = *value;
+            attr.mask = 1;
+        }
+        config.num_attrs = attributes.len() as u32;
+        Ok(config)
+    }
+}
+
+/// A requested line, released when dropped
+#[derive(Debug)]
+struct LineHandle {
+    file: File,
+}
+
+impl LineHandle {
+    /// Request the line with the given flags. The values are logical values, i.e. inverted by
+    /// the kernel for active low lines.
+    ///
+    /// Without a direction flag, the line is requested as is (see `Do::init`).
+    fn request(line: &LineRef, flags: u64, debounce: Option<Duration>) -> Result<LineHandle> {
+        let (path, offset) = line.find()?;
+        let chip = File::open(&path)?;
+
+        let mut request: LineRequest = unsafe { mem::zeroed() };
+        request.offsets[0] = offset;
+        request.num_lines = 1;
+        request.consumer[..CONSUMER.len()].copy_from_slice(CONSUMER);
+        request.config = LineConfig::new(flags, None, debounce)?;
+
+        ioctl(&chip, GPIO_V2_GET_LINE_IOCTL, &mut request).map_err(|e| {
+            error!("Failed to request GPIO line {:?}: {}", line, e);
+            e
+        })?;
+
+        Ok(LineHandle {
+            file: unsafe { File::from_raw_fd(request.fd) },
+        })
+    }
+
+    /// Change the configuration of the line, the output value is required to change the line to
+    /// an output
+    fn reconfigure(
+        &self,
+        flags: u64,
+        output: Option<bool>,
+        debounce: Option<Duration>,
+    ) -> Result<()> {
+        let mut config = LineConfig::new(flags, output, debounce)?;
+        ioctl(&self.file, GPIO_V2_LINE_SET_CONFIG_IOCTL, &mut config)
+    }
+
+    fn get(&self) -> Result<bool> {
+        let mut values = LineValues { bits: 0, mask: 1 };
+        ioctl(&self.file, GPIO_V2_LINE_GET_VALUES_IOCTL, &mut values)?;
+        Ok(values.bits & 1 != 0)
+    }
+
+    fn set(&self, value: bool) -> Result<()> {
+        let mut values = LineValues {
+            bits: value as u64,
+            mask: 1,
+        };
+        ioctl(&self.file, GPIO_V2_LINE_SET_VALUES_IOCTL, &mut values)
+    }
+}
+
+/// Digital output, which keeps the level of the line on `init()`
+#[derive(Debug)]
+pub struct Do {
+    line: LineRef,
+    active_low: bool,
+    bias: Bias,
+    drive: Drive,
+    handle: Option<LineHandle>,
+}
+
+impl Do {
+    pub fn new(chip: &str, offset: u32) -> Do {
+        Do::from_line(LineRef::Offset(chip.to_string(), offset))
+    }
+
+    pub fn by_name(name: &str) -> Do {
+        Do::from_line(LineRef::Name(name.to_string()))
+    }
+
+    pub fn from_line(line: LineRef) -> Do {
+        Do {
+            line,
+            active_low: false,
+            bias: Bias::AsIs,
+            drive: Drive::PushPull,
+            handle: None,
+        }
+    }
+
+    pub fn active_low(mut self) -> Do {
+        self.active_low = true;
+        self
+    }
+
+    pub fn bias(mut self, bias: Bias) -> Do {
+        self.bias = bias;
+        self
+    }
+
+    pub fn drive(mut self, drive: Drive) -> Do {
+        self.drive = drive;
+        self
+    }
+}
+
+impl IoChannel for Do {
+    fn init(&mut self, _chan_number: usize) -> Result<()> {
+        if self.handle.is_none() {
+            let active_low = if self.active_low {
+                GPIO_V2_LINE_FLAG_ACTIVE_LOW
+            } else {
+                0
+            };
+            // keep the direction and read the level, which is then driven by the output
+            let handle = LineHandle::request(&self.line, active_low, None)?;
+            let level = handle.get()?;
+            let flags =
+                GPIO_V2_LINE_FLAG_OUTPUT | active_low | self.bias.flags() | self.drive.flags();
+            handle.reconfigure(flags, Some(level), None)?;
+            self.handle = Some(handle);
+        }
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        self.handle = None;
+        Ok(())
+    }
+}
+
+impl DigitalOutput for Do {
+    fn set(&mut self, val: bool) -> Result<()> {
+        self.handle
+            .as_ref()
+            .ok_or_else(Error::generic_access_error)?
+            .set(val)
+    }
+
+    fn get(&mut self) -> Result<bool> {
+        self.handle
+            .as_ref()
+            .ok_or_else(Error::generic_access_error)?
+            .get()
+    }
+}
+
+#[derive(Debug)]
+pub struct Di {
+    line: LineRef,
+    active_low: bool,
+    bias: Bias,
+    debounce: Option<Duration>,
+    handle: Option<LineHandle>,
+    number: usize,
+    callback: Option<edge::Registration>,
+}
+
+impl Di {
+    pub fn new(chip: &str, offset: u32) -> Di {
+        Di::from_line(LineRef::Offset(chip.to_string(), offset))
+    }
+
+    pub fn by_name(name: &str) -> Di {
+        Di::from_line(LineRef::Name(name.to_string()))
+    }
+
+    pub fn from_line(line: LineRef) -> Di {
+        Di {
+            line,
+            active_low: false,
+            bias: Bias::AsIs,
+            debounce: None,
+            handle: None,
+            number: 0,
+            callback: None,
+        }
+    }
+
+    pub fn active_low(mut self) -> Di {
+        self.active_low = true;
+        self
+    }
+
+    pub fn bias(mut self, bias: Bias) -> Di {
+        self.bias = bias;
+        self
+    }
+
+    /// Debounce the input in hardware (or by the GPIO driver)
+    pub fn debounce(mut self, period: Duration) -> Di {
+        self.debounce = Some(period);
+        self
+    }
+
+    fn flags(&self) -> u64 {
+        let mut flags = GPIO_V2_LINE_FLAG_INPUT | self.bias.flags();
+        if self.active_low {
+            flags |= GPIO_V2_LINE_FLAG_ACTIVE_LOW;
+        }
+        flags
+    }
+
+    fn handle(&self) -> Result<&LineHandle> {
+        self.handle.as_ref().ok_or_else(Error::generic_access_error)
+    }
+}
+
+impl IoChannel for Di {
+    fn init(&mut self, chan_number: usize) -> Result<()> {
+        self.number = chan_number;
+        if self.handle.is_none() {
+            self.handle = Some(LineHandle::request(
+                &self.line,
+                self.flags(),
+                self.debounce,
+            )?);
+        }
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        // the watcher holds a duplicate of the file descriptor
+        self.callback = None;
+        self.handle = None;
+        Ok(())
+    }
+}
+
+impl DigitalInput for Di {
+    fn get(&mut self) -> Result<bool> {
+        self.handle()?.get()
+    }
+
+    fn register_callback(
+        &mut self,
+        callback: ffi::IoInputCallback,
+        trigger: ffi::IoInputTrigger,
+    ) -> Result<()> {
+        self.unregister_callback()?;
+        if callback.is_none() {
+            return Ok(());
+        }
+
+        let handle = self.handle()?;
+        let edges = GPIO_V2_LINE_FLAG_EDGE_RISING | GPIO_V2_LINE_FLAG_EDGE_FALLING;
+        handle.reconfigure(self.flags() | edges, None, self.debounce)?;
+        let source = edge::Source::LineEvents(handle.file.try_clone()?);
+        self.callback = Some(edge::watch(
+            source,
+            self.number,
+            handle.get()?,
+            callback,
+            trigger,
+        )?);
+        Ok(())
+    }
+
+    fn unregister_callback(&mut self) -> Result<()> {
+        if self.callback.take().is_some() {
+            self.handle()?
+                .reconfigure(self.flags(), None, self.debounce)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abi_test() {
+        // sizes and request codes of <linux/gpio.h>
+        assert_eq!(mem::size_of::<LineAttribute>(), 16);
+        assert_eq!(mem::size_of::<LineConfig>(), 272);
+        assert_eq!(mem::size_of::<LineRequest>(), 592);
+        assert_eq!(GPIO_V2_GET_LINE_IOCTL, 0xC250_B407);
+        assert_eq!(GPIO_V2_LINE_SET_CONFIG_IOCTL, 0xC110_B40D);
+        assert_eq!(GPIO_V2_LINE_GET_VALUES_IOCTL, 0xC010_B40E);
+        assert_eq!(GPIO_V2_LINE_SET_VALUES_IOCTL, 0xC010_B40F);
+    }
+}
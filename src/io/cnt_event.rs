@@ -0,0 +1,446 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Events of counter inputs. A single thread samples the counters with registered callbacks and
+// detects their events (see `counter::Detector`). Events signaled by the hardware are read from
+// the event chrdev of the Linux counter subsystem (`/dev/counterN`) instead, and the counter is
+// sampled rarely if the hardware signals all registered events.
+//
+// The callbacks are called without holding any lock, so they may access the library.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use nix::poll::{poll, PollFd, PollFlags};
+
+use crate::error::{Error, Result};
+use crate::ffi;
+use crate::io::counter::{Count, Detector};
+use crate::io::util::{io_none, ioctl, iow};
+
+/// Interval to sample counters with events detected in software
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+/// Interval to sample counters of which the hardware signals all registered events
+const IDLE_INTERVAL: Duration = Duration::from_millis(100);
+
+const COUNTER_COMPONENT_COUNT: u8 = 2;
+const COUNTER_SCOPE_COUNT: u8 = 2;
+const COUNTER_EVENT_OVERFLOW: u8 = 0;
+const COUNTER_EVENT_UNDERFLOW: u8 = 1;
+const COUNTER_EVENT_OVERFLOW_UNDERFLOW: u8 = 2;
+const COUNTER_EVENT_DIRECTION_CHANGE: u8 = 7;
+
+/// `struct counter_watch` watching the count value of a count
+#[repr(C)]
+struct CounterWatch {
+    component_type: u8,
+    component_scope: u8,
+    component_parent: u8,
+    component_id: u8,
+    event: u8,
+    channel: u8,
+}
+
+const COUNTER_ADD_WATCH_IOCTL: u64 = iow::<CounterWatch>(0x3e, 0x00);
+const COUNTER_ENABLE_EVENTS_IOCTL: u64 = io_none(0x3e, 0x01);
+
+/// Size of `struct counter_event`
+const EVENT_SIZE: usize = 24;
+/// Offset of the value in `struct counter_event`
+const EVENT_VALUE_OFFSET: usize = 8;
+/// Offset of the event of the watch in `struct counter_event`
+const EVENT_ID_OFFSET: usize = 20;
+/// Offset of the error status in `struct counter_event`
+const EVENT_STATUS_OFFSET: usize = 22;
+
+/// Reads the current count of the hardware and the state of the counter
+pub type Sampler = Box<dyn FnMut() -> Result<(i32, Count)> + Send>;
+
+/// Event chrdev of the Linux counter subsystem
+#[derive(Debug)]
+pub struct Chrdev {
+    file: File,
+    /// Events watched as bits of `ffi::IoCntEvent`
+    events: u8,
+}
+
+/// Device and count of the sysfs directory of a count, e.g.
+/// `/sys/bus/counter/devices/counter0/count0`
+fn device(path: &str) -> Option<(String, u8)> {
+    let mut components = Path::new(path)
+        .components()
+        .rev()
+        .filter_map(|c| c.as_os_str().to_str());
+    let count = components.next()?.strip_prefix("count")?.parse().ok()?;
+    let device = components.next()?;
+    match device.strip_prefix("counter")?.parse::<u32>() {
+        Ok(_) => Some((format!("/dev/{}", device), count)),
+        Err(_) => None,
+    }
+}
+
+impl Chrdev {
+    /// Open the chrdev of the count with the sysfs directory `path` and watch the overflows,
+    /// underflows and direction changes the driver supports
+    pub fn open(path: &str) -> Result<Chrdev> {
+        let (device, count) = device(path).ok_or(Error::NotImplemented)?;
+        let file = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(device)?;
+
+        let add_watch = |event| {
+            let mut watch = CounterWatch {
+                component_type: COUNTER_COMPONENT_COUNT,
+                component_scope: COUNTER_SCOPE_COUNT,
+                component_parent: count,
+                component_id: 0,
+                event,
+                channel: 0,
+            };
+            ioctl(&file, COUNTER_ADD_WATCH_IOCTL, &mut watch).is_ok()
+        };
+
+        let mut events = 0;
+        let overflow = add_watch(COUNTER_EVENT_OVERFLOW);
+        let underflow = add_watch(COUNTER_EVENT_UNDERFLOW);
+        if (overflow && underflow) || add_watch(COUNTER_EVENT_OVERFLOW_UNDERFLOW) {
+            events |= ffi::IoCntEvent::Overflow as u8 | ffi::IoCntEvent::Underflow as u8;
+        }
+        if add_watch(COUNTER_EVENT_DIRECTION_CHANGE) {
+            events |= ffi::IoCntEvent::Direction as u8;
+        }
+        if events == 0 {
+            return Err(Error::NotImplemented);
+        }
+        ioctl(&file, COUNTER_ENABLE_EVENTS_IOCTL, &mut 0)?;
+
+        Ok(Chrdev { file, events })
+    }
+
+    /// Events signaled by the hardware for the state `count` of the counter. The overflows and
+    /// underflows are the ones of the counter only if the hardware does the auto-reload.
+    fn signaled(&self, count: &Count) -> u8 {
+        if count.hardware_reload() {
+            self.events
+        } else {
+            self.events & ffi::IoCntEvent::Direction as u8
+        }
+    }
+
+    /// Read the pending events, returns the events as bits of `ffi::IoCntEvent` and the count
+    /// of the hardware at the event
+    fn read(&mut self) -> Result<Vec<(u8, i32)>> {
+        let mut buffer = [0; EVENT_SIZE * 16];
+        let length = match self.file.read(&mut buffer) {
+            Ok(length) => length,
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => 0,
+            Err(e) => return Err(e.into()),
+        };
+        Ok(buffer[..length]
+            .chunks_exact(EVENT_SIZE)
+            .filter(|event| event[EVENT_STATUS_OFFSET] == 0)
+            .filter_map(|event| {
+                let mut value = [0; 8];
+                value.copy_from_slice(&event[EVENT_VALUE_OFFSET..EVENT_VALUE_OFFSET + 8]);
+                let value = u64::from_ne_bytes(value) as u32 as i32;
+                let event = match event[EVENT_ID_OFFSET] {
+                    COUNTER_EVENT_OVERFLOW => ffi::IoCntEvent::Overflow,
+                    COUNTER_EVENT_UNDERFLOW => ffi::IoCntEvent::Underflow,
+                    // the hardware counts from 0 to its ceiling
+                    COUNTER_EVENT_OVERFLOW_UNDERFLOW if value == 0 => ffi::IoCntEvent::Overflow,
+                    COUNTER_EVENT_OVERFLOW_UNDERFLOW => ffi::IoCntEvent::Underflow,
+                    COUNTER_EVENT_DIRECTION_CHANGE => ffi::IoCntEvent::Direction,
+                    _ => return None,
+                };
+                Some((event as u8, value))
+            })
+            .collect())
+    }
+}
+
+type Callback = extern "C" fn(u8, ffi::IoCntEvent, i32);
+type Call = (Callback, usize, ffi::IoCntEvent, i32);
+
+struct Watch {
+    id: u64,
+    number: usize,
+    sampler: Sampler,
+    chrdev: Option<Chrdev>,
+    detector: Detector,
+    /// Events signaled by the hardware at the last sample
+    signaled: u8,
+    callback: Callback,
+    events: u8,
+}
+
+impl Watch {
+    /// The counter must be sampled in the short interval
+    fn polled(&self) -> bool {
+        self.events & !self.signaled != 0
+    }
+
+    /// Sample the counter and read the events of the chrdev if it is `readable`
+    fn update(&mut self, readable: bool, calls: &mut Vec<Call>) {
+        let mut signaled = vec![];
+        if let (true, Some(chrdev)) = (readable, self.chrdev.as_mut()) {
+            match chrdev.read() {
+                Ok(events) => signaled = events,
+                Err(e) => error!("Failed to read events of counter {}: {}", self.number, e),
+            }
+        }
+
+        let (raw, count) = match (self.sampler)() {
+            Ok(sample) => sample,
+            Err(e) => {
+                error!("Failed to read counter {}: {}", self.number, e);
+                return;
+            }
+        };
+        self.signaled = self
+            .chrdev
+            .as_ref()
+            .map_or(0, |chrdev| chrdev.signaled(&count));
+
+        let mut events: Vec<(u8, i32)> = signaled
+            .into_iter()
+            .map(|(bits, value)| (bits & self.signaled, count.value(value)))
+            .collect();
+        let detected = self.detector.update(raw, &count) & !self.signaled;
+        events.push((detected, count.value(raw)));
+
+        for (bits, value) in events {
+            for event in ffi::IoCntEvent::ALL.iter() {
+                if bits & self.events & *event as u8 != 0 {
+                    calls.push((self.callback, self.number, *event, value));
+                }
+            }
+        }
+    }
+}
+
+struct Watcher {
+    watches: Arc<Mutex<Vec<Watch>>>,
+    /// eventfd to wake up the thread after the watches changed
+    wakeup: Arc<File>,
+}
+
+impl Watcher {
+    fn start() -> Result<Watcher> {
+        let fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+        if fd < 0 {
+            return Err(Error::AccessFailed(io::Error::last_os_error()));
+        }
+        let wakeup = Arc::new(unsafe { File::from_raw_fd(fd) });
+        let watches = Arc::new(Mutex::new(Vec::new()));
+
+        let (watches_cloned, wakeup_cloned) = (Arc::clone(&watches), Arc::clone(&wakeup));
+        thread::Builder::new()
+            .name("cnt-event".to_string())
+            .spawn(move || run(&watches_cloned, &wakeup_cloned))?;
+
+        Ok(Watcher { watches, wakeup })
+    }
+
+    fn wake(&self) {
+        (&*self.wakeup).write_all(&1u64.to_ne_bytes()).ok();
+    }
+}
+
+fn run(watches: &Mutex<Vec<Watch>>, wakeup: &File) {
+    loop {
+        let (ids, mut fds, interval) = {
+            let watches = watches.lock().unwrap();
+            let (ids, fds): (Vec<u64>, Vec<PollFd>) = watches
+                .iter()
+                .filter_map(|watch| {
+                    let chrdev = watch.chrdev.as_ref()?;
+                    Some((
+                        watch.id,
+                        PollFd::new(chrdev.file.as_raw_fd(), PollFlags::POLLIN),
+                    ))
+                })
+                .unzip();
+            let interval = match (watches.is_empty(), watches.iter().any(Watch::polled)) {
+                (true, _) => None,
+                (false, true) => Some(POLL_INTERVAL),
+                (false, false) => Some(IDLE_INTERVAL),
+            };
+            (ids, fds, interval)
+        };
+        fds.push(PollFd::new(wakeup.as_raw_fd(), PollFlags::POLLIN));
+
+        let timeout = interval.map_or(-1, |interval| interval.as_millis() as i32);
+        if poll(&mut fds, timeout).is_err() {
+            continue;
+        }
+        if fds
+            .last()
+            .and_then(|fd| fd.revents())
+            .is_some_and(|r| !r.is_empty())
+        {
+            let mut buffer = [0; 8];
+            (&*wakeup).read_exact(&mut buffer).ok();
+        }
+
+        let mut calls = vec![];
+        {
+            let mut watches = watches.lock().unwrap();
+            for watch in watches.iter_mut() {
+                let readable = ids
+                    .iter()
+                    .zip(fds.iter())
+                    .any(|(id, fd)| *id == watch.id && fd.revents().is_some_and(|r| !r.is_empty()));
+                watch.update(readable, &mut calls);
+            }
+        }
+
+        for (callback, number, event, value) in calls {
+            callback(number as u8, event, value);
+        }
+    }
+}
+
+lazy_static! {
+    static ref WATCHER: Mutex<Option<Watcher>> = Mutex::new(None);
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Registered callback, removed when dropped
+#[derive(Debug)]
+pub struct Registration {
+    id: u64,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Some(watcher) = WATCHER.lock().unwrap().as_ref() {
+            watcher
+                .watches
+                .lock()
+                .unwrap()
+                .retain(|watch| watch.id != self.id);
+            watcher.wake();
+        }
+    }
+}
+
+/// Call `callback` for the `events` (bits of `ffi::IoCntEvent`) of counter `number`, which are
+/// detected from the samples of `sampler` unless signaled by the `chrdev`
+pub fn watch(
+    number: usize,
+    sampler: Sampler,
+    chrdev: Option<Chrdev>,
+    callback: ffi::IoCntCallback,
+    events: u8,
+) -> Result<Registration> {
+    let callback = callback.ok_or(Error::InvalidParameter)?;
+
+    let mut watcher = WATCHER.lock().unwrap();
+    if watcher.is_none() {
+        *watcher = Some(Watcher::start()?);
+    }
+    let watcher = watcher.as_ref().unwrap();
+
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    watcher.watches.lock().unwrap().push(Watch {
+        id,
+        number,
+        sampler,
+        chrdev,
+        detector: Detector::default(),
+        signaled: 0,
+        callback,
+        events,
+    });
+    watcher.wake();
+
+    Ok(Registration { id })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicI32;
+    use std::time::Instant;
+
+    lazy_static! {
+        static ref CALLS: Mutex<Vec<(u8, ffi::IoCntEvent, i32)>> = Mutex::new(vec![]);
+    }
+
+    extern "C" fn callback(number: u8, event: ffi::IoCntEvent, value: i32) {
+        CALLS.lock().unwrap().push((number, event, value));
+    }
+
+    fn wait_for_calls(count: usize) {
+        let start = Instant::now();
+        while CALLS.lock().unwrap().len() < count && start.elapsed() < Duration::from_secs(1) {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    fn event(id: u8, value: u64) -> [u8; EVENT_SIZE] {
+        let mut event = [0; EVENT_SIZE];
+        event[EVENT_VALUE_OFFSET..EVENT_VALUE_OFFSET + 8].copy_from_slice(&value.to_ne_bytes());
+        event[EVENT_ID_OFFSET] = id;
+        event
+    }
+
+    #[test]
+    fn device_test() {
+        assert_eq!(
+            device("/sys/bus/counter/devices/counter0/count1/"),
+            Some(("/dev/counter0".to_string(), 1))
+        );
+        assert_eq!(device("/sys/devices/soc0/soc/30650000.flextimer/"), None);
+    }
+
+    #[test]
+    fn cnt_event_test() {
+        static RAW: AtomicI32 = AtomicI32::new(0);
+
+        // counting from 0 to 9 in hardware, which signals overflows and underflows
+        let mut count = Count::default();
+        count.set_compare(Some(9), true).unwrap();
+        count.set_hardware_reload(true);
+        let sampler: Sampler = Box::new(move || Ok((RAW.load(Ordering::SeqCst), count.clone())));
+        let (read, write) = nix::unistd::pipe().unwrap();
+        let mut events = unsafe { File::from_raw_fd(write) };
+        let chrdev = Chrdev {
+            file: unsafe { File::from_raw_fd(read) },
+            events: ffi::IoCntEvent::Overflow as u8 | ffi::IoCntEvent::Underflow as u8,
+        };
+        let mask = ffi::IoCntEvent::Compare as u8 | ffi::IoCntEvent::Overflow as u8;
+        let registration = watch(2, sampler, Some(chrdev), Some(callback), mask).unwrap();
+
+        thread::sleep(Duration::from_millis(20));
+        RAW.store(9, Ordering::SeqCst);
+        wait_for_calls(1);
+        assert_eq!(*CALLS.lock().unwrap(), [(2, ffi::IoCntEvent::Compare, 9)]);
+
+        // the overflow is signaled by the hardware only
+        RAW.store(1, Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(CALLS.lock().unwrap().len(), 1);
+        events.write_all(&event(COUNTER_EVENT_OVERFLOW, 0)).unwrap();
+        wait_for_calls(2);
+        assert_eq!(CALLS.lock().unwrap()[1], (2, ffi::IoCntEvent::Overflow, 0));
+
+        // underflows are not registered
+        events
+            .write_all(&event(COUNTER_EVENT_UNDERFLOW, 9))
+            .unwrap();
+        drop(registration);
+        RAW.store(9, Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(CALLS.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,364 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Preload and compare value of counters. The drivers write the preload and the ceiling for the
+// auto-reload to the hardware where it is supported. Otherwise `Count` applies them to the
+// count read from the hardware in software.
+//
+// `Detector` detects the events of a counter from the counts sampled from the hardware.
+//
+// Without an index input of the hardware, the index pulse is the edge of a digital input handled
+// by `index_handler`. The edges of evdev inputs have the time of the kernel event.
+
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::error::{Error, Result};
+use crate::ffi;
+use crate::EdgeHandler;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Count {
+    preload: i32,
+    /// Added to the hardware count, if the preload could not be written to the hardware
+    offset: i32,
+    compare: Option<i32>,
+    auto_reload: bool,
+    /// The hardware reloads the counter at its ceiling
+    hardware_reload: bool,
+    /// Number of times the preload was loaded
+    loads: u32,
+}
+
+/// Parse the count of a sysfs attribute, unsigned 32 bit counts wrap around to negative values
+pub fn parse(value: &str) -> Result<i32> {
+    let value = value.trim();
+    match value.parse::<i32>() {
+        Ok(value) => Ok(value),
+        Err(_) => value
+            .parse::<u32>()
+            .map(|value| value as i32)
+            .map_err(|_| Error::generic_access_error()),
+    }
+}
+
+impl Count {
+    pub fn preload(&self) -> i32 {
+        self.preload
+    }
+
+    pub fn set_preload(&mut self, preload: i32) -> Result<()> {
+        if self.auto_reload && matches!(self.compare, Some(compare) if compare <= preload) {
+            return Err(Error::InvalidParameter);
+        }
+        self.preload = preload;
+        Ok(())
+    }
+
+    /// The preload was written to the hardware
+    pub fn loaded(&mut self) {
+        self.offset = 0;
+        self.loads = self.loads.wrapping_add(1);
+    }
+
+    /// Load the preload in software, `raw` is the current count of the hardware
+    pub fn load(&mut self, raw: i32) {
+        self.offset = self.preload.wrapping_sub(raw);
+        self.loads = self.loads.wrapping_add(1);
+    }
+
+    pub fn compare(&self) -> Option<i32> {
+        self.compare
+    }
+
+    /// Set the compare value. With `auto_reload` the counter continues at the preload after it
+    /// passed the compare value counting up, and at the compare value after it passed the
+    /// preload counting down.
+    pub fn set_compare(&mut self, compare: Option<i32>, auto_reload: bool) -> Result<()> {
+        match compare {
+            Some(compare) if auto_reload && compare <= self.preload => Err(Error::InvalidParameter),
+            None if auto_reload => Err(Error::InvalidParameter),
+            _ => {
+                self.compare = compare;
+                self.auto_reload = auto_reload;
+                Ok(())
+            }
+        }
+    }
+
+    /// Ceiling of a hardware counter which counts from 0 to its ceiling, if it can do the
+    /// auto-reload
+    pub fn ceiling(&self) -> Option<u32> {
+        match self.compare {
+            Some(compare) if self.auto_reload && self.preload == 0 && self.offset == 0 => {
+                Some(compare as u32)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the auto-reload is done by the hardware, see `ceiling()`
+    pub fn set_hardware_reload(&mut self, hardware_reload: bool) {
+        self.hardware_reload = hardware_reload;
+    }
+
+    pub fn hardware_reload(&self) -> bool {
+        self.hardware_reload
+    }
+
+    /// First value and number of values of the counter, it wraps around at the ends
+    fn range(&self) -> (i64, i64) {
+        match self.compare {
+            Some(compare) if self.auto_reload => {
+                let preload = i64::from(self.preload);
+                (preload, i64::from(compare) - preload + 1)
+            }
+            _ => (i64::from(i32::MIN), 1 << 32),
+        }
+    }
+
+    /// Counts of the hardware from `last` to `raw`, the shorter way around
+    fn delta(&self, last: i32, raw: i32) -> i64 {
+        let modulus = match self.compare {
+            Some(compare) if self.auto_reload && self.hardware_reload => i64::from(compare) + 1,
+            _ => 1 << 32,
+        };
+        let delta = (i64::from(raw) - i64::from(last)).rem_euclid(modulus);
+        if delta > modulus / 2 {
+            delta - modulus
+        } else {
+            delta
+        }
+    }
+
+    /// Value of the counter for the count `raw` of the hardware
+    pub fn value(&self, raw: i32) -> i32 {
+        let value = raw.wrapping_add(self.offset);
+        match self.compare {
+            Some(compare) if self.auto_reload && !self.hardware_reload => {
+                let preload = i64::from(self.preload);
+                let range = i64::from(compare) - preload + 1;
+                (preload + (i64::from(value) - preload).rem_euclid(range)) as i32
+            }
+            _ => value,
+        }
+    }
+}
+
+/// Value of a counter captured on the index pulse
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Capture {
+    pub value: i32,
+    /// Time of the index pulse
+    pub timestamp: SystemTime,
+}
+
+/// Handler of the edges of a digital input as index pulse of a counter. `reset` loads the preload,
+/// `read` reads the value to capture into `captured`.
+pub fn index_handler<R, V>(
+    mode: ffi::IoCntIndexMode,
+    trigger: ffi::IoInputTrigger,
+    captured: Arc<Mutex<Option<Capture>>>,
+    mut reset: R,
+    mut read: V,
+) -> EdgeHandler
+where
+    R: FnMut() -> Result<()> + Send + 'static,
+    V: FnMut() -> Result<i32> + Send + 'static,
+{
+    Box::new(move |value, timestamp| {
+        if !trigger.matches(value) {
+            return;
+        }
+        let result = match mode {
+            ffi::IoCntIndexMode::Off => Ok(()),
+            ffi::IoCntIndexMode::Reset => reset(),
+            ffi::IoCntIndexMode::Capture => read().map(|value| {
+                *captured.lock().unwrap() = Some(Capture { value, timestamp });
+            }),
+        };
+        if let Err(e) = result {
+            error!("Failed to handle the index pulse: {}", e);
+        }
+    })
+}
+
+/// Detects the events of a counter from its counts sampled often enough to not miss more than
+/// half of its range
+#[derive(Debug, Default)]
+pub struct Detector {
+    /// Last count of the hardware and the state of the counter at that time
+    last: Option<(i32, Count)>,
+    up: Option<bool>,
+}
+
+impl Detector {
+    /// Update with the current count `raw` of the hardware, returns the events since the last
+    /// update as bits of `ffi::IoCntEvent`. No events are detected after the state of the counter
+    /// changed, e.g. the preload was loaded.
+    pub fn update(&mut self, raw: i32, count: &Count) -> u8 {
+        let last = match self.last.replace((raw, count.clone())) {
+            Some((last, ref last_count)) if last_count == count => last,
+            _ => return 0,
+        };
+        let delta = count.delta(last, raw);
+        if delta == 0 {
+            return 0;
+        }
+
+        let mut events = 0;
+        let up = delta > 0;
+        if self.up.replace(up).is_some_and(|last_up| last_up != up) {
+            events |= ffi::IoCntEvent::Direction as u8;
+        }
+
+        // positions relative to the first value, without wrapping around
+        let (start, length) = count.range();
+        let from = i64::from(count.value(last)) - start;
+        let to = from + delta;
+        if to >= length {
+            events |= ffi::IoCntEvent::Overflow as u8;
+        } else if to < 0 {
+            events |= ffi::IoCntEvent::Underflow as u8;
+        }
+
+        // the compare value is reached, if it is in (from, to] counting up or [to, from)
+        // counting down (modulo the range)
+        if let Some(compare) = count.compare {
+            let target = i64::from(compare) - start;
+            let reached = if up {
+                (to - target).div_euclid(length) > (from - target).div_euclid(length)
+            } else {
+                (from - 1 - target).div_euclid(length) > (to - 1 - target).div_euclid(length)
+            };
+            if reached {
+                events |= ffi::IoCntEvent::Compare as u8;
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_test() {
+        assert_eq!(parse("42\n").unwrap(), 42);
+        assert_eq!(parse("4294967295").unwrap(), -1);
+        assert!(parse("x").is_err());
+
+        let mut count = Count::default();
+        count.set_preload(100).unwrap();
+        count.load(7);
+        assert_eq!(count.value(7), 100);
+        assert_eq!(count.value(2), 95);
+        assert!(count.set_compare(Some(100), true).is_err());
+        assert!(count.set_compare(None, true).is_err());
+
+        // count from 100 to 109 in software
+        count.set_compare(Some(109), true).unwrap();
+        assert_eq!(count.ceiling(), None);
+        assert_eq!(count.value(16), 109);
+        assert_eq!(count.value(17), 100);
+        assert_eq!(count.value(6), 109);
+        assert!(count.set_preload(110).is_err());
+
+        count.set_compare(Some(109), false).unwrap();
+        assert_eq!(count.value(17), 110);
+
+        // count from 0 to 9 in hardware
+        count.set_preload(0).unwrap();
+        count.loaded();
+        count.set_compare(Some(9), true).unwrap();
+        assert_eq!(count.ceiling(), Some(9));
+        count.set_hardware_reload(true);
+        assert_eq!(count.value(12), 12);
+    }
+
+    #[test]
+    fn detector_test() {
+        const COMPARE: u8 = ffi::IoCntEvent::Compare as u8;
+        const OVERFLOW: u8 = ffi::IoCntEvent::Overflow as u8;
+        const UNDERFLOW: u8 = ffi::IoCntEvent::Underflow as u8;
+        const DIRECTION: u8 = ffi::IoCntEvent::Direction as u8;
+
+        let mut count = Count::default();
+        let mut detector = Detector::default();
+        assert_eq!(detector.update(0, &count), 0);
+        count.set_compare(Some(5), false).unwrap();
+        assert_eq!(detector.update(4, &count), 0);
+        assert_eq!(detector.update(5, &count), COMPARE);
+        assert_eq!(detector.update(8, &count), 0);
+        assert_eq!(detector.update(8, &count), 0);
+        assert_eq!(detector.update(3, &count), COMPARE | DIRECTION);
+        assert_eq!(detector.update(i32::MAX, &count), COMPARE | DIRECTION);
+        assert_eq!(detector.update(i32::MIN + 1, &count), OVERFLOW);
+        assert_eq!(detector.update(i32::MAX, &count), UNDERFLOW | DIRECTION);
+
+        // counting from 10 to 19 in software, loaded at the count 100 of the hardware
+        count.set_compare(None, false).unwrap();
+        count.set_preload(10).unwrap();
+        count.set_compare(Some(19), true).unwrap();
+        count.load(100);
+        assert_eq!(detector.update(100, &count), 0);
+        assert_eq!(detector.update(108, &count), DIRECTION);
+        assert_eq!(detector.update(109, &count), COMPARE);
+        assert_eq!(detector.update(110, &count), OVERFLOW);
+        assert_eq!(detector.update(119, &count), COMPARE);
+        assert_eq!(detector.update(111, &count), DIRECTION);
+        assert_eq!(detector.update(109, &count), COMPARE | UNDERFLOW);
+
+        // counting from 0 to 9 in hardware
+        count.set_compare(None, false).unwrap();
+        count.set_preload(0).unwrap();
+        count.loaded();
+        count.set_compare(Some(9), true).unwrap();
+        count.set_hardware_reload(true);
+        assert_eq!(detector.update(8, &count), 0);
+        assert_eq!(detector.update(1, &count), COMPARE | OVERFLOW | DIRECTION);
+        assert_eq!(detector.update(9, &count), COMPARE | UNDERFLOW | DIRECTION);
+    }
+
+    #[test]
+    fn index_test() {
+        let captured = Arc::new(Mutex::new(None));
+        let resets = Arc::new(Mutex::new(0));
+        let resets_cloned = Arc::clone(&resets);
+        let mut handler = index_handler(
+            ffi::IoCntIndexMode::Reset,
+            ffi::IoInputTrigger::RisingEdge,
+            Arc::clone(&captured),
+            move || {
+                *resets_cloned.lock().unwrap() += 1;
+                Ok(())
+            },
+            || Ok(42),
+        );
+        let timestamp = SystemTime::now();
+        handler(true, timestamp);
+        handler(false, timestamp);
+        assert_eq!(*resets.lock().unwrap(), 1);
+        assert_eq!(*captured.lock().unwrap(), None);
+
+        let mut handler = index_handler(
+            ffi::IoCntIndexMode::Capture,
+            ffi::IoInputTrigger::FallingEdge,
+            Arc::clone(&captured),
+            || Err(Error::NotImplemented),
+            || Ok(42),
+        );
+        handler(true, timestamp);
+        assert_eq!(*captured.lock().unwrap(), None);
+        handler(false, timestamp);
+        assert_eq!(
+            *captured.lock().unwrap(),
+            Some(Capture {
+                value: 42,
+                timestamp
+            })
+        );
+    }
+}
@@ -0,0 +1,290 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Interrupt driven edge detection for GPIO inputs. A single thread waits with poll() for line
+// events of character device lines (`io::cdev`) and for interrupts signaled on sysfs value files
+// with the `edge` attribute set (`io::sysfs`). Callbacks are called on changes of the input with
+// the trigger semantics of the evdev collector (see `ffi::IoInputTrigger::matches`).
+//
+// The callbacks are called without holding any lock, so they may access the library.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use nix::poll::{poll, PollFd, PollFlags};
+
+use crate::error::{Error, Result};
+use crate::ffi;
+
+/// Size of `struct gpio_v2_line_event`
+const LINE_EVENT_SIZE: usize = 48;
+/// Offset of the event id in `struct gpio_v2_line_event`
+const LINE_EVENT_ID_OFFSET: usize = 8;
+const GPIO_V2_LINE_EVENT_RISING_EDGE: u32 = 1;
+
+/// File descriptor signaling interrupts
+#[derive(Debug)]
+pub enum Source {
+    /// Line request of a character device with edge detection enabled
+    LineEvents(File),
+    /// Value file of an exported sysfs GPIO with the `edge` attribute set
+    SysfsValue(File),
+}
+
+impl Source {
+    fn file(&self) -> &File {
+        match self {
+            Source::LineEvents(file) | Source::SysfsValue(file) => file,
+        }
+    }
+
+    fn poll_flags(&self) -> PollFlags {
+        match self {
+            Source::LineEvents(_) => PollFlags::POLLIN,
+            Source::SysfsValue(_) => PollFlags::POLLPRI | PollFlags::POLLERR,
+        }
+    }
+
+    /// Read the pending events, returns the new (logical) values in order
+    fn read(&mut self) -> Result<Vec<bool>> {
+        match self {
+            Source::LineEvents(file) => {
+                let mut buffer = [0; LINE_EVENT_SIZE * 16];
+                let length = file.read(&mut buffer)?;
+                Ok(buffer[..length]
+                    .chunks_exact(LINE_EVENT_SIZE)
+                    .map(|event| {
+                        let mut id = [0; 4];
+                        id.copy_from_slice(&event[LINE_EVENT_ID_OFFSET..LINE_EVENT_ID_OFFSET + 4]);
+                        u32::from_ne_bytes(id) == GPIO_V2_LINE_EVENT_RISING_EDGE
+                    })
+                    .collect())
+            }
+            Source::SysfsValue(file) => {
+                let mut buffer = [0; 1];
+                file.seek(SeekFrom::Start(0))?;
+                file.read_exact(&mut buffer)?;
+                Ok(vec![buffer[0] != b'0'])
+            }
+        }
+    }
+}
+
+struct Watch {
+    id: u64,
+    source: Source,
+    number: usize,
+    value: bool,
+    callback: extern "C" fn(u8, ffi::IoBool),
+    trigger: ffi::IoInputTrigger,
+}
+
+struct Watcher {
+    watches: Arc<Mutex<Vec<Watch>>>,
+    /// eventfd to wake up the thread after the watches changed
+    wakeup: Arc<File>,
+}
+
+impl Watcher {
+    fn start() -> Result<Watcher> {
+        let fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+        if fd < 0 {
+            return Err(Error::AccessFailed(io::Error::last_os_error()));
+        }
+        let wakeup = Arc::new(unsafe { File::from_raw_fd(fd) });
+        let watches = Arc::new(Mutex::new(Vec::new()));
+
+        let (watches_cloned, wakeup_cloned) = (Arc::clone(&watches), Arc::clone(&wakeup));
+        thread::Builder::new()
+            .name("gpio-edge".to_string())
+            .spawn(move || run(&watches_cloned, &wakeup_cloned))?;
+
+        Ok(Watcher { watches, wakeup })
+    }
+
+    fn wake(&self) {
+        (&*self.wakeup).write_all(&1u64.to_ne_bytes()).ok();
+    }
+}
+
+fn run(watches: &Mutex<Vec<Watch>>, wakeup: &File) {
+    loop {
+        let (ids, mut fds): (Vec<u64>, Vec<PollFd>) = watches
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|watch| {
+                let fd: RawFd = watch.source.file().as_raw_fd();
+                (watch.id, PollFd::new(fd, watch.source.poll_flags()))
+            })
+            .unzip();
+        fds.push(PollFd::new(wakeup.as_raw_fd(), PollFlags::POLLIN));
+
+        if poll(&mut fds, -1).is_err() {
+            continue;
+        }
+        if fds
+            .last()
+            .and_then(|fd| fd.revents())
+            .is_some_and(|r| !r.is_empty())
+        {
+            let mut buffer = [0; 8];
+            (&*wakeup).read_exact(&mut buffer).ok();
+        }
+
+        let mut calls = vec![];
+        {
+            let mut watches = watches.lock().unwrap();
+            for (id, fd) in ids.iter().zip(fds.iter()) {
+                if fd.revents().map_or(true, |r| r.is_empty()) {
+                    continue;
+                }
+                let watch = match watches.iter_mut().find(|watch| watch.id == *id) {
+                    Some(watch) => watch,
+                    // unregistered in the meantime
+                    None => continue,
+                };
+                let values = match watch.source.read() {
+                    Ok(values) => values,
+                    Err(e) => {
+                        error!("Failed to read input {}: {}", watch.number, e);
+                        continue;
+                    }
+                };
+                for value in values {
+                    if value != watch.value {
+                        watch.value = value;
+                        if watch.trigger.matches(value) {
+                            calls.push((watch.callback, watch.number, value));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (callback, number, value) in calls {
+            callback(number as u8, value.into());
+        }
+    }
+}
+
+lazy_static! {
+    static ref WATCHER: Mutex<Option<Watcher>> = Mutex::new(None);
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Registered callback, removed when dropped
+#[derive(Debug)]
+pub struct Registration {
+    id: u64,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Some(watcher) = WATCHER.lock().unwrap().as_ref() {
+            watcher
+                .watches
+                .lock()
+                .unwrap()
+                .retain(|watch| watch.id != self.id);
+            watcher.wake();
+        }
+    }
+}
+
+/// Call `callback` for input `number` on changes of `source`, `value` is the current value
+pub fn watch(
+    source: Source,
+    number: usize,
+    value: bool,
+    callback: ffi::IoInputCallback,
+    trigger: ffi::IoInputTrigger,
+) -> Result<Registration> {
+    let callback = callback.ok_or(Error::InvalidParameter)?;
+
+    let mut watcher = WATCHER.lock().unwrap();
+    if watcher.is_none() {
+        *watcher = Some(Watcher::start()?);
+    }
+    let watcher = watcher.as_ref().unwrap();
+
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    watcher.watches.lock().unwrap().push(Watch {
+        id,
+        source,
+        number,
+        value,
+        callback,
+        trigger,
+    });
+    watcher.wake();
+
+    Ok(Registration { id })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::{Duration, Instant};
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    extern "C" fn callback(number: u8, value: ffi::IoBool) {
+        assert_eq!(number, 3);
+        assert!(matches!(value, ffi::IoBool::True));
+        CALLS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn event(rising: bool) -> [u8; LINE_EVENT_SIZE] {
+        let mut event = [0; LINE_EVENT_SIZE];
+        let id: u32 = if rising { 1 } else { 2 };
+        event[LINE_EVENT_ID_OFFSET..LINE_EVENT_ID_OFFSET + 4].copy_from_slice(&id.to_ne_bytes());
+        event
+    }
+
+    fn wait_for_calls(count: usize) {
+        let start = Instant::now();
+        while CALLS.load(Ordering::SeqCst) < count && start.elapsed() < Duration::from_secs(1) {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn edge_test() {
+        // a pipe delivers the line events
+        let (read, write) = nix::unistd::pipe().unwrap();
+        let mut events = unsafe { File::from_raw_fd(write) };
+        let source = Source::LineEvents(unsafe { File::from_raw_fd(read) });
+        let registration = watch(
+            source,
+            3,
+            false,
+            Some(callback),
+            ffi::IoInputTrigger::RisingEdge,
+        )
+        .unwrap();
+
+        events.write_all(&event(true)).unwrap();
+        events.write_all(&event(false)).unwrap();
+        wait_for_calls(1);
+        // multiple events are handled by a single read
+        events
+            .write_all(&[event(true), event(false), event(true)].concat())
+            .unwrap();
+        wait_for_calls(3);
+        assert_eq!(CALLS.load(Ordering::SeqCst), 3);
+
+        drop(registration);
+        // the read end may already be closed
+        events.write_all(&event(false)).ok();
+        events.write_all(&event(true)).ok();
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(CALLS.load(Ordering::SeqCst), 3);
+    }
+}
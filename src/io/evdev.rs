@@ -3,7 +3,8 @@
 
 use std::cell::RefCell;
 use std::fmt;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
 use std::thread;
 
 pub use evdev::KeyCode;
@@ -13,7 +14,7 @@ use parking_lot::ReentrantMutex;
 use crate::error::{Error, Result};
 use crate::ffi;
 use crate::io::util::PairMap;
-use crate::{DigitalInput, IoChannel};
+use crate::{DigitalInput, EdgeHandler, EdgeWatch, IoChannel};
 
 pub struct EvdevCollector {
     gpios: Arc<Mutex<PairMap<u16, Vec<DiInnerSync>>>>,
@@ -68,8 +69,16 @@ impl EvdevCollector {
                                     Polarity::ActiveLow => true,
                                     Polarity::ActiveHigh => false,
                                 };
+                                let old_value = gpio.value;
                                 gpio.value = new_value ^ pol;
 
+                                if gpio.value != old_value {
+                                    let (value, timestamp) = (gpio.value, event.timestamp());
+                                    for (_, handler) in gpio.edge_handlers.iter_mut() {
+                                        handler(value, timestamp);
+                                    }
+                                }
+
                                 // ensure we to not borrow the gpio twice, since this would panic -> drop it!
                                 // (see documentation on RefCell)
                                 // this solves problems when calling function on inputs while the callback
@@ -83,14 +92,7 @@ impl EvdevCollector {
                                 drop(gpio);
 
                                 if let Some(Some(callback)) = callback {
-                                    let should_call = match trigger {
-                                        ffi::IoInputTrigger::None => false,
-                                        ffi::IoInputTrigger::RisingEdge => new_value,
-                                        ffi::IoInputTrigger::FallingEdge => !new_value,
-                                        ffi::IoInputTrigger::BothEdge => true,
-                                    };
-
-                                    if should_call {
+                                    if trigger.matches(new_value) {
                                         callback(number as u8, new_value.into());
                                     }
                                 }
@@ -145,10 +147,35 @@ struct DiInner {
     callback: Option<ffi::IoInputCallback>,
     trigger: ffi::IoInputTrigger,
     polarity: Polarity,
+    edge_handlers: Vec<(u64, EdgeHandler)>,
 }
 
 type DiInnerSync = Arc<ReentrantMutex<RefCell<DiInner>>>;
 
+static NEXT_EDGE_HANDLER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Removes an edge handler from the input when dropped
+struct EdgeHandlerGuard {
+    inner: Weak<ReentrantMutex<RefCell<DiInner>>>,
+    id: u64,
+}
+
+impl fmt::Debug for EdgeHandlerGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "evdev/EdgeHandler({})", self.id)
+    }
+}
+
+impl Drop for EdgeHandlerGuard {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.upgrade() {
+            let lock = inner.lock();
+            let mut _self = lock.borrow_mut();
+            _self.edge_handlers.retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
 pub struct Di {
     inner: DiInnerSync,
 }
@@ -167,6 +194,7 @@ impl Di {
             callback: None,
             trigger: ffi::IoInputTrigger::None,
             polarity,
+            edge_handlers: Vec::new(),
         })));
         collector.register(key, gpio.clone()).unwrap();
         Di { inner: gpio }
@@ -221,4 +249,16 @@ impl DigitalInput for Di {
         _self.trigger = ffi::IoInputTrigger::None;
         Ok(())
     }
+
+    fn watch_edges(&mut self, handler: EdgeHandler) -> Result<EdgeWatch> {
+        let lock = self.inner.lock();
+        let mut _self = lock.borrow_mut();
+
+        let id = NEXT_EDGE_HANDLER_ID.fetch_add(1, Ordering::Relaxed);
+        _self.edge_handlers.push((id, handler));
+        Ok(Box::new(EdgeHandlerGuard {
+            inner: Arc::downgrade(&self.inner),
+            id,
+        }))
+    }
 }
@@ -13,9 +13,10 @@ use ini::Ini;
 use crate::convert::tc;
 use crate::error::{Error, Result};
 use crate::ffi;
+use crate::health;
 use crate::io::util;
 use crate::io::util::PairMap;
-use crate::periodic::Periodic;
+use crate::scheduler::TaskConfig;
 use crate::shm;
 use crate::{AnalogInput, AnalogOutput, IoChannel, TempSensor};
 
@@ -99,45 +100,42 @@ impl<T: 'static + Getter<Out = T> + Send + Copy + std::fmt::Debug> SamplerInner<
 
         let name_cloned = name.to_owned();
 
-        thread::Builder::new()
-            .name(name_cloned.to_owned())
-            .spawn(move || {
-                let ctx = iio::Context::create_local().unwrap();
-                let dev = ctx.find_device(&name_cloned).unwrap();
+        let task = TaskConfig::from_env(name, poll_time).spawn_or_fallback(move |task| {
+            let ctx = iio::Context::create_local().unwrap();
+            let dev = ctx.find_device(&name_cloned).unwrap();
 
-                // this iio device cannot be accessed via iio index (libiio API) therefore we sort
-                // the channels by their name (e.g. 'voltage0', 'voltage1', ...)
-                let mut channels: Vec<iio::channel::Channel> = dev.channels().collect();
+            // this iio device cannot be accessed via iio index (libiio API) therefore we sort
+            // the channels by their name (e.g. 'voltage0', 'voltage1', ...)
+            let mut channels: Vec<iio::channel::Channel> = dev.channels().collect();
 
-                // Some devices behave different than others. This is a heuristic
-                // workaround to try to be compatible with all iio device implementations
-                // and should be investigated.
-                if channels[0].id().is_some() {
-                    channels
-                        .sort_by(|x, y| human_sort::compare(&x.id().unwrap(), &y.id().unwrap()));
-                } else {
-                    channels.sort_by_key(|x| x.index().unwrap());
-                }
+            // Some devices behave different than others. This is a heuristic
+            // workaround to try to be compatible with all iio device implementations
+            // and should be investigated.
+            if channels[0].id().is_some() {
+                channels.sort_by(|x, y| human_sort::compare(&x.id().unwrap(), &y.id().unwrap()));
+            } else {
+                channels.sort_by_key(|x| x.index().unwrap());
+            }
 
-                let mut interval = Periodic::new(poll_time);
-                loop {
-                    interval.next();
-
-                    for (index, value) in values_cloned.lock().unwrap().iter_mut() {
-                        if let Some(channel) = channels.get(*index) {
-                            trace!("{} => {:?}", index, value);
-                            *value = Some(T::read(channel));
-                        } else {
-                            error!("invalid channel");
-                        }
+            while task.wait() {
+                for (index, value) in values_cloned.lock().unwrap().iter_mut() {
+                    if let Some(channel) = channels.get(*index) {
+                        trace!("{} => {:?}", index, value);
+                        *value = Some(T::read(channel));
+                    } else {
+                        error!("invalid channel");
                     }
+                }
 
-                    tx.send(shm::Event::Update).ok();
+                tx.send(shm::Event::Update).ok();
 
-                    debug!("{}: {:?}", &name_cloned, interval.elapsed())
-                }
-            })
-            .unwrap();
+                health::sampler_cycle(task.elapsed());
+                debug!("{}: {:?}", &name_cloned, task.elapsed())
+            }
+        });
+        if let Err(err) = task {
+            error!("{}: failed to start sampling: {}", name, err);
+        }
 
         SamplerInner {
             values,
@@ -212,6 +210,7 @@ impl Getter for i64 {
             Ok(value) => value,
             Err(e) => {
                 error!("iio read failed: {} [{:?}]", e, thread::current().name());
+                health::sampler_read_error();
                 0
             }
         }
@@ -225,6 +224,7 @@ impl Getter for f64 {
             Ok(value) => value,
             Err(e) => {
                 error!("iio read failed: {} [{:?}]", e, thread::current().name());
+                health::sampler_read_error();
                 0.0
             }
         }
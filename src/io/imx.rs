@@ -3,22 +3,38 @@
 
 use std::fs;
 use std::path;
+use std::sync::{Arc, Mutex, MutexGuard};
 
 use crate::error::{Error, Result};
 use crate::ffi;
-use crate::{CounterInput, DigitalInput, IoChannel};
+use crate::io::cnt_event;
+use crate::io::counter::{self, Capture, Count};
+use crate::{CounterInput, DigitalInput, EdgeWatch, IoChannel};
 
+// The FlexTimer driver has no events, they are detected by sampling the count. It has no index
+// input either, the index pulse is the edge of a digital input.
+
+/// State of the counter shared with the sampler of the events and the handler of the index pulse
 #[derive(Debug)]
-pub struct Counter {
+struct Channel {
     path: &'static str,
-    mode: ffi::IoCntMode,
     trigger: ffi::IoCntTrigger,
     dir: ffi::IoCntDirection,
-    preload: i32,
+    count: Count,
     input: Box<dyn DigitalInput>,
     direction_pin: Option<Box<dyn DigitalInput>>,
 }
 
+#[derive(Debug)]
+pub struct Counter {
+    mode: ffi::IoCntMode,
+    channel: Arc<Mutex<Channel>>,
+    number: usize,
+    events: Option<cnt_event::Registration>,
+    index: Option<EdgeWatch>,
+    captured: Arc<Mutex<Option<Capture>>>,
+}
+
 impl Counter {
     pub fn new(
         path: &'static str,
@@ -26,34 +42,106 @@ impl Counter {
         direction_pin: Option<Box<dyn DigitalInput>>,
     ) -> Counter {
         Counter {
-            path,
             mode: ffi::IoCntMode::Counter,
-            trigger: ffi::IoCntTrigger::RisingEdge,
-            dir: ffi::IoCntDirection::Up,
-            preload: 0,
-            input,
-            direction_pin,
+            channel: Arc::new(Mutex::new(Channel {
+                path,
+                trigger: ffi::IoCntTrigger::RisingEdge,
+                dir: ffi::IoCntDirection::Up,
+                count: Count::default(),
+                input,
+                direction_pin,
+            })),
+            number: 0,
+            events: None,
+            index: None,
+            captured: Arc::new(Mutex::new(None)),
         }
     }
+
+    fn channel(&self) -> MutexGuard<'_, Channel> {
+        self.channel.lock().unwrap()
+    }
+}
+
+impl Channel {
+    /// Count of the hardware, on any edge it is doubled and corrected by the current input level
+    fn read_count(&mut self) -> Result<i32> {
+        let path = format!("{}/value", self.path);
+        let mut value = counter::parse(&fs::read_to_string(path)?)?;
+
+        use ffi::IoCntTrigger::*;
+        match self.trigger {
+            RisingEdge => {}
+            FallingEdge => {}
+            AnyEdge => {
+                value = value.wrapping_mul(2);
+
+                let input = self.input.get().unwrap_or(false);
+
+                let direction = match &mut self.direction_pin {
+                    None => false,
+                    Some(direction_pin) => direction_pin.get().unwrap_or(false),
+                };
+
+                use ffi::IoCntDirection::*;
+                let inc = match self.dir {
+                    Up => 1,
+                    Down => -1,
+                };
+
+                if input {
+                    if !direction {
+                        value += inc;
+                    } else {
+                        value -= inc;
+                    }
+                }
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Load the preload into the hardware, or in software if the value cannot be written (e.g.
+    /// counting on any edge)
+    fn load(&mut self) -> Result<()> {
+        let path = format!("{}/value", self.path);
+        let written = !matches!(self.trigger, ffi::IoCntTrigger::AnyEdge)
+            && fs::write(path, (self.count.preload() as u32).to_string()).is_ok();
+        if written {
+            self.count.loaded();
+        } else {
+            let raw = self.read_count()?;
+            self.count.load(raw);
+        }
+        Ok(())
+    }
 }
 
 impl IoChannel for Counter {
-    fn init(&mut self, _chan_number: usize) -> Result<()> {
-        if !path::Path::new(self.path).exists() {
+    fn init(&mut self, chan_number: usize) -> Result<()> {
+        let mut channel = self.channel();
+        if !path::Path::new(channel.path).exists() {
             return Err(Error::generic_access_error());
         }
 
-        self.input.init(0)?;
-        if let Some(ref mut direction_pin) = self.direction_pin {
+        channel.input.init(0)?;
+        if let Some(ref mut direction_pin) = channel.direction_pin {
             direction_pin.init(0)?;
         }
+        drop(channel);
+
+        self.number = chan_number;
 
         Ok(())
     }
 
     fn shutdown(&mut self) -> Result<()> {
-        self.input.shutdown()?;
-        if let Some(ref mut direction_pin) = self.direction_pin {
+        self.events = None;
+        self.index = None;
+        let mut channel = self.channel();
+        channel.input.shutdown()?;
+        if let Some(ref mut direction_pin) = channel.direction_pin {
             direction_pin.shutdown()?;
         }
 
@@ -63,7 +151,8 @@ impl IoChannel for Counter {
 
 impl CounterInput for Counter {
     fn enable(&mut self, state: bool) -> Result<()> {
-        let path_enable = format!("{}/enable", self.path);
+        let channel = self.channel();
+        let path_enable = format!("{}/enable", channel.path);
 
         if state {
             fs::write(&path_enable, b"0")?;
@@ -75,20 +164,20 @@ impl CounterInput for Counter {
             };
 
             use ffi::IoCntTrigger::*;
-            let attr_trigger = match self.trigger {
+            let attr_trigger = match channel.trigger {
                 RisingEdge => "rise",
                 FallingEdge | AnyEdge => "fall",
             };
 
             use ffi::IoCntDirection::*;
-            let direction = match self.dir {
+            let direction = match channel.dir {
                 Up => b"0",
                 Down => b"1",
             };
 
-            let path_mode = format!("{}/mode", self.path);
-            let path_trigger = format!("{}/trigger", self.path);
-            let path_direction = format!("{}/direction", self.path);
+            let path_mode = format!("{}/mode", channel.path);
+            let path_trigger = format!("{}/trigger", channel.path);
+            let path_direction = format!("{}/direction", channel.path);
 
             fs::write(path_mode, attr_mode)?;
             fs::write(path_trigger, attr_trigger)?;
@@ -109,56 +198,92 @@ impl CounterInput for Counter {
         dir: ffi::IoCntDirection,
     ) -> Result<()> {
         self.mode = mode;
-        self.trigger = trigger;
-        self.dir = dir;
+        let mut channel = self.channel();
+        channel.trigger = trigger;
+        channel.dir = dir;
         Ok(())
     }
 
     fn set_preload(&mut self, preload: i32) -> Result<()> {
-        self.preload = preload;
-        Ok(())
+        let mut channel = self.channel();
+        channel.count.set_preload(preload)?;
+        channel.load()
     }
 
     fn get(&mut self) -> Result<i32> {
-        let path = format!("{}/value", self.path);
-        let value = fs::read_to_string(path)?;
-        let mut value = value
-            .trim()
-            .parse::<i32>()
-            .map_err(|_| Error::generic_access_error())?;
+        let mut channel = self.channel();
+        let raw = channel.read_count()?;
+        Ok(channel.count.value(raw))
+    }
 
-        use ffi::IoCntTrigger::*;
-        match self.trigger {
-            RisingEdge => {}
-            FallingEdge => {}
-            AnyEdge => {
-                value *= 2;
+    fn reset(&mut self) -> Result<()> {
+        self.channel().load()
+    }
 
-                let input = self.input.get().unwrap_or(false);
+    /// The auto-reload is done in software, the FlexTimer modulo is not exposed by the driver
+    fn set_compare(&mut self, compare: Option<i32>, auto_reload: bool) -> Result<()> {
+        self.channel().count.set_compare(compare, auto_reload)
+    }
 
-                let direction = match &mut self.direction_pin {
-                    None => false,
-                    Some(direction_pin) => direction_pin.get().unwrap_or(false),
-                };
+    fn has_encoder(&self) -> bool {
+        true
+    }
 
-                use ffi::IoCntDirection::*;
-                let inc = match self.dir {
-                    Up => 1,
-                    Down => -1,
-                };
+    fn register_callback(&mut self, callback: ffi::IoCntCallback, events: u8) -> Result<()> {
+        let channel = Arc::clone(&self.channel);
+        let sampler: cnt_event::Sampler = Box::new(move || {
+            let mut channel = channel.lock().unwrap();
+            let raw = channel.read_count()?;
+            Ok((raw, channel.count.clone()))
+        });
+        self.events = Some(cnt_event::watch(
+            self.number,
+            sampler,
+            None,
+            callback,
+            events,
+        )?);
+        Ok(())
+    }
 
-                if input {
-                    if !direction {
-                        value += inc;
-                    } else {
-                        value -= inc;
-                    }
-                }
+    fn unregister_callback(&mut self) -> Result<()> {
+        self.events = None;
+        Ok(())
+    }
+
+    fn set_index(
+        &mut self,
+        mode: ffi::IoCntIndexMode,
+        trigger: ffi::IoInputTrigger,
+        input: Option<&mut dyn DigitalInput>,
+    ) -> Result<()> {
+        let input = match (input, mode) {
+            (_, ffi::IoCntIndexMode::Off) => {
+                self.index = None;
+                return Ok(());
             }
-        }
+            (None, _) => return Err(Error::NotImplemented),
+            (Some(input), _) => input,
+        };
 
-        value += self.preload;
+        self.index = None;
+        let (channel, channel_cloned) = (Arc::clone(&self.channel), Arc::clone(&self.channel));
+        let handler = counter::index_handler(
+            mode,
+            trigger,
+            Arc::clone(&self.captured),
+            move || channel.lock().unwrap().load(),
+            move || {
+                let mut channel = channel_cloned.lock().unwrap();
+                let raw = channel.read_count()?;
+                Ok(channel.count.value(raw))
+            },
+        );
+        self.index = Some(input.watch_edges(handler)?);
+        Ok(())
+    }
 
-        Ok(value)
+    fn capture(&mut self) -> Result<Option<Capture>> {
+        Ok(self.captured.lock().unwrap().take())
     }
 }
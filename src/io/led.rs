@@ -24,6 +24,14 @@ impl Led {
     pub fn new(name: &'static str) -> Led {
         Led { name, file: None }
     }
+
+    fn brightness_path(&self) -> PathBuf {
+        let mut path_buf = PathBuf::new();
+        path_buf.push("/sys/class/leds");
+        path_buf.push(self.name);
+        path_buf.push("brightness");
+        path_buf
+    }
 }
 
 impl IoChannel for Led {
@@ -40,12 +48,7 @@ impl DigitalOutput for Led {
     fn set(&mut self, val: bool) -> Result<()> {
         // open file on set and leave it open once used for better concurrent usage
         if self.file.is_none() {
-            let mut path_buf = PathBuf::new();
-            path_buf.push("/sys/class/leds");
-            path_buf.push(self.name);
-            path_buf.push("brightness");
-
-            self.file = Some(File::create(path_buf)?);
+            self.file = Some(File::create(self.brightness_path())?);
         }
 
         let v = if val { b"1" } else { b"0" };
@@ -57,4 +60,9 @@ impl DigitalOutput for Led {
             None => Err(Error::GenericError),
         }
     }
+
+    fn get(&mut self) -> Result<bool> {
+        let brightness = std::fs::read_to_string(self.brightness_path())?;
+        Ok(brightness.trim() != "0")
+    }
 }
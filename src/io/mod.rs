@@ -2,14 +2,21 @@
 // SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
 
 pub mod am62x;
+pub mod cdev;
+pub mod cnt_event;
+pub mod counter;
+pub mod edge;
 pub mod evdev;
 pub mod iio;
 pub mod imx;
 pub mod led;
 pub mod lookup;
+pub mod modbus;
 pub mod null;
+pub mod plugin;
 pub mod sensors;
 pub mod shm;
+pub mod sim;
 pub mod sysfs;
 pub mod util;
 pub mod wdg_dev;
@@ -0,0 +1,650 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Channels of remote Modbus devices (e.g. I/O couplers) via Modbus TCP or Modbus RTU.
+//
+// A `Poller` owns the connection to one unit and cyclically reads all registered inputs in its
+// own thread. Outputs are queued and written by the thread as soon as possible, a failed write is
+// retried with the next poll, unless the output was set again meanwhile. If a request fails, the
+// affected inputs report an access error until the communication is restored.
+
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::RecvTimeoutError;
+
+use crate::error::{Error, Result};
+use crate::ffi;
+use crate::modbus::client::Client;
+use crate::modbus::map::Table;
+use crate::modbus::{MAX_READ_BITS, MAX_READ_REGISTERS};
+use crate::{AnalogInput, AnalogOutput, DigitalInput, DigitalOutput, IoChannel};
+
+/// Communication state of a `Poller`
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    /// false after a failed request until the next successful request
+    pub online: bool,
+    pub requests: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug)]
+enum Write {
+    Coil(u16, bool),
+    Register(u16, u16),
+}
+
+impl Write {
+    /// Coil or register which is written
+    fn target(&self) -> (Table, u16) {
+        match self {
+            Write::Coil(address, _) => (Table::Coils, *address),
+            Write::Register(address, _) => (Table::HoldingRegisters, *address),
+        }
+    }
+}
+
+struct Callback {
+    table: Table,
+    address: u16,
+    number: usize,
+    callback: extern "C" fn(u8, ffi::IoBool),
+    trigger: ffi::IoInputTrigger,
+}
+
+struct State {
+    values: HashMap<Table, BTreeMap<u16, Option<u16>>>,
+    writes: Vec<Write>,
+    callbacks: Vec<Callback>,
+    diagnostics: Diagnostics,
+}
+
+impl State {
+    fn value(&self, table: Table, address: u16) -> Result<u16> {
+        self.values
+            .get(&table)
+            .and_then(|values| values.get(&address).copied().flatten())
+            .ok_or_else(Error::generic_access_error)
+    }
+
+    /// Queue a write, a pending write of the same output is replaced
+    fn queue(&mut self, write: Write) {
+        self.writes.retain(|w| w.target() != write.target());
+        self.writes.push(write);
+    }
+
+    fn record(&mut self, name: &str, error: Option<&Error>) {
+        let diag = &mut self.diagnostics;
+        diag.requests += 1;
+
+        match error {
+            None => {
+                if !diag.online {
+                    info!("{}: communication established", name);
+                }
+                diag.online = true;
+                diag.consecutive_failures = 0;
+            }
+            Some(e) => {
+                if diag.online {
+                    warn!("{}: communication lost: {}", name, e);
+                }
+                diag.online = false;
+                diag.failures += 1;
+                diag.consecutive_failures += 1;
+                diag.last_error = Some(match e {
+                    Error::AccessFailed(io) => format!("{}: {}", e, io),
+                    e => e.to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// Split the registered addresses into ranges, which can be read with a single request
+fn ranges(addresses: &[u16], max: u16) -> Vec<RangeInclusive<u16>> {
+    let mut ranges: Vec<RangeInclusive<u16>> = vec![];
+
+    for address in addresses {
+        match ranges.last_mut() {
+            Some(range) if *address == range.end() + 1 && address - range.start() < max => {
+                *range = *range.start()..=*address;
+            }
+            _ => ranges.push(*address..=*address),
+        }
+    }
+
+    ranges
+}
+
+#[derive(Clone)]
+pub struct Poller {
+    name: Arc<String>,
+    state: Arc<Mutex<State>>,
+    notifier: crossbeam_channel::Sender<()>,
+}
+
+impl fmt::Debug for Poller {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "modbus/Poller {}", self.name)
+    }
+}
+
+impl Poller {
+    /// Start polling the registered inputs of the unit with the given client
+    pub fn new(name: &str, client: Client, poll_time: Duration) -> Poller {
+        let state = Arc::new(Mutex::new(State {
+            values: HashMap::new(),
+            writes: vec![],
+            callbacks: vec![],
+            diagnostics: Diagnostics {
+                online: true,
+                ..Default::default()
+            },
+        }));
+
+        let (tx, rx) = crossbeam_channel::bounded(1);
+
+        let name_cloned = name.to_owned();
+        let state_cloned = state.clone();
+        thread::Builder::new()
+            .name(format!("modbus-{}", name))
+            .spawn(move || Poller::run(&name_cloned, client, poll_time, state_cloned, rx))
+            .unwrap();
+
+        Poller {
+            name: Arc::new(name.to_owned()),
+            state,
+            notifier: tx,
+        }
+    }
+
+    fn run(
+        name: &str,
+        mut client: Client,
+        poll_time: Duration,
+        state: Arc<Mutex<State>>,
+        notifier: crossbeam_channel::Receiver<()>,
+    ) {
+        let mut next_poll = Instant::now();
+
+        loop {
+            let timeout = next_poll.saturating_duration_since(Instant::now());
+            match notifier.recv_timeout(timeout) {
+                Ok(()) => {}
+                Err(RecvTimeoutError::Timeout) => {}
+                // all channels and the poller are dropped
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            // write outputs first, to get the new state with the next poll
+            let writes: Vec<Write> = state.lock().unwrap().writes.drain(..).collect();
+            let mut failed = vec![];
+            for write in writes {
+                let result = match write {
+                    Write::Coil(address, value) => client.write_coil(address, value),
+                    Write::Register(address, value) => client.write_register(address, value),
+                };
+                let mut state = state.lock().unwrap();
+                if let Err(e) = &result {
+                    if state.diagnostics.online {
+                        error!("{}: failed to write {:?}: {}", name, write, e);
+                    }
+                    failed.push(write);
+                }
+                state.record(name, result.as_ref().err());
+            }
+
+            // retry with the next poll, this also restores the communication of units without inputs
+            {
+                let mut state = state.lock().unwrap();
+                for write in failed.into_iter().rev() {
+                    if !state.writes.iter().any(|w| w.target() == write.target()) {
+                        state.writes.insert(0, write);
+                    }
+                }
+            }
+
+            if Instant::now() >= next_poll {
+                Poller::poll(name, &mut client, &state);
+                next_poll += poll_time;
+                if next_poll < Instant::now() {
+                    next_poll = Instant::now() + poll_time;
+                }
+            }
+        }
+    }
+
+    fn poll(name: &str, client: &mut Client, state: &Mutex<State>) {
+        let mut events = vec![];
+
+        for table in &[
+            Table::Coils,
+            Table::DiscreteInputs,
+            Table::InputRegisters,
+            Table::HoldingRegisters,
+        ] {
+            let addresses: Vec<u16> = match state.lock().unwrap().values.get(table) {
+                Some(values) => values.keys().copied().collect(),
+                None => continue,
+            };
+
+            let max = match table {
+                Table::Coils | Table::DiscreteInputs => MAX_READ_BITS,
+                _ => MAX_READ_REGISTERS,
+            };
+
+            for range in ranges(&addresses, max) {
+                let start = *range.start();
+                let count = range.end() - start + 1;
+
+                let result: Result<Vec<u16>> = match table {
+                    Table::Coils => client
+                        .read_coils(start, count)
+                        .map(|v| v.into_iter().map(u16::from).collect()),
+                    Table::DiscreteInputs => client
+                        .read_discrete_inputs(start, count)
+                        .map(|v| v.into_iter().map(u16::from).collect()),
+                    Table::InputRegisters => client.read_input_registers(start, count),
+                    Table::HoldingRegisters => client.read_holding_registers(start, count),
+                };
+
+                let mut state = state.lock().unwrap();
+                state.record(name, result.as_ref().err());
+
+                let new_values: Vec<Option<u16>> = match result {
+                    Ok(values) => values.into_iter().map(Some).collect(),
+                    Err(_) => vec![None; count as usize],
+                };
+
+                let State {
+                    values, callbacks, ..
+                } = &mut *state;
+                let values = values.get_mut(table).unwrap();
+
+                for (address, new_value) in range.zip(new_values) {
+                    let old_value = values.insert(address, new_value).flatten();
+
+                    if let (Some(old), Some(new)) = (old_value, new_value) {
+                        if old != new {
+                            events.extend(
+                                callbacks
+                                    .iter()
+                                    .filter(|c| c.table == *table && c.address == address)
+                                    .filter(|c| c.trigger.matches(new != 0))
+                                    .map(|c| (c.callback, c.number, new != 0)),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // the state must not be locked, since callbacks may call into the API again
+        for (callback, number, value) in events {
+            callback(number as u8, value.into());
+        }
+    }
+
+    fn register(&self, table: Table, address: u16) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .values
+            .entry(table)
+            .or_default()
+            .entry(address)
+            .or_insert(None);
+    }
+
+    fn write(&self, write: Write) -> Result<()> {
+        let mut state = self.state.lock().map_err(|_| Error::GenericError)?;
+        // the result is reported by the diagnostics, as the write is done by the poller thread
+        state.queue(write);
+        // the poller thread may be notified already
+        self.notifier.try_send(()).ok();
+        Ok(())
+    }
+
+    fn value(&self, table: Table, address: u16) -> Result<u16> {
+        let state = self.state.lock().map_err(|_| Error::GenericError)?;
+        state.value(table, address)
+    }
+
+    pub fn diagnostics(&self) -> Diagnostics {
+        self.state.lock().unwrap().diagnostics.clone()
+    }
+
+    /// Digital input for a discrete input of the remote unit
+    pub fn input(&self, address: u16) -> Di {
+        self.register(Table::DiscreteInputs, address);
+        Di {
+            poller: self.clone(),
+            address,
+            number: 0,
+        }
+    }
+
+    /// Digital output for a coil of the remote unit
+    pub fn output(&self, address: u16) -> Do {
+        Do {
+            poller: self.clone(),
+            address,
+        }
+    }
+
+    /// Analog input for an input register or holding register of the remote unit
+    pub fn analog_input(&self, table: Table, address: u16) -> Result<Ai> {
+        match table {
+            Table::InputRegisters | Table::HoldingRegisters => {}
+            _ => return Err(Error::InvalidParameter),
+        }
+
+        self.register(table, address);
+        Ok(Ai {
+            poller: self.clone(),
+            table,
+            address,
+        })
+    }
+
+    /// Analog output for a holding register of the remote unit
+    pub fn analog_output(&self, address: u16) -> Ao {
+        Ao {
+            poller: self.clone(),
+            address,
+        }
+    }
+}
+
+pub struct Di {
+    poller: Poller,
+    address: u16,
+    number: usize,
+}
+
+impl fmt::Debug for Di {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "modbus/Di {} {}", self.poller.name, self.address)
+    }
+}
+
+impl IoChannel for Di {
+    fn init(&mut self, chan_number: usize) -> Result<()> {
+        self.number = chan_number;
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        self.unregister_callback()
+    }
+}
+
+impl DigitalInput for Di {
+    fn get(&mut self) -> Result<bool> {
+        self.poller
+            .value(Table::DiscreteInputs, self.address)
+            .map(|v| v != 0)
+    }
+
+    fn register_callback(
+        &mut self,
+        callback: ffi::IoInputCallback,
+        trigger: ffi::IoInputTrigger,
+    ) -> Result<()> {
+        self.unregister_callback()?;
+
+        if let Some(callback) = callback {
+            let mut state = self.poller.state.lock().map_err(|_| Error::GenericError)?;
+            state.callbacks.push(Callback {
+                table: Table::DiscreteInputs,
+                address: self.address,
+                number: self.number,
+                callback,
+                trigger,
+            });
+        }
+        Ok(())
+    }
+
+    fn unregister_callback(&mut self) -> Result<()> {
+        let mut state = self.poller.state.lock().map_err(|_| Error::GenericError)?;
+        // other channels may use the same discrete input
+        state.callbacks.retain(|c| {
+            !(c.table == Table::DiscreteInputs
+                && c.address == self.address
+                && c.number == self.number)
+        });
+        Ok(())
+    }
+}
+
+pub struct Do {
+    poller: Poller,
+    address: u16,
+}
+
+impl fmt::Debug for Do {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "modbus/Do {} {}", self.poller.name, self.address)
+    }
+}
+
+impl IoChannel for Do {
+    fn init(&mut self, _chan_number: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl DigitalOutput for Do {
+    fn set(&mut self, val: bool) -> Result<()> {
+        self.poller.write(Write::Coil(self.address, val))
+    }
+}
+
+pub struct Ai {
+    poller: Poller,
+    table: Table,
+    address: u16,
+}
+
+impl fmt::Debug for Ai {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "modbus/Ai {} {}", self.poller.name, self.address)
+    }
+}
+
+impl IoChannel for Ai {
+    fn init(&mut self, _chan_number: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl AnalogInput for Ai {
+    fn get(&mut self) -> Result<i64> {
+        self.poller.value(self.table, self.address).map(i64::from)
+    }
+}
+
+pub struct Ao {
+    poller: Poller,
+    address: u16,
+}
+
+impl fmt::Debug for Ao {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "modbus/Ao {} {}", self.poller.name, self.address)
+    }
+}
+
+impl IoChannel for Ao {
+    fn init(&mut self, _chan_number: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl AnalogOutput for Ao {
+    fn set(&mut self, value: i64) -> Result<()> {
+        let value = u16::try_from(value).map_err(|_| Error::InvalidParameter)?;
+        self.poller.write(Write::Register(self.address, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+    use crate::modbus::server::Server;
+    use std::net::TcpListener;
+
+    fn wait_for<F: FnMut() -> bool>(mut condition: F) -> bool {
+        for _ in 0..100 {
+            if condition() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        false
+    }
+
+    #[test]
+    fn ranges_test() {
+        assert_eq!(ranges(&[0, 1, 2, 5, 6, 9], 10), [0..=2, 5..=6, 9..=9]);
+        assert_eq!(ranges(&[0, 1, 2, 3], 2), [0..=1, 2..=3]);
+    }
+
+    #[test]
+    fn poller_tcp_test() {
+        // local slave with looped back outputs
+        let sim = Simulator::new("SIM", 2, 1, 0);
+        let mut io = IoBuilder::new()
+            .output(Box::new(sim.output(0)))
+            .output(Box::new(sim.output(1)))
+            .input(Box::new(sim.input(0)))
+            .input(Box::new(sim.input(1)))
+            .analog_input(Box::new(sim.analog_input(0)))
+            .analog_output(Box::new(sim.analog_output(0)))
+            .build();
+        io.init().unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = Arc::new(Server::new(Arc::new(Mutex::new(io))));
+        thread::spawn(move || server.serve(listener));
+
+        let client = Client::tcp(&address, 1, Duration::from_millis(500));
+        let poller = Poller::new("test", client, Duration::from_millis(10));
+
+        let mut do1 = poller.output(1);
+        let mut di1 = poller.input(1);
+        let mut ao0 = poller.analog_output(0);
+        let mut ai0 = poller.analog_input(Table::InputRegisters, 0).unwrap();
+
+        do1.set(true).unwrap();
+        ao0.set(1234).unwrap();
+        assert!(wait_for(|| di1.get().unwrap_or(false)));
+        assert!(wait_for(|| ai0.get().ok() == Some(1234)));
+        assert!(poller.diagnostics().online);
+
+        assert!(ao0.set(-1).is_err());
+    }
+
+    #[test]
+    fn poller_communication_loss_test() {
+        // nothing listens on this port anymore
+        let address = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().to_string()
+        };
+
+        let client = Client::tcp(&address, 1, Duration::from_millis(100));
+        let poller = Poller::new("test", client, Duration::from_millis(10));
+        let mut di0 = poller.input(0);
+
+        assert!(wait_for(|| poller.diagnostics().failures > 0));
+        let diagnostics = poller.diagnostics();
+        assert!(!diagnostics.online);
+        assert!(diagnostics.last_error.is_some());
+        assert!(di0.get().is_err());
+    }
+
+    #[test]
+    fn poller_recovery_test() {
+        // the unit is started after the output was set
+        let address = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().to_string()
+        };
+
+        let client = Client::tcp(&address, 1, Duration::from_millis(100));
+        let poller = Poller::new("test", client, Duration::from_millis(10));
+        let mut do0 = poller.output(0);
+        do0.set(true).unwrap();
+        assert!(wait_for(|| poller.diagnostics().failures > 0));
+        assert!(!poller.diagnostics().online);
+        do0.set(false).unwrap();
+        do0.set(true).unwrap();
+
+        let sim = Simulator::new("SIM", 1, 0, 0);
+        let mut io = IoBuilder::new()
+            .output(Box::new(sim.output(0)))
+            .input(Box::new(sim.input(0)))
+            .build();
+        io.init().unwrap();
+        let listener = TcpListener::bind(&address).unwrap();
+        let server = Arc::new(Server::new(Arc::new(Mutex::new(io))));
+        thread::spawn(move || server.serve(listener));
+
+        // the failed write is retried, without any inputs being polled
+        assert!(wait_for(|| poller.diagnostics().online));
+        assert!(sim.input(0).get().unwrap());
+        assert!(poller.state.lock().unwrap().writes.is_empty());
+    }
+
+    #[test]
+    fn unregister_callback_test() {
+        extern "C" fn callback(_: u8, _: ffi::IoBool) {}
+
+        let address = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().to_string()
+        };
+        let client = Client::tcp(&address, 1, Duration::from_millis(100));
+        let poller = Poller::new("test", client, Duration::from_secs(1));
+
+        // two channels for the same discrete input
+        let mut di0 = poller.input(5);
+        let mut di1 = poller.input(5);
+        di0.init(0).unwrap();
+        di1.init(1).unwrap();
+        di0.register_callback(Some(callback), ffi::IoInputTrigger::BothEdge)
+            .unwrap();
+        di1.register_callback(Some(callback), ffi::IoInputTrigger::BothEdge)
+            .unwrap();
+
+        di0.unregister_callback().unwrap();
+        let state = poller.state.lock().unwrap();
+        let numbers: Vec<usize> = state.callbacks.iter().map(|c| c.number).collect();
+        assert_eq!(numbers, [1]);
+    }
+}
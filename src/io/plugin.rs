@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Channel provider which loads channels from a shared library (see `ffi::IoProviderPlugin`).
+
+use std::convert::TryFrom;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::os::raw::c_char;
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+use crate::ffi::{IoChannelType, IoProviderPlugin, IoResult};
+use crate::labeled::Labeled;
+use crate::provider::{ChannelProvider, ProviderChannels};
+use crate::{AnalogInput, AnalogOutput, DigitalInput, DigitalOutput, IoChannel, TempSensor};
+
+const PLUGIN_INIT_SYMBOL: &[u8] = b"IoProviderPluginInit\0";
+
+type PluginInit = extern "C" fn(*mut IoProviderPlugin) -> u32;
+
+struct Library {
+    handle: *mut libc::c_void,
+    plugin: IoProviderPlugin,
+}
+
+// The library handle and the function pointers are only used to call into the plugin. The plugin
+// is responsible for the synchronization of its functions.
+unsafe impl Send for Library {}
+unsafe impl Sync for Library {}
+
+impl Drop for Library {
+    fn drop(&mut self) {
+        unsafe {
+            libc::dlclose(self.handle);
+        }
+    }
+}
+
+fn dl_error() -> String {
+    unsafe {
+        let err = libc::dlerror();
+        if err.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(err).to_string_lossy().into_owned()
+        }
+    }
+}
+
+/// Convert a result code of the plugin, which may return any value
+fn result(code: u32) -> Result<()> {
+    IoResult::try_from(code)
+        .map_err(|_| Error::GenericError)?
+        .into_result()
+}
+
+fn to_string(s: *const c_char) -> Option<String> {
+    if s.is_null() {
+        None
+    } else {
+        Some(unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned())
+    }
+}
+
+pub struct Plugin {
+    path: String,
+    name: String,
+    lib: Arc<Library>,
+}
+
+impl fmt::Debug for Plugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "plugin/Plugin {}", self.path)
+    }
+}
+
+impl Plugin {
+    pub fn load(path: &str) -> Result<Plugin> {
+        let c_path = CString::new(path).map_err(|_| Error::InvalidParameter)?;
+
+        let handle = unsafe { libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW) };
+        if handle.is_null() {
+            error!("Failed to load plugin {}: {}", path, dl_error());
+            return Err(Error::generic_access_error());
+        }
+
+        let init = unsafe { libc::dlsym(handle, PLUGIN_INIT_SYMBOL.as_ptr() as *const c_char) };
+        if init.is_null() {
+            error!("Invalid plugin {}: {}", path, dl_error());
+            unsafe { libc::dlclose(handle) };
+            return Err(Error::InvalidParameter);
+        }
+        let init: PluginInit = unsafe { std::mem::transmute(init) };
+
+        let mut plugin = IoProviderPlugin {
+            m_pszName: std::ptr::null(),
+            m_uDiChannels: 0,
+            m_uDoChannels: 0,
+            m_uAiChannels: 0,
+            m_uAoChannels: 0,
+            m_uTmpChannels: 0,
+            m_pfnGetLabel: None,
+            m_pfnGetInput: None,
+            m_pfnSetOutput: None,
+            m_pfnAdcGetValue: None,
+            m_pfnDacSetValue: None,
+            m_pfnTmpGetValue: None,
+        };
+
+        if let Err(e) = result(init(&mut plugin)) {
+            unsafe { libc::dlclose(handle) };
+            return Err(e);
+        }
+
+        let name = to_string(plugin.m_pszName).unwrap_or_else(|| path.to_string());
+
+        Ok(Plugin {
+            path: path.to_string(),
+            name,
+            lib: Arc::new(Library { handle, plugin }),
+        })
+    }
+
+    fn label(&self, kind: IoChannelType, prefix: &str, index: u8) -> &'static str {
+        let label = self
+            .lib
+            .plugin
+            .m_pfnGetLabel
+            .and_then(|get_label| to_string(get_label(kind, index)))
+            .unwrap_or_else(|| format!("{}_{}{}", self.name, prefix, index));
+        String::leak(label)
+    }
+}
+
+impl ChannelProvider for Plugin {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.path.clone()
+    }
+
+    fn channels(&mut self) -> Result<ProviderChannels> {
+        let plugin = &self.lib.plugin;
+        let mut channels = ProviderChannels::default();
+
+        for index in 0..plugin.m_uDoChannels {
+            let label = self.label(IoChannelType::DigitalOutput, "DO", index);
+            let channel = Do {
+                index,
+                lib: self.lib.clone(),
+            };
+            channels
+                .outputs
+                .push(Box::new(Labeled::new(label, channel)));
+        }
+
+        for index in 0..plugin.m_uDiChannels {
+            let label = self.label(IoChannelType::DigitalInput, "DI", index);
+            let channel = Di {
+                index,
+                lib: self.lib.clone(),
+            };
+            channels.inputs.push(Box::new(Labeled::new(label, channel)));
+        }
+
+        for index in 0..plugin.m_uAiChannels {
+            let label = self.label(IoChannelType::AnalogInput, "AI", index);
+            let channel = Ai {
+                index,
+                lib: self.lib.clone(),
+            };
+            channels
+                .analog_inputs
+                .push(Box::new(Labeled::new(label, channel)));
+        }
+
+        for index in 0..plugin.m_uAoChannels {
+            let label = self.label(IoChannelType::AnalogOutput, "AO", index);
+            let channel = Ao {
+                index,
+                lib: self.lib.clone(),
+            };
+            channels
+                .analog_outputs
+                .push(Box::new(Labeled::new(label, channel)));
+        }
+
+        for index in 0..plugin.m_uTmpChannels {
+            let label = self.label(IoChannelType::Temperature, "TMP", index);
+            let channel = Temp {
+                index,
+                lib: self.lib.clone(),
+            };
+            channels
+                .temp_sensors
+                .push(Box::new(Labeled::new(label, channel)));
+        }
+
+        Ok(channels)
+    }
+}
+
+macro_rules! plugin_channel {
+    ( $name:ident, $desc:expr ) => {
+        pub struct $name {
+            index: u8,
+            lib: Arc<Library>,
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, concat!("plugin/", $desc, " {}"), self.index)
+            }
+        }
+
+        impl IoChannel for $name {
+            fn init(&mut self, _chan_number: usize) -> Result<()> {
+                Ok(())
+            }
+
+            fn shutdown(&mut self) -> Result<()> {
+                Ok(())
+            }
+        }
+    };
+}
+
+plugin_channel!(Do, "Do");
+plugin_channel!(Di, "Di");
+plugin_channel!(Ai, "Ai");
+plugin_channel!(Ao, "Ao");
+plugin_channel!(Temp, "Temp");
+
+impl DigitalOutput for Do {
+    fn set(&mut self, val: bool) -> Result<()> {
+        let set_output = self
+            .lib
+            .plugin
+            .m_pfnSetOutput
+            .ok_or(Error::NotImplemented)?;
+        result(set_output(self.index, val.into()))
+    }
+}
+
+impl DigitalInput for Di {
+    fn get(&mut self) -> Result<bool> {
+        let get_input = self.lib.plugin.m_pfnGetInput.ok_or(Error::NotImplemented)?;
+        let mut value = 0;
+        result(get_input(self.index, &mut value))?;
+        Ok(value != 0)
+    }
+}
+
+impl AnalogInput for Ai {
+    fn get(&mut self) -> Result<i64> {
+        let get_value = self
+            .lib
+            .plugin
+            .m_pfnAdcGetValue
+            .ok_or(Error::NotImplemented)?;
+        let mut value = 0;
+        result(get_value(self.index, &mut value))?;
+        Ok(value)
+    }
+}
+
+impl AnalogOutput for Ao {
+    fn set(&mut self, value: i64) -> Result<()> {
+        let set_value = self
+            .lib
+            .plugin
+            .m_pfnDacSetValue
+            .ok_or(Error::NotImplemented)?;
+        result(set_value(self.index, value))
+    }
+}
+
+impl TempSensor<f64> for Temp {
+    fn get(&mut self) -> Result<f64> {
+        let get_value = self
+            .lib
+            .plugin
+            .m_pfnTmpGetValue
+            .ok_or(Error::NotImplemented)?;
+        let mut value = 0;
+        result(get_value(self.index, &mut value))?;
+        Ok(value as f64 / 10000f64)
+    }
+}
@@ -3,11 +3,11 @@
 
 use std::fmt;
 use std::sync::{Arc, Mutex};
-use std::thread;
 use std::time::Duration;
 
 use crate::error::Result;
-use crate::periodic::Periodic;
+use crate::health;
+use crate::scheduler::TaskConfig;
 use crate::{IoChannel, TempSensor};
 
 pub struct LmSensor {
@@ -25,50 +25,52 @@ impl LmSensor {
         let value = Arc::new(Mutex::new(0f64));
         let cloned = value.clone();
 
-        thread::Builder::new()
-            .name(name.to_owned())
-            .spawn(move || {
-                let sensors = sensors::Sensors::new();
+        let task = TaskConfig::from_env(name, interval).spawn_or_fallback(move |task| {
+            let sensors = sensors::Sensors::new();
 
-                let chip = sensors
-                    .into_iter()
-                    .find(|chip| {
-                        chip.get_name()
-                            .ok()
-                            .filter(|chipname| chipname == name)
-                            .is_some()
-                    })
-                    .unwrap();
+            let chip = sensors
+                .into_iter()
+                .find(|chip| {
+                    chip.get_name()
+                        .ok()
+                        .filter(|chipname| chipname == name)
+                        .is_some()
+                })
+                .unwrap();
 
-                let feature = chip
-                    .into_iter()
-                    .find(|feature| {
-                        *feature.feature_type() == sensors::FeatureType::SENSORS_FEATURE_TEMP
-                    })
-                    .unwrap();
+            let feature = chip
+                .into_iter()
+                .find(|feature| {
+                    *feature.feature_type() == sensors::FeatureType::SENSORS_FEATURE_TEMP
+                })
+                .unwrap();
 
-                let subfeature = feature
-                    .into_iter()
-                    .find(|subfeature| {
-                        *subfeature.subfeature_type()
-                            == sensors::SubfeatureType::SENSORS_SUBFEATURE_TEMP_INPUT
-                    })
-                    .unwrap();
+            let subfeature = feature
+                .into_iter()
+                .find(|subfeature| {
+                    *subfeature.subfeature_type()
+                        == sensors::SubfeatureType::SENSORS_SUBFEATURE_TEMP_INPUT
+                })
+                .unwrap();
 
-                let mut interval = Periodic::new(interval);
-                loop {
-                    interval.next();
-
-                    match subfeature.get_value() {
-                        Err(_) => warn!("Failed to get sensor value for: {}", name.to_string()),
-                        Ok(value) => {
-                            let mut cloned = cloned.lock().unwrap();
-                            *cloned = value;
-                        }
+            while task.wait() {
+                match subfeature.get_value() {
+                    Err(_) => {
+                        warn!("Failed to get sensor value for: {}", name.to_string());
+                        health::sampler_read_error();
+                    }
+                    Ok(value) => {
+                        let mut cloned = cloned.lock().unwrap();
+                        *cloned = value;
                     }
                 }
-            })
-            .unwrap();
+
+                health::sampler_cycle(task.elapsed());
+            }
+        });
+        if let Err(err) = task {
+            error!("{}: failed to start sampling: {}", name, err);
+        }
 
         LmSensor { value }
     }
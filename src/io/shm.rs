@@ -1,13 +1,25 @@
 // SPDX-License-Identifier: LGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
 
+// Channels provided by the I/O daemon via shared memory (see `daemon`).
+//
+// Digital channels are GPIO lines owned by the daemon (see `io::cdev`). Outputs are applied by the
+// daemon after it checked for new configurations and inputs are sampled by the daemon, so the
+// callbacks of inputs are triggered by polling the shared memory.
+
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
 use crate::error::{Error, Result};
 use crate::ffi;
 use crate::shm;
-use crate::{AnalogInput, IoChannel, TempSensor};
+use crate::{AnalogInput, DigitalInput, DigitalOutput, IoChannel, TempSensor};
+
+/// Period of polling digital inputs with registered callbacks
+const POLL_TIME: Duration = Duration::from_millis(10);
 
 pub struct Sampler {
     inner: Arc<Mutex<SamplerInner>>,
@@ -76,6 +88,29 @@ impl SamplerInner {
             .emit_client_event()
             .map_err(|_| Error::GenericError)
     }
+
+    fn di_get_value(&mut self, index: usize) -> Result<bool> {
+        let mut shm_client = self.shm_client.lock().map_err(|_| Error::GenericError)?;
+        let mut shm = shm_client.lock();
+        Ok(shm.digital_input_get(index))
+    }
+
+    fn do_get_value(&mut self, index: usize) -> Result<bool> {
+        let mut shm_client = self.shm_client.lock().map_err(|_| Error::GenericError)?;
+        let mut shm = shm_client.lock();
+        Ok(shm.digital_output_get(index))
+    }
+
+    fn do_set_value(&mut self, index: usize, value: bool) -> Result<()> {
+        let mut shm_client = self.shm_client.lock().map_err(|_| Error::GenericError)?;
+        {
+            let mut shm = shm_client.lock();
+            shm.digital_output_cfg_set(index, value);
+        }
+        shm_client
+            .emit_client_event()
+            .map_err(|_| Error::GenericError)
+    }
 }
 
 pub struct Ai {
@@ -169,3 +204,149 @@ impl TempSensor<f64> for Temp {
         sampler.temp_set_mode(self.index, mode, sensor_type)
     }
 }
+
+pub struct Do {
+    index: usize,
+    sampler: Arc<Mutex<SamplerInner>>,
+}
+
+impl Do {
+    pub fn new(sampler: &Sampler, index: usize) -> Do {
+        Do {
+            index,
+            sampler: sampler.inner.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for Do {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "shm/Do {}", self.index)
+    }
+}
+
+impl IoChannel for Do {
+    fn init(&mut self, _chan_number: usize) -> Result<()> {
+        if self.index >= shm::NUM_CHANNELS_PER_TYPE {
+            Err(Error::InvalidChannel)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl DigitalOutput for Do {
+    fn set(&mut self, val: bool) -> Result<()> {
+        let mut sampler = self.sampler.lock().map_err(|_| Error::GenericError)?;
+        sampler.do_set_value(self.index, val)
+    }
+
+    fn get(&mut self) -> Result<bool> {
+        let mut sampler = self.sampler.lock().map_err(|_| Error::GenericError)?;
+        sampler.do_get_value(self.index)
+    }
+}
+
+pub struct Di {
+    index: usize,
+    sampler: Arc<Mutex<SamplerInner>>,
+    number: usize,
+    /// Stops the polling thread of the registered callback
+    callback: Option<Arc<AtomicBool>>,
+}
+
+impl Di {
+    pub fn new(sampler: &Sampler, index: usize) -> Di {
+        Di {
+            index,
+            sampler: sampler.inner.clone(),
+            number: 0,
+            callback: None,
+        }
+    }
+}
+
+impl fmt::Debug for Di {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "shm/Di {}", self.index)
+    }
+}
+
+impl IoChannel for Di {
+    fn init(&mut self, chan_number: usize) -> Result<()> {
+        self.number = chan_number;
+        if self.index >= shm::NUM_CHANNELS_PER_TYPE {
+            Err(Error::InvalidChannel)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        self.unregister_callback()
+    }
+}
+
+impl DigitalInput for Di {
+    fn get(&mut self) -> Result<bool> {
+        let mut sampler = self.sampler.lock().map_err(|_| Error::GenericError)?;
+        sampler.di_get_value(self.index)
+    }
+
+    fn register_callback(
+        &mut self,
+        callback: ffi::IoInputCallback,
+        trigger: ffi::IoInputTrigger,
+    ) -> Result<()> {
+        self.unregister_callback()?;
+        let callback = match callback {
+            Some(callback) => callback,
+            None => return Ok(()),
+        };
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let (index, number) = (self.index, self.number);
+        let sampler = self.sampler.clone();
+        let mut value = self.get()?;
+        let stop_cloned = stop.clone();
+        // the thread is not joined, as the callback may wait for the caller of unregister
+        thread::Builder::new()
+            .name(format!("shm-di{}", index))
+            .spawn(move || loop {
+                thread::sleep(POLL_TIME);
+                if stop_cloned.load(Ordering::Relaxed) {
+                    break;
+                }
+                let new_value = match sampler.lock().map(|mut s| s.di_get_value(index)) {
+                    Ok(Ok(new_value)) => new_value,
+                    _ => break,
+                };
+                if new_value != value {
+                    value = new_value;
+                    if trigger.matches(value) {
+                        callback(number as u8, value.into());
+                    }
+                }
+            })?;
+
+        self.callback = Some(stop);
+        Ok(())
+    }
+
+    fn unregister_callback(&mut self) -> Result<()> {
+        if let Some(stop) = self.callback.take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Di {
+    fn drop(&mut self) {
+        self.unregister_callback().ok();
+    }
+}
@@ -0,0 +1,667 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Simulated channels which are only backed by memory. Digital outputs are looped back to the
+// digital inputs with the same index, analog outputs to the analog inputs with the same index.
+// PWM outputs only record their configuration. This is useful for testing applications and
+// services on a PC.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::error::{Error, Result};
+use crate::ffi;
+use crate::labeled::Labeled;
+use crate::provider::{ChannelProvider, ProviderChannels};
+use crate::{
+    AnalogInput, AnalogOutput, DigitalInput, DigitalOutput, IoChannel, PwmOutput, TempSensor,
+};
+
+struct DigitalState {
+    value: bool,
+    number: usize,
+    callback: ffi::IoInputCallback,
+    trigger: ffi::IoInputTrigger,
+}
+
+/// Configuration of a simulated PWM output
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PwmState {
+    pub enabled: bool,
+    /// Period in nanoseconds
+    pub period: u64,
+    /// Duty cycle in nanoseconds
+    pub duty_cycle: u64,
+    pub polarity: ffi::IoPwmPolarity,
+    /// Timebase of `PwmOutput::setup()` in nanoseconds
+    timebase: u64,
+}
+
+impl Default for PwmState {
+    fn default() -> PwmState {
+        PwmState {
+            enabled: false,
+            period: 0,
+            duty_cycle: 0,
+            polarity: ffi::IoPwmPolarity::Normal,
+            timebase: 800,
+        }
+    }
+}
+
+struct Image {
+    digital: Vec<DigitalState>,
+    analog: Vec<i64>,
+    temperature: Vec<f64>,
+    pwm: Vec<PwmState>,
+    events: crossbeam_channel::Sender<(extern "C" fn(u8, ffi::IoBool), u8, bool)>,
+}
+
+type ImageSync = Arc<Mutex<Image>>;
+
+impl Image {
+    fn set_digital(&mut self, index: usize, value: bool) -> Result<()> {
+        let state = self.digital.get_mut(index).ok_or(Error::InvalidChannel)?;
+        let changed = state.value != value;
+        state.value = value;
+
+        if changed && state.trigger.matches(value) {
+            if let Some(callback) = state.callback {
+                // callbacks are dispatched by a separate thread, since they may call into the
+                // API again while the caller of this function is still holding a lock
+                self.events
+                    .send((callback, state.number as u8, value))
+                    .map_err(|_| Error::GenericError)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Memory based I/O image, which can be used as channel provider
+#[derive(Clone)]
+pub struct Simulator {
+    name: String,
+    image: ImageSync,
+}
+
+impl fmt::Debug for Simulator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sim/Simulator {}", self.name)
+    }
+}
+
+impl Simulator {
+    pub fn new(name: &str, digital: usize, analog: usize, temperature: usize) -> Simulator {
+        let (tx, rx) = crossbeam_channel::unbounded::<(extern "C" fn(u8, ffi::IoBool), u8, bool)>();
+
+        thread::Builder::new()
+            .name(format!("sim-{}", name))
+            .spawn(move || {
+                for (callback, number, value) in rx.iter() {
+                    callback(number, value.into());
+                }
+            })
+            .unwrap();
+
+        let image = Image {
+            digital: (0..digital)
+                .map(|_| DigitalState {
+                    value: false,
+                    number: 0,
+                    callback: None,
+                    trigger: ffi::IoInputTrigger::None,
+                })
+                .collect(),
+            analog: vec![0; analog],
+            temperature: vec![25.0; temperature],
+            pwm: Vec::new(),
+            events: tx,
+        };
+
+        Simulator {
+            name: name.to_string(),
+            image: Arc::new(Mutex::new(image)),
+        }
+    }
+
+    /// Add `count` simulated PWM outputs, they are not provided as channels by
+    /// `ChannelProvider`
+    pub fn with_pwm_outputs(self, count: usize) -> Simulator {
+        self.image
+            .lock()
+            .unwrap()
+            .pwm
+            .resize(count, PwmState::default());
+        self
+    }
+
+    /// Current configuration of a simulated PWM output
+    pub fn pwm_state(&self, index: usize) -> Result<PwmState> {
+        let image = self.image.lock().map_err(|_| Error::GenericError)?;
+        image.pwm.get(index).copied().ok_or(Error::InvalidChannel)
+    }
+
+    /// Change the value of a simulated digital input
+    pub fn set_input(&self, index: usize, value: bool) -> Result<()> {
+        let mut image = self.image.lock().map_err(|_| Error::GenericError)?;
+        image.set_digital(index, value)
+    }
+
+    /// Change the value of a simulated analog input
+    pub fn set_analog(&self, index: usize, value: i64) -> Result<()> {
+        let mut image = self.image.lock().map_err(|_| Error::GenericError)?;
+        *image.analog.get_mut(index).ok_or(Error::InvalidChannel)? = value;
+        Ok(())
+    }
+
+    /// Change the value of a simulated temperature sensor in °C
+    pub fn set_temperature(&self, index: usize, value: f64) -> Result<()> {
+        let mut image = self.image.lock().map_err(|_| Error::GenericError)?;
+        *image
+            .temperature
+            .get_mut(index)
+            .ok_or(Error::InvalidChannel)? = value;
+        Ok(())
+    }
+
+    pub fn output(&self, index: usize) -> Do {
+        Do {
+            index,
+            image: self.image.clone(),
+        }
+    }
+
+    pub fn input(&self, index: usize) -> Di {
+        Di {
+            index,
+            image: self.image.clone(),
+        }
+    }
+
+    pub fn analog_input(&self, index: usize) -> Ai {
+        Ai {
+            index,
+            image: self.image.clone(),
+        }
+    }
+
+    pub fn analog_output(&self, index: usize) -> Ao {
+        Ao {
+            index,
+            image: self.image.clone(),
+        }
+    }
+
+    pub fn temp_sensor(&self, index: usize) -> Temp {
+        Temp {
+            index,
+            image: self.image.clone(),
+        }
+    }
+
+    pub fn pwm(&self, index: usize) -> Pwm {
+        Pwm {
+            index,
+            image: self.image.clone(),
+        }
+    }
+
+    fn counts(&self) -> (usize, usize, usize) {
+        let image = self.image.lock().unwrap();
+        (
+            image.digital.len(),
+            image.analog.len(),
+            image.temperature.len(),
+        )
+    }
+}
+
+impl ChannelProvider for Simulator {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn description(&self) -> String {
+        "simulated channels (outputs are looped back to inputs)".to_string()
+    }
+
+    fn channels(&mut self) -> Result<ProviderChannels> {
+        let (digital, analog, temperature) = self.counts();
+        let label = |kind: &str, i: usize| -> &'static str {
+            String::leak(format!("{}_{}{}", self.name, kind, i))
+        };
+
+        let mut channels = ProviderChannels::default();
+        for i in 0..digital {
+            channels
+                .outputs
+                .push(Box::new(Labeled::new(label("DO", i), self.output(i))));
+            channels
+                .inputs
+                .push(Box::new(Labeled::new(label("DI", i), self.input(i))));
+        }
+        for i in 0..analog {
+            channels.analog_outputs.push(Box::new(Labeled::new(
+                label("AO", i),
+                self.analog_output(i),
+            )));
+            channels
+                .analog_inputs
+                .push(Box::new(Labeled::new(label("AI", i), self.analog_input(i))));
+        }
+        for i in 0..temperature {
+            channels
+                .temp_sensors
+                .push(Box::new(Labeled::new(label("TMP", i), self.temp_sensor(i))));
+        }
+
+        Ok(channels)
+    }
+}
+
+pub struct Do {
+    index: usize,
+    image: ImageSync,
+}
+
+impl fmt::Debug for Do {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sim/Do {}", self.index)
+    }
+}
+
+impl IoChannel for Do {
+    fn init(&mut self, _chan_number: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl DigitalOutput for Do {
+    fn set(&mut self, val: bool) -> Result<()> {
+        let mut image = self.image.lock().map_err(|_| Error::GenericError)?;
+        image.set_digital(self.index, val)
+    }
+
+    fn get(&mut self) -> Result<bool> {
+        let image = self.image.lock().map_err(|_| Error::GenericError)?;
+        let state = image.digital.get(self.index).ok_or(Error::InvalidChannel)?;
+        Ok(state.value)
+    }
+}
+
+pub struct Di {
+    index: usize,
+    image: ImageSync,
+}
+
+impl fmt::Debug for Di {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sim/Di {}", self.index)
+    }
+}
+
+impl IoChannel for Di {
+    fn init(&mut self, chan_number: usize) -> Result<()> {
+        let mut image = self.image.lock().map_err(|_| Error::GenericError)?;
+        let state = image
+            .digital
+            .get_mut(self.index)
+            .ok_or(Error::InvalidChannel)?;
+        state.number = chan_number;
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl DigitalInput for Di {
+    fn get(&mut self) -> Result<bool> {
+        let image = self.image.lock().map_err(|_| Error::GenericError)?;
+        let state = image.digital.get(self.index).ok_or(Error::InvalidChannel)?;
+        Ok(state.value)
+    }
+
+    fn register_callback(
+        &mut self,
+        callback: ffi::IoInputCallback,
+        trigger: ffi::IoInputTrigger,
+    ) -> Result<()> {
+        let mut image = self.image.lock().map_err(|_| Error::GenericError)?;
+        let state = image
+            .digital
+            .get_mut(self.index)
+            .ok_or(Error::InvalidChannel)?;
+        state.callback = callback;
+        state.trigger = trigger;
+        Ok(())
+    }
+
+    fn unregister_callback(&mut self) -> Result<()> {
+        let mut image = self.image.lock().map_err(|_| Error::GenericError)?;
+        let state = image
+            .digital
+            .get_mut(self.index)
+            .ok_or(Error::InvalidChannel)?;
+        state.callback = None;
+        state.trigger = ffi::IoInputTrigger::None;
+        Ok(())
+    }
+}
+
+pub struct Ai {
+    index: usize,
+    image: ImageSync,
+}
+
+impl fmt::Debug for Ai {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sim/Ai {}", self.index)
+    }
+}
+
+impl IoChannel for Ai {
+    fn init(&mut self, _chan_number: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl AnalogInput for Ai {
+    fn get(&mut self) -> Result<i64> {
+        let image = self.image.lock().map_err(|_| Error::GenericError)?;
+        image
+            .analog
+            .get(self.index)
+            .copied()
+            .ok_or(Error::InvalidChannel)
+    }
+
+    fn set_mode(&mut self, _mode: ffi::IoAnalogMode) -> Result<()> {
+        Ok(())
+    }
+}
+
+pub struct Ao {
+    index: usize,
+    image: ImageSync,
+}
+
+impl fmt::Debug for Ao {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sim/Ao {}", self.index)
+    }
+}
+
+impl IoChannel for Ao {
+    fn init(&mut self, _chan_number: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl AnalogOutput for Ao {
+    fn set(&mut self, value: i64) -> Result<()> {
+        let mut image = self.image.lock().map_err(|_| Error::GenericError)?;
+        *image
+            .analog
+            .get_mut(self.index)
+            .ok_or(Error::InvalidChannel)? = value;
+        Ok(())
+    }
+}
+
+pub struct Temp {
+    index: usize,
+    image: ImageSync,
+}
+
+impl fmt::Debug for Temp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sim/Temp {}", self.index)
+    }
+}
+
+impl IoChannel for Temp {
+    fn init(&mut self, _chan_number: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl TempSensor<f64> for Temp {
+    fn get(&mut self) -> Result<f64> {
+        let image = self.image.lock().map_err(|_| Error::GenericError)?;
+        image
+            .temperature
+            .get(self.index)
+            .copied()
+            .ok_or(Error::InvalidChannel)
+    }
+
+    fn set_mode(
+        &mut self,
+        _mode: ffi::IoTmpMode,
+        _sensor_type: ffi::IoTmpSensorType,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+pub struct Pwm {
+    index: usize,
+    image: ImageSync,
+}
+
+impl fmt::Debug for Pwm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sim/Pwm {}", self.index)
+    }
+}
+
+impl Pwm {
+    fn with_state<T, F: FnOnce(&mut PwmState) -> T>(&self, f: F) -> Result<T> {
+        let mut image = self.image.lock().map_err(|_| Error::GenericError)?;
+        let state = image.pwm.get_mut(self.index).ok_or(Error::InvalidChannel)?;
+        Ok(f(state))
+    }
+}
+
+impl IoChannel for Pwm {
+    fn init(&mut self, _chan_number: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl PwmOutput for Pwm {
+    fn enable(&mut self, state: bool) -> Result<()> {
+        self.with_state(|pwm| pwm.enabled = state)
+    }
+
+    fn setup(&mut self, period: u16, duty_cycle: u16) -> Result<()> {
+        self.with_state(|pwm| {
+            pwm.period = period as u64 * pwm.timebase;
+            pwm.duty_cycle = duty_cycle as u64 * pwm.timebase;
+        })
+    }
+
+    fn set_timebase(&mut self, timebase: ffi::IoPwmTimebase) -> Result<()> {
+        self.with_state(|pwm| {
+            pwm.timebase = match timebase {
+                ffi::IoPwmTimebase::Ms1 => 1000 * 1000,
+                ffi::IoPwmTimebase::Ns800 => 800,
+            }
+        })
+    }
+
+    fn timebase(&self) -> Result<u64> {
+        self.with_state(|pwm| pwm.timebase)
+    }
+
+    fn configure(&mut self, period: u64, duty_cycle: u64) -> Result<()> {
+        self.with_state(|pwm| {
+            pwm.period = period;
+            pwm.duty_cycle = duty_cycle;
+        })
+    }
+
+    fn set_polarity(&mut self, polarity: ffi::IoPwmPolarity) -> Result<()> {
+        self.with_state(|pwm| pwm.polarity = polarity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+
+    #[test]
+    fn provider_loopback_test() {
+        let mut io = IoBuilder::new()
+            .output(Box::new(crate::io::null::Output::not_implemented()))
+            .build();
+        io.init().unwrap();
+
+        let mut sim = Simulator::new("SIM", 2, 1, 1);
+        let info = io.add_provider(&mut sim).unwrap();
+        assert_eq!(info.outputs, 1..3);
+        assert_eq!(info.inputs, 0..2);
+        assert_eq!(info.analog_inputs, 0..1);
+
+        io.output_set(2, true).unwrap();
+        assert!(!io.input_get(0).unwrap());
+        assert!(io.input_get(1).unwrap());
+
+        io.analog_output_set(0, 1234).unwrap();
+        assert_eq!(io.analog_input_get(0).unwrap(), 1234);
+
+        sim.set_temperature(0, 42.5).unwrap();
+        assert!(approx_eq!(f64, io.tmp_input_get(0).unwrap(), 42.5));
+
+        assert_eq!(io.get_channel_info().inputs[1].label(), Some("SIM_DI1"));
+
+        // the same provider must not be added twice
+        assert!(io.add_provider(&mut sim).is_err());
+    }
+
+    #[derive(Debug)]
+    struct Failing {}
+
+    impl IoChannel for Failing {
+        fn init(&mut self, _chan_number: usize) -> Result<()> {
+            Err(Error::GenericError)
+        }
+
+        fn shutdown(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl TempSensor<f64> for Failing {
+        fn get(&mut self) -> Result<f64> {
+            Err(Error::GenericError)
+        }
+    }
+
+    /// Output counting its shutdowns
+    #[derive(Debug)]
+    struct Tracked(Arc<Mutex<usize>>);
+
+    impl IoChannel for Tracked {
+        fn init(&mut self, _chan_number: usize) -> Result<()> {
+            Ok(())
+        }
+
+        fn shutdown(&mut self) -> Result<()> {
+            *self.0.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    impl DigitalOutput for Tracked {
+        fn set(&mut self, _val: bool) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct FailingProvider {
+        shutdowns: Arc<Mutex<usize>>,
+    }
+
+    impl ChannelProvider for FailingProvider {
+        fn name(&self) -> String {
+            "FAILING".to_string()
+        }
+
+        fn channels(&mut self) -> Result<ProviderChannels> {
+            let mut channels = Simulator::new("FAILING", 2, 1, 1).channels()?;
+            channels
+                .outputs
+                .push(Box::new(Tracked(self.shutdowns.clone())));
+            channels.temp_sensors.push(Box::new(Failing {}));
+            Ok(channels)
+        }
+    }
+
+    #[test]
+    fn provider_failing_test() {
+        let mut io = IoBuilder::new().build();
+        io.init().unwrap();
+
+        // the channels which were initialized before the failing one are shut down and not added
+        let mut provider = FailingProvider {
+            shutdowns: Arc::new(Mutex::new(0)),
+        };
+        assert!(io.add_provider(&mut provider).is_err());
+        assert_eq!(*provider.shutdowns.lock().unwrap(), 1);
+        let info = io.get_channel_info();
+        assert_eq!((info.outputs.len(), info.inputs.len()), (0, 0));
+        assert!(io.providers().is_empty());
+
+        let info = io
+            .add_provider(&mut Simulator::new("SIM", 2, 1, 1))
+            .unwrap();
+        assert_eq!(info.outputs, 0..2);
+    }
+
+    #[test]
+    fn provider_limit_test() {
+        let mut io = IoBuilder::new()
+            .output(Box::new(crate::io::null::Output::not_implemented()))
+            .build();
+        io.init().unwrap();
+
+        // channel numbers must fit into u8
+        assert!(matches!(
+            io.add_provider(&mut Simulator::new("SIM", 255, 0, 0)),
+            Err(Error::InvalidParameter)
+        ));
+        assert!(io.providers().is_empty());
+
+        let info = io
+            .add_provider(&mut Simulator::new("SIM", 254, 0, 0))
+            .unwrap();
+        assert_eq!(info.outputs, 1..255);
+    }
+}
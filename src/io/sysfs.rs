@@ -10,6 +10,7 @@ use std::sync::{Arc, Mutex};
 
 use crate::error::*;
 use crate::ffi;
+use crate::io::edge;
 use crate::{DigitalInput, DigitalOutput, IoChannel, PwmOutput};
 
 #[derive(Copy, Clone)]
@@ -75,6 +76,12 @@ impl GpioSysFs {
         Ok(())
     }
 
+    /// Set the edges signaled by poll() on the value file ("none", "rising", "falling", "both")
+    fn set_edge(&self, edge: &str) -> Result<()> {
+        write(format!("/sys/class/gpio/gpio{}/edge", self.gpionum), edge)?;
+        Ok(())
+    }
+
     fn value_file_path(&self) -> String {
         format!("/sys/class/gpio/gpio{}/value", self.gpionum)
     }
@@ -108,6 +115,12 @@ impl GpioSysFs {
         Ok(())
     }
 
+    /// Read the value independent of the direction (the file of outputs is opened write-only)
+    fn read(&self) -> Result<bool> {
+        let value = std::fs::read_to_string(self.value_file_path())?;
+        Ok(value.trim() != "0")
+    }
+
     fn get(&mut self) -> Result<bool> {
         let mut buffer = [0; 1];
         match &mut self.file {
@@ -148,15 +161,44 @@ impl PwmSysFs {
         Ok(())
     }
 
-    fn update(&mut self, period: u32, duty_cycle: u32) -> Result<()> {
+    fn read_ns(&self, attribute: &str) -> Result<u64> {
+        let value = std::fs::read_to_string(format!("{}/{}", self.base_path(), attribute))?;
+        value.trim().parse().map_err(|_| Error::GenericError)
+    }
+
+    /// Change period and duty cycle. Only changed values are written, so a change of the duty
+    /// cycle or the period alone is applied at once. If both change, the sysfs interface needs
+    /// two writes and the PWM may run one cycle with the new period and the old duty cycle (or
+    /// vice versa). The kernel rejects a duty cycle larger than the period, so the order of the
+    /// writes depends on the current period.
+    fn update(&mut self, period: u64, duty_cycle: u64) -> Result<()> {
         let base_path = self.base_path();
-        // The PWM cannot be used, if the period and duty_cycle are not initialized
-        write(format!("{}/duty_cycle", base_path), "0").ok(); // Ignore any error
-        write(format!("{}/period", base_path), format!("{}", period))?;
-        write(
-            format!("{}/duty_cycle", base_path),
-            format!("{}", duty_cycle),
-        )?;
+        // an unconfigured PWM reports a period of 0
+        let current_period = self.read_ns("period").unwrap_or(0);
+        let current_duty_cycle = self.read_ns("duty_cycle").ok();
+
+        if period == current_period {
+            if current_duty_cycle != Some(duty_cycle) {
+                write(
+                    format!("{}/duty_cycle", base_path),
+                    format!("{}", duty_cycle),
+                )?;
+            }
+        } else if current_duty_cycle == Some(duty_cycle) {
+            write(format!("{}/period", base_path), format!("{}", period))?;
+        } else if duty_cycle > current_period {
+            write(format!("{}/period", base_path), format!("{}", period))?;
+            write(
+                format!("{}/duty_cycle", base_path),
+                format!("{}", duty_cycle),
+            )?;
+        } else {
+            write(
+                format!("{}/duty_cycle", base_path),
+                format!("{}", duty_cycle),
+            )?;
+            write(format!("{}/period", base_path), format!("{}", period))?;
+        }
         Ok(())
     }
 
@@ -164,6 +206,28 @@ impl PwmSysFs {
         format!("/sys/class/pwm/pwmchip{}/pwm{}", self.chip, self.channel)
     }
 
+    fn set_polarity(&mut self, polarity: ffi::IoPwmPolarity) -> Result<()> {
+        let base_path = self.base_path();
+        let value = match polarity {
+            ffi::IoPwmPolarity::Normal => "normal",
+            ffi::IoPwmPolarity::Inversed => "inversed",
+        };
+        if std::fs::read_to_string(format!("{}/polarity", base_path))?.trim() == value {
+            return Ok(());
+        }
+
+        // the polarity can only be changed while disabled
+        let enabled = std::fs::read_to_string(format!("{}/enable", base_path))?.trim() == "1";
+        if enabled {
+            self.enable(false)?;
+        }
+        let result = write(format!("{}/polarity", base_path), value);
+        if enabled {
+            self.enable(true)?;
+        }
+        Ok(result?)
+    }
+
     fn enable(&mut self, enable: bool) -> Result<()> {
         debug!("Set PWM {} = {}", self.channel, enable);
         let base_path = self.base_path();
@@ -236,12 +300,18 @@ impl DigitalOutput for Do {
     fn set(&mut self, val: bool) -> Result<()> {
         self.sysfs.set(val)
     }
+
+    fn get(&mut self) -> Result<bool> {
+        self.sysfs.read()
+    }
 }
 
 #[derive(Debug)]
 pub struct Di {
     polarity: Polarity,
     sysfs: GpioSysFs,
+    number: usize,
+    callback: Option<edge::Registration>,
 }
 
 impl Di {
@@ -249,6 +319,8 @@ impl Di {
         Di {
             polarity: Polarity::ActiveHigh,
             sysfs: GpioSysFs::new(gpionum),
+            number: 0,
+            callback: None,
         }
     }
 
@@ -256,12 +328,15 @@ impl Di {
         Di {
             polarity: Polarity::ActiveLow,
             sysfs: GpioSysFs::new(gpionum),
+            number: 0,
+            callback: None,
         }
     }
 }
 
 impl IoChannel for Di {
-    fn init(&mut self, _chan_number: usize) -> Result<()> {
+    fn init(&mut self, chan_number: usize) -> Result<()> {
+        self.number = chan_number;
         if !self.sysfs.is_exported() {
             self.sysfs.export()?;
             self.sysfs.set_polarity(&self.polarity)?;
@@ -272,7 +347,7 @@ impl IoChannel for Di {
     }
 
     fn shutdown(&mut self) -> Result<()> {
-        Ok(())
+        self.unregister_callback()
     }
 }
 
@@ -280,18 +355,62 @@ impl DigitalInput for Di {
     fn get(&mut self) -> Result<bool> {
         self.sysfs.get()
     }
+
+    fn register_callback(
+        &mut self,
+        callback: ffi::IoInputCallback,
+        trigger: ffi::IoInputTrigger,
+    ) -> Result<()> {
+        self.unregister_callback()?;
+        if callback.is_none() {
+            return Ok(());
+        }
+
+        self.sysfs.set_edge("both")?;
+        let source = edge::Source::SysfsValue(File::open(self.sysfs.value_file_path())?);
+        self.callback = Some(edge::watch(
+            source,
+            self.number,
+            self.sysfs.get()?,
+            callback,
+            trigger,
+        )?);
+        Ok(())
+    }
+
+    fn unregister_callback(&mut self) -> Result<()> {
+        if self.callback.take().is_some() {
+            self.sysfs.set_edge("none")?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
 pub struct PwmInner {
     sysfs: PwmSysFs,
-    timebase: u32,
-    period: u32,
-    duty_cycle: u32,
+    timebase: u64,
+    /// Period and duty cycle in units of the timebase, if set by `setup()`
+    setup: Option<(u16, u16)>,
+    /// Period in nanoseconds
+    period: u64,
+    /// Duty cycle in nanoseconds
+    duty_cycle: u64,
     update_needed: bool,
     is_gpio: bool,
 }
 
+impl PwmInner {
+    /// Write period and duty cycle in nanoseconds
+    fn apply(&mut self, period: u64, duty_cycle: u64) -> Result<()> {
+        self.sysfs.update(period, duty_cycle)?;
+        self.period = period;
+        self.duty_cycle = duty_cycle;
+        self.update_needed = true;
+        Ok(())
+    }
+}
+
 type PwmInnerSync = Arc<Mutex<PwmInner>>;
 
 #[derive(Debug)]
@@ -317,8 +436,9 @@ impl Pwm {
                 let inner = Arc::new(Mutex::new(PwmInner {
                     sysfs: PwmSysFs::new(chip, channel),
                     timebase: 800,
-                    period: 1,
-                    duty_cycle: 1,
+                    setup: None,
+                    period: 800,
+                    duty_cycle: 800,
                     update_needed: true,
                     is_gpio: true,
                 }));
@@ -357,13 +477,9 @@ impl PwmOutput for Pwm {
 
     fn setup(&mut self, period: u16, duty_cycle: u16) -> Result<()> {
         let mut inner = self.inner.lock().map_err(|_| Error::GenericError)?;
-        inner.period = period as u32;
-        inner.duty_cycle = duty_cycle as u32;
-
-        let period_new = inner.period * inner.timebase;
-        let duty_cycle_new = inner.duty_cycle * inner.timebase;
-        inner.sysfs.update(period_new, duty_cycle_new)?;
-        inner.update_needed = true;
+        let timebase = inner.timebase;
+        inner.apply(period as u64 * timebase, duty_cycle as u64 * timebase)?;
+        inner.setup = Some((period, duty_cycle));
         Ok(())
     }
 
@@ -373,24 +489,45 @@ impl PwmOutput for Pwm {
             ffi::IoPwmTimebase::Ms1 => 1000 * 1000,
             ffi::IoPwmTimebase::Ns800 => 800,
         };
+        // the values of `setup()` are in units of the timebase
+        if let Some((period, duty_cycle)) = inner.setup {
+            let timebase = inner.timebase;
+            inner.apply(period as u64 * timebase, duty_cycle as u64 * timebase)?;
+        }
         inner.update_needed = true;
         Ok(())
     }
+
+    fn timebase(&self) -> Result<u64> {
+        Ok(self.inner.lock().map_err(|_| Error::GenericError)?.timebase)
+    }
+
+    fn configure(&mut self, period: u64, duty_cycle: u64) -> Result<()> {
+        let mut inner = self.inner.lock().map_err(|_| Error::GenericError)?;
+        inner.apply(period, duty_cycle)?;
+        inner.setup = None;
+        Ok(())
+    }
+
+    fn set_polarity(&mut self, polarity: ffi::IoPwmPolarity) -> Result<()> {
+        let mut inner = self.inner.lock().map_err(|_| Error::GenericError)?;
+        inner.sysfs.set_polarity(polarity)
+    }
 }
 
 impl DigitalOutput for Pwm {
     fn set(&mut self, val: bool) -> Result<()> {
         let mut inner = self.inner.lock().map_err(|_| Error::GenericError)?;
         if inner.update_needed {
-            let period: u32;
-            let duty_cycle: u32;
+            let period: u64;
+            let duty_cycle: u64;
 
             if inner.is_gpio {
                 period = 100;
                 duty_cycle = 100;
             } else {
-                period = inner.period * inner.timebase;
-                duty_cycle = inner.duty_cycle * inner.timebase;
+                period = inner.period;
+                duty_cycle = inner.duty_cycle;
             }
             inner.sysfs.update(period, duty_cycle)?;
             inner.update_needed = false;
@@ -1,12 +1,44 @@
 // SPDX-License-Identifier: LGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
 
+use std::fs::File;
+use std::io;
+use std::mem;
+use std::os::unix::io::AsRawFd;
+
 use ini::Ini;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::ffi;
 use crate::{AnalogInput, DigitalOutput, IoChannel, TempSensor};
 
+/// Request code `_IO(ty, nr)` of the generic ioctl encoding (e.g. ARM, x86)
+pub const fn io_none(ty: u8, nr: u8) -> u64 {
+    ((ty as u64) << 8) | nr as u64
+}
+
+/// Request code `_IOW(ty, nr, T)` of the generic ioctl encoding (e.g. ARM, x86)
+pub const fn iow<T>(ty: u8, nr: u8) -> u64 {
+    (1 << 30) | ((mem::size_of::<T>() as u64) << 16) | ((ty as u64) << 8) | nr as u64
+}
+
+/// Request code `_IOR(ty, nr, T)` of the generic ioctl encoding (e.g. ARM, x86)
+pub const fn ior<T>(ty: u8, nr: u8) -> u64 {
+    (2 << 30) | ((mem::size_of::<T>() as u64) << 16) | ((ty as u64) << 8) | nr as u64
+}
+
+/// Request code `_IOWR(ty, nr, T)` of the generic ioctl encoding (e.g. ARM, x86)
+pub const fn iowr<T>(ty: u8, nr: u8) -> u64 {
+    (3 << 30) | ((mem::size_of::<T>() as u64) << 16) | ((ty as u64) << 8) | nr as u64
+}
+
+pub fn ioctl<T>(file: &File, request: u64, data: &mut T) -> Result<()> {
+    if unsafe { libc::ioctl(file.as_raw_fd(), request as _, data as *mut T) } < 0 {
+        return Err(Error::AccessFailed(io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct DoOnly<T: DigitalOutput> {
     inner: T,
@@ -1,99 +1,226 @@
 // SPDX-License-Identifier: LGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
 
-// use std::fs::File;
-// use std::io::prelude::*;
-// use std::os::unix::io::AsRawFd;
-// use std::time::Instant;
+// Hardware watchdog via the Linux watchdog API (/dev/watchdogN). Opening the device starts the
+// watchdog, it is stopped by the magic close ('V' written before closing) on `disable()` or drop,
+// unless the kernel is configured with "nowayout". A drop while unwinding from a panic closes the
+// device without the magic close, so the watchdog resets the device if the servicing code died.
+//
+// In monitor mode the hardware watchdog is not started. Instead `service()` reports
+// `Error::WatchdogTimeout` if it was not called within the timeout.
+//
+// While the device is closed, the timeout, time left and boot status are read from sysfs
+// (/sys/class/watchdog/watchdogN), because opening the device would start the watchdog.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use libc::c_int;
 
 use crate::error::{Error, Result};
+use crate::io::util::{ioctl, ior, iowr};
 use crate::Watchdog;
 
-// TODO: This implementation works except setting the watchdog's timeout.
+const WATCHDOG_IOCTL_BASE: u8 = b'W';
+const WDIOC_GETBOOTSTATUS: u64 = ior::<c_int>(WATCHDOG_IOCTL_BASE, 2);
+const WDIOC_KEEPALIVE: u64 = ior::<c_int>(WATCHDOG_IOCTL_BASE, 5);
+const WDIOC_SETTIMEOUT: u64 = iowr::<c_int>(WATCHDOG_IOCTL_BASE, 6);
+const WDIOC_GETTIMEOUT: u64 = ior::<c_int>(WATCHDOG_IOCTL_BASE, 7);
+const WDIOC_GETTIMELEFT: u64 = ior::<c_int>(WATCHDOG_IOCTL_BASE, 10);
 
-// const WATCHDOG_IOCTL_BASE: u8 = b'W'; // Defined in linux/spi/spidev.h
-// const WDIOC_SETTIMEOUT: u8 = 6;
-// ioctl_write_int!(wdg_set_timeout, WATCHDOG_IOCTL_BASE, WDIOC_SETTIMEOUT);
-//
-// const WDG_INTERVAL: nix::sys::ioctl::ioctl_param_type = 30;
+/// Boot status flag: the last reset was caused by the watchdog
+pub const WDIOF_CARDRESET: u32 = 0x0020;
+
+/// Timeout of the monitor mode if none was configured
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+enum Mode {
+    Disabled,
+    Hardware(File),
+    Monitor,
+}
 
 #[derive(Debug)]
 pub struct Wdg {
-    //     path: &'static str,
-    //     file: Option<File>,
-    //     last_ticks: Instant,
+    path: String,
+    /// Timeout to apply on enable, the driver default is used if unset
+    timeout: Option<Duration>,
+    mode: Mode,
+    last_service: Instant,
 }
 
 impl Wdg {
-    pub fn new(_path: &'static str) -> Wdg {
+    pub fn new(path: impl Into<String>) -> Wdg {
         Wdg {
-            // path,
-            // file: None,
-            // last_ticks: Instant::now(),
+            path: path.into(),
+            timeout: None,
+            mode: Mode::Disabled,
+            last_service: Instant::now(),
         }
     }
+
+    fn sysfs_read(&self, attribute: &str) -> Result<u32> {
+        let name = self.path.trim_start_matches("/dev/");
+        let value = fs::read_to_string(format!("/sys/class/watchdog/{}/{}", name, attribute))?;
+        Ok(value.trim().parse()?)
+    }
+
+    fn ioctl_read(file: &File, request: u64) -> Result<u32> {
+        let mut value: c_int = 0;
+        ioctl(file, request, &mut value)?;
+        Ok(value as u32)
+    }
+
+    fn monitor_timeout(&self) -> Duration {
+        self.timeout.unwrap_or(DEFAULT_TIMEOUT)
+    }
 }
 
 impl Watchdog for Wdg {
-    fn enable(&mut self, _monitor: bool) -> Result<()> {
-        Err(Error::NotImplemented)
-        // let mut f = File::create(self.path)?;
-
-        // if monitor {
-        //     write!(f, "V")?;
-        //     self.file = None;
-        //     drop(f);
-        // } else {
-        //     unsafe {
-        //         dbg!(f.as_raw_fd());
-        //         let ret = wdg_set_timeout(f.as_raw_fd(), WDG_INTERVAL);
-        //         dbg!(ret);
-        //     }
-
-        //     self.file = Some(f);
-        // }
-
-        // Ok(())
+    fn enable(&mut self, monitor: bool) -> Result<()> {
+        self.disable()?;
+
+        if !monitor {
+            let file = OpenOptions::new().write(true).open(&self.path)?;
+            self.mode = Mode::Hardware(file);
+            if let Some(timeout) = self.timeout {
+                // stop the watchdog, otherwise it resets the device after the default timeout
+                if let Err(e) = self.set_timeout(timeout) {
+                    self.disable().ok();
+                    return Err(e);
+                }
+            }
+            info!("Watchdog {} enabled", self.path);
+        } else {
+            self.mode = Mode::Monitor;
+        }
+
+        self.service()
     }
 
     fn service(&mut self) -> Result<()> {
-        Err(Error::NotImplemented)
-        // match &mut self.file {
-        //     Some(f) => {
-        //         write!(f, "a")?;
-        //     },
-        //     _ => { /* monitoring mode */ }
-        // }
-
-        // let now = Instant::now();
-        // let diff = now.checked_duration_since(self.last_ticks);
-        // self.last_ticks = now;
-
-        // match diff {
-        //     None => {
-        //         Err(Error::WatchdogTimeout)
-        //     },
-        //     Some(diff) => {
-        //         if diff.as_millis() > (WDG_INTERVAL * 1000) as u128 {
-        //             Err(Error::WatchdogTimeout)
-        //         } else {
-        //             Ok(())
-        //         }
-        //     },
-        // }
+        let elapsed = self.last_service.elapsed();
+        self.last_service = Instant::now();
+
+        match &self.mode {
+            Mode::Disabled => Ok(()),
+            Mode::Hardware(file) => ioctl(file, WDIOC_KEEPALIVE, &mut 0),
+            Mode::Monitor if elapsed > self.monitor_timeout() => Err(Error::WatchdogTimeout),
+            Mode::Monitor => Ok(()),
+        }
+    }
+
+    fn disable(&mut self) -> Result<()> {
+        if let Mode::Hardware(mut file) = std::mem::replace(&mut self.mode, Mode::Disabled) {
+            file.write_all(b"V")?;
+            info!("Watchdog {} disabled", self.path);
+        }
+        Ok(())
+    }
+
+    fn timeout(&mut self) -> Result<Duration> {
+        let seconds = match &self.mode {
+            Mode::Hardware(file) => Wdg::ioctl_read(file, WDIOC_GETTIMEOUT)?,
+            Mode::Monitor => return Ok(self.monitor_timeout()),
+            Mode::Disabled => match self.timeout {
+                Some(timeout) => return Ok(timeout),
+                None => self.sysfs_read("timeout")?,
+            },
+        };
+        Ok(Duration::from_secs(seconds as u64))
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        if timeout.as_secs() == 0 || timeout.as_secs() > c_int::MAX as u64 {
+            return Err(Error::InvalidParameter);
+        }
+
+        if let Mode::Hardware(file) = &self.mode {
+            let mut value = timeout.as_secs() as c_int;
+            ioctl(file, WDIOC_SETTIMEOUT, &mut value)?;
+            // the driver may round the timeout to a supported value
+            self.timeout = Some(Duration::from_secs(value as u64));
+        } else {
+            self.timeout = Some(timeout);
+        }
+        Ok(())
+    }
+
+    fn time_left(&mut self) -> Result<Duration> {
+        let seconds = match &self.mode {
+            Mode::Hardware(file) => Wdg::ioctl_read(file, WDIOC_GETTIMELEFT)?,
+            Mode::Monitor => {
+                return Ok(self
+                    .monitor_timeout()
+                    .saturating_sub(self.last_service.elapsed()))
+            }
+            Mode::Disabled => self.sysfs_read("timeleft")?,
+        };
+        Ok(Duration::from_secs(seconds as u64))
+    }
+
+    fn boot_status(&mut self) -> Result<u32> {
+        match &self.mode {
+            Mode::Hardware(file) => Wdg::ioctl_read(file, WDIOC_GETBOOTSTATUS),
+            _ => self.sysfs_read("bootstatus"),
+        }
     }
 }
 
-// impl Drop for Wdg {
-//     fn drop(&mut self) {
-//         match &mut self.file {
-//             Some(f) => {
-//                 write!(f, "V").unwrap();
-//                 drop(f);
-//             },
-//             _ => { }
-//         }
-//
-//         self.file = None;
-//     }
-// }
+impl Drop for Wdg {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            if let Mode::Hardware(_) = self.mode {
+                error!("Watchdog {} closed on panic, it keeps running", self.path);
+            }
+            return;
+        }
+        self.disable().ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn monitor_test() {
+        let mut wdg = Wdg::new("/dev/watchdog-test");
+        wdg.set_timeout(Duration::from_secs(1)).unwrap();
+        assert!(matches!(
+            wdg.set_timeout(Duration::from_secs(0)),
+            Err(Error::InvalidParameter)
+        ));
+
+        wdg.enable(true).unwrap();
+        assert_eq!(wdg.timeout().unwrap(), Duration::from_secs(1));
+        assert!(wdg.time_left().unwrap() > Duration::from_millis(900));
+        wdg.service().unwrap();
+
+        wdg.last_service -= Duration::from_secs(2);
+        assert_eq!(wdg.time_left().unwrap(), Duration::from_secs(0));
+        assert!(matches!(wdg.service(), Err(Error::WatchdogTimeout)));
+        wdg.service().unwrap();
+
+        assert_eq!(WDIOC_KEEPALIVE, 0x8004_5705);
+        assert_eq!(WDIOC_SETTIMEOUT, 0xC004_5706);
+    }
+
+    #[test]
+    fn enable_failed_test() {
+        // the ioctl fails on a regular file
+        let path = std::env::temp_dir().join(format!("sysworxx-io-wdg-{}", std::process::id()));
+        fs::write(&path, "").unwrap();
+
+        let mut wdg = Wdg::new(path.to_str().unwrap());
+        wdg.set_timeout(Duration::from_secs(10)).unwrap();
+        assert!(wdg.enable(false).is_err());
+        assert!(matches!(wdg.mode, Mode::Disabled));
+        // stopped by the magic close
+        assert_eq!(fs::read(&path).unwrap(), b"V");
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,11 +1,14 @@
 // SPDX-License-Identifier: LGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
 
+use std::time::Duration;
+
 use crate::error::Result;
 use crate::ffi;
+use crate::io::counter::Capture;
 use crate::{
-    AnalogInput, AnalogOutput, CounterInput, DigitalInput, DigitalOutput, IoChannel, PwmOutput,
-    TempSensor, Watchdog,
+    AnalogInput, AnalogOutput, CounterInput, DigitalInput, DigitalOutput, EdgeHandler, EdgeWatch,
+    IoChannel, PwmOutput, TempSensor, Watchdog,
 };
 
 #[derive(Debug)]
@@ -48,6 +51,10 @@ where
     fn set(&mut self, val: bool) -> Result<()> {
         self.inner.set(val)
     }
+
+    fn get(&mut self) -> Result<bool> {
+        self.inner.get()
+    }
 }
 
 impl<T> DigitalInput for Labeled<T>
@@ -69,6 +76,10 @@ where
     fn unregister_callback(&mut self) -> Result<()> {
         self.inner.unregister_callback()
     }
+
+    fn watch_edges(&mut self, handler: EdgeHandler) -> Result<EdgeWatch> {
+        self.inner.watch_edges(handler)
+    }
 }
 
 impl<T> AnalogInput for Labeled<T>
@@ -114,6 +125,21 @@ where
     fn service(&mut self) -> Result<()> {
         self.inner.service()
     }
+    fn disable(&mut self) -> Result<()> {
+        self.inner.disable()
+    }
+    fn timeout(&mut self) -> Result<Duration> {
+        self.inner.timeout()
+    }
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.inner.set_timeout(timeout)
+    }
+    fn time_left(&mut self) -> Result<Duration> {
+        self.inner.time_left()
+    }
+    fn boot_status(&mut self) -> Result<u32> {
+        self.inner.boot_status()
+    }
 }
 
 impl<T> CounterInput for Labeled<T>
@@ -137,6 +163,32 @@ where
     fn get(&mut self) -> Result<i32> {
         self.inner.get()
     }
+    fn reset(&mut self) -> Result<()> {
+        self.inner.reset()
+    }
+    fn set_compare(&mut self, compare: Option<i32>, auto_reload: bool) -> Result<()> {
+        self.inner.set_compare(compare, auto_reload)
+    }
+    fn has_encoder(&self) -> bool {
+        self.inner.has_encoder()
+    }
+    fn register_callback(&mut self, callback: ffi::IoCntCallback, events: u8) -> Result<()> {
+        self.inner.register_callback(callback, events)
+    }
+    fn unregister_callback(&mut self) -> Result<()> {
+        self.inner.unregister_callback()
+    }
+    fn set_index(
+        &mut self,
+        mode: ffi::IoCntIndexMode,
+        trigger: ffi::IoInputTrigger,
+        input: Option<&mut dyn DigitalInput>,
+    ) -> Result<()> {
+        self.inner.set_index(mode, trigger, input)
+    }
+    fn capture(&mut self) -> Result<Option<Capture>> {
+        self.inner.capture()
+    }
 }
 
 impl<T> PwmOutput for Labeled<T>
@@ -152,4 +204,13 @@ where
     fn set_timebase(&mut self, timebase: ffi::IoPwmTimebase) -> Result<()> {
         self.inner.set_timebase(timebase)
     }
+    fn timebase(&self) -> Result<u64> {
+        self.inner.timebase()
+    }
+    fn configure(&mut self, period: u64, duty_cycle: u64) -> Result<()> {
+        self.inner.configure(period, duty_cycle)
+    }
+    fn set_polarity(&mut self, polarity: ffi::IoPwmPolarity) -> Result<()> {
+        self.inner.set_polarity(polarity)
+    }
 }
@@ -12,19 +12,47 @@ extern crate float_cmp;
 
 #[macro_use]
 pub mod macros;
+pub mod axis;
+pub mod blocks;
+pub mod builder;
 pub mod convert;
+pub mod daemon;
 pub mod definition;
 pub mod error;
+pub mod events;
 pub mod ffi;
+pub mod health;
+pub mod http;
 pub mod hw_rev;
 pub mod io;
 pub mod labeled;
+pub mod logger;
+pub mod metrics;
+pub mod modbus;
+pub mod mqtt;
+pub mod opcua;
 pub mod periodic;
+pub mod pid;
+pub mod provider;
+pub mod pto;
+pub mod rules;
+pub mod scheduler;
 pub mod shm;
 pub mod signal;
+pub mod supervisor;
+pub mod systemd;
 
 use crate::error::{Error, Result};
-use std::{fmt, fs::File, io::Write};
+use crate::provider::{ChannelProvider, ProviderInfo};
+use std::{
+    fmt,
+    fs::File,
+    io::Write,
+    time::{Duration, SystemTime},
+};
+
+/// Maximum number of channels per type, as channel numbers are u8 in the C API
+const MAX_CHANNELS: usize = 255;
 
 pub trait IoChannel {
     fn init(&mut self, chan_number: usize) -> Result<()>;
@@ -39,8 +67,19 @@ pub trait IoChannel {
 
 pub trait DigitalOutput: fmt::Debug + Send + IoChannel {
     fn set(&mut self, val: bool) -> Result<()>;
+
+    /// Read back the current state of the output
+    fn get(&mut self) -> Result<bool> {
+        Err(Error::NotImplemented)
+    }
 }
 
+/// Handler of the edges of a digital input, called with the new value and the time of the edge
+pub type EdgeHandler = Box<dyn FnMut(bool, SystemTime) + Send>;
+
+/// Removes the edge handler of a digital input when dropped
+pub type EdgeWatch = Box<dyn fmt::Debug + Send>;
+
 pub trait DigitalInput: fmt::Debug + Send + IoChannel {
     fn get(&mut self) -> Result<bool>;
 
@@ -55,6 +94,12 @@ pub trait DigitalInput: fmt::Debug + Send + IoChannel {
     fn unregister_callback(&mut self) -> Result<()> {
         Err(Error::NotImplemented)
     }
+
+    /// Call `handler` on the edges of the input with the time of the edge taken by the kernel,
+    /// until the returned watch is dropped
+    fn watch_edges(&mut self, _handler: EdgeHandler) -> Result<EdgeWatch> {
+        Err(Error::NotImplemented)
+    }
 }
 
 pub trait AnalogInput: fmt::Debug + Send + IoChannel {
@@ -82,6 +127,29 @@ pub trait TempSensor<T>: fmt::Debug + Send + IoChannel {
 pub trait Watchdog: fmt::Debug + Send {
     fn enable(&mut self, monitor: bool) -> Result<()>;
     fn service(&mut self) -> Result<()>;
+
+    /// Stop the watchdog
+    fn disable(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn timeout(&mut self) -> Result<Duration> {
+        Err(Error::NotImplemented)
+    }
+
+    fn set_timeout(&mut self, _timeout: Duration) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
+
+    /// Time until the watchdog expires
+    fn time_left(&mut self) -> Result<Duration> {
+        Err(Error::NotImplemented)
+    }
+
+    /// Reason of the last reset as WDIOF_* flags of <linux/watchdog.h>
+    fn boot_status(&mut self) -> Result<u32> {
+        Err(Error::NotImplemented)
+    }
 }
 
 pub trait CounterInput: fmt::Debug + Send + IoChannel {
@@ -92,14 +160,70 @@ pub trait CounterInput: fmt::Debug + Send + IoChannel {
         trigger: ffi::IoCntTrigger,
         direction: ffi::IoCntDirection,
     ) -> Result<()>;
+    /// Set the preload and load it into the counter
     fn set_preload(&mut self, preload: i32) -> Result<()>;
     fn get(&mut self) -> Result<i32>;
+
+    /// Load the preload into the counter
+    fn reset(&mut self) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
+
+    /// Set or clear the compare value. With `auto_reload` the counter continues at the preload
+    /// after it passed the compare value, which must be greater than the preload.
+    fn set_compare(&mut self, _compare: Option<i32>, _auto_reload: bool) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
+
+    /// The counter supports `IoCntMode::ABEncoder`
+    fn has_encoder(&self) -> bool {
+        false
+    }
+
+    /// Call `callback` for the `events`, a mask of `ffi::IoCntEvent` bits
+    fn register_callback(&mut self, _callback: ffi::IoCntCallback, _events: u8) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
+
+    fn unregister_callback(&mut self) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
+
+    /// Load the preload or capture the value of the counter on the index pulse, which is the
+    /// index input of the counter hardware or the edges of `input` matching `trigger`
+    fn set_index(
+        &mut self,
+        _mode: ffi::IoCntIndexMode,
+        _trigger: ffi::IoInputTrigger,
+        _input: Option<&mut dyn DigitalInput>,
+    ) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
+
+    /// The value captured on the index pulse since the last call, if any
+    fn capture(&mut self) -> Result<Option<io::counter::Capture>> {
+        Err(Error::NotImplemented)
+    }
 }
 
 pub trait PwmOutput: fmt::Debug + Send + IoChannel {
     fn enable(&mut self, state: bool) -> Result<()>;
     fn setup(&mut self, period: u16, duty_cycle: u16) -> Result<()>;
     fn set_timebase(&mut self, timebase: ffi::IoPwmTimebase) -> Result<()>;
+
+    /// Timebase of `setup()` in nanoseconds
+    fn timebase(&self) -> Result<u64> {
+        Err(Error::NotImplemented)
+    }
+
+    /// Set period and duty cycle in nanoseconds, the duty cycle must not exceed the period
+    fn configure(&mut self, _period: u64, _duty_cycle: u64) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
+
+    fn set_polarity(&mut self, _polarity: ffi::IoPwmPolarity) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
 }
 
 #[derive(Debug)]
@@ -117,6 +241,7 @@ pub struct Io {
     counter_input: Vec<Box<dyn CounterInput>>,
     relay_offset: Option<u8>,
     pwm_outputs: Vec<Box<dyn PwmOutput>>,
+    providers: Vec<ProviderInfo>,
 }
 
 pub struct IoChannelInfo<'a> {
@@ -129,6 +254,9 @@ pub struct IoChannelInfo<'a> {
     pub analog_inputs: &'a Vec<Box<dyn AnalogInput>>,
     pub temp_sensors: &'a Vec<Box<dyn TempSensor<f64>>>,
     pub counter_input: &'a Vec<Box<dyn CounterInput>>,
+    pub analog_outputs: &'a Vec<Box<dyn AnalogOutput>>,
+    pub pwm_outputs: &'a Vec<Box<dyn PwmOutput>>,
+    pub providers: &'a Vec<ProviderInfo>,
 }
 
 impl Io {
@@ -160,7 +288,7 @@ impl Io {
         channels_shutdown!(self.analog_outputs);
         channels_shutdown!(self.temp_sensors);
         channels_shutdown!(self.counter_input);
-        Ok(())
+        self.watchdog.disable()
     }
 
     pub fn get_ticks(&mut self) -> Result<u32> {
@@ -179,6 +307,22 @@ impl Io {
         self.watchdog.service()
     }
 
+    pub fn watchdog_timeout(&mut self) -> Result<Duration> {
+        self.watchdog.timeout()
+    }
+
+    pub fn watchdog_set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.watchdog.set_timeout(timeout)
+    }
+
+    pub fn watchdog_time_left(&mut self) -> Result<Duration> {
+        self.watchdog.time_left()
+    }
+
+    pub fn watchdog_boot_status(&mut self) -> Result<u32> {
+        self.watchdog.boot_status()
+    }
+
     pub fn get_channel_info(&self) -> IoChannelInfo<'_> {
         IoChannelInfo {
             inputs: &self.inputs,
@@ -190,7 +334,117 @@ impl Io {
             analog_inputs: &self.analog_inputs,
             temp_sensors: &self.temp_sensors,
             counter_input: &self.counter_input,
+            analog_outputs: &self.analog_outputs,
+            pwm_outputs: &self.pwm_outputs,
+            providers: &self.providers,
+        }
+    }
+
+    /// Append the channels of a provider to the channel tables. The new channels get initialized
+    /// immediately, so this has to be called after `init()`.
+    pub fn add_provider(&mut self, provider: &mut dyn ChannelProvider) -> Result<ProviderInfo> {
+        let name = provider.name();
+        if self.providers.iter().any(|p| p.name == name) {
+            return Err(Error::InvalidParameter);
+        }
+
+        let mut channels = provider.channels()?;
+
+        let counts = [
+            self.outputs.len() + channels.outputs.len(),
+            self.inputs.len() + channels.inputs.len(),
+            self.analog_inputs.len() + channels.analog_inputs.len(),
+            self.analog_outputs.len() + channels.analog_outputs.len(),
+            self.temp_sensors.len() + channels.temp_sensors.len(),
+        ];
+        if counts.iter().any(|count| *count > MAX_CHANNELS) {
+            error!(
+                "Channel provider '{}' exceeds {} channels",
+                name, MAX_CHANNELS
+            );
+            return Err(Error::InvalidParameter);
+        }
+
+        // all channels are initialized before they are added, so a failing provider does not
+        // leave some of its channels in the tables. The channels initialized before the failing
+        // one are shut down again.
+        fn init<T: IoChannel + fmt::Debug + ?Sized>(
+            first: usize,
+            channels: &mut [Box<T>],
+        ) -> Result<()> {
+            for i in 0..channels.len() {
+                if let Err(e) = channels[i].init(first + i) {
+                    shutdown(&mut channels[..i]);
+                    return Err(e);
+                }
+            }
+            Ok(())
+        }
+
+        fn shutdown<T: IoChannel + fmt::Debug + ?Sized>(channels: &mut [Box<T>]) {
+            for channel in channels.iter_mut() {
+                if let Err(e) = channel.shutdown() {
+                    warn!("Failed to shut down {:?}: {}", channel, e);
+                }
+            }
+        }
+
+        for table in 0..5 {
+            let result = match table {
+                0 => init(self.outputs.len(), &mut channels.outputs),
+                1 => init(self.inputs.len(), &mut channels.inputs),
+                2 => init(self.analog_inputs.len(), &mut channels.analog_inputs),
+                3 => init(self.analog_outputs.len(), &mut channels.analog_outputs),
+                _ => init(self.temp_sensors.len(), &mut channels.temp_sensors),
+            };
+            if let Err(e) = result {
+                // the tables initialized completely
+                if table > 0 {
+                    shutdown(&mut channels.outputs);
+                }
+                if table > 1 {
+                    shutdown(&mut channels.inputs);
+                }
+                if table > 2 {
+                    shutdown(&mut channels.analog_inputs);
+                }
+                if table > 3 {
+                    shutdown(&mut channels.analog_outputs);
+                }
+                return Err(e);
+            }
+        }
+
+        fn append<T: ?Sized>(
+            table: &mut Vec<Box<T>>,
+            channels: Vec<Box<T>>,
+        ) -> std::ops::Range<usize> {
+            let first = table.len();
+            table.extend(channels);
+            first..table.len()
         }
+
+        let info = ProviderInfo {
+            name,
+            description: provider.description(),
+            outputs: append(&mut self.outputs, channels.outputs),
+            inputs: append(&mut self.inputs, channels.inputs),
+            analog_inputs: append(&mut self.analog_inputs, channels.analog_inputs),
+            analog_outputs: append(&mut self.analog_outputs, channels.analog_outputs),
+            temp_sensors: append(&mut self.temp_sensors, channels.temp_sensors),
+        };
+
+        info!(
+            "Added channel provider '{}' {}",
+            info.name, info.description
+        );
+
+        self.providers.push(info.clone());
+        Ok(info)
+    }
+
+    pub fn providers(&self) -> &[ProviderInfo] {
+        &self.providers
     }
 
     pub fn get_hardware_info(&mut self, hwinfo: &mut ffi::IoHwInfo) -> Result<()> {
@@ -201,6 +455,11 @@ impl Io {
         hwinfo.m_uAoChannels = self.analog_outputs.len() as u8;
         hwinfo.m_uTmpChannels = self.temp_sensors.len() as u8;
         hwinfo.m_uCntChannels = self.counter_input.len() as u8;
+        hwinfo.m_uEncChannels = self
+            .counter_input
+            .iter()
+            .filter(|c| c.has_encoder())
+            .count() as u8;
         match self.relay_offset {
             None => {
                 hwinfo.m_uLegacyRelayOffset = 0;
@@ -247,6 +506,8 @@ impl Io {
             temp_sensors: { },
             counter_inputs: { },
             pwm_outputs: { },
+            providers: [ ],
+            modbus: { },
         };
 
         fn add_to_json<T: IoChannel + ?Sized>(
@@ -271,6 +532,12 @@ impl Io {
         add_to_json(&mut obj, "temp_sensors", &self.temp_sensors)?;
         add_to_json(&mut obj, "counter_inputs", &self.counter_input)?;
         add_to_json(&mut obj, "analog_outputs", &self.analog_outputs)?;
+        for provider in &self.providers {
+            obj["providers"]
+                .push(provider.to_json())
+                .map_err(|_| Error::GenericError)?;
+        }
+        obj["modbus"] = modbus::map::RegisterMap::new(&self.get_channel_info()).to_json();
         let obj_str = obj.dump();
         let mut file = File::create(path).expect("Error creating file to write information!");
         file.write(obj_str.as_bytes())
@@ -287,6 +554,14 @@ impl Io {
         self.err_led.set(value)
     }
 
+    pub fn get_run_led(&mut self) -> Result<bool> {
+        self.run_led.get()
+    }
+
+    pub fn get_err_led(&mut self) -> Result<bool> {
+        self.err_led.get()
+    }
+
     pub fn get_run_switch(&mut self) -> Result<bool> {
         self.run_switch.get()
     }
@@ -302,6 +577,13 @@ impl Io {
             .set(value)
     }
 
+    pub fn output_get(&mut self, channel: usize) -> Result<bool> {
+        self.outputs
+            .get_mut(channel)
+            .ok_or(Error::InvalidChannel)?
+            .get()
+    }
+
     pub fn input_get(&mut self, channel: usize) -> Result<bool> {
         self.inputs
             .get_mut(channel)
@@ -395,6 +677,25 @@ impl Io {
             .set_preload(preload)
     }
 
+    pub fn cnt_reset(&mut self, channel: usize) -> Result<()> {
+        self.counter_input
+            .get_mut(channel)
+            .ok_or(Error::InvalidChannel)?
+            .reset()
+    }
+
+    pub fn cnt_set_compare(
+        &mut self,
+        channel: usize,
+        compare: Option<i32>,
+        auto_reload: bool,
+    ) -> Result<()> {
+        self.counter_input
+            .get_mut(channel)
+            .ok_or(Error::InvalidChannel)?
+            .set_compare(compare, auto_reload)
+    }
+
     pub fn cnt_get(&mut self, channel: usize) -> Result<i32> {
         self.counter_input
             .get_mut(channel)
@@ -402,6 +703,57 @@ impl Io {
             .get()
     }
 
+    pub fn cnt_register_callback(
+        &mut self,
+        channel: usize,
+        callback: ffi::IoCntCallback,
+        events: u8,
+    ) -> Result<()> {
+        self.counter_input
+            .get_mut(channel)
+            .ok_or(Error::InvalidChannel)?
+            .register_callback(callback, events)
+    }
+
+    pub fn cnt_unregister_callback(&mut self, channel: usize) -> Result<()> {
+        self.counter_input
+            .get_mut(channel)
+            .ok_or(Error::InvalidChannel)?
+            .unregister_callback()
+    }
+
+    /// Set the handling of the index pulse of a counter, `input` is the digital input of the
+    /// index pulse or `None` for the index input of the counter hardware
+    pub fn cnt_set_index(
+        &mut self,
+        channel: usize,
+        mode: ffi::IoCntIndexMode,
+        trigger: ffi::IoInputTrigger,
+        input: Option<usize>,
+    ) -> Result<()> {
+        let counter = self
+            .counter_input
+            .get_mut(channel)
+            .ok_or(Error::InvalidChannel)?;
+        let input: Option<&mut dyn DigitalInput> = match input {
+            Some(input) => Some(
+                self.inputs
+                    .get_mut(input)
+                    .ok_or(Error::InvalidParameter)?
+                    .as_mut(),
+            ),
+            None => None,
+        };
+        counter.set_index(mode, trigger, input)
+    }
+
+    pub fn cnt_capture(&mut self, channel: usize) -> Result<Option<io::counter::Capture>> {
+        self.counter_input
+            .get_mut(channel)
+            .ok_or(Error::InvalidChannel)?
+            .capture()
+    }
+
     pub fn pwm_enable(&mut self, channel: usize, state: bool) -> Result<()> {
         self.pwm_outputs
             .get_mut(channel)
@@ -422,4 +774,41 @@ impl Io {
             .ok_or(Error::InvalidChannel)?
             .set_timebase(timebase)
     }
+
+    /// Timebase of a PWM output in nanoseconds
+    pub fn pwm_timebase(&self, channel: usize) -> Result<u64> {
+        self.pwm_outputs
+            .get(channel)
+            .ok_or(Error::InvalidChannel)?
+            .timebase()
+    }
+
+    /// Set period and duty cycle of a PWM output in nanoseconds
+    pub fn pwm_configure(&mut self, channel: usize, period: u64, duty_cycle: u64) -> Result<()> {
+        if period == 0 || duty_cycle > period {
+            return Err(Error::InvalidParameter);
+        }
+        self.pwm_outputs
+            .get_mut(channel)
+            .ok_or(Error::InvalidChannel)?
+            .configure(period, duty_cycle)
+    }
+
+    /// Set frequency (Hz) and duty cycle (percent) of a PWM output, rounded to nanoseconds
+    pub fn pwm_set_frequency(
+        &mut self,
+        channel: usize,
+        frequency: f64,
+        duty_cycle: f64,
+    ) -> Result<()> {
+        let (period, duty_cycle) = convert::pwm::from_frequency(frequency, duty_cycle)?;
+        self.pwm_configure(channel, period, duty_cycle)
+    }
+
+    pub fn pwm_set_polarity(&mut self, channel: usize, polarity: ffi::IoPwmPolarity) -> Result<()> {
+        self.pwm_outputs
+            .get_mut(channel)
+            .ok_or(Error::InvalidChannel)?
+            .set_polarity(polarity)
+    }
 }
@@ -0,0 +1,400 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Line based log files (CSV, InfluxDB line protocol) with rotation and retention. Files are only
+// appended, so a power loss can at most lose the lines written after the last sync.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::{format_time, Record, Sink, Value};
+use crate::error::Result;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rotation {
+    pub directory: PathBuf,
+    /// Files are named "<prefix>-<UTC time>.<extension>"
+    pub prefix: String,
+    pub max_file_size: u64,
+    pub max_file_age: Duration,
+    /// Remove the oldest files if all files exceed this size, 0 to disable
+    pub max_total_size: u64,
+    pub max_age: Option<Duration>,
+}
+
+struct Current {
+    writer: BufWriter<File>,
+    path: PathBuf,
+    size: u64,
+    created: SystemTime,
+}
+
+pub struct RotatingFile {
+    rotation: Rotation,
+    extension: &'static str,
+    /// First line of each file
+    header: Option<String>,
+    current: Option<Current>,
+}
+
+/// Sync a directory, so that created and removed files survive a power loss
+fn sync_directory(directory: &Path) -> Result<()> {
+    File::open(directory)?.sync_all()?;
+    Ok(())
+}
+
+impl RotatingFile {
+    pub fn new(
+        rotation: Rotation,
+        extension: &'static str,
+        header: Option<String>,
+    ) -> Result<RotatingFile> {
+        fs::create_dir_all(&rotation.directory)?;
+        Ok(RotatingFile {
+            rotation,
+            extension,
+            header,
+            current: None,
+        })
+    }
+
+    /// Log files sorted from oldest to newest
+    pub fn files(&self) -> Result<Vec<PathBuf>> {
+        let prefix = format!("{}-", self.rotation.prefix);
+        let suffix = format!(".{}", self.extension);
+        let mut files = vec![];
+        for entry in fs::read_dir(&self.rotation.directory)? {
+            let path = entry?.path();
+            let matches = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(&suffix));
+            if matches {
+                files.push(path);
+            }
+        }
+        // the names contain the creation time
+        files.sort();
+        Ok(files)
+    }
+
+    fn needs_rotation(&self, now: SystemTime) -> bool {
+        match &self.current {
+            None => true,
+            Some(current) => {
+                current.size >= self.rotation.max_file_size
+                    || now.duration_since(current.created).unwrap_or_default()
+                        >= self.rotation.max_file_age
+            }
+        }
+    }
+
+    fn rotate(&mut self, now: SystemTime) -> Result<()> {
+        self.close()?;
+
+        let time: String = format_time(now)
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '.')
+            .collect();
+        let mut path = self.rotation.directory.join(format!(
+            "{}-{}.{}",
+            self.rotation.prefix, time, self.extension
+        ));
+        let mut counter = 1;
+        while path.exists() {
+            path = self.rotation.directory.join(format!(
+                "{}-{}-{}.{}",
+                self.rotation.prefix, time, counter, self.extension
+            ));
+            counter += 1;
+        }
+
+        let file = OpenOptions::new()
+            .create_new(true)
+            .append(true)
+            .open(&path)?;
+        let mut current = Current {
+            writer: BufWriter::new(file),
+            path,
+            size: 0,
+            created: now,
+        };
+        if let Some(header) = &self.header {
+            writeln!(current.writer, "{}", header)?;
+            current.size += header.len() as u64 + 1;
+        }
+        self.current = Some(current);
+        sync_directory(&self.rotation.directory)?;
+
+        self.apply_retention(now)
+    }
+
+    /// Sync and close the current file
+    fn close(&mut self) -> Result<()> {
+        if let Some(mut current) = self.current.take() {
+            current.writer.flush()?;
+            current.writer.get_ref().sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// Remove the oldest files exceeding the total size or the maximum age
+    pub fn apply_retention(&mut self, now: SystemTime) -> Result<()> {
+        let current = self.current.as_ref().map(|current| current.path.clone());
+        let mut files = vec![];
+        for path in self.files()? {
+            let metadata = fs::metadata(&path)?;
+            // the current file may contain buffered data
+            let size = match &self.current {
+                Some(current) if current.path == path => current.size,
+                _ => metadata.len(),
+            };
+            files.push((path, size, metadata.modified()?));
+        }
+
+        let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+        let mut removed = false;
+        for (path, size, modified) in files {
+            if Some(&path) == current.as_ref() {
+                continue;
+            }
+            let too_large =
+                self.rotation.max_total_size > 0 && total > self.rotation.max_total_size;
+            let too_old = self
+                .rotation
+                .max_age
+                .is_some_and(|age| now.duration_since(modified).unwrap_or_default() > age);
+            if too_large || too_old {
+                debug!("Remove log file {}", path.display());
+                fs::remove_file(&path)?;
+                total -= size;
+                removed = true;
+            }
+        }
+        if removed {
+            sync_directory(&self.rotation.directory)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_line(&mut self, line: &str, now: SystemTime) -> Result<()> {
+        if self.needs_rotation(now) {
+            self.rotate(now)?;
+        }
+        let current = self.current.as_mut().unwrap();
+        writeln!(current.writer, "{}", line)?;
+        current.size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    pub fn sync(&mut self) -> Result<()> {
+        if let Some(current) = &mut self.current {
+            current.writer.flush()?;
+            current.writer.get_ref().sync_data()?;
+        }
+        if self.rotation.max_age.is_some() {
+            self.apply_retention(SystemTime::now())?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for RotatingFile {
+    fn drop(&mut self) {
+        self.close().ok();
+    }
+}
+
+/// CSV files with the columns time (ISO 8601, UTC), channel and value
+pub struct CsvSink {
+    file: RotatingFile,
+}
+
+impl CsvSink {
+    pub fn new(rotation: Rotation) -> Result<CsvSink> {
+        Ok(CsvSink {
+            file: RotatingFile::new(rotation, "csv", Some("time,channel,value".to_string()))?,
+        })
+    }
+}
+
+/// Quote a CSV field if required (RFC 4180)
+fn csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl Sink for CsvSink {
+    fn write(&mut self, records: &[Record]) -> Result<()> {
+        for record in records {
+            let line = format!(
+                "{},{},{}",
+                format_time(record.time),
+                csv_field(&record.channel),
+                record.value
+            );
+            self.file.write_line(&line, record.time)?;
+        }
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        self.file.sync()
+    }
+}
+
+/// InfluxDB line protocol files with the measurement "sysworxx_io", the tags device and channel
+/// and the field value. Files can be imported with `influx write --file`.
+pub struct InfluxSink {
+    file: RotatingFile,
+    device: String,
+}
+
+/// Escape a tag value of the line protocol
+fn influx_tag(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if [',', '=', ' '].contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl InfluxSink {
+    pub fn new(rotation: Rotation, device: &str) -> Result<InfluxSink> {
+        Ok(InfluxSink {
+            file: RotatingFile::new(rotation, "lp", None)?,
+            device: influx_tag(device),
+        })
+    }
+
+    fn line(&self, record: &Record) -> String {
+        let value = match record.value {
+            Value::Bool(value) => value.to_string(),
+            Value::Int(value) => format!("{}i", value),
+            Value::Float(value) => format!("{:?}", value),
+        };
+        let time = record.time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        format!(
+            "sysworxx_io,device={},channel={} value={} {}",
+            self.device,
+            influx_tag(&record.channel),
+            value,
+            time.as_nanos()
+        )
+    }
+}
+
+impl Sink for InfluxSink {
+    fn write(&mut self, records: &[Record]) -> Result<()> {
+        for record in records {
+            let line = self.line(record);
+            self.file.write_line(&line, record.time)?;
+        }
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        self.file.sync()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("sysworxx-io-{}-{}", name, std::process::id()));
+        fs::remove_dir_all(&directory).ok();
+        directory
+    }
+
+    fn record(ms: u64, channel: &str, value: Value) -> Record {
+        Record {
+            time: UNIX_EPOCH + Duration::from_millis(1_700_000_000_000 + ms),
+            channel: channel.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn csv_rotation_test() {
+        let directory = directory("csv");
+        let rotation = Rotation {
+            directory: directory.clone(),
+            prefix: "test".to_string(),
+            max_file_size: 60,
+            max_file_age: Duration::from_secs(3600),
+            max_total_size: 150,
+            max_age: None,
+        };
+        let mut sink = CsvSink::new(rotation).unwrap();
+
+        sink.write(&[
+            record(0, "AI0", Value::Int(1)),
+            record(1, "a,b", Value::Bool(true)),
+        ])
+        .unwrap();
+        sink.sync().unwrap();
+        let files = sink.file.files().unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            fs::read_to_string(&files[0]).unwrap(),
+            "time,channel,value\n2023-11-14T22:13:20.000Z,AI0,1\n\
+             2023-11-14T22:13:20.001Z,\"a,b\",1\n"
+        );
+
+        // every file holds the header and two records, the oldest files are removed
+        for i in 0..8 {
+            sink.write(&[record(10 + i, "TMP0", Value::Float(21.5))])
+                .unwrap();
+        }
+        sink.sync().unwrap();
+        let files = sink.file.files().unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files[1]
+            .to_str()
+            .unwrap()
+            .ends_with("test-20231114T221320.016Z.csv"));
+
+        drop(sink);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn influx_line_test() {
+        let directory = directory("influx");
+        let rotation = Rotation {
+            directory: directory.clone(),
+            prefix: "test".to_string(),
+            max_file_size: 1024,
+            max_file_age: Duration::from_secs(3600),
+            max_total_size: 0,
+            max_age: None,
+        };
+        let sink = InfluxSink::new(rotation, "ctr 700").unwrap();
+        assert_eq!(
+            sink.line(&record(5, "AI0", Value::Int(-3))),
+            "sysworxx_io,device=ctr\\ 700,channel=AI0 value=-3i 1700000000005000000"
+        );
+        assert_eq!(
+            sink.line(&record(0, "a=b", Value::Float(20.0))),
+            "sysworxx_io,device=ctr\\ 700,channel=a\\=b value=20.0 1700000000000000000"
+        );
+        assert_eq!(
+            sink.line(&record(0, "DI0", Value::Bool(false))),
+            "sysworxx_io,device=ctr\\ 700,channel=DI0 value=false 1700000000000000000"
+        );
+
+        drop(sink);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
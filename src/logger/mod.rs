@@ -0,0 +1,696 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Data logger sampling configured inputs of an `Io` periodically and/or on change and writing
+// them to rotating CSV files, InfluxDB line protocol files or a SQLite database.
+//
+// Records are buffered and made persistent every `sync_interval` (fsync, respectively a database
+// commit). If a powerfail input is configured, all records are synced as soon as it becomes
+// active and logging is suspended until the input is inactive again. This way a power loss
+// never leaves partially written data behind.
+//
+// Configuration (INI):
+//
+//   [logger]
+//   sink = csv                   ; csv, influx or sqlite
+//   path = /var/log/sysworxx-io  ; directory of the files, database file for sqlite
+//
+//   [channel AI0]                ; channel label or kind and index (di0, ai0, tmp0, cnt0)
+//   interval = 1000              ; sample every second
+//   on_change = true             ; additionally log changes larger than the deadband
+//   deadband = 10
+
+pub mod file;
+pub mod sqlite;
+
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crossbeam_channel::Receiver;
+use ini::ini::Properties;
+use ini::Ini;
+
+use crate::error::{Error, Result};
+use crate::events::{self, InputEvent};
+use crate::Io;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SinkType {
+    Csv,
+    Influx,
+    Sqlite,
+}
+
+impl FromStr for SinkType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<SinkType> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(SinkType::Csv),
+            "influx" => Ok(SinkType::Influx),
+            "sqlite" => Ok(SinkType::Sqlite),
+            _ => Err(Error::InvalidParameter),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelConfig {
+    /// Channel label or kind and index, e.g. "ai0"
+    pub channel: String,
+    /// Name of the channel in the records, the channel label by default
+    pub name: Option<String>,
+    /// Sample periodically
+    pub interval: Option<Duration>,
+    /// Log changes larger than the deadband, checked every poll interval
+    pub on_change: bool,
+    pub deadband: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub sink: SinkType,
+    /// Directory of the log files or path of the database
+    pub path: PathBuf,
+    pub poll_interval: Duration,
+    pub sync_interval: Duration,
+    /// Input signalling an imminent power loss (label or index)
+    pub powerfail_input: Option<String>,
+    /// Level of the powerfail input if active
+    pub powerfail_active_high: bool,
+    /// Start a new file if the current one exceeds this size (bytes)
+    pub max_file_size: u64,
+    /// Start a new file if the current one is older
+    pub max_file_age: Duration,
+    /// Remove the oldest data if all files (the database) exceed this size, 0 to disable
+    pub max_total_size: u64,
+    /// Remove data older than this, disabled if `None`
+    pub max_age: Option<Duration>,
+    pub channels: Vec<ChannelConfig>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            sink: SinkType::Csv,
+            path: PathBuf::from("/var/log/sysworxx-io"),
+            poll_interval: Duration::from_millis(100),
+            sync_interval: Duration::from_secs(10),
+            powerfail_input: None,
+            powerfail_active_high: false,
+            max_file_size: 1024 * 1024,
+            max_file_age: Duration::from_secs(24 * 60 * 60),
+            max_total_size: 64 * 1024 * 1024,
+            max_age: Some(Duration::from_secs(30 * 24 * 60 * 60)),
+            channels: vec![],
+        }
+    }
+}
+
+fn parse<T: FromStr>(value: Option<&String>, default: T) -> Result<T> {
+    match value {
+        Some(value) => value.trim().parse().map_err(|_| Error::InvalidParameter),
+        None => Ok(default),
+    }
+}
+
+fn parse_channel(channel: &str, section: &Properties) -> Result<ChannelConfig> {
+    let interval: u64 = parse(section.get("interval"), 0)?;
+    let config = ChannelConfig {
+        channel: channel.trim().to_string(),
+        name: section.get("name").map(|name| name.trim().to_string()),
+        interval: match interval {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        },
+        on_change: parse(section.get("on_change"), false)?,
+        deadband: parse(section.get("deadband"), 0.0)?,
+    };
+    if config.interval.is_none() && !config.on_change {
+        error!(
+            "Channel {} is neither sampled nor logged on change",
+            channel
+        );
+        return Err(Error::InvalidParameter);
+    }
+    Ok(config)
+}
+
+impl Config {
+    /// Load the configuration from an INI file with the section "logger" and a section
+    /// "channel <name>" per logged channel
+    pub fn load(path: &str) -> Result<Config> {
+        let ini = Ini::load_from_file(path).map_err(|e| {
+            error!("Failed to load {}: {}", path, e);
+            Error::InvalidParameter
+        })?;
+        Config::from_ini(&ini)
+    }
+
+    pub fn from_ini(ini: &Ini) -> Result<Config> {
+        let mut config = Config::default();
+
+        if let Some(logger) = ini.section(Some("logger")) {
+            config.sink = parse(logger.get("sink"), config.sink)?;
+            if let Some(path) = logger.get("path") {
+                config.path = PathBuf::from(path.trim());
+            }
+            config.poll_interval = Duration::from_millis(parse(
+                logger.get("poll_interval"),
+                config.poll_interval.as_millis() as u64,
+            )?);
+            config.sync_interval = Duration::from_secs(parse(
+                logger.get("sync_interval"),
+                config.sync_interval.as_secs(),
+            )?);
+            config.powerfail_input = logger
+                .get("powerfail_input")
+                .map(|input| input.trim().to_string())
+                .filter(|input| !input.is_empty());
+            config.powerfail_active_high = match logger.get("powerfail_level") {
+                None => config.powerfail_active_high,
+                Some(level) if level.trim() == "high" => true,
+                Some(level) if level.trim() == "low" => false,
+                Some(_) => return Err(Error::InvalidParameter),
+            };
+            config.max_file_size = parse(logger.get("max_file_size"), config.max_file_size)?;
+            config.max_file_age = Duration::from_secs(parse(
+                logger.get("max_file_age"),
+                config.max_file_age.as_secs(),
+            )?);
+            config.max_total_size = parse(logger.get("max_total_size"), config.max_total_size)?;
+            config.max_age = match parse(
+                logger.get("max_age"),
+                config.max_age.map_or(0, |age| age.as_secs()),
+            )? {
+                0 => None,
+                seconds => Some(Duration::from_secs(seconds)),
+            };
+        }
+
+        for (name, section) in ini.iter() {
+            if let Some(channel) = name.as_ref().and_then(|name| name.strip_prefix("channel ")) {
+                config.channels.push(parse_channel(channel, section)?);
+            }
+        }
+        config.channels.sort_by(|a, b| a.channel.cmp(&b.channel));
+
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+}
+
+impl Value {
+    fn as_f64(self) -> f64 {
+        match self {
+            Value::Bool(value) => value as u8 as f64,
+            Value::Int(value) => value as f64,
+            Value::Float(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(value) => write!(f, "{}", *value as u8),
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Float(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub time: SystemTime,
+    pub channel: String,
+    pub value: Value,
+}
+
+/// Time in UTC as ISO 8601 string with milliseconds, e.g. "2025-01-31T12:00:00.000Z"
+pub fn format_time(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs() as libc::time_t;
+    // SAFETY: gmtime_r only writes to the given struct
+    let tm = unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        libc::gmtime_r(&seconds, &mut tm);
+        tm
+    };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Storage of the records
+pub trait Sink: Send {
+    /// Append records, which may be buffered until the next `sync`
+    fn write(&mut self, records: &[Record]) -> Result<()>;
+
+    /// Store all written records persistently and apply the retention limits
+    fn sync(&mut self) -> Result<()>;
+}
+
+/// Open the sink selected by the configuration
+pub fn open_sink(config: &Config, device: &str) -> Result<Box<dyn Sink>> {
+    let rotation = file::Rotation {
+        directory: config.path.clone(),
+        prefix: "sysworxx-io".to_string(),
+        max_file_size: config.max_file_size,
+        max_file_age: config.max_file_age,
+        max_total_size: config.max_total_size,
+        max_age: config.max_age,
+    };
+    Ok(match config.sink {
+        SinkType::Csv => Box::new(file::CsvSink::new(rotation)?),
+        SinkType::Influx => Box::new(file::InfluxSink::new(rotation, device)?),
+        SinkType::Sqlite => Box::new(sqlite::SqliteSink::open(
+            &config.path,
+            config.max_total_size,
+            config.max_age,
+        )?),
+    })
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Kind {
+    Input,
+    AnalogInput,
+    Temperature,
+    Counter,
+}
+
+impl Kind {
+    /// Prefix of the channels without label, e.g. "ai0"
+    fn prefix(self) -> &'static str {
+        match self {
+            Kind::Input => "di",
+            Kind::AnalogInput => "ai",
+            Kind::Temperature => "tmp",
+            Kind::Counter => "cnt",
+        }
+    }
+}
+
+/// Find a channel by label (case insensitive) or by kind and index, returns also its name
+fn find_channel(io: &Io, channel: &str) -> Option<(Kind, usize, String)> {
+    let info = io.get_channel_info();
+    let kinds = [
+        (
+            Kind::Input,
+            info.inputs.iter().map(|c| c.label()).collect::<Vec<_>>(),
+        ),
+        (
+            Kind::AnalogInput,
+            info.analog_inputs.iter().map(|c| c.label()).collect(),
+        ),
+        (
+            Kind::Temperature,
+            info.temp_sensors.iter().map(|c| c.label()).collect(),
+        ),
+        (
+            Kind::Counter,
+            info.counter_input.iter().map(|c| c.label()).collect(),
+        ),
+    ];
+
+    for (kind, labels) in &kinds {
+        for (index, label) in labels.iter().enumerate() {
+            let fallback = format!("{}{}", kind.prefix(), index);
+            if label.is_some_and(|label| label.eq_ignore_ascii_case(channel))
+                || fallback.eq_ignore_ascii_case(channel)
+            {
+                return Some((*kind, index, label.map_or(fallback, str::to_string)));
+            }
+        }
+    }
+    None
+}
+
+fn read(io: &mut Io, kind: Kind, index: usize) -> Result<Value> {
+    Ok(match kind {
+        Kind::Input => Value::Bool(io.input_get(index)?),
+        Kind::AnalogInput => Value::Int(io.analog_input_get(index)?),
+        Kind::Temperature => Value::Float(io.tmp_input_get(index)?),
+        Kind::Counter => Value::Int(io.cnt_get(index)?.into()),
+    })
+}
+
+struct Source {
+    kind: Kind,
+    index: usize,
+    name: String,
+    config: ChannelConfig,
+    next_sample: Instant,
+    /// Last logged value
+    last: Option<Value>,
+}
+
+impl Source {
+    /// Check if the value has to be logged and update the state
+    fn update(&mut self, value: Value, now: Instant) -> bool {
+        let mut log = false;
+        if let Some(interval) = self.config.interval {
+            if now >= self.next_sample {
+                log = true;
+                // keep the sampling grid, but do not catch up missed samples
+                self.next_sample += interval;
+                if self.next_sample <= now {
+                    self.next_sample = now + interval;
+                }
+            }
+        }
+        if self.config.on_change {
+            log |= match self.last {
+                None => true,
+                Some(last) => {
+                    value != last && (value.as_f64() - last.as_f64()).abs() >= self.config.deadband
+                }
+            };
+        }
+        if log {
+            self.last = Some(value);
+        }
+        log
+    }
+}
+
+pub struct Logger {
+    io: Io,
+    sink: Box<dyn Sink>,
+    sources: Vec<Source>,
+    powerfail: Option<usize>,
+    powerfail_active_high: bool,
+    powerfail_events: Option<Receiver<InputEvent>>,
+    /// Logging is suspended while the powerfail input is active
+    suspended: bool,
+    poll_interval: Duration,
+    sync_interval: Duration,
+    last_sync: Instant,
+}
+
+impl Logger {
+    /// Create a logger for an initialized `Io` with the sink selected by the configuration
+    pub fn new(io: Io, device: &str, config: &Config) -> Result<Logger> {
+        let sink = open_sink(config, device)?;
+        Logger::with_sink(io, config, sink)
+    }
+
+    pub fn with_sink(mut io: Io, config: &Config, sink: Box<dyn Sink>) -> Result<Logger> {
+        let now = Instant::now();
+        let mut sources = vec![];
+        for channel in &config.channels {
+            let (kind, index, label) = find_channel(&io, &channel.channel).ok_or_else(|| {
+                error!("Unknown channel {}", channel.channel);
+                Error::InvalidChannel
+            })?;
+            sources.push(Source {
+                kind,
+                index,
+                name: channel.name.clone().unwrap_or(label),
+                config: channel.clone(),
+                next_sample: now,
+                last: None,
+            });
+        }
+
+        let powerfail = match &config.powerfail_input {
+            Some(input) => match find_channel(&io, input) {
+                Some((Kind::Input, index, _)) => Some(index),
+                _ => {
+                    error!("Unknown powerfail input {}", input);
+                    return Err(Error::InvalidChannel);
+                }
+            },
+            None => None,
+        };
+
+        // edges are handled immediately if the input supports callbacks, otherwise it is polled
+        let mut powerfail_events = None;
+        if let Some(index) = powerfail {
+            let (tx, rx) = crossbeam_channel::bounded(1);
+            match events::register(&mut io, index) {
+                Ok(()) => {
+                    events::subscribe(tx);
+                    powerfail_events = Some(rx);
+                }
+                Err(e) => debug!("No callback for powerfail input {}: {}", index, e),
+            }
+        }
+
+        Ok(Logger {
+            io,
+            sink,
+            sources,
+            powerfail,
+            powerfail_active_high: config.powerfail_active_high,
+            powerfail_events,
+            suspended: false,
+            poll_interval: config.poll_interval,
+            sync_interval: config.sync_interval,
+            last_sync: now,
+        })
+    }
+
+    /// Check the powerfail input, syncs and suspends logging if it is active
+    pub fn check_powerfail(&mut self) -> Result<()> {
+        let index = match self.powerfail {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+        let active = self.io.input_get(index)? == self.powerfail_active_high;
+        if active && !self.suspended {
+            warn!("Powerfail detected, logging suspended");
+            self.suspended = true;
+            self.sync()?;
+        } else if !active && self.suspended {
+            info!("Power restored, logging resumed");
+            self.suspended = false;
+        }
+        Ok(())
+    }
+
+    /// Sample all due channels and sync the sink if the sync interval elapsed
+    pub fn poll(&mut self, now: Instant) -> Result<()> {
+        self.check_powerfail()?;
+        if self.suspended {
+            return Ok(());
+        }
+
+        let time = SystemTime::now();
+        let mut records = vec![];
+        for source in &mut self.sources {
+            match read(&mut self.io, source.kind, source.index) {
+                Ok(value) => {
+                    if source.update(value, now) {
+                        records.push(Record {
+                            time,
+                            channel: source.name.clone(),
+                            value,
+                        });
+                    }
+                }
+                Err(e) => debug!("Failed to read {}: {}", source.name, e),
+            }
+        }
+        if !records.is_empty() {
+            self.sink.write(&records)?;
+        }
+
+        if now.duration_since(self.last_sync) >= self.sync_interval {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    pub fn sync(&mut self) -> Result<()> {
+        self.last_sync = Instant::now();
+        self.sink.sync()
+    }
+
+    /// Log until a message is received from `stop`, the records are synced before returning
+    pub fn run<T>(&mut self, stop: &Receiver<T>) -> Result<()> {
+        let ticker = crossbeam_channel::tick(self.poll_interval);
+        let powerfail = self
+            .powerfail_events
+            .clone()
+            .unwrap_or_else(crossbeam_channel::never);
+
+        self.poll(Instant::now())?;
+        loop {
+            crossbeam_channel::select! {
+                recv(ticker) -> now => {
+                    if let Err(e) = self.poll(now.unwrap()) {
+                        error!("Logging failed: {}", e);
+                    }
+                }
+                recv(powerfail) -> _ => {
+                    if let Err(e) = self.check_powerfail() {
+                        error!("Powerfail handling failed: {}", e);
+                    }
+                }
+                recv(stop) -> _ => break,
+            }
+        }
+        self.sync()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+    use crate::labeled::Labeled;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Memory {
+        records: Vec<Record>,
+        synced: usize,
+    }
+
+    struct MemorySink(Arc<Mutex<Memory>>);
+
+    impl Sink for MemorySink {
+        fn write(&mut self, records: &[Record]) -> Result<()> {
+            self.0.lock().unwrap().records.extend_from_slice(records);
+            Ok(())
+        }
+
+        fn sync(&mut self) -> Result<()> {
+            let mut memory = self.0.lock().unwrap();
+            memory.synced = memory.records.len();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn config_test() {
+        let ini = Ini::load_from_str(
+            "[logger]\nsink = influx\npath = /tmp/log\nmax_age = 0\npowerfail_input = /Powerfail\n\
+             [channel TMP0]\ninterval = 500\n[channel ai1]\non_change = true\ndeadband = 2.5\n",
+        )
+        .unwrap();
+        let config = Config::from_ini(&ini).unwrap();
+        assert_eq!(config.sink, SinkType::Influx);
+        assert_eq!(config.path, PathBuf::from("/tmp/log"));
+        assert_eq!(config.max_age, None);
+        assert_eq!(config.powerfail_input.as_deref(), Some("/Powerfail"));
+        assert!(!config.powerfail_active_high);
+        assert_eq!(config.channels.len(), 2);
+        assert_eq!(config.channels[0].channel, "TMP0");
+        assert_eq!(
+            config.channels[0].interval,
+            Some(Duration::from_millis(500))
+        );
+        assert!(config.channels[1].on_change);
+        assert_eq!(config.channels[1].deadband, 2.5);
+
+        let ini = Ini::load_from_str("[channel ai0]\ndeadband = 1\n").unwrap();
+        assert!(Config::from_ini(&ini).is_err());
+    }
+
+    #[test]
+    fn logger_test() {
+        let sim = Simulator::new("SIM", 2, 1, 1);
+        let mut io = IoBuilder::new()
+            .output(Box::new(sim.output(0)))
+            .output(Box::new(sim.output(1)))
+            .input(Box::new(Labeled::new("DI0", sim.input(0))))
+            .input(Box::new(Labeled::new("/Powerfail", sim.input(1))))
+            .analog_input(Box::new(sim.analog_input(0)))
+            .temp_sensor(Box::new(Labeled::new("CPU", sim.temp_sensor(0))))
+            .build();
+        io.init().unwrap();
+        // power is good while the (active low) powerfail input is set
+        io.output_set(1, true).unwrap();
+
+        let config = Config {
+            powerfail_input: Some("/Powerfail".to_string()),
+            channels: vec![
+                ChannelConfig {
+                    channel: "ai0".to_string(),
+                    name: Some("level".to_string()),
+                    interval: None,
+                    on_change: true,
+                    deadband: 10.0,
+                },
+                ChannelConfig {
+                    channel: "cpu".to_string(),
+                    name: None,
+                    interval: Some(Duration::from_secs(1)),
+                    on_change: false,
+                    deadband: 0.0,
+                },
+            ],
+            ..Default::default()
+        };
+        let memory = Arc::new(Mutex::new(Memory::default()));
+        let mut logger =
+            Logger::with_sink(io, &config, Box::new(MemorySink(memory.clone()))).unwrap();
+        let values = || {
+            memory
+                .lock()
+                .unwrap()
+                .records
+                .iter()
+                .map(|r| (r.channel.clone(), r.value))
+                .collect::<Vec<_>>()
+        };
+
+        let start = Instant::now();
+        sim.set_analog(0, 100).unwrap();
+        logger.poll(start).unwrap();
+        assert_eq!(
+            values(),
+            [
+                ("level".to_string(), Value::Int(100)),
+                ("CPU".to_string(), Value::Float(25.0)),
+            ]
+        );
+
+        // changes within the deadband and before the interval are not logged
+        sim.set_analog(0, 109).unwrap();
+        logger.poll(start + Duration::from_millis(500)).unwrap();
+        assert_eq!(values().len(), 2);
+
+        sim.set_analog(0, 90).unwrap();
+        logger.poll(start + Duration::from_secs(1)).unwrap();
+        assert_eq!(values().len(), 4);
+        assert_eq!(values()[2], ("level".to_string(), Value::Int(90)));
+        assert_eq!(memory.lock().unwrap().synced, 0);
+
+        // powerfail syncs and suspends logging until the power is restored
+        logger.io.output_set(1, false).unwrap();
+        sim.set_analog(0, 200).unwrap();
+        logger.poll(start + Duration::from_secs(2)).unwrap();
+        assert_eq!(values().len(), 4);
+        assert_eq!(memory.lock().unwrap().synced, 4);
+
+        logger.io.output_set(1, true).unwrap();
+        logger.poll(start + Duration::from_secs(3)).unwrap();
+        assert_eq!(values().len(), 6);
+    }
+
+    #[test]
+    fn format_time_test() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        assert_eq!(format_time(time), "2023-11-14T22:13:20.123Z");
+    }
+}
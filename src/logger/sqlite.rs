@@ -0,0 +1,406 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// SQLite sink. The library is loaded at runtime (libsqlite3.so.0), so it is only required on
+// devices actually logging to a database.
+//
+// Records are stored in the table "samples" (time in milliseconds since the UNIX epoch, channel,
+// value). They are inserted in a single transaction per sync. The database uses a write-ahead
+// log with full synchronization, so a power loss only loses uncommitted records.
+
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_void};
+use std::path::Path;
+use std::ptr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::{Record, Sink, Value};
+use crate::error::{Error, Result};
+
+const LIBRARY: &[u8] = b"libsqlite3.so.0\0";
+
+const SQLITE_OK: c_int = 0;
+const SQLITE_ROW: c_int = 100;
+const SQLITE_DONE: c_int = 101;
+const SQLITE_OPEN_READWRITE: c_int = 0x02;
+const SQLITE_OPEN_CREATE: c_int = 0x04;
+/// Destructor telling SQLite to copy bound strings
+const SQLITE_TRANSIENT: isize = -1;
+
+/// Records kept while the database cannot be written, the oldest are dropped beyond this
+const MAX_PENDING: usize = 100_000;
+
+const SCHEMA: &str = "PRAGMA journal_mode = WAL;
+    PRAGMA synchronous = FULL;
+    CREATE TABLE IF NOT EXISTS samples (time INTEGER NOT NULL, channel TEXT NOT NULL, value);
+    CREATE INDEX IF NOT EXISTS samples_time ON samples (time);";
+
+type Db = c_void;
+type Stmt = c_void;
+
+/// Functions of the SQLite C API
+struct Api {
+    handle: *mut c_void,
+    open_v2: extern "C" fn(*const c_char, *mut *mut Db, c_int, *const c_char) -> c_int,
+    close: extern "C" fn(*mut Db) -> c_int,
+    exec: extern "C" fn(*mut Db, *const c_char, *const c_void, *mut c_void, *mut c_void) -> c_int,
+    errmsg: extern "C" fn(*mut Db) -> *const c_char,
+    prepare_v2:
+        extern "C" fn(*mut Db, *const c_char, c_int, *mut *mut Stmt, *mut *const c_char) -> c_int,
+    bind_int64: extern "C" fn(*mut Stmt, c_int, i64) -> c_int,
+    bind_double: extern "C" fn(*mut Stmt, c_int, f64) -> c_int,
+    bind_text: extern "C" fn(*mut Stmt, c_int, *const c_char, c_int, isize) -> c_int,
+    step: extern "C" fn(*mut Stmt) -> c_int,
+    reset: extern "C" fn(*mut Stmt) -> c_int,
+    column_int64: extern "C" fn(*mut Stmt, c_int) -> i64,
+    finalize: extern "C" fn(*mut Stmt) -> c_int,
+}
+
+impl Drop for Api {
+    fn drop(&mut self) {
+        unsafe {
+            libc::dlclose(self.handle);
+        }
+    }
+}
+
+fn dl_error() -> String {
+    unsafe {
+        let err = libc::dlerror();
+        if err.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(err).to_string_lossy().into_owned()
+        }
+    }
+}
+
+macro_rules! symbol {
+    ($handle:expr, $name:expr) => {{
+        let symbol = unsafe {
+            libc::dlsym(
+                $handle,
+                concat!("sqlite3_", $name, "\0").as_ptr() as *const c_char,
+            )
+        };
+        if symbol.is_null() {
+            error!("Failed to load sqlite3_{}: {}", $name, dl_error());
+            unsafe { libc::dlclose($handle) };
+            return Err(Error::NotImplemented);
+        }
+        // SAFETY: the symbol has the signature of the SQLite C API
+        unsafe { std::mem::transmute_copy(&symbol) }
+    }};
+}
+
+impl Api {
+    fn load() -> Result<Api> {
+        let handle = unsafe { libc::dlopen(LIBRARY.as_ptr() as *const c_char, libc::RTLD_NOW) };
+        if handle.is_null() {
+            error!("Failed to load SQLite: {}", dl_error());
+            return Err(Error::NotImplemented);
+        }
+
+        Ok(Api {
+            handle,
+            open_v2: symbol!(handle, "open_v2"),
+            close: symbol!(handle, "close"),
+            exec: symbol!(handle, "exec"),
+            errmsg: symbol!(handle, "errmsg"),
+            prepare_v2: symbol!(handle, "prepare_v2"),
+            bind_int64: symbol!(handle, "bind_int64"),
+            bind_double: symbol!(handle, "bind_double"),
+            bind_text: symbol!(handle, "bind_text"),
+            step: symbol!(handle, "step"),
+            reset: symbol!(handle, "reset"),
+            column_int64: symbol!(handle, "column_int64"),
+            finalize: symbol!(handle, "finalize"),
+        })
+    }
+}
+
+/// Prepared statement, finalized on drop
+struct Statement<'a> {
+    api: &'a Api,
+    stmt: *mut Stmt,
+}
+
+impl Drop for Statement<'_> {
+    fn drop(&mut self) {
+        (self.api.finalize)(self.stmt);
+    }
+}
+
+pub struct SqliteSink {
+    api: Api,
+    db: *mut Db,
+    pending: Vec<Record>,
+    max_pending: usize,
+    /// Records dropped since the last successful sync
+    dropped: usize,
+    max_size: u64,
+    max_age: Option<Duration>,
+}
+
+// The connection is only used by the owner of the sink
+unsafe impl Send for SqliteSink {}
+
+fn millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+impl SqliteSink {
+    /// Open or create the database, the oldest records are removed if the data exceeds
+    /// `max_size` bytes (0 to disable) or is older than `max_age`
+    pub fn open(path: &Path, max_size: u64, max_age: Option<Duration>) -> Result<SqliteSink> {
+        let api = Api::load()?;
+        let c_path =
+            CString::new(path.to_string_lossy().as_bytes()).map_err(|_| Error::InvalidParameter)?;
+
+        let mut db = ptr::null_mut();
+        let result = (api.open_v2)(
+            c_path.as_ptr(),
+            &mut db,
+            SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+            ptr::null(),
+        );
+        let sink = SqliteSink {
+            api,
+            db,
+            pending: vec![],
+            max_pending: MAX_PENDING,
+            dropped: 0,
+            max_size,
+            max_age,
+        };
+        if result != SQLITE_OK {
+            error!(
+                "Failed to open {}: {}",
+                path.display(),
+                sink.error_message()
+            );
+            return Err(Error::generic_access_error());
+        }
+
+        sink.execute(SCHEMA)?;
+        Ok(sink)
+    }
+
+    fn error_message(&self) -> String {
+        let message = (self.api.errmsg)(self.db);
+        if message.is_null() {
+            String::new()
+        } else {
+            unsafe { CStr::from_ptr(message) }
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+
+    fn check(&self, result: c_int, expected: c_int) -> Result<()> {
+        if result == expected {
+            Ok(())
+        } else {
+            error!("SQLite error {}: {}", result, self.error_message());
+            Err(Error::generic_access_error())
+        }
+    }
+
+    fn execute(&self, sql: &str) -> Result<()> {
+        let sql = CString::new(sql).map_err(|_| Error::InvalidParameter)?;
+        let result = (self.api.exec)(
+            self.db,
+            sql.as_ptr(),
+            ptr::null(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        self.check(result, SQLITE_OK)
+    }
+
+    fn prepare(&self, sql: &str) -> Result<Statement<'_>> {
+        let sql = CString::new(sql).map_err(|_| Error::InvalidParameter)?;
+        let mut stmt = ptr::null_mut();
+        let result = (self.api.prepare_v2)(self.db, sql.as_ptr(), -1, &mut stmt, ptr::null_mut());
+        self.check(result, SQLITE_OK)?;
+        Ok(Statement {
+            api: &self.api,
+            stmt,
+        })
+    }
+
+    /// Run a query returning a single integer
+    fn query_int(&self, sql: &str) -> Result<i64> {
+        let statement = self.prepare(sql)?;
+        self.check((self.api.step)(statement.stmt), SQLITE_ROW)?;
+        Ok((self.api.column_int64)(statement.stmt, 0))
+    }
+
+    fn insert(&self, records: &[Record]) -> Result<()> {
+        let statement =
+            self.prepare("INSERT INTO samples (time, channel, value) VALUES (?, ?, ?)")?;
+        let stmt = statement.stmt;
+        for record in records {
+            let channel =
+                CString::new(record.channel.as_str()).map_err(|_| Error::InvalidParameter)?;
+            self.check(
+                (self.api.bind_int64)(stmt, 1, millis(record.time)),
+                SQLITE_OK,
+            )?;
+            self.check(
+                (self.api.bind_text)(stmt, 2, channel.as_ptr(), -1, SQLITE_TRANSIENT),
+                SQLITE_OK,
+            )?;
+            let result = match record.value {
+                Value::Bool(value) => (self.api.bind_int64)(stmt, 3, value as i64),
+                Value::Int(value) => (self.api.bind_int64)(stmt, 3, value),
+                Value::Float(value) => (self.api.bind_double)(stmt, 3, value),
+            };
+            self.check(result, SQLITE_OK)?;
+            self.check((self.api.step)(stmt), SQLITE_DONE)?;
+            (self.api.reset)(stmt);
+        }
+        Ok(())
+    }
+
+    /// Size of the used pages of the database
+    fn size(&self) -> Result<u64> {
+        let pages =
+            self.query_int("PRAGMA page_count")? - self.query_int("PRAGMA freelist_count")?;
+        Ok((pages * self.query_int("PRAGMA page_size")?) as u64)
+    }
+
+    fn apply_retention(&self) -> Result<()> {
+        if let Some(age) = self.max_age {
+            let limit = millis(SystemTime::now()) - age.as_millis() as i64;
+            self.execute(&format!("DELETE FROM samples WHERE time < {}", limit))?;
+        }
+        if self.max_size > 0 && self.size()? > self.max_size {
+            // freed pages are reused, so the file does not grow any further
+            self.execute(
+                "DELETE FROM samples WHERE rowid IN \
+                 (SELECT rowid FROM samples ORDER BY time LIMIT \
+                 (SELECT count(*) / 10 + 1 FROM samples))",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Number of stored records
+    pub fn count(&self) -> Result<i64> {
+        self.query_int("SELECT count(*) FROM samples")
+    }
+}
+
+impl Sink for SqliteSink {
+    fn write(&mut self, records: &[Record]) -> Result<()> {
+        self.pending.extend_from_slice(records);
+        if self.pending.len() > self.max_pending {
+            if self.dropped == 0 {
+                warn!("Database is not written, dropping the oldest records");
+            }
+            let excess = self.pending.len() - self.max_pending;
+            self.pending.drain(..excess);
+            self.dropped += excess;
+        }
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        self.execute("BEGIN")?;
+        let result = self
+            .insert(&self.pending)
+            .and_then(|_| self.apply_retention());
+        match result {
+            Ok(()) => {
+                self.execute("COMMIT")?;
+                self.pending.clear();
+                if self.dropped > 0 {
+                    warn!("{} records were dropped", self.dropped);
+                    self.dropped = 0;
+                }
+                Ok(())
+            }
+            Err(e) => {
+                self.execute("ROLLBACK").ok();
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Drop for SqliteSink {
+    fn drop(&mut self) {
+        if !self.pending.is_empty() {
+            self.sync().ok();
+        }
+        (self.api.close)(self.db);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqlite_test() {
+        let path =
+            std::env::temp_dir().join(format!("sysworxx-io-sqlite-{}.db", std::process::id()));
+        let mut sink = match SqliteSink::open(&path, 0, Some(Duration::from_secs(3600))) {
+            Ok(sink) => sink,
+            // SQLite is not installed
+            Err(Error::NotImplemented) => return,
+            Err(e) => panic!("{}", e),
+        };
+
+        let now = SystemTime::now();
+        let old = now - Duration::from_secs(7200);
+        sink.write(&[
+            Record {
+                time: now,
+                channel: "AI0".to_string(),
+                value: Value::Int(1),
+            },
+            Record {
+                time: old,
+                channel: "DI0".to_string(),
+                value: Value::Bool(true),
+            },
+            Record {
+                time: now,
+                channel: "TMP0".to_string(),
+                value: Value::Float(21.5),
+            },
+        ])
+        .unwrap();
+        assert_eq!(sink.count().unwrap(), 0);
+
+        // the old record is removed by the retention
+        sink.sync().unwrap();
+        assert_eq!(sink.count().unwrap(), 2);
+
+        // the oldest pending records are dropped
+        sink.max_pending = 2;
+        let records: Vec<Record> = (0..5)
+            .map(|i| Record {
+                time: now,
+                channel: "AI0".to_string(),
+                value: Value::Int(i),
+            })
+            .collect();
+        sink.write(&records).unwrap();
+        assert_eq!(sink.pending.len(), 2);
+        assert_eq!(sink.pending[0].value, Value::Int(3));
+        assert_eq!(sink.dropped, 3);
+        sink.sync().unwrap();
+        assert_eq!(sink.count().unwrap(), 4);
+        assert_eq!(sink.dropped, 0);
+
+        drop(sink);
+        for suffix in ["", "-wal", "-shm"].iter() {
+            std::fs::remove_file(format!("{}{}", path.display(), suffix)).ok();
+        }
+    }
+}
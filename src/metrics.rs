@@ -0,0 +1,454 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Prometheus exporter serving the inputs of the active device definition and the health
+// statistics of the library (see `health`) in the text exposition format at "/metrics".
+//
+// All channel metrics are gauges labeled with the device name (`hw_rev`), the channel label
+// (`IoChannel::label()`, the index if unlabeled) and the channel index:
+//
+//   sysworxx_io_digital_input{device="ctr700",channel="DI0",index="0"} 1
+//   sysworxx_io_analog_input{device="ctr700",channel="AI0",index="0"} 1234
+//   sysworxx_io_temperature_celsius{device="ctr700",channel="CPU",index="0"} 47.5
+//   sysworxx_io_counter{device="ctr700",channel="CNT0",index="0"} 42
+//
+// Failed reads are left out and counted in `sysworxx_io_read_errors_total`. The sampler
+// statistics of this process and of the iodaemon (if running) are exported per sampling thread.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::BufReader;
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ini::Ini;
+
+use crate::error::{Error, Result};
+use crate::health::{self, SamplerStats};
+use crate::http::{Request, Response};
+use crate::shm::ShmClient;
+use crate::Io;
+
+/// Connections without a request for this duration are closed
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub address: String,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            address: "0.0.0.0:9183".to_string(),
+        }
+    }
+}
+
+impl Config {
+    /// Load the configuration from an INI file with the section "server"
+    pub fn load(path: &str) -> Result<Config> {
+        let ini = Ini::load_from_file(path).map_err(|e| {
+            error!("Failed to load {}: {}", path, e);
+            Error::InvalidParameter
+        })?;
+        Config::from_ini(&ini)
+    }
+
+    pub fn from_ini(ini: &Ini) -> Result<Config> {
+        let mut config = Config::default();
+
+        if let Some(server) = ini.section(Some("server")) {
+            if let Some(address) = server.get("address") {
+                config.address = address.trim().to_string();
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Kind {
+    Input,
+    AnalogInput,
+    Temperature,
+    Counter,
+}
+
+const KINDS: [Kind; 4] = [
+    Kind::Input,
+    Kind::AnalogInput,
+    Kind::Temperature,
+    Kind::Counter,
+];
+
+impl Kind {
+    fn metric(self) -> &'static str {
+        match self {
+            Kind::Input => "sysworxx_io_digital_input",
+            Kind::AnalogInput => "sysworxx_io_analog_input",
+            Kind::Temperature => "sysworxx_io_temperature_celsius",
+            Kind::Counter => "sysworxx_io_counter",
+        }
+    }
+
+    fn help(self) -> &'static str {
+        match self {
+            Kind::Input => "State of the digital input (0 or 1)",
+            Kind::AnalogInput => "Raw value of the analog input",
+            Kind::Temperature => "Temperature of the sensor in degree Celsius",
+            Kind::Counter => "Value of the counter input",
+        }
+    }
+
+    /// Name of the kind in the "kind" label of the error metric
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Input => "digital_input",
+            Kind::AnalogInput => "analog_input",
+            Kind::Temperature => "temperature",
+            Kind::Counter => "counter",
+        }
+    }
+}
+
+/// Escape a label value (backslash, double quote and line feed)
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Builder for a metrics page in the text exposition format
+#[derive(Debug, Default)]
+struct Page {
+    text: String,
+}
+
+impl Page {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        writeln!(self.text, "# HELP {} {}", name, help).unwrap();
+        writeln!(self.text, "# TYPE {} {}", name, kind).unwrap();
+    }
+
+    fn sample<V: std::fmt::Display>(&mut self, name: &str, labels: &[(&str, &str)], value: V) {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+            .collect();
+        writeln!(self.text, "{}{{{}}} {}", name, labels.join(","), value).unwrap();
+    }
+}
+
+/// Name, type, help text and value of a metric family of the sampler statistics
+type SamplerFamily = (
+    &'static str,
+    &'static str,
+    &'static str,
+    fn(&SamplerStats) -> Option<f64>,
+);
+
+struct Channel {
+    label: String,
+    index: String,
+}
+
+pub struct Exporter {
+    device: String,
+    channels: BTreeMap<Kind, Vec<Channel>>,
+    io: Mutex<Io>,
+    /// Failed reads per channel since the start of the exporter
+    read_errors: Mutex<BTreeMap<(Kind, usize), u64>>,
+    /// Access to the statistics of the iodaemon
+    shm: Option<Mutex<ShmClient>>,
+}
+
+impl Exporter {
+    /// Create an exporter for an initialized `Io`
+    pub fn new(io: Io, device: &str) -> Exporter {
+        let mut channels = BTreeMap::new();
+        {
+            let info = io.get_channel_info();
+            let mut insert = |kind, labels: Vec<Option<&str>>| {
+                let list = labels
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, label)| Channel {
+                        label: label.map_or_else(|| index.to_string(), str::to_string),
+                        index: index.to_string(),
+                    })
+                    .collect();
+                channels.insert(kind, list);
+            };
+            insert(Kind::Input, info.inputs.iter().map(|c| c.label()).collect());
+            insert(
+                Kind::AnalogInput,
+                info.analog_inputs.iter().map(|c| c.label()).collect(),
+            );
+            insert(
+                Kind::Temperature,
+                info.temp_sensors.iter().map(|c| c.label()).collect(),
+            );
+            insert(
+                Kind::Counter,
+                info.counter_input.iter().map(|c| c.label()).collect(),
+            );
+        }
+
+        let shm = match ShmClient::new() {
+            Ok(client) => Some(Mutex::new(client)),
+            Err(e) => {
+                debug!("No iodaemon statistics: {}", e);
+                None
+            }
+        };
+
+        Exporter {
+            device: device.to_string(),
+            channels,
+            io: Mutex::new(io),
+            read_errors: Mutex::new(BTreeMap::new()),
+            shm,
+        }
+    }
+
+    fn read(&self, kind: Kind, index: usize) -> Result<String> {
+        let mut io = self.io.lock().unwrap();
+        Ok(match kind {
+            Kind::Input => (io.input_get(index)? as u8).to_string(),
+            Kind::AnalogInput => io.analog_input_get(index)?.to_string(),
+            Kind::Temperature => io.tmp_input_get(index)?.to_string(),
+            Kind::Counter => io.cnt_get(index)?.to_string(),
+        })
+    }
+
+    /// Statistics of the sampling threads of this process and the iodaemon
+    fn samplers(&self) -> BTreeMap<String, SamplerStats> {
+        let mut samplers = health::samplers();
+        if let Some(shm) = &self.shm {
+            samplers.extend(shm.lock().unwrap().lock().health_get());
+        }
+        samplers
+    }
+
+    /// Read all channels and render the metrics page
+    pub fn render(&self) -> String {
+        let start = Instant::now();
+        let device = self.device.as_str();
+        let mut page = Page::default();
+
+        page.family("sysworxx_io_info", "gauge", "Device and library version");
+        page.sample(
+            "sysworxx_io_info",
+            &[("device", device), ("version", env!("CARGO_PKG_VERSION"))],
+            1,
+        );
+
+        for kind in KINDS.iter().copied() {
+            let channels = &self.channels[&kind];
+            if channels.is_empty() {
+                continue;
+            }
+            page.family(kind.metric(), "gauge", kind.help());
+            for (index, channel) in channels.iter().enumerate() {
+                match self.read(kind, index) {
+                    Ok(value) => page.sample(
+                        kind.metric(),
+                        &[
+                            ("device", device),
+                            ("channel", &channel.label),
+                            ("index", &channel.index),
+                        ],
+                        value,
+                    ),
+                    Err(e) => {
+                        debug!("Failed to read {} {}: {}", kind.name(), index, e);
+                        *self
+                            .read_errors
+                            .lock()
+                            .unwrap()
+                            .entry((kind, index))
+                            .or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        let read_errors = self.read_errors.lock().unwrap().clone();
+        if !read_errors.is_empty() {
+            page.family(
+                "sysworxx_io_read_errors_total",
+                "counter",
+                "Failed channel reads of the exporter",
+            );
+            for ((kind, index), count) in read_errors {
+                let channel = &self.channels[&kind][index];
+                page.sample(
+                    "sysworxx_io_read_errors_total",
+                    &[
+                        ("device", device),
+                        ("kind", kind.name()),
+                        ("channel", &channel.label),
+                        ("index", &channel.index),
+                    ],
+                    count,
+                );
+            }
+        }
+
+        let samplers = self.samplers();
+        if !samplers.is_empty() {
+            let families: [SamplerFamily; 5] = [
+                (
+                    "sysworxx_io_sampler_cycles_total",
+                    "counter",
+                    "Finished cycles of the sampling thread",
+                    |stats| Some(stats.cycles as f64),
+                ),
+                (
+                    "sysworxx_io_sampler_read_errors_total",
+                    "counter",
+                    "Failed reads of the sampling thread",
+                    |stats| Some(stats.read_errors as f64),
+                ),
+                (
+                    "sysworxx_io_sampler_missed_intervals_total",
+                    "counter",
+                    "Intervals skipped by the sampling thread due to overruns",
+                    |stats| Some(stats.missed_intervals as f64),
+                ),
+                (
+                    "sysworxx_io_sampler_cycle_seconds",
+                    "gauge",
+                    "Duration of the last cycle of the sampling thread",
+                    |stats| Some(stats.cycle_time.as_secs_f64()),
+                ),
+                (
+                    "sysworxx_io_sampler_lag_seconds",
+                    "gauge",
+                    "Time since the last finished cycle of the sampling thread",
+                    |stats| stats.age().map(|age| age.as_secs_f64()),
+                ),
+            ];
+            for (name, kind, help, value) in families.iter() {
+                page.family(name, kind, help);
+                for (sampler, stats) in &samplers {
+                    if let Some(value) = value(stats) {
+                        page.sample(name, &[("device", device), ("sampler", sampler)], value);
+                    }
+                }
+            }
+        }
+
+        page.family(
+            "sysworxx_io_scrape_duration_seconds",
+            "gauge",
+            "Duration of reading all channels",
+        );
+        page.sample(
+            "sysworxx_io_scrape_duration_seconds",
+            &[("device", device)],
+            start.elapsed().as_secs_f64(),
+        );
+
+        page.text
+    }
+
+    pub fn serve(self: Arc<Self>, listener: TcpListener) -> std::io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let exporter = self.clone();
+
+            thread::Builder::new()
+                .name("metrics-client".to_string())
+                .spawn(move || {
+                    if let Err(e) = exporter.handle_client(&stream) {
+                        debug!("HTTP connection closed: {}", e);
+                    }
+                    stream.shutdown(Shutdown::Both).ok();
+                })?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_client(&self, stream: &TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = stream.try_clone()?;
+
+        while let Some(request) = Request::read(&mut reader)? {
+            self.handle(&request).write(&mut writer, request.close)?;
+            if request.close {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle(&self, request: &Request) -> Response {
+        if request.path != ["metrics"] {
+            return Response::error(404, "not found");
+        }
+        match request.method.as_str() {
+            "GET" => Response::new(200)
+                .header("Content-Type", CONTENT_TYPE)
+                .body(self.render().into_bytes()),
+            "HEAD" => Response::new(200).header("Content-Type", CONTENT_TYPE),
+            _ => Response::error(405, "method not allowed").header("Allow", "GET, HEAD"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+    use crate::labeled::Labeled;
+
+    #[test]
+    fn escape_test() {
+        assert_eq!(escape("DI0"), "DI0");
+        assert_eq!(escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+
+    #[test]
+    fn render_test() {
+        let sim = Simulator::new("SIM", 2, 1, 1);
+        let mut io = IoBuilder::new()
+            .output(Box::new(sim.output(0)))
+            .input(Box::new(Labeled::new("DI0", sim.input(0))))
+            .input(Box::new(sim.input(1)))
+            .analog_input(Box::new(Labeled::new("AI0", sim.analog_input(0))))
+            .temp_sensor(Box::new(Labeled::new("CPU", sim.temp_sensor(0))))
+            .build();
+        io.init().unwrap();
+        sim.set_analog(0, 1234).unwrap();
+        sim.set_temperature(0, 47.5).unwrap();
+        io.output_set(0, true).unwrap();
+        let exporter = Exporter::new(io, "sim");
+
+        let text = exporter.render();
+        assert!(text.contains("# TYPE sysworxx_io_digital_input gauge\n"));
+        assert!(text
+            .contains("sysworxx_io_digital_input{device=\"sim\",channel=\"DI0\",index=\"0\"} 1\n"));
+        assert!(text
+            .contains("sysworxx_io_digital_input{device=\"sim\",channel=\"1\",index=\"1\"} 0\n"));
+        assert!(text.contains(
+            "sysworxx_io_analog_input{device=\"sim\",channel=\"AI0\",index=\"0\"} 1234\n"
+        ));
+        assert!(text.contains(
+            "sysworxx_io_temperature_celsius{device=\"sim\",channel=\"CPU\",index=\"0\"} 47.5\n"
+        ));
+        assert!(!text.contains("sysworxx_io_counter"));
+        assert!(!text.contains("sysworxx_io_read_errors_total"));
+    }
+}
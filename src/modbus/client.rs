@@ -0,0 +1,216 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Modbus client (master) for Modbus TCP and Modbus RTU.
+
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use super::rtu::{Parity, RtuTransport};
+use super::*;
+use crate::error::{Error, Result};
+
+impl From<Exception> for Error {
+    fn from(exception: Exception) -> Error {
+        match exception {
+            Exception::IllegalFunction => Error::NotImplemented,
+            Exception::IllegalDataAddress => Error::InvalidChannel,
+            Exception::IllegalDataValue => Error::InvalidParameter,
+            Exception::ServerDeviceFailure => Error::GenericError,
+        }
+    }
+}
+
+/// Transfer of a request PDU to a unit and reception of the response PDU
+pub trait Transport: Send {
+    fn transact(&mut self, unit: u8, pdu: &[u8]) -> Result<Vec<u8>>;
+}
+
+pub struct TcpTransport {
+    address: String,
+    timeout: Duration,
+    stream: Option<TcpStream>,
+    transaction: u16,
+}
+
+impl TcpTransport {
+    /// Create a transport for the given address (e.g. "192.168.1.10:502"). The connection is
+    /// established with the first request and re-established after errors.
+    pub fn new(address: &str, timeout: Duration) -> TcpTransport {
+        TcpTransport {
+            address: address.to_string(),
+            timeout,
+            stream: None,
+            transaction: 0,
+        }
+    }
+
+    fn connect(&self) -> Result<TcpStream> {
+        let address = self
+            .address
+            .to_socket_addrs()?
+            .next()
+            .ok_or(Error::InvalidParameter)?;
+
+        let stream = TcpStream::connect_timeout(&address, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        stream.set_nodelay(true)?;
+        Ok(stream)
+    }
+
+    fn transfer(&mut self, request: &TcpFrame) -> Result<TcpFrame> {
+        if self.stream.is_none() {
+            self.stream = Some(self.connect()?);
+        }
+        let stream = self.stream.as_mut().ok_or(Error::GenericError)?;
+
+        request.write_to(stream)?;
+        loop {
+            let response = TcpFrame::read_from(stream)?;
+            // skip late responses of previous (timed out) requests
+            if response.transaction == request.transaction {
+                return Ok(response);
+            }
+        }
+    }
+}
+
+impl Transport for TcpTransport {
+    fn transact(&mut self, unit: u8, pdu: &[u8]) -> Result<Vec<u8>> {
+        self.transaction = self.transaction.wrapping_add(1);
+        let request = TcpFrame {
+            transaction: self.transaction,
+            unit,
+            pdu: pdu.to_vec(),
+        };
+
+        match self.transfer(&request) {
+            Ok(response) => Ok(response.pdu),
+            Err(e) => {
+                self.stream = None;
+                Err(e)
+            }
+        }
+    }
+}
+
+pub struct Client {
+    transport: Box<dyn Transport>,
+    unit: u8,
+}
+
+impl Client {
+    pub fn new(transport: Box<dyn Transport>, unit: u8) -> Client {
+        Client { transport, unit }
+    }
+
+    pub fn tcp(address: &str, unit: u8, timeout: Duration) -> Client {
+        Client::new(Box::new(TcpTransport::new(address, timeout)), unit)
+    }
+
+    pub fn rtu(
+        path: &str,
+        baud: u32,
+        parity: Parity,
+        unit: u8,
+        timeout: Duration,
+    ) -> Result<Client> {
+        let transport = RtuTransport::open(path, baud, parity, timeout)?;
+        Ok(Client::new(Box::new(transport), unit))
+    }
+
+    fn request(&mut self, pdu: &[u8]) -> Result<Vec<u8>> {
+        let response = self.transport.transact(self.unit, pdu)?;
+
+        match response.first() {
+            Some(f) if *f == pdu[0] => Ok(response),
+            Some(f) if *f == pdu[0] | 0x80 => {
+                let exception = Exception::from_code(response.get(1).copied().unwrap_or(0));
+                debug!("Modbus exception {:?} for function {}", exception, pdu[0]);
+                Err(exception.into())
+            }
+            _ => Err(Error::generic_access_error()),
+        }
+    }
+
+    fn read(&mut self, function: u8, start: u16, count: u16) -> Result<Vec<u8>> {
+        let mut pdu = vec![function];
+        pdu.extend_from_slice(&start.to_be_bytes());
+        pdu.extend_from_slice(&count.to_be_bytes());
+
+        let response = self.request(&pdu)?;
+        let length = *response.get(1).ok_or_else(Error::generic_access_error)? as usize;
+        response
+            .get(2..2 + length)
+            .map(|data| data.to_vec())
+            .ok_or_else(Error::generic_access_error)
+    }
+
+    fn read_bits(&mut self, function: u8, start: u16, count: u16) -> Result<Vec<bool>> {
+        let data = self.read(function, start, count)?;
+        if data.len() != (count as usize).div_ceil(8) {
+            return Err(Error::generic_access_error());
+        }
+        Ok(unpack_bits(&data, count as usize))
+    }
+
+    fn read_registers(&mut self, function: u8, start: u16, count: u16) -> Result<Vec<u16>> {
+        let data = self.read(function, start, count)?;
+        if data.len() != count as usize * 2 {
+            return Err(Error::generic_access_error());
+        }
+        Ok(unpack_registers(&data))
+    }
+
+    pub fn read_coils(&mut self, start: u16, count: u16) -> Result<Vec<bool>> {
+        self.read_bits(FC_READ_COILS, start, count)
+    }
+
+    pub fn read_discrete_inputs(&mut self, start: u16, count: u16) -> Result<Vec<bool>> {
+        self.read_bits(FC_READ_DISCRETE_INPUTS, start, count)
+    }
+
+    pub fn read_holding_registers(&mut self, start: u16, count: u16) -> Result<Vec<u16>> {
+        self.read_registers(FC_READ_HOLDING_REGISTERS, start, count)
+    }
+
+    pub fn read_input_registers(&mut self, start: u16, count: u16) -> Result<Vec<u16>> {
+        self.read_registers(FC_READ_INPUT_REGISTERS, start, count)
+    }
+
+    pub fn write_coil(&mut self, address: u16, value: bool) -> Result<()> {
+        let value: u16 = if value { 0xff00 } else { 0x0000 };
+        let mut pdu = vec![FC_WRITE_SINGLE_COIL];
+        pdu.extend_from_slice(&address.to_be_bytes());
+        pdu.extend_from_slice(&value.to_be_bytes());
+        self.request(&pdu).map(|_| ())
+    }
+
+    pub fn write_register(&mut self, address: u16, value: u16) -> Result<()> {
+        let mut pdu = vec![FC_WRITE_SINGLE_REGISTER];
+        pdu.extend_from_slice(&address.to_be_bytes());
+        pdu.extend_from_slice(&value.to_be_bytes());
+        self.request(&pdu).map(|_| ())
+    }
+
+    pub fn write_coils(&mut self, start: u16, values: &[bool]) -> Result<()> {
+        let bytes = pack_bits(values);
+        let mut pdu = vec![FC_WRITE_MULTIPLE_COILS];
+        pdu.extend_from_slice(&start.to_be_bytes());
+        pdu.extend_from_slice(&(values.len() as u16).to_be_bytes());
+        pdu.push(bytes.len() as u8);
+        pdu.extend(bytes);
+        self.request(&pdu).map(|_| ())
+    }
+
+    pub fn write_registers(&mut self, start: u16, values: &[u16]) -> Result<()> {
+        let bytes = pack_registers(values);
+        let mut pdu = vec![FC_WRITE_MULTIPLE_REGISTERS];
+        pdu.extend_from_slice(&start.to_be_bytes());
+        pdu.extend_from_slice(&(values.len() as u16).to_be_bytes());
+        pdu.push(bytes.len() as u8);
+        pdu.extend(bytes);
+        self.request(&pdu).map(|_| ())
+    }
+}
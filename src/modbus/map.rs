@@ -0,0 +1,270 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Mapping of the channels of an `Io` instance to the Modbus data model. The channels of each
+// type are placed one after another starting at address 0 of the corresponding table:
+//
+// - coils: digital outputs, run LED, error LED
+// - discrete inputs: digital inputs, run switch, config switch
+// - input registers: analog inputs (1 register), temperatures in 1/10000 °C (2 registers),
+//   counters (2 registers)
+// - holding registers: analog outputs (1 register), analog input modes (1 register),
+//   temperature modes (2 registers), counter configuration (6 registers) and PWM configuration
+//   (4 registers)
+//
+// 32 bit values are transferred with the high word first.
+
+use crate::IoChannelInfo;
+
+/// Offsets of the temperature mode registers
+pub const TMP_MODE: u16 = 0;
+pub const TMP_SENSOR_TYPE: u16 = 1;
+
+/// Offsets of the counter configuration registers
+pub const CNT_ENABLE: u16 = 0;
+pub const CNT_MODE: u16 = 1;
+pub const CNT_TRIGGER: u16 = 2;
+pub const CNT_DIRECTION: u16 = 3;
+pub const CNT_PRELOAD: u16 = 4;
+
+/// Offsets of the PWM configuration registers
+pub const PWM_ENABLE: u16 = 0;
+pub const PWM_TIMEBASE: u16 = 1;
+pub const PWM_PERIOD: u16 = 2;
+pub const PWM_DUTY_CYCLE: u16 = 3;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Table {
+    Coils,
+    DiscreteInputs,
+    InputRegisters,
+    HoldingRegisters,
+}
+
+impl Table {
+    pub fn name(self) -> &'static str {
+        match self {
+            Table::Coils => "coils",
+            Table::DiscreteInputs => "discrete_inputs",
+            Table::InputRegisters => "input_registers",
+            Table::HoldingRegisters => "holding_registers",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Kind {
+    Output,
+    RunLed,
+    ErrLed,
+    Input,
+    RunSwitch,
+    ConfigSwitch,
+    AnalogInput,
+    Temperature,
+    Counter,
+    AnalogOutput,
+    AnalogMode,
+    TempMode,
+    CounterConfig,
+    PwmConfig,
+}
+
+impl Kind {
+    pub fn table(self) -> Table {
+        match self {
+            Kind::Output | Kind::RunLed | Kind::ErrLed => Table::Coils,
+            Kind::Input | Kind::RunSwitch | Kind::ConfigSwitch => Table::DiscreteInputs,
+            Kind::AnalogInput | Kind::Temperature | Kind::Counter => Table::InputRegisters,
+            Kind::AnalogOutput
+            | Kind::AnalogMode
+            | Kind::TempMode
+            | Kind::CounterConfig
+            | Kind::PwmConfig => Table::HoldingRegisters,
+        }
+    }
+
+    /// Number of bits/registers used per channel
+    pub fn size(self) -> u16 {
+        match self {
+            Kind::Temperature | Kind::Counter | Kind::TempMode => 2,
+            Kind::PwmConfig => 4,
+            Kind::CounterConfig => 6,
+            _ => 1,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Output => "output",
+            Kind::RunLed => "run_led",
+            Kind::ErrLed => "err_led",
+            Kind::Input => "input",
+            Kind::RunSwitch => "run_switch",
+            Kind::ConfigSwitch => "config_switch",
+            Kind::AnalogInput => "analog_input",
+            Kind::Temperature => "temperature",
+            Kind::Counter => "counter",
+            Kind::AnalogOutput => "analog_output",
+            Kind::AnalogMode => "analog_mode",
+            Kind::TempMode => "temperature_mode",
+            Kind::CounterConfig => "counter_config",
+            Kind::PwmConfig => "pwm_config",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub kind: Kind,
+    pub channel: usize,
+    pub address: u16,
+    pub label: Option<&'static str>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RegisterMap {
+    entries: Vec<Entry>,
+}
+
+impl RegisterMap {
+    pub fn new(info: &IoChannelInfo) -> RegisterMap {
+        let mut map = RegisterMap { entries: vec![] };
+
+        for (i, c) in info.outputs.iter().enumerate() {
+            map.push(Kind::Output, i, c.label());
+        }
+        map.push(Kind::RunLed, 0, info.run_led.label());
+        map.push(Kind::ErrLed, 0, info.err_led.label());
+
+        for (i, c) in info.inputs.iter().enumerate() {
+            map.push(Kind::Input, i, c.label());
+        }
+        map.push(Kind::RunSwitch, 0, info.run_switch.label());
+        map.push(Kind::ConfigSwitch, 0, info.config_switch.label());
+
+        for (i, c) in info.analog_inputs.iter().enumerate() {
+            map.push(Kind::AnalogInput, i, c.label());
+        }
+        for (i, c) in info.temp_sensors.iter().enumerate() {
+            map.push(Kind::Temperature, i, c.label());
+        }
+        for (i, c) in info.counter_input.iter().enumerate() {
+            map.push(Kind::Counter, i, c.label());
+        }
+
+        for (i, c) in info.analog_outputs.iter().enumerate() {
+            map.push(Kind::AnalogOutput, i, c.label());
+        }
+        for (i, c) in info.analog_inputs.iter().enumerate() {
+            map.push(Kind::AnalogMode, i, c.label());
+        }
+        for (i, c) in info.temp_sensors.iter().enumerate() {
+            map.push(Kind::TempMode, i, c.label());
+        }
+        for (i, c) in info.counter_input.iter().enumerate() {
+            map.push(Kind::CounterConfig, i, c.label());
+        }
+        for (i, c) in info.pwm_outputs.iter().enumerate() {
+            map.push(Kind::PwmConfig, i, c.label());
+        }
+
+        map
+    }
+
+    fn push(&mut self, kind: Kind, channel: usize, label: Option<&'static str>) {
+        let address = self.size(kind.table());
+        self.entries.push(Entry {
+            kind,
+            channel,
+            address,
+            label,
+        });
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Number of bits/registers used in the given table
+    pub fn size(&self, table: Table) -> u16 {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.kind.table() == table)
+            .map_or(0, |e| e.address + e.kind.size())
+    }
+
+    /// Find the entry of the given address and the offset of the address within the entry
+    pub fn find(&self, table: Table, address: u16) -> Option<(&Entry, u16)> {
+        self.entries
+            .iter()
+            .filter(|e| e.kind.table() == table)
+            .find(|e| address >= e.address && address < e.address + e.kind.size())
+            .map(|e| (e, address - e.address))
+    }
+
+    pub fn to_json(&self) -> json::JsonValue {
+        let mut obj = json::JsonValue::new_object();
+
+        for table in &[
+            Table::Coils,
+            Table::DiscreteInputs,
+            Table::InputRegisters,
+            Table::HoldingRegisters,
+        ] {
+            obj[table.name()] = json::JsonValue::new_array();
+
+            for entry in self.entries.iter().filter(|e| e.kind.table() == *table) {
+                let mut value = json::object! {
+                    address: entry.address,
+                    count: entry.kind.size(),
+                    type: entry.kind.name(),
+                    channel: entry.channel,
+                };
+                if let Some(label) = entry.label {
+                    value["label"] = label.into();
+                }
+
+                obj[table.name()].push(value).ok();
+            }
+        }
+
+        obj
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+
+    #[test]
+    fn register_map_test() {
+        let sim = Simulator::new("SIM", 2, 1, 2);
+        let io = IoBuilder::new()
+            .output(Box::new(sim.output(0)))
+            .output(Box::new(sim.output(1)))
+            .analog_input(Box::new(sim.analog_input(0)))
+            .temp_sensor(Box::new(sim.temp_sensor(0)))
+            .temp_sensor(Box::new(sim.temp_sensor(1)))
+            .build();
+        let map = RegisterMap::new(&io.get_channel_info());
+
+        assert_eq!(map.size(Table::Coils), 4);
+        assert_eq!(map.size(Table::DiscreteInputs), 2);
+        assert_eq!(map.size(Table::InputRegisters), 5);
+        assert_eq!(map.size(Table::HoldingRegisters), 5);
+
+        let (entry, offset) = map.find(Table::InputRegisters, 4).unwrap();
+        assert_eq!(entry.kind, Kind::Temperature);
+        assert_eq!(entry.channel, 1);
+        assert_eq!(offset, 1);
+
+        let (entry, _) = map.find(Table::Coils, 3).unwrap();
+        assert_eq!(entry.kind, Kind::ErrLed);
+
+        assert!(map.find(Table::Coils, 4).is_none());
+    }
+}
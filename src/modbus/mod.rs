@@ -0,0 +1,171 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Modbus protocol support (Modbus Application Protocol V1.1b3 and Modbus TCP framing).
+
+pub mod client;
+pub mod map;
+pub mod rtu;
+pub mod server;
+
+use std::io::{Read, Write};
+
+pub const FC_READ_COILS: u8 = 0x01;
+pub const FC_READ_DISCRETE_INPUTS: u8 = 0x02;
+pub const FC_READ_HOLDING_REGISTERS: u8 = 0x03;
+pub const FC_READ_INPUT_REGISTERS: u8 = 0x04;
+pub const FC_WRITE_SINGLE_COIL: u8 = 0x05;
+pub const FC_WRITE_SINGLE_REGISTER: u8 = 0x06;
+pub const FC_WRITE_MULTIPLE_COILS: u8 = 0x0f;
+pub const FC_WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+
+/// Maximum number of bits which can be read with a single request
+pub const MAX_READ_BITS: u16 = 2000;
+/// Maximum number of registers which can be read with a single request
+pub const MAX_READ_REGISTERS: u16 = 125;
+/// Maximum number of bits which can be written with a single request
+pub const MAX_WRITE_BITS: u16 = 1968;
+/// Maximum number of registers which can be written with a single request
+pub const MAX_WRITE_REGISTERS: u16 = 123;
+
+/// Maximum size of a PDU (function code and data)
+pub const MAX_PDU_SIZE: usize = 253;
+
+const MBAP_HEADER_SIZE: usize = 7;
+const MODBUS_PROTOCOL_ID: u16 = 0;
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Exception {
+    IllegalFunction = 0x01,
+    IllegalDataAddress = 0x02,
+    IllegalDataValue = 0x03,
+    ServerDeviceFailure = 0x04,
+}
+
+impl Exception {
+    pub fn from_code(code: u8) -> Exception {
+        match code {
+            0x01 => Exception::IllegalFunction,
+            0x02 => Exception::IllegalDataAddress,
+            0x03 => Exception::IllegalDataValue,
+            _ => Exception::ServerDeviceFailure,
+        }
+    }
+}
+
+/// Build an exception response for the given function code
+pub fn exception_response(function: u8, exception: Exception) -> Vec<u8> {
+    vec![function | 0x80, exception as u8]
+}
+
+/// Modbus TCP frame (MBAP header and PDU)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TcpFrame {
+    pub transaction: u16,
+    pub unit: u8,
+    pub pdu: Vec<u8>,
+}
+
+impl TcpFrame {
+    /// Read a single frame from a stream
+    pub fn read_from<R: Read>(reader: &mut R) -> std::io::Result<TcpFrame> {
+        let mut header = [0u8; MBAP_HEADER_SIZE];
+        reader.read_exact(&mut header)?;
+
+        let transaction = u16::from_be_bytes([header[0], header[1]]);
+        let protocol = u16::from_be_bytes([header[2], header[3]]);
+        let length = u16::from_be_bytes([header[4], header[5]]) as usize;
+        let unit = header[6];
+
+        if protocol != MODBUS_PROTOCOL_ID || !(2..=MAX_PDU_SIZE + 1).contains(&length) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "invalid MBAP header",
+            ));
+        }
+
+        let mut pdu = vec![0u8; length - 1];
+        reader.read_exact(&mut pdu)?;
+
+        Ok(TcpFrame {
+            transaction,
+            unit,
+            pdu,
+        })
+    }
+
+    /// Write the frame to a stream
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let mut buffer = Vec::with_capacity(MBAP_HEADER_SIZE + self.pdu.len());
+        buffer.extend_from_slice(&self.transaction.to_be_bytes());
+        buffer.extend_from_slice(&MODBUS_PROTOCOL_ID.to_be_bytes());
+        buffer.extend_from_slice(&(self.pdu.len() as u16 + 1).to_be_bytes());
+        buffer.push(self.unit);
+        buffer.extend_from_slice(&self.pdu);
+        writer.write_all(&buffer)
+    }
+}
+
+/// Pack bits into bytes (LSB first), as used for coils and discrete inputs
+pub fn pack_bits(bits: &[bool]) -> Vec<u8> {
+    let mut bytes = vec![0u8; bits.len().div_ceil(8)];
+    for (i, bit) in bits.iter().enumerate() {
+        if *bit {
+            bytes[i / 8] |= 1 << (i % 8);
+        }
+    }
+    bytes
+}
+
+/// Unpack `count` bits from bytes (LSB first)
+pub fn unpack_bits(bytes: &[u8], count: usize) -> Vec<bool> {
+    (0..count)
+        .map(|i| bytes.get(i / 8).is_some_and(|b| b & (1 << (i % 8)) != 0))
+        .collect()
+}
+
+/// Convert registers to bytes (big endian)
+pub fn pack_registers(registers: &[u16]) -> Vec<u8> {
+    registers.iter().flat_map(|r| r.to_be_bytes()).collect()
+}
+
+/// Convert bytes (big endian) to registers
+pub fn unpack_registers(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_be_bytes([c[0], c[1]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tcp_frame_test() {
+        let frame = TcpFrame {
+            transaction: 0x1234,
+            unit: 0xff,
+            pdu: vec![FC_READ_COILS, 0x00, 0x00, 0x00, 0x08],
+        };
+
+        let mut buffer = vec![];
+        frame.write_to(&mut buffer).unwrap();
+        assert_eq!(
+            buffer,
+            [0x12, 0x34, 0x00, 0x00, 0x00, 0x06, 0xff, 0x01, 0x00, 0x00, 0x00, 0x08]
+        );
+
+        let read = TcpFrame::read_from(&mut buffer.as_slice()).unwrap();
+        assert_eq!(read, frame);
+    }
+
+    #[test]
+    fn pack_bits_test() {
+        let bits = [true, false, true, true, false, false, false, false, true];
+        let bytes = pack_bits(&bits);
+        assert_eq!(bytes, [0x0d, 0x01]);
+        assert_eq!(unpack_bits(&bytes, bits.len()), bits);
+    }
+}
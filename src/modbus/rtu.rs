@@ -0,0 +1,245 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Modbus RTU framing over a serial port (8 data bits, 1 stop bit, configurable parity).
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::AsRawFd;
+use std::time::{Duration, Instant};
+
+use nix::poll::{poll, PollFd, PollFlags};
+use nix::sys::termios::{self, BaudRate, ControlFlags, FlushArg, SetArg};
+
+use super::client::Transport;
+use super::*;
+use crate::error::{Error, Result};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Parity {
+    None,
+    Even,
+    Odd,
+}
+
+/// CRC-16 (Modbus) of the given data
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xffffu16;
+    for byte in data {
+        crc ^= *byte as u16;
+        for _ in 0..8 {
+            if crc & 0x0001 != 0 {
+                crc = (crc >> 1) ^ 0xa001;
+            } else {
+                crc >>= 1;
+            }
+        }
+    }
+    crc
+}
+
+/// Build a RTU frame (unit, PDU, CRC)
+pub fn frame(unit: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut adu = Vec::with_capacity(pdu.len() + 3);
+    adu.push(unit);
+    adu.extend_from_slice(pdu);
+    let crc = crc16(&adu);
+    adu.extend_from_slice(&crc.to_le_bytes());
+    adu
+}
+
+fn baud_rate(baud: u32) -> Result<BaudRate> {
+    let rate = match baud {
+        1200 => BaudRate::B1200,
+        2400 => BaudRate::B2400,
+        4800 => BaudRate::B4800,
+        9600 => BaudRate::B9600,
+        19200 => BaudRate::B19200,
+        38400 => BaudRate::B38400,
+        57600 => BaudRate::B57600,
+        115200 => BaudRate::B115200,
+        230400 => BaudRate::B230400,
+        _ => return Err(Error::InvalidParameter),
+    };
+    Ok(rate)
+}
+
+fn timeout_error() -> Error {
+    Error::AccessFailed(std::io::Error::new(
+        std::io::ErrorKind::TimedOut,
+        "Modbus RTU response timeout",
+    ))
+}
+
+pub struct RtuTransport {
+    port: File,
+    timeout: Duration,
+    frame_delay: Duration,
+}
+
+impl RtuTransport {
+    pub fn open(path: &str, baud: u32, parity: Parity, timeout: Duration) -> Result<RtuTransport> {
+        let port = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY | libc::O_NONBLOCK)
+            .open(path)?;
+        let fd = port.as_raw_fd();
+
+        // O_NONBLOCK only prevents blocking on open (e.g. on the carrier detect of a modem line),
+        // the reads wait for data with poll() and the writes block until the frame is written
+        let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+        if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_NONBLOCK) } < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        let mut tio = termios::tcgetattr(fd).map_err(|_| Error::generic_access_error())?;
+        termios::cfmakeraw(&mut tio);
+        termios::cfsetspeed(&mut tio, baud_rate(baud)?)
+            .map_err(|_| Error::generic_access_error())?;
+        tio.control_flags &= !(ControlFlags::PARENB | ControlFlags::PARODD | ControlFlags::CSTOPB);
+        tio.control_flags |= ControlFlags::CLOCAL | ControlFlags::CREAD | ControlFlags::CS8;
+        match parity {
+            Parity::None => {}
+            Parity::Even => tio.control_flags |= ControlFlags::PARENB,
+            Parity::Odd => tio.control_flags |= ControlFlags::PARENB | ControlFlags::PARODD,
+        }
+        termios::tcsetattr(fd, SetArg::TCSANOW, &tio).map_err(|_| Error::generic_access_error())?;
+
+        // 3.5 characters (11 bits) between frames, fixed to 1.75 ms above 19200 baud
+        let frame_delay = if baud > 19200 {
+            Duration::from_micros(1750)
+        } else {
+            Duration::from_micros(11 * 3500 * 1000 / baud as u64)
+        };
+
+        Ok(RtuTransport {
+            port,
+            timeout,
+            frame_delay,
+        })
+    }
+
+    fn read_exact(&mut self, buffer: &mut [u8], deadline: Instant) -> Result<()> {
+        let mut read = 0;
+        while read < buffer.len() {
+            let remaining = deadline
+                .checked_duration_since(Instant::now())
+                .ok_or_else(timeout_error)?;
+
+            let mut fds = [PollFd::new(self.port.as_raw_fd(), PollFlags::POLLIN)];
+            let ready = poll(&mut fds, remaining.as_millis() as i32)
+                .map_err(|_| Error::generic_access_error())?;
+            if ready == 0 {
+                return Err(timeout_error());
+            }
+
+            match self.port.read(&mut buffer[read..])? {
+                0 => return Err(Error::generic_access_error()),
+                n => read += n,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Transport for RtuTransport {
+    fn transact(&mut self, unit: u8, pdu: &[u8]) -> Result<Vec<u8>> {
+        // drop late responses of previous requests
+        termios::tcflush(self.port.as_raw_fd(), FlushArg::TCIFLUSH)
+            .map_err(|_| Error::generic_access_error())?;
+
+        std::thread::sleep(self.frame_delay);
+        self.port.write_all(&frame(unit, pdu))?;
+
+        let deadline = Instant::now() + self.timeout;
+
+        let mut adu = vec![0u8; 2];
+        self.read_exact(&mut adu, deadline)?;
+
+        let remaining = match adu[1] {
+            f if f & 0x80 != 0 => 1,
+            FC_READ_COILS
+            | FC_READ_DISCRETE_INPUTS
+            | FC_READ_HOLDING_REGISTERS
+            | FC_READ_INPUT_REGISTERS => {
+                let mut count = [0u8];
+                self.read_exact(&mut count, deadline)?;
+                adu.push(count[0]);
+                count[0] as usize
+            }
+            _ => 4,
+        };
+
+        let start = adu.len();
+        adu.resize(start + remaining + 2, 0);
+        self.read_exact(&mut adu[start..], deadline)?;
+
+        let (data, crc) = adu.split_at(adu.len() - 2);
+        if crc16(data) != u16::from_le_bytes([crc[0], crc[1]]) {
+            warn!("Modbus RTU: CRC error");
+            return Err(Error::generic_access_error());
+        }
+        if data[0] != unit {
+            warn!("Modbus RTU: response from unexpected unit {}", data[0]);
+            return Err(Error::generic_access_error());
+        }
+
+        Ok(data[1..].to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::io::FromRawFd;
+    use std::thread;
+
+    #[test]
+    fn crc16_test() {
+        // read holding registers 0..1 of unit 1
+        let adu = frame(1, &[FC_READ_HOLDING_REGISTERS, 0x00, 0x00, 0x00, 0x01]);
+        assert_eq!(adu, [0x01, 0x03, 0x00, 0x00, 0x00, 0x01, 0x84, 0x0a]);
+        assert_eq!(crc16(&adu), 0);
+    }
+    /// Open a pseudo terminal, returns the master and the path of the slave
+    fn pty() -> (File, String) {
+        unsafe {
+            let master = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+            assert!(master >= 0);
+            assert_eq!(libc::grantpt(master), 0);
+            assert_eq!(libc::unlockpt(master), 0);
+            let mut name = [0 as libc::c_char; 64];
+            assert_eq!(libc::ptsname_r(master, name.as_mut_ptr(), name.len()), 0);
+            let path = std::ffi::CStr::from_ptr(name.as_ptr())
+                .to_string_lossy()
+                .into_owned();
+            (File::from_raw_fd(master), path)
+        }
+    }
+
+    #[test]
+    fn transact_test() {
+        let (mut device, path) = pty();
+        let mut rtu =
+            RtuTransport::open(&path, 115200, Parity::Even, Duration::from_millis(500)).unwrap();
+
+        let request = [FC_READ_HOLDING_REGISTERS, 0x00, 0x00, 0x00, 0x01];
+        let server = thread::spawn(move || {
+            let mut adu = [0u8; 8];
+            device.read_exact(&mut adu).unwrap();
+            assert_eq!(adu[..], frame(1, &request)[..]);
+            device
+                .write_all(&frame(1, &[FC_READ_HOLDING_REGISTERS, 2, 0x12, 0x34]))
+                .unwrap();
+            device
+        });
+        let response = rtu.transact(1, &request).unwrap();
+        assert_eq!(response, [FC_READ_HOLDING_REGISTERS, 2, 0x12, 0x34]);
+
+        // the device does not answer
+        let _device = server.join().unwrap();
+        assert!(rtu.transact(2, &request).is_err());
+    }
+}
@@ -0,0 +1,507 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Modbus TCP server which provides access to all channels of an `Io` instance
+// (see `modbus::map` for the register layout).
+//
+// Outputs and holding registers cannot be read back from the hardware, so the server keeps the
+// last written values. Channels which are not implemented read as 0 and reject writes.
+
+use std::convert::TryFrom;
+use std::net::{TcpListener, TcpStream};
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use super::map::{self, Entry, Kind, RegisterMap, Table};
+use super::*;
+use crate::error::{Error, Result};
+use crate::ffi;
+use crate::Io;
+
+impl From<Error> for Exception {
+    fn from(error: Error) -> Exception {
+        match error {
+            Error::InvalidChannel | Error::NotImplemented => Exception::IllegalDataAddress,
+            Error::InvalidParameter => Exception::IllegalDataValue,
+            _ => Exception::ServerDeviceFailure,
+        }
+    }
+}
+
+fn to_enum<T: TryFrom<u8, Error = Error>>(value: u16) -> Result<T> {
+    T::try_from(u8::try_from(value).map_err(|_| Error::InvalidParameter)?)
+}
+
+fn split(value: i32) -> [u16; 2] {
+    [(value >> 16) as u16, value as u16]
+}
+
+fn join(words: &[u16]) -> i32 {
+    ((words[0] as i32) << 16) | words[1] as i32
+}
+
+fn written(range: &Range<u16>, first: u16, count: u16) -> bool {
+    range.start < first + count && first < range.end
+}
+
+/// Read a value and return 0 for channels which are not implemented
+fn or_zero<T: Default>(result: Result<T>) -> Result<T> {
+    match result {
+        Err(Error::NotImplemented) => Ok(T::default()),
+        other => other,
+    }
+}
+
+struct Shadow {
+    coils: Vec<bool>,
+    holding: Vec<u16>,
+}
+
+pub struct Server {
+    io: Arc<Mutex<Io>>,
+    map: RegisterMap,
+    shadow: Mutex<Shadow>,
+}
+
+impl Server {
+    pub fn new(io: Arc<Mutex<Io>>) -> Server {
+        let map = RegisterMap::new(&io.lock().unwrap().get_channel_info());
+        let shadow = Shadow {
+            coils: vec![false; map.size(Table::Coils) as usize],
+            holding: vec![0; map.size(Table::HoldingRegisters) as usize],
+        };
+
+        Server {
+            io,
+            map,
+            shadow: Mutex::new(shadow),
+        }
+    }
+
+    pub fn map(&self) -> &RegisterMap {
+        &self.map
+    }
+
+    /// Accept clients and serve each of them in a separate thread. This blocks until the
+    /// listener fails.
+    pub fn serve(self: Arc<Self>, listener: TcpListener) -> std::io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = self.clone();
+
+            thread::Builder::new()
+                .name("modbus-client".to_string())
+                .spawn(move || server.handle_client(stream))?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_client(&self, mut stream: TcpStream) {
+        let peer = stream.peer_addr().ok();
+        info!("Modbus client connected: {:?}", peer);
+        stream.set_nodelay(true).ok();
+
+        loop {
+            let request = match TcpFrame::read_from(&mut stream) {
+                Ok(request) => request,
+                Err(e) => {
+                    if e.kind() != std::io::ErrorKind::UnexpectedEof {
+                        warn!("Modbus client {:?}: {}", peer, e);
+                    }
+                    break;
+                }
+            };
+
+            let response = TcpFrame {
+                transaction: request.transaction,
+                unit: request.unit,
+                pdu: self.process(&request.pdu),
+            };
+
+            if let Err(e) = response.write_to(&mut stream) {
+                warn!("Modbus client {:?}: {}", peer, e);
+                break;
+            }
+        }
+
+        info!("Modbus client disconnected: {:?}", peer);
+    }
+
+    /// Process a request PDU and return the response PDU
+    pub fn process(&self, pdu: &[u8]) -> Vec<u8> {
+        let function = match pdu.first() {
+            Some(function) => *function,
+            None => return exception_response(0, Exception::IllegalFunction),
+        };
+
+        let data = &pdu[1..];
+        let word = |i: usize| -> std::result::Result<u16, Exception> {
+            data.get(i..i + 2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]))
+                .ok_or(Exception::IllegalDataValue)
+        };
+
+        let result = match function {
+            FC_READ_COILS | FC_READ_DISCRETE_INPUTS => (|| {
+                let table = if function == FC_READ_COILS {
+                    Table::Coils
+                } else {
+                    Table::DiscreteInputs
+                };
+                let bits = self.read_bits(table, word(0)?, word(2)?)?;
+                let bytes = pack_bits(&bits);
+
+                let mut response = vec![function, bytes.len() as u8];
+                response.extend(bytes);
+                Ok(response)
+            })(),
+            FC_READ_HOLDING_REGISTERS | FC_READ_INPUT_REGISTERS => (|| {
+                let table = if function == FC_READ_HOLDING_REGISTERS {
+                    Table::HoldingRegisters
+                } else {
+                    Table::InputRegisters
+                };
+                let registers = self.read_registers(table, word(0)?, word(2)?)?;
+                let bytes = pack_registers(&registers);
+
+                let mut response = vec![function, bytes.len() as u8];
+                response.extend(bytes);
+                Ok(response)
+            })(),
+            FC_WRITE_SINGLE_COIL => (|| {
+                let value = match word(2)? {
+                    0xff00 => true,
+                    0x0000 => false,
+                    _ => return Err(Exception::IllegalDataValue),
+                };
+                self.write_coils(word(0)?, &[value])?;
+                Ok(pdu.to_vec())
+            })(),
+            FC_WRITE_SINGLE_REGISTER => (|| {
+                self.write_registers(word(0)?, &[word(2)?])?;
+                Ok(pdu.to_vec())
+            })(),
+            FC_WRITE_MULTIPLE_COILS => (|| {
+                let (start, count) = (word(0)?, word(2)?);
+                let bytes = data.get(5..).ok_or(Exception::IllegalDataValue)?;
+                if count == 0
+                    || count > MAX_WRITE_BITS
+                    || data[4] as usize != (count as usize).div_ceil(8)
+                    || bytes.len() != data[4] as usize
+                {
+                    return Err(Exception::IllegalDataValue);
+                }
+                self.write_coils(start, &unpack_bits(bytes, count as usize))?;
+                Ok(pdu[..5].to_vec())
+            })(),
+            FC_WRITE_MULTIPLE_REGISTERS => (|| {
+                let (start, count) = (word(0)?, word(2)?);
+                let bytes = data.get(5..).ok_or(Exception::IllegalDataValue)?;
+                if count == 0
+                    || count > MAX_WRITE_REGISTERS
+                    || data[4] as usize != count as usize * 2
+                    || bytes.len() != data[4] as usize
+                {
+                    return Err(Exception::IllegalDataValue);
+                }
+                self.write_registers(start, &unpack_registers(bytes))?;
+                Ok(pdu[..5].to_vec())
+            })(),
+            _ => Err(Exception::IllegalFunction),
+        };
+
+        match result {
+            Ok(response) => response,
+            Err(exception) => {
+                debug!("Modbus function {:#04x} failed: {:?}", function, exception);
+                exception_response(function, exception)
+            }
+        }
+    }
+
+    fn check_range(
+        &self,
+        table: Table,
+        start: u16,
+        count: u16,
+        max: u16,
+    ) -> std::result::Result<(), Exception> {
+        if count == 0 || count > max {
+            return Err(Exception::IllegalDataValue);
+        }
+        if start as u32 + count as u32 > self.map.size(table) as u32 {
+            return Err(Exception::IllegalDataAddress);
+        }
+        Ok(())
+    }
+
+    fn read_bits(
+        &self,
+        table: Table,
+        start: u16,
+        count: u16,
+    ) -> std::result::Result<Vec<bool>, Exception> {
+        self.check_range(table, start, count, MAX_READ_BITS)?;
+
+        let mut io = self.io.lock().map_err(|_| Exception::ServerDeviceFailure)?;
+        let shadow = self
+            .shadow
+            .lock()
+            .map_err(|_| Exception::ServerDeviceFailure)?;
+
+        (start..start + count)
+            .map(|address| {
+                let (entry, _) = self
+                    .map
+                    .find(table, address)
+                    .ok_or(Exception::IllegalDataAddress)?;
+
+                let value = match entry.kind {
+                    Kind::Input => or_zero(io.input_get(entry.channel))?,
+                    Kind::RunSwitch => or_zero(io.get_run_switch())?,
+                    Kind::ConfigSwitch => or_zero(io.get_config_switch())?,
+                    _ => shadow.coils[address as usize],
+                };
+                Ok(value)
+            })
+            .collect()
+    }
+
+    fn read_entry(&self, io: &mut Io, shadow: &Shadow, entry: &Entry) -> Result<Vec<u16>> {
+        let values = match entry.kind {
+            Kind::AnalogInput => {
+                let value = or_zero(io.analog_input_get(entry.channel))?;
+                vec![value.max(0).min(u16::MAX as i64) as u16]
+            }
+            Kind::Temperature => {
+                let value = or_zero(io.tmp_input_get(entry.channel))?;
+                split((value * 10000f64) as i32).to_vec()
+            }
+            Kind::Counter => split(or_zero(io.cnt_get(entry.channel))?).to_vec(),
+            _ => {
+                let first = entry.address as usize;
+                shadow.holding[first..first + entry.kind.size() as usize].to_vec()
+            }
+        };
+        Ok(values)
+    }
+
+    fn read_registers(
+        &self,
+        table: Table,
+        start: u16,
+        count: u16,
+    ) -> std::result::Result<Vec<u16>, Exception> {
+        self.check_range(table, start, count, MAX_READ_REGISTERS)?;
+
+        let mut io = self.io.lock().map_err(|_| Exception::ServerDeviceFailure)?;
+        let shadow = self
+            .shadow
+            .lock()
+            .map_err(|_| Exception::ServerDeviceFailure)?;
+
+        // read multi register values at once, to get consistent values
+        let mut registers = Vec::with_capacity(count as usize);
+        let mut address = start;
+        while address < start + count {
+            let (entry, offset) = self
+                .map
+                .find(table, address)
+                .ok_or(Exception::IllegalDataAddress)?;
+
+            let values = self.read_entry(&mut io, &shadow, entry)?;
+            for value in values.iter().skip(offset as usize) {
+                if address < start + count {
+                    registers.push(*value);
+                    address += 1;
+                }
+            }
+        }
+
+        Ok(registers)
+    }
+
+    fn write_coils(&self, start: u16, values: &[bool]) -> std::result::Result<(), Exception> {
+        self.check_range(Table::Coils, start, values.len() as u16, MAX_WRITE_BITS)?;
+
+        let mut io = self.io.lock().map_err(|_| Exception::ServerDeviceFailure)?;
+        let mut shadow = self
+            .shadow
+            .lock()
+            .map_err(|_| Exception::ServerDeviceFailure)?;
+
+        for (address, value) in (start..).zip(values.iter().copied()) {
+            let (entry, _) = self
+                .map
+                .find(Table::Coils, address)
+                .ok_or(Exception::IllegalDataAddress)?;
+
+            match entry.kind {
+                Kind::Output => io.output_set(entry.channel, value)?,
+                Kind::RunLed => io.set_run_led(value)?,
+                Kind::ErrLed => io.set_err_led(value)?,
+                _ => return Err(Exception::IllegalDataAddress),
+            }
+
+            shadow.coils[address as usize] = value;
+        }
+
+        Ok(())
+    }
+
+    fn write_registers(&self, start: u16, values: &[u16]) -> std::result::Result<(), Exception> {
+        let count = values.len() as u16;
+        self.check_range(Table::HoldingRegisters, start, count, MAX_WRITE_REGISTERS)?;
+
+        let mut io = self.io.lock().map_err(|_| Exception::ServerDeviceFailure)?;
+        let mut shadow = self
+            .shadow
+            .lock()
+            .map_err(|_| Exception::ServerDeviceFailure)?;
+
+        let mut address = start;
+        while address < start + count {
+            let (entry, offset) = self
+                .map
+                .find(Table::HoldingRegisters, address)
+                .ok_or(Exception::IllegalDataAddress)?;
+
+            let first = entry.address as usize;
+            let size = entry.kind.size();
+            let n = (size - offset).min(start + count - address);
+
+            let mut registers = shadow.holding[first..first + size as usize].to_vec();
+            let src = (address - start) as usize;
+            registers[offset as usize..(offset + n) as usize]
+                .copy_from_slice(&values[src..src + n as usize]);
+
+            apply(&mut io, entry, &registers, offset..offset + n)?;
+
+            shadow.holding[first..first + size as usize].copy_from_slice(&registers);
+            address += n;
+        }
+
+        Ok(())
+    }
+}
+
+/// Apply the holding registers of an entry, of which the registers in `range` were written
+fn apply(io: &mut Io, entry: &Entry, registers: &[u16], range: Range<u16>) -> Result<()> {
+    let channel = entry.channel;
+
+    match entry.kind {
+        Kind::AnalogOutput => io.analog_output_set(channel, registers[0] as i64),
+        Kind::AnalogMode => io.analog_mode_set(channel, to_enum(registers[0])?),
+        Kind::TempMode => io.tmp_set_mode(
+            channel,
+            to_enum(registers[map::TMP_MODE as usize])?,
+            to_enum(registers[map::TMP_SENSOR_TYPE as usize])?,
+        ),
+        Kind::CounterConfig => {
+            if written(&range, map::CNT_MODE, 3) {
+                io.cnt_setup(
+                    channel,
+                    to_enum(registers[map::CNT_MODE as usize])?,
+                    to_enum(registers[map::CNT_TRIGGER as usize])?,
+                    to_enum(registers[map::CNT_DIRECTION as usize])?,
+                )?;
+            }
+            if written(&range, map::CNT_PRELOAD, 2) {
+                let preload = map::CNT_PRELOAD as usize;
+                io.cnt_set_preload(channel, join(&registers[preload..preload + 2]))?;
+            }
+            if written(&range, map::CNT_ENABLE, 1) {
+                io.cnt_enable(channel, registers[map::CNT_ENABLE as usize] != 0)?;
+            }
+            Ok(())
+        }
+        Kind::PwmConfig => {
+            if written(&range, map::PWM_TIMEBASE, 1) {
+                let timebase: ffi::IoPwmTimebase = to_enum(registers[map::PWM_TIMEBASE as usize])?;
+                io.pwm_set_timebase(channel, timebase)?;
+            }
+            if written(&range, map::PWM_PERIOD, 2) {
+                io.pwm_setup(
+                    channel,
+                    registers[map::PWM_PERIOD as usize],
+                    registers[map::PWM_DUTY_CYCLE as usize],
+                )?;
+            }
+            if written(&range, map::PWM_ENABLE, 1) {
+                io.pwm_enable(channel, registers[map::PWM_ENABLE as usize] != 0)?;
+            }
+            Ok(())
+        }
+        _ => Err(Error::InvalidChannel),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+
+    #[test]
+    fn server_loopback_test() {
+        let sim = Simulator::new("SIM", 2, 1, 1);
+        let mut io = IoBuilder::new()
+            .output(Box::new(sim.output(0)))
+            .output(Box::new(sim.output(1)))
+            .input(Box::new(sim.input(0)))
+            .input(Box::new(sim.input(1)))
+            .analog_input(Box::new(sim.analog_input(0)))
+            .analog_output(Box::new(sim.analog_output(0)))
+            .temp_sensor(Box::new(sim.temp_sensor(0)))
+            .build();
+        io.init().unwrap();
+        let server = Server::new(Arc::new(Mutex::new(io)));
+
+        // write DO1 and read back DI0..1
+        assert_eq!(
+            server.process(&[FC_WRITE_SINGLE_COIL, 0x00, 0x01, 0xff, 0x00]),
+            [FC_WRITE_SINGLE_COIL, 0x00, 0x01, 0xff, 0x00]
+        );
+        assert_eq!(
+            server.process(&[FC_READ_DISCRETE_INPUTS, 0x00, 0x00, 0x00, 0x02]),
+            [FC_READ_DISCRETE_INPUTS, 0x01, 0x02]
+        );
+        assert_eq!(
+            server.process(&[FC_READ_COILS, 0x00, 0x00, 0x00, 0x02]),
+            [FC_READ_COILS, 0x01, 0x02]
+        );
+
+        // write AO0 and read back AI0 and TMP0 (25 °C)
+        server.process(&[FC_WRITE_SINGLE_REGISTER, 0x00, 0x00, 0x12, 0x34]);
+        assert_eq!(
+            server.process(&[FC_READ_INPUT_REGISTERS, 0x00, 0x00, 0x00, 0x03]),
+            [
+                FC_READ_INPUT_REGISTERS,
+                0x06,
+                0x12,
+                0x34,
+                0x00,
+                0x03,
+                0xd0,
+                0x90
+            ]
+        );
+
+        // invalid enum value for the analog mode
+        assert_eq!(
+            server.process(&[FC_WRITE_SINGLE_REGISTER, 0x00, 0x01, 0x00, 0x05]),
+            [
+                FC_WRITE_SINGLE_REGISTER | 0x80,
+                Exception::IllegalDataValue as u8
+            ]
+        );
+
+        // out of range
+        assert_eq!(
+            server.process(&[FC_READ_COILS, 0x00, 0x03, 0x00, 0x02]),
+            [FC_READ_COILS | 0x80, Exception::IllegalDataAddress as u8]
+        );
+    }
+}
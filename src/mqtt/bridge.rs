@@ -0,0 +1,625 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Bridge between the channels of a device and a MQTT broker.
+//
+// Topics are derived from the channel labels (or the channel index for unlabeled channels):
+//
+//   <prefix>/status                        "online" / "offline" (retained, last will)
+//   <prefix>/input/<name>                  "0" / "1"
+//   <prefix>/analog_input/<name>           raw value
+//   <prefix>/temperature/<name>            °C
+//   <prefix>/counter/<name>                counter value
+//   <prefix>/output/<name>[/set]           "0" / "1"
+//   <prefix>/analog_output/<name>[/set]    raw value
+//   <prefix>/pwm/<name>[/set]              {"period": 1000, "duty_cycle": 500, "timebase": "1ms",
+//                                           "enable": true}
+//   <prefix>/analog_input/<name>/mode[/set] "voltage" / "current"
+//
+// All state topics are retained. Commands are sent to the topics ending with "/set".
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use ini::Ini;
+use libc::c_int;
+
+use super::client::{Client, Options};
+use super::Message;
+use crate::error::{Error, Result};
+use crate::events::{self, InputEvent};
+use crate::{ffi, Io};
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub broker: Options,
+    pub prefix: String,
+    pub poll_interval: Duration,
+    pub reconnect_interval: Duration,
+    /// Minimum change of an analog input (raw value) to publish a new value
+    pub analog_deadband: i64,
+    /// Minimum change of a temperature (°C) to publish a new value
+    pub temperature_deadband: f64,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            broker: Options::default(),
+            prefix: "sysworxx".to_string(),
+            poll_interval: Duration::from_millis(100),
+            reconnect_interval: Duration::from_secs(5),
+            analog_deadband: 0,
+            temperature_deadband: 0.0,
+        }
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: Option<&String>, default: T) -> Result<T> {
+    match value {
+        Some(value) => value.trim().parse().map_err(|_| Error::InvalidParameter),
+        None => Ok(default),
+    }
+}
+
+impl Config {
+    /// Load the configuration from an INI file with the sections "broker" and "bridge"
+    pub fn load(path: &str) -> Result<Config> {
+        let ini = Ini::load_from_file(path).map_err(|e| {
+            error!("Failed to load {}: {}", path, e);
+            Error::InvalidParameter
+        })?;
+        Config::from_ini(&ini)
+    }
+
+    pub fn from_ini(ini: &Ini) -> Result<Config> {
+        let mut config = Config::default();
+
+        if let Some(broker) = ini.section(Some("broker")) {
+            let options = &mut config.broker;
+            options.host = broker.get("host").unwrap_or(&options.host).to_string();
+            options.port = parse(broker.get("port"), options.port)?;
+            options.client_id = broker
+                .get("client_id")
+                .unwrap_or(&options.client_id)
+                .to_string();
+            options.username = broker.get("username").cloned();
+            options.password = broker.get("password").cloned();
+            options.keep_alive = parse(broker.get("keep_alive"), options.keep_alive)?;
+        }
+
+        if let Some(bridge) = ini.section(Some("bridge")) {
+            config.prefix = bridge
+                .get("topic_prefix")
+                .unwrap_or(&config.prefix)
+                .trim_end_matches('/')
+                .to_string();
+            config.poll_interval = Duration::from_millis(parse(
+                bridge.get("poll_interval"),
+                config.poll_interval.as_millis() as u64,
+            )?);
+            config.reconnect_interval = Duration::from_secs(parse(
+                bridge.get("reconnect_interval"),
+                config.reconnect_interval.as_secs(),
+            )?);
+            config.analog_deadband = parse(bridge.get("analog_deadband"), config.analog_deadband)?;
+            config.temperature_deadband = parse(
+                bridge.get("temperature_deadband"),
+                config.temperature_deadband,
+            )?;
+        }
+
+        config.broker.will = Some(Message::new(
+            &format!("{}/status", config.prefix),
+            "offline",
+            true,
+        ));
+
+        Ok(config)
+    }
+}
+
+/// Name of a channel used in topics
+fn channel_name(label: Option<&'static str>, index: usize) -> String {
+    match label {
+        Some(label) => label
+            .chars()
+            .map(|c| match c {
+                '/' | '+' | '#' | ' ' => '_',
+                c => c,
+            })
+            .collect(),
+        None => index.to_string(),
+    }
+}
+
+#[derive(Debug, Default)]
+struct Names {
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    analog_inputs: Vec<String>,
+    analog_outputs: Vec<String>,
+    temp_sensors: Vec<String>,
+    counters: Vec<String>,
+    pwm_outputs: Vec<String>,
+}
+
+/// Values of the inputs, which were published last
+#[derive(Debug, Default)]
+struct Published {
+    inputs: Vec<Option<bool>>,
+    analog_inputs: Vec<Option<i64>>,
+    temperatures: Vec<Option<f64>>,
+    counters: Vec<Option<i32>>,
+}
+
+pub struct Bridge {
+    io: Io,
+    config: Config,
+    names: Names,
+    published: Published,
+    /// State of the outputs set by commands, which is published again after reconnecting
+    retained: BTreeMap<String, String>,
+    pwm: Vec<json::JsonValue>,
+    /// Edges of the inputs with callback support
+    events: crossbeam_channel::Receiver<InputEvent>,
+}
+
+impl Bridge {
+    /// Create a bridge for an initialized `Io`
+    pub fn new(mut io: Io, config: Config) -> Bridge {
+        let mut names = Names::default();
+        {
+            let info = io.get_channel_info();
+            let map = |labels: Vec<Option<&'static str>>| -> Vec<String> {
+                labels
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, label)| channel_name(label, i))
+                    .collect()
+            };
+            names.inputs = map(info.inputs.iter().map(|c| c.label()).collect());
+            names.outputs = map(info.outputs.iter().map(|c| c.label()).collect());
+            names.analog_inputs = map(info.analog_inputs.iter().map(|c| c.label()).collect());
+            names.analog_outputs = map(info.analog_outputs.iter().map(|c| c.label()).collect());
+            names.temp_sensors = map(info.temp_sensors.iter().map(|c| c.label()).collect());
+            names.counters = map(info.counter_input.iter().map(|c| c.label()).collect());
+            names.pwm_outputs = map(info.pwm_outputs.iter().map(|c| c.label()).collect());
+        }
+
+        // edges are published immediately if supported by the channel, otherwise on the next
+        // poll cycle
+        for i in 0..names.inputs.len() {
+            if let Err(e) = events::register(&mut io, i) {
+                debug!("No callback for input {}: {}", i, e);
+            }
+        }
+
+        let pwm = vec![json::object! {}; names.pwm_outputs.len()];
+        let (event_tx, events) = crossbeam_channel::unbounded();
+        events::subscribe(event_tx);
+
+        Bridge {
+            io,
+            config,
+            names,
+            published: Published::default(),
+            retained: BTreeMap::new(),
+            pwm,
+            events,
+        }
+    }
+
+    fn topic(&self, kind: &str, name: &str) -> String {
+        format!("{}/{}/{}", self.config.prefix, kind, name)
+    }
+
+    fn state(&self, kind: &str, name: &str, payload: String) -> Message {
+        Message::new(&self.topic(kind, name), &payload, true)
+    }
+
+    /// Forget the published values, to publish all values again
+    fn reset(&mut self) {
+        self.published = Published {
+            inputs: vec![None; self.names.inputs.len()],
+            analog_inputs: vec![None; self.names.analog_inputs.len()],
+            temperatures: vec![None; self.names.temp_sensors.len()],
+            counters: vec![None; self.names.counters.len()],
+        };
+    }
+
+    fn input_changed(&mut self, channel: usize, value: bool) -> Option<Message> {
+        let published = self.published.inputs.get_mut(channel)?;
+        if *published == Some(value) {
+            return None;
+        }
+        *published = Some(value);
+        Some(self.state(
+            "input",
+            &self.names.inputs[channel],
+            (value as u8).to_string(),
+        ))
+    }
+
+    /// Read all inputs and return the messages for changed values
+    pub fn poll(&mut self) -> Vec<Message> {
+        let mut messages = vec![];
+
+        for i in 0..self.names.inputs.len() {
+            if let Ok(value) = self.io.input_get(i) {
+                messages.extend(self.input_changed(i, value));
+            }
+        }
+
+        for i in 0..self.names.analog_inputs.len() {
+            if let Ok(value) = self.io.analog_input_get(i) {
+                let deadband = self.config.analog_deadband.max(1);
+                match self.published.analog_inputs[i] {
+                    Some(last) if (value - last).abs() < deadband => {}
+                    _ => {
+                        self.published.analog_inputs[i] = Some(value);
+                        let name = &self.names.analog_inputs[i];
+                        messages.push(self.state("analog_input", name, value.to_string()));
+                    }
+                }
+            }
+        }
+
+        for i in 0..self.names.temp_sensors.len() {
+            if let Ok(value) = self.io.tmp_input_get(i) {
+                let deadband = self.config.temperature_deadband;
+                match self.published.temperatures[i] {
+                    Some(last) if last == value || (value - last).abs() < deadband => {}
+                    _ => {
+                        self.published.temperatures[i] = Some(value);
+                        let name = &self.names.temp_sensors[i];
+                        messages.push(self.state("temperature", name, value.to_string()));
+                    }
+                }
+            }
+        }
+
+        for i in 0..self.names.counters.len() {
+            if let Ok(value) = self.io.cnt_get(i) {
+                if self.published.counters[i] != Some(value) {
+                    self.published.counters[i] = Some(value);
+                    let name = &self.names.counters[i];
+                    messages.push(self.state("counter", name, value.to_string()));
+                }
+            }
+        }
+
+        messages
+    }
+
+    /// Execute a command and return the messages for the new state
+    pub fn command(&mut self, message: &Message) -> Result<Vec<Message>> {
+        let path = message
+            .topic
+            .strip_prefix(&self.config.prefix)
+            .and_then(|path| path.strip_prefix('/'))
+            .ok_or(Error::InvalidParameter)?;
+        let payload = std::str::from_utf8(&message.payload)
+            .map_err(|_| Error::InvalidParameter)?
+            .trim();
+
+        let parts: Vec<&str> = path.split('/').collect();
+        let (kind, name) = match parts.as_slice() {
+            [kind, name, "set"] => (*kind, *name),
+            ["analog_input", name, "mode", "set"] => ("analog_mode", *name),
+            _ => return Err(Error::InvalidParameter),
+        };
+
+        let find = |names: &[String]| -> Result<usize> {
+            names
+                .iter()
+                .position(|n| n == name)
+                .ok_or(Error::InvalidChannel)
+        };
+
+        let state = match kind {
+            "output" => {
+                let channel = find(&self.names.outputs)?;
+                let value = match payload.to_lowercase().as_str() {
+                    "1" | "true" | "on" => true,
+                    "0" | "false" | "off" => false,
+                    _ => return Err(Error::InvalidParameter),
+                };
+                self.io.output_set(channel, value)?;
+                self.state("output", name, (value as u8).to_string())
+            }
+            "analog_output" => {
+                let channel = find(&self.names.analog_outputs)?;
+                let value: i64 = payload.parse().map_err(|_| Error::InvalidParameter)?;
+                self.io.analog_output_set(channel, value)?;
+                self.state("analog_output", name, value.to_string())
+            }
+            "analog_mode" => {
+                let channel = find(&self.names.analog_inputs)?;
+                let mode: ffi::IoAnalogMode = payload.parse()?;
+                self.io.analog_mode_set(channel, mode)?;
+                self.state(
+                    "analog_input",
+                    &format!("{}/mode", name),
+                    mode.name().to_string(),
+                )
+            }
+            "pwm" => {
+                let channel = find(&self.names.pwm_outputs)?;
+                let config = json::parse(payload).map_err(|_| Error::InvalidParameter)?;
+                self.pwm_command(channel, &config)?;
+                self.state("pwm", name, self.pwm[channel].dump())
+            }
+            _ => return Err(Error::InvalidParameter),
+        };
+
+        self.retained.insert(
+            state.topic.clone(),
+            String::from_utf8_lossy(&state.payload).into_owned(),
+        );
+        Ok(vec![state])
+    }
+
+    fn pwm_command(&mut self, channel: usize, config: &json::JsonValue) -> Result<()> {
+        if !config.is_object() {
+            return Err(Error::InvalidParameter);
+        }
+
+        if !config["timebase"].is_null() {
+            let timebase: ffi::IoPwmTimebase = config["timebase"]
+                .as_str()
+                .ok_or(Error::InvalidParameter)?
+                .parse()?;
+            self.io.pwm_set_timebase(channel, timebase)?;
+            self.pwm[channel]["timebase"] = timebase.name().into();
+        }
+
+        // period and duty cycle can only be set together
+        if !config["period"].is_null() || !config["duty_cycle"].is_null() {
+            let period = config["period"].as_u16().ok_or(Error::InvalidParameter)?;
+            let duty_cycle = config["duty_cycle"]
+                .as_u16()
+                .ok_or(Error::InvalidParameter)?;
+            self.io.pwm_setup(channel, period, duty_cycle)?;
+            self.pwm[channel]["period"] = period.into();
+            self.pwm[channel]["duty_cycle"] = duty_cycle.into();
+        }
+
+        if !config["enable"].is_null() {
+            let enable = config["enable"].as_bool().ok_or(Error::InvalidParameter)?;
+            self.io.pwm_enable(channel, enable)?;
+            self.pwm[channel]["enable"] = enable.into();
+        }
+
+        Ok(())
+    }
+
+    fn connect(&mut self) -> Result<(Client, crossbeam_channel::Receiver<Message>)> {
+        let (mut client, messages) = Client::connect(&self.config.broker)?;
+
+        let status = format!("{}/status", self.config.prefix);
+        client.publish(&Message::new(&status, "online", true))?;
+        for (topic, payload) in &self.retained {
+            client.publish(&Message::new(topic, payload, true))?;
+        }
+        client.subscribe(&[
+            format!("{}/+/+/set", self.config.prefix),
+            format!("{}/+/+/mode/set", self.config.prefix),
+        ])?;
+
+        self.reset();
+        Ok((client, messages))
+    }
+
+    /// Exchange messages until the connection is lost (returns false) or a signal is received
+    /// (returns true)
+    fn serve(
+        &mut self,
+        mut client: Client,
+        messages: &crossbeam_channel::Receiver<Message>,
+        signals: &crossbeam_channel::Receiver<c_int>,
+    ) -> bool {
+        let events = self.events.clone();
+        let mut next_poll = Instant::now();
+
+        loop {
+            let mut outgoing = vec![];
+            let timeout = next_poll.saturating_duration_since(Instant::now());
+
+            crossbeam_channel::select! {
+                recv(signals) -> _ => {
+                    let status = format!("{}/status", self.config.prefix);
+                    client.publish(&Message::new(&status, "offline", true)).ok();
+                    client.disconnect().ok();
+                    return true;
+                },
+                recv(messages) -> message => match message {
+                    Ok(message) => match self.command(&message) {
+                        Ok(messages) => outgoing = messages,
+                        Err(e) => warn!("Invalid command on {}: {}", message.topic, e),
+                    },
+                    Err(_) => {
+                        warn!("Connection to MQTT broker lost");
+                        return false;
+                    }
+                },
+                recv(events) -> event => {
+                    if let Ok((channel, value)) = event {
+                        outgoing.extend(self.input_changed(channel as usize, value));
+                    }
+                },
+                default(timeout) => {},
+            }
+
+            if Instant::now() >= next_poll {
+                outgoing.extend(self.poll());
+                next_poll = Instant::now() + self.config.poll_interval;
+            }
+
+            let result = outgoing
+                .iter()
+                .try_for_each(|message| client.publish(message))
+                .and_then(|_| client.keep_alive());
+            if let Err(e) = result {
+                warn!("Connection to MQTT broker lost: {}", e);
+                return false;
+            }
+        }
+    }
+
+    /// Run the bridge until one of the given signals is received
+    pub fn run(&mut self, signals: &crossbeam_channel::Receiver<c_int>) {
+        loop {
+            match self.connect() {
+                Ok((client, messages)) => {
+                    info!(
+                        "Connected to MQTT broker {}:{}",
+                        self.config.broker.host, self.config.broker.port
+                    );
+                    if self.serve(client, &messages, signals) {
+                        return;
+                    }
+                }
+                Err(e) => warn!(
+                    "Failed to connect to MQTT broker {}:{}: {}",
+                    self.config.broker.host, self.config.broker.port, e
+                ),
+            }
+
+            crossbeam_channel::select! {
+                recv(signals) -> _ => return,
+                default(self.config.reconnect_interval) => {},
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+    use crate::labeled::Labeled;
+
+    fn bridge(sim: &Simulator, config: Config) -> Bridge {
+        let mut io = IoBuilder::new()
+            .output(Box::new(Labeled::new("DO0", sim.output(0))))
+            .input(Box::new(Labeled::new("DI0", sim.input(0))))
+            .analog_input(Box::new(sim.analog_input(0)))
+            .analog_output(Box::new(sim.analog_output(0)))
+            .temp_sensor(Box::new(sim.temp_sensor(0)))
+            .build();
+        io.init().unwrap();
+        Bridge::new(io, config)
+    }
+
+    fn payloads(messages: &[Message]) -> Vec<(&str, &str)> {
+        messages
+            .iter()
+            .map(|m| (m.topic.as_str(), std::str::from_utf8(&m.payload).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn config_test() {
+        let ini = Ini::load_from_str(
+            "[broker]\nhost = broker.local\nport = 8883\n\
+             [bridge]\ntopic_prefix = plant/io/\nanalog_deadband = 20\n",
+        )
+        .unwrap();
+        let config = Config::from_ini(&ini).unwrap();
+        assert_eq!(config.broker.host, "broker.local");
+        assert_eq!(config.broker.port, 8883);
+        assert_eq!(config.prefix, "plant/io");
+        assert_eq!(config.analog_deadband, 20);
+        assert_eq!(config.broker.will.unwrap().topic, "plant/io/status");
+
+        let ini = Ini::load_from_str("[broker]\nport = x\n").unwrap();
+        assert!(Config::from_ini(&ini).is_err());
+    }
+
+    #[test]
+    fn poll_deadband_test() {
+        let sim = Simulator::new("SIM", 1, 1, 1);
+        let config = Config {
+            analog_deadband: 10,
+            temperature_deadband: 0.5,
+            ..Default::default()
+        };
+        let mut bridge = bridge(&sim, config);
+        bridge.reset();
+
+        sim.set_analog(0, 100).unwrap();
+        sim.set_temperature(0, 20.0).unwrap();
+        assert_eq!(
+            payloads(&bridge.poll()),
+            [
+                ("sysworxx/input/DI0", "0"),
+                ("sysworxx/analog_input/0", "100"),
+                ("sysworxx/temperature/0", "20"),
+            ]
+        );
+
+        sim.set_analog(0, 109).unwrap();
+        sim.set_temperature(0, 20.4).unwrap();
+        assert!(bridge.poll().is_empty());
+
+        sim.set_input(0, true).unwrap();
+        sim.set_analog(0, 90).unwrap();
+        sim.set_temperature(0, 20.5).unwrap();
+        assert_eq!(
+            payloads(&bridge.poll()),
+            [
+                ("sysworxx/input/DI0", "1"),
+                ("sysworxx/analog_input/0", "90"),
+                ("sysworxx/temperature/0", "20.5"),
+            ]
+        );
+    }
+
+    #[test]
+    fn command_test() {
+        let sim = Simulator::new("SIM", 1, 1, 1);
+        let mut bridge = bridge(&sim, Config::default());
+        bridge.reset();
+
+        let state = bridge
+            .command(&Message::new("sysworxx/output/DO0/set", "on", false))
+            .unwrap();
+        assert_eq!(payloads(&state), [("sysworxx/output/DO0", "1")]);
+        assert!(state[0].retain);
+        // looped back by the simulator
+        assert_eq!(payloads(&bridge.poll())[0], ("sysworxx/input/DI0", "1"));
+
+        let state = bridge
+            .command(&Message::new("sysworxx/analog_output/0/set", "1234", false))
+            .unwrap();
+        assert_eq!(payloads(&state), [("sysworxx/analog_output/0", "1234")]);
+
+        let state = bridge
+            .command(&Message::new(
+                "sysworxx/analog_input/0/mode/set",
+                "Current",
+                false,
+            ))
+            .unwrap();
+        assert_eq!(
+            payloads(&state),
+            [("sysworxx/analog_input/0/mode", "current")]
+        );
+
+        assert_eq!(bridge.retained.len(), 3);
+
+        for (topic, payload) in &[
+            ("sysworxx/output/DO1/set", "1"),
+            ("sysworxx/output/DO0/set", "maybe"),
+            ("sysworxx/input/DI0/set", "1"),
+            ("other/output/DO0/set", "1"),
+            ("sysworxx/pwm/0/set", "{}"),
+        ] {
+            assert!(bridge
+                .command(&Message::new(topic, payload, false))
+                .is_err());
+        }
+    }
+}
@@ -0,0 +1,252 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Blocking MQTT client. Received messages are forwarded by a separate thread through a channel,
+// which is disconnected as soon as the connection to the broker is lost.
+
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{Message, Packet};
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Keep alive interval in seconds (0 disables the keep alive mechanism)
+    pub keep_alive: u16,
+    /// Message published by the broker if the connection is lost unexpectedly
+    pub will: Option<Message>,
+    /// Timeout for establishing the connection
+    pub timeout: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            host: "localhost".to_string(),
+            port: 1883,
+            client_id: "sysworxx-io".to_string(),
+            username: None,
+            password: None,
+            keep_alive: 30,
+            will: None,
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+pub struct Client {
+    stream: TcpStream,
+    keep_alive: Duration,
+    last_write: Instant,
+    /// Time of the last packet received from the broker, updated by the receiving thread
+    last_read: Arc<Mutex<Instant>>,
+    last_ping: Instant,
+    packet_id: u16,
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        // terminates the receiving thread
+        self.stream.shutdown(Shutdown::Both).ok();
+    }
+}
+
+impl Client {
+    /// Connect to the broker. Returns the client and the receiver of subscribed messages.
+    pub fn connect(options: &Options) -> Result<(Client, crossbeam_channel::Receiver<Message>)> {
+        let address = (options.host.as_str(), options.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or(Error::InvalidParameter)?;
+
+        let mut stream = TcpStream::connect_timeout(&address, options.timeout)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(options.timeout))?;
+        stream.set_write_timeout(Some(options.timeout))?;
+
+        Packet::Connect {
+            client_id: options.client_id.clone(),
+            keep_alive: options.keep_alive,
+            username: options.username.clone(),
+            password: options.password.clone(),
+            will: options.will.clone(),
+        }
+        .write_to(&mut stream)?;
+
+        match Packet::read_from(&mut stream)? {
+            Packet::ConnAck { return_code: 0, .. } => {}
+            Packet::ConnAck { return_code, .. } => {
+                error!(
+                    "MQTT broker refused connection (return code {})",
+                    return_code
+                );
+                return Err(Error::generic_access_error());
+            }
+            _ => return Err(Error::generic_access_error()),
+        }
+
+        // the broker answers pings within the keep alive interval, otherwise the connection is
+        // considered lost (see `keep_alive()`)
+        let keep_alive = Duration::from_secs(options.keep_alive as u64);
+        stream.set_read_timeout(if keep_alive > Duration::from_secs(0) {
+            Some(keep_alive * 3 / 2)
+        } else {
+            None
+        })?;
+
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut reader = stream.try_clone()?;
+        let last_read = Arc::new(Mutex::new(Instant::now()));
+        let received = last_read.clone();
+        thread::Builder::new()
+            .name("mqtt-client".to_string())
+            .spawn(move || loop {
+                let packet = Packet::read_from(&mut reader);
+                if packet.is_ok() {
+                    *received.lock().unwrap() = Instant::now();
+                }
+                match packet {
+                    Ok(Packet::Publish(message)) => {
+                        if tx.send(message).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(packet) => trace!("MQTT: received {:?}", packet),
+                    Err(e) => {
+                        debug!("MQTT: connection closed: {}", e);
+                        break;
+                    }
+                }
+            })?;
+
+        let client = Client {
+            stream,
+            keep_alive,
+            last_write: Instant::now(),
+            last_read,
+            last_ping: Instant::now(),
+            packet_id: 0,
+        };
+
+        Ok((client, rx))
+    }
+
+    fn send(&mut self, packet: &Packet) -> Result<()> {
+        packet.write_to(&mut self.stream)?;
+        self.last_write = Instant::now();
+        Ok(())
+    }
+
+    pub fn publish(&mut self, message: &Message) -> Result<()> {
+        self.send(&Packet::Publish(message.clone()))
+    }
+
+    pub fn subscribe(&mut self, filters: &[String]) -> Result<()> {
+        self.packet_id = self.packet_id.wrapping_add(1).max(1);
+        let packet = Packet::Subscribe {
+            packet_id: self.packet_id,
+            filters: filters.to_vec(),
+        };
+        self.send(&packet)
+    }
+
+    /// Send a ping to the broker if nothing was sent or received for half of the keep alive
+    /// interval. Publishing does not cause the broker to answer, so the ping keeps the receiving
+    /// thread from timing out while only publishing. A broker, which does not answer the ping,
+    /// is detected by the timeout.
+    pub fn keep_alive(&mut self) -> Result<()> {
+        if self.keep_alive == Duration::from_secs(0) {
+            return Ok(());
+        }
+
+        let interval = self.keep_alive / 2;
+        let last_read = *self.last_read.lock().unwrap();
+        if self.last_write.elapsed() > interval
+            || (last_read.elapsed() > interval && self.last_ping.elapsed() > interval)
+        {
+            self.send(&Packet::PingReq)?;
+            self.last_ping = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Disconnect from the broker without publishing the will message
+    pub fn disconnect(mut self) -> Result<()> {
+        self.send(&Packet::Disconnect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{decode_length, CONNECT, DISCONNECT, PINGREQ};
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    /// Read a packet sent by the client, returns the packet type
+    fn read_packet(stream: &mut TcpStream) -> u8 {
+        let mut header = [0u8];
+        stream.read_exact(&mut header).unwrap();
+        let length = decode_length(stream).unwrap();
+        let mut body = vec![0u8; length];
+        stream.read_exact(&mut body).unwrap();
+        header[0] >> 4
+    }
+
+    #[test]
+    fn keep_alive_test() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let broker = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            assert_eq!(read_packet(&mut stream), CONNECT);
+            Packet::ConnAck {
+                session_present: false,
+                return_code: 0,
+            }
+            .write_to(&mut stream)
+            .unwrap();
+
+            let mut pings = 0;
+            loop {
+                match read_packet(&mut stream) {
+                    PINGREQ => {
+                        pings += 1;
+                        Packet::PingResp.write_to(&mut stream).unwrap();
+                    }
+                    DISCONNECT => break pings,
+                    _ => {}
+                }
+            }
+        });
+
+        let options = Options {
+            port,
+            keep_alive: 1,
+            ..Options::default()
+        };
+        let (mut client, _rx) = Client::connect(&options).unwrap();
+
+        // only publishing, the broker does not send anything without the pings
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_millis(1800) {
+            client.publish(&Message::new("a/b", "1", false)).unwrap();
+            client.keep_alive().unwrap();
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        // the receiving thread did not time out (1.5 s)
+        assert!(client.last_read.lock().unwrap().elapsed() < Duration::from_secs(1));
+        client.disconnect().unwrap();
+        let pings = broker.join().unwrap();
+        assert!((2..=4).contains(&pings), "{} pings", pings);
+    }
+}
@@ -0,0 +1,322 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// MQTT 3.1.1 support (subset required by a client publishing and subscribing with QoS 0).
+
+pub mod bridge;
+pub mod client;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+const CONNECT: u8 = 1;
+const CONNACK: u8 = 2;
+const PUBLISH: u8 = 3;
+const PUBACK: u8 = 4;
+const SUBSCRIBE: u8 = 8;
+const SUBACK: u8 = 9;
+const PINGREQ: u8 = 12;
+const PINGRESP: u8 = 13;
+const DISCONNECT: u8 = 14;
+
+const PROTOCOL_NAME: &str = "MQTT";
+const PROTOCOL_LEVEL: u8 = 4;
+
+/// Maximum value of the "remaining length" field of the fixed header
+const MAX_REMAINING_LENGTH: usize = 268_435_455;
+
+/// Application message
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub topic: String,
+    pub payload: Vec<u8>,
+    pub retain: bool,
+}
+
+impl Message {
+    pub fn new(topic: &str, payload: &str, retain: bool) -> Message {
+        Message {
+            topic: topic.to_string(),
+            payload: payload.as_bytes().to_vec(),
+            retain,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Packet {
+    Connect {
+        client_id: String,
+        keep_alive: u16,
+        username: Option<String>,
+        password: Option<String>,
+        will: Option<Message>,
+    },
+    ConnAck {
+        session_present: bool,
+        return_code: u8,
+    },
+    Publish(Message),
+    PubAck(u16),
+    Subscribe {
+        packet_id: u16,
+        filters: Vec<String>,
+    },
+    SubAck {
+        packet_id: u16,
+        return_codes: Vec<u8>,
+    },
+    PingReq,
+    PingResp,
+    Disconnect,
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+fn put_string(buffer: &mut Vec<u8>, value: &str) {
+    buffer.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    buffer.extend_from_slice(value.as_bytes());
+}
+
+fn take_u16(data: &mut &[u8]) -> Result<u16> {
+    if data.len() < 2 {
+        return Err(invalid_data("MQTT packet too short"));
+    }
+    let value = u16::from_be_bytes([data[0], data[1]]);
+    *data = &data[2..];
+    Ok(value)
+}
+
+fn take_string(data: &mut &[u8]) -> Result<String> {
+    let length = take_u16(data)? as usize;
+    if data.len() < length {
+        return Err(invalid_data("MQTT packet too short"));
+    }
+    let value = String::from_utf8(data[..length].to_vec())
+        .map_err(|_| invalid_data("invalid UTF-8 string in MQTT packet"))?;
+    *data = &data[length..];
+    Ok(value)
+}
+
+/// Encode the "remaining length" of the fixed header
+fn encode_length(buffer: &mut Vec<u8>, mut length: usize) {
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        buffer.push(byte);
+        if length == 0 {
+            break;
+        }
+    }
+}
+
+fn decode_length<R: Read>(reader: &mut R) -> Result<usize> {
+    let mut length = 0;
+    let mut multiplier = 1;
+    loop {
+        let mut byte = [0u8];
+        reader.read_exact(&mut byte)?;
+        length += (byte[0] & 0x7f) as usize * multiplier;
+        if byte[0] & 0x80 == 0 {
+            return Ok(length);
+        }
+        multiplier *= 128;
+        if multiplier > 128 * 128 * 128 {
+            return Err(invalid_data("invalid MQTT remaining length"));
+        }
+    }
+}
+
+impl Packet {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut body = vec![];
+        let header = match self {
+            Packet::Connect {
+                client_id,
+                keep_alive,
+                username,
+                password,
+                will,
+            } => {
+                // always start a clean session
+                let mut flags = 0x02;
+                if let Some(will) = will {
+                    flags |= 0x04;
+                    if will.retain {
+                        flags |= 0x20;
+                    }
+                }
+                if username.is_some() {
+                    flags |= 0x80;
+                }
+                if password.is_some() {
+                    flags |= 0x40;
+                }
+
+                put_string(&mut body, PROTOCOL_NAME);
+                body.push(PROTOCOL_LEVEL);
+                body.push(flags);
+                body.extend_from_slice(&keep_alive.to_be_bytes());
+                put_string(&mut body, client_id);
+                if let Some(will) = will {
+                    put_string(&mut body, &will.topic);
+                    body.extend_from_slice(&(will.payload.len() as u16).to_be_bytes());
+                    body.extend_from_slice(&will.payload);
+                }
+                if let Some(username) = username {
+                    put_string(&mut body, username);
+                }
+                if let Some(password) = password {
+                    put_string(&mut body, password);
+                }
+                CONNECT << 4
+            }
+            Packet::ConnAck {
+                session_present,
+                return_code,
+            } => {
+                body.push(*session_present as u8);
+                body.push(*return_code);
+                CONNACK << 4
+            }
+            Packet::Publish(message) => {
+                put_string(&mut body, &message.topic);
+                body.extend_from_slice(&message.payload);
+                PUBLISH << 4 | message.retain as u8
+            }
+            Packet::PubAck(packet_id) => {
+                body.extend_from_slice(&packet_id.to_be_bytes());
+                PUBACK << 4
+            }
+            Packet::Subscribe { packet_id, filters } => {
+                body.extend_from_slice(&packet_id.to_be_bytes());
+                for filter in filters {
+                    put_string(&mut body, filter);
+                    // maximum QoS
+                    body.push(0);
+                }
+                SUBSCRIBE << 4 | 0x02
+            }
+            Packet::SubAck {
+                packet_id,
+                return_codes,
+            } => {
+                body.extend_from_slice(&packet_id.to_be_bytes());
+                body.extend_from_slice(return_codes);
+                SUBACK << 4
+            }
+            Packet::PingReq => PINGREQ << 4,
+            Packet::PingResp => PINGRESP << 4,
+            Packet::Disconnect => DISCONNECT << 4,
+        };
+
+        let mut packet = vec![header];
+        encode_length(&mut packet, body.len());
+        packet.extend(body);
+        packet
+    }
+
+    /// Write the packet to a stream
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(&self.encode())
+    }
+
+    /// Read a single packet sent by a broker from a stream
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Packet> {
+        let mut header = [0u8];
+        reader.read_exact(&mut header)?;
+        let length = decode_length(reader)?;
+        if length > MAX_REMAINING_LENGTH {
+            return Err(invalid_data("invalid MQTT remaining length"));
+        }
+
+        let mut body = vec![0u8; length];
+        reader.read_exact(&mut body)?;
+        let mut data = body.as_slice();
+
+        let packet = match header[0] >> 4 {
+            CONNACK => {
+                if data.len() != 2 {
+                    return Err(invalid_data("invalid MQTT CONNACK"));
+                }
+                Packet::ConnAck {
+                    session_present: data[0] & 0x01 != 0,
+                    return_code: data[1],
+                }
+            }
+            PUBLISH => {
+                let qos = (header[0] >> 1) & 0x03;
+                let topic = take_string(&mut data)?;
+                if qos > 0 {
+                    // packet identifier, only required for the acknowledgement
+                    take_u16(&mut data)?;
+                }
+                Packet::Publish(Message {
+                    topic,
+                    payload: data.to_vec(),
+                    retain: header[0] & 0x01 != 0,
+                })
+            }
+            PUBACK => Packet::PubAck(take_u16(&mut data)?),
+            SUBACK => Packet::SubAck {
+                packet_id: take_u16(&mut data)?,
+                return_codes: data.to_vec(),
+            },
+            PINGRESP => Packet::PingResp,
+            _ => return Err(invalid_data("unexpected MQTT packet type")),
+        };
+
+        Ok(packet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_length_test() {
+        for length in &[0, 127, 128, 16_383, 16_384, 2_097_151, MAX_REMAINING_LENGTH] {
+            let mut buffer = vec![];
+            encode_length(&mut buffer, *length);
+            assert_eq!(decode_length(&mut buffer.as_slice()).unwrap(), *length);
+        }
+    }
+
+    #[test]
+    fn connect_test() {
+        let packet = Packet::Connect {
+            client_id: "io".to_string(),
+            keep_alive: 30,
+            username: None,
+            password: None,
+            will: Some(Message::new("s", "off", true)),
+        };
+        assert_eq!(
+            packet.encode(),
+            [
+                0x10, 0x16, 0x00, 0x04, b'M', b'Q', b'T', b'T', 0x04, 0x26, 0x00, 0x1e, 0x00, 0x02,
+                b'i', b'o', 0x00, 0x01, b's', 0x00, 0x03, b'o', b'f', b'f'
+            ][..]
+        );
+    }
+
+    #[test]
+    fn publish_test() {
+        let packet = Packet::Publish(Message::new("a/b", "1", true));
+        let buffer = packet.encode();
+        assert_eq!(buffer, [0x31, 0x06, 0x00, 0x03, b'a', b'/', b'b', b'1']);
+        assert_eq!(Packet::read_from(&mut buffer.as_slice()).unwrap(), packet);
+
+        // QoS 1 with packet identifier
+        let buffer = [0x32, 0x08, 0x00, 0x03, b'a', b'/', b'b', 0x00, 0x07, b'1'];
+        assert_eq!(
+            Packet::read_from(&mut &buffer[..]).unwrap(),
+            Packet::Publish(Message::new("a/b", "1", false))
+        );
+    }
+}
@@ -0,0 +1,1340 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Address space of the OPC UA server. It consists of a minimal subset of namespace 0 (folders,
+// types and the server object) and the channels of the device in namespace 1:
+//
+//   Objects/<device>/DigitalInputs/<DI>                 Boolean
+//   Objects/<device>/DigitalOutputs/<DO>                Boolean (writable)
+//   Objects/<device>/AnalogInputs/<AI>[/Mode]           Int64 (AnalogItemType), Int32 (writable)
+//   Objects/<device>/AnalogOutputs/<AO>                 Int64 (AnalogItemType, writable)
+//   Objects/<device>/TemperatureSensors/<TMP>           Double in °C (AnalogItemType)
+//                                        [/Mode, /SensorType]  Byte (writable)
+//   Objects/<device>/Counters/<CNT>/{Value, Enable, SetPreload(), Reset()}
+//   Objects/<device>/PwmOutputs/<PWM>/{Period, DutyCycle, Timebase, Enable}
+//   Objects/<device>/System/{RunLED, ErrorLED, RunSwitch, ConfigSwitch}
+//
+// Node ids of the channels are strings composed of the folder and channel name, e.g.
+// "ns=1;s=DigitalInputs.DI0". Channel names are the channel labels if available.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::Mutex;
+
+use super::encoding::{
+    now, DataValue, Encoder, ExtensionObject, Identifier, LocalizedText, NodeId, QualifiedName,
+    Variant,
+};
+use super::status::{self, StatusCode};
+use super::{attribute, id};
+use crate::{ffi, Io, IoChannelInfo};
+
+pub const NAMESPACE_URI: &str = "urn:systec-electronic:sysworxx-io";
+pub const APPLICATION_URI: &str = "urn:systec-electronic:sysworxx-io:server";
+pub const PRODUCT_URI: &str = "https://www.systec-electronic.com/sysworxx-io";
+
+/// Range of the raw values of analog inputs and outputs
+const ANALOG_RANGE: (f64, f64) = (0.0, 65535.0);
+/// Range of the supported temperature sensors in °C
+const TEMPERATURE_RANGE: (f64, f64) = (-200.0, 850.0);
+/// Unit id of °C (UNECE code "CEL")
+const UNIT_CELSIUS: i32 = 0x0043_454c;
+/// Unit id of the raw values of the analog channels (UNECE code "C62", dimensionless)
+const UNIT_ONE: i32 = 0x0043_3632;
+
+/// Channel value a variable is bound to
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Source {
+    Input(usize),
+    Output(usize),
+    RunLed,
+    ErrLed,
+    RunSwitch,
+    ConfigSwitch,
+    AnalogInput(usize),
+    AnalogMode(usize),
+    AnalogOutput(usize),
+    Temperature(usize),
+    TempMode(usize),
+    TempSensorType(usize),
+    Counter(usize),
+    CounterEnable(usize),
+    PwmPeriod(usize),
+    PwmDutyCycle(usize),
+    PwmTimebase(usize),
+    PwmEnable(usize),
+    ServerStatus,
+    CurrentTime,
+}
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Static(Variant),
+    Io(Source),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MethodKind {
+    CounterSetPreload(usize),
+    CounterReset(usize),
+}
+
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NodeClass {
+    Object = 1,
+    Variable = 2,
+    Method = 4,
+    ObjectType = 8,
+    VariableType = 16,
+    ReferenceType = 32,
+    DataType = 64,
+}
+
+#[derive(Debug, Clone)]
+pub enum Kind {
+    Object,
+    Variable {
+        data_type: u32,
+        value_rank: i32,
+        writable: bool,
+        value: Value,
+    },
+    Method(MethodKind),
+    ObjectType,
+    VariableType,
+    ReferenceType {
+        symmetric: bool,
+        inverse_name: Option<&'static str>,
+    },
+    DataType,
+}
+
+#[derive(Debug, Clone)]
+pub struct Reference {
+    /// Reference type (namespace 0)
+    pub type_id: u32,
+    /// Index of the target node
+    pub target: usize,
+    pub forward: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub id: NodeId,
+    pub browse_name: QualifiedName,
+    pub display_name: LocalizedText,
+    pub description: Option<LocalizedText>,
+    pub kind: Kind,
+    pub references: Vec<Reference>,
+}
+
+impl Node {
+    pub fn class(&self) -> NodeClass {
+        match self.kind {
+            Kind::Object => NodeClass::Object,
+            Kind::Variable { .. } => NodeClass::Variable,
+            Kind::Method(_) => NodeClass::Method,
+            Kind::ObjectType => NodeClass::ObjectType,
+            Kind::VariableType => NodeClass::VariableType,
+            Kind::ReferenceType { .. } => NodeClass::ReferenceType,
+            Kind::DataType => NodeClass::DataType,
+        }
+    }
+
+    pub fn source(&self) -> Option<Source> {
+        match self.kind {
+            Kind::Variable {
+                value: Value::Io(source),
+                ..
+            } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+fn range(range: (f64, f64)) -> Variant {
+    Variant::ExtensionObject(ExtensionObject::encode(id::RANGE_ENCODING, |e| {
+        e.f64(range.0);
+        e.f64(range.1);
+    }))
+}
+
+fn engineering_units(unit_id: i32, name: &str, description: &str) -> Variant {
+    Variant::ExtensionObject(ExtensionObject::encode(id::EU_INFORMATION_ENCODING, |e| {
+        e.str("http://www.opcfoundation.org/UA/units/un/cefact");
+        e.i32(unit_id);
+        e.localized_text(&LocalizedText::new(name));
+        e.localized_text(&LocalizedText::new(description));
+    }))
+}
+
+fn argument(name: &str, data_type: u32, description: &str) -> Variant {
+    Variant::ExtensionObject(ExtensionObject::encode(id::ARGUMENT_ENCODING, |e| {
+        e.str(name);
+        e.node_id(&NodeId::numeric(0, data_type));
+        // scalar
+        e.i32(-1);
+        e.array::<u32, _>(&[], |e, v| e.u32(*v));
+        e.localized_text(&LocalizedText::new(description));
+    }))
+}
+
+/// Name of a channel used for browse names and node ids
+fn channel_name(label: Option<&'static str>, prefix: &str, index: usize) -> String {
+    match label {
+        Some(label) => label.to_string(),
+        None => format!("{}{}", prefix, index),
+    }
+}
+
+/// Node id, name, parent type, symmetric and inverse name of a reference type
+type ReferenceTypeDefinition = (u32, &'static str, Option<u32>, bool, Option<&'static str>);
+
+pub struct AddressSpace {
+    nodes: Vec<Node>,
+    index: HashMap<NodeId, usize>,
+    /// Last written values of channels which can not be read back
+    shadow: Mutex<HashMap<Source, Variant>>,
+    inputs: Vec<usize>,
+    start_time: i64,
+}
+
+impl AddressSpace {
+    pub fn new(device: &str, info: &IoChannelInfo) -> AddressSpace {
+        let mut space = AddressSpace {
+            nodes: vec![],
+            index: HashMap::new(),
+            shadow: Mutex::new(HashMap::new()),
+            inputs: vec![],
+            start_time: now(),
+        };
+
+        space.add_namespace0();
+        space.add_device(device, info);
+        space
+    }
+
+    fn add(&mut self, id: NodeId, name: &str, kind: Kind) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            browse_name: QualifiedName::new(id.namespace, name),
+            display_name: LocalizedText::new(name),
+            description: None,
+            id: id.clone(),
+            kind,
+            references: vec![],
+        });
+        self.index.insert(id, index);
+        index
+    }
+
+    fn add_ns0(&mut self, id: u32, name: &str, kind: Kind) -> usize {
+        self.add(NodeId::numeric(0, id), name, kind)
+    }
+
+    fn reference(&mut self, source: usize, type_id: u32, target: usize) {
+        self.nodes[source].references.push(Reference {
+            type_id,
+            target,
+            forward: true,
+        });
+        self.nodes[target].references.push(Reference {
+            type_id,
+            target: source,
+            forward: false,
+        });
+    }
+
+    fn reference_ns0(&mut self, source: usize, type_id: u32, target: u32) {
+        let target = self.index[&NodeId::numeric(0, target)];
+        self.reference(source, type_id, target);
+    }
+
+    fn describe(&mut self, node: usize, description: &str) {
+        self.nodes[node].description = Some(LocalizedText::new(description));
+    }
+
+    /// Node id of a child node, e.g. "AnalogInputs.AI0.Mode"
+    fn child_id(&self, parent: usize, name: &str) -> NodeId {
+        let parent = &self.nodes[parent];
+        match &parent.id.identifier {
+            Identifier::String(id) => NodeId::string(1, &format!("{}.{}", id, name)),
+            _ => NodeId::string(1, &format!("{}.{}", parent.browse_name.name, name)),
+        }
+    }
+
+    fn folder(&mut self, parent: usize, id: NodeId, name: &str) -> usize {
+        let node = self.add(id, name, Kind::Object);
+        self.reference(parent, id::ORGANIZES, node);
+        self.reference_ns0(node, id::HAS_TYPE_DEFINITION, id::FOLDER_TYPE);
+        node
+    }
+
+    fn object(&mut self, parent: usize, id: NodeId, name: &str, reference: u32) -> usize {
+        let node = self.add(id, name, Kind::Object);
+        self.reference(parent, reference, node);
+        self.reference_ns0(node, id::HAS_TYPE_DEFINITION, id::BASE_OBJECT_TYPE);
+        node
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn variable(
+        &mut self,
+        parent: usize,
+        reference: u32,
+        id: NodeId,
+        name: &str,
+        data_type: u32,
+        writable: bool,
+        value: Value,
+        type_definition: u32,
+    ) -> usize {
+        let value_rank = match value {
+            Value::Static(Variant::Array(..)) => 1,
+            _ => -1,
+        };
+        let kind = Kind::Variable {
+            data_type,
+            value_rank,
+            writable,
+            value,
+        };
+        let node = self.add(id, name, kind);
+        self.reference(parent, reference, node);
+        self.reference_ns0(node, id::HAS_TYPE_DEFINITION, type_definition);
+        node
+    }
+
+    fn property(&mut self, parent: usize, name: &str, data_type: u32, value: Variant) -> usize {
+        let id = self.child_id(parent, name);
+        let node = self.variable(
+            parent,
+            id::HAS_PROPERTY,
+            id,
+            name,
+            data_type,
+            false,
+            Value::Static(value),
+            id::PROPERTY_TYPE,
+        );
+        self.nodes[node].browse_name.namespace = 0;
+        node
+    }
+
+    fn method(&mut self, parent: usize, name: &str, kind: MethodKind, arguments: Vec<Variant>) {
+        let id = self.child_id(parent, name);
+        let node = self.add(id, name, Kind::Method(kind));
+        self.reference(parent, id::HAS_COMPONENT, node);
+        if !arguments.is_empty() {
+            self.property(
+                node,
+                "InputArguments",
+                id::ARGUMENT,
+                Variant::Array(22, arguments),
+            );
+        }
+    }
+
+    fn add_namespace0(&mut self) {
+        // reference types
+        let reference_types: [ReferenceTypeDefinition; 10] = [
+            (id::REFERENCES, "References", None, true, None),
+            (
+                id::HIERARCHICAL_REFERENCES,
+                "HierarchicalReferences",
+                Some(id::REFERENCES),
+                false,
+                None,
+            ),
+            (
+                id::NON_HIERARCHICAL_REFERENCES,
+                "NonHierarchicalReferences",
+                Some(id::REFERENCES),
+                false,
+                None,
+            ),
+            (
+                id::HAS_CHILD,
+                "HasChild",
+                Some(id::HIERARCHICAL_REFERENCES),
+                false,
+                None,
+            ),
+            (
+                id::ORGANIZES,
+                "Organizes",
+                Some(id::HIERARCHICAL_REFERENCES),
+                false,
+                Some("OrganizedBy"),
+            ),
+            (
+                id::AGGREGATES,
+                "Aggregates",
+                Some(id::HAS_CHILD),
+                false,
+                None,
+            ),
+            (
+                id::HAS_SUBTYPE,
+                "HasSubtype",
+                Some(id::HAS_CHILD),
+                false,
+                Some("SubtypeOf"),
+            ),
+            (
+                id::HAS_PROPERTY,
+                "HasProperty",
+                Some(id::AGGREGATES),
+                false,
+                Some("PropertyOf"),
+            ),
+            (
+                id::HAS_COMPONENT,
+                "HasComponent",
+                Some(id::AGGREGATES),
+                false,
+                Some("ComponentOf"),
+            ),
+            (
+                id::HAS_TYPE_DEFINITION,
+                "HasTypeDefinition",
+                Some(id::NON_HIERARCHICAL_REFERENCES),
+                false,
+                Some("TypeDefinitionOf"),
+            ),
+        ];
+        for (node_id, name, _, symmetric, inverse_name) in reference_types.iter() {
+            self.add_ns0(
+                *node_id,
+                name,
+                Kind::ReferenceType {
+                    symmetric: *symmetric,
+                    inverse_name: *inverse_name,
+                },
+            );
+        }
+        for (node_id, _, parent, _, _) in reference_types.iter() {
+            if let Some(parent) = parent {
+                let parent = self.index[&NodeId::numeric(0, *parent)];
+                self.reference_ns0(parent, id::HAS_SUBTYPE, *node_id);
+            }
+        }
+
+        // object and variable types
+        let base_object_type =
+            self.add_ns0(id::BASE_OBJECT_TYPE, "BaseObjectType", Kind::ObjectType);
+        let folder_type = self.add_ns0(id::FOLDER_TYPE, "FolderType", Kind::ObjectType);
+        self.reference(base_object_type, id::HAS_SUBTYPE, folder_type);
+
+        let variable_types = [
+            (id::BASE_VARIABLE_TYPE, "BaseVariableType", None),
+            (
+                id::BASE_DATA_VARIABLE_TYPE,
+                "BaseDataVariableType",
+                Some(id::BASE_VARIABLE_TYPE),
+            ),
+            (
+                id::PROPERTY_TYPE,
+                "PropertyType",
+                Some(id::BASE_VARIABLE_TYPE),
+            ),
+            (
+                id::DATA_ITEM_TYPE,
+                "DataItemType",
+                Some(id::BASE_DATA_VARIABLE_TYPE),
+            ),
+            (
+                id::ANALOG_ITEM_TYPE,
+                "AnalogItemType",
+                Some(id::DATA_ITEM_TYPE),
+            ),
+        ];
+        for (node_id, name, parent) in variable_types.iter() {
+            let node = self.add_ns0(*node_id, name, Kind::VariableType);
+            if let Some(parent) = parent {
+                let parent = self.index[&NodeId::numeric(0, *parent)];
+                self.reference(parent, id::HAS_SUBTYPE, node);
+            }
+        }
+
+        let data_types = [
+            (id::BASE_DATA_TYPE, "BaseDataType", None),
+            (id::BOOLEAN, "Boolean", Some(id::BASE_DATA_TYPE)),
+            (id::STRING, "String", Some(id::BASE_DATA_TYPE)),
+            (id::DATE_TIME, "DateTime", Some(id::BASE_DATA_TYPE)),
+            (id::NUMBER, "Number", Some(id::BASE_DATA_TYPE)),
+            (id::INTEGER, "Integer", Some(id::NUMBER)),
+            (id::UINTEGER, "UInteger", Some(id::NUMBER)),
+            (id::DOUBLE, "Double", Some(id::NUMBER)),
+            (id::INT32, "Int32", Some(id::INTEGER)),
+            (id::INT64, "Int64", Some(id::INTEGER)),
+            (id::BYTE, "Byte", Some(id::UINTEGER)),
+            (id::UINT16, "UInt16", Some(id::UINTEGER)),
+            (id::UINT32, "UInt32", Some(id::UINTEGER)),
+            (id::STRUCTURE, "Structure", Some(id::BASE_DATA_TYPE)),
+            (id::ARGUMENT, "Argument", Some(id::STRUCTURE)),
+            (id::RANGE, "Range", Some(id::STRUCTURE)),
+            (id::EU_INFORMATION, "EUInformation", Some(id::STRUCTURE)),
+            (
+                id::SERVER_STATUS_DATA_TYPE,
+                "ServerStatusDataType",
+                Some(id::STRUCTURE),
+            ),
+        ];
+        for (node_id, name, parent) in data_types.iter() {
+            let node = self.add_ns0(*node_id, name, Kind::DataType);
+            if let Some(parent) = parent {
+                let parent = self.index[&NodeId::numeric(0, *parent)];
+                self.reference(parent, id::HAS_SUBTYPE, node);
+            }
+        }
+
+        // folders
+        let root = self.add_ns0(id::ROOT_FOLDER, "Root", Kind::Object);
+        self.reference_ns0(root, id::HAS_TYPE_DEFINITION, id::FOLDER_TYPE);
+        let objects = self.folder(root, NodeId::numeric(0, id::OBJECTS_FOLDER), "Objects");
+        let types = self.folder(root, NodeId::numeric(0, id::TYPES_FOLDER), "Types");
+        self.folder(root, NodeId::numeric(0, id::VIEWS_FOLDER), "Views");
+
+        let folders = [
+            (id::OBJECT_TYPES_FOLDER, "ObjectTypes", id::BASE_OBJECT_TYPE),
+            (
+                id::VARIABLE_TYPES_FOLDER,
+                "VariableTypes",
+                id::BASE_VARIABLE_TYPE,
+            ),
+            (id::DATA_TYPES_FOLDER, "DataTypes", id::BASE_DATA_TYPE),
+            (id::REFERENCE_TYPES_FOLDER, "ReferenceTypes", id::REFERENCES),
+        ];
+        for (node_id, name, child) in folders.iter() {
+            let folder = self.folder(types, NodeId::numeric(0, *node_id), name);
+            self.reference_ns0(folder, id::ORGANIZES, *child);
+        }
+
+        // server object
+        let server = self.object(
+            objects,
+            NodeId::numeric(0, id::SERVER),
+            "Server",
+            id::ORGANIZES,
+        );
+        self.property(
+            server,
+            "ServerArray",
+            id::STRING,
+            Variant::Array(12, vec![Variant::String(Some(APPLICATION_URI.to_string()))]),
+        );
+        self.property(
+            server,
+            "NamespaceArray",
+            id::STRING,
+            Variant::Array(
+                12,
+                vec![
+                    Variant::String(Some("http://opcfoundation.org/UA/".to_string())),
+                    Variant::String(Some(NAMESPACE_URI.to_string())),
+                ],
+            ),
+        );
+        // properties of the server object have well known node ids
+        let ids = [id::SERVER_SERVER_ARRAY, id::SERVER_NAMESPACE_ARRAY];
+        for (i, node_id) in ids.iter().enumerate() {
+            let node = self.nodes.len() - 2 + i;
+            self.rename(node, NodeId::numeric(0, *node_id));
+        }
+
+        let status = self.variable(
+            server,
+            id::HAS_COMPONENT,
+            NodeId::numeric(0, id::SERVER_SERVER_STATUS),
+            "ServerStatus",
+            id::SERVER_STATUS_DATA_TYPE,
+            false,
+            Value::Io(Source::ServerStatus),
+            id::BASE_DATA_VARIABLE_TYPE,
+        );
+        let start_time = self.start_time;
+        self.variable(
+            status,
+            id::HAS_COMPONENT,
+            NodeId::numeric(0, id::SERVER_SERVER_STATUS_START_TIME),
+            "StartTime",
+            id::DATE_TIME,
+            false,
+            Value::Static(Variant::DateTime(start_time)),
+            id::BASE_DATA_VARIABLE_TYPE,
+        );
+        self.variable(
+            status,
+            id::HAS_COMPONENT,
+            NodeId::numeric(0, id::SERVER_SERVER_STATUS_CURRENT_TIME),
+            "CurrentTime",
+            id::DATE_TIME,
+            false,
+            Value::Io(Source::CurrentTime),
+            id::BASE_DATA_VARIABLE_TYPE,
+        );
+        // ServerState "Running"
+        self.variable(
+            status,
+            id::HAS_COMPONENT,
+            NodeId::numeric(0, id::SERVER_SERVER_STATUS_STATE),
+            "State",
+            id::INT32,
+            false,
+            Value::Static(Variant::Int32(0)),
+            id::BASE_DATA_VARIABLE_TYPE,
+        );
+    }
+
+    fn rename(&mut self, node: usize, id: NodeId) {
+        let old = std::mem::replace(&mut self.nodes[node].id, id.clone());
+        self.index.remove(&old);
+        self.index.insert(id, node);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn channel(
+        &mut self,
+        folder: usize,
+        folder_name: &str,
+        name: &str,
+        data_type: u32,
+        writable: bool,
+        source: Source,
+        type_definition: u32,
+    ) -> usize {
+        let mut id = NodeId::string(1, &format!("{}.{}", folder_name, name));
+        if self.index.contains_key(&id) {
+            // labels are not necessarily unique
+            id = NodeId::string(1, &format!("{}.{}_{}", folder_name, name, self.nodes.len()));
+        }
+        self.variable(
+            folder,
+            id::ORGANIZES,
+            id,
+            name,
+            data_type,
+            writable,
+            Value::Io(source),
+            type_definition,
+        )
+    }
+
+    fn component(
+        &mut self,
+        parent: usize,
+        name: &str,
+        data_type: u32,
+        writable: bool,
+        source: Source,
+    ) -> usize {
+        let id = self.child_id(parent, name);
+        self.variable(
+            parent,
+            id::HAS_COMPONENT,
+            id,
+            name,
+            data_type,
+            writable,
+            Value::Io(source),
+            id::BASE_DATA_VARIABLE_TYPE,
+        )
+    }
+
+    fn channel_object(&mut self, folder: usize, folder_name: &str, name: &str) -> usize {
+        let id = NodeId::string(1, &format!("{}.{}", folder_name, name));
+        self.object(folder, id, name, id::ORGANIZES)
+    }
+
+    fn add_device(&mut self, device: &str, info: &IoChannelInfo) {
+        let objects = self.index[&NodeId::numeric(0, id::OBJECTS_FOLDER)];
+        let device = self.folder(objects, NodeId::string(1, "Device"), device);
+
+        if !info.inputs.is_empty() {
+            let folder = self.folder(device, NodeId::string(1, "DigitalInputs"), "DigitalInputs");
+            for (i, c) in info.inputs.iter().enumerate() {
+                let name = channel_name(c.label(), "DI", i);
+                let node = self.channel(
+                    folder,
+                    "DigitalInputs",
+                    &name,
+                    id::BOOLEAN,
+                    false,
+                    Source::Input(i),
+                    id::BASE_DATA_VARIABLE_TYPE,
+                );
+                self.inputs.push(node);
+            }
+        }
+
+        if !info.outputs.is_empty() {
+            let folder = self.folder(
+                device,
+                NodeId::string(1, "DigitalOutputs"),
+                "DigitalOutputs",
+            );
+            for (i, c) in info.outputs.iter().enumerate() {
+                let name = channel_name(c.label(), "DO", i);
+                self.channel(
+                    folder,
+                    "DigitalOutputs",
+                    &name,
+                    id::BOOLEAN,
+                    true,
+                    Source::Output(i),
+                    id::BASE_DATA_VARIABLE_TYPE,
+                );
+            }
+        }
+
+        if !info.analog_inputs.is_empty() {
+            let folder = self.folder(device, NodeId::string(1, "AnalogInputs"), "AnalogInputs");
+            for (i, c) in info.analog_inputs.iter().enumerate() {
+                let name = channel_name(c.label(), "AI", i);
+                let node = self.channel(
+                    folder,
+                    "AnalogInputs",
+                    &name,
+                    id::INT64,
+                    false,
+                    Source::AnalogInput(i),
+                    id::ANALOG_ITEM_TYPE,
+                );
+                self.property(node, "EURange", id::RANGE, range(ANALOG_RANGE));
+                self.property(
+                    node,
+                    "EngineeringUnits",
+                    id::EU_INFORMATION,
+                    engineering_units(UNIT_ONE, "1", "raw value of the ADC"),
+                );
+                let mode = self.component(node, "Mode", id::INT32, true, Source::AnalogMode(i));
+                self.describe(mode, "0 = voltage, 1 = current");
+            }
+        }
+
+        if !info.analog_outputs.is_empty() {
+            let folder = self.folder(device, NodeId::string(1, "AnalogOutputs"), "AnalogOutputs");
+            for (i, c) in info.analog_outputs.iter().enumerate() {
+                let name = channel_name(c.label(), "AO", i);
+                let node = self.channel(
+                    folder,
+                    "AnalogOutputs",
+                    &name,
+                    id::INT64,
+                    true,
+                    Source::AnalogOutput(i),
+                    id::ANALOG_ITEM_TYPE,
+                );
+                self.property(node, "EURange", id::RANGE, range(ANALOG_RANGE));
+                self.property(
+                    node,
+                    "EngineeringUnits",
+                    id::EU_INFORMATION,
+                    engineering_units(UNIT_ONE, "1", "raw value of the DAC"),
+                );
+            }
+        }
+
+        if !info.temp_sensors.is_empty() {
+            let folder = self.folder(
+                device,
+                NodeId::string(1, "TemperatureSensors"),
+                "TemperatureSensors",
+            );
+            for (i, c) in info.temp_sensors.iter().enumerate() {
+                let name = channel_name(c.label(), "TMP", i);
+                let node = self.channel(
+                    folder,
+                    "TemperatureSensors",
+                    &name,
+                    id::DOUBLE,
+                    false,
+                    Source::Temperature(i),
+                    id::ANALOG_ITEM_TYPE,
+                );
+                self.property(node, "EURange", id::RANGE, range(TEMPERATURE_RANGE));
+                self.property(
+                    node,
+                    "EngineeringUnits",
+                    id::EU_INFORMATION,
+                    engineering_units(UNIT_CELSIUS, "°C", "degree Celsius"),
+                );
+                let mode = self.component(node, "Mode", id::BYTE, true, Source::TempMode(i));
+                self.describe(mode, "0 = 2-wire, 1 = 3-wire, 2 = 4-wire");
+                let sensor = self.component(
+                    node,
+                    "SensorType",
+                    id::BYTE,
+                    true,
+                    Source::TempSensorType(i),
+                );
+                self.describe(sensor, "0 = PT100, 1 = PT1000");
+            }
+        }
+
+        if !info.counter_input.is_empty() {
+            let folder = self.folder(device, NodeId::string(1, "Counters"), "Counters");
+            for (i, c) in info.counter_input.iter().enumerate() {
+                let name = channel_name(c.label(), "CNT", i);
+                let node = self.channel_object(folder, "Counters", &name);
+                self.component(node, "Value", id::INT32, false, Source::Counter(i));
+                self.component(node, "Enable", id::BOOLEAN, true, Source::CounterEnable(i));
+                self.method(
+                    node,
+                    "SetPreload",
+                    MethodKind::CounterSetPreload(i),
+                    vec![argument(
+                        "Preload",
+                        id::INT32,
+                        "Initial value of the counter",
+                    )],
+                );
+                self.method(node, "Reset", MethodKind::CounterReset(i), vec![]);
+            }
+        }
+
+        if !info.pwm_outputs.is_empty() {
+            let folder = self.folder(device, NodeId::string(1, "PwmOutputs"), "PwmOutputs");
+            for (i, c) in info.pwm_outputs.iter().enumerate() {
+                let name = channel_name(c.label(), "PWM", i);
+                let node = self.channel_object(folder, "PwmOutputs", &name);
+                self.component(node, "Period", id::UINT16, true, Source::PwmPeriod(i));
+                self.component(node, "DutyCycle", id::UINT16, true, Source::PwmDutyCycle(i));
+                let timebase =
+                    self.component(node, "Timebase", id::INT32, true, Source::PwmTimebase(i));
+                self.describe(timebase, "1 = 800 ns, 2 = 1 ms");
+                self.component(node, "Enable", id::BOOLEAN, true, Source::PwmEnable(i));
+            }
+        }
+
+        let system = self.folder(device, NodeId::string(1, "System"), "System");
+        let channels = [
+            (info.run_led.label(), "RunLED", true, Source::RunLed),
+            (info.err_led.label(), "ErrorLED", true, Source::ErrLed),
+            (
+                info.run_switch.label(),
+                "RunSwitch",
+                false,
+                Source::RunSwitch,
+            ),
+            (
+                info.config_switch.label(),
+                "ConfigSwitch",
+                false,
+                Source::ConfigSwitch,
+            ),
+        ];
+        for (_, name, writable, source) in channels.iter() {
+            self.channel(
+                system,
+                "System",
+                name,
+                id::BOOLEAN,
+                *writable,
+                *source,
+                id::BASE_DATA_VARIABLE_TYPE,
+            );
+        }
+    }
+
+    pub fn node(&self, index: usize) -> &Node {
+        &self.nodes[index]
+    }
+
+    pub fn find(&self, id: &NodeId) -> Option<usize> {
+        self.index.get(id).copied()
+    }
+
+    /// Node of the digital input with the given channel number
+    pub fn input_node(&self, channel: usize) -> Option<usize> {
+        self.inputs.get(channel).copied()
+    }
+
+    /// Check whether the reference type is the given type or a subtype of it
+    pub fn is_subtype(&self, type_id: u32, of: u32) -> bool {
+        let mut current = type_id;
+        loop {
+            if current == of {
+                return true;
+            }
+            let node = match self.find(&NodeId::numeric(0, current)) {
+                Some(node) => &self.nodes[node],
+                None => return false,
+            };
+            let parent = node
+                .references
+                .iter()
+                .find(|r| r.type_id == id::HAS_SUBTYPE && !r.forward);
+            match parent.and_then(|r| self.nodes[r.target].id.ns0()) {
+                Some(parent) => current = parent,
+                None => return false,
+            }
+        }
+    }
+
+    /// Type definition of an object or variable
+    pub fn type_definition(&self, node: usize) -> Option<&NodeId> {
+        self.nodes[node]
+            .references
+            .iter()
+            .find(|r| r.type_id == id::HAS_TYPE_DEFINITION && r.forward)
+            .map(|r| &self.nodes[r.target].id)
+    }
+
+    fn read_source(&self, io: &Mutex<Io>, source: Source) -> Result<Variant, StatusCode> {
+        if let Some(value) = self.shadow.lock().unwrap().get(&source) {
+            return Ok(value.clone());
+        }
+
+        let mut io = io.lock().map_err(|_| status::BAD_INTERNAL_ERROR)?;
+        let value = match source {
+            Source::Input(channel) => io.input_get(channel).map(Variant::Boolean),
+            Source::RunSwitch => io.get_run_switch().map(Variant::Boolean),
+            Source::ConfigSwitch => io.get_config_switch().map(Variant::Boolean),
+            Source::AnalogInput(channel) => io.analog_input_get(channel).map(Variant::Int64),
+            Source::Temperature(channel) => io.tmp_input_get(channel).map(Variant::Double),
+            Source::Counter(channel) => io.cnt_get(channel).map(Variant::Int32),
+            Source::CurrentTime => Ok(Variant::DateTime(now())),
+            Source::ServerStatus => Ok(Variant::ExtensionObject(ExtensionObject::encode(
+                id::SERVER_STATUS_DATA_TYPE_ENCODING,
+                |e| self.encode_server_status(e),
+            ))),
+            // outputs and configurations can not be read back
+            _ => return Err(status::BAD_WAITING_FOR_INITIAL_DATA),
+        };
+
+        value.map_err(|e| status::from_error(&e))
+    }
+
+    fn encode_server_status(&self, e: &mut Encoder) {
+        e.i64(self.start_time);
+        e.i64(now());
+        // state: running
+        e.i32(0);
+        // build info
+        e.str(PRODUCT_URI);
+        e.str("SYS TEC electronic AG");
+        e.str("sysWORXX I/O OPC UA Server");
+        e.str(env!("CARGO_PKG_VERSION"));
+        e.str(env!("CARGO_PKG_VERSION"));
+        e.i64(self.start_time);
+        // seconds till shutdown, shutdown reason
+        e.u32(0);
+        e.localized_text(&LocalizedText::default());
+    }
+
+    /// Read the value of a variable
+    pub fn read_value(&self, io: &Mutex<Io>, node: usize) -> DataValue {
+        let timestamp = now();
+        let result = match &self.nodes[node].kind {
+            Kind::Variable {
+                value: Value::Static(value),
+                ..
+            } => Ok(value.clone()),
+            Kind::Variable {
+                value: Value::Io(source),
+                ..
+            } => self.read_source(io, *source),
+            _ => return DataValue::bad(status::BAD_ATTRIBUTE_ID_INVALID),
+        };
+
+        match result {
+            Ok(value) => DataValue {
+                value: Some(value),
+                status: None,
+                source_timestamp: Some(timestamp),
+                server_timestamp: Some(timestamp),
+            },
+            Err(status) => DataValue {
+                status: Some(status),
+                server_timestamp: Some(timestamp),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Read an attribute of a node
+    pub fn read(&self, io: &Mutex<Io>, node: usize, attribute_id: u32) -> DataValue {
+        let n = &self.nodes[node];
+
+        let value = match (attribute_id, &n.kind) {
+            (attribute::NODE_ID, _) => Variant::NodeId(n.id.clone()),
+            (attribute::NODE_CLASS, _) => Variant::Int32(n.class() as i32),
+            (attribute::BROWSE_NAME, _) => Variant::QualifiedName(n.browse_name.clone()),
+            (attribute::DISPLAY_NAME, _) => Variant::LocalizedText(n.display_name.clone()),
+            (attribute::DESCRIPTION, _) => {
+                Variant::LocalizedText(n.description.clone().unwrap_or_default())
+            }
+            (attribute::WRITE_MASK, _) | (attribute::USER_WRITE_MASK, _) => Variant::UInt32(0),
+            (attribute::EVENT_NOTIFIER, Kind::Object) => Variant::Byte(0),
+            (attribute::IS_ABSTRACT, Kind::ObjectType)
+            | (attribute::IS_ABSTRACT, Kind::VariableType)
+            | (attribute::IS_ABSTRACT, Kind::DataType)
+            | (attribute::IS_ABSTRACT, Kind::ReferenceType { .. }) => {
+                let abstract_types = [
+                    id::BASE_VARIABLE_TYPE,
+                    id::BASE_DATA_TYPE,
+                    id::NUMBER,
+                    id::INTEGER,
+                    id::UINTEGER,
+                    id::STRUCTURE,
+                    id::REFERENCES,
+                    id::HIERARCHICAL_REFERENCES,
+                    id::NON_HIERARCHICAL_REFERENCES,
+                    id::HAS_CHILD,
+                    id::AGGREGATES,
+                ];
+                Variant::Boolean(n.id.ns0().is_some_and(|i| abstract_types.contains(&i)))
+            }
+            (attribute::SYMMETRIC, Kind::ReferenceType { symmetric, .. }) => {
+                Variant::Boolean(*symmetric)
+            }
+            (attribute::INVERSE_NAME, Kind::ReferenceType { inverse_name, .. }) => {
+                Variant::LocalizedText(inverse_name.map(LocalizedText::new).unwrap_or_default())
+            }
+            (attribute::VALUE, Kind::Variable { .. }) => return self.read_value(io, node),
+            (attribute::DATA_TYPE, Kind::Variable { data_type, .. }) => {
+                Variant::NodeId(NodeId::numeric(0, *data_type))
+            }
+            (attribute::DATA_TYPE, Kind::VariableType) => {
+                Variant::NodeId(NodeId::numeric(0, id::BASE_DATA_TYPE))
+            }
+            (attribute::VALUE_RANK, Kind::Variable { value_rank, .. }) => {
+                Variant::Int32(*value_rank)
+            }
+            // any value rank
+            (attribute::VALUE_RANK, Kind::VariableType) => Variant::Int32(-2),
+            (attribute::ARRAY_DIMENSIONS, Kind::Variable { value_rank: 1, .. }) => {
+                Variant::Array(7, vec![Variant::UInt32(0)])
+            }
+            (attribute::ACCESS_LEVEL, Kind::Variable { writable, .. })
+            | (attribute::USER_ACCESS_LEVEL, Kind::Variable { writable, .. }) => {
+                // CurrentRead, CurrentWrite
+                Variant::Byte(if *writable { 0x03 } else { 0x01 })
+            }
+            (attribute::MINIMUM_SAMPLING_INTERVAL, Kind::Variable { .. }) => Variant::Double(0.0),
+            (attribute::HISTORIZING, Kind::Variable { .. }) => Variant::Boolean(false),
+            (attribute::EXECUTABLE, Kind::Method(_))
+            | (attribute::USER_EXECUTABLE, Kind::Method(_)) => Variant::Boolean(true),
+            _ => return DataValue::bad(status::BAD_ATTRIBUTE_ID_INVALID),
+        };
+
+        DataValue::new(value)
+    }
+
+    /// Write the value attribute of a variable
+    pub fn write(
+        &self,
+        io: &Mutex<Io>,
+        node: usize,
+        attribute_id: u32,
+        value: &DataValue,
+    ) -> StatusCode {
+        let source = match &self.nodes[node].kind {
+            Kind::Variable {
+                writable: true,
+                value: Value::Io(source),
+                ..
+            } if attribute_id == attribute::VALUE => *source,
+            Kind::Variable { .. } if attribute_id == attribute::VALUE => {
+                return status::BAD_NOT_WRITABLE
+            }
+            _ if attribute_id <= attribute::USER_EXECUTABLE => return status::BAD_NOT_WRITABLE,
+            _ => return status::BAD_ATTRIBUTE_ID_INVALID,
+        };
+        let value = match &value.value {
+            Some(value) => value,
+            None => return status::BAD_TYPE_MISMATCH,
+        };
+
+        match self.write_source(io, source, value) {
+            Ok(value) => {
+                self.shadow.lock().unwrap().insert(source, value);
+                status::GOOD
+            }
+            Err(status) => status,
+        }
+    }
+
+    fn shadow_value(&self, source: Source) -> Option<i64> {
+        self.shadow
+            .lock()
+            .unwrap()
+            .get(&source)
+            .and_then(Variant::as_i64)
+    }
+
+    /// Write a value to the channel and return the value to remember
+    fn write_source(
+        &self,
+        io: &Mutex<Io>,
+        source: Source,
+        value: &Variant,
+    ) -> Result<Variant, StatusCode> {
+        let to_bool = |value: &Variant| value.as_bool().ok_or(status::BAD_TYPE_MISMATCH);
+        let to_int = |value: &Variant, min: i64, max: i64| match value.as_i64() {
+            Some(v) if (min..=max).contains(&v) => Ok(v),
+            Some(_) => Err(status::BAD_OUT_OF_RANGE),
+            None => Err(status::BAD_TYPE_MISMATCH),
+        };
+        let to_u8 = |value: i64| value as u8;
+
+        let mut io = io.lock().map_err(|_| status::BAD_INTERNAL_ERROR)?;
+        let error = |e: crate::error::Error| status::from_error(&e);
+
+        let value = match source {
+            Source::Output(channel) => {
+                let value = to_bool(value)?;
+                io.output_set(channel, value).map_err(error)?;
+                Variant::Boolean(value)
+            }
+            Source::RunLed => {
+                let value = to_bool(value)?;
+                io.set_run_led(value).map_err(error)?;
+                Variant::Boolean(value)
+            }
+            Source::ErrLed => {
+                let value = to_bool(value)?;
+                io.set_err_led(value).map_err(error)?;
+                Variant::Boolean(value)
+            }
+            Source::AnalogMode(channel) => {
+                let mode = to_int(value, 0, 255)?;
+                let mode = ffi::IoAnalogMode::try_from(to_u8(mode)).map_err(error)?;
+                io.analog_mode_set(channel, mode).map_err(error)?;
+                Variant::Int32(mode as i32)
+            }
+            Source::AnalogOutput(channel) => {
+                let value = to_int(value, i64::MIN, i64::MAX)?;
+                io.analog_output_set(channel, value).map_err(error)?;
+                Variant::Int64(value)
+            }
+            Source::TempMode(channel) | Source::TempSensorType(channel) => {
+                // both are set at once, the other one is taken from the last write (or default)
+                let new = to_int(value, 0, 255)?;
+                let (mode, sensor_type) = match source {
+                    Source::TempMode(_) => (
+                        new,
+                        self.shadow_value(Source::TempSensorType(channel))
+                            .unwrap_or(0),
+                    ),
+                    _ => (
+                        self.shadow_value(Source::TempMode(channel)).unwrap_or(0),
+                        new,
+                    ),
+                };
+                let mode = ffi::IoTmpMode::try_from(to_u8(mode)).map_err(error)?;
+                let sensor_type =
+                    ffi::IoTmpSensorType::try_from(to_u8(sensor_type)).map_err(error)?;
+                io.tmp_set_mode(channel, mode, sensor_type).map_err(error)?;
+                Variant::Byte(to_u8(new))
+            }
+            Source::CounterEnable(channel) => {
+                let value = to_bool(value)?;
+                io.cnt_enable(channel, value).map_err(error)?;
+                Variant::Boolean(value)
+            }
+            Source::PwmPeriod(channel) | Source::PwmDutyCycle(channel) => {
+                // period and duty cycle are set at once
+                let new = to_int(value, 0, u16::MAX as i64)?;
+                let (period, duty_cycle) = match source {
+                    Source::PwmPeriod(_) => (
+                        new,
+                        self.shadow_value(Source::PwmDutyCycle(channel))
+                            .unwrap_or(0),
+                    ),
+                    _ => (
+                        self.shadow_value(Source::PwmPeriod(channel)).unwrap_or(0),
+                        new,
+                    ),
+                };
+                io.pwm_setup(channel, period as u16, duty_cycle as u16)
+                    .map_err(error)?;
+                Variant::UInt16(new as u16)
+            }
+            Source::PwmTimebase(channel) => {
+                let timebase = to_int(value, 0, 255)?;
+                let timebase = ffi::IoPwmTimebase::try_from(to_u8(timebase)).map_err(error)?;
+                io.pwm_set_timebase(channel, timebase).map_err(error)?;
+                Variant::Int32(timebase as i32)
+            }
+            Source::PwmEnable(channel) => {
+                let value = to_bool(value)?;
+                io.pwm_enable(channel, value).map_err(error)?;
+                Variant::Boolean(value)
+            }
+            _ => return Err(status::BAD_NOT_WRITABLE),
+        };
+
+        Ok(value)
+    }
+
+    /// Call a method of an object
+    pub fn call(
+        &self,
+        io: &Mutex<Io>,
+        object: usize,
+        method: usize,
+        arguments: &[Variant],
+    ) -> (StatusCode, Vec<StatusCode>) {
+        let kind = match self.nodes[method].kind {
+            Kind::Method(kind) => kind,
+            _ => return (status::BAD_METHOD_INVALID, vec![]),
+        };
+        let is_component = self.nodes[object]
+            .references
+            .iter()
+            .any(|r| r.forward && r.type_id == id::HAS_COMPONENT && r.target == method);
+        if !is_component {
+            return (status::BAD_METHOD_INVALID, vec![]);
+        }
+
+        let expected = match kind {
+            MethodKind::CounterSetPreload(_) => 1,
+            MethodKind::CounterReset(_) => 0,
+        };
+        if arguments.len() < expected {
+            return (status::BAD_ARGUMENTS_MISSING, vec![]);
+        }
+        if arguments.len() > expected {
+            return (status::BAD_TOO_MANY_ARGUMENTS, vec![]);
+        }
+
+        let mut io = match io.lock() {
+            Ok(io) => io,
+            Err(_) => return (status::BAD_INTERNAL_ERROR, vec![]),
+        };
+
+        let result = match kind {
+            MethodKind::CounterSetPreload(channel) => match arguments[0] {
+                Variant::Int32(preload) => io.cnt_set_preload(channel, preload),
+                _ => {
+                    return (
+                        status::BAD_INVALID_ARGUMENT,
+                        vec![status::BAD_TYPE_MISMATCH],
+                    )
+                }
+            },
+            // load the preload into the counter
+            MethodKind::CounterReset(channel) => io.cnt_reset(channel),
+        };
+
+        match result {
+            Ok(()) => (status::GOOD, vec![status::GOOD; arguments.len()]),
+            Err(e) => (status::from_error(&e), vec![]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+
+    fn space() -> (AddressSpace, Mutex<Io>) {
+        let sim = Simulator::new("SIM", 1, 1, 1);
+        let mut io = IoBuilder::new()
+            .output(Box::new(sim.output(0)))
+            .input(Box::new(sim.input(0)))
+            .analog_input(Box::new(sim.analog_input(0)))
+            .temp_sensor(Box::new(sim.temp_sensor(0)))
+            .build();
+        io.init().unwrap();
+        sim.set_temperature(0, 21.5).unwrap();
+        (
+            AddressSpace::new("sim", &io.get_channel_info()),
+            Mutex::new(io),
+        )
+    }
+
+    #[test]
+    fn hierarchy_test() {
+        let (space, _) = space();
+        assert!(space.is_subtype(id::HAS_PROPERTY, id::HIERARCHICAL_REFERENCES));
+        assert!(!space.is_subtype(id::HAS_TYPE_DEFINITION, id::HIERARCHICAL_REFERENCES));
+
+        let input = space.find(&NodeId::string(1, "DigitalInputs.DI0")).unwrap();
+        assert_eq!(space.input_node(0), Some(input));
+        assert_eq!(
+            space.type_definition(input),
+            Some(&NodeId::numeric(0, id::BASE_DATA_VARIABLE_TYPE))
+        );
+
+        // the analog items have a range and a unit
+        for name in ["AnalogInputs.AI0", "TemperatureSensors.TMP0"].iter() {
+            for property in ["EURange", "EngineeringUnits"].iter() {
+                let id = NodeId::string(1, &format!("{}.{}", name, property));
+                assert!(space.find(&id).is_some(), "{:?}", id);
+            }
+        }
+
+        // all references are bidirectional
+        for node in &space.nodes {
+            for r in &node.references {
+                assert!(space.nodes[r.target]
+                    .references
+                    .iter()
+                    .any(|b| b.type_id == r.type_id && b.forward != r.forward));
+            }
+        }
+    }
+
+    #[test]
+    fn read_write_test() {
+        let (space, io) = space();
+        let output = space
+            .find(&NodeId::string(1, "DigitalOutputs.DO0"))
+            .unwrap();
+        let input = space.find(&NodeId::string(1, "DigitalInputs.DI0")).unwrap();
+        let temperature = space
+            .find(&NodeId::string(1, "TemperatureSensors.TMP0"))
+            .unwrap();
+
+        assert_eq!(
+            space.read(&io, output, attribute::VALUE).status(),
+            status::BAD_WAITING_FOR_INITIAL_DATA
+        );
+        let value = DataValue::new(Variant::Boolean(true));
+        assert_eq!(
+            space.write(&io, output, attribute::VALUE, &value),
+            status::GOOD
+        );
+        assert_eq!(
+            space.read(&io, output, attribute::VALUE).value,
+            Some(Variant::Boolean(true))
+        );
+        assert_eq!(
+            space.read(&io, input, attribute::VALUE).value,
+            Some(Variant::Boolean(true))
+        );
+        assert_eq!(
+            space.write(&io, input, attribute::VALUE, &value),
+            status::BAD_NOT_WRITABLE
+        );
+        assert_eq!(
+            space.write(
+                &io,
+                output,
+                attribute::VALUE,
+                &DataValue::new(Variant::Int32(1))
+            ),
+            status::BAD_TYPE_MISMATCH
+        );
+
+        assert_eq!(
+            space.read(&io, temperature, attribute::VALUE).value,
+            Some(Variant::Double(21.5))
+        );
+        assert_eq!(
+            space.read(&io, temperature, attribute::DATA_TYPE).value,
+            Some(Variant::NodeId(NodeId::numeric(0, id::DOUBLE)))
+        );
+        assert_eq!(
+            space.read(&io, temperature, attribute::EXECUTABLE).status(),
+            status::BAD_ATTRIBUTE_ID_INVALID
+        );
+    }
+}
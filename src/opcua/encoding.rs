@@ -0,0 +1,780 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// OPC UA binary encoding (OPC 10000-6, 5.2) of the built-in types used by the server.
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::status::{self, StatusCode};
+
+pub type UaResult<T> = std::result::Result<T, StatusCode>;
+
+/// Difference between 1601-01-01 (OPC UA epoch) and 1970-01-01 in 100 ns ticks
+const EPOCH_DIFFERENCE: i64 = 116_444_736_000_000_000;
+
+/// Maximum length of strings and arrays accepted by the decoder
+const MAX_LENGTH: usize = 1 << 20;
+
+/// Current time as OPC UA DateTime
+pub fn now() -> i64 {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    since_epoch.as_nanos() as i64 / 100 + EPOCH_DIFFERENCE
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Identifier {
+    Numeric(u32),
+    String(String),
+    Guid([u8; 16]),
+    Opaque(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    pub namespace: u16,
+    pub identifier: Identifier,
+}
+
+impl NodeId {
+    pub fn numeric(namespace: u16, id: u32) -> NodeId {
+        NodeId {
+            namespace,
+            identifier: Identifier::Numeric(id),
+        }
+    }
+
+    pub fn string(namespace: u16, id: &str) -> NodeId {
+        NodeId {
+            namespace,
+            identifier: Identifier::String(id.to_string()),
+        }
+    }
+
+    pub fn null() -> NodeId {
+        NodeId::numeric(0, 0)
+    }
+
+    pub fn is_null(&self) -> bool {
+        *self == NodeId::null()
+    }
+
+    /// Numeric identifier of a node in namespace 0
+    pub fn ns0(&self) -> Option<u32> {
+        match self.identifier {
+            Identifier::Numeric(id) if self.namespace == 0 => Some(id),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.identifier {
+            Identifier::Numeric(id) => write!(f, "ns={};i={}", self.namespace, id),
+            Identifier::String(id) => write!(f, "ns={};s={}", self.namespace, id),
+            Identifier::Guid(_) => write!(f, "ns={};g=...", self.namespace),
+            Identifier::Opaque(_) => write!(f, "ns={};b=...", self.namespace),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct QualifiedName {
+    pub namespace: u16,
+    pub name: String,
+}
+
+impl QualifiedName {
+    pub fn new(namespace: u16, name: &str) -> QualifiedName {
+        QualifiedName {
+            namespace,
+            name: name.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LocalizedText {
+    pub locale: Option<String>,
+    pub text: Option<String>,
+}
+
+impl LocalizedText {
+    pub fn new(text: &str) -> LocalizedText {
+        LocalizedText {
+            locale: None,
+            text: Some(text.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtensionObject {
+    /// Node id of the binary encoding of the contained structure
+    pub type_id: NodeId,
+    pub body: Option<Vec<u8>>,
+}
+
+impl ExtensionObject {
+    pub fn null() -> ExtensionObject {
+        ExtensionObject {
+            type_id: NodeId::null(),
+            body: None,
+        }
+    }
+
+    /// Encode a structure with the given binary encoding id
+    pub fn encode<F: FnOnce(&mut Encoder)>(encoding_id: u32, f: F) -> ExtensionObject {
+        let mut encoder = Encoder::new();
+        f(&mut encoder);
+        ExtensionObject {
+            type_id: NodeId::numeric(0, encoding_id),
+            body: Some(encoder.into_inner()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Variant {
+    Empty,
+    Boolean(bool),
+    SByte(i8),
+    Byte(u8),
+    Int16(i16),
+    UInt16(u16),
+    Int32(i32),
+    UInt32(u32),
+    Int64(i64),
+    UInt64(u64),
+    Float(f32),
+    Double(f64),
+    String(Option<String>),
+    DateTime(i64),
+    ByteString(Option<Vec<u8>>),
+    NodeId(NodeId),
+    StatusCode(StatusCode),
+    QualifiedName(QualifiedName),
+    LocalizedText(LocalizedText),
+    ExtensionObject(ExtensionObject),
+    /// One dimensional array with the type id of the elements
+    Array(u8, Vec<Variant>),
+}
+
+impl Variant {
+    pub fn type_id(&self) -> u8 {
+        match self {
+            Variant::Empty => 0,
+            Variant::Boolean(_) => 1,
+            Variant::SByte(_) => 2,
+            Variant::Byte(_) => 3,
+            Variant::Int16(_) => 4,
+            Variant::UInt16(_) => 5,
+            Variant::Int32(_) => 6,
+            Variant::UInt32(_) => 7,
+            Variant::Int64(_) => 8,
+            Variant::UInt64(_) => 9,
+            Variant::Float(_) => 10,
+            Variant::Double(_) => 11,
+            Variant::String(_) => 12,
+            Variant::DateTime(_) => 13,
+            Variant::ByteString(_) => 15,
+            Variant::NodeId(_) => 17,
+            Variant::StatusCode(_) => 19,
+            Variant::QualifiedName(_) => 20,
+            Variant::LocalizedText(_) => 21,
+            Variant::ExtensionObject(_) => 22,
+            Variant::Array(type_id, _) => *type_id,
+        }
+    }
+
+    /// Value of an integer variant (clients often write with a different integer type)
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Variant::SByte(v) => Some(v as i64),
+            Variant::Byte(v) => Some(v as i64),
+            Variant::Int16(v) => Some(v as i64),
+            Variant::UInt16(v) => Some(v as i64),
+            Variant::Int32(v) => Some(v as i64),
+            Variant::UInt32(v) => Some(v as i64),
+            Variant::Int64(v) => Some(v),
+            Variant::UInt64(v) if v <= i64::MAX as u64 => Some(v as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Variant::Float(v) => Some(v as f64),
+            Variant::Double(v) => Some(v),
+            _ => self.as_i64().map(|v| v as f64),
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Variant::Boolean(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DataValue {
+    pub value: Option<Variant>,
+    pub status: Option<StatusCode>,
+    pub source_timestamp: Option<i64>,
+    pub server_timestamp: Option<i64>,
+}
+
+impl DataValue {
+    pub fn new(value: Variant) -> DataValue {
+        DataValue {
+            value: Some(value),
+            ..Default::default()
+        }
+    }
+
+    pub fn bad(status: StatusCode) -> DataValue {
+        DataValue {
+            status: Some(status),
+            ..Default::default()
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status.unwrap_or(status::GOOD)
+    }
+}
+
+pub struct Encoder {
+    buffer: Vec<u8>,
+}
+
+impl Default for Encoder {
+    fn default() -> Encoder {
+        Encoder::new()
+    }
+}
+
+impl Encoder {
+    pub fn new() -> Encoder {
+        Encoder { buffer: vec![] }
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    pub fn bytes(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    pub fn u8(&mut self, value: u8) {
+        self.buffer.push(value);
+    }
+
+    pub fn bool(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+
+    pub fn u16(&mut self, value: u16) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn i32(&mut self, value: i32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn i64(&mut self, value: i64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn f64(&mut self, value: f64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn string(&mut self, value: Option<&str>) {
+        self.byte_string(value.map(str::as_bytes));
+    }
+
+    pub fn str(&mut self, value: &str) {
+        self.string(Some(value));
+    }
+
+    pub fn byte_string(&mut self, value: Option<&[u8]>) {
+        match value {
+            Some(data) => {
+                self.i32(data.len() as i32);
+                self.bytes(data);
+            }
+            None => self.i32(-1),
+        }
+    }
+
+    pub fn array<T, F: FnMut(&mut Encoder, &T)>(&mut self, items: &[T], mut f: F) {
+        self.i32(items.len() as i32);
+        for item in items {
+            f(self, item);
+        }
+    }
+
+    /// Empty array of diagnostic infos
+    pub fn no_diagnostics(&mut self) {
+        self.i32(-1);
+    }
+
+    pub fn node_id(&mut self, node_id: &NodeId) {
+        match &node_id.identifier {
+            Identifier::Numeric(id) if node_id.namespace == 0 && *id <= 0xff => {
+                self.u8(0x00);
+                self.u8(*id as u8);
+            }
+            Identifier::Numeric(id) if node_id.namespace <= 0xff && *id <= 0xffff => {
+                self.u8(0x01);
+                self.u8(node_id.namespace as u8);
+                self.u16(*id as u16);
+            }
+            Identifier::Numeric(id) => {
+                self.u8(0x02);
+                self.u16(node_id.namespace);
+                self.u32(*id);
+            }
+            Identifier::String(id) => {
+                self.u8(0x03);
+                self.u16(node_id.namespace);
+                self.str(id);
+            }
+            Identifier::Guid(id) => {
+                self.u8(0x04);
+                self.u16(node_id.namespace);
+                self.bytes(id);
+            }
+            Identifier::Opaque(id) => {
+                self.u8(0x05);
+                self.u16(node_id.namespace);
+                self.byte_string(Some(id));
+            }
+        }
+    }
+
+    /// Expanded node id of a local node (without namespace URI and server index)
+    pub fn expanded_node_id(&mut self, node_id: &NodeId) {
+        self.node_id(node_id);
+    }
+
+    pub fn qualified_name(&mut self, name: &QualifiedName) {
+        self.u16(name.namespace);
+        self.str(&name.name);
+    }
+
+    pub fn localized_text(&mut self, text: &LocalizedText) {
+        let mask = text.locale.is_some() as u8 | (text.text.is_some() as u8) << 1;
+        self.u8(mask);
+        if let Some(locale) = &text.locale {
+            self.str(locale);
+        }
+        if let Some(text) = &text.text {
+            self.str(text);
+        }
+    }
+
+    pub fn extension_object(&mut self, object: &ExtensionObject) {
+        self.node_id(&object.type_id);
+        match &object.body {
+            Some(body) => {
+                self.u8(0x01);
+                self.byte_string(Some(body));
+            }
+            None => self.u8(0x00),
+        }
+    }
+
+    fn variant_value(&mut self, value: &Variant) {
+        match value {
+            Variant::Empty | Variant::Array(..) => {}
+            Variant::Boolean(v) => self.bool(*v),
+            Variant::SByte(v) => self.u8(*v as u8),
+            Variant::Byte(v) => self.u8(*v),
+            Variant::Int16(v) => self.bytes(&v.to_le_bytes()),
+            Variant::UInt16(v) => self.u16(*v),
+            Variant::Int32(v) => self.i32(*v),
+            Variant::UInt32(v) => self.u32(*v),
+            Variant::Int64(v) => self.i64(*v),
+            Variant::UInt64(v) => self.bytes(&v.to_le_bytes()),
+            Variant::Float(v) => self.bytes(&v.to_le_bytes()),
+            Variant::Double(v) => self.f64(*v),
+            Variant::String(v) => self.string(v.as_deref()),
+            Variant::DateTime(v) => self.i64(*v),
+            Variant::ByteString(v) => self.byte_string(v.as_deref()),
+            Variant::NodeId(v) => self.node_id(v),
+            Variant::StatusCode(v) => self.u32(*v),
+            Variant::QualifiedName(v) => self.qualified_name(v),
+            Variant::LocalizedText(v) => self.localized_text(v),
+            Variant::ExtensionObject(v) => self.extension_object(v),
+        }
+    }
+
+    pub fn variant(&mut self, value: &Variant) {
+        match value {
+            Variant::Array(type_id, items) => {
+                self.u8(type_id | 0x80);
+                self.array(items, |e, item| e.variant_value(item));
+            }
+            value => {
+                self.u8(value.type_id());
+                self.variant_value(value);
+            }
+        }
+    }
+
+    pub fn data_value(&mut self, value: &DataValue) {
+        let mask = value.value.is_some() as u8
+            | (value.status.is_some() as u8) << 1
+            | (value.source_timestamp.is_some() as u8) << 2
+            | (value.server_timestamp.is_some() as u8) << 3;
+        self.u8(mask);
+        if let Some(v) = &value.value {
+            self.variant(v);
+        }
+        if let Some(status) = value.status {
+            self.u32(status);
+        }
+        if let Some(timestamp) = value.source_timestamp {
+            self.i64(timestamp);
+        }
+        if let Some(timestamp) = value.server_timestamp {
+            self.i64(timestamp);
+        }
+    }
+}
+
+pub struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(data: &'a [u8]) -> Decoder<'a> {
+        Decoder { data }
+    }
+
+    pub fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn bytes(&mut self, count: usize) -> UaResult<&'a [u8]> {
+        if self.data.len() < count {
+            return Err(status::BAD_DECODING_ERROR);
+        }
+        let (bytes, rest) = self.data.split_at(count);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn array_of<const N: usize>(&mut self) -> UaResult<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.bytes(N)?);
+        Ok(array)
+    }
+
+    pub fn u8(&mut self) -> UaResult<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn bool(&mut self) -> UaResult<bool> {
+        Ok(self.u8()? != 0)
+    }
+
+    pub fn u16(&mut self) -> UaResult<u16> {
+        Ok(u16::from_le_bytes(self.array_of()?))
+    }
+
+    pub fn i32(&mut self) -> UaResult<i32> {
+        Ok(i32::from_le_bytes(self.array_of()?))
+    }
+
+    pub fn u32(&mut self) -> UaResult<u32> {
+        Ok(u32::from_le_bytes(self.array_of()?))
+    }
+
+    pub fn i64(&mut self) -> UaResult<i64> {
+        Ok(i64::from_le_bytes(self.array_of()?))
+    }
+
+    pub fn f64(&mut self) -> UaResult<f64> {
+        Ok(f64::from_le_bytes(self.array_of()?))
+    }
+
+    fn length(&mut self) -> UaResult<Option<usize>> {
+        match self.i32()? {
+            length if length < 0 => Ok(None),
+            length if length as usize > MAX_LENGTH => Err(status::BAD_ENCODING_LIMITS_EXCEEDED),
+            length => Ok(Some(length as usize)),
+        }
+    }
+
+    pub fn byte_string(&mut self) -> UaResult<Option<Vec<u8>>> {
+        match self.length()? {
+            Some(length) => Ok(Some(self.bytes(length)?.to_vec())),
+            None => Ok(None),
+        }
+    }
+
+    pub fn string(&mut self) -> UaResult<Option<String>> {
+        match self.byte_string()? {
+            Some(data) => String::from_utf8(data)
+                .map(Some)
+                .map_err(|_| status::BAD_DECODING_ERROR),
+            None => Ok(None),
+        }
+    }
+
+    pub fn array<T, F: FnMut(&mut Decoder<'a>) -> UaResult<T>>(
+        &mut self,
+        mut f: F,
+    ) -> UaResult<Vec<T>> {
+        let length = self.length()?.unwrap_or(0);
+        (0..length).map(|_| f(self)).collect()
+    }
+
+    pub fn node_id(&mut self) -> UaResult<NodeId> {
+        let encoding = self.u8()?;
+        self.node_id_body(encoding & 0x3f)
+    }
+
+    fn node_id_body(&mut self, encoding: u8) -> UaResult<NodeId> {
+        let node_id = match encoding {
+            0x00 => NodeId::numeric(0, self.u8()? as u32),
+            0x01 => {
+                let namespace = self.u8()? as u16;
+                NodeId::numeric(namespace, self.u16()? as u32)
+            }
+            0x02 => {
+                let namespace = self.u16()?;
+                NodeId::numeric(namespace, self.u32()?)
+            }
+            0x03 => {
+                let namespace = self.u16()?;
+                NodeId {
+                    namespace,
+                    identifier: Identifier::String(self.string()?.unwrap_or_default()),
+                }
+            }
+            0x04 => {
+                let namespace = self.u16()?;
+                NodeId {
+                    namespace,
+                    identifier: Identifier::Guid(self.array_of()?),
+                }
+            }
+            0x05 => {
+                let namespace = self.u16()?;
+                NodeId {
+                    namespace,
+                    identifier: Identifier::Opaque(self.byte_string()?.unwrap_or_default()),
+                }
+            }
+            _ => return Err(status::BAD_DECODING_ERROR),
+        };
+        Ok(node_id)
+    }
+
+    /// Expanded node id. Namespace URI and server index are skipped.
+    pub fn expanded_node_id(&mut self) -> UaResult<NodeId> {
+        let encoding = self.u8()?;
+        let node_id = self.node_id_body(encoding & 0x3f)?;
+        if encoding & 0x80 != 0 {
+            self.string()?;
+        }
+        if encoding & 0x40 != 0 {
+            self.u32()?;
+        }
+        Ok(node_id)
+    }
+
+    pub fn qualified_name(&mut self) -> UaResult<QualifiedName> {
+        Ok(QualifiedName {
+            namespace: self.u16()?,
+            name: self.string()?.unwrap_or_default(),
+        })
+    }
+
+    pub fn localized_text(&mut self) -> UaResult<LocalizedText> {
+        let mask = self.u8()?;
+        let locale = if mask & 0x01 != 0 {
+            self.string()?
+        } else {
+            None
+        };
+        let text = if mask & 0x02 != 0 {
+            self.string()?
+        } else {
+            None
+        };
+        Ok(LocalizedText { locale, text })
+    }
+
+    pub fn extension_object(&mut self) -> UaResult<ExtensionObject> {
+        let type_id = self.node_id()?;
+        let body = match self.u8()? {
+            0x00 => None,
+            // binary and XML bodies are both length prefixed
+            0x01 | 0x02 => self.byte_string()?,
+            _ => return Err(status::BAD_DECODING_ERROR),
+        };
+        Ok(ExtensionObject { type_id, body })
+    }
+
+    /// Skip a diagnostic info
+    pub fn diagnostic_info(&mut self) -> UaResult<()> {
+        let mask = self.u8()?;
+        for bit in 0..4 {
+            if mask & (1 << bit) != 0 {
+                self.i32()?;
+            }
+        }
+        if mask & 0x10 != 0 {
+            self.string()?;
+        }
+        if mask & 0x20 != 0 {
+            self.u32()?;
+        }
+        if mask & 0x40 != 0 {
+            self.diagnostic_info()?;
+        }
+        Ok(())
+    }
+
+    fn variant_value(&mut self, type_id: u8) -> UaResult<Variant> {
+        let value = match type_id {
+            0 => Variant::Empty,
+            1 => Variant::Boolean(self.bool()?),
+            2 => Variant::SByte(self.u8()? as i8),
+            3 => Variant::Byte(self.u8()?),
+            4 => Variant::Int16(i16::from_le_bytes(self.array_of()?)),
+            5 => Variant::UInt16(self.u16()?),
+            6 => Variant::Int32(self.i32()?),
+            7 => Variant::UInt32(self.u32()?),
+            8 => Variant::Int64(self.i64()?),
+            9 => Variant::UInt64(u64::from_le_bytes(self.array_of()?)),
+            10 => Variant::Float(f32::from_le_bytes(self.array_of()?)),
+            11 => Variant::Double(self.f64()?),
+            12 => Variant::String(self.string()?),
+            13 => Variant::DateTime(self.i64()?),
+            15 => Variant::ByteString(self.byte_string()?),
+            17 => Variant::NodeId(self.node_id()?),
+            19 => Variant::StatusCode(self.u32()?),
+            20 => Variant::QualifiedName(self.qualified_name()?),
+            21 => Variant::LocalizedText(self.localized_text()?),
+            22 => Variant::ExtensionObject(self.extension_object()?),
+            _ => return Err(status::BAD_DECODING_ERROR),
+        };
+        Ok(value)
+    }
+
+    pub fn variant(&mut self) -> UaResult<Variant> {
+        let encoding = self.u8()?;
+        let type_id = encoding & 0x3f;
+
+        if encoding & 0x80 == 0 {
+            return self.variant_value(type_id);
+        }
+
+        let items = self.array(|d| d.variant_value(type_id))?;
+        if encoding & 0x40 != 0 {
+            // dimensions of multi dimensional arrays are not used
+            self.array(|d| d.i32())?;
+        }
+        Ok(Variant::Array(type_id, items))
+    }
+
+    pub fn data_value(&mut self) -> UaResult<DataValue> {
+        let mask = self.u8()?;
+        let mut value = DataValue::default();
+        if mask & 0x01 != 0 {
+            value.value = Some(self.variant()?);
+        }
+        if mask & 0x02 != 0 {
+            value.status = Some(self.u32()?);
+        }
+        if mask & 0x04 != 0 {
+            value.source_timestamp = Some(self.i64()?);
+        }
+        if mask & 0x10 != 0 {
+            self.u16()?;
+        }
+        if mask & 0x08 != 0 {
+            value.server_timestamp = Some(self.i64()?);
+        }
+        if mask & 0x20 != 0 {
+            self.u16()?;
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_id_test() {
+        let ids = [
+            (NodeId::numeric(0, 85), vec![0x00, 0x55]),
+            (NodeId::numeric(1, 1025), vec![0x01, 0x01, 0x01, 0x04]),
+            (
+                NodeId::numeric(0, 100_000),
+                vec![0x02, 0x00, 0x00, 0xa0, 0x86, 0x01, 0x00],
+            ),
+            (
+                NodeId::string(1, "DI0"),
+                vec![0x03, 0x01, 0x00, 0x03, 0x00, 0x00, 0x00, b'D', b'I', b'0'],
+            ),
+        ];
+
+        for (id, bytes) in &ids {
+            let mut encoder = Encoder::new();
+            encoder.node_id(id);
+            assert_eq!(&encoder.into_inner(), bytes);
+            assert_eq!(&Decoder::new(bytes).node_id().unwrap(), id);
+        }
+    }
+
+    #[test]
+    fn variant_test() {
+        let values = [
+            Variant::Boolean(true),
+            Variant::Int64(-2),
+            Variant::Double(21.5),
+            Variant::String(Some("°C".to_string())),
+            Variant::LocalizedText(LocalizedText::new("text")),
+            Variant::Array(6, vec![Variant::Int32(1), Variant::Int32(2)]),
+        ];
+
+        for value in &values {
+            let data_value = DataValue {
+                value: Some(value.clone()),
+                status: Some(status::GOOD),
+                source_timestamp: Some(now()),
+                server_timestamp: None,
+            };
+            let mut encoder = Encoder::new();
+            encoder.data_value(&data_value);
+            let bytes = encoder.into_inner();
+            let mut decoder = Decoder::new(&bytes);
+            assert_eq!(decoder.data_value().unwrap(), data_value);
+            assert!(decoder.remaining().is_empty());
+        }
+
+        assert_eq!(
+            Decoder::new(&[0x06, 0x01, 0x00]).variant(),
+            Err(status::BAD_DECODING_ERROR)
+        );
+    }
+}
@@ -0,0 +1,223 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// OPC UA server support (UA TCP binary protocol, security policy None, anonymous sessions).
+
+pub mod address_space;
+pub mod encoding;
+pub mod server;
+mod subscription;
+
+/// Status codes (OPC 10000-4, 7.34 and OPC 10000-6, A.2)
+pub mod status {
+    pub type StatusCode = u32;
+
+    pub const GOOD: StatusCode = 0;
+    pub const BAD_INTERNAL_ERROR: StatusCode = 0x8002_0000;
+    pub const BAD_ENCODING_ERROR: StatusCode = 0x8006_0000;
+    pub const BAD_DECODING_ERROR: StatusCode = 0x8007_0000;
+    pub const BAD_ENCODING_LIMITS_EXCEEDED: StatusCode = 0x8008_0000;
+    pub const BAD_SERVICE_UNSUPPORTED: StatusCode = 0x800b_0000;
+    pub const BAD_NOTHING_TO_DO: StatusCode = 0x800f_0000;
+    pub const BAD_TOO_MANY_OPERATIONS: StatusCode = 0x8010_0000;
+    pub const BAD_USER_ACCESS_DENIED: StatusCode = 0x801f_0000;
+    pub const BAD_IDENTITY_TOKEN_INVALID: StatusCode = 0x8020_0000;
+    pub const BAD_SECURE_CHANNEL_ID_INVALID: StatusCode = 0x8022_0000;
+    pub const BAD_SESSION_ID_INVALID: StatusCode = 0x8025_0000;
+    pub const BAD_SESSION_NOT_ACTIVATED: StatusCode = 0x8027_0000;
+    pub const BAD_SUBSCRIPTION_ID_INVALID: StatusCode = 0x8028_0000;
+    pub const BAD_TIMESTAMPS_TO_RETURN_INVALID: StatusCode = 0x802b_0000;
+    pub const BAD_WAITING_FOR_INITIAL_DATA: StatusCode = 0x8032_0000;
+    pub const BAD_NODE_ID_INVALID: StatusCode = 0x8033_0000;
+    pub const BAD_NODE_ID_UNKNOWN: StatusCode = 0x8034_0000;
+    pub const BAD_ATTRIBUTE_ID_INVALID: StatusCode = 0x8035_0000;
+    pub const BAD_INDEX_RANGE_INVALID: StatusCode = 0x8036_0000;
+    pub const BAD_DATA_ENCODING_UNSUPPORTED: StatusCode = 0x8039_0000;
+    pub const BAD_NOT_READABLE: StatusCode = 0x803a_0000;
+    pub const BAD_NOT_WRITABLE: StatusCode = 0x803b_0000;
+    pub const BAD_OUT_OF_RANGE: StatusCode = 0x803c_0000;
+    pub const BAD_NOT_SUPPORTED: StatusCode = 0x803d_0000;
+    pub const BAD_MONITORING_MODE_INVALID: StatusCode = 0x8041_0000;
+    pub const BAD_MONITORED_ITEM_ID_INVALID: StatusCode = 0x8042_0000;
+    pub const BAD_MONITORED_ITEM_FILTER_INVALID: StatusCode = 0x8043_0000;
+    pub const BAD_MONITORED_ITEM_FILTER_UNSUPPORTED: StatusCode = 0x8044_0000;
+    pub const BAD_FILTER_NOT_ALLOWED: StatusCode = 0x8045_0000;
+    pub const BAD_CONTINUATION_POINT_INVALID: StatusCode = 0x804a_0000;
+    pub const BAD_NO_CONTINUATION_POINTS: StatusCode = 0x804b_0000;
+    pub const BAD_REFERENCE_TYPE_ID_INVALID: StatusCode = 0x804c_0000;
+    pub const BAD_BROWSE_DIRECTION_INVALID: StatusCode = 0x804d_0000;
+    pub const BAD_SECURITY_MODE_REJECTED: StatusCode = 0x8054_0000;
+    pub const BAD_SECURITY_POLICY_REJECTED: StatusCode = 0x8055_0000;
+    pub const BAD_TOO_MANY_SESSIONS: StatusCode = 0x8056_0000;
+    pub const BAD_BROWSE_NAME_INVALID: StatusCode = 0x8060_0000;
+    pub const BAD_VIEW_ID_UNKNOWN: StatusCode = 0x806b_0000;
+    pub const BAD_NO_MATCH: StatusCode = 0x806f_0000;
+    pub const BAD_TYPE_MISMATCH: StatusCode = 0x8074_0000;
+    pub const BAD_METHOD_INVALID: StatusCode = 0x8075_0000;
+    pub const BAD_ARGUMENTS_MISSING: StatusCode = 0x8076_0000;
+    pub const BAD_TOO_MANY_PUBLISH_REQUESTS: StatusCode = 0x8078_0000;
+    pub const BAD_NO_SUBSCRIPTION: StatusCode = 0x8079_0000;
+    pub const BAD_SEQUENCE_NUMBER_UNKNOWN: StatusCode = 0x807a_0000;
+    pub const BAD_MESSAGE_NOT_AVAILABLE: StatusCode = 0x807b_0000;
+    pub const BAD_TCP_MESSAGE_TYPE_INVALID: StatusCode = 0x807e_0000;
+    pub const BAD_TCP_MESSAGE_TOO_LARGE: StatusCode = 0x8080_0000;
+    pub const BAD_DEVICE_FAILURE: StatusCode = 0x808b_0000;
+    pub const BAD_DEADBAND_FILTER_INVALID: StatusCode = 0x808e_0000;
+    pub const BAD_INVALID_ARGUMENT: StatusCode = 0x80ab_0000;
+    pub const BAD_RESPONSE_TOO_LARGE: StatusCode = 0x80b9_0000;
+    pub const BAD_PROTOCOL_VERSION_UNSUPPORTED: StatusCode = 0x80be_0000;
+    pub const BAD_TOO_MANY_ARGUMENTS: StatusCode = 0x80e5_0000;
+
+    /// Status of a failed channel access
+    pub fn from_error(error: &crate::error::Error) -> StatusCode {
+        use crate::error::Error;
+        match error {
+            Error::InvalidChannel => BAD_NODE_ID_UNKNOWN,
+            Error::InvalidParameter => BAD_OUT_OF_RANGE,
+            Error::NotImplemented => BAD_NOT_SUPPORTED,
+            Error::AccessFailed(_) => BAD_DEVICE_FAILURE,
+            _ => BAD_INTERNAL_ERROR,
+        }
+    }
+}
+
+/// Numeric node ids of namespace 0 (OPC 10000-6, NodeIds.csv)
+pub mod id {
+    // data types
+    pub const BOOLEAN: u32 = 1;
+    pub const BYTE: u32 = 3;
+    pub const UINT16: u32 = 5;
+    pub const INT32: u32 = 6;
+    pub const UINT32: u32 = 7;
+    pub const INT64: u32 = 8;
+    pub const DOUBLE: u32 = 11;
+    pub const STRING: u32 = 12;
+    pub const DATE_TIME: u32 = 13;
+    pub const STRUCTURE: u32 = 22;
+    pub const BASE_DATA_TYPE: u32 = 24;
+    pub const NUMBER: u32 = 26;
+    pub const INTEGER: u32 = 27;
+    pub const UINTEGER: u32 = 28;
+    pub const ARGUMENT: u32 = 296;
+    pub const SERVER_STATUS_DATA_TYPE: u32 = 862;
+    pub const RANGE: u32 = 884;
+    pub const EU_INFORMATION: u32 = 887;
+
+    // reference types
+    pub const REFERENCES: u32 = 31;
+    pub const NON_HIERARCHICAL_REFERENCES: u32 = 32;
+    pub const HIERARCHICAL_REFERENCES: u32 = 33;
+    pub const HAS_CHILD: u32 = 34;
+    pub const ORGANIZES: u32 = 35;
+    pub const HAS_TYPE_DEFINITION: u32 = 40;
+    pub const AGGREGATES: u32 = 44;
+    pub const HAS_SUBTYPE: u32 = 45;
+    pub const HAS_PROPERTY: u32 = 46;
+    pub const HAS_COMPONENT: u32 = 47;
+
+    // object and variable types
+    pub const BASE_OBJECT_TYPE: u32 = 58;
+    pub const FOLDER_TYPE: u32 = 61;
+    pub const BASE_VARIABLE_TYPE: u32 = 62;
+    pub const BASE_DATA_VARIABLE_TYPE: u32 = 63;
+    pub const PROPERTY_TYPE: u32 = 68;
+    pub const DATA_ITEM_TYPE: u32 = 2365;
+    pub const ANALOG_ITEM_TYPE: u32 = 2368;
+
+    // objects and variables
+    pub const ROOT_FOLDER: u32 = 84;
+    pub const OBJECTS_FOLDER: u32 = 85;
+    pub const TYPES_FOLDER: u32 = 86;
+    pub const VIEWS_FOLDER: u32 = 87;
+    pub const OBJECT_TYPES_FOLDER: u32 = 88;
+    pub const VARIABLE_TYPES_FOLDER: u32 = 89;
+    pub const DATA_TYPES_FOLDER: u32 = 90;
+    pub const REFERENCE_TYPES_FOLDER: u32 = 91;
+    pub const SERVER: u32 = 2253;
+    pub const SERVER_SERVER_ARRAY: u32 = 2254;
+    pub const SERVER_NAMESPACE_ARRAY: u32 = 2255;
+    pub const SERVER_SERVER_STATUS: u32 = 2256;
+    pub const SERVER_SERVER_STATUS_START_TIME: u32 = 2257;
+    pub const SERVER_SERVER_STATUS_CURRENT_TIME: u32 = 2258;
+    pub const SERVER_SERVER_STATUS_STATE: u32 = 2259;
+
+    // binary encodings of structures
+    pub const ARGUMENT_ENCODING: u32 = 298;
+    pub const ANONYMOUS_IDENTITY_TOKEN_ENCODING: u32 = 321;
+    pub const SERVICE_FAULT_ENCODING: u32 = 397;
+    pub const FIND_SERVERS_REQUEST: u32 = 422;
+    pub const FIND_SERVERS_RESPONSE: u32 = 425;
+    pub const GET_ENDPOINTS_REQUEST: u32 = 428;
+    pub const GET_ENDPOINTS_RESPONSE: u32 = 431;
+    pub const OPEN_SECURE_CHANNEL_REQUEST: u32 = 446;
+    pub const OPEN_SECURE_CHANNEL_RESPONSE: u32 = 449;
+    pub const CLOSE_SECURE_CHANNEL_REQUEST: u32 = 452;
+    pub const CREATE_SESSION_REQUEST: u32 = 461;
+    pub const CREATE_SESSION_RESPONSE: u32 = 464;
+    pub const ACTIVATE_SESSION_REQUEST: u32 = 467;
+    pub const ACTIVATE_SESSION_RESPONSE: u32 = 470;
+    pub const CLOSE_SESSION_REQUEST: u32 = 473;
+    pub const CLOSE_SESSION_RESPONSE: u32 = 476;
+    pub const BROWSE_REQUEST: u32 = 527;
+    pub const BROWSE_RESPONSE: u32 = 530;
+    pub const BROWSE_NEXT_REQUEST: u32 = 533;
+    pub const BROWSE_NEXT_RESPONSE: u32 = 536;
+    pub const TRANSLATE_BROWSE_PATHS_REQUEST: u32 = 554;
+    pub const TRANSLATE_BROWSE_PATHS_RESPONSE: u32 = 557;
+    pub const READ_REQUEST: u32 = 631;
+    pub const READ_RESPONSE: u32 = 634;
+    pub const WRITE_REQUEST: u32 = 673;
+    pub const WRITE_RESPONSE: u32 = 676;
+    pub const CALL_REQUEST: u32 = 712;
+    pub const CALL_RESPONSE: u32 = 715;
+    pub const DATA_CHANGE_FILTER_ENCODING: u32 = 724;
+    pub const CREATE_MONITORED_ITEMS_REQUEST: u32 = 751;
+    pub const CREATE_MONITORED_ITEMS_RESPONSE: u32 = 754;
+    pub const MODIFY_MONITORED_ITEMS_REQUEST: u32 = 763;
+    pub const MODIFY_MONITORED_ITEMS_RESPONSE: u32 = 766;
+    pub const SET_MONITORING_MODE_REQUEST: u32 = 769;
+    pub const SET_MONITORING_MODE_RESPONSE: u32 = 772;
+    pub const DELETE_MONITORED_ITEMS_REQUEST: u32 = 781;
+    pub const DELETE_MONITORED_ITEMS_RESPONSE: u32 = 784;
+    pub const CREATE_SUBSCRIPTION_REQUEST: u32 = 787;
+    pub const CREATE_SUBSCRIPTION_RESPONSE: u32 = 790;
+    pub const MODIFY_SUBSCRIPTION_REQUEST: u32 = 793;
+    pub const MODIFY_SUBSCRIPTION_RESPONSE: u32 = 796;
+    pub const SET_PUBLISHING_MODE_REQUEST: u32 = 799;
+    pub const SET_PUBLISHING_MODE_RESPONSE: u32 = 802;
+    pub const DATA_CHANGE_NOTIFICATION_ENCODING: u32 = 811;
+    pub const PUBLISH_REQUEST: u32 = 826;
+    pub const PUBLISH_RESPONSE: u32 = 829;
+    pub const REPUBLISH_REQUEST: u32 = 832;
+    pub const REPUBLISH_RESPONSE: u32 = 835;
+    pub const DELETE_SUBSCRIPTIONS_REQUEST: u32 = 847;
+    pub const DELETE_SUBSCRIPTIONS_RESPONSE: u32 = 850;
+    pub const SERVER_STATUS_DATA_TYPE_ENCODING: u32 = 864;
+    pub const RANGE_ENCODING: u32 = 886;
+    pub const EU_INFORMATION_ENCODING: u32 = 889;
+}
+
+/// Attribute ids (OPC 10000-6, A.1)
+pub mod attribute {
+    pub const NODE_ID: u32 = 1;
+    pub const NODE_CLASS: u32 = 2;
+    pub const BROWSE_NAME: u32 = 3;
+    pub const DISPLAY_NAME: u32 = 4;
+    pub const DESCRIPTION: u32 = 5;
+    pub const WRITE_MASK: u32 = 6;
+    pub const USER_WRITE_MASK: u32 = 7;
+    pub const IS_ABSTRACT: u32 = 8;
+    pub const SYMMETRIC: u32 = 9;
+    pub const INVERSE_NAME: u32 = 10;
+    pub const EVENT_NOTIFIER: u32 = 12;
+    pub const VALUE: u32 = 13;
+    pub const DATA_TYPE: u32 = 14;
+    pub const VALUE_RANK: u32 = 15;
+    pub const ARRAY_DIMENSIONS: u32 = 16;
+    pub const ACCESS_LEVEL: u32 = 17;
+    pub const USER_ACCESS_LEVEL: u32 = 18;
+    pub const MINIMUM_SAMPLING_INTERVAL: u32 = 19;
+    pub const HISTORIZING: u32 = 20;
+    pub const EXECUTABLE: u32 = 21;
+    pub const USER_EXECUTABLE: u32 = 22;
+}
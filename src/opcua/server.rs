@@ -0,0 +1,1576 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// OPC UA server for the channels of a device (UA TCP binary protocol, OPC 10000-6).
+//
+// Only the security policy None and anonymous user tokens are supported. Each connection is
+// served by a separate thread. Sessions are bound to the connection they were created on and are
+// closed as soon as the connection is lost.
+
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::convert::TryFrom;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::address_space::{AddressSpace, NodeClass, Reference, APPLICATION_URI, PRODUCT_URI};
+use super::encoding::{
+    now, DataValue, Decoder, Encoder, ExtensionObject, Identifier, LocalizedText, NodeId,
+    QualifiedName, UaResult,
+};
+use super::id;
+use super::status::{self, StatusCode};
+use super::subscription::{
+    MonitoredItem, MonitoringMode, MonitoringParameters, Subscription, SubscriptionParameters,
+    TimestampsToReturn,
+};
+use crate::events::{self, InputEvent};
+use crate::Io;
+
+const SECURITY_POLICY_NONE: &str = "http://opcfoundation.org/UA/SecurityPolicy#None";
+const TRANSPORT_PROFILE: &str = "http://opcfoundation.org/UA-Profile/Transport/uatcp-uasc-uabinary";
+const ANONYMOUS_POLICY_ID: &str = "anonymous";
+
+/// Size of the send and receive buffers (maximum size of a chunk)
+const BUFFER_SIZE: usize = 65535;
+/// Maximum size of a request composed of several chunks
+const MAX_MESSAGE_SIZE: usize = 4 << 20;
+/// Maximum number of operations (e.g. nodes to read) per request
+const MAX_OPERATIONS: usize = 1000;
+const MAX_SESSIONS: usize = 10;
+const MAX_PUBLISH_REQUESTS: usize = 10;
+const MAX_CONTINUATION_POINTS: usize = 10;
+/// Size of the message header, secure channel id, token id and sequence header of a MSG chunk
+const MSG_OVERHEAD: usize = 24;
+/// Interval of sampling and publishing while subscriptions exist
+const TICK: Duration = Duration::from_millis(10);
+
+/// Random bytes used for nonces and authentication tokens
+fn random_bytes(count: usize) -> Vec<u8> {
+    let mut bytes = vec![];
+    while bytes.len() < count {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_usize(bytes.len());
+        bytes.extend_from_slice(&hasher.finish().to_le_bytes());
+    }
+    bytes.truncate(count);
+    bytes
+}
+
+struct RequestHeader {
+    authentication_token: NodeId,
+    request_handle: u32,
+}
+
+impl RequestHeader {
+    fn decode(d: &mut Decoder) -> UaResult<RequestHeader> {
+        let authentication_token = d.node_id()?;
+        // timestamp
+        d.i64()?;
+        let request_handle = d.u32()?;
+        // return diagnostics, audit entry id, timeout hint, additional header
+        d.u32()?;
+        d.string()?;
+        d.u32()?;
+        d.extension_object()?;
+
+        Ok(RequestHeader {
+            authentication_token,
+            request_handle,
+        })
+    }
+}
+
+fn encode_response_header(e: &mut Encoder, request_handle: u32, service_result: StatusCode) {
+    e.i64(now());
+    e.u32(request_handle);
+    e.u32(service_result);
+    // service diagnostics, string table, additional header
+    e.u8(0);
+    e.i32(-1);
+    e.extension_object(&ExtensionObject::null());
+}
+
+struct ReadValueId {
+    node_id: NodeId,
+    attribute_id: u32,
+    index_range: Option<String>,
+    data_encoding: QualifiedName,
+}
+
+impl ReadValueId {
+    fn decode(d: &mut Decoder) -> UaResult<ReadValueId> {
+        Ok(ReadValueId {
+            node_id: d.node_id()?,
+            attribute_id: d.u32()?,
+            index_range: d.string()?,
+            data_encoding: d.qualified_name()?,
+        })
+    }
+
+    /// Check the parts of the request, which are not supported
+    fn check(&self) -> UaResult<()> {
+        if self.index_range.as_ref().is_some_and(|r| !r.is_empty()) {
+            return Err(status::BAD_INDEX_RANGE_INVALID);
+        }
+        if !self.data_encoding.name.is_empty() {
+            return Err(status::BAD_DATA_ENCODING_UNSUPPORTED);
+        }
+        Ok(())
+    }
+}
+
+fn check_operations<T>(operations: &[T]) -> UaResult<()> {
+    match operations.len() {
+        0 => Err(status::BAD_NOTHING_TO_DO),
+        n if n > MAX_OPERATIONS => Err(status::BAD_TOO_MANY_OPERATIONS),
+        _ => Ok(()),
+    }
+}
+
+/// Body of a response (following the response header) and its type id
+type Response = (u32, Encoder);
+
+fn response<F: FnOnce(&mut Encoder)>(type_id: u32, f: F) -> UaResult<Response> {
+    let mut e = Encoder::new();
+    f(&mut e);
+    Ok((type_id, e))
+}
+
+/// Browse results, which were not returned yet
+struct ContinuationPoint {
+    references: Vec<Reference>,
+    result_mask: u32,
+    max: usize,
+}
+
+struct PublishRequest {
+    request_id: u32,
+    request_handle: u32,
+    results: Vec<StatusCode>,
+}
+
+struct Session {
+    id: NodeId,
+    token: NodeId,
+    activated: bool,
+    subscriptions: BTreeMap<u32, Subscription>,
+    publish_requests: VecDeque<PublishRequest>,
+    continuation_points: HashMap<u32, ContinuationPoint>,
+    next_continuation_point: u32,
+}
+
+pub struct Server {
+    io: Arc<Mutex<Io>>,
+    space: AddressSpace,
+    endpoint_url: String,
+    next_id: AtomicU32,
+}
+
+impl Server {
+    /// Create the server for an initialized `Io`
+    pub fn new(io: Arc<Mutex<Io>>, device: &str, endpoint_url: &str) -> Server {
+        let space = {
+            let mut io = io.lock().unwrap();
+            let space = AddressSpace::new(device, &io.get_channel_info());
+
+            // edges are reported immediately if supported by the channel, otherwise on the next
+            // sampling
+            let inputs = io.get_channel_info().inputs.len();
+            for i in 0..inputs {
+                if let Err(e) = events::register(&mut io, i) {
+                    debug!("No callback for input {}: {}", i, e);
+                }
+            }
+
+            space
+        };
+
+        Server {
+            io,
+            space,
+            endpoint_url: endpoint_url.to_string(),
+            next_id: AtomicU32::new(1),
+        }
+    }
+
+    pub fn space(&self) -> &AddressSpace {
+        &self.space
+    }
+
+    /// Accept clients and serve each of them in a separate thread. This blocks until the
+    /// listener fails.
+    pub fn serve(self: Arc<Self>, listener: TcpListener) -> std::io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = self.clone();
+
+            thread::Builder::new()
+                .name("opcua-client".to_string())
+                .spawn(move || server.handle_client(stream))?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_client(&self, stream: TcpStream) {
+        let peer = stream.peer_addr().ok();
+        info!("OPC UA client connected: {:?}", peer);
+
+        if let Err(e) = self.run_connection(stream) {
+            warn!("OPC UA client {:?}: {}", peer, e);
+        }
+
+        info!("OPC UA client disconnected: {:?}", peer);
+    }
+
+    fn run_connection(&self, stream: TcpStream) -> std::io::Result<()> {
+        stream.set_nodelay(true)?;
+
+        let (chunk_tx, chunks) = crossbeam_channel::bounded(4);
+        let mut reader = stream.try_clone()?;
+        thread::Builder::new()
+            .name("opcua-reader".to_string())
+            .spawn(move || loop {
+                let chunk = read_chunk(&mut reader);
+                let failed = chunk.is_err();
+                if chunk_tx.send(chunk).is_err() || failed {
+                    break;
+                }
+            })?;
+
+        let (event_tx, events) = crossbeam_channel::unbounded();
+        events::subscribe(event_tx);
+
+        let mut connection = Connection::new(self, stream);
+        let result = connection.run(&chunks, &events);
+        connection.stream.shutdown(Shutdown::Both).ok();
+        result
+    }
+
+    fn next_id(&self) -> u32 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn encode_application_description(&self, e: &mut Encoder, url: &str) {
+        e.str(APPLICATION_URI);
+        e.str(PRODUCT_URI);
+        e.localized_text(&LocalizedText::new("sysWORXX I/O OPC UA Server"));
+        // server
+        e.u32(0);
+        // gateway server, discovery profile
+        e.string(None);
+        e.string(None);
+        e.array(&[url], |e, url| e.str(url));
+    }
+
+    fn encode_endpoint(&self, e: &mut Encoder, url: &str) {
+        e.str(url);
+        self.encode_application_description(e, url);
+        // certificate
+        e.byte_string(None);
+        // security mode None
+        e.u32(1);
+        e.str(SECURITY_POLICY_NONE);
+        e.array(&[ANONYMOUS_POLICY_ID], |e, policy_id| {
+            e.str(policy_id);
+            // anonymous
+            e.u32(0);
+            e.string(None);
+            e.string(None);
+            e.string(None);
+        });
+        e.str(TRANSPORT_PROFILE);
+        // security level
+        e.u8(0);
+    }
+
+    /// URL of the endpoint returned to clients. The URL used by the client is preferred, since the
+    /// configured one may contain the unspecified address.
+    fn url<'a>(&'a self, requested: &'a Option<String>) -> &'a str {
+        match requested {
+            Some(url) if url.starts_with("opc.tcp://") => url,
+            _ => &self.endpoint_url,
+        }
+    }
+
+    fn get_endpoints(&self, d: &mut Decoder) -> UaResult<Response> {
+        let url = d.string()?;
+        // locale ids
+        d.array(|d| d.string())?;
+        let profiles = d.array(|d| d.string())?;
+
+        let supported =
+            profiles.is_empty() || profiles.iter().flatten().any(|p| p == TRANSPORT_PROFILE);
+        let url = self.url(&url);
+
+        response(id::GET_ENDPOINTS_RESPONSE, |e| {
+            let endpoints = if supported { vec![url] } else { vec![] };
+            e.array(&endpoints, |e, url| self.encode_endpoint(e, url));
+        })
+    }
+
+    fn find_servers(&self, d: &mut Decoder) -> UaResult<Response> {
+        let url = d.string()?;
+        // locale ids
+        d.array(|d| d.string())?;
+        let server_uris = d.array(|d| d.string())?;
+
+        let found = server_uris.is_empty()
+            || server_uris
+                .iter()
+                .flatten()
+                .any(|uri| uri == APPLICATION_URI);
+        let url = self.url(&url);
+
+        response(id::FIND_SERVERS_RESPONSE, |e| {
+            let servers = if found { vec![url] } else { vec![] };
+            e.array(&servers, |e, url| {
+                self.encode_application_description(e, url)
+            });
+        })
+    }
+
+    fn activate_session(&self, session: &mut Session, d: &mut Decoder) -> UaResult<Response> {
+        // client signature
+        d.string()?;
+        d.byte_string()?;
+        // client software certificates
+        d.array(|d| {
+            d.byte_string()?;
+            d.byte_string()
+        })?;
+        // locale ids
+        d.array(|d| d.string())?;
+        let token = d.extension_object()?;
+
+        match token.type_id.ns0() {
+            Some(0) | Some(id::ANONYMOUS_IDENTITY_TOKEN_ENCODING) => {}
+            _ => return Err(status::BAD_IDENTITY_TOKEN_INVALID),
+        }
+        session.activated = true;
+
+        response(id::ACTIVATE_SESSION_RESPONSE, |e| {
+            e.byte_string(Some(&random_bytes(32)));
+            // results, diagnostic infos
+            e.i32(0);
+            e.i32(0);
+        })
+    }
+
+    /// Reference type of a browse or translate request (`None` selects all references)
+    fn reference_type(&self, node_id: &NodeId) -> UaResult<Option<u32>> {
+        if node_id.is_null() {
+            return Ok(None);
+        }
+        match self.space.find(node_id) {
+            Some(node) if self.space.node(node).class() == NodeClass::ReferenceType => {
+                Ok(node_id.ns0())
+            }
+            _ => Err(status::BAD_REFERENCE_TYPE_ID_INVALID),
+        }
+    }
+
+    fn matches_reference_type(
+        &self,
+        reference: &Reference,
+        type_id: Option<u32>,
+        include_subtypes: bool,
+    ) -> bool {
+        match type_id {
+            None => true,
+            Some(type_id) if include_subtypes => self.space.is_subtype(reference.type_id, type_id),
+            Some(type_id) => reference.type_id == type_id,
+        }
+    }
+
+    /// References of a node matching a browse description
+    fn browse_node(&self, d: &mut Decoder) -> UaResult<UaResult<(Vec<Reference>, u32)>> {
+        let node_id = d.node_id()?;
+        let direction = d.u32()?;
+        let reference_type = d.node_id()?;
+        let include_subtypes = d.bool()?;
+        let node_class_mask = d.u32()?;
+        let result_mask = d.u32()?;
+
+        let browse = || {
+            let node = self
+                .space
+                .find(&node_id)
+                .ok_or(status::BAD_NODE_ID_UNKNOWN)?;
+            let reference_type = self.reference_type(&reference_type)?;
+            if direction > 2 {
+                return Err(status::BAD_BROWSE_DIRECTION_INVALID);
+            }
+
+            let references = self
+                .space
+                .node(node)
+                .references
+                .iter()
+                .filter(|r| match direction {
+                    0 => r.forward,
+                    1 => !r.forward,
+                    _ => true,
+                })
+                .filter(|r| self.matches_reference_type(r, reference_type, include_subtypes))
+                .filter(|r| {
+                    node_class_mask == 0
+                        || node_class_mask & self.space.node(r.target).class() as u32 != 0
+                })
+                .cloned()
+                .collect();
+
+            Ok((references, result_mask))
+        };
+
+        Ok(browse())
+    }
+
+    fn encode_reference(&self, e: &mut Encoder, reference: &Reference, result_mask: u32) {
+        let target = self.space.node(reference.target);
+
+        if result_mask & 0x01 != 0 {
+            e.node_id(&NodeId::numeric(0, reference.type_id));
+        } else {
+            e.node_id(&NodeId::null());
+        }
+        e.bool(result_mask & 0x02 != 0 && reference.forward);
+        e.expanded_node_id(&target.id);
+        if result_mask & 0x08 != 0 {
+            e.qualified_name(&target.browse_name);
+        } else {
+            e.qualified_name(&QualifiedName::default());
+        }
+        if result_mask & 0x10 != 0 {
+            e.localized_text(&target.display_name);
+        } else {
+            e.localized_text(&LocalizedText::default());
+        }
+        e.u32(if result_mask & 0x04 != 0 {
+            target.class() as u32
+        } else {
+            0
+        });
+        match self.space.type_definition(reference.target) {
+            Some(type_definition) if result_mask & 0x20 != 0 => e.expanded_node_id(type_definition),
+            _ => e.expanded_node_id(&NodeId::null()),
+        }
+    }
+
+    /// Encode a browse result. References exceeding the maximum are kept in a continuation
+    /// point of the session.
+    fn encode_browse_result(
+        &self,
+        e: &mut Encoder,
+        session: &mut Session,
+        result: UaResult<(Vec<Reference>, u32)>,
+        max: usize,
+    ) {
+        let (mut references, result_mask) = match result {
+            Ok(result) => result,
+            Err(status) => {
+                e.u32(status);
+                e.byte_string(None);
+                e.i32(0);
+                return;
+            }
+        };
+
+        let mut continuation_point = None;
+        if max > 0 && references.len() > max {
+            if session.continuation_points.len() >= MAX_CONTINUATION_POINTS {
+                e.u32(status::BAD_NO_CONTINUATION_POINTS);
+                e.byte_string(None);
+                e.i32(0);
+                return;
+            }
+
+            let id = session.next_continuation_point;
+            session.next_continuation_point = id.wrapping_add(1);
+            session.continuation_points.insert(
+                id,
+                ContinuationPoint {
+                    references: references.split_off(max),
+                    result_mask,
+                    max,
+                },
+            );
+            continuation_point = Some(id.to_le_bytes());
+        }
+
+        e.u32(status::GOOD);
+        e.byte_string(continuation_point.as_ref().map(|c| &c[..]));
+        e.array(&references, |e, r| self.encode_reference(e, r, result_mask));
+    }
+
+    fn browse(&self, session: &mut Session, d: &mut Decoder) -> UaResult<Response> {
+        // view description
+        let view = d.node_id()?;
+        d.i64()?;
+        d.u32()?;
+        let max = d.u32()? as usize;
+        let results = d.array(|d| self.browse_node(d))?;
+
+        if !view.is_null() {
+            return Err(status::BAD_VIEW_ID_UNKNOWN);
+        }
+        check_operations(&results)?;
+
+        response(id::BROWSE_RESPONSE, |e| {
+            let mut results = results.into_iter();
+            e.i32(results.len() as i32);
+            for result in &mut results {
+                self.encode_browse_result(e, session, result, max);
+            }
+            e.i32(0);
+        })
+    }
+
+    fn browse_next(&self, session: &mut Session, d: &mut Decoder) -> UaResult<Response> {
+        let release = d.bool()?;
+        let continuation_points = d.array(|d| d.byte_string())?;
+        check_operations(&continuation_points)?;
+
+        response(id::BROWSE_NEXT_RESPONSE, |e| {
+            e.i32(continuation_points.len() as i32);
+            for continuation_point in &continuation_points {
+                let point = continuation_point
+                    .as_ref()
+                    .and_then(|c| <[u8; 4]>::try_from(c.as_slice()).ok())
+                    .and_then(|c| session.continuation_points.remove(&u32::from_le_bytes(c)));
+                let (result, max) = match point {
+                    Some(_) if release => (Ok((vec![], 0)), 0),
+                    Some(point) => (Ok((point.references, point.result_mask)), point.max),
+                    None => (Err(status::BAD_CONTINUATION_POINT_INVALID), 0),
+                };
+                self.encode_browse_result(e, session, result, max);
+            }
+            e.i32(0);
+        })
+    }
+
+    /// Follow a relative path from a starting node
+    fn translate_path(&self, d: &mut Decoder) -> UaResult<UaResult<Vec<usize>>> {
+        let start = d.node_id()?;
+        let elements = d.array(|d| {
+            let reference_type = d.node_id()?;
+            let inverse = d.bool()?;
+            let include_subtypes = d.bool()?;
+            let target_name = d.qualified_name()?;
+            Ok((reference_type, inverse, include_subtypes, target_name))
+        })?;
+
+        let translate = || {
+            let start = self.space.find(&start).ok_or(status::BAD_NODE_ID_UNKNOWN)?;
+            if elements.is_empty() {
+                return Err(status::BAD_NOTHING_TO_DO);
+            }
+
+            let mut nodes = vec![start];
+            for (reference_type, inverse, include_subtypes, target_name) in &elements {
+                if target_name.name.is_empty() {
+                    return Err(status::BAD_BROWSE_NAME_INVALID);
+                }
+                let reference_type = self.reference_type(reference_type)?;
+
+                let mut targets = vec![];
+                for node in &nodes {
+                    for r in &self.space.node(*node).references {
+                        let target = self.space.node(r.target);
+                        if r.forward != *inverse
+                            && self.matches_reference_type(r, reference_type, *include_subtypes)
+                            && target.browse_name == *target_name
+                            && !targets.contains(&r.target)
+                        {
+                            targets.push(r.target);
+                        }
+                    }
+                }
+                nodes = targets;
+            }
+
+            if nodes.is_empty() {
+                return Err(status::BAD_NO_MATCH);
+            }
+            Ok(nodes)
+        };
+
+        Ok(translate())
+    }
+
+    fn translate_browse_paths(&self, d: &mut Decoder) -> UaResult<Response> {
+        let results = d.array(|d| self.translate_path(d))?;
+        check_operations(&results)?;
+
+        response(id::TRANSLATE_BROWSE_PATHS_RESPONSE, |e| {
+            e.array(&results, |e, result| match result {
+                Ok(nodes) => {
+                    e.u32(status::GOOD);
+                    e.array(nodes, |e, node| {
+                        e.expanded_node_id(&self.space.node(*node).id);
+                        // remaining path index: complete
+                        e.u32(u32::MAX);
+                    });
+                }
+                Err(status) => {
+                    e.u32(*status);
+                    e.i32(0);
+                }
+            });
+            e.i32(0);
+        })
+    }
+
+    fn read(&self, d: &mut Decoder) -> UaResult<Response> {
+        // max age
+        d.f64()?;
+        let timestamps = d.u32()?;
+        let nodes = d.array(ReadValueId::decode)?;
+
+        check_operations(&nodes)?;
+        let timestamps = TimestampsToReturn::from_u32(timestamps)?;
+
+        response(id::READ_RESPONSE, |e| {
+            e.array(&nodes, |e, read| {
+                let value = match (read.check(), self.space.find(&read.node_id)) {
+                    (Err(status), _) => DataValue::bad(status),
+                    (_, None) => DataValue::bad(status::BAD_NODE_ID_UNKNOWN),
+                    (_, Some(node)) => {
+                        timestamps.apply(self.space.read(&self.io, node, read.attribute_id))
+                    }
+                };
+                e.data_value(&value);
+            });
+            e.i32(0);
+        })
+    }
+
+    fn write(&self, d: &mut Decoder) -> UaResult<Response> {
+        let nodes = d.array(|d| {
+            let node_id = d.node_id()?;
+            let attribute_id = d.u32()?;
+            let index_range = d.string()?;
+            let value = d.data_value()?;
+            Ok((node_id, attribute_id, index_range, value))
+        })?;
+        check_operations(&nodes)?;
+
+        response(id::WRITE_RESPONSE, |e| {
+            e.array(&nodes, |e, (node_id, attribute_id, index_range, value)| {
+                let result = match self.space.find(node_id) {
+                    _ if index_range.as_ref().is_some_and(|r| !r.is_empty()) => {
+                        status::BAD_INDEX_RANGE_INVALID
+                    }
+                    Some(node) => self.space.write(&self.io, node, *attribute_id, value),
+                    None => status::BAD_NODE_ID_UNKNOWN,
+                };
+                e.u32(result);
+            });
+            e.i32(0);
+        })
+    }
+
+    fn call(&self, d: &mut Decoder) -> UaResult<Response> {
+        let methods = d.array(|d| {
+            let object = d.node_id()?;
+            let method = d.node_id()?;
+            let arguments = d.array(|d| d.variant())?;
+            Ok((object, method, arguments))
+        })?;
+        check_operations(&methods)?;
+
+        response(id::CALL_RESPONSE, |e| {
+            e.array(&methods, |e, (object, method, arguments)| {
+                let (result, argument_results) =
+                    match (self.space.find(object), self.space.find(method)) {
+                        (Some(object), Some(method)) => {
+                            self.space.call(&self.io, object, method, arguments)
+                        }
+                        (None, _) => (status::BAD_NODE_ID_UNKNOWN, vec![]),
+                        (_, None) => (status::BAD_METHOD_INVALID, vec![]),
+                    };
+                e.u32(result);
+                e.array(&argument_results, |e, r| e.u32(*r));
+                // argument diagnostic infos, output arguments
+                e.i32(0);
+                e.i32(0);
+            });
+            e.i32(0);
+        })
+    }
+
+    fn create_subscription(&self, session: &mut Session, d: &mut Decoder) -> UaResult<Response> {
+        let parameters = SubscriptionParameters {
+            publishing_interval: d.f64()?,
+            lifetime_count: d.u32()?,
+            max_keep_alive_count: d.u32()?,
+            max_notifications: d.u32()?,
+        };
+        let publishing_enabled = d.bool()?;
+        // priority
+        d.u8()?;
+
+        let id = self.next_id();
+        let mut subscription = Subscription::new(id, publishing_enabled);
+        let (interval, lifetime_count, keep_alive_count) = subscription.modify(&parameters);
+        session.subscriptions.insert(id, subscription);
+
+        response(id::CREATE_SUBSCRIPTION_RESPONSE, |e| {
+            e.u32(id);
+            e.f64(interval);
+            e.u32(lifetime_count);
+            e.u32(keep_alive_count);
+        })
+    }
+
+    fn modify_subscription(&self, session: &mut Session, d: &mut Decoder) -> UaResult<Response> {
+        let id = d.u32()?;
+        let parameters = SubscriptionParameters {
+            publishing_interval: d.f64()?,
+            lifetime_count: d.u32()?,
+            max_keep_alive_count: d.u32()?,
+            max_notifications: d.u32()?,
+        };
+        // priority
+        d.u8()?;
+
+        let subscription = session
+            .subscriptions
+            .get_mut(&id)
+            .ok_or(status::BAD_SUBSCRIPTION_ID_INVALID)?;
+        let (interval, lifetime_count, keep_alive_count) = subscription.modify(&parameters);
+
+        response(id::MODIFY_SUBSCRIPTION_RESPONSE, |e| {
+            e.f64(interval);
+            e.u32(lifetime_count);
+            e.u32(keep_alive_count);
+        })
+    }
+
+    fn set_publishing_mode(&self, session: &mut Session, d: &mut Decoder) -> UaResult<Response> {
+        let enabled = d.bool()?;
+        let ids = d.array(|d| d.u32())?;
+        check_operations(&ids)?;
+
+        response(id::SET_PUBLISHING_MODE_RESPONSE, |e| {
+            e.array(&ids, |e, id| {
+                e.u32(match session.subscriptions.get_mut(id) {
+                    Some(subscription) => {
+                        subscription.set_publishing_enabled(enabled);
+                        status::GOOD
+                    }
+                    None => status::BAD_SUBSCRIPTION_ID_INVALID,
+                })
+            });
+            e.i32(0);
+        })
+    }
+
+    fn delete_subscriptions(&self, session: &mut Session, d: &mut Decoder) -> UaResult<Response> {
+        let ids = d.array(|d| d.u32())?;
+        check_operations(&ids)?;
+
+        response(id::DELETE_SUBSCRIPTIONS_RESPONSE, |e| {
+            e.array(&ids, |e, id| {
+                e.u32(match session.subscriptions.remove(id) {
+                    Some(_) => status::GOOD,
+                    None => status::BAD_SUBSCRIPTION_ID_INVALID,
+                })
+            });
+            e.i32(0);
+        })
+    }
+
+    fn create_monitored_item(
+        &self,
+        subscription: &mut Subscription,
+        timestamps: TimestampsToReturn,
+        item: &ReadValueId,
+        mode: u32,
+        parameters: &MonitoringParameters,
+    ) -> UaResult<(u32, f64, u32)> {
+        item.check()?;
+        let node = self
+            .space
+            .find(&item.node_id)
+            .ok_or(status::BAD_NODE_ID_UNKNOWN)?;
+        if self.space.read(&self.io, node, item.attribute_id).status()
+            == status::BAD_ATTRIBUTE_ID_INVALID
+        {
+            return Err(status::BAD_ATTRIBUTE_ID_INVALID);
+        }
+        let mode = MonitoringMode::from_u32(mode)?;
+
+        let (item, revised) = MonitoredItem::new(
+            node,
+            item.attribute_id,
+            mode,
+            timestamps,
+            parameters,
+            subscription.publishing_interval(),
+        )?;
+        let id = subscription.add_item(item);
+
+        Ok((id, revised.sampling_interval, revised.queue_size))
+    }
+
+    fn create_monitored_items(&self, session: &mut Session, d: &mut Decoder) -> UaResult<Response> {
+        let id = d.u32()?;
+        let timestamps = d.u32()?;
+        let items = d.array(|d| {
+            let item = ReadValueId::decode(d)?;
+            let mode = d.u32()?;
+            let parameters = MonitoringParameters::decode(d)?;
+            Ok((item, mode, parameters))
+        })?;
+
+        let subscription = session
+            .subscriptions
+            .get_mut(&id)
+            .ok_or(status::BAD_SUBSCRIPTION_ID_INVALID)?;
+        let timestamps = TimestampsToReturn::from_u32(timestamps)?;
+        check_operations(&items)?;
+
+        response(id::CREATE_MONITORED_ITEMS_RESPONSE, |e| {
+            e.array(&items, |e, (item, mode, parameters)| {
+                match self.create_monitored_item(subscription, timestamps, item, *mode, parameters)
+                {
+                    Ok((id, sampling_interval, queue_size)) => {
+                        e.u32(status::GOOD);
+                        e.u32(id);
+                        e.f64(sampling_interval);
+                        e.u32(queue_size);
+                    }
+                    Err(status) => {
+                        e.u32(status);
+                        e.u32(0);
+                        e.f64(0.0);
+                        e.u32(0);
+                    }
+                }
+                e.extension_object(&ExtensionObject::null());
+            });
+            e.i32(0);
+        })
+    }
+
+    fn modify_monitored_items(&self, session: &mut Session, d: &mut Decoder) -> UaResult<Response> {
+        let id = d.u32()?;
+        let timestamps = d.u32()?;
+        let items = d.array(|d| {
+            let id = d.u32()?;
+            let parameters = MonitoringParameters::decode(d)?;
+            Ok((id, parameters))
+        })?;
+
+        let subscription = session
+            .subscriptions
+            .get_mut(&id)
+            .ok_or(status::BAD_SUBSCRIPTION_ID_INVALID)?;
+        let timestamps = TimestampsToReturn::from_u32(timestamps)?;
+        check_operations(&items)?;
+        let publishing_interval = subscription.publishing_interval();
+
+        response(id::MODIFY_MONITORED_ITEMS_RESPONSE, |e| {
+            e.array(&items, |e, (id, parameters)| {
+                let revised = subscription
+                    .item_mut(*id)
+                    .ok_or(status::BAD_MONITORED_ITEM_ID_INVALID)
+                    .and_then(|item| item.modify(timestamps, parameters, publishing_interval));
+                match revised {
+                    Ok(revised) => {
+                        e.u32(status::GOOD);
+                        e.f64(revised.sampling_interval);
+                        e.u32(revised.queue_size);
+                    }
+                    Err(status) => {
+                        e.u32(status);
+                        e.f64(0.0);
+                        e.u32(0);
+                    }
+                }
+                e.extension_object(&ExtensionObject::null());
+            });
+            e.i32(0);
+        })
+    }
+
+    fn set_monitoring_mode(&self, session: &mut Session, d: &mut Decoder) -> UaResult<Response> {
+        let id = d.u32()?;
+        let mode = d.u32()?;
+        let ids = d.array(|d| d.u32())?;
+
+        let subscription = session
+            .subscriptions
+            .get_mut(&id)
+            .ok_or(status::BAD_SUBSCRIPTION_ID_INVALID)?;
+        let mode = MonitoringMode::from_u32(mode)?;
+        check_operations(&ids)?;
+
+        response(id::SET_MONITORING_MODE_RESPONSE, |e| {
+            e.array(&ids, |e, id| {
+                e.u32(match subscription.item_mut(*id) {
+                    Some(item) => {
+                        item.set_mode(mode);
+                        status::GOOD
+                    }
+                    None => status::BAD_MONITORED_ITEM_ID_INVALID,
+                })
+            });
+            e.i32(0);
+        })
+    }
+
+    fn delete_monitored_items(&self, session: &mut Session, d: &mut Decoder) -> UaResult<Response> {
+        let id = d.u32()?;
+        let ids = d.array(|d| d.u32())?;
+
+        let subscription = session
+            .subscriptions
+            .get_mut(&id)
+            .ok_or(status::BAD_SUBSCRIPTION_ID_INVALID)?;
+        check_operations(&ids)?;
+
+        response(id::DELETE_MONITORED_ITEMS_RESPONSE, |e| {
+            e.array(&ids, |e, id| {
+                e.u32(if subscription.remove_item(*id) {
+                    status::GOOD
+                } else {
+                    status::BAD_MONITORED_ITEM_ID_INVALID
+                })
+            });
+            e.i32(0);
+        })
+    }
+
+    fn republish(&self, session: &mut Session, d: &mut Decoder) -> UaResult<Response> {
+        let id = d.u32()?;
+        let sequence_number = d.u32()?;
+
+        let subscription = session
+            .subscriptions
+            .get(&id)
+            .ok_or(status::BAD_SUBSCRIPTION_ID_INVALID)?;
+        let message = subscription
+            .republish(sequence_number)
+            .ok_or(status::BAD_MESSAGE_NOT_AVAILABLE)?;
+
+        response(id::REPUBLISH_RESPONSE, |e| e.bytes(message))
+    }
+}
+
+type Chunk = std::io::Result<([u8; 4], Vec<u8>)>;
+
+/// Read a chunk and return the message type (including the chunk type) and the body
+fn read_chunk(stream: &mut TcpStream) -> Chunk {
+    let mut header = [0; 8];
+    stream.read_exact(&mut header)?;
+
+    let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    if !(8..=BUFFER_SIZE).contains(&size) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "invalid message size",
+        ));
+    }
+
+    let mut body = vec![0; size - 8];
+    stream.read_exact(&mut body)?;
+
+    Ok(([header[0], header[1], header[2], header[3]], body))
+}
+
+/// State of a connection (and its secure channel)
+struct Connection<'a> {
+    server: &'a Server,
+    stream: TcpStream,
+    hello: bool,
+    closed: bool,
+    send_buffer_size: usize,
+    max_response_size: usize,
+    max_chunk_count: usize,
+    channel_id: u32,
+    token_id: u32,
+    sequence_number: u32,
+    chunks: Vec<u8>,
+    sessions: Vec<Session>,
+}
+
+impl<'a> Connection<'a> {
+    fn new(server: &'a Server, stream: TcpStream) -> Connection<'a> {
+        Connection {
+            server,
+            stream,
+            hello: false,
+            closed: false,
+            send_buffer_size: BUFFER_SIZE,
+            max_response_size: 0,
+            max_chunk_count: 0,
+            channel_id: 0,
+            token_id: 0,
+            sequence_number: 0,
+            chunks: vec![],
+            sessions: vec![],
+        }
+    }
+
+    fn run(
+        &mut self,
+        chunks: &crossbeam_channel::Receiver<Chunk>,
+        events: &crossbeam_channel::Receiver<InputEvent>,
+    ) -> std::io::Result<()> {
+        while !self.closed {
+            let subscribed = self.sessions.iter().any(|s| !s.subscriptions.is_empty());
+            let timeout = if subscribed {
+                TICK
+            } else {
+                Duration::from_secs(1)
+            };
+
+            crossbeam_channel::select! {
+                recv(chunks) -> chunk => match chunk {
+                    Ok(Ok((header, body))) => self.receive(header, &body)?,
+                    Ok(Err(e)) if e.kind() == std::io::ErrorKind::InvalidData => {
+                        self.error(status::BAD_TCP_MESSAGE_TOO_LARGE, &e.to_string())?
+                    }
+                    // connection closed by the client
+                    _ => break,
+                },
+                recv(events) -> event => {
+                    if let Ok(event) = event {
+                        self.input_changed(event);
+                    }
+                },
+                default(timeout) => {},
+            }
+
+            self.tick()?;
+        }
+
+        Ok(())
+    }
+
+    /// Send an error message and close the connection
+    fn error(&mut self, error: StatusCode, reason: &str) -> std::io::Result<()> {
+        debug!("OPC UA: closing connection: {:#010x} {}", error, reason);
+        let mut e = Encoder::new();
+        e.u32(error);
+        e.str(reason);
+        self.closed = true;
+        self.send_chunk(b"ERRF", &e.into_inner())
+    }
+
+    fn send_chunk(&mut self, header: &[u8; 4], body: &[u8]) -> std::io::Result<()> {
+        let mut chunk = Vec::with_capacity(body.len() + 8);
+        chunk.extend_from_slice(header);
+        chunk.extend_from_slice(&(body.len() as u32 + 8).to_le_bytes());
+        chunk.extend_from_slice(body);
+        self.stream.write_all(&chunk)
+    }
+
+    fn next_sequence_number(&mut self) -> u32 {
+        self.sequence_number = self.sequence_number.wrapping_add(1).max(1);
+        self.sequence_number
+    }
+
+    fn receive(&mut self, header: [u8; 4], body: &[u8]) -> std::io::Result<()> {
+        let chunk_type = header[3];
+        match &header[..3] {
+            b"HEL" if !self.hello && chunk_type == b'F' => self.hello(body),
+            _ if !self.hello => self.error(status::BAD_TCP_MESSAGE_TYPE_INVALID, "expected HEL"),
+            b"OPN" if chunk_type == b'F' => self.open(body),
+            b"MSG" => self.message(chunk_type, body),
+            b"CLO" => {
+                self.closed = true;
+                Ok(())
+            }
+            _ => self.error(status::BAD_TCP_MESSAGE_TYPE_INVALID, "unexpected message"),
+        }
+    }
+
+    fn hello(&mut self, body: &[u8]) -> std::io::Result<()> {
+        let mut d = Decoder::new(body);
+        let hello = (|| {
+            let version = d.u32()?;
+            let receive_buffer_size = d.u32()? as usize;
+            let send_buffer_size = d.u32()? as usize;
+            let max_message_size = d.u32()? as usize;
+            let max_chunk_count = d.u32()? as usize;
+            Ok((
+                version,
+                receive_buffer_size,
+                send_buffer_size,
+                max_message_size,
+                max_chunk_count,
+            ))
+        })();
+        let (_, receive_buffer_size, send_buffer_size, max_message_size, max_chunk_count) =
+            match hello {
+                Ok(hello) => hello,
+                Err(status) => return self.error(status, "invalid HEL message"),
+            };
+
+        if receive_buffer_size < 8192 || send_buffer_size < 8192 {
+            return self.error(
+                status::BAD_PROTOCOL_VERSION_UNSUPPORTED,
+                "buffer sizes too small",
+            );
+        }
+
+        self.hello = true;
+        self.send_buffer_size = receive_buffer_size.min(BUFFER_SIZE);
+        self.max_response_size = max_message_size;
+        self.max_chunk_count = max_chunk_count;
+
+        let mut e = Encoder::new();
+        e.u32(0);
+        e.u32(send_buffer_size.min(BUFFER_SIZE) as u32);
+        e.u32(self.send_buffer_size as u32);
+        e.u32(MAX_MESSAGE_SIZE as u32);
+        e.u32(0);
+        self.send_chunk(b"ACKF", &e.into_inner())
+    }
+
+    fn open(&mut self, body: &[u8]) -> std::io::Result<()> {
+        let mut d = Decoder::new(body);
+        let result = (|| {
+            let channel_id = d.u32()?;
+            let policy = d.string()?;
+            // sender certificate, receiver certificate thumbprint
+            d.byte_string()?;
+            d.byte_string()?;
+            // sequence number
+            d.u32()?;
+            let request_id = d.u32()?;
+
+            if policy.as_deref() != Some(SECURITY_POLICY_NONE) {
+                return Err(status::BAD_SECURITY_POLICY_REJECTED);
+            }
+            if d.expanded_node_id()?.ns0() != Some(id::OPEN_SECURE_CHANNEL_REQUEST) {
+                return Err(status::BAD_TCP_MESSAGE_TYPE_INVALID);
+            }
+
+            let header = RequestHeader::decode(&mut d)?;
+            // protocol version
+            d.u32()?;
+            let request_type = d.u32()?;
+            let security_mode = d.u32()?;
+            // client nonce
+            d.byte_string()?;
+            let lifetime = d.u32()?;
+
+            if security_mode != 1 {
+                return Err(status::BAD_SECURITY_MODE_REJECTED);
+            }
+            match request_type {
+                // issue
+                0 if self.channel_id == 0 => self.channel_id = self.server.next_id(),
+                // renew
+                1 if channel_id == self.channel_id && channel_id != 0 => {}
+                _ => return Err(status::BAD_SECURE_CHANNEL_ID_INVALID),
+            }
+            self.token_id += 1;
+
+            Ok((request_id, header.request_handle, lifetime))
+        })();
+
+        let (request_id, request_handle, lifetime) = match result {
+            Ok(result) => result,
+            Err(status) => return self.error(status, "failed to open secure channel"),
+        };
+
+        let mut e = Encoder::new();
+        e.u32(self.channel_id);
+        e.str(SECURITY_POLICY_NONE);
+        e.byte_string(None);
+        e.byte_string(None);
+        let sequence_number = self.next_sequence_number();
+        e.u32(sequence_number);
+        e.u32(request_id);
+        e.node_id(&NodeId::numeric(0, id::OPEN_SECURE_CHANNEL_RESPONSE));
+        encode_response_header(&mut e, request_handle, status::GOOD);
+        e.u32(0);
+        e.u32(self.channel_id);
+        e.u32(self.token_id);
+        e.i64(now());
+        e.u32(lifetime.clamp(60_000, 3_600_000));
+        e.byte_string(Some(&[]));
+
+        self.send_chunk(b"OPNF", &e.into_inner())
+    }
+
+    fn message(&mut self, chunk_type: u8, body: &[u8]) -> std::io::Result<()> {
+        let mut d = Decoder::new(body);
+        let header = (|| {
+            let channel_id = d.u32()?;
+            // token id, sequence number
+            d.u32()?;
+            d.u32()?;
+            let request_id = d.u32()?;
+            Ok((channel_id, request_id))
+        })();
+        let (channel_id, request_id) = match header {
+            Ok(header) => header,
+            Err(status) => return self.error(status, "invalid MSG header"),
+        };
+        if channel_id != self.channel_id || channel_id == 0 {
+            return self.error(status::BAD_SECURE_CHANNEL_ID_INVALID, "unknown channel");
+        }
+
+        match chunk_type {
+            b'C' if self.chunks.len() + d.remaining().len() > MAX_MESSAGE_SIZE => {
+                self.error(status::BAD_TCP_MESSAGE_TOO_LARGE, "request too large")
+            }
+            b'C' => {
+                self.chunks.extend_from_slice(d.remaining());
+                Ok(())
+            }
+            b'A' => {
+                self.chunks.clear();
+                Ok(())
+            }
+            b'F' => {
+                let mut message = std::mem::take(&mut self.chunks);
+                message.extend_from_slice(d.remaining());
+                self.request(request_id, &message)
+            }
+            _ => self.error(status::BAD_TCP_MESSAGE_TYPE_INVALID, "invalid chunk type"),
+        }
+    }
+
+    /// Send a response message split into chunks
+    fn send_message(&mut self, request_id: u32, message: &[u8]) -> std::io::Result<()> {
+        let chunks: Vec<_> = message
+            .chunks(self.send_buffer_size - MSG_OVERHEAD)
+            .collect();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let mut e = Encoder::new();
+            e.u32(self.channel_id);
+            e.u32(self.token_id);
+            let sequence_number = self.next_sequence_number();
+            e.u32(sequence_number);
+            e.u32(request_id);
+            e.bytes(chunk);
+
+            let header = if i + 1 == chunks.len() {
+                b"MSGF"
+            } else {
+                b"MSGC"
+            };
+            self.send_chunk(header, &e.into_inner())?;
+        }
+        Ok(())
+    }
+
+    fn send_response(
+        &mut self,
+        request_id: u32,
+        request_handle: u32,
+        (type_id, body): Response,
+    ) -> std::io::Result<()> {
+        let mut e = Encoder::new();
+        e.node_id(&NodeId::numeric(0, type_id));
+        encode_response_header(&mut e, request_handle, status::GOOD);
+        e.bytes(&body.into_inner());
+        let message = e.into_inner();
+
+        let chunk_count = message.len().div_ceil(self.send_buffer_size - MSG_OVERHEAD);
+        if (self.max_response_size > 0 && message.len() > self.max_response_size)
+            || (self.max_chunk_count > 0 && chunk_count > self.max_chunk_count)
+        {
+            return self.send_fault(request_id, request_handle, status::BAD_RESPONSE_TOO_LARGE);
+        }
+
+        self.send_message(request_id, &message)
+    }
+
+    fn send_fault(
+        &mut self,
+        request_id: u32,
+        request_handle: u32,
+        status: StatusCode,
+    ) -> std::io::Result<()> {
+        let mut e = Encoder::new();
+        e.node_id(&NodeId::numeric(0, id::SERVICE_FAULT_ENCODING));
+        encode_response_header(&mut e, request_handle, status);
+        self.send_message(request_id, &e.into_inner())
+    }
+
+    fn request(&mut self, request_id: u32, message: &[u8]) -> std::io::Result<()> {
+        let mut d = Decoder::new(message);
+        let header = (|| {
+            let type_id = d.expanded_node_id()?;
+            let header = RequestHeader::decode(&mut d)?;
+            Ok((type_id, header))
+        })();
+        let (type_id, header) = match header {
+            Ok(header) => header,
+            Err(status) => return self.send_fault(request_id, 0, status),
+        };
+        let type_id = type_id.ns0().unwrap_or(0);
+        trace!("OPC UA: request {} ({})", type_id, request_id);
+
+        match self.dispatch(type_id, &header, &mut d, request_id) {
+            Ok(Some(response)) => self.send_response(request_id, header.request_handle, response),
+            Ok(None) => Ok(()),
+            Err(status) => self.send_fault(request_id, header.request_handle, status),
+        }
+    }
+
+    fn session(&self, token: &NodeId) -> UaResult<usize> {
+        self.sessions
+            .iter()
+            .position(|s| s.token == *token)
+            .ok_or(status::BAD_SESSION_ID_INVALID)
+    }
+
+    /// Process a request. Returns `None` if the response is sent later.
+    fn dispatch(
+        &mut self,
+        type_id: u32,
+        header: &RequestHeader,
+        d: &mut Decoder,
+        request_id: u32,
+    ) -> UaResult<Option<Response>> {
+        let server = self.server;
+
+        // services without session
+        match type_id {
+            id::GET_ENDPOINTS_REQUEST => return server.get_endpoints(d).map(Some),
+            id::FIND_SERVERS_REQUEST => return server.find_servers(d).map(Some),
+            id::CREATE_SESSION_REQUEST => return self.create_session(d).map(Some),
+            _ => {}
+        }
+
+        let index = self.session(&header.authentication_token)?;
+        if type_id == id::CLOSE_SESSION_REQUEST {
+            // delete subscriptions
+            d.bool()?;
+            self.sessions.remove(index);
+            return response(id::CLOSE_SESSION_RESPONSE, |_| {}).map(Some);
+        }
+
+        let session = &mut self.sessions[index];
+        if type_id == id::ACTIVATE_SESSION_REQUEST {
+            return server.activate_session(session, d).map(Some);
+        }
+        if !session.activated {
+            return Err(status::BAD_SESSION_NOT_ACTIVATED);
+        }
+
+        let response = match type_id {
+            id::BROWSE_REQUEST => server.browse(session, d),
+            id::BROWSE_NEXT_REQUEST => server.browse_next(session, d),
+            id::TRANSLATE_BROWSE_PATHS_REQUEST => server.translate_browse_paths(d),
+            id::READ_REQUEST => server.read(d),
+            id::WRITE_REQUEST => server.write(d),
+            id::CALL_REQUEST => server.call(d),
+            id::CREATE_SUBSCRIPTION_REQUEST => server.create_subscription(session, d),
+            id::MODIFY_SUBSCRIPTION_REQUEST => server.modify_subscription(session, d),
+            id::SET_PUBLISHING_MODE_REQUEST => server.set_publishing_mode(session, d),
+            id::DELETE_SUBSCRIPTIONS_REQUEST => server.delete_subscriptions(session, d),
+            id::CREATE_MONITORED_ITEMS_REQUEST => server.create_monitored_items(session, d),
+            id::MODIFY_MONITORED_ITEMS_REQUEST => server.modify_monitored_items(session, d),
+            id::SET_MONITORING_MODE_REQUEST => server.set_monitoring_mode(session, d),
+            id::DELETE_MONITORED_ITEMS_REQUEST => server.delete_monitored_items(session, d),
+            id::REPUBLISH_REQUEST => server.republish(session, d),
+            id::PUBLISH_REQUEST => {
+                self.publish(index, header.request_handle, request_id, d)?;
+                return Ok(None);
+            }
+            _ => Err(status::BAD_SERVICE_UNSUPPORTED),
+        };
+
+        response.map(Some)
+    }
+
+    fn create_session(&mut self, d: &mut Decoder) -> UaResult<Response> {
+        // client description
+        d.string()?;
+        d.string()?;
+        d.localized_text()?;
+        d.u32()?;
+        d.string()?;
+        d.string()?;
+        d.array(|d| d.string())?;
+        // server uri
+        d.string()?;
+        let url = d.string()?;
+        // session name, client nonce, client certificate
+        d.string()?;
+        d.byte_string()?;
+        d.byte_string()?;
+        let timeout = d.f64()?;
+        let max_response_size = d.u32()? as usize;
+
+        if self.sessions.len() >= MAX_SESSIONS {
+            return Err(status::BAD_TOO_MANY_SESSIONS);
+        }
+        if max_response_size > 0 {
+            self.max_response_size = match self.max_response_size {
+                0 => max_response_size,
+                size => size.min(max_response_size),
+            };
+        }
+
+        let session = Session {
+            id: NodeId::numeric(1, self.server.next_id()),
+            token: NodeId {
+                namespace: 0,
+                identifier: Identifier::Opaque(random_bytes(16)),
+            },
+            activated: false,
+            subscriptions: BTreeMap::new(),
+            publish_requests: VecDeque::new(),
+            continuation_points: HashMap::new(),
+            next_continuation_point: 1,
+        };
+
+        // sessions are closed with the connection, so the timeout is not used
+        let timeout = if timeout.is_nan() {
+            3_600_000.0
+        } else {
+            timeout.clamp(10_000.0, 3_600_000.0)
+        };
+
+        let server = self.server;
+        let url = server.url(&url).to_string();
+        let result = response(id::CREATE_SESSION_RESPONSE, |e| {
+            e.node_id(&session.id);
+            e.node_id(&session.token);
+            e.f64(timeout);
+            e.byte_string(Some(&random_bytes(32)));
+            // server certificate
+            e.byte_string(None);
+            e.array(&[&url], |e, url| server.encode_endpoint(e, url));
+            // server software certificates, server signature
+            e.i32(0);
+            e.string(None);
+            e.byte_string(None);
+            e.u32(MAX_MESSAGE_SIZE as u32);
+        });
+
+        info!("OPC UA: session {} created", session.id);
+        self.sessions.push(session);
+        result
+    }
+
+    fn publish(
+        &mut self,
+        session: usize,
+        request_handle: u32,
+        request_id: u32,
+        d: &mut Decoder,
+    ) -> UaResult<()> {
+        let acknowledgements = d.array(|d| Ok((d.u32()?, d.u32()?)))?;
+
+        let session = &mut self.sessions[session];
+        if session.subscriptions.is_empty() {
+            return Err(status::BAD_NO_SUBSCRIPTION);
+        }
+
+        let results = acknowledgements
+            .iter()
+            .map(|(subscription, sequence_number)| {
+                match session.subscriptions.get_mut(subscription) {
+                    Some(subscription) => subscription.acknowledge(*sequence_number),
+                    None => status::BAD_SUBSCRIPTION_ID_INVALID,
+                }
+            })
+            .collect();
+
+        session.publish_requests.push_back(PublishRequest {
+            request_id,
+            request_handle,
+            results,
+        });
+        Ok(())
+    }
+
+    fn input_changed(&mut self, (channel, value): InputEvent) {
+        let node = match self.server.space.input_node(channel as usize) {
+            Some(node) => node,
+            None => return,
+        };
+        let timestamp = now();
+        let value = DataValue {
+            value: Some(super::encoding::Variant::Boolean(value)),
+            status: None,
+            source_timestamp: Some(timestamp),
+            server_timestamp: Some(timestamp),
+        };
+
+        for session in &mut self.sessions {
+            for subscription in session.subscriptions.values_mut() {
+                subscription.notify(node, &value);
+            }
+        }
+    }
+
+    /// Sample the monitored items and answer publish requests
+    fn tick(&mut self) -> std::io::Result<()> {
+        let time = Instant::now();
+        let server = self.server;
+        let mut responses = vec![];
+        let mut faults = vec![];
+
+        for session in &mut self.sessions {
+            let Session {
+                subscriptions,
+                publish_requests,
+                ..
+            } = session;
+
+            // requests exceeding the limit or without subscription are rejected
+            while publish_requests.len() > MAX_PUBLISH_REQUESTS
+                || (subscriptions.is_empty() && !publish_requests.is_empty())
+            {
+                let request = publish_requests.pop_front().unwrap();
+                let status = if subscriptions.is_empty() {
+                    status::BAD_NO_SUBSCRIPTION
+                } else {
+                    status::BAD_TOO_MANY_PUBLISH_REQUESTS
+                };
+                faults.push((request.request_id, request.request_handle, status));
+            }
+
+            for subscription in subscriptions.values_mut() {
+                subscription.sample(&server.space, &server.io, time);
+
+                if publish_requests.is_empty() {
+                    continue;
+                }
+                if let Some((_, message, more)) = subscription.publish(time) {
+                    let request = publish_requests.pop_front().unwrap();
+                    let available = subscription.available_sequence_numbers();
+                    let response = response(id::PUBLISH_RESPONSE, |e| {
+                        e.u32(subscription.id);
+                        e.array(&available, |e, n| e.u32(*n));
+                        e.bool(more);
+                        e.bytes(&message);
+                        e.array(&request.results, |e, r| e.u32(*r));
+                        e.i32(0);
+                    });
+                    if let Ok(response) = response {
+                        responses.push((request.request_id, request.request_handle, response));
+                    }
+                }
+            }
+        }
+
+        for (request_id, request_handle, status) in faults {
+            self.send_fault(request_id, request_handle, status)?;
+        }
+        for (request_id, request_handle, response) in responses {
+            self.send_response(request_id, request_handle, response)?;
+        }
+
+        Ok(())
+    }
+}
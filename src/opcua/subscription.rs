@@ -0,0 +1,628 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Subscriptions and monitored items (OPC 10000-4, 5.12 and 5.13).
+//
+// Monitored items are sampled by the connection thread in their sampling interval. Changes of
+// digital inputs are additionally reported immediately through the input callbacks. Queued
+// notifications are sent with the next publish request after the publishing interval elapsed.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::address_space::AddressSpace;
+use super::encoding::{now, DataValue, Decoder, Encoder, ExtensionObject, UaResult};
+use super::status::{self, StatusCode};
+use super::{attribute, id};
+use crate::Io;
+
+/// Shortest supported sampling and publishing interval
+pub const MIN_INTERVAL: Duration = Duration::from_millis(50);
+const MAX_INTERVAL: Duration = Duration::from_secs(3600);
+const MAX_QUEUE_SIZE: u32 = 100;
+/// Number of sent notification messages kept for republishing
+const RETRANSMISSION_QUEUE_SIZE: usize = 16;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MonitoringMode {
+    Disabled = 0,
+    Sampling = 1,
+    Reporting = 2,
+}
+
+impl MonitoringMode {
+    pub fn from_u32(value: u32) -> UaResult<MonitoringMode> {
+        match value {
+            0 => Ok(MonitoringMode::Disabled),
+            1 => Ok(MonitoringMode::Sampling),
+            2 => Ok(MonitoringMode::Reporting),
+            _ => Err(status::BAD_MONITORING_MODE_INVALID),
+        }
+    }
+}
+
+/// Which timestamps are sent with values
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TimestampsToReturn {
+    Source = 0,
+    Server = 1,
+    Both = 2,
+    Neither = 3,
+}
+
+impl TimestampsToReturn {
+    pub fn from_u32(value: u32) -> UaResult<TimestampsToReturn> {
+        match value {
+            0 => Ok(TimestampsToReturn::Source),
+            1 => Ok(TimestampsToReturn::Server),
+            2 => Ok(TimestampsToReturn::Both),
+            3 => Ok(TimestampsToReturn::Neither),
+            _ => Err(status::BAD_TIMESTAMPS_TO_RETURN_INVALID),
+        }
+    }
+
+    pub fn apply(self, mut value: DataValue) -> DataValue {
+        if self == TimestampsToReturn::Server || self == TimestampsToReturn::Neither {
+            value.source_timestamp = None;
+        }
+        if self == TimestampsToReturn::Source || self == TimestampsToReturn::Neither {
+            value.server_timestamp = None;
+        }
+        value
+    }
+}
+
+/// Data change filter (OPC 10000-4, 7.22.2)
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Filter {
+    /// 0 = status, 1 = status and value, 2 = status, value and source timestamp
+    trigger: u32,
+    /// Absolute deadband
+    deadband: Option<f64>,
+}
+
+impl Default for Filter {
+    fn default() -> Filter {
+        Filter {
+            trigger: 1,
+            deadband: None,
+        }
+    }
+}
+
+impl Filter {
+    fn decode(filter: &ExtensionObject) -> UaResult<Filter> {
+        let body = match (&filter.body, filter.type_id.ns0()) {
+            (None, _) => return Ok(Filter::default()),
+            (Some(body), Some(id::DATA_CHANGE_FILTER_ENCODING)) => body,
+            _ => return Err(status::BAD_MONITORED_ITEM_FILTER_UNSUPPORTED),
+        };
+
+        let mut d = Decoder::new(body);
+        let trigger = d.u32()?;
+        let deadband_type = d.u32()?;
+        let deadband_value = d.f64()?;
+
+        let deadband = match deadband_type {
+            0 => None,
+            1 if deadband_value >= 0.0 => Some(deadband_value),
+            1 => return Err(status::BAD_DEADBAND_FILTER_INVALID),
+            // percent deadbands are not supported
+            _ => return Err(status::BAD_MONITORED_ITEM_FILTER_UNSUPPORTED),
+        };
+        if trigger > 2 {
+            return Err(status::BAD_MONITORED_ITEM_FILTER_INVALID);
+        }
+
+        Ok(Filter { trigger, deadband })
+    }
+
+    /// Check whether the new value has to be reported
+    fn changed(&self, last: &DataValue, new: &DataValue) -> bool {
+        if last.status() != new.status() {
+            return true;
+        }
+        if self.trigger == 0 {
+            return false;
+        }
+        if self.trigger == 2 && last.source_timestamp != new.source_timestamp {
+            return true;
+        }
+
+        match (&last.value, &new.value, self.deadband) {
+            (Some(last), Some(new), Some(deadband)) => match (last.as_f64(), new.as_f64()) {
+                (Some(last), Some(new)) => (new - last).abs() > deadband,
+                _ => last != new,
+            },
+            (last, new, _) => last != new,
+        }
+    }
+}
+
+/// Requested parameters of a monitored item
+pub struct MonitoringParameters {
+    pub client_handle: u32,
+    pub sampling_interval: f64,
+    pub filter: ExtensionObject,
+    pub queue_size: u32,
+    pub discard_oldest: bool,
+}
+
+impl MonitoringParameters {
+    pub fn decode(d: &mut Decoder) -> UaResult<MonitoringParameters> {
+        Ok(MonitoringParameters {
+            client_handle: d.u32()?,
+            sampling_interval: d.f64()?,
+            filter: d.extension_object()?,
+            queue_size: d.u32()?,
+            discard_oldest: d.bool()?,
+        })
+    }
+}
+
+/// Revised parameters of a created or modified monitored item
+pub struct Revised {
+    pub sampling_interval: f64,
+    pub queue_size: u32,
+}
+
+pub struct MonitoredItem {
+    node: usize,
+    attribute_id: u32,
+    client_handle: u32,
+    mode: MonitoringMode,
+    timestamps: TimestampsToReturn,
+    sampling_interval: Duration,
+    queue_size: usize,
+    discard_oldest: bool,
+    filter: Filter,
+    last_sample: Option<Instant>,
+    last_value: Option<DataValue>,
+    queue: VecDeque<DataValue>,
+}
+
+impl MonitoredItem {
+    pub fn new(
+        node: usize,
+        attribute_id: u32,
+        mode: MonitoringMode,
+        timestamps: TimestampsToReturn,
+        parameters: &MonitoringParameters,
+        publishing_interval: Duration,
+    ) -> UaResult<(MonitoredItem, Revised)> {
+        let mut item = MonitoredItem {
+            node,
+            attribute_id,
+            client_handle: 0,
+            mode,
+            timestamps,
+            sampling_interval: MIN_INTERVAL,
+            queue_size: 1,
+            discard_oldest: true,
+            filter: Filter::default(),
+            last_sample: None,
+            last_value: None,
+            queue: VecDeque::new(),
+        };
+        let revised = item.modify(timestamps, parameters, publishing_interval)?;
+        Ok((item, revised))
+    }
+
+    pub fn modify(
+        &mut self,
+        timestamps: TimestampsToReturn,
+        parameters: &MonitoringParameters,
+        publishing_interval: Duration,
+    ) -> UaResult<Revised> {
+        let filter = Filter::decode(&parameters.filter)?;
+        if filter != Filter::default() && self.attribute_id != attribute::VALUE {
+            return Err(status::BAD_FILTER_NOT_ALLOWED);
+        }
+
+        // a negative interval selects the publishing interval
+        let sampling_interval = if parameters.sampling_interval < 0.0 {
+            publishing_interval
+        } else {
+            revise_interval(parameters.sampling_interval)
+        };
+
+        self.client_handle = parameters.client_handle;
+        self.timestamps = timestamps;
+        self.sampling_interval = sampling_interval;
+        self.queue_size = parameters.queue_size.clamp(1, MAX_QUEUE_SIZE) as usize;
+        self.discard_oldest = parameters.discard_oldest;
+        self.filter = filter;
+        while self.queue.len() > self.queue_size {
+            self.queue.pop_front();
+        }
+
+        Ok(Revised {
+            sampling_interval: sampling_interval.as_secs_f64() * 1000.0,
+            queue_size: self.queue_size as u32,
+        })
+    }
+
+    pub fn set_mode(&mut self, mode: MonitoringMode) {
+        if mode == MonitoringMode::Disabled {
+            self.queue.clear();
+            self.last_value = None;
+        }
+        if self.mode == MonitoringMode::Disabled {
+            // the current value is reported again after enabling
+            self.last_sample = None;
+        }
+        self.mode = mode;
+    }
+
+    fn push(&mut self, value: DataValue) {
+        if self.queue.len() >= self.queue_size {
+            if self.discard_oldest {
+                self.queue.pop_front();
+            } else {
+                self.queue.pop_back();
+            }
+        }
+        self.queue.push_back(value);
+    }
+
+    /// Report a new value if it passes the filter
+    fn update(&mut self, value: DataValue) {
+        let changed = match &self.last_value {
+            Some(last) => self.filter.changed(last, &value),
+            None => true,
+        };
+        if changed {
+            self.last_value = Some(value.clone());
+            self.push(self.timestamps.apply(value));
+        }
+    }
+
+    /// Sample the value if the sampling interval elapsed
+    fn sample(&mut self, space: &AddressSpace, io: &Mutex<Io>, time: Instant) {
+        if self.mode == MonitoringMode::Disabled {
+            return;
+        }
+        if let Some(last) = self.last_sample {
+            if time.duration_since(last) < self.sampling_interval {
+                return;
+            }
+        }
+        self.last_sample = Some(time);
+        self.update(space.read(io, self.node, self.attribute_id));
+    }
+}
+
+/// Clamp a requested interval in ms to the supported range
+fn revise_interval(interval: f64) -> Duration {
+    if interval.is_nan() {
+        return MIN_INTERVAL;
+    }
+    Duration::from_secs_f64(interval.max(0.0) / 1000.0).clamp(MIN_INTERVAL, MAX_INTERVAL)
+}
+
+/// Requested parameters of a subscription
+pub struct SubscriptionParameters {
+    pub publishing_interval: f64,
+    pub lifetime_count: u32,
+    pub max_keep_alive_count: u32,
+    pub max_notifications: u32,
+}
+
+pub struct Subscription {
+    pub id: u32,
+    publishing_interval: Duration,
+    lifetime_count: u32,
+    max_keep_alive_count: u32,
+    max_notifications: u32,
+    publishing_enabled: bool,
+    items: BTreeMap<u32, MonitoredItem>,
+    next_item_id: u32,
+    sequence_number: u32,
+    last_publish: Instant,
+    keep_alive_counter: u32,
+    /// The first publish response is a keep alive message if there are no notifications
+    first_publish: bool,
+    sent: VecDeque<(u32, Vec<u8>)>,
+}
+
+impl Subscription {
+    /// Create a subscription, the parameters are applied with `modify`
+    pub fn new(id: u32, publishing_enabled: bool) -> Subscription {
+        Subscription {
+            id,
+            publishing_interval: MIN_INTERVAL,
+            lifetime_count: 3,
+            max_keep_alive_count: 1,
+            max_notifications: 0,
+            publishing_enabled,
+            items: BTreeMap::new(),
+            next_item_id: 1,
+            sequence_number: 1,
+            last_publish: Instant::now(),
+            keep_alive_counter: 0,
+            first_publish: true,
+            sent: VecDeque::new(),
+        }
+    }
+
+    /// Apply the parameters and return the revised publishing interval (ms), lifetime count and
+    /// keep alive count
+    pub fn modify(&mut self, parameters: &SubscriptionParameters) -> (f64, u32, u32) {
+        self.publishing_interval = revise_interval(parameters.publishing_interval);
+        self.max_keep_alive_count = parameters.max_keep_alive_count.clamp(1, 1000);
+        // the lifetime has to be at least three times the keep alive interval
+        self.lifetime_count = parameters.lifetime_count.max(self.max_keep_alive_count * 3);
+        self.max_notifications = parameters.max_notifications;
+
+        (
+            self.publishing_interval.as_secs_f64() * 1000.0,
+            self.lifetime_count,
+            self.max_keep_alive_count,
+        )
+    }
+
+    pub fn publishing_interval(&self) -> Duration {
+        self.publishing_interval
+    }
+
+    pub fn set_publishing_enabled(&mut self, enabled: bool) {
+        self.publishing_enabled = enabled;
+    }
+
+    pub fn add_item(&mut self, item: MonitoredItem) -> u32 {
+        let id = self.next_item_id;
+        self.next_item_id += 1;
+        self.items.insert(id, item);
+        id
+    }
+
+    pub fn item_mut(&mut self, id: u32) -> Option<&mut MonitoredItem> {
+        self.items.get_mut(&id)
+    }
+
+    pub fn remove_item(&mut self, id: u32) -> bool {
+        self.items.remove(&id).is_some()
+    }
+
+    /// Sample all monitored items
+    pub fn sample(&mut self, space: &AddressSpace, io: &Mutex<Io>, time: Instant) {
+        for item in self.items.values_mut() {
+            item.sample(space, io, time);
+        }
+    }
+
+    /// Report a value of a node immediately (e.g. on an input edge)
+    pub fn notify(&mut self, node: usize, value: &DataValue) {
+        for item in self.items.values_mut() {
+            if item.node == node
+                && item.attribute_id == attribute::VALUE
+                && item.mode != MonitoringMode::Disabled
+            {
+                item.update(value.clone());
+            }
+        }
+    }
+
+    /// Encode the notification message of a publishing cycle. Returns `None` if the publishing
+    /// interval did not elapse or there is nothing to send.
+    pub fn publish(&mut self, time: Instant) -> Option<(u32, Vec<u8>, bool)> {
+        if time.duration_since(self.last_publish) < self.publishing_interval {
+            return None;
+        }
+        self.last_publish = time;
+
+        let mut notifications = vec![];
+        let mut more = false;
+        if self.publishing_enabled {
+            let max = match self.max_notifications {
+                0 => usize::MAX,
+                max => max as usize,
+            };
+            for item in self.items.values_mut() {
+                if item.mode != MonitoringMode::Reporting {
+                    continue;
+                }
+                while let Some(value) = item.queue.pop_front() {
+                    if notifications.len() >= max {
+                        item.queue.push_front(value);
+                        more = true;
+                        break;
+                    }
+                    notifications.push((item.client_handle, value));
+                }
+            }
+        }
+
+        if notifications.is_empty() {
+            self.keep_alive_counter += 1;
+            if !self.first_publish && self.keep_alive_counter < self.max_keep_alive_count {
+                return None;
+            }
+            self.first_publish = false;
+            self.keep_alive_counter = 0;
+            // keep alive messages contain the next sequence number, which is not consumed
+            let message = encode_notification_message(self.sequence_number, &[]);
+            return Some((self.sequence_number, message, false));
+        }
+
+        self.first_publish = false;
+        self.keep_alive_counter = 0;
+        let sequence_number = self.sequence_number;
+        self.sequence_number = self.sequence_number.wrapping_add(1).max(1);
+
+        let message = encode_notification_message(sequence_number, &notifications);
+        if self.sent.len() >= RETRANSMISSION_QUEUE_SIZE {
+            self.sent.pop_front();
+        }
+        self.sent.push_back((sequence_number, message.clone()));
+
+        Some((sequence_number, message, more))
+    }
+
+    /// Remove an acknowledged notification message from the retransmission queue
+    pub fn acknowledge(&mut self, sequence_number: u32) -> StatusCode {
+        match self.sent.iter().position(|(n, _)| *n == sequence_number) {
+            Some(index) => {
+                self.sent.remove(index);
+                status::GOOD
+            }
+            None => status::BAD_SEQUENCE_NUMBER_UNKNOWN,
+        }
+    }
+
+    pub fn available_sequence_numbers(&self) -> Vec<u32> {
+        self.sent.iter().map(|(n, _)| *n).collect()
+    }
+
+    pub fn republish(&self, sequence_number: u32) -> Option<&[u8]> {
+        self.sent
+            .iter()
+            .find(|(n, _)| *n == sequence_number)
+            .map(|(_, message)| message.as_slice())
+    }
+}
+
+/// Encode a NotificationMessage with a DataChangeNotification
+fn encode_notification_message(
+    sequence_number: u32,
+    notifications: &[(u32, DataValue)],
+) -> Vec<u8> {
+    let mut e = Encoder::new();
+    e.u32(sequence_number);
+    e.i64(now());
+
+    if notifications.is_empty() {
+        e.i32(0);
+    } else {
+        let data = ExtensionObject::encode(id::DATA_CHANGE_NOTIFICATION_ENCODING, |e| {
+            e.array(notifications, |e, (client_handle, value)| {
+                e.u32(*client_handle);
+                e.data_value(value);
+            });
+            e.no_diagnostics();
+        });
+        e.array(&[data], |e, data| e.extension_object(data));
+    }
+
+    e.into_inner()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opcua::encoding::Variant;
+
+    fn parameters(queue_size: u32, filter: ExtensionObject) -> MonitoringParameters {
+        MonitoringParameters {
+            client_handle: 7,
+            sampling_interval: 0.0,
+            filter,
+            queue_size,
+            discard_oldest: true,
+        }
+    }
+
+    #[test]
+    fn deadband_test() {
+        let filter = ExtensionObject::encode(id::DATA_CHANGE_FILTER_ENCODING, |e| {
+            e.u32(1);
+            e.u32(1);
+            e.f64(10.0);
+        });
+        let (mut item, revised) = MonitoredItem::new(
+            0,
+            attribute::VALUE,
+            MonitoringMode::Reporting,
+            TimestampsToReturn::Neither,
+            &parameters(5, filter),
+            MIN_INTERVAL,
+        )
+        .unwrap();
+        assert_eq!(revised.sampling_interval, 50.0);
+        assert_eq!(revised.queue_size, 5);
+
+        for value in &[100, 105, 111, 103, 125] {
+            item.update(DataValue::new(Variant::Int64(*value)));
+        }
+        let values: Vec<_> = item
+            .queue
+            .iter()
+            .map(|v| v.value.clone().unwrap())
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                Variant::Int64(100),
+                Variant::Int64(111),
+                Variant::Int64(125)
+            ]
+        );
+
+        let percent = ExtensionObject::encode(id::DATA_CHANGE_FILTER_ENCODING, |e| {
+            e.u32(1);
+            e.u32(2);
+            e.f64(10.0);
+        });
+        assert_eq!(
+            item.modify(
+                TimestampsToReturn::Both,
+                &parameters(1, percent),
+                MIN_INTERVAL
+            )
+            .err(),
+            Some(status::BAD_MONITORED_ITEM_FILTER_UNSUPPORTED)
+        );
+    }
+
+    #[test]
+    fn publish_test() {
+        let parameters = SubscriptionParameters {
+            publishing_interval: 0.0,
+            lifetime_count: 0,
+            max_keep_alive_count: 2,
+            max_notifications: 0,
+        };
+        let mut subscription = Subscription::new(1, true);
+        subscription.modify(&parameters);
+        let (item, _) = MonitoredItem::new(
+            3,
+            attribute::VALUE,
+            MonitoringMode::Reporting,
+            TimestampsToReturn::Both,
+            &self::tests::parameters(1, ExtensionObject::null()),
+            MIN_INTERVAL,
+        )
+        .unwrap();
+        subscription.add_item(item);
+
+        let mut time = Instant::now() + MIN_INTERVAL;
+        // first response is a keep alive
+        let (sequence_number, message, _) = subscription.publish(time).unwrap();
+        assert_eq!(sequence_number, 1);
+        assert_eq!(&message[12..], &[0, 0, 0, 0]);
+        assert!(subscription.available_sequence_numbers().is_empty());
+
+        subscription.notify(3, &DataValue::new(Variant::Boolean(true)));
+        subscription.notify(4, &DataValue::new(Variant::Boolean(true)));
+        assert!(subscription.publish(time).is_none());
+        time += MIN_INTERVAL;
+        let (sequence_number, _, more) = subscription.publish(time).unwrap();
+        assert_eq!(sequence_number, 1);
+        assert!(!more);
+        assert_eq!(subscription.available_sequence_numbers(), vec![1]);
+        assert!(subscription.republish(1).is_some());
+
+        // keep alive after the maximum keep alive count
+        time += MIN_INTERVAL;
+        assert!(subscription.publish(time).is_none());
+        time += MIN_INTERVAL;
+        assert_eq!(subscription.publish(time).unwrap().0, 2);
+
+        assert_eq!(subscription.acknowledge(1), status::GOOD);
+        assert_eq!(
+            subscription.acknowledge(1),
+            status::BAD_SEQUENCE_NUMBER_UNKNOWN
+        );
+    }
+}
@@ -4,6 +4,8 @@
 use std::thread;
 use std::time::{Duration, Instant};
 
+use crate::health;
+
 pub struct Periodic {
     interval: Duration,
     last: Option<Instant>,
@@ -36,6 +38,7 @@ impl Periodic {
     fn calc_delay(&mut self, now: Instant, last: Instant) -> Duration {
         let mut diff = now - last;
         let mut last = last;
+        let mut missed = 0;
 
         while diff > self.interval {
             error!(
@@ -45,6 +48,10 @@ impl Periodic {
             );
             last += self.interval;
             diff = now - last;
+            missed += 1;
+        }
+        if missed > 0 {
+            health::sampler_missed_intervals(missed);
         }
 
         self.last = Some(last + self.interval);
@@ -0,0 +1,188 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Relay autotune (Åström-Hägglund). The output is switched between two levels around the
+// setpoint, so that the process oscillates. The ultimate gain and period are calculated from the
+// amplitude and period of the oscillation and the gains are set by the Ziegler-Nichols rules.
+
+use std::f64::consts::PI;
+use std::time::{Duration, Instant};
+
+use super::Gains;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AutotuneConfig {
+    /// Output while the process value is above the setpoint
+    pub low: f64,
+    /// Output while the process value is below the setpoint
+    pub high: f64,
+    /// Hysteresis of the relay around the setpoint (noise band of the process value)
+    pub hysteresis: f64,
+    /// Number of oscillations to measure, the first oscillation is not measured
+    pub cycles: usize,
+    /// Maximum duration of the autotune
+    pub timeout: Duration,
+}
+
+impl Default for AutotuneConfig {
+    fn default() -> AutotuneConfig {
+        AutotuneConfig {
+            low: 0.0,
+            high: 100.0,
+            hysteresis: 0.5,
+            cycles: 3,
+            timeout: Duration::from_secs(3600),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AutotuneResult {
+    /// The autotune is running, the output has to be set to the value
+    Running(f64),
+    Done(Gains),
+    /// The process did not oscillate within the timeout
+    Failed,
+}
+
+#[derive(Debug, Clone)]
+pub struct Autotune {
+    config: AutotuneConfig,
+    setpoint: f64,
+    start: Option<Instant>,
+    high: bool,
+    /// Time of the last switch to the high output
+    period_start: Option<Instant>,
+    max: f64,
+    min: f64,
+    /// Measured periods and amplitudes (peak to peak)
+    periods: Vec<(Duration, f64)>,
+}
+
+impl Autotune {
+    pub fn new(config: AutotuneConfig, setpoint: f64) -> Autotune {
+        Autotune {
+            config,
+            setpoint,
+            start: None,
+            high: true,
+            period_start: None,
+            max: f64::MIN,
+            min: f64::MAX,
+            periods: vec![],
+        }
+    }
+
+    pub fn step(&mut self, pv: f64, now: Instant) -> AutotuneResult {
+        let start = *self.start.get_or_insert(now);
+        if now.saturating_duration_since(start) > self.config.timeout {
+            return AutotuneResult::Failed;
+        }
+
+        self.max = self.max.max(pv);
+        self.min = self.min.min(pv);
+
+        if self.high && pv > self.setpoint + self.config.hysteresis {
+            self.high = false;
+        } else if !self.high && pv < self.setpoint - self.config.hysteresis {
+            self.high = true;
+            if let Some(period_start) = self.period_start {
+                self.periods.push((
+                    now.saturating_duration_since(period_start),
+                    self.max - self.min,
+                ));
+            }
+            self.period_start = Some(now);
+            self.max = pv;
+            self.min = pv;
+
+            // the first period is skipped, it starts from an arbitrary state
+            if self.periods.len() > self.config.cycles {
+                return self.result();
+            }
+        }
+
+        AutotuneResult::Running(if self.high {
+            self.config.high
+        } else {
+            self.config.low
+        })
+    }
+
+    fn result(&self) -> AutotuneResult {
+        let measured = &self.periods[1..];
+        let count = measured.len() as f64;
+        let period = measured
+            .iter()
+            .map(|(period, _)| period.as_secs_f64())
+            .sum::<f64>()
+            / count;
+        let amplitude = measured.iter().map(|(_, peak)| peak / 2.0).sum::<f64>() / count;
+        let hysteresis = self.config.hysteresis;
+        if period <= 0.0 || amplitude <= hysteresis {
+            return AutotuneResult::Failed;
+        }
+
+        let relay = (self.config.high - self.config.low).abs() / 2.0;
+        let ku = 4.0 * relay / (PI * (amplitude * amplitude - hysteresis * hysteresis).sqrt());
+        let kp = 0.6 * ku;
+        AutotuneResult::Done(Gains {
+            kp,
+            ki: kp / (period / 2.0),
+            kd: kp * period / 8.0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autotune_test() {
+        // first order process with dead time: T = 20 s, L = 2 s, gain 1
+        let config = AutotuneConfig {
+            hysteresis: 0.1,
+            ..AutotuneConfig::default()
+        };
+        let mut autotune = Autotune::new(config, 50.0);
+        let start = Instant::now();
+        let dt = 0.1;
+        let mut pv = 20.0;
+        let mut delayed = vec![0.0; 20];
+
+        let mut result = AutotuneResult::Failed;
+        for i in 0..100_000 {
+            let now = start + Duration::from_secs_f64(i as f64 * dt);
+            result = autotune.step(pv, now);
+            match result {
+                AutotuneResult::Running(output) => {
+                    delayed.push(output);
+                    pv += (delayed.remove(0) - pv) * dt / 20.0;
+                }
+                _ => break,
+            }
+        }
+
+        // ultimate gain and period of the process are about 16 and 7.6 s
+        let gains = match result {
+            AutotuneResult::Done(gains) => gains,
+            result => panic!("{:?}", result),
+        };
+        assert!(gains.kp > 7.0 && gains.kp < 12.0, "{:?}", gains);
+        assert!(gains.ki > 1.5 && gains.ki < 3.5, "{:?}", gains);
+
+        let mut autotune = Autotune::new(
+            AutotuneConfig {
+                timeout: Duration::from_secs(10),
+                ..config
+            },
+            50.0,
+        );
+        assert_eq!(autotune.step(20.0, start), AutotuneResult::Running(100.0));
+        assert_eq!(
+            autotune.step(20.0, start + Duration::from_secs(11)),
+            AutotuneResult::Failed
+        );
+    }
+}
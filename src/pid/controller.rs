@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// PID algorithm in parallel form. The derivative acts on the process value, so setpoint changes
+// do not cause output spikes. The integral term is limited to the output range and is frozen
+// while the output saturates (anti-windup). When switching from manual to automatic mode, the
+// integral term is initialized from the manual output, so the transfer is bumpless.
+
+use std::time::Instant;
+
+use crate::blocks::Ramp;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Gains {
+    /// Proportional gain (output per process value unit)
+    pub kp: f64,
+    /// Integral gain (output per process value unit and second)
+    pub ki: f64,
+    /// Derivative gain (output per process value unit per second)
+    pub kd: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pid {
+    gains: Gains,
+    min: f64,
+    max: f64,
+    target: f64,
+    /// Setpoint ramped towards the target
+    setpoint: Ramp,
+    ramped: bool,
+    integral: f64,
+    output: f64,
+    manual: Option<f64>,
+    /// Process value, time and error of the last update
+    last: Option<(f64, Instant, f64)>,
+}
+
+impl Pid {
+    pub fn new(gains: Gains, min: f64, max: f64) -> Pid {
+        Pid {
+            gains,
+            min: min.min(max),
+            max: min.max(max),
+            target: 0.0,
+            setpoint: Ramp::new(0.0, 0.0),
+            ramped: false,
+            integral: 0.0,
+            output: min.min(max),
+            manual: None,
+            last: None,
+        }
+    }
+
+    pub fn gains(&self) -> Gains {
+        self.gains
+    }
+
+    pub fn set_gains(&mut self, gains: Gains) {
+        self.gains = gains;
+    }
+
+    pub fn set_limits(&mut self, min: f64, max: f64) {
+        self.min = min.min(max);
+        self.max = min.max(max);
+        self.integral = self.integral.max(self.min).min(self.max);
+    }
+
+    /// Set the setpoint, which is approached with `rate` units per second (0 for a step)
+    pub fn set_setpoint(&mut self, setpoint: f64, rate: f64) {
+        self.target = setpoint;
+        self.ramped = rate > 0.0;
+        self.setpoint.set_rate(rate);
+        if !self.ramped {
+            self.setpoint.reset(setpoint);
+        }
+    }
+
+    /// Current (ramped) setpoint
+    pub fn setpoint(&self) -> f64 {
+        self.setpoint.out()
+    }
+
+    pub fn output(&self) -> f64 {
+        self.output
+    }
+
+    /// Switch to manual mode with a fixed output
+    pub fn set_manual(&mut self, output: f64) {
+        self.manual = Some(output.max(self.min).min(self.max));
+    }
+
+    /// Switch to automatic mode, starting from the current output
+    pub fn set_auto(&mut self) {
+        if self.manual.take().is_some() {
+            // the gains may have changed in manual mode
+            let error = self.last.map(|(_, _, error)| error).unwrap_or_default();
+            self.integral = (self.output - self.gains.kp * error)
+                .max(self.min)
+                .min(self.max);
+        }
+    }
+
+    pub fn is_manual(&self) -> bool {
+        self.manual.is_some()
+    }
+
+    /// Calculate the output for the process value `pv` measured at `now`
+    pub fn update(&mut self, pv: f64, now: Instant) -> f64 {
+        if self.last.is_none() && self.ramped {
+            // the ramp starts at the process value
+            self.setpoint.reset(pv);
+        }
+        let setpoint = self.setpoint.call(self.target, now);
+        let error = setpoint - pv;
+
+        let (dt, derivative) = match self.last {
+            Some((last_pv, last, _)) => {
+                let dt = now.saturating_duration_since(last).as_secs_f64();
+                let derivative = if dt > 0.0 {
+                    -self.gains.kd * (pv - last_pv) / dt
+                } else {
+                    0.0
+                };
+                (dt, derivative)
+            }
+            None => (0.0, 0.0),
+        };
+        self.last = Some((pv, now, error));
+        let proportional = self.gains.kp * error;
+
+        if let Some(output) = self.manual {
+            self.output = output;
+            return output;
+        }
+
+        let integral = self.integral + self.gains.ki * error * dt;
+        let output = proportional + integral + derivative;
+        // integrate only if the output does not saturate in the direction of the error
+        let saturated = (output > self.max && error * self.gains.ki > 0.0)
+            || (output < self.min && error * self.gains.ki < 0.0);
+        if !saturated {
+            self.integral = integral.max(self.min).min(self.max);
+        }
+
+        self.output = (proportional + self.integral + derivative)
+            .max(self.min)
+            .min(self.max);
+        self.output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn pid_test() {
+        let start = Instant::now();
+        let s = |s| start + Duration::from_secs(s);
+
+        let mut pid = Pid::new(
+            Gains {
+                kp: 2.0,
+                ki: 0.5,
+                kd: 1.0,
+            },
+            0.0,
+            100.0,
+        );
+        pid.set_setpoint(50.0, 0.0);
+        assert_eq!(pid.update(40.0, s(0)), 20.0);
+        // P 20 + I 5 - D 0
+        assert_eq!(pid.update(40.0, s(1)), 25.0);
+        // P 10 + I 7.5 - D 5
+        assert_eq!(pid.update(45.0, s(2)), 12.5);
+
+        // the integral does not wind up while the output saturates
+        for t in 3..100 {
+            assert_eq!(pid.update(0.0, s(t)), 100.0);
+        }
+        assert_eq!(pid.update(60.0, s(100)), 0.0);
+        assert_eq!(pid.update(51.0, s(101)), 14.0);
+
+        // bumpless transfer from manual to automatic mode
+        pid.set_manual(30.0);
+        assert_eq!(pid.update(50.0, s(102)), 30.0);
+        assert!(pid.is_manual());
+        pid.set_auto();
+        assert_eq!(pid.update(50.0, s(103)), 30.0);
+
+        // the setpoint ramp starts at the process value
+        let mut pid = Pid::new(
+            Gains {
+                kp: 1.0,
+                ..Gains::default()
+            },
+            -100.0,
+            100.0,
+        );
+        pid.set_setpoint(30.0, 2.0);
+        assert_eq!(pid.update(20.0, s(0)), 0.0);
+        assert_eq!(pid.update(20.0, s(2)), 4.0);
+        assert_eq!(pid.setpoint(), 24.0);
+        assert_eq!(pid.update(20.0, s(10)), 10.0);
+    }
+}
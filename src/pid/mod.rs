@@ -0,0 +1,431 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// PID control loops. Each loop runs in its own thread on a fixed cycle (see
+// `periodic::Periodic`), reads the process value from an analog input or temperature sensor and
+// drives an analog output, the duty cycle of a PWM output or a digital output with time
+// proportioning. The controller output is in percent of the output range.
+//
+// The loops are controlled via `PidLoop` or the IoPid* functions of the C API. If the process
+// value cannot be read, the output is switched off until the next successful read.
+
+mod autotune;
+mod controller;
+
+pub use self::autotune::{Autotune, AutotuneConfig, AutotuneResult};
+pub use self::controller::{Gains, Pid};
+
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+
+use crate::error::{Error, Result};
+use crate::periodic::Periodic;
+use crate::{health, Io};
+
+/// Process value of a loop
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Input {
+    /// Raw value of an analog input
+    AnalogInput(usize),
+    /// Temperature in °C
+    Temperature(usize),
+}
+
+/// Manipulated value of a loop, 100 % of the controller output correspond to:
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Output {
+    /// The raw value `full_scale` of an analog output
+    AnalogOutput { channel: usize, full_scale: i64 },
+    /// A duty cycle of `period` (in the timebase of the PWM output)
+    Pwm { channel: usize, period: u16 },
+    /// A digital output switched on for the whole `window`
+    TimeProportional { channel: usize, window: Duration },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoopConfig {
+    pub input: Input,
+    pub output: Output,
+    pub cycle: Duration,
+    pub gains: Gains,
+    /// Limits of the controller output in percent
+    pub min: f64,
+    pub max: f64,
+}
+
+impl LoopConfig {
+    pub fn new(input: Input, output: Output) -> LoopConfig {
+        LoopConfig {
+            input,
+            output,
+            cycle: Duration::from_millis(100),
+            gains: Gains {
+                kp: 1.0,
+                ..Gains::default()
+            },
+            min: 0.0,
+            max: 100.0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AutotuneStatus {
+    Off,
+    Running,
+    /// The gains were set from the autotune result
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Status {
+    /// Current (ramped) setpoint
+    pub setpoint: f64,
+    pub process_value: Option<f64>,
+    /// Controller output in percent
+    pub output: f64,
+    pub manual: bool,
+    pub autotune: AutotuneStatus,
+}
+
+struct State {
+    pid: Pid,
+    autotune: Option<Autotune>,
+    autotune_status: AutotuneStatus,
+    process_value: Option<f64>,
+}
+
+/// Running control loop, stopped on drop
+pub struct PidLoop {
+    state: Arc<Mutex<State>>,
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+fn check_channel(count: usize, channel: usize) -> Result<()> {
+    if channel < count {
+        Ok(())
+    } else {
+        Err(Error::InvalidChannel)
+    }
+}
+
+/// Drives the output of a loop
+struct Actuator {
+    output: Output,
+    window_start: Option<Instant>,
+    /// Last written value of a digital output
+    state: Option<bool>,
+}
+
+impl Actuator {
+    fn write(&mut self, io: &mut Io, percent: f64, now: Instant) -> Result<()> {
+        let ratio = percent.clamp(0.0, 100.0) / 100.0;
+        match self.output {
+            Output::AnalogOutput {
+                channel,
+                full_scale,
+            } => io.analog_output_set(channel, (ratio * full_scale as f64).round() as i64),
+            Output::Pwm { channel, period } => {
+                io.pwm_setup(channel, period, (ratio * period as f64).round() as u16)
+            }
+            Output::TimeProportional { channel, window } => {
+                let start = *self.window_start.get_or_insert(now);
+                let mut elapsed = now.saturating_duration_since(start);
+                if elapsed >= window {
+                    // the output is only changed at the start of a window
+                    self.window_start = Some(now);
+                    elapsed = Duration::default();
+                }
+                let value = elapsed.as_secs_f64() < ratio * window.as_secs_f64();
+                if self.state != Some(value) {
+                    io.output_set(channel, value)?;
+                    self.state = Some(value);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn off(&mut self, io: &mut Io) -> Result<()> {
+        match self.output {
+            Output::AnalogOutput { channel, .. } => io.analog_output_set(channel, 0),
+            Output::Pwm { channel, .. } => io.pwm_enable(channel, false),
+            Output::TimeProportional { channel, .. } => {
+                self.state = Some(false);
+                io.output_set(channel, false)
+            }
+        }
+    }
+}
+
+fn read(io: &mut Io, input: Input) -> Result<f64> {
+    match input {
+        Input::AnalogInput(channel) => Ok(io.analog_input_get(channel)? as f64),
+        Input::Temperature(channel) => io.tmp_input_get(channel),
+    }
+}
+
+impl PidLoop {
+    /// Start a control loop in a thread called `name`, the setpoint is 0 in automatic mode
+    pub fn start(io: Arc<Mutex<Io>>, name: &str, config: &LoopConfig) -> Result<PidLoop> {
+        if config.cycle == Duration::default() {
+            return Err(Error::InvalidParameter);
+        }
+        {
+            let mut io = io.lock().map_err(|_| Error::GenericError)?;
+            let info = io.get_channel_info();
+            match config.input {
+                Input::AnalogInput(channel) => check_channel(info.analog_inputs.len(), channel)?,
+                Input::Temperature(channel) => check_channel(info.temp_sensors.len(), channel)?,
+            }
+            match config.output {
+                Output::AnalogOutput { channel, .. } => {
+                    check_channel(info.analog_outputs.len(), channel)?
+                }
+                Output::Pwm { channel, .. } => {
+                    check_channel(info.pwm_outputs.len(), channel)?;
+                    io.pwm_setup(channel, 0, 0)?;
+                    io.pwm_enable(channel, true)?;
+                }
+                Output::TimeProportional { channel, .. } => {
+                    check_channel(info.outputs.len(), channel)?
+                }
+            }
+        }
+
+        let state = Arc::new(Mutex::new(State {
+            pid: Pid::new(config.gains, config.min, config.max),
+            autotune: None,
+            autotune_status: AutotuneStatus::Off,
+            process_value: None,
+        }));
+        let (stop, stopped) = crossbeam_channel::bounded(1);
+        let mut actuator = Actuator {
+            output: config.output,
+            window_start: None,
+            state: None,
+        };
+        let (input, cycle) = (config.input, config.cycle);
+        let thread_state = state.clone();
+
+        let thread = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                let mut periodic = Periodic::new(cycle);
+                let mut failed = false;
+                while stopped.try_recv().is_err() {
+                    periodic.next();
+                    let now = Instant::now();
+                    let mut io = io.lock().unwrap();
+
+                    let pv = read(&mut io, input);
+                    let output = {
+                        let mut state = thread_state.lock().unwrap();
+                        state.update(pv.as_ref().ok().copied(), now)
+                    };
+                    let result = match output {
+                        Some(output) => actuator.write(&mut io, output, now),
+                        None => actuator.off(&mut io),
+                    };
+
+                    match pv.and(result) {
+                        Ok(()) if failed => {
+                            info!("Control loop recovered");
+                            failed = false;
+                        }
+                        Err(e) if !failed => {
+                            error!("Control loop failed: {}", e);
+                            failed = true;
+                        }
+                        _ => {}
+                    }
+                    if failed {
+                        health::sampler_read_error();
+                    }
+                    health::sampler_cycle(periodic.elapsed());
+                }
+
+                if let Err(e) = actuator.off(&mut io.lock().unwrap()) {
+                    error!("Failed to switch off the output: {}", e);
+                }
+            })
+            .map_err(|e| {
+                error!("Failed to start the control loop: {}", e);
+                Error::GenericError
+            })?;
+
+        Ok(PidLoop {
+            state,
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+
+    fn with_state<T, F: FnOnce(&mut State) -> T>(&self, f: F) -> T {
+        f(&mut self.state.lock().unwrap())
+    }
+
+    /// Set the setpoint, which is approached with `rate` units per second (0 for a step)
+    pub fn set_setpoint(&self, setpoint: f64, rate: f64) {
+        self.with_state(|state| state.pid.set_setpoint(setpoint, rate))
+    }
+
+    pub fn gains(&self) -> Gains {
+        self.with_state(|state| state.pid.gains())
+    }
+
+    pub fn set_gains(&self, gains: Gains) {
+        self.with_state(|state| state.pid.set_gains(gains))
+    }
+
+    /// Set the limits of the controller output in percent
+    pub fn set_limits(&self, min: f64, max: f64) -> Result<()> {
+        if min < 0.0 || max > 100.0 || min > max {
+            return Err(Error::InvalidParameter);
+        }
+        self.with_state(|state| state.pid.set_limits(min, max));
+        Ok(())
+    }
+
+    /// Switch to manual mode with a fixed output in percent, a running autotune is aborted
+    pub fn set_manual(&self, output: f64) {
+        self.with_state(|state| {
+            state.abort_autotune();
+            state.pid.set_manual(output)
+        })
+    }
+
+    /// Switch to automatic mode, the transfer from manual mode is bumpless
+    pub fn set_auto(&self) {
+        self.with_state(|state| state.pid.set_auto())
+    }
+
+    /// Start an autotune around the current setpoint, the gains are set on success
+    pub fn autotune(&self, config: AutotuneConfig) {
+        self.with_state(|state| {
+            let setpoint = state.pid.setpoint();
+            state.autotune = Some(Autotune::new(config, setpoint));
+            state.autotune_status = AutotuneStatus::Running;
+        })
+    }
+
+    pub fn status(&self) -> Status {
+        self.with_state(|state| Status {
+            setpoint: state.pid.setpoint(),
+            process_value: state.process_value,
+            output: state.pid.output(),
+            manual: state.pid.is_manual(),
+            autotune: state.autotune_status,
+        })
+    }
+
+    /// Stop the loop and switch off the output
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            stop.send(()).ok();
+        }
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+impl Drop for PidLoop {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+impl State {
+    fn abort_autotune(&mut self) {
+        if self.autotune.take().is_some() {
+            self.autotune_status = AutotuneStatus::Off;
+        }
+    }
+
+    /// Returns the output for the process value or `None` if the output has to be switched off
+    fn update(&mut self, pv: Option<f64>, now: Instant) -> Option<f64> {
+        self.process_value = pv;
+        let pv = pv?;
+
+        if let Some(autotune) = self.autotune.as_mut() {
+            match autotune.step(pv, now) {
+                AutotuneResult::Running(output) => {
+                    // the controller tracks the output for a bumpless transfer afterwards
+                    self.pid.set_manual(output);
+                    self.pid.update(pv, now);
+                    self.pid.set_auto();
+                    return Some(output);
+                }
+                AutotuneResult::Done(gains) => {
+                    info!("Autotune finished: {:?}", gains);
+                    self.pid.set_gains(gains);
+                    self.autotune_status = AutotuneStatus::Done;
+                }
+                AutotuneResult::Failed => {
+                    error!("Autotune failed");
+                    self.autotune_status = AutotuneStatus::Failed;
+                }
+            }
+            self.autotune = None;
+        }
+
+        Some(self.pid.update(pv, now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+
+    #[test]
+    fn pid_loop_test() {
+        // the analog output is looped back to the analog input
+        let sim = Simulator::new("SIM", 0, 1, 0);
+        let io = IoBuilder::new()
+            .analog_input(Box::new(sim.analog_input(0)))
+            .analog_output(Box::new(sim.analog_output(0)))
+            .build();
+        let io = Arc::new(Mutex::new(io));
+
+        let mut config = LoopConfig::new(
+            Input::AnalogInput(0),
+            Output::AnalogOutput {
+                channel: 0,
+                full_scale: 1000,
+            },
+        );
+        config.cycle = Duration::from_millis(5);
+        let invalid = LoopConfig {
+            input: Input::Temperature(0),
+            ..config.clone()
+        };
+        assert!(matches!(
+            PidLoop::start(io.clone(), "pid-test", &invalid),
+            Err(Error::InvalidChannel)
+        ));
+
+        let pid = PidLoop::start(io.clone(), "pid-test", &config).unwrap();
+        pid.set_manual(40.0);
+        thread::sleep(Duration::from_millis(50));
+        let status = pid.status();
+        assert_eq!(status.process_value, Some(400.0));
+        assert_eq!(status.output, 40.0);
+        assert!(status.manual);
+        assert!(pid.set_limits(0.0, 120.0).is_err());
+
+        pid.stop();
+        assert_eq!(io.lock().unwrap().analog_input_get(0).unwrap(), 0);
+    }
+}
@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Channel providers allow to extend an existing `Io` instance at runtime with channels from
+// other sources (e.g. remote I/O modules, simulated channels or plugins). The channels are
+// appended to the channel tables of `Io` and can be accessed like any other channel afterwards.
+
+use std::ops::Range;
+
+use crate::error::Result;
+use crate::{AnalogInput, AnalogOutput, DigitalInput, DigitalOutput, TempSensor};
+
+/// Channels contributed by a provider
+///
+/// Channels should be wrapped in `Labeled` to be identifiable by their label.
+#[derive(Default)]
+pub struct ProviderChannels {
+    pub outputs: Vec<Box<dyn DigitalOutput>>,
+    pub inputs: Vec<Box<dyn DigitalInput>>,
+    pub analog_inputs: Vec<Box<dyn AnalogInput>>,
+    pub analog_outputs: Vec<Box<dyn AnalogOutput>>,
+    pub temp_sensors: Vec<Box<dyn TempSensor<f64>>>,
+}
+
+pub trait ChannelProvider {
+    /// Unique name of the provider
+    fn name(&self) -> String;
+
+    /// Additional information about the provider (e.g. the address of a remote device)
+    fn description(&self) -> String {
+        String::new()
+    }
+
+    /// Create the channels of the provider. This is called once, when the provider gets added.
+    fn channels(&mut self) -> Result<ProviderChannels>;
+}
+
+/// Channel numbers which got assigned to the channels of a provider
+#[derive(Debug, Clone)]
+pub struct ProviderInfo {
+    pub name: String,
+    pub description: String,
+    pub outputs: Range<usize>,
+    pub inputs: Range<usize>,
+    pub analog_inputs: Range<usize>,
+    pub analog_outputs: Range<usize>,
+    pub temp_sensors: Range<usize>,
+}
+
+impl ProviderInfo {
+    pub fn to_json(&self) -> json::JsonValue {
+        fn range(r: &Range<usize>) -> json::JsonValue {
+            json::object! {
+                first: r.start,
+                count: r.len(),
+            }
+        }
+
+        json::object! {
+            name: self.name.as_str(),
+            description: self.description.as_str(),
+            outputs: range(&self.outputs),
+            inputs: range(&self.inputs),
+            analog_inputs: range(&self.analog_inputs),
+            analog_outputs: range(&self.analog_outputs),
+            temp_sensors: range(&self.temp_sensors),
+        }
+    }
+}
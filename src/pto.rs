@@ -0,0 +1,438 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Pulse train output (PTO) on top of a PWM output, e.g. to drive stepper motors. A parameter
+// table of segments is output one after the other. Each segment starts with a period, which is
+// changed by a delta after every pulse (negative to accelerate, positive to decelerate), until
+// its pulse count is reached. The output is switched off after the last segment and the
+// completion callback is called.
+//
+// The PWM hardware cannot count pulses, so a thread follows the output: it calculates the pulses
+// from the elapsed time, updates the period for the ramp and switches to the next segment on
+// time. Segments may be added while the output is running, e.g. to continue a movement.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+
+use crate::error::{Error, Result};
+use crate::Io;
+
+/// Maximum number of segments in the parameter table
+pub const MAX_SEGMENTS: usize = 16;
+
+/// Interval in which the period is updated for ramps
+const UPDATE_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Shortest period of a ramp in nanoseconds
+pub const MIN_PERIOD: i64 = 1000;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Segment {
+    /// Period of the first pulse in nanoseconds
+    pub period: u64,
+    /// Change of the period after each pulse in nanoseconds
+    pub delta: i64,
+    pub pulses: u32,
+}
+
+impl Segment {
+    fn validate(&self) -> Result<()> {
+        if self.pulses == 0 || (self.period as i64) < MIN_PERIOD {
+            return Err(Error::InvalidParameter);
+        }
+        // the ramp must not reach a period of 0
+        let last = (self.period as i64).saturating_add(self.delta * (self.pulses as i64 - 1));
+        if last < MIN_PERIOD {
+            return Err(Error::InvalidDelta);
+        }
+        Ok(())
+    }
+
+    /// Period of the pulse with the given (zero based) number
+    pub fn period_at(&self, pulse: u32) -> u64 {
+        (self.period as i64 + self.delta * pulse as i64) as u64
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum State {
+    Idle,
+    Running,
+    /// All segments were output
+    Done,
+}
+
+/// Called after all segments were output, it runs in a separate thread
+pub type Callback = Box<dyn FnMut() + Send>;
+
+struct Shared {
+    table: VecDeque<Segment>,
+    state: State,
+    /// Pulses of the current or last run
+    pulses: u64,
+}
+
+/// Pulse train output of a PWM channel, it is stopped on drop
+pub struct Pto {
+    io: Arc<Mutex<Io>>,
+    channel: usize,
+    shared: Arc<Mutex<Shared>>,
+    callback: Arc<Mutex<Option<Callback>>>,
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// Segment which is currently output
+struct Generator {
+    segment: Segment,
+    /// Pulses output of the segment, including the fraction of the current pulse
+    pulses: f64,
+    /// Period which is configured for the output
+    period: u64,
+}
+
+impl Generator {
+    fn new(segment: Segment) -> Generator {
+        Generator {
+            segment,
+            pulses: 0.0,
+            period: segment.period,
+        }
+    }
+
+    /// Count the pulses output within `elapsed`, returns the number of completed pulses
+    fn advance(&mut self, elapsed: Duration) -> u64 {
+        let before = self.pulses as u64;
+        self.pulses = (self.pulses + elapsed.as_nanos() as f64 / self.period as f64)
+            .min(self.segment.pulses as f64);
+        self.pulses as u64 - before
+    }
+
+    fn finished(&self) -> bool {
+        self.pulses >= self.segment.pulses as f64
+    }
+
+    /// Period for the current pulse
+    fn next_period(&self) -> u64 {
+        self.segment.period_at(self.pulses as u32)
+    }
+
+    /// Time until the end of the segment, estimated with the current period
+    fn remaining(&self) -> Duration {
+        let pulses = self.segment.pulses as f64 - self.pulses;
+        Duration::from_nanos((pulses * self.period as f64).ceil() as u64)
+    }
+}
+
+fn configure(io: &Mutex<Io>, channel: usize, period: u64) -> Result<()> {
+    let mut io = io.lock().map_err(|_| Error::GenericError)?;
+    io.pwm_configure(channel, period, period / 2)
+}
+
+fn run(
+    io: &Mutex<Io>,
+    channel: usize,
+    shared: &Mutex<Shared>,
+    stopped: &crossbeam_channel::Receiver<()>,
+) -> Result<bool> {
+    let mut generator: Option<Generator> = None;
+    let mut last = Instant::now();
+
+    loop {
+        let now = Instant::now();
+        let elapsed = now - last;
+        last = now;
+
+        if let Some(current) = generator.as_mut() {
+            let pulses = current.advance(elapsed);
+            shared.lock().unwrap().pulses += pulses;
+        }
+
+        let finished = match &generator {
+            Some(current) => current.finished(),
+            None => true,
+        };
+        if finished {
+            match shared.lock().unwrap().table.pop_front() {
+                Some(segment) => {
+                    let next = Generator::new(segment);
+                    configure(io, channel, next.period)?;
+                    generator = Some(next);
+                }
+                None => return Ok(true),
+            }
+        } else if let Some(current) = generator.as_mut() {
+            let period = current.next_period();
+            if period != current.period {
+                configure(io, channel, period)?;
+                current.period = period;
+            }
+        }
+
+        let sleep = generator
+            .as_ref()
+            .map_or(UPDATE_INTERVAL, |g| g.remaining().min(UPDATE_INTERVAL));
+        if stopped.recv_timeout(sleep).is_ok() {
+            return Ok(false);
+        }
+    }
+}
+
+impl Pto {
+    pub fn new(io: Arc<Mutex<Io>>, channel: usize) -> Result<Pto> {
+        {
+            let io = io.lock().map_err(|_| Error::GenericError)?;
+            if channel >= io.get_channel_info().pwm_outputs.len() {
+                return Err(Error::InvalidChannel);
+            }
+        }
+
+        Ok(Pto {
+            io,
+            channel,
+            shared: Arc::new(Mutex::new(Shared {
+                table: VecDeque::new(),
+                state: State::Idle,
+                pulses: 0,
+            })),
+            callback: Arc::new(Mutex::new(None)),
+            stop: None,
+            thread: None,
+        })
+    }
+
+    /// Append a segment to the parameter table, also while the output is running
+    pub fn add_segment(&self, segment: Segment) -> Result<()> {
+        segment.validate()?;
+        let mut shared = self.shared.lock().unwrap();
+        if shared.table.len() >= MAX_SEGMENTS {
+            return Err(Error::PtoParamTabFull);
+        }
+        shared.table.push_back(segment);
+        Ok(())
+    }
+
+    /// Remove all segments which were not output yet
+    pub fn clear(&self) {
+        self.shared.lock().unwrap().table.clear();
+    }
+
+    pub fn set_callback(&self, callback: Option<Callback>) {
+        *self.callback.lock().unwrap() = callback;
+    }
+
+    /// State and the number of pulses output by the current or last run
+    pub fn state(&self) -> (State, u64) {
+        let shared = self.shared.lock().unwrap();
+        (shared.state, shared.pulses)
+    }
+
+    /// Start to output the parameter table
+    pub fn start(&mut self) -> Result<()> {
+        {
+            let mut shared = self.shared.lock().unwrap();
+            match shared.state {
+                State::Running => return Ok(()),
+                _ if shared.table.is_empty() => return Err(Error::InvalidParameter),
+                _ => {}
+            }
+            shared.state = State::Running;
+            shared.pulses = 0;
+        }
+        // the thread of the last run has finished or is about to
+        self.shutdown();
+
+        let (stop, stopped) = crossbeam_channel::bounded(1);
+        let (io, channel) = (self.io.clone(), self.channel);
+        let shared = self.shared.clone();
+        let callback = self.callback.clone();
+        let name = format!("pto{}", channel);
+
+        let started = io
+            .lock()
+            .map_err(|_| Error::GenericError)
+            .and_then(|mut io| io.pwm_enable(channel, true));
+        let thread = started.and_then(|()| {
+            thread::Builder::new()
+                .name(name.clone())
+                .spawn(move || {
+                    let result = run(&io, channel, &shared, &stopped);
+                    if let Err(e) = io.lock().unwrap().pwm_enable(channel, false) {
+                        error!("Failed to switch off the output: {}", e);
+                    }
+
+                    match result {
+                        Ok(true) => {
+                            shared.lock().unwrap().state = State::Done;
+                            // the callback may use the API again, which waits for this thread
+                            thread::Builder::new()
+                                .name(format!("{}-done", name))
+                                .spawn(move || {
+                                    if let Some(callback) = callback.lock().unwrap().as_mut() {
+                                        callback();
+                                    }
+                                })
+                                .ok();
+                        }
+                        Ok(false) => shared.lock().unwrap().state = State::Idle,
+                        Err(e) => {
+                            error!("Pulse train output failed: {}", e);
+                            let mut shared = shared.lock().unwrap();
+                            shared.state = State::Idle;
+                            shared.table.clear();
+                        }
+                    }
+                })
+                .map_err(|e| {
+                    error!("Failed to start the pulse train output: {}", e);
+                    Error::GenericError
+                })
+        });
+
+        match thread {
+            Ok(thread) => {
+                self.stop = Some(stop);
+                self.thread = Some(thread);
+                Ok(())
+            }
+            Err(e) => {
+                self.shared.lock().unwrap().state = State::Idle;
+                Err(e)
+            }
+        }
+    }
+
+    /// Stop the output, the remaining segments are discarded
+    pub fn stop(&mut self) {
+        self.shutdown();
+        let mut shared = self.shared.lock().unwrap();
+        shared.table.clear();
+        if shared.state == State::Running {
+            shared.state = State::Idle;
+        }
+    }
+
+    fn shutdown(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            stop.send(()).ok();
+        }
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+impl Drop for Pto {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+
+    #[test]
+    fn segment_test() {
+        let segment = Segment {
+            period: 100_000,
+            delta: -10_000,
+            pulses: 10,
+        };
+        assert!(segment.validate().is_ok());
+        assert_eq!(segment.period_at(9), 10_000);
+        assert!(matches!(
+            Segment {
+                pulses: 11,
+                ..segment
+            }
+            .validate(),
+            Err(Error::InvalidDelta)
+        ));
+        assert!(Segment {
+            pulses: 0,
+            ..segment
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn generator_test() {
+        // 2 ms, decelerate to 4 ms in 5 pulses
+        let mut generator = Generator::new(Segment {
+            period: 2_000_000,
+            delta: 500_000,
+            pulses: 5,
+        });
+        assert_eq!(generator.advance(Duration::from_millis(4)), 2);
+        assert_eq!(generator.next_period(), 3_000_000);
+        generator.period = generator.next_period();
+        assert_eq!(generator.remaining(), Duration::from_millis(9));
+
+        assert_eq!(generator.advance(Duration::from_millis(6)), 2);
+        assert_eq!(generator.next_period(), 4_000_000);
+        assert!(!generator.finished());
+        // a late update does not output more pulses than the segment has
+        assert_eq!(generator.advance(Duration::from_millis(100)), 1);
+        assert!(generator.finished());
+    }
+
+    #[test]
+    fn pto_test() {
+        let sim = Simulator::new("SIM", 0, 0, 0).with_pwm_outputs(1);
+        let io = IoBuilder::new().pwm(Box::new(sim.pwm(0))).build();
+        let io = Arc::new(Mutex::new(io));
+        assert!(matches!(
+            Pto::new(io.clone(), 1),
+            Err(Error::InvalidChannel)
+        ));
+
+        let mut pto = Pto::new(io, 0).unwrap();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        pto.set_callback(Some(Box::new(move || tx.send(()).unwrap())));
+        assert!(pto.start().is_err());
+
+        // 10 pulses of 2 ms, decelerate to 4 ms in 5 pulses
+        let segment = Segment {
+            period: 2_000_000,
+            delta: 0,
+            pulses: 10,
+        };
+        pto.add_segment(segment).unwrap();
+        pto.add_segment(Segment {
+            delta: 500_000,
+            pulses: 5,
+            ..segment
+        })
+        .unwrap();
+        pto.start().unwrap();
+        assert_eq!(pto.state().0, State::Running);
+        assert!(sim.pwm_state(0).unwrap().enabled);
+
+        rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(pto.state(), (State::Done, 15));
+        // the periods of the ramp depend on the scheduling of the thread (see `generator_test`)
+        assert!(!sim.pwm_state(0).unwrap().enabled);
+
+        for _ in 0..MAX_SEGMENTS {
+            pto.add_segment(segment).unwrap();
+        }
+        assert!(matches!(
+            pto.add_segment(segment),
+            Err(Error::PtoParamTabFull)
+        ));
+        pto.start().unwrap();
+        pto.stop();
+        assert_eq!(pto.state().0, State::Idle);
+        assert!(pto.state().1 < 10);
+        assert!(!sim.pwm_state(0).unwrap().enabled);
+        assert!(rx.try_recv().is_err());
+    }
+}
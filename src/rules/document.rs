@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Parsers for the subsets of TOML and YAML used by rule files: tables (mappings) with scalar
+// values and arrays of such tables. Both formats result in the same `Document`:
+//
+//   [engine]                  engine:
+//   cycle = 50                  cycle: 50
+//
+//   [[rules]]                 rules:
+//   name = "pump"               - name: pump
+//   when = "DI2 and not DI3"      when: DI2 and not DI3
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scalar {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl fmt::Display for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scalar::Str(value) => write!(f, "{}", value),
+            Scalar::Int(value) => write!(f, "{}", value),
+            Scalar::Float(value) => write!(f, "{}", value),
+            Scalar::Bool(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+pub type Table = BTreeMap<String, Scalar>;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    /// Tables by name, keys outside of a table are stored in the table ""
+    pub tables: BTreeMap<String, Table>,
+    /// Arrays of tables by name
+    pub arrays: BTreeMap<String, Vec<Table>>,
+}
+
+fn syntax_error(line: usize, message: &str) -> Error {
+    error!("line {}: {}", line + 1, message);
+    Error::InvalidParameter
+}
+
+/// Remove a comment starting with '#' outside of quotes
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '#') => return &line[..i],
+            _ => {}
+        }
+        escaped = false;
+    }
+    line
+}
+
+/// Parse a quoted string, returns `None` if the value is not quoted
+fn parse_quoted(value: &str) -> Option<std::result::Result<String, &'static str>> {
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    if value.len() < 2 || !value.ends_with(quote) {
+        return Some(Err("unterminated string"));
+    }
+    let inner = &value[1..value.len() - 1];
+    if quote == '\'' {
+        return Some(Ok(inner.to_string()));
+    }
+
+    let mut result = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        result.push(match chars.next() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('"') => '"',
+            Some('\\') => '\\',
+            _ => return Some(Err("invalid escape sequence")),
+        });
+    }
+    Some(Ok(result))
+}
+
+fn parse_number(value: &str) -> Option<Scalar> {
+    let value = value.replace('_', "");
+    if let Ok(value) = value.parse() {
+        return Some(Scalar::Int(value));
+    }
+    if value.contains(|c: char| c.is_ascii_digit()) {
+        if let Ok(value) = value.parse() {
+            return Some(Scalar::Float(value));
+        }
+    }
+    None
+}
+
+fn parse_toml_value(value: &str) -> std::result::Result<Scalar, &'static str> {
+    if let Some(string) = parse_quoted(value) {
+        return string.map(Scalar::Str);
+    }
+    match value {
+        "true" => Ok(Scalar::Bool(true)),
+        "false" => Ok(Scalar::Bool(false)),
+        _ if value.starts_with('[') || value.starts_with('{') => {
+            Err("arrays and inline tables are not supported")
+        }
+        _ => parse_number(value).ok_or("invalid value"),
+    }
+}
+
+/// Parse a key, which may be quoted
+fn parse_key(key: &str) -> std::result::Result<String, &'static str> {
+    let key = key.trim();
+    match parse_quoted(key) {
+        Some(key) => key,
+        None if !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') =>
+        {
+            Ok(key.to_string())
+        }
+        None => Err("invalid key"),
+    }
+}
+
+pub fn parse_toml(text: &str) -> Result<Document> {
+    let mut document = Document::default();
+    // table for the following key/value pairs: (name, index in the array of tables)
+    let mut current: (String, Option<usize>) = (String::new(), None);
+
+    for (number, line) in text.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line
+            .strip_prefix("[[")
+            .and_then(|line| line.strip_suffix("]]"))
+        {
+            let name = parse_key(name).map_err(|e| syntax_error(number, e))?;
+            let array = document.arrays.entry(name.clone()).or_default();
+            array.push(Table::new());
+            current = (name, Some(array.len() - 1));
+        } else if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            let name = parse_key(name).map_err(|e| syntax_error(number, e))?;
+            if document.tables.contains_key(&name) {
+                return Err(syntax_error(number, "duplicate table"));
+            }
+            document.tables.insert(name.clone(), Table::new());
+            current = (name, None);
+        } else {
+            let position = line
+                .find('=')
+                .ok_or_else(|| syntax_error(number, "expected key = value"))?;
+            let key = parse_key(&line[..position]).map_err(|e| syntax_error(number, e))?;
+            let value = parse_toml_value(line[position + 1..].trim())
+                .map_err(|e| syntax_error(number, e))?;
+
+            let table = match current {
+                (ref name, Some(index)) => &mut document.arrays.get_mut(name).unwrap()[index],
+                (ref name, None) => document.tables.entry(name.clone()).or_default(),
+            };
+            if table.insert(key, value).is_some() {
+                return Err(syntax_error(number, "duplicate key"));
+            }
+        }
+    }
+
+    Ok(document)
+}
+
+fn parse_yaml_value(value: &str) -> std::result::Result<Scalar, &'static str> {
+    if let Some(string) = parse_quoted(value) {
+        return string.map(Scalar::Str);
+    }
+    Ok(match value {
+        "true" | "yes" | "on" => Scalar::Bool(true),
+        "false" | "no" | "off" => Scalar::Bool(false),
+        _ => parse_number(value).unwrap_or_else(|| Scalar::Str(value.to_string())),
+    })
+}
+
+/// Split "key: value", the value is empty if the key starts a block
+fn split_yaml_pair(line: &str) -> std::result::Result<(String, &str), &'static str> {
+    let position = line
+        .find(": ")
+        .or_else(|| line.strip_suffix(':').map(|line| line.len()))
+        .ok_or("expected key: value")?;
+    let key = parse_key(&line[..position])?;
+    Ok((key, line[position + 1..].trim()))
+}
+
+pub fn parse_yaml(text: &str) -> Result<Document> {
+    let mut document = Document::default();
+    // name of the top level block
+    let mut block: Option<String> = None;
+    // indentation of the keys of the current sequence item
+    let mut item_indent: Option<usize> = None;
+
+    for (number, line) in text.lines().enumerate() {
+        let content = strip_comment(line).trim_end();
+        if content.trim().is_empty() || content == "---" {
+            continue;
+        }
+        let indent = content.len() - content.trim_start().len();
+        let content = content.trim_start();
+        if line.starts_with('\t') {
+            return Err(syntax_error(number, "tabs are not allowed for indentation"));
+        }
+
+        if indent == 0 {
+            let (key, value) = split_yaml_pair(content).map_err(|e| syntax_error(number, e))?;
+            item_indent = None;
+            if value.is_empty() {
+                block = Some(key);
+            } else {
+                let value = parse_yaml_value(value).map_err(|e| syntax_error(number, e))?;
+                document
+                    .tables
+                    .entry(String::new())
+                    .or_default()
+                    .insert(key, value);
+                block = None;
+            }
+            continue;
+        }
+
+        let name = block
+            .clone()
+            .ok_or_else(|| syntax_error(number, "unexpected indentation"))?;
+
+        let (pair, table) = if let Some(item) = content.strip_prefix("- ") {
+            if document.tables.contains_key(&name) {
+                return Err(syntax_error(number, "mixed mapping and sequence"));
+            }
+            item_indent = Some(indent + 2 + (item.len() - item.trim_start().len()));
+            let array = document.arrays.entry(name).or_default();
+            array.push(Table::new());
+            (item.trim_start(), array.last_mut().unwrap())
+        } else if let Some(item_indent) = item_indent {
+            if indent != item_indent {
+                return Err(syntax_error(number, "invalid indentation"));
+            }
+            (
+                content,
+                document.arrays.get_mut(&name).unwrap().last_mut().unwrap(),
+            )
+        } else {
+            (content, document.tables.entry(name).or_default())
+        };
+
+        let (key, value) = split_yaml_pair(pair).map_err(|e| syntax_error(number, e))?;
+        if value.is_empty() {
+            return Err(syntax_error(number, "nested blocks are not supported"));
+        }
+        let value = parse_yaml_value(value).map_err(|e| syntax_error(number, e))?;
+        if table.insert(key, value).is_some() {
+            return Err(syntax_error(number, "duplicate key"));
+        }
+    }
+
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_yaml_test() {
+        let toml = parse_toml(
+            "version = 1\n\n[engine] # comment\ncycle = 1_000\nevents = true\n\n\
+             [[rules]]\nname = \"pump # 1\"\nwhen = 'DI2 and not DI3'\n\n\
+             [[rules]]\n\"name\" = \"alarm\\n\"\nlimit = 80.5\n",
+        )
+        .unwrap();
+        let yaml = parse_yaml(
+            "---\nversion: 1\n\nengine:  # comment\n  cycle: 1000\n  events: yes\n\n\
+             rules:\n  - name: \"pump # 1\"\n    when: DI2 and not DI3\n\
+             \x20 -   name: \"alarm\\n\"\n      limit: 80.5\n",
+        )
+        .unwrap();
+        assert_eq!(toml, yaml);
+
+        assert_eq!(toml.tables[""]["version"], Scalar::Int(1));
+        assert_eq!(toml.tables["engine"]["cycle"], Scalar::Int(1000));
+        assert_eq!(toml.tables["engine"]["events"], Scalar::Bool(true));
+        let rules = &toml.arrays["rules"];
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0]["name"], Scalar::Str("pump # 1".to_string()));
+        assert_eq!(rules[0]["when"], Scalar::Str("DI2 and not DI3".to_string()));
+        assert_eq!(rules[1]["name"], Scalar::Str("alarm\n".to_string()));
+        assert_eq!(rules[1]["limit"], Scalar::Float(80.5));
+
+        assert!(parse_toml("[a]\nb = [1, 2]\n").is_err());
+        assert!(parse_toml("[a]\nb = 1\nb = 2\n").is_err());
+        assert!(parse_toml("a = \"open\n").is_err());
+        assert!(parse_yaml("a:\n  - b: 1\n   c: 2\n").is_err());
+        assert!(parse_yaml("  a: 1\n").is_err());
+    }
+}
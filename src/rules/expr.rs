@@ -0,0 +1,598 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Expressions of the rule conditions, e.g. "DI2 and not DI3", "TMP3 > 80 °C" or
+// "ton(DI0, 2s)".
+//
+// Operators by increasing precedence: or (||), xor, and (&&), not (!), comparisons
+// (< <= > >= == !=), + -, * /, unary minus. Numbers may have the suffixes ms, s and min (in
+// milliseconds) or °C. Channels are referenced by label or by kind and index (see
+// `rules::resolve_channel`), labels with other characters can be quoted: 'Pump 1'.
+//
+// Functions keep their state per call site and are evaluated in every cycle:
+//
+//   rise(x), fall(x)   true for one cycle on the rising/falling edge of x
+//   change(x)          true for one cycle if the value of x changed
+//   overflow(x)        true for one cycle if the value of x decreased (counter wrap around)
+//   ton(x, t)          on delay: true if x is true for at least t
+//   tof(x, t)          off delay: true while x is true and for t after x became false
+//   tp(x, t)           pulse: true for t after a rising edge of x
+
+use std::time::{Duration, Instant};
+
+use super::Channel;
+use crate::blocks::{FTrig, RTrig, Ton, Tp};
+use crate::error::{Error, Result};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Num(f64),
+}
+
+impl Value {
+    pub fn as_bool(self) -> bool {
+        match self {
+            Value::Bool(value) => value,
+            Value::Num(value) => value != 0.0,
+        }
+    }
+
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Value::Bool(value) => value as u8 as f64,
+            Value::Num(value) => value,
+        }
+    }
+}
+
+/// Access to the channel values during an evaluation
+pub trait Context {
+    fn read(&mut self, channel: Channel) -> Result<Value>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Op(&'static str),
+    Open,
+    Close,
+    Comma,
+}
+
+const OPERATORS: [&str; 14] = [
+    "<=", ">=", "==", "!=", "&&", "||", "<", ">", "+", "-", "*", "/", "!", "=",
+];
+
+fn tokenize(text: &str) -> std::result::Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        // a name may start with '/' for active low signals (e.g. "/Powerfail"), if an operand is
+        // expected at this position
+        let operand_expected = matches!(
+            tokens.last(),
+            None | Some(Token::Op(_)) | Some(Token::Open) | Some(Token::Comma)
+        );
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            let mut value: f64 = number
+                .parse()
+                .map_err(|_| format!("invalid number {}", number))?;
+
+            // a space is allowed before the unit °C
+            let spaces = chars[i..].iter().take_while(|c| **c == ' ').count();
+            if chars.get(i + spaces) == Some(&'°') {
+                i += spaces;
+            }
+            let unit_start = i;
+            while i < chars.len() && (chars[i].is_alphabetic() || chars[i] == '°') {
+                i += 1;
+            }
+            let unit: String = chars[unit_start..i].iter().collect();
+            value *= match unit.as_str() {
+                "" | "ms" | "°C" => 1.0,
+                "s" => 1000.0,
+                "min" => 60_000.0,
+                _ => return Err(format!("unknown unit {}", unit)),
+            };
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() || c == '_' || (c == '/' && operand_expected) {
+            let start = i;
+            i += 1;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+            {
+                i += 1;
+            }
+            let name: String = chars[start..i].iter().collect();
+            tokens.push(match name.as_str() {
+                "and" => Token::Op("&&"),
+                "or" => Token::Op("||"),
+                "not" => Token::Op("!"),
+                "xor" => Token::Op("xor"),
+                _ => Token::Name(name),
+            });
+        } else if c == '\'' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|c| *c == '\'')
+                .ok_or("unterminated name")?;
+            tokens.push(Token::Name(chars[i + 1..i + 1 + end].iter().collect()));
+            i += end + 2;
+        } else if c == '(' {
+            tokens.push(Token::Open);
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token::Close);
+            i += 1;
+        } else if c == ',' {
+            tokens.push(Token::Comma);
+            i += 1;
+        } else {
+            let rest: String = chars[i..].iter().take(2).collect();
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(*op))
+                .ok_or_else(|| format!("unexpected character {}", c))?;
+            // "=" is accepted as comparison as well
+            let op: &'static str = op;
+            tokens.push(Token::Op(if op == "=" { "==" } else { op }));
+            i += op.len();
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum BinaryOp {
+    Or,
+    Xor,
+    And,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Const(Value),
+    Channel(Channel),
+    Not(Box<Node>),
+    Neg(Box<Node>),
+    Binary(BinaryOp, Box<Node>, Box<Node>),
+    Rise(Box<Node>, RTrig),
+    Fall(Box<Node>, FTrig),
+    Change(Box<Node>, Option<Value>),
+    Overflow(Box<Node>, Option<f64>),
+    Ton(Box<Node>, Ton),
+    /// Off delay: argument, time, end of the delay. Unlike `blocks::Tof` the delay starts at the
+    /// last scan with a true argument, not at the first scan with a false one.
+    Tof(Box<Node>, Duration, Option<Instant>),
+    Tp(Box<Node>, Tp),
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    resolve: &'a dyn Fn(&str) -> Option<Channel>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Consume the operator if it is one of `ops`
+    fn operator(&mut self, ops: &[&str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.position += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> std::result::Result<(), String> {
+        match self.next() {
+            Some(next) if next == token => Ok(()),
+            next => Err(format!("expected {:?}, found {:?}", token, next)),
+        }
+    }
+
+    fn binary(
+        &mut self,
+        ops: &[(&str, BinaryOp)],
+        operand: fn(&mut Self) -> std::result::Result<Node, String>,
+    ) -> std::result::Result<Node, String> {
+        let names: Vec<&str> = ops.iter().map(|(name, _)| *name).collect();
+        let mut node = operand(self)?;
+        while let Some(op) = self.operator(&names) {
+            let op = ops.iter().find(|(name, _)| *name == op).unwrap().1;
+            node = Node::Binary(op, Box::new(node), Box::new(operand(self)?));
+        }
+        Ok(node)
+    }
+
+    fn or(&mut self) -> std::result::Result<Node, String> {
+        self.binary(&[("||", BinaryOp::Or)], Self::xor)
+    }
+
+    fn xor(&mut self) -> std::result::Result<Node, String> {
+        self.binary(&[("xor", BinaryOp::Xor)], Self::and)
+    }
+
+    fn and(&mut self) -> std::result::Result<Node, String> {
+        self.binary(&[("&&", BinaryOp::And)], Self::not)
+    }
+
+    fn not(&mut self) -> std::result::Result<Node, String> {
+        if self.operator(&["!"]).is_some() {
+            return Ok(Node::Not(Box::new(self.not()?)));
+        }
+        self.compare()
+    }
+
+    fn compare(&mut self) -> std::result::Result<Node, String> {
+        let node = self.sum()?;
+        let ops = [
+            ("<", BinaryOp::Less),
+            ("<=", BinaryOp::LessEqual),
+            (">", BinaryOp::Greater),
+            (">=", BinaryOp::GreaterEqual),
+            ("==", BinaryOp::Equal),
+            ("!=", BinaryOp::NotEqual),
+        ];
+        let names: Vec<&str> = ops.iter().map(|(name, _)| *name).collect();
+        match self.operator(&names) {
+            Some(op) => {
+                let op = ops.iter().find(|(name, _)| *name == op).unwrap().1;
+                Ok(Node::Binary(op, Box::new(node), Box::new(self.sum()?)))
+            }
+            None => Ok(node),
+        }
+    }
+
+    fn sum(&mut self) -> std::result::Result<Node, String> {
+        self.binary(&[("+", BinaryOp::Add), ("-", BinaryOp::Sub)], Self::product)
+    }
+
+    fn product(&mut self) -> std::result::Result<Node, String> {
+        self.binary(&[("*", BinaryOp::Mul), ("/", BinaryOp::Div)], Self::unary)
+    }
+
+    fn unary(&mut self) -> std::result::Result<Node, String> {
+        if self.operator(&["-"]).is_some() {
+            return Ok(Node::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> std::result::Result<Node, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Node::Const(Value::Num(value))),
+            Some(Token::Open) => {
+                let node = self.or()?;
+                self.expect(Token::Close)?;
+                Ok(node)
+            }
+            Some(Token::Name(name)) if self.peek() == Some(&Token::Open) => {
+                self.position += 1;
+                self.function(&name)
+            }
+            Some(Token::Name(name)) => match name.as_str() {
+                "true" => Ok(Node::Const(Value::Bool(true))),
+                "false" => Ok(Node::Const(Value::Bool(false))),
+                _ => (self.resolve)(&name)
+                    .map(Node::Channel)
+                    .ok_or_else(|| format!("unknown channel {}", name)),
+            },
+            token => Err(format!("unexpected {:?}", token)),
+        }
+    }
+
+    fn function(&mut self, name: &str) -> std::result::Result<Node, String> {
+        let arg = Box::new(self.or()?);
+        let time = match name {
+            "ton" | "tof" | "tp" => {
+                self.expect(Token::Comma)?;
+                match self.next() {
+                    Some(Token::Number(ms)) if ms >= 0.0 => Duration::from_millis(ms as u64),
+                    token => return Err(format!("expected time, found {:?}", token)),
+                }
+            }
+            _ => Duration::default(),
+        };
+        self.expect(Token::Close)?;
+
+        Ok(match name {
+            "rise" => Node::Rise(arg, RTrig::new()),
+            "fall" => Node::Fall(arg, FTrig::new()),
+            "change" => Node::Change(arg, None),
+            "overflow" => Node::Overflow(arg, None),
+            "ton" => Node::Ton(arg, Ton::new(time)),
+            "tof" => Node::Tof(arg, time, None),
+            "tp" => Node::Tp(arg, Tp::new(time)),
+            _ => return Err(format!("unknown function {}", name)),
+        })
+    }
+}
+
+impl Node {
+    fn eval(&mut self, context: &mut dyn Context, now: Instant) -> Result<Value> {
+        Ok(match self {
+            Node::Const(value) => *value,
+            Node::Channel(channel) => context.read(*channel)?,
+            Node::Not(arg) => Value::Bool(!arg.eval(context, now)?.as_bool()),
+            Node::Neg(arg) => Value::Num(-arg.eval(context, now)?.as_f64()),
+            Node::Binary(op, left, right) => {
+                // both sides are always evaluated to update the state of functions
+                let (a, b) = (left.eval(context, now)?, right.eval(context, now)?);
+                let (x, y) = (a.as_f64(), b.as_f64());
+                match op {
+                    BinaryOp::Or => Value::Bool(a.as_bool() || b.as_bool()),
+                    BinaryOp::Xor => Value::Bool(a.as_bool() != b.as_bool()),
+                    BinaryOp::And => Value::Bool(a.as_bool() && b.as_bool()),
+                    BinaryOp::Less => Value::Bool(x < y),
+                    BinaryOp::LessEqual => Value::Bool(x <= y),
+                    BinaryOp::Greater => Value::Bool(x > y),
+                    BinaryOp::GreaterEqual => Value::Bool(x >= y),
+                    BinaryOp::Equal => Value::Bool(x == y),
+                    BinaryOp::NotEqual => Value::Bool(x != y),
+                    BinaryOp::Add => Value::Num(x + y),
+                    BinaryOp::Sub => Value::Num(x - y),
+                    BinaryOp::Mul => Value::Num(x * y),
+                    BinaryOp::Div if y == 0.0 => return Err(Error::InvalidParameter),
+                    BinaryOp::Div => Value::Num(x / y),
+                }
+            }
+            Node::Rise(arg, trigger) => {
+                Value::Bool(trigger.call(arg.eval(context, now)?.as_bool()))
+            }
+            Node::Fall(arg, trigger) => {
+                Value::Bool(trigger.call(arg.eval(context, now)?.as_bool()))
+            }
+            Node::Change(arg, last) => {
+                let value = arg.eval(context, now)?;
+                let changed = last.is_some_and(|last| last.as_f64() != value.as_f64());
+                *last = Some(value);
+                Value::Bool(changed)
+            }
+            Node::Overflow(arg, last) => {
+                let value = arg.eval(context, now)?.as_f64();
+                let wrapped = last.is_some_and(|last| value < last);
+                *last = Some(value);
+                Value::Bool(wrapped)
+            }
+            Node::Ton(arg, timer) => {
+                Value::Bool(timer.call(arg.eval(context, now)?.as_bool(), now))
+            }
+            Node::Tof(arg, time, until) => {
+                if arg.eval(context, now)?.as_bool() {
+                    // the delay starts only after the argument was true once
+                    *until = Some(now + *time);
+                    Value::Bool(true)
+                } else {
+                    Value::Bool(until.is_some_and(|end| now < end))
+                }
+            }
+            Node::Tp(arg, timer) => Value::Bool(timer.call(arg.eval(context, now)?.as_bool(), now)),
+        })
+    }
+
+    fn channels(&self, channels: &mut Vec<Channel>) {
+        match self {
+            Node::Const(_) => {}
+            Node::Channel(channel) => channels.push(*channel),
+            Node::Binary(_, left, right) => {
+                left.channels(channels);
+                right.channels(channels);
+            }
+            Node::Not(arg)
+            | Node::Neg(arg)
+            | Node::Rise(arg, _)
+            | Node::Fall(arg, _)
+            | Node::Change(arg, _)
+            | Node::Overflow(arg, _)
+            | Node::Ton(arg, _)
+            | Node::Tof(arg, _, _)
+            | Node::Tp(arg, _) => arg.channels(channels),
+        }
+    }
+}
+
+/// Compiled expression with the state of its functions
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    root: Node,
+}
+
+impl Expr {
+    /// Parse an expression, channel names are resolved with `resolve`
+    pub fn parse(
+        text: &str,
+        resolve: &dyn Fn(&str) -> Option<Channel>,
+    ) -> std::result::Result<Expr, String> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            position: 0,
+            resolve,
+        };
+        let root = parser.or()?;
+        match parser.next() {
+            None => Ok(Expr { root }),
+            Some(token) => Err(format!("unexpected {:?}", token)),
+        }
+    }
+
+    pub fn eval(&mut self, context: &mut dyn Context, now: Instant) -> Result<Value> {
+        self.root.eval(context, now)
+    }
+
+    /// Channels referenced by the expression
+    pub fn channels(&self) -> Vec<Channel> {
+        let mut channels = vec![];
+        self.root.channels(&mut channels);
+        channels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct Values(HashMap<Channel, Value>);
+
+    impl Context for Values {
+        fn read(&mut self, channel: Channel) -> Result<Value> {
+            self.0.get(&channel).copied().ok_or(Error::InvalidChannel)
+        }
+    }
+
+    fn resolve(name: &str) -> Option<Channel> {
+        match name {
+            "DI0" => Some(Channel::Input(0)),
+            "DI1" => Some(Channel::Input(1)),
+            "/Powerfail" => Some(Channel::Input(2)),
+            "TMP0" => Some(Channel::Temperature(0)),
+            "Pump 1" => Some(Channel::Output(0)),
+            _ => None,
+        }
+    }
+
+    fn eval(text: &str, values: &mut Values) -> Value {
+        Expr::parse(text, &resolve)
+            .unwrap()
+            .eval(values, Instant::now())
+            .unwrap()
+    }
+
+    #[test]
+    fn expr_test() {
+        let mut values = Values(HashMap::new());
+        values.0.insert(Channel::Input(0), Value::Bool(true));
+        values.0.insert(Channel::Input(1), Value::Bool(false));
+        values.0.insert(Channel::Input(2), Value::Bool(false));
+        values.0.insert(Channel::Temperature(0), Value::Num(81.5));
+        values.0.insert(Channel::Output(0), Value::Bool(true));
+
+        assert_eq!(eval("DI0 and not DI1", &mut values), Value::Bool(true));
+        assert_eq!(
+            eval("DI0 && !(DI1 || true)", &mut values),
+            Value::Bool(false)
+        );
+        assert_eq!(eval("DI0 xor DI1", &mut values), Value::Bool(true));
+        assert_eq!(eval("TMP0 > 80 °C", &mut values), Value::Bool(true));
+        assert_eq!(
+            eval("TMP0 - 1.5 * 2 = 78.5", &mut values),
+            Value::Bool(true)
+        );
+        assert_eq!(eval("-TMP0 / 2 < -40", &mut values), Value::Bool(true));
+        assert_eq!(
+            eval("not /Powerfail and 'Pump 1'", &mut values),
+            Value::Bool(true)
+        );
+        assert_eq!(eval("TMP0/2", &mut values), Value::Num(40.75));
+        assert_eq!(eval("1.5s + 2min", &mut values), Value::Num(121_500.0));
+
+        for invalid in &[
+            "DI0 and", "DI5", "(DI0", "DI0 DI1", "ton(DI0)", "foo(DI0)", "5 kg",
+        ] {
+            assert!(Expr::parse(invalid, &resolve).is_err(), "{}", invalid);
+        }
+        assert_eq!(
+            Expr::parse("rise(DI0) or TMP0 > 1", &resolve)
+                .unwrap()
+                .channels(),
+            [Channel::Input(0), Channel::Temperature(0)]
+        );
+    }
+
+    #[test]
+    fn function_test() {
+        let mut values = Values(HashMap::new());
+        let start = Instant::now();
+        let ms = |ms| start + Duration::from_millis(ms);
+        let mut step = |expr: &mut Expr, input: f64, time: Instant| {
+            values.0.insert(Channel::Input(0), Value::Num(input));
+            expr.eval(&mut values, time).unwrap().as_bool()
+        };
+
+        let mut rise = Expr::parse("rise(DI0)", &resolve).unwrap();
+        let mut fall = Expr::parse("fall(DI0)", &resolve).unwrap();
+        let mut change = Expr::parse("change(DI0)", &resolve).unwrap();
+        let mut overflow = Expr::parse("overflow(DI0)", &resolve).unwrap();
+        let inputs = [1.0, 1.0, 0.0, 5.0, 3.0];
+        let results: Vec<_> = inputs
+            .iter()
+            .map(|input| {
+                (
+                    step(&mut rise, *input, start),
+                    step(&mut fall, *input, start),
+                    step(&mut change, *input, start),
+                    step(&mut overflow, *input, start),
+                )
+            })
+            .collect();
+        assert_eq!(
+            results,
+            [
+                (true, false, false, false),
+                (false, false, false, false),
+                (false, true, true, true),
+                (true, false, true, false),
+                (false, false, true, true),
+            ]
+        );
+
+        let mut ton = Expr::parse("ton(DI0, 100ms)", &resolve).unwrap();
+        assert!(!step(&mut ton, 1.0, ms(0)));
+        assert!(!step(&mut ton, 1.0, ms(99)));
+        assert!(step(&mut ton, 1.0, ms(100)));
+        assert!(!step(&mut ton, 0.0, ms(150)));
+
+        let mut tof = Expr::parse("tof(DI0, 0.1s)", &resolve).unwrap();
+        assert!(!step(&mut tof, 0.0, ms(0)));
+        assert!(step(&mut tof, 1.0, ms(10)));
+        assert!(step(&mut tof, 0.0, ms(109)));
+        assert!(!step(&mut tof, 0.0, ms(110)));
+
+        let mut tp = Expr::parse("tp(DI0, 100)", &resolve).unwrap();
+        assert!(step(&mut tp, 1.0, ms(0)));
+        assert!(step(&mut tp, 0.0, ms(50)));
+        assert!(step(&mut tp, 1.0, ms(60)));
+        assert!(!step(&mut tp, 1.0, ms(100)));
+        assert!(!step(&mut tp, 0.0, ms(110)));
+        assert!(step(&mut tp, 1.0, ms(120)));
+    }
+}
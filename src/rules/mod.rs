@@ -0,0 +1,552 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Rule engine for simple automation tasks without a PLC program. Rules are loaded from a TOML or
+// YAML file (see `config/rules.toml`), each rule drives a digital output or system LED from a
+// condition (see `expr` for the syntax):
+//
+//   [engine]
+//   cycle = 50                   ; evaluation cycle in milliseconds
+//   events = true                ; evaluate additionally on input edges
+//
+//   [[rules]]
+//   name = "pump"
+//   when = "DI2 and not DI3"
+//   target = "DO0"
+//   action = "follow"            ; follow, set, reset, toggle or pulse (with duration)
+//
+// "follow" writes the condition to the target, the other actions are executed on the rising
+// edge of the condition. Rules are evaluated in order, so later rules win if several rules
+// drive the same target.
+
+pub mod document;
+pub mod expr;
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Receiver;
+
+use self::document::{Document, Scalar, Table};
+use self::expr::{Context, Expr, Value};
+use crate::error::{Error, Result};
+use crate::events::{self, InputEvent};
+use crate::Io;
+
+/// Channel referenced by a rule
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Channel {
+    Input(usize),
+    /// Read back state of a digital output
+    Output(usize),
+    AnalogInput(usize),
+    Temperature(usize),
+    Counter(usize),
+    RunSwitch,
+    ConfigSwitch,
+    RunLed,
+    ErrLed,
+}
+
+/// Resolve a channel by label (case insensitive), by kind and index (di0, do0, ai0, tmp0, cnt0)
+/// or by the names of the system channels (run_switch, config_switch, run_led, err_led)
+pub fn resolve_channel(io: &Io, name: &str) -> Option<Channel> {
+    let system = [
+        ("run_switch", Channel::RunSwitch),
+        ("config_switch", Channel::ConfigSwitch),
+        ("run_led", Channel::RunLed),
+        ("err_led", Channel::ErrLed),
+    ];
+    if let Some((_, channel)) = system
+        .iter()
+        .find(|(system, _)| system.eq_ignore_ascii_case(name))
+    {
+        return Some(*channel);
+    }
+
+    let info = io.get_channel_info();
+    find_channel(info.inputs.iter().map(|c| c.label()), "di", name)
+        .map(Channel::Input)
+        .or_else(|| {
+            find_channel(info.outputs.iter().map(|c| c.label()), "do", name).map(Channel::Output)
+        })
+        .or_else(|| {
+            find_channel(info.analog_inputs.iter().map(|c| c.label()), "ai", name)
+                .map(Channel::AnalogInput)
+        })
+        .or_else(|| {
+            find_channel(info.temp_sensors.iter().map(|c| c.label()), "tmp", name)
+                .map(Channel::Temperature)
+        })
+        .or_else(|| {
+            find_channel(info.counter_input.iter().map(|c| c.label()), "cnt", name)
+                .map(Channel::Counter)
+        })
+}
+
+/// Index of the channel with the label `name`, otherwise of the channel named prefix + index
+pub(crate) fn find_channel<'a>(
+    labels: impl Iterator<Item = Option<&'a str>> + Clone,
+    prefix: &str,
+    name: &str,
+) -> Option<usize> {
+    labels
+        .clone()
+        .position(|label| label.is_some_and(|label| label.eq_ignore_ascii_case(name)))
+        .or_else(|| {
+            (0..labels.count())
+                .find(|index| format!("{}{}", prefix, index).eq_ignore_ascii_case(name))
+        })
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Action {
+    /// Write the condition to the target
+    Follow,
+    /// Switch the target on at the rising edge of the condition
+    Set,
+    /// Switch the target off at the rising edge of the condition
+    Reset,
+    /// Invert the target at the rising edge of the condition
+    Toggle,
+    /// Switch the target on for a duration at the rising edge of the condition
+    Pulse(Duration),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleConfig {
+    pub name: String,
+    pub when: String,
+    pub target: String,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleSet {
+    pub cycle: Duration,
+    /// Evaluate the rules on edges of the referenced inputs
+    pub events: bool,
+    pub rules: Vec<RuleConfig>,
+}
+
+impl Default for RuleSet {
+    fn default() -> RuleSet {
+        RuleSet {
+            cycle: Duration::from_millis(100),
+            events: true,
+            rules: vec![],
+        }
+    }
+}
+
+fn get_string(table: &Table, key: &str) -> Option<String> {
+    table.get(key).map(|value| value.to_string())
+}
+
+fn get_u64(table: &Table, key: &str, default: u64) -> Result<u64> {
+    match table.get(key) {
+        None => Ok(default),
+        Some(Scalar::Int(value)) if *value >= 0 => Ok(*value as u64),
+        Some(value) => {
+            error!("{}: invalid value {}", key, value);
+            Err(Error::InvalidParameter)
+        }
+    }
+}
+
+impl RuleSet {
+    /// Load the rules from a YAML file (extension .yaml or .yml) or a TOML file
+    pub fn load(path: &str) -> Result<RuleSet> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            error!("Failed to load {}: {}", path, e);
+            Error::InvalidParameter
+        })?;
+        let yaml = Path::new(path)
+            .extension()
+            .is_some_and(|extension| extension == "yaml" || extension == "yml");
+        if yaml {
+            RuleSet::from_document(&document::parse_yaml(&text)?)
+        } else {
+            RuleSet::from_document(&document::parse_toml(&text)?)
+        }
+    }
+
+    pub fn from_document(document: &Document) -> Result<RuleSet> {
+        let mut rule_set = RuleSet::default();
+
+        if let Some(engine) = document.tables.get("engine") {
+            rule_set.cycle =
+                Duration::from_millis(get_u64(engine, "cycle", rule_set.cycle.as_millis() as u64)?);
+            rule_set.events = match engine.get("events") {
+                None => rule_set.events,
+                Some(Scalar::Bool(events)) => *events,
+                Some(value) => {
+                    error!("events: invalid value {}", value);
+                    return Err(Error::InvalidParameter);
+                }
+            };
+        }
+
+        for (index, rule) in document
+            .arrays
+            .get("rules")
+            .into_iter()
+            .flatten()
+            .enumerate()
+        {
+            let name = get_string(rule, "name").unwrap_or_else(|| format!("rule{}", index));
+            let (when, target) = match (get_string(rule, "when"), get_string(rule, "target")) {
+                (Some(when), Some(target)) => (when, target),
+                _ => {
+                    error!("{}: \"when\" and \"target\" are required", name);
+                    return Err(Error::InvalidParameter);
+                }
+            };
+            let action = match get_string(rule, "action").as_deref() {
+                None | Some("follow") => Action::Follow,
+                Some("set") => Action::Set,
+                Some("reset") => Action::Reset,
+                Some("toggle") => Action::Toggle,
+                Some("pulse") => {
+                    Action::Pulse(Duration::from_millis(get_u64(rule, "duration", 1000)?))
+                }
+                Some(action) => {
+                    error!("{}: unknown action {}", name, action);
+                    return Err(Error::InvalidParameter);
+                }
+            };
+            rule_set.rules.push(RuleConfig {
+                name,
+                when,
+                target,
+                action,
+            });
+        }
+
+        Ok(rule_set)
+    }
+}
+
+impl FromStr for RuleSet {
+    type Err = Error;
+
+    /// Parse rules in TOML format
+    fn from_str(text: &str) -> Result<RuleSet> {
+        RuleSet::from_document(&document::parse_toml(text)?)
+    }
+}
+
+struct Rule {
+    name: String,
+    condition: Expr,
+    target: Channel,
+    action: Action,
+    /// Condition of the last evaluation
+    last: bool,
+    /// End of a running pulse
+    pulse_end: Option<Instant>,
+    /// The last evaluation failed, used to log errors only once
+    failed: bool,
+}
+
+/// Reads the channels of an `Io`, each channel is read only once per evaluation
+pub(crate) struct IoContext<'a> {
+    pub(crate) io: &'a mut Io,
+    pub(crate) cache: HashMap<Channel, Value>,
+}
+
+impl Context for IoContext<'_> {
+    fn read(&mut self, channel: Channel) -> Result<Value> {
+        if let Some(value) = self.cache.get(&channel) {
+            return Ok(*value);
+        }
+        let io = &mut *self.io;
+        let value = match channel {
+            Channel::Input(index) => Value::Bool(io.input_get(index)?),
+            Channel::Output(index) => Value::Bool(io.output_get(index)?),
+            Channel::AnalogInput(index) => Value::Num(io.analog_input_get(index)? as f64),
+            Channel::Temperature(index) => Value::Num(io.tmp_input_get(index)?),
+            Channel::Counter(index) => Value::Num(io.cnt_get(index)? as f64),
+            Channel::RunSwitch => Value::Bool(io.get_run_switch()?),
+            Channel::ConfigSwitch => Value::Bool(io.get_config_switch()?),
+            Channel::RunLed => Value::Bool(io.get_run_led()?),
+            Channel::ErrLed => Value::Bool(io.get_err_led()?),
+        };
+        self.cache.insert(channel, value);
+        Ok(value)
+    }
+}
+
+pub struct Engine {
+    io: Io,
+    rules: Vec<Rule>,
+    cycle: Duration,
+    events: Option<Receiver<InputEvent>>,
+    /// States written to the targets
+    states: HashMap<Channel, bool>,
+}
+
+impl Engine {
+    /// Compile the rules for an initialized `Io`
+    pub fn new(mut io: Io, rule_set: &RuleSet) -> Result<Engine> {
+        let mut rules = vec![];
+        for config in &rule_set.rules {
+            let resolve = |name: &str| resolve_channel(&io, name);
+            let condition = Expr::parse(&config.when, &resolve).map_err(|e| {
+                error!("{}: {}", config.name, e);
+                Error::InvalidParameter
+            })?;
+            let target = match resolve(&config.target) {
+                Some(target @ Channel::Output(_))
+                | Some(target @ Channel::RunLed)
+                | Some(target @ Channel::ErrLed) => target,
+                _ => {
+                    error!("{}: invalid target {}", config.name, config.target);
+                    return Err(Error::InvalidChannel);
+                }
+            };
+            rules.push(Rule {
+                name: config.name.clone(),
+                condition,
+                target,
+                action: config.action,
+                last: false,
+                pulse_end: None,
+                failed: false,
+            });
+        }
+
+        // edges are handled immediately if the input supports callbacks, otherwise in the cycle
+        let mut events = None;
+        if rule_set.events {
+            let (tx, rx) = crossbeam_channel::bounded(1);
+            let mut inputs: Vec<usize> = rules
+                .iter()
+                .flat_map(|rule| rule.condition.channels())
+                .filter_map(|channel| match channel {
+                    Channel::Input(index) => Some(index),
+                    _ => None,
+                })
+                .collect();
+            inputs.sort_unstable();
+            inputs.dedup();
+            for index in inputs {
+                if let Err(e) = events::register(&mut io, index) {
+                    debug!("No callback for input {}: {}", index, e);
+                }
+            }
+            events::subscribe(tx);
+            events = Some(rx);
+        }
+
+        Ok(Engine {
+            io,
+            rules,
+            cycle: rule_set.cycle,
+            events,
+            states: HashMap::new(),
+        })
+    }
+
+    fn write(io: &mut Io, target: Channel, value: bool) -> Result<()> {
+        match target {
+            Channel::Output(index) => io.output_set(index, value),
+            Channel::RunLed => io.set_run_led(value),
+            Channel::ErrLed => io.set_err_led(value),
+            _ => Err(Error::InvalidChannel),
+        }
+    }
+
+    /// Evaluate all rules once and write the targets
+    pub fn evaluate(&mut self, now: Instant) {
+        let mut context = IoContext {
+            io: &mut self.io,
+            cache: HashMap::new(),
+        };
+        // pending writes, only the last write of a target is executed
+        let mut writes: HashMap<Channel, (&str, bool)> = HashMap::new();
+        for rule in &mut self.rules {
+            let condition = match rule.condition.eval(&mut context, now) {
+                Ok(value) => {
+                    if rule.failed {
+                        info!("{}: evaluation recovered", rule.name);
+                        rule.failed = false;
+                    }
+                    value.as_bool()
+                }
+                Err(e) => {
+                    if !rule.failed {
+                        error!("{}: evaluation failed: {}", rule.name, e);
+                        rule.failed = true;
+                    }
+                    continue;
+                }
+            };
+            let rising = condition && !rule.last;
+            rule.last = condition;
+
+            let value = match rule.action {
+                Action::Follow => Some(condition),
+                Action::Set if rising => Some(true),
+                Action::Reset if rising => Some(false),
+                Action::Toggle if rising => match context.read(rule.target) {
+                    Ok(current) => Some(!current.as_bool()),
+                    Err(e) => {
+                        error!("{}: failed to read {:?}: {}", rule.name, rule.target, e);
+                        None
+                    }
+                },
+                Action::Pulse(duration) => {
+                    if rising {
+                        rule.pulse_end = Some(now + duration);
+                        Some(true)
+                    } else if rule.pulse_end.is_some_and(|end| now >= end) {
+                        rule.pulse_end = None;
+                        Some(false)
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            };
+            if let Some(value) = value {
+                writes.insert(rule.target, (&rule.name, value));
+                // the following rules read the new state
+                context.cache.insert(rule.target, Value::Bool(value));
+            }
+        }
+
+        for (target, (name, value)) in writes {
+            if self.states.get(&target) == Some(&value) {
+                continue;
+            }
+            match Engine::write(&mut self.io, target, value) {
+                Ok(()) => {
+                    self.states.insert(target, value);
+                }
+                Err(e) => error!("{}: failed to write {:?}: {}", name, target, e),
+            }
+        }
+    }
+
+    /// Evaluate the rules cyclically and on input events until a message is received from
+    /// `stop`
+    pub fn run<T>(&mut self, stop: &Receiver<T>) {
+        let ticker = crossbeam_channel::tick(self.cycle);
+        let events = self.events.clone().unwrap_or_else(crossbeam_channel::never);
+
+        self.evaluate(Instant::now());
+        loop {
+            crossbeam_channel::select! {
+                recv(ticker) -> now => self.evaluate(now.unwrap()),
+                recv(events) -> _ => self.evaluate(Instant::now()),
+                recv(stop) -> _ => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::IoBuilder;
+    use crate::io::sim::Simulator;
+    use crate::labeled::Labeled;
+
+    #[test]
+    fn rule_set_test() {
+        let rules: RuleSet = "[engine]\ncycle = 20\nevents = false\n\
+                              [[rules]]\nwhen = \"DI0\"\ntarget = \"DO0\"\n\
+                              [[rules]]\nname = \"flash\"\nwhen = \"rise(DI1)\"\ntarget = \"DO1\"\n\
+                              action = \"pulse\"\nduration = 250\n"
+            .parse()
+            .unwrap();
+        assert_eq!(rules.cycle, Duration::from_millis(20));
+        assert!(!rules.events);
+        assert_eq!(
+            rules.rules,
+            [
+                RuleConfig {
+                    name: "rule0".to_string(),
+                    when: "DI0".to_string(),
+                    target: "DO0".to_string(),
+                    action: Action::Follow,
+                },
+                RuleConfig {
+                    name: "flash".to_string(),
+                    when: "rise(DI1)".to_string(),
+                    target: "DO1".to_string(),
+                    action: Action::Pulse(Duration::from_millis(250)),
+                },
+            ]
+        );
+
+        assert!("[[rules]]\nwhen = \"DI0\"\n".parse::<RuleSet>().is_err());
+        assert!(
+            "[[rules]]\nwhen = \"DI0\"\ntarget = \"DO0\"\naction = \"blink\"\n"
+                .parse::<RuleSet>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn engine_test() {
+        // DO0..DO2 are looped back to DI0..DI2 by the simulator, DO3..DO5 are driven by rules
+        let sim = Simulator::new("SIM", 6, 0, 1);
+        let mut builder = IoBuilder::new();
+        for i in 0..6 {
+            builder = builder
+                .output(Box::new(Labeled::new(
+                    ["DO0", "DO1", "DO2", "Lamp", "Alarm", "Horn"][i],
+                    sim.output(i),
+                )))
+                .input(Box::new(sim.input(i)));
+        }
+        let mut io = builder
+            .temp_sensor(Box::new(Labeled::new("TMP3", sim.temp_sensor(0))))
+            .build();
+        io.init().unwrap();
+        io.output_set(1, true).unwrap();
+
+        let rules: RuleSet = "[engine]\nevents = false\n\
+            [[rules]]\nwhen = \"di0 and not di1\"\ntarget = \"lamp\"\n\
+            [[rules]]\nwhen = \"TMP3 > 80 °C\"\ntarget = \"Alarm\"\naction = \"set\"\n\
+            [[rules]]\nwhen = \"rise(di2)\"\ntarget = \"Horn\"\naction = \"pulse\"\nduration = 100\n"
+            .parse()
+            .unwrap();
+        let mut engine = Engine::new(io, &rules).unwrap();
+        let start = Instant::now();
+        let ms = |ms| start + Duration::from_millis(ms);
+        let outputs = |engine: &mut Engine| {
+            (3..6)
+                .map(|i| engine.io.output_get(i).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        engine.evaluate(ms(0));
+        assert_eq!(outputs(&mut engine), [false, false, false]);
+
+        engine.io.output_set(0, true).unwrap();
+        engine.io.output_set(1, false).unwrap();
+        engine.io.output_set(2, true).unwrap();
+        sim.set_temperature(0, 85.0).unwrap();
+        engine.evaluate(ms(10));
+        assert_eq!(outputs(&mut engine), [true, true, true]);
+
+        // the alarm stays set, the pulse ends after its duration
+        engine.io.output_set(1, true).unwrap();
+        sim.set_temperature(0, 20.0).unwrap();
+        engine.evaluate(ms(50));
+        assert_eq!(outputs(&mut engine), [false, true, true]);
+        engine.evaluate(ms(110));
+        assert_eq!(outputs(&mut engine), [false, true, false]);
+
+        let invalid: RuleSet = "[[rules]]\nwhen = \"DI0\"\ntarget = \"TMP3\"\n"
+            .parse()
+            .unwrap();
+        let io = IoBuilder::new().build();
+        assert!(Engine::new(io, &invalid).is_err());
+    }
+}
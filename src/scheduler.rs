@@ -0,0 +1,539 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Cyclic tasks for deterministic control. Every task runs in its own thread and is released on
+// absolute deadlines of the monotonic clock (`clock_nanosleep` with TIMER_ABSTIME), so the
+// period does not drift with the execution time of the task. Threads can run with SCHED_FIFO
+// priority and be pinned to CPUs, `lock_memory` prevents page faults of the whole process.
+//
+// The wake-up latency (jitter) and the overruns of all tasks of this process are recorded and
+// can be queried with `tasks()`. A task overruns if it is still running at its next release,
+// the `Overrun` policy decides how it continues.
+//
+// The sampling threads (iio, lm-sensors) run as tasks, too. Their priority and CPUs are taken
+// from the environment variables IO_SCHED_PRIORITY and IO_SCHED_CPUS (e.g. "2,3"), they fall back
+// to the default scheduling if these cannot be applied.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::health;
+
+/// Behaviour of a task which is still running at its next release
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Overrun {
+    /// Skip the missed releases and continue with the next one in the future
+    Skip,
+    /// Run the missed releases immediately one after the other
+    CatchUp,
+    /// Stop the task
+    Fault,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskConfig {
+    pub name: String,
+    pub period: Duration,
+    /// SCHED_FIFO priority (1..99), the default scheduling policy is used if unset
+    pub priority: Option<i32>,
+    /// CPUs the task is pinned to, all CPUs if empty
+    pub cpus: Vec<usize>,
+    pub overrun: Overrun,
+}
+
+impl TaskConfig {
+    pub fn new(name: &str, period: Duration) -> TaskConfig {
+        TaskConfig {
+            name: name.to_string(),
+            period,
+            priority: None,
+            cpus: vec![],
+            overrun: Overrun::Skip,
+        }
+    }
+
+    /// Configuration with priority and CPUs from IO_SCHED_PRIORITY and IO_SCHED_CPUS
+    pub fn from_env(name: &str, period: Duration) -> TaskConfig {
+        let mut config = TaskConfig::new(name, period);
+        if let Ok(priority) = std::env::var("IO_SCHED_PRIORITY") {
+            match priority.trim().parse() {
+                Ok(priority) => config.priority = Some(priority),
+                Err(_) => warn!("Invalid IO_SCHED_PRIORITY: {}", priority),
+            }
+        }
+        if let Ok(cpus) = std::env::var("IO_SCHED_CPUS") {
+            match cpus.split(',').map(|cpu| cpu.trim().parse()).collect() {
+                Ok(cpus) => config.cpus = cpus,
+                Err(_) => warn!("Invalid IO_SCHED_CPUS: {}", cpus),
+            }
+        }
+        config
+    }
+
+    pub fn priority(mut self, priority: i32) -> TaskConfig {
+        self.priority = Some(priority);
+        self
+    }
+
+    pub fn cpus(mut self, cpus: &[usize]) -> TaskConfig {
+        self.cpus = cpus.to_vec();
+        self
+    }
+
+    pub fn overrun(mut self, overrun: Overrun) -> TaskConfig {
+        self.overrun = overrun;
+        self
+    }
+
+    /// Start a thread running `f`, which calls `Task::wait` to wait for each release. Fails if
+    /// the priority or CPU affinity cannot be applied.
+    pub fn spawn<F>(self, f: F) -> Result<TaskHandle>
+    where
+        F: FnOnce(&mut Task) + Send + 'static,
+    {
+        self.spawn_thread(f, true)
+    }
+
+    /// Like `spawn`, but the task runs with the default scheduling if the priority or CPU
+    /// affinity cannot be applied (e.g. without CAP_SYS_NICE or with an offline CPU).
+    pub fn spawn_or_fallback<F>(self, f: F) -> Result<TaskHandle>
+    where
+        F: FnOnce(&mut Task) + Send + 'static,
+    {
+        self.spawn_thread(f, false)
+    }
+
+    fn spawn_thread<F>(self, f: F, strict: bool) -> Result<TaskHandle>
+    where
+        F: FnOnce(&mut Task) + Send + 'static,
+    {
+        if self.period == Duration::from_secs(0) {
+            return Err(Error::InvalidParameter);
+        }
+
+        let stats = Arc::new(Mutex::new(TaskStats::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (tx, rx) = mpsc::channel();
+
+        let name = self.name.clone();
+        let mut task = Task {
+            config: self,
+            release: None,
+            woken: Duration::from_secs(0),
+            stats: Arc::clone(&stats),
+            stop: Arc::clone(&stop),
+        };
+        let thread = thread::Builder::new().name(name.clone()).spawn(move || {
+            let mut result = apply_attributes(&task.config);
+            if !strict && result.is_err() {
+                warn!("{}: using the default scheduling", task.config.name);
+                result = Ok(());
+            }
+            let failed = result.is_err();
+            tx.send(result).ok();
+            if !failed {
+                task.register();
+                f(&mut task);
+            }
+        })?;
+
+        rx.recv().map_err(|_| Error::GenericError)??;
+        Ok(TaskHandle {
+            name,
+            stats,
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+/// Timing statistics of a task, the jitter is the delay of the wake-up after the release
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskStats {
+    pub cycles: u64,
+    pub overruns: u64,
+    /// Releases skipped due to overruns
+    pub skipped: u64,
+    /// The task was stopped by the overrun policy `Fault`
+    pub faulted: bool,
+    pub jitter_min: Duration,
+    pub jitter_max: Duration,
+    jitter_sum: Duration,
+    /// Execution time of the last cycle
+    pub exec_time: Duration,
+    pub exec_time_max: Duration,
+}
+
+impl TaskStats {
+    pub fn jitter_avg(&self) -> Duration {
+        if self.cycles == 0 {
+            Duration::from_secs(0)
+        } else {
+            self.jitter_sum / self.cycles as u32
+        }
+    }
+
+    fn record_release(&mut self, jitter: Duration) {
+        if self.cycles == 0 || jitter < self.jitter_min {
+            self.jitter_min = jitter;
+        }
+        self.jitter_max = self.jitter_max.max(jitter);
+        self.jitter_sum += jitter;
+        self.cycles += 1;
+    }
+
+    fn record_exec_time(&mut self, exec_time: Duration) {
+        self.exec_time = exec_time;
+        self.exec_time_max = self.exec_time_max.max(exec_time);
+    }
+}
+
+lazy_static! {
+    static ref TASKS: Mutex<BTreeMap<String, Arc<Mutex<TaskStats>>>> = Mutex::new(BTreeMap::new());
+}
+
+/// Statistics of all running tasks of this process, sorted by name
+pub fn tasks() -> BTreeMap<String, TaskStats> {
+    TASKS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(name, stats)| (name.clone(), stats.lock().unwrap().clone()))
+        .collect()
+}
+
+/// Lock all current and future pages of the process into memory
+pub fn lock_memory() -> Result<()> {
+    if unsafe { libc::mlockall(libc::MCL_CURRENT | libc::MCL_FUTURE) } != 0 {
+        let err = io::Error::last_os_error();
+        error!("Failed to lock memory: {}", err);
+        return Err(Error::AccessFailed(err));
+    }
+    Ok(())
+}
+
+/// Apply priority and CPU affinity to the current thread
+fn apply_attributes(config: &TaskConfig) -> Result<()> {
+    if let Some(priority) = config.priority {
+        let (min, max) = unsafe {
+            (
+                libc::sched_get_priority_min(libc::SCHED_FIFO),
+                libc::sched_get_priority_max(libc::SCHED_FIFO),
+            )
+        };
+        if priority < min || priority > max {
+            error!(
+                "{}: priority must be in the range {}..{}",
+                config.name, min, max
+            );
+            return Err(Error::InvalidParameter);
+        }
+
+        let param = libc::sched_param {
+            sched_priority: priority,
+        };
+        let result =
+            unsafe { libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param) };
+        if result != 0 {
+            let err = io::Error::from_raw_os_error(result);
+            error!("{}: failed to set priority: {}", config.name, err);
+            return Err(Error::AccessFailed(err));
+        }
+    }
+
+    if !config.cpus.is_empty() {
+        let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+        for &cpu in &config.cpus {
+            if cpu >= libc::CPU_SETSIZE as usize {
+                return Err(Error::InvalidParameter);
+            }
+            unsafe { libc::CPU_SET(cpu, &mut set) };
+        }
+        if unsafe { libc::sched_setaffinity(0, mem::size_of::<libc::cpu_set_t>(), &set) } != 0 {
+            let err = io::Error::last_os_error();
+            error!("{}: failed to set CPU affinity: {}", config.name, err);
+            return Err(Error::AccessFailed(err));
+        }
+    }
+
+    Ok(())
+}
+
+/// Current time of the monotonic clock
+fn monotonic_now() -> Duration {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) };
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+/// Sleep until the absolute time `deadline` of the monotonic clock
+fn sleep_until(deadline: Duration) {
+    let ts = libc::timespec {
+        tv_sec: deadline.as_secs() as libc::time_t,
+        tv_nsec: deadline.subsec_nanos() as libc::c_long,
+    };
+    loop {
+        let result = unsafe {
+            libc::clock_nanosleep(
+                libc::CLOCK_MONOTONIC,
+                libc::TIMER_ABSTIME,
+                &ts,
+                ptr::null_mut(),
+            )
+        };
+        if result != libc::EINTR {
+            break;
+        }
+    }
+}
+
+/// Next release after `release` if the task is ready at `now`, and the number of skipped
+/// releases. `None` if the task faulted.
+fn next_release(
+    release: Duration,
+    now: Duration,
+    period: Duration,
+    overrun: Overrun,
+) -> Option<(Duration, u64)> {
+    let next = release + period;
+    if now <= next {
+        return Some((next, 0));
+    }
+    match overrun {
+        Overrun::Skip => {
+            let skipped = ((now - next).as_nanos() / period.as_nanos()) as u64 + 1;
+            Some((next + period * skipped as u32, skipped))
+        }
+        Overrun::CatchUp => Some((next, 0)),
+        Overrun::Fault => None,
+    }
+}
+
+/// A running task, passed to the function of the task
+pub struct Task {
+    config: TaskConfig,
+    /// Current release
+    release: Option<Duration>,
+    /// Wake-up time of the current cycle
+    woken: Duration,
+    stats: Arc<Mutex<TaskStats>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Task {
+    fn register(&self) {
+        let mut tasks = TASKS.lock().unwrap();
+        if tasks.contains_key(&self.config.name) {
+            warn!("Task {} is already running", self.config.name);
+        }
+        tasks.insert(self.config.name.clone(), Arc::clone(&self.stats));
+    }
+
+    /// Wait for the next release. Returns false if the task has to finish, because it was
+    /// stopped or faulted. The first release is immediately.
+    pub fn wait(&mut self) -> bool {
+        if self.stop.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        let now = monotonic_now();
+        let release = match self.release {
+            None => now,
+            Some(release) => {
+                let mut stats = self.stats.lock().unwrap();
+                stats.record_exec_time(now.saturating_sub(self.woken));
+                if now > release + self.config.period {
+                    stats.overruns += 1;
+                }
+
+                match next_release(release, now, self.config.period, self.config.overrun) {
+                    Some((next, skipped)) => {
+                        if skipped > 0 {
+                            warn!("{}: skipped {} releases", self.config.name, skipped);
+                            stats.skipped += skipped;
+                            health::sampler_missed_intervals(skipped);
+                        }
+                        drop(stats);
+                        sleep_until(next);
+                        next
+                    }
+                    None => {
+                        error!("{}: overrun, stopping task", self.config.name);
+                        stats.faulted = true;
+                        return false;
+                    }
+                }
+            }
+        };
+
+        self.woken = monotonic_now();
+        self.release = Some(release);
+        self.stats
+            .lock()
+            .unwrap()
+            .record_release(self.woken.saturating_sub(release));
+
+        !self.stop.load(Ordering::Relaxed)
+    }
+
+    /// Time since the current release
+    pub fn elapsed(&self) -> Duration {
+        self.release
+            .map(|release| monotonic_now().saturating_sub(release))
+            .unwrap_or_default()
+    }
+
+    pub fn config(&self) -> &TaskConfig {
+        &self.config
+    }
+
+    pub fn stats(&self) -> TaskStats {
+        self.stats.lock().unwrap().clone()
+    }
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        let mut tasks = TASKS.lock().unwrap();
+        if tasks
+            .get(&self.config.name)
+            .is_some_and(|stats| Arc::ptr_eq(stats, &self.stats))
+        {
+            tasks.remove(&self.config.name);
+        }
+    }
+}
+
+/// Handle of a spawned task. Like `thread::JoinHandle`, dropping it detaches the task.
+pub struct TaskHandle {
+    name: String,
+    stats: Arc<Mutex<TaskStats>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TaskHandle {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn stats(&self) -> TaskStats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// Stop the task at its next release and wait for it to finish
+    pub fn stop(mut self) -> Result<()> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.thread.take() {
+            Some(thread) => thread.join().map_err(|_| Error::GenericError),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn next_release_test() {
+        let period = 10 * MS;
+        let release = 100 * MS;
+        for &overrun in [Overrun::Skip, Overrun::CatchUp, Overrun::Fault].iter() {
+            assert_eq!(
+                next_release(release, 104 * MS, period, overrun),
+                Some((110 * MS, 0))
+            );
+            assert_eq!(
+                next_release(release, 110 * MS, period, overrun),
+                Some((110 * MS, 0))
+            );
+        }
+
+        assert_eq!(
+            next_release(release, 111 * MS, period, Overrun::Skip),
+            Some((120 * MS, 1))
+        );
+        assert_eq!(
+            next_release(release, 135 * MS, period, Overrun::Skip),
+            Some((140 * MS, 3))
+        );
+        assert_eq!(
+            next_release(release, 135 * MS, period, Overrun::CatchUp),
+            Some((110 * MS, 0))
+        );
+        assert_eq!(
+            next_release(release, 135 * MS, period, Overrun::Fault),
+            None
+        );
+    }
+
+    #[test]
+    fn task_test() {
+        let (tx, rx) = mpsc::channel();
+        let task = TaskConfig::new("task_test", 2 * MS)
+            .spawn(move |task| {
+                while task.wait() {
+                    tx.send(()).ok();
+                }
+            })
+            .unwrap();
+        for _ in 0..5 {
+            rx.recv().unwrap();
+        }
+        assert!(tasks().contains_key("task_test"));
+
+        let stats = task.stats();
+        assert!(stats.cycles >= 5);
+        assert!(stats.jitter_min <= stats.jitter_avg() && stats.jitter_avg() <= stats.jitter_max);
+        task.stop().unwrap();
+        assert!(!tasks().contains_key("task_test"));
+
+        // the task stops at the first overrun
+        let task = TaskConfig::new("task_fault_test", 2 * MS)
+            .overrun(Overrun::Fault)
+            .spawn(|task| {
+                while task.wait() {
+                    thread::sleep(5 * MS);
+                }
+            })
+            .unwrap();
+        thread::sleep(20 * MS);
+        let stats = task.stats();
+        assert!(stats.faulted);
+        assert_eq!((stats.cycles, stats.overruns), (1, 1));
+        task.stop().unwrap();
+
+        let invalid = TaskConfig::new("task_invalid_test", 2 * MS).priority(100);
+        assert!(matches!(
+            invalid.spawn(|_| {}),
+            Err(Error::InvalidParameter)
+        ));
+
+        // the task runs anyway with the fallback
+        let (tx, rx) = mpsc::channel();
+        let task = TaskConfig::new("task_fallback_test", 2 * MS)
+            .priority(100)
+            .spawn_or_fallback(move |task| {
+                if task.wait() {
+                    tx.send(()).ok();
+                }
+            })
+            .unwrap();
+        rx.recv().unwrap();
+        task.stop().unwrap();
+    }
+}
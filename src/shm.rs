@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: LGPL-3.0-or-later
 // SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
 
+use std::collections::BTreeMap;
 use std::mem;
+use std::time::{Duration, UNIX_EPOCH};
 
 use raw_sync::events::{EventImpl, EventInit, EventState};
 use raw_sync::locks::{LockGuard, LockImpl, LockInit, Mutex};
@@ -9,6 +11,8 @@ use raw_sync::Timeout;
 use shared_memory_extended::{Shmem, ShmemConf};
 
 use crate::ffi;
+use crate::health::SamplerStats;
+use crate::scheduler::TaskConfig;
 
 #[derive(Debug)]
 pub enum Event {
@@ -19,6 +23,7 @@ pub enum Event {
 pub enum Channels {
     AnalogInput(Vec<usize>),
     TempInput(Vec<usize>),
+    DigitalInput(Vec<usize>),
 }
 
 pub struct Group {
@@ -37,6 +42,8 @@ pub const GLOBAL_LOCK_ID: usize = 0;
 pub const DAEMON_EVT_ID: usize = 0;
 pub const CLIENT_EVT_ID: usize = 1;
 pub const NUM_CHANNELS_PER_TYPE: usize = 32;
+pub const NUM_SAMPLERS: usize = 8;
+const SAMPLER_NAME_LEN: usize = 24;
 
 struct ShmImage {
     _mem: Shmem,
@@ -48,6 +55,12 @@ struct ShmImage {
     analog_config_offset: usize,
     temperature_config_offset: usize,
 
+    // Server -> Monitoring
+    health_offset: usize,
+
+    // Server <-> Client
+    digital_offset: usize,
+
     // lock for all data inside the image
     mutex: Box<dyn LockImpl>,
     server_event: Box<dyn EventImpl>,
@@ -60,6 +73,8 @@ struct ShmImageGuard<'g> {
     temperature_values_offset: usize,
     analog_config_offset: usize,
     temperature_config_offset: usize,
+    health_offset: usize,
+    digital_offset: usize,
 }
 
 impl ShmImage {
@@ -70,6 +85,29 @@ impl ShmImage {
             .writable(true)
     }
 
+    /// Offset of data of type `T` appended at `end`. The sampler health and the digital channels
+    /// are appended after the events to keep the layout of older clients (e.g. statically linked
+    /// ones).
+    fn append_offset<T>(end: usize) -> Result<usize, Box<dyn std::error::Error>> {
+        let start = end.next_multiple_of(mem::align_of::<T>());
+        if start + mem::size_of::<T>() > SHM_SIZE {
+            return Err("shared memory too small".into());
+        }
+        Ok(start)
+    }
+
+    /// Offsets of the sampler health and the digital channels
+    fn appended_offsets(
+        events_end: *mut u8,
+        ptr_image: *mut u8,
+    ) -> Result<(usize, usize), Box<dyn std::error::Error>> {
+        let health_start =
+            Self::append_offset::<SamplerHealths>(events_end as usize - ptr_image as usize)?;
+        let digital_start =
+            Self::append_offset::<Digital>(health_start + mem::size_of::<SamplerHealths>())?;
+        Ok((health_start, digital_start))
+    }
+
     fn create() -> Result<Self, Box<dyn std::error::Error>> {
         let _ = std::fs::remove_file(FLINK_PATH);
         let mem = Self::config().create()?;
@@ -91,15 +129,20 @@ impl ShmImage {
 
         ptr = ptr.wrapping_add(server_event_len);
 
-        let (client_event, _client_event_len) = unsafe { raw_sync::events::Event::new(ptr, true) }?;
+        let (client_event, client_event_len) = unsafe { raw_sync::events::Event::new(ptr, true) }?;
         server_event.set(EventState::Clear)?;
 
+        let (health_start, digital_start) =
+            Self::appended_offsets(ptr.wrapping_add(client_event_len), ptr_image)?;
+
         Ok(Self {
             _mem: mem,
             analog_values_offset: analog_values_end,
             temperature_values_offset: temperature_values_end,
             analog_config_offset: analog_config_end,
             temperature_config_offset: temp_config_end,
+            health_offset: health_start,
+            digital_offset: digital_start,
             mutex,
             server_event,
             client_event,
@@ -127,16 +170,21 @@ impl ShmImage {
 
         ptr = ptr.wrapping_add(server_event_len);
 
-        let (client_event, _client_event_len) =
+        let (client_event, client_event_len) =
             unsafe { raw_sync::events::Event::from_existing(ptr) }?;
         server_event.set(EventState::Clear)?;
 
+        let (health_start, digital_start) =
+            Self::appended_offsets(ptr.wrapping_add(client_event_len), ptr_image)?;
+
         Ok(Self {
             _mem: mem,
             analog_values_offset: analog_values_end,
             temperature_values_offset: temperature_values_end,
             analog_config_offset: analog_config_end,
             temperature_config_offset: temp_config_end,
+            health_offset: health_start,
+            digital_offset: digital_start,
             server_event,
             client_event,
             mutex,
@@ -150,6 +198,8 @@ impl ShmImage {
             temperature_values_offset: self.temperature_values_offset,
             analog_config_offset: self.analog_config_offset,
             temperature_config_offset: self.temperature_config_offset,
+            health_offset: self.health_offset,
+            digital_offset: self.digital_offset,
         }
     }
 }
@@ -178,6 +228,37 @@ impl<'g> ShmImageGuard<'g> {
             (*self.guard).wrapping_add(self.temperature_config_offset) as *mut TemperatureConfigs;
         unsafe { (*values).get_mut(index) }.expect("invalid TMP channel")
     }
+
+    fn health(&mut self) -> &mut SamplerHealths {
+        let values = (*self.guard).wrapping_add(self.health_offset) as *mut SamplerHealths;
+        unsafe { &mut *values }
+    }
+
+    fn digital(&mut self) -> &mut Digital {
+        let values = (*self.guard).wrapping_add(self.digital_offset) as *mut Digital;
+        unsafe { &mut *values }
+    }
+
+    fn digital_input_value(&mut self, index: usize) -> &mut u8 {
+        self.digital()
+            .input_values
+            .get_mut(index)
+            .expect("invalid DI channel")
+    }
+
+    fn digital_output_value(&mut self, index: usize) -> &mut u8 {
+        self.digital()
+            .output_values
+            .get_mut(index)
+            .expect("invalid DO channel")
+    }
+
+    fn digital_output_cfg(&mut self, index: usize) -> &mut Config<u8> {
+        self.digital()
+            .output_configs
+            .get_mut(index)
+            .expect("invalid DO channel")
+    }
 }
 
 pub struct ShmServer {
@@ -235,6 +316,38 @@ impl<'g> ShmServerGuard<'g> {
     pub fn temperature_cfg_set_confirm(&mut self, index: usize) {
         *self.0.temperature_cfg(index) = Config::Keep
     }
+
+    /// Publish the sampler statistics of the daemon, at most `NUM_SAMPLERS` are stored
+    pub fn health_set(&mut self, samplers: &BTreeMap<String, SamplerStats>) {
+        let health = self.0.health();
+        let mut entries = samplers.iter();
+        for entry in health.iter_mut() {
+            *entry = match entries.next() {
+                Some((name, stats)) => SamplerHealth::new(name, stats),
+                None => SamplerHealth::default(),
+            }
+        }
+    }
+
+    pub fn digital_input_set(&mut self, index: usize, value: bool) {
+        *self.0.digital_input_value(index) = value as u8
+    }
+
+    pub fn digital_output_set(&mut self, index: usize, value: bool) {
+        *self.0.digital_output_value(index) = value as u8
+    }
+
+    /// Value of an output requested by a client
+    pub fn digital_output_cfg_get(&mut self, index: usize) -> Config<bool> {
+        match *self.0.digital_output_cfg(index) {
+            Config::Keep => Config::Keep,
+            Config::Change(value) => Config::Change(value != 0),
+        }
+    }
+
+    pub fn digital_output_cfg_set_confirm(&mut self, index: usize) {
+        *self.0.digital_output_cfg(index) = Config::Keep
+    }
 }
 
 pub struct ShmClient {
@@ -289,6 +402,31 @@ impl<'g> ShmClientGuard<'g> {
     ) {
         *self.0.temperature_cfg(index) = Config::Change(TmpConfig(mode, sensor_type))
     }
+
+    /// Sampler statistics published by the daemon
+    pub fn health_get(&mut self) -> BTreeMap<String, SamplerStats> {
+        self.0
+            .health()
+            .iter()
+            .filter_map(SamplerHealth::get)
+            .collect()
+    }
+
+    pub fn digital_input_get(&mut self, index: usize) -> bool {
+        *self.0.digital_input_value(index) != 0
+    }
+
+    /// Value of an output, a value not yet applied by the daemon is returned as well
+    pub fn digital_output_get(&mut self, index: usize) -> bool {
+        match *self.0.digital_output_cfg(index) {
+            Config::Keep => *self.0.digital_output_value(index) != 0,
+            Config::Change(value) => value != 0,
+        }
+    }
+
+    pub fn digital_output_cfg_set(&mut self, index: usize, value: bool) {
+        *self.0.digital_output_cfg(index) = Config::Change(value as u8)
+    }
 }
 
 #[repr(u8)]
@@ -306,3 +444,86 @@ type AnalogInputValues = [i64; NUM_CHANNELS_PER_TYPE];
 type TemperatureValues = [f64; NUM_CHANNELS_PER_TYPE];
 type AnalogInputConfigs = [Config<ffi::IoAnalogMode>; NUM_CHANNELS_PER_TYPE];
 type TemperatureConfigs = [Config<TmpConfig>; NUM_CHANNELS_PER_TYPE];
+type SamplerHealths = [SamplerHealth; NUM_SAMPLERS];
+
+/// Digital channels of the daemon (e.g. GPIO lines, which can be requested by one process only)
+#[repr(C)]
+struct Digital {
+    input_values: [u8; NUM_CHANNELS_PER_TYPE],
+    output_values: [u8; NUM_CHANNELS_PER_TYPE],
+    output_configs: [Config<u8>; NUM_CHANNELS_PER_TYPE],
+}
+
+/// Sampler statistics as stored in the shared memory, an empty name marks an unused entry
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+struct SamplerHealth {
+    name: [u8; SAMPLER_NAME_LEN],
+    cycles: u64,
+    read_errors: u64,
+    missed_intervals: u64,
+    cycle_time_us: u64,
+    /// Milliseconds since the UNIX epoch, 0 if there was no cycle yet
+    last_cycle_ms: u64,
+}
+
+impl SamplerHealth {
+    fn new(name: &str, stats: &SamplerStats) -> SamplerHealth {
+        let mut health = SamplerHealth {
+            name: [0; SAMPLER_NAME_LEN],
+            cycles: stats.cycles,
+            read_errors: stats.read_errors,
+            missed_intervals: stats.missed_intervals,
+            cycle_time_us: stats.cycle_time.as_micros() as u64,
+            last_cycle_ms: stats
+                .last_cycle
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |time| time.as_millis() as u64),
+        };
+        let length = name.len().min(SAMPLER_NAME_LEN);
+        health.name[..length].copy_from_slice(&name.as_bytes()[..length]);
+        health
+    }
+
+    fn get(&self) -> Option<(String, SamplerStats)> {
+        let length = self
+            .name
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(SAMPLER_NAME_LEN);
+        if length == 0 {
+            return None;
+        }
+        let stats = SamplerStats {
+            cycles: self.cycles,
+            read_errors: self.read_errors,
+            missed_intervals: self.missed_intervals,
+            cycle_time: Duration::from_micros(self.cycle_time_us),
+            last_cycle: match self.last_cycle_ms {
+                0 => None,
+                ms => Some(UNIX_EPOCH + Duration::from_millis(ms)),
+            },
+        };
+        Some((
+            String::from_utf8_lossy(&self.name[..length]).into_owned(),
+            stats,
+        ))
+    }
+}
+
+/// Notifier for channels without an own sampler (e.g. GPIO lines), which signals an update every
+/// `period`
+pub fn ticker(name: &str, period: Duration) -> crossbeam_channel::Receiver<Event> {
+    let (tx, notifier) = crossbeam_channel::unbounded();
+    let task = TaskConfig::from_env(name, period).spawn_or_fallback(move |task| {
+        while task.wait() {
+            if tx.send(Event::Update).is_err() {
+                break;
+            }
+        }
+    });
+    if let Err(err) = task {
+        error!("{}: failed to start ticker: {}", name, err);
+    }
+    notifier
+}
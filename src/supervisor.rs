@@ -0,0 +1,598 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Software watchdog for multiple processes. Only one process can own the hardware watchdog, so
+// iodaemon owns it and services it on behalf of its clients. Clients register via a Unix socket
+// with their own timeout and send heartbeats. If a client misses its timeout (or its connection
+// is closed without unregistering, e.g. because it crashed), the configured action is applied:
+//
+//   log         only log the failure
+//   safe_state  switch the outputs of the `[safe_state]` section to their safe values
+//   restart     restart the systemd service of the client
+//   reset       stop servicing the hardware watchdog, so the device is reset
+//
+// The failed client is removed after the action was applied, a restarted client registers again.
+// As long as no client failed with the action "reset", the hardware watchdog is serviced.
+//
+// Protocol (one command per line, each is answered with "OK" or "ERR <reason>"):
+//
+//   REGISTER <name> <timeout in ms>
+//   HEARTBEAT
+//   UNREGISTER
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::process::Command;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ini::Ini;
+
+use crate::error::{Error, Result};
+use crate::io::wdg_dev;
+use crate::rules::{self, Channel};
+use crate::systemd;
+use crate::{definition, hw_rev, Io, Watchdog};
+
+pub const SOCKET_PATH: &str = "/run/iodaemon-watchdog.sock";
+
+/// Interval of the client timeout checks
+const CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Action applied on the failure of a client
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Action {
+    Log,
+    SafeState,
+    Restart,
+    Reset,
+}
+
+impl FromStr for Action {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Action> {
+        match s.trim() {
+            "log" => Ok(Action::Log),
+            "safe_state" => Ok(Action::SafeState),
+            "restart" => Ok(Action::Restart),
+            "reset" => Ok(Action::Reset),
+            _ => Err(Error::InvalidParameter),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientConfig {
+    pub action: Action,
+    /// systemd unit restarted by `Action::Restart`, "<name>.service" if unset
+    pub service: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub enable: bool,
+    /// Hardware watchdog device, clients are only supervised if unset
+    pub device: Option<String>,
+    /// Timeout of the hardware watchdog, the driver default is used if unset
+    pub timeout: Option<Duration>,
+    pub socket: String,
+    /// Action for clients without own configuration
+    pub action: Action,
+    /// Outputs (label or do0, do1, ...) and their values applied by `Action::SafeState`
+    pub safe_state: Vec<(String, bool)>,
+    pub clients: BTreeMap<String, ClientConfig>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            enable: false,
+            device: Some("/dev/watchdog0".to_string()),
+            timeout: None,
+            socket: SOCKET_PATH.to_string(),
+            action: Action::Reset,
+            safe_state: Vec::new(),
+            clients: BTreeMap::new(),
+        }
+    }
+}
+
+fn parse<T: FromStr>(value: Option<&String>, default: T) -> Result<T> {
+    match value {
+        Some(value) => value.trim().parse().map_err(|_| Error::InvalidParameter),
+        None => Ok(default),
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool> {
+    match value.trim() {
+        "1" | "true" | "on" => Ok(true),
+        "0" | "false" | "off" => Ok(false),
+        _ => Err(Error::InvalidParameter),
+    }
+}
+
+impl Config {
+    /// Load the configuration from an INI file with the sections "watchdog", "safe_state" and
+    /// "client <name>"
+    pub fn load(path: &str) -> Result<Config> {
+        let ini = Ini::load_from_file(path).map_err(|e| {
+            error!("Failed to load {}: {}", path, e);
+            Error::InvalidParameter
+        })?;
+        Config::from_ini(&ini)
+    }
+
+    pub fn from_ini(ini: &Ini) -> Result<Config> {
+        let mut config = Config::default();
+
+        if let Some(watchdog) = ini.section(Some("watchdog")) {
+            config.enable = match watchdog.get("enable") {
+                Some(enable) => parse_bool(enable)?,
+                None => config.enable,
+            };
+            if let Some(device) = watchdog.get("device") {
+                config.device = Some(device.trim().to_string()).filter(|d| !d.is_empty());
+            }
+            config.timeout = match parse(watchdog.get("timeout"), 0)? {
+                0 => None,
+                seconds => Some(Duration::from_secs(seconds)),
+            };
+            config.socket = watchdog
+                .get("socket")
+                .map_or(config.socket, |socket| socket.trim().to_string());
+            config.action = parse(watchdog.get("action"), config.action)?;
+        }
+
+        if let Some(safe_state) = ini.section(Some("safe_state")) {
+            for (output, value) in safe_state.iter() {
+                config
+                    .safe_state
+                    .push((output.trim().to_string(), parse_bool(value)?));
+            }
+        }
+
+        for (name, section) in ini.iter() {
+            if let Some(client) = name.as_ref().and_then(|name| name.strip_prefix("client ")) {
+                let client_config = ClientConfig {
+                    action: parse(section.get("action"), config.action)?,
+                    service: section.get("service").map(|s| s.trim().to_string()),
+                };
+                config
+                    .clients
+                    .insert(client.trim().to_string(), client_config);
+            }
+        }
+
+        Ok(config)
+    }
+
+    fn client(&self, name: &str) -> ClientConfig {
+        self.clients.get(name).cloned().unwrap_or(ClientConfig {
+            action: self.action,
+            service: None,
+        })
+    }
+}
+
+/// Switches outputs to their safe values, see `Action::SafeState`
+pub type SafeState = Box<dyn FnMut(&[(String, bool)]) -> Result<()> + Send>;
+
+/// Safe state handler using the device definition of the device. It is loaded on the first
+/// failure, outputs requested by other processes can not be switched.
+pub fn device_safe_state() -> SafeState {
+    let mut device: Option<Io> = None;
+
+    Box::new(move |outputs| {
+        if device.is_none() {
+            let mut io = definition::load_device_definition(
+                &hw_rev::get_device_name().unwrap_or("fallback".to_string()),
+            );
+            io.init()?;
+            device = Some(io);
+        }
+        let io = device.as_mut().unwrap();
+
+        for (output, value) in outputs {
+            let result = match rules::resolve_channel(io, output) {
+                Some(Channel::Output(index)) => io.output_set(index, *value),
+                Some(Channel::RunLed) => io.set_run_led(*value),
+                Some(Channel::ErrLed) => io.set_err_led(*value),
+                _ => Err(Error::InvalidChannel),
+            };
+            if let Err(e) = result {
+                error!("Failed to set {} to its safe state: {}", output, e);
+            }
+        }
+        Ok(())
+    })
+}
+
+#[derive(Debug)]
+struct Client {
+    name: String,
+    timeout: Duration,
+    last_heartbeat: Instant,
+    /// The connection was closed without unregistering
+    closed: bool,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    clients: BTreeMap<u64, Client>,
+}
+
+impl State {
+    fn register(&mut self, id: u64, name: &str, timeout: Duration) -> Result<()> {
+        if name.is_empty() || timeout == Duration::from_secs(0) {
+            return Err(Error::InvalidParameter);
+        }
+        if self.clients.values().any(|client| client.name == name) {
+            return Err(Error::InvalidParameter);
+        }
+        self.clients.insert(
+            id,
+            Client {
+                name: name.to_string(),
+                timeout,
+                last_heartbeat: Instant::now(),
+                closed: false,
+            },
+        );
+        Ok(())
+    }
+
+    fn heartbeat(&mut self, id: u64) -> Result<()> {
+        match self.clients.get_mut(&id) {
+            Some(client) => {
+                client.last_heartbeat = Instant::now();
+                Ok(())
+            }
+            // not registered or already removed due to a timeout
+            None => Err(Error::WatchdogTimeout),
+        }
+    }
+
+    /// Remove and return the names of the failed clients
+    fn check(&mut self, now: Instant) -> Vec<String> {
+        let mut failed = Vec::new();
+        self.clients.retain(|_, client| {
+            let expired = client.closed
+                || now.saturating_duration_since(client.last_heartbeat) > client.timeout;
+            if expired {
+                failed.push(client.name.clone());
+            }
+            !expired
+        });
+        failed
+    }
+}
+
+fn handle_command(state: &Mutex<State>, id: u64, line: &str) -> Result<()> {
+    let mut words = line.split_whitespace();
+    let mut state = state.lock().unwrap();
+
+    match (words.next(), words.next(), words.next()) {
+        (Some("REGISTER"), Some(name), Some(timeout)) => {
+            let timeout = timeout.parse().map_err(|_| Error::InvalidParameter)?;
+            state.register(id, name, Duration::from_millis(timeout))?;
+            info!("Watchdog client {} registered ({} ms)", name, timeout);
+            Ok(())
+        }
+        (Some("HEARTBEAT"), None, None) => state.heartbeat(id),
+        (Some("UNREGISTER"), None, None) => match state.clients.remove(&id) {
+            Some(client) => {
+                info!("Watchdog client {} unregistered", client.name);
+                Ok(())
+            }
+            None => Err(Error::WatchdogTimeout),
+        },
+        _ => Err(Error::InvalidParameter),
+    }
+}
+
+fn serve_connection(state: &Mutex<State>, id: u64, stream: UnixStream) -> Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let response = match handle_command(state, id, &line?) {
+            Ok(()) => "OK".to_string(),
+            Err(e) => format!("ERR {}", e),
+        };
+        writeln!(writer, "{}", response)?;
+    }
+    Ok(())
+}
+
+fn listen(listener: UnixListener, state: Arc<Mutex<State>>) {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                error!("Failed to accept watchdog client: {}", e);
+                continue;
+            }
+        };
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let state = Arc::clone(&state);
+
+        let spawned = thread::Builder::new()
+            .name(format!("wdg-client{}", id))
+            .spawn(move || {
+                if let Err(e) = serve_connection(&state, id, stream) {
+                    debug!("Watchdog client connection failed: {}", e);
+                }
+                if let Some(client) = state.lock().unwrap().clients.get_mut(&id) {
+                    client.closed = true;
+                }
+            });
+        if let Err(e) = spawned {
+            error!("Failed to spawn watchdog client thread: {}", e);
+        }
+    }
+}
+
+/// Owner of the hardware watchdog, stops supervising when dropped
+pub struct Supervisor {
+    running: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+    socket: String,
+}
+
+impl Supervisor {
+    pub fn start(config: Config, safe_state: SafeState) -> Result<Supervisor> {
+        let mut watchdog = match &config.device {
+            Some(device) => {
+                let mut watchdog = wdg_dev::Wdg::new(device.as_str());
+                if let Some(timeout) = config.timeout {
+                    watchdog.set_timeout(timeout)?;
+                }
+                watchdog.enable(false)?;
+                Some(watchdog)
+            }
+            None => None,
+        };
+        let service_interval = match watchdog.as_mut().map(|w| w.timeout()) {
+            Some(Ok(timeout)) => (timeout / 4).max(CHECK_INTERVAL),
+            _ => Duration::from_secs(1),
+        };
+
+        let listener = match systemd::take_listener(&config.socket) {
+            Some(listener) => listener,
+            None => {
+                fs::remove_file(&config.socket).ok();
+                UnixListener::bind(&config.socket)?
+            }
+        };
+        let state = Arc::new(Mutex::new(State::default()));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let state_cloned = Arc::clone(&state);
+        thread::Builder::new()
+            .name("wdg-listener".to_string())
+            .spawn(move || listen(listener, state_cloned))?;
+
+        let running_cloned = Arc::clone(&running);
+        let socket = config.socket.clone();
+        let thread = thread::Builder::new()
+            .name("wdg-supervisor".to_string())
+            .spawn(move || {
+                supervise(
+                    &config,
+                    &state,
+                    &running_cloned,
+                    watchdog,
+                    service_interval,
+                    safe_state,
+                )
+            })?;
+
+        Ok(Supervisor {
+            running,
+            thread: Some(thread),
+            socket,
+        })
+    }
+}
+
+impl Drop for Supervisor {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+        fs::remove_file(&self.socket).ok();
+    }
+}
+
+fn supervise(
+    config: &Config,
+    state: &Mutex<State>,
+    running: &AtomicBool,
+    mut watchdog: Option<wdg_dev::Wdg>,
+    service_interval: Duration,
+    mut safe_state: SafeState,
+) {
+    let mut last_service = Instant::now();
+
+    while running.load(Ordering::SeqCst) {
+        let failed = state.lock().unwrap().check(Instant::now());
+
+        for name in failed {
+            let client = config.client(&name);
+            error!(
+                "Watchdog client {} failed, action: {:?}",
+                name, client.action
+            );
+
+            match client.action {
+                Action::Log => {}
+                Action::SafeState => {
+                    if let Err(e) = safe_state(&config.safe_state) {
+                        error!("Failed to apply the safe state: {}", e);
+                    }
+                }
+                Action::Restart => {
+                    let service = client.service.unwrap_or(format!("{}.service", name));
+                    let result = Command::new("systemctl")
+                        .args(["restart", "--no-block", service.as_str()])
+                        .status();
+                    match result {
+                        Ok(status) if status.success() => info!("Restarted {}", service),
+                        Ok(status) => error!("Failed to restart {}: {}", service, status),
+                        Err(e) => error!("Failed to restart {}: {}", service, e),
+                    }
+                }
+                Action::Reset => match watchdog.take() {
+                    Some(watchdog) => {
+                        error!("Stop servicing the hardware watchdog");
+                        // closing without the magic close keeps the watchdog running
+                        std::mem::forget(watchdog);
+                    }
+                    None => error!("No hardware watchdog to reset the device"),
+                },
+            }
+        }
+
+        if let Some(watchdog) = watchdog.as_mut() {
+            if last_service.elapsed() >= service_interval {
+                if let Err(e) = watchdog.service() {
+                    error!("Failed to service the hardware watchdog: {}", e);
+                }
+                last_service = Instant::now();
+            }
+        }
+
+        thread::sleep(CHECK_INTERVAL);
+    }
+}
+
+/// Connection of a supervised process to the supervisor. Dropping it without `unregister()` is
+/// handled as failure of the process.
+#[derive(Debug)]
+pub struct WatchdogClient {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl WatchdogClient {
+    /// Register at the supervisor of iodaemon, `heartbeat()` must be called within `timeout`
+    pub fn register(name: &str, timeout: Duration) -> Result<WatchdogClient> {
+        WatchdogClient::register_at(SOCKET_PATH, name, timeout)
+    }
+
+    pub fn register_at(socket: &str, name: &str, timeout: Duration) -> Result<WatchdogClient> {
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(Error::InvalidParameter);
+        }
+        let writer = UnixStream::connect(socket)?;
+        let mut client = WatchdogClient {
+            reader: BufReader::new(writer.try_clone()?),
+            writer,
+        };
+        client.command(&format!("REGISTER {} {}", name, timeout.as_millis()))?;
+        Ok(client)
+    }
+
+    /// Fails with `Error::WatchdogTimeout` if the client was already removed due to a timeout
+    pub fn heartbeat(&mut self) -> Result<()> {
+        self.command("HEARTBEAT")
+    }
+
+    pub fn unregister(mut self) -> Result<()> {
+        self.command("UNREGISTER")
+    }
+
+    fn command(&mut self, command: &str) -> Result<()> {
+        writeln!(self.writer, "{}", command)?;
+        let mut response = String::new();
+        self.reader.read_line(&mut response)?;
+
+        match response.trim_end() {
+            "OK" => Ok(()),
+            response if response.contains(&Error::WatchdogTimeout.to_string()) => {
+                Err(Error::WatchdogTimeout)
+            }
+            _ => Err(Error::InvalidParameter),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_test() {
+        let ini = Ini::load_from_str(
+            "[watchdog]\nenable = true\ndevice =\naction = log\n\
+             [safe_state]\ndo0 = 0\nRelay = 1\n\
+             [client plc]\naction = restart\nservice = codesys.service\n",
+        )
+        .unwrap();
+        let mut config = Config::from_ini(&ini).unwrap();
+        assert!(config.enable);
+        assert_eq!(config.device, None);
+        // the order of the keys is not preserved by the INI parser
+        config.safe_state.sort();
+        assert_eq!(
+            config.safe_state,
+            vec![("Relay".to_string(), true), ("do0".to_string(), false)]
+        );
+        assert_eq!(config.client("plc").action, Action::Restart);
+        assert_eq!(config.client("hmi").action, Action::Log);
+
+        let ini = Ini::load_from_str("[client plc]\naction = reboot\n").unwrap();
+        assert!(Config::from_ini(&ini).is_err());
+    }
+
+    #[test]
+    fn supervisor_test() {
+        let socket = format!("/tmp/sysworxx-io-wdg-{}.sock", std::process::id());
+        let config = Config {
+            enable: true,
+            device: None,
+            socket: socket.clone(),
+            action: Action::SafeState,
+            ..Default::default()
+        };
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let safe_state: SafeState = Box::new(move |_| {
+            tx.send(()).unwrap();
+            Ok(())
+        });
+        let _supervisor = Supervisor::start(config, safe_state).unwrap();
+
+        let mut plc =
+            WatchdogClient::register_at(&socket, "plc", Duration::from_millis(300)).unwrap();
+        assert!(WatchdogClient::register_at(&socket, "plc", Duration::from_millis(300)).is_err());
+        let hmi = WatchdogClient::register_at(&socket, "hmi", Duration::from_secs(10)).unwrap();
+
+        for _ in 0..5 {
+            thread::sleep(Duration::from_millis(100));
+            plc.heartbeat().unwrap();
+        }
+        assert!(rx.try_recv().is_err());
+
+        // missed heartbeat
+        thread::sleep(Duration::from_millis(500));
+        assert!(rx.recv_timeout(Duration::from_secs(1)).is_ok());
+        assert!(matches!(plc.heartbeat(), Err(Error::WatchdogTimeout)));
+
+        // closed connection
+        drop(hmi);
+        assert!(rx.recv_timeout(Duration::from_secs(1)).is_ok());
+
+        let plc = WatchdogClient::register_at(&socket, "plc", Duration::from_millis(300)).unwrap();
+        plc.unregister().unwrap();
+        thread::sleep(Duration::from_millis(500));
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,253 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// SPDX-FileCopyrightText: 2025 SYS TEC electronic AG <https://www.systec-electronic.com/>
+
+// Minimal implementation of the systemd service protocols, so that no libsystemd is required:
+//
+//   - `notify()` sends state changes (READY=1, WATCHDOG=1, STATUS=...) to the service manager
+//   - `watchdog_interval()` returns the interval requested by WatchdogSec= of the unit
+//   - `take_listener()` takes a socket passed by socket activation
+//   - `init_logger()` logs via the native journal protocol if the process runs as service
+//
+// All functions do nothing if the process is not started by systemd.
+
+use std::env;
+use std::io::Write;
+use std::os::linux::net::SocketAddrExt;
+use std::os::unix::io::{FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::net::{SocketAddr, UnixDatagram, UnixListener};
+use std::time::Duration;
+
+use log::kv::{self, Key, Value, VisitSource};
+use log::{Level, Log, Metadata, Record};
+
+use crate::error::Result;
+
+/// File descriptor of the first socket passed by socket activation
+const LISTEN_FDS_START: RawFd = 3;
+
+const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// Send a state change (e.g. "READY=1") to the service manager. Returns `false` if the process
+/// was not started by systemd.
+pub fn notify(state: &str) -> Result<bool> {
+    let path = match env::var("NOTIFY_SOCKET") {
+        Ok(path) => path,
+        Err(_) => return Ok(false),
+    };
+    let addr = match path.strip_prefix('@') {
+        Some(name) => SocketAddr::from_abstract_name(name)?,
+        None => SocketAddr::from_pathname(&path)?,
+    };
+
+    let socket = UnixDatagram::unbound()?;
+    socket.send_to_addr(state.as_bytes(), &addr)?;
+    Ok(true)
+}
+
+/// Interval in which "WATCHDOG=1" must be sent, if the watchdog is enabled for the service
+pub fn watchdog_interval() -> Option<Duration> {
+    if let Ok(pid) = env::var("WATCHDOG_PID") {
+        if pid.parse() != Ok(std::process::id()) {
+            return None;
+        }
+    }
+    let usec: u64 = env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    Some(Duration::from_micros(usec))
+}
+
+fn listen_fds() -> std::ops::Range<RawFd> {
+    let passed =
+        env::var("LISTEN_PID").ok().and_then(|pid| pid.parse().ok()) == Some(std::process::id());
+    let count = match env::var("LISTEN_FDS").ok().and_then(|fds| fds.parse().ok()) {
+        Some(count) if passed => count,
+        _ => 0,
+    };
+    LISTEN_FDS_START..LISTEN_FDS_START + count
+}
+
+/// Take the listening Unix socket bound to `path` if it was passed by socket activation
+pub fn take_listener(path: &str) -> Option<UnixListener> {
+    for fd in listen_fds() {
+        // only sockets of this process are passed, so the descriptor is valid
+        let listener = unsafe { UnixListener::from_raw_fd(fd) };
+        let matches = listener
+            .local_addr()
+            .map(|addr| addr.as_pathname() == Some(path.as_ref()))
+            .unwrap_or(false);
+        if matches {
+            unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) };
+            return Some(listener);
+        }
+        // keep the descriptor open for other users
+        let _ = listener.into_raw_fd();
+    }
+    None
+}
+
+fn priority(level: Level) -> u8 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
+/// Append a field in the format of the native journal protocol
+fn append_field(buffer: &mut Vec<u8>, name: &str, value: &str) {
+    buffer.extend(
+        name.chars()
+            .map(|c| c.to_ascii_uppercase())
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .skip_while(|c| *c == '_')
+            .map(|c| c as u8),
+    );
+    if value.contains('\n') {
+        buffer.push(b'\n');
+        buffer.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        buffer.push(b'=');
+    }
+    buffer.extend_from_slice(value.as_bytes());
+    buffer.push(b'\n');
+}
+
+struct Fields<'a>(&'a mut Vec<u8>);
+
+impl<'kvs> VisitSource<'kvs> for Fields<'_> {
+    fn visit_pair(
+        &mut self,
+        key: Key<'kvs>,
+        value: Value<'kvs>,
+    ) -> std::result::Result<(), kv::Error> {
+        append_field(self.0, key.as_str(), &value.to_string());
+        Ok(())
+    }
+}
+
+fn journal_entry(record: &Record, identifier: &str) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    append_field(&mut buffer, "MESSAGE", &record.args().to_string());
+    append_field(
+        &mut buffer,
+        "PRIORITY",
+        &priority(record.level()).to_string(),
+    );
+    append_field(&mut buffer, "SYSLOG_IDENTIFIER", identifier);
+    append_field(&mut buffer, "TARGET", record.target());
+    if let Some(file) = record.file() {
+        append_field(&mut buffer, "CODE_FILE", file);
+    }
+    if let Some(line) = record.line() {
+        append_field(&mut buffer, "CODE_LINE", &line.to_string());
+    }
+    if let Some(module) = record.module_path() {
+        append_field(&mut buffer, "CODE_MODULE", module);
+    }
+    record.key_values().visit(&mut Fields(&mut buffer)).ok();
+    buffer
+}
+
+/// Logger for the native journal protocol, key-values of the records are logged as fields
+/// (e.g. `error!(channel = 3, error:% = e; "Read failed")` adds CHANNEL= and ERROR=)
+struct JournalLogger {
+    socket: UnixDatagram,
+    identifier: String,
+    filter: env_logger::filter::Filter,
+}
+
+impl Log for JournalLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.filter.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.filter.matches(record) {
+            return;
+        }
+        let entry = journal_entry(record, &self.identifier);
+        if self.socket.send_to(&entry, JOURNAL_SOCKET).is_err() {
+            // e.g. too large for a datagram, stderr is connected to the journal anyway
+            writeln!(std::io::stderr(), "{}", record.args()).ok();
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Initialize the logger with the level filter from the environment variable `filter_env`. If
+/// stderr is connected to the journal, the native journal protocol is used, otherwise
+/// env_logger with the style from `style_env`.
+pub fn init_logger(filter_env: &str, style_env: &str) {
+    let socket = UnixDatagram::unbound();
+    match socket {
+        Ok(socket) if env::var_os("JOURNAL_STREAM").is_some() => {
+            let mut builder = env_logger::filter::Builder::from_env(filter_env);
+            let filter = builder.build();
+            let identifier = env::args()
+                .next()
+                .and_then(|arg| arg.rsplit('/').next().map(str::to_string))
+                .unwrap_or_default();
+
+            log::set_max_level(filter.filter());
+            log::set_boxed_logger(Box::new(JournalLogger {
+                socket,
+                identifier,
+                filter,
+            }))
+            .expect("logger initialized once");
+        }
+        _ => {
+            use env_logger::Env;
+            let env = Env::new().filter(filter_env).write_style(style_env);
+            env_logger::init_from_env(env);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journal_entry_test() {
+        let mut buffer = Vec::new();
+        append_field(&mut buffer, "channel", "3");
+        append_field(&mut buffer, "error", "line1\nline2");
+        let mut expected = b"CHANNEL=3\nERROR\n".to_vec();
+        expected.extend_from_slice(&11u64.to_le_bytes());
+        expected.extend_from_slice(b"line1\nline2\n");
+        assert_eq!(buffer, expected);
+
+        let kvs = [("device", "ctr700")];
+        let entry = journal_entry(
+            &Record::builder()
+                .args(format_args!("Failed"))
+                .level(Level::Warn)
+                .target("test")
+                .key_values(&kvs)
+                .build(),
+            "iodaemon",
+        );
+        let entry = String::from_utf8(entry).unwrap();
+        assert!(entry.starts_with("MESSAGE=Failed\nPRIORITY=4\nSYSLOG_IDENTIFIER=iodaemon\n"));
+        assert!(entry.ends_with("DEVICE=ctr700\n"));
+    }
+
+    #[test]
+    fn notify_test() {
+        let path = format!("/tmp/sysworxx-io-notify-{}.sock", std::process::id());
+        std::fs::remove_file(&path).ok();
+        let socket = UnixDatagram::bind(&path).unwrap();
+
+        env::set_var("NOTIFY_SOCKET", &path);
+        assert!(notify("READY=1").unwrap());
+        env::remove_var("NOTIFY_SOCKET");
+        assert!(!notify("READY=1").unwrap());
+
+        let mut buffer = [0; 16];
+        let len = socket.recv(&mut buffer).unwrap();
+        assert_eq!(&buffer[..len], b"READY=1");
+        std::fs::remove_file(&path).ok();
+    }
+}